@@ -41,12 +41,12 @@
 
 use crate::defaults::{Defaults, Testnet1};
 use log::warn;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 
-use witnet_data_structures::chain::{ConsensusConstants, Environment};
+use witnet_data_structures::chain::{ConsensusConstants, Environment, Epoch};
 
 /// Module containing the partial configuration struct that is
 /// returned by the loaders.
@@ -71,6 +71,30 @@ pub struct Config {
 
     /// JSON-RPC API configuration
     pub jsonrpc: JsonRPC,
+
+    /// Mempool-related configuration
+    pub mempool: Mempool,
+
+    /// Mining-related configuration
+    pub mining: Mining,
+
+    /// Witnessing-related configuration
+    pub witnessing: Witnessing,
+
+    /// Wallet-related configuration
+    pub wallet: Wallet,
+
+    /// Telemetry-related configuration
+    pub telemetry: Telemetry,
+
+    /// Actor mailboxes configuration
+    pub mailboxes: Mailboxes,
+
+    /// Handler watchdog configuration
+    pub watchdog: Watchdog,
+
+    /// Node-specific configuration
+    pub node: Node,
 }
 
 /// Connection-specific configuration.
@@ -81,6 +105,10 @@ pub struct Connections {
     /// peers should bind to
     pub server_addr: SocketAddr,
 
+    /// Additional socket addresses to which the server should bind
+    /// to as well (e.g. an IPv6 address, for dual-stack nodes)
+    pub listen_addrs: Vec<SocketAddr>,
+
     /// Maximum number of concurrent connections the server should
     /// accept
     pub inbound_limit: u16,
@@ -105,6 +133,35 @@ pub struct Connections {
 
     /// Handshake timeout
     pub handshake_timeout: Duration,
+
+    /// Time during which the items announced to a peer are accumulated
+    /// before being sent in a single Inv message
+    pub announcement_debounce: Duration,
+
+    /// Whether to try to open the server port in the router (UPnP or NAT-PMP)
+    pub port_mapping: bool,
+
+    /// Whether to encrypt the connections with the peers supporting it
+    pub encryption: bool,
+
+    /// Whether to compress the messages sent to the peers supporting it
+    pub compression: bool,
+
+    /// Minimum size (in bytes) of the messages to be compressed
+    pub compression_threshold: usize,
+
+    /// Number of days after which the addresses which have not been reachable are removed from
+    /// the known peers
+    pub peer_eviction_days: u32,
+
+    /// Host names (along with their port) resolved to find new peers
+    /// when the node is isolated from the network
+    pub dns_seeds: Vec<String>,
+
+    /// Number of epochs without consolidated outbound sessions, or
+    /// without new blocks while the peers report a higher beacon,
+    /// after which the node is considered isolated from the network
+    pub isolation_epochs: Epoch,
 }
 
 /// Storage-specific configuration
@@ -112,6 +169,17 @@ pub struct Connections {
 pub struct Storage {
     /// Path to the directory that will contain the database files
     pub db_path: PathBuf,
+    /// Maximum size (in bytes) of the values kept in the read cache of the storage
+    /// (0 disables the cache)
+    pub cache_size: usize,
+    /// Period of the storage maintenance task (deletion of stale entries and compaction)
+    pub maintenance_period: Duration,
+    /// Minimum time without network activity for the node to be considered idle, so that the
+    /// maintenance task can run
+    pub maintenance_idle: Duration,
+    /// Number of epochs during which stale entries (e.g. orphaned blocks) are kept before
+    /// being deleted
+    pub stale_retention_epochs: Epoch,
 }
 
 /// JsonRPC API configuration
@@ -124,6 +192,151 @@ pub struct JsonRPC {
     pub server_address: SocketAddr,
 }
 
+/// Mempool-specific configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mempool {
+    /// Minimum fee a transaction must pay to be accepted into the
+    /// mempool and relayed to other peers
+    pub min_relay_fee: u64,
+
+    /// Period of the persist mempool task
+    pub storage_period: Duration,
+
+    /// Number of epochs after which the transactions which have not
+    /// been confirmed are dropped from the mempool (0 disables the
+    /// expiry)
+    pub expiry_epochs: Epoch,
+}
+
+/// Mining-specific configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mining {
+    /// Minimum fee a transaction must pay to be included in the
+    /// blocks mined by this node
+    pub min_fee: u64,
+}
+
+/// Witnessing-specific configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Witnessing {
+    /// Maximum number of data requests being witnessed by this node
+    /// at the same time
+    pub max_concurrent_jobs: usize,
+
+    /// Wall-clock limit for running the retrievals and the scripts of
+    /// a data request, HTTP requests included
+    pub sandbox_timeout: Duration,
+
+    /// Maximum size of the data retrieved from a source, in bytes
+    pub max_retrieved_size: usize,
+}
+
+/// Wallet-specific configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wallet {
+    /// Socket address of an external signer holding the keys of the
+    /// wallet. When it is not set, the keys are derived from the seed
+    /// stored by the node
+    pub external_signer: Option<SocketAddr>,
+}
+
+/// Telemetry-specific configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Telemetry {
+    /// Binary flag telling whether to report the anonymized
+    /// statistics of the node or not
+    pub enabled: bool,
+
+    /// URL to which the statistics are posted
+    pub endpoint: Option<String>,
+
+    /// Period of the telemetry reports
+    pub period: Duration,
+}
+
+/// Actor mailboxes configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mailboxes {
+    /// Maximum number of messages queued in the mailbox of the
+    /// BlocksManager actor
+    pub blocks_manager: usize,
+
+    /// Maximum number of messages queued in the mailbox of the
+    /// StorageManager actor
+    pub storage_manager: usize,
+
+    /// Maximum number of messages queued in the mailbox of each
+    /// Session actor
+    pub session: usize,
+}
+
+/// Handler watchdog configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watchdog {
+    /// Maximum time a handler is expected to take processing a
+    /// message, unless the message type has its own budget
+    pub budget: Duration,
+
+    /// Budgets of the message types which need a different one, by
+    /// actor and message type (e.g. `Session::BLOCK`)
+    pub budgets: HashMap<String, Duration>,
+
+    /// Number of messages of a peer exceeding their budget within the
+    /// window after which its session is closed
+    pub slow_messages_limit: usize,
+
+    /// Window in which the messages of a peer exceeding their budget
+    /// are counted
+    pub slow_messages_window: Duration,
+}
+
+/// Role performed by a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Mine blocks
+    Miner,
+    /// Witness data requests
+    Witness,
+    /// Relay the transactions received from other peers
+    Relay,
+}
+
+/// Node-specific configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    /// Roles performed by the node
+    pub roles: HashSet<Role>,
+
+    /// Maximum number of restarts of a supervised actor within the restart window before the
+    /// node is stopped
+    pub max_restarts: u32,
+
+    /// Window in which the restarts of a supervised actor are counted
+    pub restart_window: Duration,
+
+    /// Maximum number of epochs the local chain can be rolled back:
+    /// the blocks older than this window are final
+    pub max_reorg_depth: Epoch,
+
+    /// Maximum number of epochs the local chain tip can lag behind
+    /// the network (the wall-clock epoch or the epochs reported by
+    /// the peers) before the node goes back to the syncing state
+    pub max_epochs_behind: Epoch,
+
+    /// Whether the protocol messages received are tagged with a
+    /// correlation ID which is written in the log lines of every
+    /// actor taking part in their processing
+    pub tracing: bool,
+
+    /// Whether block candidates are announced to the peers as soon as
+    /// their header has been checked, before being fully validated
+    pub relay_first: bool,
+
+    /// Number of threads verifying the signatures of the blocks
+    pub signature_workers: usize,
+}
+
 impl Config {
     pub fn from_partial(config: &partial::Config) -> Self {
         let defaults: Box<Defaults> = match config.environment {
@@ -157,6 +370,14 @@ impl Config {
             storage: Storage::from_partial(&config.storage, &*defaults),
             consensus_constants,
             jsonrpc: JsonRPC::from_partial(&config.jsonrpc, &*defaults),
+            mempool: Mempool::from_partial(&config.mempool, &*defaults),
+            mining: Mining::from_partial(&config.mining, &*defaults),
+            witnessing: Witnessing::from_partial(&config.witnessing, &*defaults),
+            wallet: Wallet::from_partial(&config.wallet, &*defaults),
+            telemetry: Telemetry::from_partial(&config.telemetry, &*defaults),
+            mailboxes: Mailboxes::from_partial(&config.mailboxes, &*defaults),
+            watchdog: Watchdog::from_partial(&config.watchdog, &*defaults),
+            node: Node::from_partial(&config.node, &*defaults),
         }
     }
 }
@@ -186,6 +407,27 @@ pub fn consensus_constants_from_partial(
             .reputation_punishment
             .to_owned()
             .unwrap_or_else(|| defaults.consensus_constants_reputation_punishment()),
+        trusted_checkpoints: config
+            .trusted_checkpoints
+            .to_owned()
+            .map(|mut trusted_checkpoints| {
+                trusted_checkpoints.sort_by_key(|trusted| trusted.checkpoint);
+                trusted_checkpoints
+            })
+            .unwrap_or_else(|| defaults.consensus_constants_trusted_checkpoints()),
+        initial_block_reward: config
+            .initial_block_reward
+            .unwrap_or_else(|| defaults.consensus_constants_initial_block_reward()),
+        halving_period: config
+            .halving_period
+            .unwrap_or_else(|| defaults.consensus_constants_halving_period()),
+        reward_maturity: config
+            .reward_maturity
+            .unwrap_or_else(|| defaults.consensus_constants_reward_maturity()),
+        genesis_allocation: config
+            .genesis_allocation
+            .to_owned()
+            .unwrap_or_else(|| defaults.consensus_constants_genesis_allocation()),
     }
 }
 
@@ -202,6 +444,10 @@ impl Connections {
                 .server_addr
                 .to_owned()
                 .unwrap_or_else(|| defaults.connections_server_addr()),
+            listen_addrs: config
+                .listen_addrs
+                .to_owned()
+                .unwrap_or_else(|| defaults.connections_listen_addrs()),
             inbound_limit: config
                 .inbound_limit
                 .to_owned()
@@ -230,8 +476,47 @@ impl Connections {
             handshake_timeout: config
                 .handshake_timeout
                 .unwrap_or_else(|| defaults.connections_handshake_timeout()),
+            announcement_debounce: config
+                .announcement_debounce
+                .unwrap_or_else(|| defaults.connections_announcement_debounce()),
+            port_mapping: config
+                .port_mapping
+                .unwrap_or_else(|| defaults.connections_port_mapping()),
+            encryption: config
+                .encryption
+                .unwrap_or_else(|| defaults.connections_encryption()),
+            compression: config
+                .compression
+                .unwrap_or_else(|| defaults.connections_compression()),
+            compression_threshold: config
+                .compression_threshold
+                .unwrap_or_else(|| defaults.connections_compression_threshold()),
+            peer_eviction_days: config
+                .peer_eviction_days
+                .unwrap_or_else(|| defaults.connections_peer_eviction_days()),
+            dns_seeds: config
+                .dns_seeds
+                .to_owned()
+                .unwrap_or_else(|| defaults.connections_dns_seeds()),
+            isolation_epochs: config
+                .isolation_epochs
+                .unwrap_or_else(|| defaults.connections_isolation_epochs()),
         }
     }
+
+    /// All the socket addresses to which the server should bind to: the
+    /// server address followed by the additional listening addresses
+    /// (without duplicates)
+    pub fn bind_addrs(&self) -> Vec<SocketAddr> {
+        let mut addrs = vec![self.server_addr];
+        for addr in &self.listen_addrs {
+            if !addrs.contains(addr) {
+                addrs.push(*addr);
+            }
+        }
+
+        addrs
+    }
 }
 
 impl Storage {
@@ -241,6 +526,22 @@ impl Storage {
                 .db_path
                 .to_owned()
                 .unwrap_or_else(|| defaults.storage_db_path()),
+            cache_size: config
+                .cache_size
+                .to_owned()
+                .unwrap_or_else(|| defaults.storage_cache_size()),
+            maintenance_period: config
+                .maintenance_period
+                .to_owned()
+                .unwrap_or_else(|| defaults.storage_maintenance_period()),
+            maintenance_idle: config
+                .maintenance_idle
+                .to_owned()
+                .unwrap_or_else(|| defaults.storage_maintenance_idle()),
+            stale_retention_epochs: config
+                .stale_retention_epochs
+                .to_owned()
+                .unwrap_or_else(|| defaults.storage_stale_retention_epochs()),
         }
     }
 }
@@ -260,9 +561,176 @@ impl JsonRPC {
     }
 }
 
+impl Mempool {
+    pub fn from_partial(config: &partial::Mempool, defaults: &dyn Defaults) -> Self {
+        Mempool {
+            min_relay_fee: config
+                .min_relay_fee
+                .to_owned()
+                .unwrap_or_else(|| defaults.mempool_min_relay_fee()),
+            storage_period: config
+                .storage_period
+                .to_owned()
+                .unwrap_or_else(|| defaults.mempool_storage_period()),
+            expiry_epochs: config
+                .expiry_epochs
+                .unwrap_or_else(|| defaults.mempool_expiry_epochs()),
+        }
+    }
+}
+
+impl Mining {
+    pub fn from_partial(config: &partial::Mining, defaults: &dyn Defaults) -> Self {
+        Mining {
+            min_fee: config
+                .min_fee
+                .to_owned()
+                .unwrap_or_else(|| defaults.mining_min_fee()),
+        }
+    }
+}
+
+impl Witnessing {
+    pub fn from_partial(config: &partial::Witnessing, defaults: &dyn Defaults) -> Self {
+        Witnessing {
+            max_concurrent_jobs: config
+                .max_concurrent_jobs
+                .to_owned()
+                .unwrap_or_else(|| defaults.witnessing_max_concurrent_jobs()),
+            sandbox_timeout: config
+                .sandbox_timeout
+                .unwrap_or_else(|| defaults.witnessing_sandbox_timeout()),
+            max_retrieved_size: config
+                .max_retrieved_size
+                .unwrap_or_else(|| defaults.witnessing_max_retrieved_size()),
+        }
+    }
+}
+
+impl Wallet {
+    pub fn from_partial(config: &partial::Wallet, defaults: &dyn Defaults) -> Self {
+        Wallet {
+            external_signer: config
+                .external_signer
+                .or_else(|| defaults.wallet_external_signer()),
+        }
+    }
+}
+
+impl Telemetry {
+    pub fn from_partial(config: &partial::Telemetry, defaults: &dyn Defaults) -> Self {
+        Telemetry {
+            enabled: config
+                .enabled
+                .to_owned()
+                .unwrap_or_else(|| defaults.telemetry_enabled()),
+            endpoint: config
+                .endpoint
+                .to_owned()
+                .or_else(|| defaults.telemetry_endpoint()),
+            period: config
+                .period
+                .to_owned()
+                .unwrap_or_else(|| defaults.telemetry_period()),
+        }
+    }
+}
+
+impl Mailboxes {
+    pub fn from_partial(config: &partial::Mailboxes, defaults: &dyn Defaults) -> Self {
+        Mailboxes {
+            blocks_manager: config
+                .blocks_manager
+                .to_owned()
+                .unwrap_or_else(|| defaults.mailbox_blocks_manager()),
+            storage_manager: config
+                .storage_manager
+                .to_owned()
+                .unwrap_or_else(|| defaults.mailbox_storage_manager()),
+            session: config
+                .session
+                .to_owned()
+                .unwrap_or_else(|| defaults.mailbox_session()),
+        }
+    }
+}
+
+impl Watchdog {
+    pub fn from_partial(config: &partial::Watchdog, defaults: &dyn Defaults) -> Self {
+        let mut budgets = defaults.watchdog_budgets();
+        if let Some(overrides) = &config.budgets {
+            budgets.extend(
+                overrides
+                    .iter()
+                    .map(|(name, millis)| (name.clone(), Duration::from_millis(*millis))),
+            );
+        }
+
+        Watchdog {
+            budget: config.budget.unwrap_or_else(|| defaults.watchdog_budget()),
+            budgets,
+            slow_messages_limit: config
+                .slow_messages_limit
+                .unwrap_or_else(|| defaults.watchdog_slow_messages_limit()),
+            slow_messages_window: config
+                .slow_messages_window
+                .unwrap_or_else(|| defaults.watchdog_slow_messages_window()),
+        }
+    }
+
+    /// Budget of a message type handled by an actor
+    pub fn budget_of(&self, actor: &str, message: &str) -> Duration {
+        self.budgets
+            .get(&format!("{}::{}", actor, message))
+            .cloned()
+            .unwrap_or(self.budget)
+    }
+}
+
+impl Node {
+    pub fn from_partial(config: &partial::Node, defaults: &dyn Defaults) -> Self {
+        let roles = config
+            .roles
+            .to_owned()
+            .unwrap_or_else(|| defaults.node_roles());
+        if roles.is_empty() {
+            warn!("The node has no roles: it will only keep track of the chain");
+        }
+
+        Node {
+            roles,
+            max_restarts: config
+                .max_restarts
+                .unwrap_or_else(|| defaults.node_max_restarts()),
+            restart_window: config
+                .restart_window
+                .unwrap_or_else(|| defaults.node_restart_window()),
+            max_reorg_depth: config
+                .max_reorg_depth
+                .unwrap_or_else(|| defaults.node_max_reorg_depth()),
+            max_epochs_behind: config
+                .max_epochs_behind
+                .unwrap_or_else(|| defaults.node_max_epochs_behind()),
+            tracing: config.tracing.unwrap_or_else(|| defaults.node_tracing()),
+            relay_first: config
+                .relay_first
+                .unwrap_or_else(|| defaults.node_relay_first()),
+            signature_workers: config
+                .signature_workers
+                .unwrap_or_else(|| defaults.node_signature_workers()),
+        }
+    }
+
+    /// Whether the node performs a role
+    pub fn has_role(&self, role: Role) -> bool {
+        self.roles.contains(&role)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use witnet_data_structures::chain::{Hash, TrustedCheckpoint, ValueTransferOutput};
 
     #[test]
     fn test_storage_default_from_partial() {
@@ -271,6 +739,16 @@ mod tests {
         let config = Storage::from_partial(&partial_config, &*defaults);
 
         assert_eq!(config.db_path.to_str(), Testnet1.storage_db_path().to_str());
+        assert_eq!(config.cache_size, Testnet1.storage_cache_size());
+        assert_eq!(
+            config.maintenance_period,
+            Testnet1.storage_maintenance_period()
+        );
+        assert_eq!(config.maintenance_idle, Testnet1.storage_maintenance_idle());
+        assert_eq!(
+            config.stale_retention_epochs,
+            Testnet1.storage_stale_retention_epochs()
+        );
     }
 
     #[test]
@@ -278,10 +756,18 @@ mod tests {
         let defaults: Box<Defaults> = Box::new(Testnet1);
         let partial_config = partial::Storage {
             db_path: Some(PathBuf::from("other")),
+            cache_size: Some(1024),
+            maintenance_period: Some(Duration::from_secs(60)),
+            maintenance_idle: Some(Duration::from_secs(5)),
+            stale_retention_epochs: Some(10),
         };
         let config = Storage::from_partial(&partial_config, &*defaults);
 
         assert_eq!(config.db_path.to_str(), Some("other"));
+        assert_eq!(config.cache_size, 1024);
+        assert_eq!(config.maintenance_period, Duration::from_secs(60));
+        assert_eq!(config.maintenance_idle, Duration::from_secs(5));
+        assert_eq!(config.stale_retention_epochs, 10);
     }
 
     #[test]
@@ -291,6 +777,7 @@ mod tests {
         let config = Connections::from_partial(&partial_config, &*defaults);
 
         assert_eq!(config.server_addr, Testnet1.connections_server_addr());
+        assert_eq!(config.listen_addrs, Testnet1.connections_listen_addrs());
         assert_eq!(config.inbound_limit, Testnet1.connections_inbound_limit());
         assert_eq!(config.outbound_limit, Testnet1.connections_outbound_limit());
         assert_eq!(config.known_peers, Testnet1.connections_known_peers());
@@ -310,14 +797,36 @@ mod tests {
             config.handshake_timeout,
             Testnet1.connections_handshake_timeout()
         );
+        assert_eq!(
+            config.announcement_debounce,
+            Testnet1.connections_announcement_debounce()
+        );
+        assert_eq!(config.port_mapping, Testnet1.connections_port_mapping());
+        assert_eq!(config.encryption, Testnet1.connections_encryption());
+        assert_eq!(config.compression, Testnet1.connections_compression());
+        assert_eq!(
+            config.compression_threshold,
+            Testnet1.connections_compression_threshold()
+        );
+        assert_eq!(
+            config.peer_eviction_days,
+            Testnet1.connections_peer_eviction_days()
+        );
+        assert_eq!(config.dns_seeds, Testnet1.connections_dns_seeds());
+        assert_eq!(
+            config.isolation_epochs,
+            Testnet1.connections_isolation_epochs()
+        );
     }
 
     #[test]
     fn test_connections_from_partial() {
         let defaults: Box<Defaults> = Box::new(Testnet1);
         let addr: SocketAddr = "127.0.0.1:3000".parse().unwrap();
+        let addr6: SocketAddr = "[::1]:3000".parse().unwrap();
         let partial_config = partial::Connections {
             server_addr: Some(addr),
+            listen_addrs: Some(vec![addr6]),
             inbound_limit: Some(3),
             outbound_limit: Some(4),
             known_peers: [addr].iter().cloned().collect(),
@@ -325,10 +834,19 @@ mod tests {
             storage_peers_period: Some(Duration::from_secs(60)),
             discovery_peers_period: Some(Duration::from_secs(100)),
             handshake_timeout: Some(Duration::from_secs(3)),
+            announcement_debounce: Some(Duration::from_millis(100)),
+            port_mapping: Some(true),
+            encryption: Some(false),
+            compression: Some(false),
+            compression_threshold: Some(4096),
+            peer_eviction_days: Some(3),
+            dns_seeds: Some(vec!["seed.example.com:21337".to_string()]),
+            isolation_epochs: Some(20),
         };
         let config = Connections::from_partial(&partial_config, &*defaults);
 
         assert_eq!(config.server_addr, addr);
+        assert_eq!(config.listen_addrs, vec![addr6]);
         assert_eq!(config.inbound_limit, 3);
         assert_eq!(config.outbound_limit, 4);
         assert!(config.known_peers.contains(&addr));
@@ -336,6 +854,29 @@ mod tests {
         assert_eq!(config.storage_peers_period, Duration::from_secs(60));
         assert_eq!(config.discovery_peers_period, Duration::from_secs(100));
         assert_eq!(config.handshake_timeout, Duration::from_secs(3));
+        assert_eq!(config.announcement_debounce, Duration::from_millis(100));
+        assert!(config.port_mapping);
+        assert!(!config.encryption);
+        assert!(!config.compression);
+        assert_eq!(config.compression_threshold, 4096);
+        assert_eq!(config.peer_eviction_days, 3);
+        assert_eq!(config.dns_seeds, vec!["seed.example.com:21337".to_string()]);
+        assert_eq!(config.isolation_epochs, 20);
+    }
+
+    #[test]
+    fn test_connections_bind_addrs() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let addr: SocketAddr = "0.0.0.0:3000".parse().unwrap();
+        let addr6: SocketAddr = "[::]:3000".parse().unwrap();
+        let partial_config = partial::Connections {
+            server_addr: Some(addr),
+            listen_addrs: Some(vec![addr6, addr, addr6]),
+            ..partial::Connections::default()
+        };
+        let config = Connections::from_partial(&partial_config, &*defaults);
+
+        assert_eq!(config.bind_addrs(), vec![addr, addr6]);
     }
 
     #[test]
@@ -360,6 +901,288 @@ mod tests {
         assert_eq!(config.server_address, addr);
     }
 
+    #[test]
+    fn test_mempool_default_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let partial_config = partial::Mempool::default();
+        let config = Mempool::from_partial(&partial_config, &*defaults);
+
+        assert_eq!(config.min_relay_fee, Testnet1.mempool_min_relay_fee());
+        assert_eq!(config.storage_period, Testnet1.mempool_storage_period());
+        assert_eq!(config.expiry_epochs, Testnet1.mempool_expiry_epochs());
+    }
+
+    #[test]
+    fn test_mempool_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let partial_config = partial::Mempool {
+            min_relay_fee: Some(10),
+            storage_period: Some(Duration::from_secs(30)),
+            expiry_epochs: Some(5),
+        };
+        let config = Mempool::from_partial(&partial_config, &*defaults);
+
+        assert_eq!(config.min_relay_fee, 10);
+        assert_eq!(config.storage_period, Duration::from_secs(30));
+        assert_eq!(config.expiry_epochs, 5);
+    }
+
+    #[test]
+    fn test_mining_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let default_config = Mining::from_partial(&partial::Mining::default(), &*defaults);
+        let partial_config = partial::Mining { min_fee: Some(20) };
+        let config = Mining::from_partial(&partial_config, &*defaults);
+
+        assert_eq!(default_config.min_fee, Testnet1.mining_min_fee());
+        assert_eq!(config.min_fee, 20);
+    }
+
+    #[test]
+    fn test_witnessing_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let default_config = Witnessing::from_partial(&partial::Witnessing::default(), &*defaults);
+        let partial_config = partial::Witnessing {
+            max_concurrent_jobs: Some(2),
+            sandbox_timeout: Some(Duration::from_secs(3)),
+            max_retrieved_size: Some(1024),
+        };
+        let config = Witnessing::from_partial(&partial_config, &*defaults);
+
+        assert_eq!(
+            default_config.max_concurrent_jobs,
+            Testnet1.witnessing_max_concurrent_jobs()
+        );
+        assert_eq!(
+            default_config.sandbox_timeout,
+            Testnet1.witnessing_sandbox_timeout()
+        );
+        assert_eq!(
+            default_config.max_retrieved_size,
+            Testnet1.witnessing_max_retrieved_size()
+        );
+        assert_eq!(config.max_concurrent_jobs, 2);
+        assert_eq!(config.sandbox_timeout, Duration::from_secs(3));
+        assert_eq!(config.max_retrieved_size, 1024);
+    }
+
+    #[test]
+    fn test_wallet_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let default_config = Wallet::from_partial(&partial::Wallet::default(), &*defaults);
+        let partial_config = partial::Wallet {
+            external_signer: Some("127.0.0.1:21339".parse().unwrap()),
+        };
+        let config = Wallet::from_partial(&partial_config, &*defaults);
+
+        assert_eq!(
+            default_config.external_signer,
+            Testnet1.wallet_external_signer()
+        );
+        assert_eq!(
+            config.external_signer,
+            Some("127.0.0.1:21339".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_telemetry_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let default_config = Telemetry::from_partial(&partial::Telemetry::default(), &*defaults);
+        let partial_config = partial::Telemetry {
+            enabled: Some(true),
+            endpoint: Some("https://example.com/report".to_string()),
+            period: Some(Duration::from_secs(600)),
+        };
+        let config = Telemetry::from_partial(&partial_config, &*defaults);
+
+        assert_eq!(default_config.enabled, false);
+        assert_eq!(default_config.endpoint, Testnet1.telemetry_endpoint());
+        assert_eq!(default_config.period, Testnet1.telemetry_period());
+        assert_eq!(config.enabled, true);
+        assert_eq!(
+            config.endpoint,
+            Some("https://example.com/report".to_string())
+        );
+        assert_eq!(config.period, Duration::from_secs(600));
+    }
+
+    #[test]
+    fn test_mailboxes_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let partial_config = partial::Mailboxes {
+            blocks_manager: Some(1024),
+            storage_manager: None,
+            session: Some(8),
+        };
+        let config = Mailboxes::from_partial(&partial_config, &*defaults);
+
+        assert_eq!(config.blocks_manager, 1024);
+        assert_eq!(config.storage_manager, Testnet1.mailbox_storage_manager());
+        assert_eq!(config.session, 8);
+    }
+
+    #[test]
+    fn test_watchdog_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let default_config = Watchdog::from_partial(&partial::Watchdog::default(), &*defaults);
+        let partial_config = partial::Watchdog {
+            budget: Some(Duration::from_millis(50)),
+            budgets: Some(vec![("Session::INV".to_string(), 20)].into_iter().collect()),
+            slow_messages_limit: None,
+            slow_messages_window: Some(Duration::from_secs(10)),
+        };
+        let config = Watchdog::from_partial(&partial_config, &*defaults);
+
+        assert_eq!(default_config.budget, Testnet1.watchdog_budget());
+        assert_eq!(default_config.budgets, Testnet1.watchdog_budgets());
+        assert_eq!(config.budget, Duration::from_millis(50));
+        assert_eq!(
+            config.slow_messages_limit,
+            Testnet1.watchdog_slow_messages_limit()
+        );
+        assert_eq!(config.slow_messages_window, Duration::from_secs(10));
+
+        // The budgets given in the configuration are added to the default ones
+        assert_eq!(
+            config.budget_of("Session", "INV"),
+            Duration::from_millis(20)
+        );
+        assert_eq!(
+            config.budget_of("Session", "BLOCK"),
+            Duration::from_millis(500)
+        );
+        assert_eq!(config.budget_of("Session", "PING"), config.budget);
+    }
+
+    #[test]
+    fn test_node_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let default_config = Node::from_partial(&partial::Node::default(), &*defaults);
+        let partial_config = partial::Node {
+            roles: Some(vec![Role::Relay].into_iter().collect()),
+            max_restarts: Some(0),
+            restart_window: Some(Duration::from_secs(10)),
+            max_reorg_depth: Some(5),
+            max_epochs_behind: Some(3),
+            tracing: Some(true),
+            relay_first: Some(true),
+            signature_workers: Some(2),
+        };
+        let config = Node::from_partial(&partial_config, &*defaults);
+
+        assert_eq!(default_config.roles, Testnet1.node_roles());
+        assert_eq!(default_config.max_restarts, Testnet1.node_max_restarts());
+        assert_eq!(
+            default_config.restart_window,
+            Testnet1.node_restart_window()
+        );
+        assert_eq!(config.max_restarts, 0);
+        assert_eq!(config.restart_window, Duration::from_secs(10));
+        assert_eq!(
+            default_config.max_reorg_depth,
+            Testnet1.node_max_reorg_depth()
+        );
+        assert_eq!(config.max_reorg_depth, 5);
+        assert_eq!(
+            default_config.max_epochs_behind,
+            Testnet1.node_max_epochs_behind()
+        );
+        assert_eq!(config.max_epochs_behind, 3);
+        assert_eq!(default_config.tracing, Testnet1.node_tracing());
+        assert!(config.tracing);
+        assert_eq!(default_config.relay_first, Testnet1.node_relay_first());
+        assert!(config.relay_first);
+        assert_eq!(
+            default_config.signature_workers,
+            Testnet1.node_signature_workers()
+        );
+        assert_eq!(config.signature_workers, 2);
+        assert!(default_config.has_role(Role::Miner));
+        assert!(config.has_role(Role::Relay));
+        assert!(!config.has_role(Role::Miner));
+        assert!(!config.has_role(Role::Witness));
+    }
+
+    #[test]
+    fn test_trusted_checkpoints_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let default_config =
+            consensus_constants_from_partial(&partial::ConsensusConstants::default(), &*defaults);
+        let trusted = |checkpoint, byte| TrustedCheckpoint {
+            checkpoint,
+            hash: Hash::SHA256([byte; 32]),
+        };
+        let partial_config = partial::ConsensusConstants {
+            trusted_checkpoints: Some(vec![trusted(200, 2), trusted(100, 1)]),
+            ..partial::ConsensusConstants::default()
+        };
+        let config = consensus_constants_from_partial(&partial_config, &*defaults);
+
+        assert_eq!(
+            default_config.trusted_checkpoints,
+            Testnet1.consensus_constants_trusted_checkpoints()
+        );
+        // Trusted checkpoints are sorted by checkpoint
+        assert_eq!(
+            config.trusted_checkpoints,
+            vec![trusted(100, 1), trusted(200, 2)]
+        );
+    }
+
+    #[test]
+    fn test_genesis_allocation_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let default_config =
+            consensus_constants_from_partial(&partial::ConsensusConstants::default(), &*defaults);
+        let allocation = vec![ValueTransferOutput {
+            pkh: [1; 20],
+            value: 1000,
+            time_lock: 0,
+        }];
+        let partial_config = partial::ConsensusConstants {
+            genesis_allocation: Some(allocation.clone()),
+            ..partial::ConsensusConstants::default()
+        };
+        let config = consensus_constants_from_partial(&partial_config, &*defaults);
+
+        assert_eq!(
+            default_config.genesis_allocation,
+            Testnet1.consensus_constants_genesis_allocation()
+        );
+        assert_eq!(config.genesis_allocation, allocation);
+    }
+
+    #[test]
+    fn test_emission_schedule_from_partial() {
+        let defaults: Box<Defaults> = Box::new(Testnet1);
+        let default_config =
+            consensus_constants_from_partial(&partial::ConsensusConstants::default(), &*defaults);
+        let partial_config = partial::ConsensusConstants {
+            initial_block_reward: Some(1000),
+            halving_period: Some(10),
+            reward_maturity: Some(5),
+            ..partial::ConsensusConstants::default()
+        };
+        let config = consensus_constants_from_partial(&partial_config, &*defaults);
+
+        assert_eq!(
+            default_config.initial_block_reward,
+            Testnet1.consensus_constants_initial_block_reward()
+        );
+        assert_eq!(
+            default_config.halving_period,
+            Testnet1.consensus_constants_halving_period()
+        );
+        assert_eq!(
+            default_config.reward_maturity,
+            Testnet1.consensus_constants_reward_maturity()
+        );
+        assert_eq!(config.initial_block_reward, 1000);
+        assert_eq!(config.halving_period, 10);
+        assert_eq!(config.reward_maturity, 5);
+    }
+
     #[test]
     fn test_config_default_from_partial() {
         let partial_config = partial::Config::default();
@@ -403,5 +1226,38 @@ mod tests {
             config.jsonrpc.server_address,
             Testnet1.jsonrpc_server_address()
         );
+        assert_eq!(
+            config.mempool.min_relay_fee,
+            Testnet1.mempool_min_relay_fee()
+        );
+        assert_eq!(config.mining.min_fee, Testnet1.mining_min_fee());
+        assert_eq!(
+            config.witnessing.max_concurrent_jobs,
+            Testnet1.witnessing_max_concurrent_jobs()
+        );
+        assert_eq!(
+            config.witnessing.sandbox_timeout,
+            Testnet1.witnessing_sandbox_timeout()
+        );
+        assert_eq!(
+            config.witnessing.max_retrieved_size,
+            Testnet1.witnessing_max_retrieved_size()
+        );
+        assert_eq!(
+            config.wallet.external_signer,
+            Testnet1.wallet_external_signer()
+        );
+        assert_eq!(config.telemetry.enabled, Testnet1.telemetry_enabled());
+        assert_eq!(
+            config.mailboxes.blocks_manager,
+            Testnet1.mailbox_blocks_manager()
+        );
+        assert_eq!(
+            config.mailboxes.storage_manager,
+            Testnet1.mailbox_storage_manager()
+        );
+        assert_eq!(config.mailboxes.session, Testnet1.mailbox_session());
+        assert_eq!(config.watchdog.budget, Testnet1.watchdog_budget());
+        assert_eq!(config.node.roles, Testnet1.node_roles());
     }
 }
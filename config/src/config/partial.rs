@@ -8,12 +8,16 @@
 //! later, the `config` module will use this partial config object and
 //! the environment-specific defaults (see the `environment` module)
 //! to produce a __total__ (no `Option` fields) configuration object.
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::default::Default;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
-use witnet_data_structures::chain::{Environment, Hash};
+use witnet_data_structures::chain::{
+    Environment, Epoch, Hash, TrustedCheckpoint, ValueTransferOutput,
+};
+
+use super::Role;
 
 /// The partial configuration object that contains all other, more
 /// specific, configuration objects (connections, storage, etc).
@@ -39,6 +43,38 @@ pub struct Config {
     /// JSON-RPC API configuration
     #[serde(default)]
     pub jsonrpc: JsonRPC,
+
+    /// Mempool-related configuration
+    #[serde(default)]
+    pub mempool: Mempool,
+
+    /// Mining-related configuration
+    #[serde(default)]
+    pub mining: Mining,
+
+    /// Witnessing-related configuration
+    #[serde(default)]
+    pub witnessing: Witnessing,
+
+    /// Wallet-related configuration
+    #[serde(default)]
+    pub wallet: Wallet,
+
+    /// Telemetry-related configuration
+    #[serde(default)]
+    pub telemetry: Telemetry,
+
+    /// Actor mailboxes configuration
+    #[serde(default)]
+    pub mailboxes: Mailboxes,
+
+    /// Handler watchdog configuration
+    #[serde(default)]
+    pub watchdog: Watchdog,
+
+    /// Node-specific configuration
+    #[serde(default)]
+    pub node: Node,
 }
 
 /// Connection-specific partial configuration.
@@ -49,6 +85,11 @@ pub struct Connections {
     /// peers should bind to
     pub server_addr: Option<SocketAddr>,
 
+    /// Additional socket addresses to which the server should bind
+    /// to as well (e.g. an IPv6 address, for dual-stack nodes)
+    #[serde(default)]
+    pub listen_addrs: Option<Vec<SocketAddr>>,
+
     /// Maximum number of concurrent connections the server should
     /// accept
     pub inbound_limit: Option<u16>,
@@ -86,6 +127,45 @@ pub struct Connections {
     #[serde(deserialize_with = "from_secs")]
     #[serde(rename = "handshake_timeout_seconds")]
     pub handshake_timeout: Option<Duration>,
+
+    /// Time during which the items announced to a peer are accumulated
+    /// before being sent in a single Inv message
+    #[serde(default)]
+    #[serde(deserialize_with = "from_millis")]
+    #[serde(rename = "announcement_debounce_millis")]
+    pub announcement_debounce: Option<Duration>,
+
+    /// Whether to try to open the server port in the router (UPnP or NAT-PMP)
+    #[serde(default)]
+    pub port_mapping: Option<bool>,
+
+    /// Whether to encrypt the connections with the peers supporting it
+    #[serde(default)]
+    pub encryption: Option<bool>,
+
+    /// Whether to compress the messages sent to the peers supporting it
+    #[serde(default)]
+    pub compression: Option<bool>,
+
+    /// Minimum size (in bytes) of the messages to be compressed
+    #[serde(default)]
+    pub compression_threshold: Option<usize>,
+
+    /// Number of days after which the addresses which have not been reachable are removed from
+    /// the known peers
+    #[serde(default)]
+    pub peer_eviction_days: Option<u32>,
+
+    /// Host names (along with their port) resolved to find new peers
+    /// when the node is isolated from the network
+    #[serde(default)]
+    pub dns_seeds: Option<Vec<String>>,
+
+    /// Number of epochs without consolidated outbound sessions, or
+    /// without new blocks while the peers report a higher beacon,
+    /// after which the node is considered isolated from the network
+    #[serde(default)]
+    pub isolation_epochs: Option<Epoch>,
 }
 
 /// Storage-specific configuration
@@ -94,6 +174,26 @@ pub struct Storage {
     #[serde(default)]
     /// Path to the directory that will contain the database files
     pub db_path: Option<PathBuf>,
+
+    #[serde(default)]
+    /// Maximum size (in bytes) of the values kept in the read cache of the storage
+    pub cache_size: Option<usize>,
+
+    /// Period of the storage maintenance task
+    #[serde(default)]
+    #[serde(deserialize_with = "from_secs")]
+    #[serde(rename = "maintenance_period_seconds")]
+    pub maintenance_period: Option<Duration>,
+
+    /// Minimum time without network activity before running the storage maintenance task
+    #[serde(default)]
+    #[serde(deserialize_with = "from_secs")]
+    #[serde(rename = "maintenance_idle_seconds")]
+    pub maintenance_idle: Option<Duration>,
+
+    #[serde(default)]
+    /// Number of epochs during which stale entries are kept before being deleted
+    pub stale_retention_epochs: Option<Epoch>,
 }
 
 /// Consensus-critical configuration
@@ -119,6 +219,26 @@ pub struct ConsensusConstants {
     /// Punishment value for claims out of the consensus bounds
     #[serde(default)]
     pub reputation_punishment: Option<f64>,
+
+    /// Blocks known to belong to the valid chain
+    #[serde(default)]
+    pub trusted_checkpoints: Option<Vec<TrustedCheckpoint>>,
+
+    /// Reward of the first blocks, which is halved every `halving_period` epochs
+    #[serde(default)]
+    pub initial_block_reward: Option<u64>,
+
+    /// Number of epochs between two halvings of the block reward
+    #[serde(default)]
+    pub halving_period: Option<u32>,
+
+    /// Number of epochs after which the reward of a block can be spent
+    #[serde(default)]
+    pub reward_maturity: Option<u32>,
+
+    /// Outputs created by the genesis block
+    #[serde(default)]
+    pub genesis_allocation: Option<Vec<ValueTransferOutput>>,
 }
 
 /// JSON-RPC API configuration
@@ -131,6 +251,121 @@ pub struct JsonRPC {
     pub server_address: Option<SocketAddr>,
 }
 
+/// Mempool-specific partial configuration
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Mempool {
+    /// Minimum fee a transaction must pay to be accepted into the
+    /// mempool and relayed to other peers
+    pub min_relay_fee: Option<u64>,
+
+    /// Period of the persist mempool task
+    #[serde(default)]
+    #[serde(deserialize_with = "from_secs")]
+    #[serde(rename = "storage_period_seconds")]
+    pub storage_period: Option<Duration>,
+
+    /// Number of epochs after which the unconfirmed transactions are dropped from the mempool
+    #[serde(default)]
+    pub expiry_epochs: Option<Epoch>,
+}
+
+/// Mining-specific partial configuration
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Mining {
+    /// Minimum fee a transaction must pay to be included in the
+    /// blocks mined by this node
+    pub min_fee: Option<u64>,
+}
+
+/// Witnessing-specific partial configuration
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Witnessing {
+    /// Maximum number of data requests being witnessed by this node
+    /// at the same time
+    pub max_concurrent_jobs: Option<usize>,
+
+    /// Wall-clock limit for running the retrievals and the scripts of
+    /// a data request, HTTP requests included
+    #[serde(default)]
+    #[serde(deserialize_with = "from_secs")]
+    #[serde(rename = "sandbox_timeout_seconds")]
+    pub sandbox_timeout: Option<Duration>,
+
+    /// Maximum size of the data retrieved from a source, in bytes
+    #[serde(default)]
+    pub max_retrieved_size: Option<usize>,
+}
+
+/// Wallet-specific partial configuration
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Wallet {
+    /// Socket address of an external signer holding the keys of the
+    /// wallet
+    pub external_signer: Option<SocketAddr>,
+}
+
+/// Telemetry-specific partial configuration
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Telemetry {
+    /// Binary flag telling whether to report the anonymized
+    /// statistics of the node or not
+    pub enabled: Option<bool>,
+
+    /// URL to which the statistics are posted
+    pub endpoint: Option<String>,
+
+    /// Period of the telemetry reports
+    #[serde(default)]
+    #[serde(deserialize_with = "from_secs")]
+    #[serde(rename = "period_seconds")]
+    pub period: Option<Duration>,
+}
+
+/// Actor mailboxes partial configuration
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Mailboxes {
+    /// Maximum number of messages queued in the mailbox of the
+    /// BlocksManager actor
+    pub blocks_manager: Option<usize>,
+
+    /// Maximum number of messages queued in the mailbox of the
+    /// StorageManager actor
+    pub storage_manager: Option<usize>,
+
+    /// Maximum number of messages queued in the mailbox of each
+    /// Session actor
+    pub session: Option<usize>,
+}
+
+/// Handler watchdog partial configuration
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Watchdog {
+    /// Maximum time a handler is expected to take processing a
+    /// message, unless the message type has its own budget
+    #[serde(default)]
+    #[serde(deserialize_with = "from_millis")]
+    #[serde(rename = "budget_millis")]
+    pub budget: Option<Duration>,
+
+    /// Budgets (in milliseconds) of the message types which need a
+    /// different one, by actor and message type
+    #[serde(default)]
+    #[serde(rename = "budgets_millis")]
+    pub budgets: Option<HashMap<String, u64>>,
+
+    /// Number of messages of a peer exceeding their budget within the
+    /// window after which its session is closed
+    #[serde(default)]
+    pub slow_messages_limit: Option<usize>,
+
+    /// Window in which the messages of a peer exceeding their budget
+    /// are counted
+    #[serde(default)]
+    #[serde(deserialize_with = "from_secs")]
+    #[serde(rename = "slow_messages_window_seconds")]
+    pub slow_messages_window: Option<Duration>,
+}
+
 impl Config {
     pub fn default_mainnet() -> Self {
         let mut default = Config::default();
@@ -151,3 +386,57 @@ where
         Err(_) => None,
     })
 }
+
+// Create a duration type from a u64 representing milliseconds
+fn from_millis<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match u64::deserialize(deserializer) {
+        Ok(millis) => Some(Duration::from_millis(millis)),
+        Err(_) => None,
+    })
+}
+
+/// Node-specific partial configuration
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Node {
+    /// Roles performed by the node (any subset of miner, witness
+    /// and relay)
+    pub roles: Option<HashSet<Role>>,
+
+    /// Maximum number of restarts of a supervised actor within the
+    /// restart window before the node is stopped
+    #[serde(default)]
+    pub max_restarts: Option<u32>,
+
+    /// Window in which the restarts of a supervised actor are counted
+    #[serde(default)]
+    #[serde(deserialize_with = "from_secs")]
+    #[serde(rename = "restart_window_seconds")]
+    pub restart_window: Option<Duration>,
+
+    /// Maximum number of epochs the local chain can be rolled back
+    /// (finality window)
+    #[serde(default)]
+    pub max_reorg_depth: Option<Epoch>,
+
+    /// Maximum number of epochs the local chain tip can lag behind
+    /// the network before the node goes back to syncing
+    #[serde(default)]
+    pub max_epochs_behind: Option<Epoch>,
+
+    /// Whether the protocol messages received are tagged with a
+    /// correlation ID written in the log lines of every actor
+    #[serde(default)]
+    pub tracing: Option<bool>,
+
+    /// Whether block candidates are announced to the peers as soon as
+    /// their header has been checked, before being fully validated
+    #[serde(default)]
+    pub relay_first: Option<bool>,
+
+    /// Number of threads verifying the signatures of the blocks
+    #[serde(default)]
+    pub signature_workers: Option<usize>,
+}
@@ -2,12 +2,17 @@
 //!
 //! This module contains per-environment default values for the Witnet
 //! protocol params.
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use std::time::Duration;
 
-use witnet_data_structures::chain::Hash;
+use crate::config::Role;
+use crate::dirs;
+use witnet_data_structures::{
+    chain::{Epoch, Hash, TrustedCheckpoint, ValueTransferOutput},
+    emission,
+};
 
 // When changing the defaults, remember to update the documentation!
 // https://github.com/witnet/witnet-rust/blob/master/docs/configuration/toml-file.md
@@ -20,6 +25,11 @@ pub trait Defaults {
     /// Default server addr
     fn connections_server_addr(&self) -> SocketAddr;
 
+    /// Default additional listening addresses: none
+    fn connections_listen_addrs(&self) -> Vec<SocketAddr> {
+        vec![]
+    }
+
     /// Default inbound limit for connections: `128`
     fn connections_inbound_limit(&self) -> u16 {
         128
@@ -38,6 +48,27 @@ pub trait Defaults {
     /// Default path for the database
     fn storage_db_path(&self) -> PathBuf;
 
+    /// Default size (in bytes) of the read cache of the storage: 16 MiB
+    fn storage_cache_size(&self) -> usize {
+        16 * 1024 * 1024
+    }
+
+    /// Default period of the storage maintenance task: 1 hour
+    fn storage_maintenance_period(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    /// Default time without network activity before running the storage maintenance task
+    fn storage_maintenance_idle(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    /// Default number of epochs during which stale entries are kept in storage: about a day
+    /// with 90 seconds epochs
+    fn storage_stale_retention_epochs(&self) -> Epoch {
+        960
+    }
+
     /// Default period for bootstrap peers
     fn connections_bootstrap_peers_period(&self) -> Duration {
         Duration::from_secs(5)
@@ -58,6 +89,46 @@ pub trait Defaults {
         Duration::from_secs(5)
     }
 
+    /// Default debounce window of the announcements
+    fn connections_announcement_debounce(&self) -> Duration {
+        Duration::from_millis(250)
+    }
+
+    /// Default port mapping: disabled
+    fn connections_port_mapping(&self) -> bool {
+        false
+    }
+
+    /// Default encryption of the connections: enabled
+    fn connections_encryption(&self) -> bool {
+        true
+    }
+
+    /// Default compression of the messages: enabled
+    fn connections_compression(&self) -> bool {
+        true
+    }
+
+    /// Default minimum size of the compressed messages: 1 KiB
+    fn connections_compression_threshold(&self) -> usize {
+        1024
+    }
+
+    /// Default number of days after which unreachable addresses are removed: a week
+    fn connections_peer_eviction_days(&self) -> u32 {
+        7
+    }
+
+    /// Default DNS seeds: none
+    fn connections_dns_seeds(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Default number of epochs after which the node is considered isolated
+    fn connections_isolation_epochs(&self) -> Epoch {
+        10
+    }
+
     /// Timestamp at the start of epoch 0
     fn consensus_constants_checkpoint_zero_timestamp(&self) -> i64;
 
@@ -84,6 +155,31 @@ pub trait Defaults {
         0.0
     }
 
+    /// Default list of blocks known to belong to the valid chain
+    fn consensus_constants_trusted_checkpoints(&self) -> Vec<TrustedCheckpoint> {
+        vec![]
+    }
+
+    /// Default reward of the first blocks
+    fn consensus_constants_initial_block_reward(&self) -> u64 {
+        emission::DEFAULT_INITIAL_BLOCK_REWARD
+    }
+
+    /// Default number of epochs between two halvings of the block reward
+    fn consensus_constants_halving_period(&self) -> u32 {
+        emission::DEFAULT_HALVING_PERIOD
+    }
+
+    /// Default number of epochs after which the reward of a block can be spent
+    fn consensus_constants_reward_maturity(&self) -> u32 {
+        emission::DEFAULT_REWARD_MATURITY
+    }
+
+    /// Default outputs created by the genesis block (none, every coin is issued by mining)
+    fn consensus_constants_genesis_allocation(&self) -> Vec<ValueTransferOutput> {
+        vec![]
+    }
+
     /// JSON-RPC server enabled by default
     fn jsonrpc_enabled(&self) -> bool {
         true
@@ -91,6 +187,157 @@ pub trait Defaults {
 
     /// Default JSON-RPC server addr
     fn jsonrpc_server_address(&self) -> SocketAddr;
+
+    /// Default minimum fee for transactions to be relayed: no minimum, as a relay fee higher than
+    /// the one of the rest of the network would only keep this node from seeing the transactions
+    /// that will be mined
+    fn mempool_min_relay_fee(&self) -> u64 {
+        0
+    }
+
+    /// Default period for persisting the mempool into storage
+    fn mempool_storage_period(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    /// Default number of epochs after which the unconfirmed transactions are dropped from the
+    /// mempool: about a day with 90 seconds epochs
+    fn mempool_expiry_epochs(&self) -> Epoch {
+        960
+    }
+
+    /// Default minimum fee for transactions to be mined: no minimum, so every relayed transaction
+    /// can be mined and the fees only decide the order in which they are included
+    fn mining_min_fee(&self) -> u64 {
+        0
+    }
+
+    /// Default maximum number of data requests witnessed at the same time
+    fn witnessing_max_concurrent_jobs(&self) -> usize {
+        4
+    }
+
+    /// Default wall-clock limit for witnessing a data request: a source which hangs must not
+    /// keep a job slot busy for a large part of the epoch
+    fn witnessing_sandbox_timeout(&self) -> Duration {
+        Duration::from_secs(10)
+    }
+
+    /// Default maximum size of the data retrieved from a source (1 MiB)
+    fn witnessing_max_retrieved_size(&self) -> usize {
+        1024 * 1024
+    }
+
+    /// Default external signer of the wallet: none, the keys are
+    /// derived from the seed stored by the node
+    fn wallet_external_signer(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    /// Default telemetry: disabled, no statistics are reported unless
+    /// the operator opts in
+    fn telemetry_enabled(&self) -> bool {
+        false
+    }
+
+    /// Default telemetry endpoint: none, it must be set explicitly
+    fn telemetry_endpoint(&self) -> Option<String> {
+        None
+    }
+
+    /// Default period of the telemetry reports: 1 hour
+    fn telemetry_period(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    /// Default mailbox capacity of the BlocksManager actor
+    fn mailbox_blocks_manager(&self) -> usize {
+        256
+    }
+
+    /// Default mailbox capacity of the StorageManager actor
+    fn mailbox_storage_manager(&self) -> usize {
+        256
+    }
+
+    /// Default mailbox capacity of each Session actor
+    fn mailbox_session(&self) -> usize {
+        64
+    }
+
+    /// Default budget of the handlers: 100 milliseconds
+    fn watchdog_budget(&self) -> Duration {
+        Duration::from_millis(100)
+    }
+
+    /// Default budgets of the message types which need a different one: the blocks take longer
+    /// to validate
+    fn watchdog_budgets(&self) -> HashMap<String, Duration> {
+        vec![
+            ("Session::BLOCK".to_string(), Duration::from_millis(500)),
+            (
+                "BlocksManager::AddNewBlock".to_string(),
+                Duration::from_millis(500),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    /// Default number of slow messages of a peer after which its session is closed
+    fn watchdog_slow_messages_limit(&self) -> usize {
+        5
+    }
+
+    /// Default window in which the slow messages of a peer are counted: 1 minute
+    fn watchdog_slow_messages_window(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    /// Default roles of the node: all of them
+    fn node_roles(&self) -> HashSet<Role> {
+        vec![Role::Miner, Role::Witness, Role::Relay]
+            .into_iter()
+            .collect()
+    }
+
+    /// Default maximum number of restarts of a supervised actor
+    fn node_max_restarts(&self) -> u32 {
+        3
+    }
+
+    /// Default window in which the restarts of a supervised actor are counted
+    fn node_restart_window(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    /// Default maximum depth of a reorganization of the local chain: `100` epochs
+    fn node_max_reorg_depth(&self) -> Epoch {
+        100
+    }
+
+    /// Default maximum number of epochs the local chain tip can lag behind the network before
+    /// the node goes back to syncing: `10` epochs
+    fn node_max_epochs_behind(&self) -> Epoch {
+        10
+    }
+
+    /// Tracing of the protocol messages is disabled by default
+    fn node_tracing(&self) -> bool {
+        false
+    }
+
+    /// Block candidates are only relayed once they have been fully validated by default, as the
+    /// peers relaying invalid candidates are not penalized yet, so relaying before validating
+    /// would let a single peer flood the network with invalid blocks through this node
+    fn node_relay_first(&self) -> bool {
+        false
+    }
+
+    /// Default number of threads verifying the signatures of the blocks: `4`
+    fn node_signature_workers(&self) -> usize {
+        4
+    }
 }
 
 /// Struct that will implement all the mainnet defaults
@@ -109,7 +356,7 @@ impl Defaults for Mainnet {
     }
 
     fn storage_db_path(&self) -> PathBuf {
-        PathBuf::from(".witnet-rust-mainnet")
+        environment_data_dir("mainnet", ".witnet-rust-mainnet")
     }
 
     fn consensus_constants_checkpoint_zero_timestamp(&self) -> i64 {
@@ -129,7 +376,7 @@ impl Defaults for Testnet1 {
     }
 
     fn storage_db_path(&self) -> PathBuf {
-        PathBuf::from(".witnet-rust-testnet-1")
+        environment_data_dir("testnet-1", ".witnet-rust-testnet-1")
     }
 
     fn consensus_constants_checkpoint_zero_timestamp(&self) -> i64 {
@@ -138,3 +385,12 @@ impl Defaults for Testnet1 {
         9_999_999_999_999
     }
 }
+
+/// Directory of an environment inside the data directory of the
+/// platform, or a directory relative to the working directory if the
+/// data directory of the platform cannot be determined
+fn environment_data_dir(environment: &str, fallback: &str) -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join(environment))
+        .unwrap_or_else(|| PathBuf::from(fallback))
+}
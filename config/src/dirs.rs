@@ -0,0 +1,111 @@
+//! # Platform directories
+//!
+//! Default locations of the configuration file and of the data of the
+//! node, following the conventions of every platform:
+//!
+//! | Platform | Configuration directory                            | Data directory                                          |
+//! |----------|----------------------------------------------------|---------------------------------------------------------|
+//! | Linux    | `$XDG_CONFIG_HOME/witnet` or `~/.config/witnet`    | `$XDG_DATA_HOME/witnet` or `~/.local/share/witnet`      |
+//! | macOS    | `~/Library/Application Support/witnet`             | `~/Library/Application Support/witnet`                  |
+//! | Windows  | `%APPDATA%\witnet`                                 | `%APPDATA%\witnet`                                      |
+//!
+//! These functions return `None` when the base directory of the
+//! platform cannot be determined (e.g. `HOME` is not set).
+use std::env;
+use std::path::PathBuf;
+
+/// Name of the directory of the node inside the platform directories
+pub const APP_DIR_NAME: &str = "witnet";
+
+/// Name of the configuration file
+pub const CONFIG_FILE_NAME: &str = "witnet.toml";
+
+/// Directory containing the configuration file of the node
+pub fn config_dir() -> Option<PathBuf> {
+    config_base_dir().map(|dir| dir.join(APP_DIR_NAME))
+}
+
+/// Directory containing the data of the node, such as the database
+pub fn data_dir() -> Option<PathBuf> {
+    data_base_dir().map(|dir| dir.join(APP_DIR_NAME))
+}
+
+/// Default path of the configuration file of the node
+pub fn config_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn config_base_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn data_base_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// Directory from an XDG environment variable, ignoring relative paths
+/// as mandated by the specification, or else from its fallback in the
+/// home directory
+#[cfg(all(unix, not(target_os = "macos")))]
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home_dir().map(|home| home.join(fallback)))
+}
+
+#[cfg(target_os = "macos")]
+fn config_base_dir() -> Option<PathBuf> {
+    home_dir().map(|home| home.join("Library/Application Support"))
+}
+
+#[cfg(target_os = "macos")]
+fn data_base_dir() -> Option<PathBuf> {
+    config_base_dir()
+}
+
+#[cfg(unix)]
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+}
+
+#[cfg(windows)]
+fn config_base_dir() -> Option<PathBuf> {
+    env::var_os("APPDATA").map(PathBuf::from)
+}
+
+#[cfg(windows)]
+fn data_base_dir() -> Option<PathBuf> {
+    config_base_dir()
+}
+
+#[cfg(not(any(unix, windows)))]
+fn config_base_dir() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(not(any(unix, windows)))]
+fn data_base_dir() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_dirs() {
+        if let Some(dir) = config_dir() {
+            assert!(dir.ends_with(APP_DIR_NAME));
+            assert_eq!(config_file(), Some(dir.join(CONFIG_FILE_NAME)));
+        }
+        if let Some(dir) = data_dir() {
+            assert!(dir.is_absolute());
+            assert!(dir.ends_with(APP_DIR_NAME));
+        }
+    }
+}
@@ -20,4 +20,7 @@ extern crate failure;
 
 pub mod config;
 pub mod defaults;
+pub mod dirs;
 pub mod loaders;
+pub mod template;
+pub mod validation;
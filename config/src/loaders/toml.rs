@@ -125,6 +125,7 @@ inbound_limit = 999
             r"
 [connections]
 server_addr = '127.0.0.1:1234'
+listen_addrs = ['[::1]:1234']
 known_peers = ['192.168.1.12:1234']
 ",
         )
@@ -136,22 +137,42 @@ known_peers = ['192.168.1.12:1234']
             config.connections.server_addr,
             Some("127.0.0.1:1234".parse().unwrap())
         );
+        assert_eq!(
+            config.connections.listen_addrs,
+            Some(vec!["[::1]:1234".parse().unwrap()])
+        );
         assert_eq!(config.connections.known_peers.len(), 1);
     }
 
     #[test]
     fn test_configure_storage() {
+        use std::time::Duration;
+
         let empty_config = super::from_str("[storage]").unwrap();
         let config = super::from_str(
             r"
 [storage]
 db_path = 'dbfiles'
+cache_size = 1048576
+maintenance_period_seconds = 600
+maintenance_idle_seconds = 30
+stale_retention_epochs = 100
 ",
         )
         .unwrap();
 
         assert_eq!(empty_config.storage, Storage::default());
         assert_eq!(config.storage.db_path, Some(PathBuf::from("dbfiles")));
+        assert_eq!(config.storage.cache_size, Some(1_048_576));
+        assert_eq!(
+            config.storage.maintenance_period,
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(
+            config.storage.maintenance_idle,
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(config.storage.stale_retention_epochs, Some(100));
     }
 
     #[test]
@@ -165,6 +186,12 @@ db_path = 'dbfiles'
 bootstrap_peers_period_seconds = 11
 storage_peers_period_seconds = 7
 handshake_timeout_seconds = 21
+announcement_debounce_millis = 50
+port_mapping = true
+encryption = false
+compression = false
+compression_threshold = 4096
+peer_eviction_days = 30
 ",
         )
         .unwrap();
@@ -182,6 +209,30 @@ handshake_timeout_seconds = 21
             empty_config.connections.handshake_timeout,
             Connections::default().handshake_timeout
         );
+        assert_eq!(
+            empty_config.connections.announcement_debounce,
+            Connections::default().announcement_debounce
+        );
+        assert_eq!(
+            empty_config.connections.port_mapping,
+            Connections::default().port_mapping
+        );
+        assert_eq!(
+            empty_config.connections.encryption,
+            Connections::default().encryption
+        );
+        assert_eq!(
+            empty_config.connections.compression,
+            Connections::default().compression
+        );
+        assert_eq!(
+            empty_config.connections.compression_threshold,
+            Connections::default().compression_threshold
+        );
+        assert_eq!(
+            empty_config.connections.peer_eviction_days,
+            Connections::default().peer_eviction_days
+        );
 
         // Check values in initialized config
         assert_eq!(
@@ -196,6 +247,15 @@ handshake_timeout_seconds = 21
             config.connections.handshake_timeout,
             Some(Duration::from_secs(21))
         );
+        assert_eq!(
+            config.connections.announcement_debounce,
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(config.connections.port_mapping, Some(true));
+        assert_eq!(config.connections.encryption, Some(false));
+        assert_eq!(config.connections.compression, Some(false));
+        assert_eq!(config.connections.compression_threshold, Some(4096));
+        assert_eq!(config.connections.peer_eviction_days, Some(30));
     }
 
     #[test]
@@ -223,4 +283,196 @@ enabled = false
         );
         assert_eq!(config_disabled.jsonrpc.enabled, Some(false),);
     }
+
+    #[test]
+    fn test_configure_fee_policy() {
+        use std::time::Duration;
+
+        let empty_config = super::from_str("[mempool]\n[mining]").unwrap();
+        let config = super::from_str(
+            r"
+[mempool]
+min_relay_fee = 10
+storage_period_seconds = 30
+
+[mining]
+min_fee = 20
+",
+        )
+        .unwrap();
+
+        assert_eq!(empty_config.mempool, Mempool::default());
+        assert_eq!(empty_config.mining, Mining::default());
+        assert_eq!(config.mempool.min_relay_fee, Some(10));
+        assert_eq!(config.mempool.storage_period, Some(Duration::from_secs(30)));
+        assert_eq!(config.mining.min_fee, Some(20));
+    }
+
+    #[test]
+    fn test_configure_genesis_allocation() {
+        use witnet_data_structures::chain::ValueTransferOutput;
+
+        let config = super::from_str(
+            r"
+[[consensus_constants.genesis_allocation]]
+pkh = [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]
+value = 1000
+
+[[consensus_constants.genesis_allocation]]
+pkh = [2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2]
+value = 500
+time_lock = 100
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.consensus_constants.genesis_allocation,
+            Some(vec![
+                ValueTransferOutput {
+                    pkh: [1; 20],
+                    value: 1000,
+                    time_lock: 0,
+                },
+                ValueTransferOutput {
+                    pkh: [2; 20],
+                    value: 500,
+                    time_lock: 100,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_configure_witnessing() {
+        use std::time::Duration;
+
+        let empty_config = super::from_str("[witnessing]").unwrap();
+        let config = super::from_str(
+            r"
+[witnessing]
+max_concurrent_jobs = 2
+sandbox_timeout_seconds = 3
+max_retrieved_size = 1024
+",
+        )
+        .unwrap();
+
+        assert_eq!(empty_config.witnessing, Witnessing::default());
+        assert_eq!(config.witnessing.max_concurrent_jobs, Some(2));
+        assert_eq!(
+            config.witnessing.sandbox_timeout,
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(config.witnessing.max_retrieved_size, Some(1024));
+    }
+
+    #[test]
+    fn test_configure_wallet() {
+        let empty_config = super::from_str("[wallet]").unwrap();
+        let config = super::from_str(
+            r#"
+[wallet]
+external_signer = "127.0.0.1:21339"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(empty_config.wallet, Wallet::default());
+        assert_eq!(
+            config.wallet.external_signer,
+            Some("127.0.0.1:21339".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_configure_telemetry() {
+        let empty_config = super::from_str("[telemetry]").unwrap();
+        let config = super::from_str(
+            r#"
+[telemetry]
+enabled = true
+endpoint = "https://example.com/report"
+period_seconds = 600
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(empty_config.telemetry, Telemetry::default());
+        assert_eq!(config.telemetry.enabled, Some(true));
+        assert_eq!(
+            config.telemetry.endpoint,
+            Some("https://example.com/report".to_string())
+        );
+        assert_eq!(
+            config.telemetry.period,
+            Some(std::time::Duration::from_secs(600))
+        );
+    }
+
+    #[test]
+    fn test_configure_mailboxes() {
+        let empty_config = super::from_str("[mailboxes]").unwrap();
+        let config = super::from_str(
+            r"
+[mailboxes]
+blocks_manager = 1024
+session = 8
+",
+        )
+        .unwrap();
+
+        assert_eq!(empty_config.mailboxes, Mailboxes::default());
+        assert_eq!(config.mailboxes.blocks_manager, Some(1024));
+        assert_eq!(config.mailboxes.storage_manager, None);
+        assert_eq!(config.mailboxes.session, Some(8));
+    }
+
+    #[test]
+    fn test_configure_node_roles() {
+        use crate::config::Role;
+
+        let empty_config = super::from_str("[node]").unwrap();
+        let config = super::from_str(
+            r#"
+[node]
+roles = ["witness", "relay"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(empty_config.node, Node::default());
+        assert_eq!(
+            config.node.roles,
+            Some(vec![Role::Witness, Role::Relay].into_iter().collect())
+        );
+        assert!(super::from_str("[node]\nroles = [\"validator\"]").is_err());
+    }
+
+    #[test]
+    fn test_configure_node_restarts() {
+        use std::time::Duration;
+
+        let config = super::from_str(
+            r#"
+[node]
+max_restarts = 5
+restart_window_seconds = 30
+max_reorg_depth = 20
+max_epochs_behind = 5
+tracing = true
+relay_first = true
+signature_workers = 2
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.node.max_restarts, Some(5));
+        assert_eq!(config.node.restart_window, Some(Duration::from_secs(30)));
+        assert_eq!(config.node.max_reorg_depth, Some(20));
+        assert_eq!(config.node.max_epochs_behind, Some(5));
+        assert_eq!(config.node.tracing, Some(true));
+        assert_eq!(config.node.relay_first, Some(true));
+        assert_eq!(config.node.signature_workers, Some(2));
+    }
 }
@@ -0,0 +1,192 @@
+//! # Configuration file template
+//!
+//! Commented configuration file written by `witnet init`. Every param
+//! is listed with its default value for the environment, commented
+//! out, so the file documents what can be configured without
+//! overriding any default.
+use std::time::Duration;
+
+use crate::defaults::Defaults;
+
+/// Contents of the configuration file with the defaults of an
+/// environment
+pub fn config_file_contents(environment: &str, defaults: &dyn Defaults) -> String {
+    format!(
+        r#"# Witnet node configuration
+#
+# The commented out params show their default value. You can read more about
+# all the configuration params in https://docs.witnet.io/configuration/toml-file/
+
+environment = "{environment}"
+
+[connections] # section for params related to the P2P connections
+# server_addr = "{server_addr}"
+# inbound_limit = {inbound_limit}
+# outbound_limit = {outbound_limit}
+# known_peers = []
+# bootstrap_peers_period_seconds = {bootstrap_peers_period}
+# storage_peers_period_seconds = {storage_peers_period}
+# discovery_peers_period_seconds = {discovery_peers_period}
+# handshake_timeout_seconds = {handshake_timeout}
+# announcement_debounce_millis = {announcement_debounce}
+# port_mapping = {port_mapping}
+# encryption = {encryption}
+# compression = {compression}
+# compression_threshold = {compression_threshold}
+# peer_eviction_days = {peer_eviction_days}
+# dns_seeds = []
+# isolation_epochs = {isolation_epochs}
+
+[storage] # section for storage-related params
+db_path = {db_path:?}
+# cache_size = {cache_size}
+# maintenance_period_seconds = {maintenance_period}
+# maintenance_idle_seconds = {maintenance_idle}
+# stale_retention_epochs = {stale_retention_epochs}
+
+[jsonrpc] # section for params related to JSON-RPC API
+# enabled = {jsonrpc_enabled}
+# server_address = "{jsonrpc_server_address}"
+
+[mempool] # section for params related to the transactions pool
+# min_relay_fee = {min_relay_fee}
+# storage_period_seconds = {mempool_storage_period}
+# expiry_epochs = {mempool_expiry_epochs}
+
+[mining] # section for params related to mining
+# min_fee = {min_fee}
+
+[witnessing] # section for params related to witnessing data requests
+# max_concurrent_jobs = {max_concurrent_jobs}
+# sandbox_timeout_seconds = {sandbox_timeout}
+# max_retrieved_size = {max_retrieved_size}
+
+[wallet] # section for params related to the wallet
+# external_signer = "127.0.0.1:21339"
+
+[telemetry] # section for params related to the anonymized statistics reports
+# enabled = {telemetry_enabled}
+# endpoint = "https://example.com/report"
+# period_seconds = {telemetry_period}
+
+[watchdog] # section for the time budgets of the message handlers
+# budget_millis = {watchdog_budget}
+# budgets_millis = {watchdog_budgets}
+# slow_messages_limit = {slow_messages_limit}
+# slow_messages_window_seconds = {slow_messages_window}
+
+[node] # section for node-specific params
+# roles = ["miner", "witness", "relay"]
+# max_restarts = {max_restarts}
+# restart_window_seconds = {restart_window}
+# max_reorg_depth = {max_reorg_depth}
+# max_epochs_behind = {max_epochs_behind}
+# tracing = {tracing}
+# relay_first = {relay_first}
+# signature_workers = {signature_workers}
+"#,
+        environment = environment,
+        server_addr = defaults.connections_server_addr(),
+        inbound_limit = defaults.connections_inbound_limit(),
+        outbound_limit = defaults.connections_outbound_limit(),
+        bootstrap_peers_period = defaults.connections_bootstrap_peers_period().as_secs(),
+        storage_peers_period = defaults.connections_storage_peers_period().as_secs(),
+        discovery_peers_period = defaults.connections_discovery_peers_period().as_secs(),
+        handshake_timeout = defaults.connections_handshake_timeout().as_secs(),
+        announcement_debounce = millis(defaults.connections_announcement_debounce()),
+        port_mapping = defaults.connections_port_mapping(),
+        encryption = defaults.connections_encryption(),
+        compression = defaults.connections_compression(),
+        compression_threshold = defaults.connections_compression_threshold(),
+        peer_eviction_days = defaults.connections_peer_eviction_days(),
+        isolation_epochs = defaults.connections_isolation_epochs(),
+        db_path = defaults.storage_db_path(),
+        cache_size = defaults.storage_cache_size(),
+        maintenance_period = defaults.storage_maintenance_period().as_secs(),
+        maintenance_idle = defaults.storage_maintenance_idle().as_secs(),
+        stale_retention_epochs = defaults.storage_stale_retention_epochs(),
+        jsonrpc_enabled = defaults.jsonrpc_enabled(),
+        jsonrpc_server_address = defaults.jsonrpc_server_address(),
+        min_relay_fee = defaults.mempool_min_relay_fee(),
+        mempool_storage_period = defaults.mempool_storage_period().as_secs(),
+        mempool_expiry_epochs = defaults.mempool_expiry_epochs(),
+        min_fee = defaults.mining_min_fee(),
+        max_concurrent_jobs = defaults.witnessing_max_concurrent_jobs(),
+        sandbox_timeout = defaults.witnessing_sandbox_timeout().as_secs(),
+        max_retrieved_size = defaults.witnessing_max_retrieved_size(),
+        telemetry_enabled = defaults.telemetry_enabled(),
+        telemetry_period = defaults.telemetry_period().as_secs(),
+        watchdog_budget = millis(defaults.watchdog_budget()),
+        watchdog_budgets = budgets(defaults),
+        slow_messages_limit = defaults.watchdog_slow_messages_limit(),
+        slow_messages_window = defaults.watchdog_slow_messages_window().as_secs(),
+        max_restarts = defaults.node_max_restarts(),
+        restart_window = defaults.node_restart_window().as_secs(),
+        max_reorg_depth = defaults.node_max_reorg_depth(),
+        max_epochs_behind = defaults.node_max_epochs_behind(),
+        tracing = defaults.node_tracing(),
+        relay_first = defaults.node_relay_first(),
+        signature_workers = defaults.node_signature_workers(),
+    )
+}
+
+/// Whole milliseconds of a duration
+fn millis(duration: Duration) -> u64 {
+    duration.as_secs() * 1000 + u64::from(duration.subsec_millis())
+}
+
+/// Default budgets of the message types as a TOML inline table, sorted by message type
+fn budgets(defaults: &dyn Defaults) -> String {
+    let mut budgets: Vec<_> = defaults
+        .watchdog_budgets()
+        .into_iter()
+        .map(|(name, budget)| format!("{:?} = {}", name, millis(budget)))
+        .collect();
+    budgets.sort();
+
+    format!("{{ {} }}", budgets.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{partial, Config};
+    use crate::defaults::Testnet1;
+    use crate::loaders::toml;
+
+    #[test]
+    fn test_template_uses_the_defaults() {
+        let contents = config_file_contents("testnet-1", &Testnet1);
+        let partial_config = toml::from_str(&contents).unwrap();
+
+        assert_eq!(
+            partial_config.storage.db_path,
+            Some(Testnet1.storage_db_path())
+        );
+        assert_eq!(partial_config.connections, partial::Connections::default());
+        assert_eq!(Config::from_partial(&partial_config), Config::default());
+    }
+
+    #[test]
+    fn test_template_commented_params_are_valid() {
+        // Uncommenting every param yields a valid configuration with the same values
+        let contents: String = config_file_contents("testnet-1", &Testnet1)
+            .lines()
+            .map(|line| match line {
+                param if param.starts_with("# ") && param.contains(" = ") => &param[2..],
+                line => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let partial_config = toml::from_str(&contents).unwrap();
+
+        assert_eq!(
+            Config::from_partial(&partial_config).connections,
+            Config::default().connections
+        );
+        assert_eq!(
+            Config::from_partial(&partial_config).watchdog,
+            Config::default().watchdog
+        );
+    }
+}
@@ -0,0 +1,568 @@
+//! # Configuration validation
+//!
+//! The loaders only check that every field has the right type. This
+//! module checks the consistency of the values of a total
+//! [Config](config::Config), so that the node refuses to start with
+//! a list of all the problems found instead of failing later, in the
+//! middle of the execution of some actor.
+//!
+//! ```
+//! use witnet_config::{config::Config, validation};
+//!
+//! assert_eq!(validation::validate(&Config::default()), Ok(()));
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// Maximum number of sessions (inbound plus outbound) a node can keep open at the same time, as
+/// every session takes a file descriptor and most systems allow 1024 of them per process
+pub const MAX_SESSIONS: u32 = 1024;
+
+/// Problem found in the configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    /// Field of the configuration, as written in the configuration file
+    /// (e.g. `connections.outbound_limit`)
+    pub field: &'static str,
+    /// Description of the problem and how to fix it
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Check the consistency of a configuration
+///
+/// Returns all the problems found, not just the first one, so they
+/// can be fixed at once.
+pub fn validate(config: &Config) -> Result<(), Vec<Problem>> {
+    let mut problems = vec![];
+    let mut problem = |field, message: String| problems.push(Problem { field, message });
+
+    // Connections
+    let connections = &config.connections;
+    let sessions = u32::from(connections.inbound_limit) + u32::from(connections.outbound_limit);
+    if sessions > MAX_SESSIONS {
+        problem(
+            "connections.outbound_limit",
+            format!(
+                "the outbound limit ({}) plus the inbound limit ({}) must not be greater than \
+                 the maximum number of sessions ({})",
+                connections.outbound_limit, connections.inbound_limit, MAX_SESSIONS
+            ),
+        );
+    }
+    if connections.listen_addrs.contains(&connections.server_addr) {
+        problem(
+            "connections.listen_addrs",
+            format!(
+                "{} is already the server address, remove it from the additional addresses",
+                connections.server_addr
+            ),
+        );
+    }
+    let own_addrs: Vec<_> = connections
+        .known_peers
+        .iter()
+        .filter(|peer| **peer == connections.server_addr || connections.listen_addrs.contains(peer))
+        .collect();
+    for peer in own_addrs {
+        problem(
+            "connections.known_peers",
+            format!(
+                "{} is an address of this node, it cannot be a known peer",
+                peer
+            ),
+        );
+    }
+    for &(field, period) in &[
+        (
+            "connections.bootstrap_peers_period_seconds",
+            connections.bootstrap_peers_period,
+        ),
+        (
+            "connections.storage_peers_period_seconds",
+            connections.storage_peers_period,
+        ),
+        (
+            "connections.discovery_peers_period_seconds",
+            connections.discovery_peers_period,
+        ),
+        (
+            "connections.handshake_timeout_seconds",
+            connections.handshake_timeout,
+        ),
+        (
+            "mempool.storage_period_seconds",
+            config.mempool.storage_period,
+        ),
+        (
+            "storage.maintenance_period_seconds",
+            config.storage.maintenance_period,
+        ),
+    ] {
+        if period == Duration::from_secs(0) {
+            problem(field, "must be greater than 0".to_string());
+        }
+    }
+    if connections.peer_eviction_days == 0 {
+        problem(
+            "connections.peer_eviction_days",
+            "must be greater than 0".to_string(),
+        );
+    }
+    if connections.isolation_epochs == 0 {
+        problem(
+            "connections.isolation_epochs",
+            "must be greater than 0".to_string(),
+        );
+    }
+    for seed in connections
+        .dns_seeds
+        .iter()
+        .filter(|seed| !is_host_and_port(seed))
+    {
+        problem(
+            "connections.dns_seeds",
+            format!(
+                "{} must be a host name followed by a port (host:port)",
+                seed
+            ),
+        );
+    }
+
+    // Storage
+    if let Err(message) = check_writable_dir(&config.storage.db_path) {
+        problem("storage.db_path", message);
+    }
+
+    // JSON-RPC
+    if config.jsonrpc.enabled
+        && (config.jsonrpc.server_address == connections.server_addr
+            || connections
+                .listen_addrs
+                .contains(&config.jsonrpc.server_address))
+    {
+        problem(
+            "jsonrpc.server_address",
+            format!(
+                "{} is also an address of the server accepting peer connections, use another port",
+                config.jsonrpc.server_address
+            ),
+        );
+    }
+
+    // Consensus constants
+    let constants = &config.consensus_constants;
+    if constants.checkpoints_period == 0 {
+        problem(
+            "consensus_constants.checkpoints_period_seconds",
+            "must be greater than 0".to_string(),
+        );
+    }
+    if constants.halving_period == 0 {
+        problem(
+            "consensus_constants.halving_period",
+            "must be greater than 0".to_string(),
+        );
+    }
+    if constants
+        .genesis_allocation
+        .iter()
+        .any(|output| output.value == 0)
+    {
+        problem(
+            "consensus_constants.genesis_allocation",
+            "the outputs must have a value greater than 0".to_string(),
+        );
+    }
+    if constants
+        .genesis_allocation
+        .iter()
+        .try_fold(0u64, |total, output| total.checked_add(output.value))
+        .is_none()
+    {
+        problem(
+            "consensus_constants.genesis_allocation",
+            "the total value of the outputs overflows".to_string(),
+        );
+    }
+    for &(field, fraction) in &[
+        (
+            "consensus_constants.reputation_demurrage",
+            constants.reputation_demurrage,
+        ),
+        (
+            "consensus_constants.reputation_punishment",
+            constants.reputation_punishment,
+        ),
+    ] {
+        if fraction.is_nan() || fraction < 0.0 || fraction > 1.0 {
+            problem(
+                field,
+                format!("must be a fraction between 0 and 1, not {}", fraction),
+            );
+        }
+    }
+
+    // Telemetry
+    if config.telemetry.enabled {
+        match &config.telemetry.endpoint {
+            None => problem(
+                "telemetry.endpoint",
+                "must be set when the telemetry is enabled".to_string(),
+            ),
+            Some(endpoint)
+                if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") =>
+            {
+                problem(
+                    "telemetry.endpoint",
+                    format!("{} is not an HTTP or HTTPS URL", endpoint),
+                )
+            }
+            Some(_) => {}
+        }
+        if config.telemetry.period == Duration::from_secs(0) {
+            problem(
+                "telemetry.period_seconds",
+                "must be greater than 0".to_string(),
+            );
+        }
+    }
+
+    // Witnessing
+    if config.witnessing.max_concurrent_jobs == 0 {
+        problem(
+            "witnessing.max_concurrent_jobs",
+            "must be greater than 0".to_string(),
+        );
+    }
+    if config.witnessing.sandbox_timeout == Duration::from_secs(0) {
+        problem(
+            "witnessing.sandbox_timeout_seconds",
+            "must be greater than 0".to_string(),
+        );
+    }
+    if config.witnessing.max_retrieved_size == 0 {
+        problem(
+            "witnessing.max_retrieved_size",
+            "must be greater than 0".to_string(),
+        );
+    }
+
+    // Mailboxes
+    for &(field, capacity) in &[
+        ("mailboxes.blocks_manager", config.mailboxes.blocks_manager),
+        (
+            "mailboxes.storage_manager",
+            config.mailboxes.storage_manager,
+        ),
+        ("mailboxes.session", config.mailboxes.session),
+    ] {
+        if capacity == 0 {
+            problem(field, "must be greater than 0".to_string());
+        }
+    }
+
+    // Watchdog
+    let watchdog = &config.watchdog;
+    if watchdog.budget == Duration::from_secs(0) {
+        problem(
+            "watchdog.budget_millis",
+            "must be greater than 0".to_string(),
+        );
+    }
+    let mut zero_budgets: Vec<_> = watchdog
+        .budgets
+        .iter()
+        .filter(|(_, budget)| **budget == Duration::from_secs(0))
+        .map(|(name, _)| name)
+        .collect();
+    zero_budgets.sort();
+    for name in zero_budgets {
+        problem(
+            "watchdog.budgets_millis",
+            format!("the budget of {} must be greater than 0", name),
+        );
+    }
+    if watchdog.slow_messages_limit == 0 {
+        problem(
+            "watchdog.slow_messages_limit",
+            "must be greater than 0".to_string(),
+        );
+    }
+    if watchdog.slow_messages_window == Duration::from_secs(0) {
+        problem(
+            "watchdog.slow_messages_window_seconds",
+            "must be greater than 0".to_string(),
+        );
+    }
+
+    // Node
+    if config.node.max_restarts == 0 {
+        problem(
+            "node.max_restarts",
+            "must be greater than 0, otherwise the node stops on the first failure of an actor"
+                .to_string(),
+        );
+    }
+
+    if config.node.max_epochs_behind == 0 {
+        problem(
+            "node.max_epochs_behind",
+            "must be greater than 0, otherwise the node goes back to syncing on every epoch"
+                .to_string(),
+        );
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+/// Check that an address is a host name followed by a port
+fn is_host_and_port(address: &str) -> bool {
+    let mut parts = address.rsplitn(2, ':');
+    let port = parts.next().map(str::parse::<u16>);
+    let host = parts.next();
+
+    match (host, port) {
+        (Some(host), Some(Ok(_))) => !host.is_empty(),
+        _ => false,
+    }
+}
+
+/// Check that a directory can be written: either it exists and is
+/// writable, or it can be created inside its nearest existing ancestor
+///
+/// The permission bits do not tell whether the process can actually
+/// write (ACLs, ownership, read-only mounts...), so a temporary file
+/// is created and removed to find out.
+fn check_writable_dir(path: &Path) -> Result<(), String> {
+    let existing = match path.ancestors().find(|ancestor| ancestor.exists()) {
+        Some(existing) => existing,
+        // A relative path with no existing ancestor is created in the working directory
+        None => Path::new("."),
+    };
+    let metadata = fs::metadata(existing)
+        .map_err(|e| format!("{} cannot be accessed: {}", existing.display(), e))?;
+    if !metadata.is_dir() {
+        return Err(format!("{} is not a directory", existing.display()));
+    }
+    let probe = existing.join(format!(".witnet-write-check-{}", std::process::id()));
+    fs::File::create(&probe)
+        .map_err(|e| format!("{} is not writable: {}", existing.display(), e))?;
+    fs::remove_file(&probe).map_err(|e| format!("{} cannot be removed: {}", probe.display(), e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fields(config: &Config) -> Vec<&'static str> {
+        validate(config)
+            .unwrap_err()
+            .into_iter()
+            .map(|problem| problem.field)
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_default() {
+        assert_eq!(validate(&Config::default()), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_reports_all_problems() {
+        let mut config = Config::default();
+        config.connections.inbound_limit = 1000;
+        config.connections.outbound_limit = 100;
+        config.consensus_constants.checkpoints_period = 0;
+        config.consensus_constants.reputation_punishment = 1.5;
+        config.jsonrpc.enabled = true;
+        config.jsonrpc.server_address = config.connections.server_addr;
+
+        assert_eq!(
+            fields(&config),
+            vec![
+                "connections.outbound_limit",
+                "jsonrpc.server_address",
+                "consensus_constants.checkpoints_period_seconds",
+                "consensus_constants.reputation_punishment",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_session_limits() {
+        // Nodes which do not accept many connections can still open more than they accept
+        let mut config = Config::default();
+        config.connections.inbound_limit = 4;
+        config.connections.outbound_limit = 8;
+        assert_eq!(validate(&config), Ok(()));
+
+        config.connections.inbound_limit = 0;
+        config.connections.outbound_limit = 1025;
+        assert_eq!(fields(&config), vec!["connections.outbound_limit"]);
+    }
+
+    #[test]
+    fn test_validate_addresses() {
+        let mut config = Config::default();
+        let server_addr = config.connections.server_addr;
+        config.connections.listen_addrs = vec![server_addr];
+        config.connections.known_peers.insert(server_addr);
+
+        assert_eq!(
+            fields(&config),
+            vec!["connections.listen_addrs", "connections.known_peers"]
+        );
+    }
+
+    #[test]
+    fn test_validate_dns_seeds() {
+        let mut config = Config::default();
+        config.connections.dns_seeds = vec![
+            "seed.example.com:21337".to_string(),
+            "seed.example.com".to_string(),
+            ":21337".to_string(),
+            "seed.example.com:port".to_string(),
+        ];
+
+        assert_eq!(
+            fields(&config),
+            vec![
+                "connections.dns_seeds",
+                "connections.dns_seeds",
+                "connections.dns_seeds",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_zero_values() {
+        let mut config = Config::default();
+        config.connections.handshake_timeout = Duration::from_secs(0);
+        config.connections.peer_eviction_days = 0;
+        config.connections.isolation_epochs = 0;
+        config.witnessing.max_concurrent_jobs = 0;
+        config.witnessing.sandbox_timeout = Duration::from_secs(0);
+        config.witnessing.max_retrieved_size = 0;
+        config.mailboxes.session = 0;
+        config.node.max_epochs_behind = 0;
+
+        assert_eq!(
+            fields(&config),
+            vec![
+                "connections.handshake_timeout_seconds",
+                "connections.peer_eviction_days",
+                "connections.isolation_epochs",
+                "witnessing.max_concurrent_jobs",
+                "witnessing.sandbox_timeout_seconds",
+                "witnessing.max_retrieved_size",
+                "mailboxes.session",
+                "node.max_epochs_behind",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_watchdog() {
+        let mut config = Config::default();
+        config.watchdog.budget = Duration::from_secs(0);
+        config
+            .watchdog
+            .budgets
+            .insert("Session::INV".to_string(), Duration::from_secs(0));
+        config.watchdog.slow_messages_limit = 0;
+
+        assert_eq!(
+            fields(&config),
+            vec![
+                "watchdog.budget_millis",
+                "watchdog.budgets_millis",
+                "watchdog.slow_messages_limit",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_genesis_allocation() {
+        use witnet_data_structures::chain::ValueTransferOutput;
+
+        let output = |value| ValueTransferOutput {
+            pkh: [1; 20],
+            value,
+            time_lock: 0,
+        };
+        let mut config = Config::default();
+        config.consensus_constants.genesis_allocation = vec![output(1000), output(2000)];
+        assert_eq!(validate(&config), Ok(()));
+
+        config.consensus_constants.genesis_allocation = vec![output(0)];
+        assert_eq!(
+            fields(&config),
+            vec!["consensus_constants.genesis_allocation"]
+        );
+
+        config.consensus_constants.genesis_allocation = vec![output(u64::max_value()), output(1)];
+        assert_eq!(
+            fields(&config),
+            vec!["consensus_constants.genesis_allocation"]
+        );
+    }
+
+    #[test]
+    fn test_validate_telemetry() {
+        let mut config = Config::default();
+        config.telemetry.enabled = true;
+        assert_eq!(fields(&config), vec!["telemetry.endpoint"]);
+
+        config.telemetry.endpoint = Some("example.com".to_string());
+        assert_eq!(fields(&config), vec!["telemetry.endpoint"]);
+
+        config.telemetry.endpoint = Some("https://example.com/report".to_string());
+        assert_eq!(validate(&config), Ok(()));
+    }
+
+    #[test]
+    fn test_problem_display() {
+        let problem = Problem {
+            field: "witnessing.max_concurrent_jobs",
+            message: "must be greater than 0".to_string(),
+        };
+
+        assert_eq!(
+            problem.to_string(),
+            "witnessing.max_concurrent_jobs: must be greater than 0"
+        );
+    }
+
+    #[test]
+    fn test_check_writable_dir() {
+        // A file cannot be used as a directory
+        let file = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        assert!(check_writable_dir(&file.join("db")).is_err());
+
+        // Missing directories are created in their nearest existing ancestor
+        let missing = std::env::temp_dir().join("witnet-missing-dir").join("db");
+        assert_eq!(check_writable_dir(&missing), Ok(()));
+        // The probe file is removed
+        let probe =
+            std::env::temp_dir().join(format!(".witnet-write-check-{}", std::process::id()));
+        assert!(!probe.exists());
+    }
+}
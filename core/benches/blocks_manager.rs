@@ -0,0 +1,95 @@
+//! Hashing of block candidates and their insertion into the candidate pool of the BlocksManager
+//!
+//! Run with `cargo bench -p witnet_core --bench blocks_manager`
+
+use std::time::Instant;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use witnet_core::actors::blocks_manager::messages::AddNewBlock;
+use witnet_core::tracing::Trace;
+use witnet_crypto::{hash::calculate_sha256, merkle::merkle_root};
+use witnet_data_structures::{
+    chain::{
+        Block, BlockHeader, BlockHeaderWithProof, CheckpointBeacon, ConsensusConstants, Epoch,
+        LeadershipProof, MintTransaction, Transaction, ValueTransferOutput,
+    },
+    emission,
+};
+use witnet_storage::storage::Storable;
+
+pub mod support;
+
+use self::support::BenchNode;
+
+/// Number of transactions of the block candidates
+const TRANSACTIONS: usize = 100;
+
+/// Build a valid block candidate for an epoch extending the genesis block, which only differs
+/// from the other candidates in its influence
+fn block_candidate(
+    epoch: Epoch,
+    influence: u64,
+    consensus_constants: &ConsensusConstants,
+) -> Block {
+    Block {
+        header: BlockHeaderWithProof {
+            block_header: BlockHeader {
+                version: 1,
+                beacon: CheckpointBeacon {
+                    checkpoint: epoch,
+                    hash_prev_block: consensus_constants.genesis_hash,
+                },
+                hash_merkle_root: merkle_root(&[]),
+                utxo_merkle_root: merkle_root(&[]),
+            },
+            proof: LeadershipProof {
+                block_sig: None,
+                influence,
+            },
+        },
+        txn_count: TRANSACTIONS as u32,
+        txns: vec![Transaction; TRANSACTIONS],
+        mint: MintTransaction {
+            epoch,
+            output: ValueTransferOutput {
+                pkh: [0; 20],
+                value: emission::block_reward(epoch, consensus_constants),
+                time_lock: emission::reward_time_lock(epoch, consensus_constants),
+            },
+        },
+    }
+}
+
+fn candidates(c: &mut Criterion) {
+    let mut node = BenchNode::start("blocks_manager");
+    let epoch = node.epoch;
+    let consensus_constants = node.config.consensus_constants.clone();
+
+    let block = block_candidate(epoch, 0, &consensus_constants);
+    c.bench_function("hash a block candidate", move |b| {
+        b.iter(|| calculate_sha256(&block.to_bytes().unwrap()))
+    });
+
+    // Every candidate has a higher influence than the previous ones, so once the candidate pool
+    // of the epoch is full every new candidate evicts the one with the lowest influence
+    let mut influence = 0;
+    c.bench_function("hash and insert a block candidate", move |b| {
+        b.iter(|| {
+            influence += 1;
+            let block = block_candidate(epoch, influence, &consensus_constants);
+            node.system
+                .block_on(node.blocks_manager.send(AddNewBlock {
+                    block,
+                    received_at: Instant::now(),
+                    trace: Trace::default(),
+                    origin: None,
+                }))
+                .unwrap()
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, candidates);
+criterion_main!(benches);
@@ -0,0 +1,100 @@
+//! Encoding and decoding of a large block as it is sent to and received from the peers: the
+//! protocol message is serialized, compressed and framed by the `P2PCodec`, and the other way
+//! around
+//!
+//! Run with `cargo bench -p witnet_core --bench codec`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use tokio::codec::{Decoder, Encoder};
+
+use witnet_core::actors::codec::{compress_message, decompress_message, BytesMut, P2PCodec};
+use witnet_data_structures::{
+    chain::{
+        BlockHeader, BlockHeaderWithProof, CheckpointBeacon, Hash, LeadershipProof,
+        MintTransaction, Transaction, ValueTransferOutput,
+    },
+    serializers::TryFrom,
+    types::Message as WitnetMessage,
+};
+
+/// Number of transactions of the block
+const TRANSACTIONS: usize = 1000;
+
+/// Minimum size of the messages which are compressed (the default of
+/// `connections.compression_threshold`)
+const COMPRESSION_THRESHOLD: usize = 1024;
+
+fn large_block() -> WitnetMessage {
+    WitnetMessage::build_block(
+        BlockHeaderWithProof {
+            block_header: BlockHeader {
+                version: 1,
+                beacon: CheckpointBeacon {
+                    checkpoint: 1,
+                    hash_prev_block: Hash::SHA256([1; 32]),
+                },
+                hash_merkle_root: Hash::SHA256([2; 32]),
+                utxo_merkle_root: Hash::SHA256([3; 32]),
+            },
+            proof: LeadershipProof {
+                block_sig: None,
+                influence: 1,
+            },
+        },
+        vec![Transaction; TRANSACTIONS],
+        MintTransaction {
+            epoch: 1,
+            output: ValueTransferOutput {
+                pkh: [4; 20],
+                value: 500,
+                time_lock: 2,
+            },
+        },
+    )
+}
+
+/// Serialize, compress and frame a message
+fn encode(msg: WitnetMessage, compression_threshold: usize) -> BytesMut {
+    let bytes: Vec<u8> = msg.into();
+    let frame = compress_message(bytes, compression_threshold).unwrap();
+    let mut dst = BytesMut::new();
+    P2PCodec.encode(BytesMut::from(frame), &mut dst).unwrap();
+
+    dst
+}
+
+/// Unframe, decompress and deserialize a message
+fn decode(mut src: BytesMut) -> WitnetMessage {
+    let frame = P2PCodec.decode(&mut src).unwrap().unwrap();
+    let bytes = decompress_message(&frame).unwrap();
+
+    WitnetMessage::try_from(bytes).unwrap()
+}
+
+fn block(c: &mut Criterion) {
+    let msg = large_block();
+    c.bench_function("encode a block of 1000 transactions", move |b| {
+        b.iter(|| encode(msg.clone(), COMPRESSION_THRESHOLD))
+    });
+
+    let frame = encode(large_block(), COMPRESSION_THRESHOLD);
+    c.bench_function("decode a block of 1000 transactions", move |b| {
+        b.iter(|| decode(frame.clone()))
+    });
+
+    // Without compression
+    let msg = large_block();
+    c.bench_function(
+        "encode an uncompressed block of 1000 transactions",
+        move |b| b.iter(|| encode(msg.clone(), usize::max_value())),
+    );
+
+    let frame = encode(large_block(), usize::max_value());
+    c.bench_function(
+        "decode an uncompressed block of 1000 transactions",
+        move |b| b.iter(|| decode(frame.clone())),
+    );
+}
+
+criterion_group!(benches, block);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+//! Admission of transactions into the mempool while many of them are received at the same time
+//!
+//! Run with `cargo bench -p witnet_core --bench mempool`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use futures::future::join_all;
+
+use witnet_core::actors::mempool_manager::{
+    messages::{AddTransaction, TransactionsConfirmed},
+    MempoolTransaction,
+};
+use witnet_core::tracing::Trace;
+use witnet_data_structures::chain::{Hash, Transaction};
+
+pub mod support;
+
+use self::support::BenchNode;
+
+/// Number of transactions received at the same time
+const TRANSACTIONS: usize = 1000;
+
+fn admission(c: &mut Criterion) {
+    let mut node = BenchNode::start("mempool");
+
+    // The mempool manager rejects the transactions until it has read its configuration
+    while node
+        .system
+        .block_on(node.mempool_manager.send(AddTransaction {
+            transaction: Transaction,
+            fee: 0,
+        }))
+        .unwrap()
+        .is_err()
+    {
+        node.pause();
+    }
+
+    let hash = MempoolTransaction::new(Transaction, 0, 0).unwrap().hash;
+    let BenchNode {
+        mut system,
+        mempool_manager,
+        epoch,
+        ..
+    } = node;
+    c.bench_function("admit 1000 transactions into the mempool", move |b| {
+        b.iter_with_setup(
+            // Every run starts with an empty mempool
+            || {
+                mempool_manager.do_send(TransactionsConfirmed {
+                    block_hash: Hash::SHA256([0; 32]),
+                    checkpoint: epoch,
+                    transactions: vec![hash],
+                    trace: Trace::default(),
+                })
+            },
+            |()| {
+                let admissions = (0..TRANSACTIONS).map(|_| {
+                    mempool_manager.send(AddTransaction {
+                        transaction: Transaction,
+                        fee: 0,
+                    })
+                });
+                system.block_on(join_all(admissions)).unwrap()
+            },
+        )
+    });
+}
+
+criterion_group!(benches, admission);
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+//! Verification of the signatures of a block with many transactions, in the current thread
+//! and in the pool of the SignatureVerifier
+//!
+//! Run with `cargo bench -p witnet_core`
+
+use actix::System;
+use criterion::{criterion_group, criterion_main, Criterion};
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+use witnet_core::actors::signature_verifier::{verify_in_parallel, SignatureVerifier};
+use witnet_crypto::signature::{sign, verify_all, SignatureJob};
+
+/// Number of signatures of the block
+const SIGNATURES: usize = 1000;
+
+/// Number of workers of the pool
+const WORKERS: usize = 4;
+
+fn signature_jobs() -> Vec<SignatureJob> {
+    let secp = Secp256k1::new();
+
+    (0..SIGNATURES)
+        .map(|i| {
+            let mut secret = [1; 32];
+            secret[..8].copy_from_slice(&(i as u64).to_be_bytes());
+            let secret_key = SecretKey::from_slice(&secret).unwrap();
+            let message = [(i % 256) as u8; 32];
+
+            SignatureJob {
+                public_key: PublicKey::from_secret_key(&secp, &secret_key)
+                    .serialize()
+                    .to_vec(),
+                message,
+                signature: sign(&secp, &secret_key, &message),
+            }
+        })
+        .collect()
+}
+
+fn sequential(c: &mut Criterion) {
+    let secp = Secp256k1::verification_only();
+    let jobs = signature_jobs();
+
+    c.bench_function("verify 1000 signatures sequentially", move |b| {
+        b.iter(|| verify_all(&secp, &jobs).unwrap())
+    });
+}
+
+fn parallel(c: &mut Criterion) {
+    let mut system = System::new("signature_verification");
+    let pool = SignatureVerifier::start_pool(WORKERS);
+    let jobs = signature_jobs();
+
+    c.bench_function(
+        "verify 1000 signatures with a pool of 4 workers",
+        move |b| {
+            b.iter(|| {
+                system
+                    .block_on(verify_in_parallel(&pool, jobs.clone(), WORKERS))
+                    .unwrap()
+            })
+        },
+    );
+}
+
+criterion_group!(benches, sequential, parallel);
+criterion_main!(benches);
@@ -0,0 +1,100 @@
+//! Writes and reads of values through the StorageManager and directly into each storage backend
+//!
+//! Run with `cargo bench -p witnet_core --bench storage`
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use witnet_core::actors::storage_manager::messages::{Get, Put};
+use witnet_storage::{
+    backends::{in_memory::InMemoryStorage, rocks::RocksStorage},
+    storage::Storage,
+};
+
+pub mod support;
+
+use self::support::BenchNode;
+
+/// Number of different keys written by the benchmarks
+const KEYS: usize = 1000;
+
+/// Size of the values written by the benchmarks
+const VALUE_SIZE: usize = 1024;
+
+/// Storage of the RocksDB backend benchmarks
+const ROCKS_DB_PATH: &str = "../target/bench-rocks";
+
+fn keys() -> Vec<Vec<u8>> {
+    (0..KEYS)
+        .map(|i| format!("bench-key-{}", i).into_bytes())
+        .collect()
+}
+
+fn storage_manager(c: &mut Criterion) {
+    // The node is shared by both benchmarks
+    let node = Rc::new(RefCell::new(BenchNode::start("storage")));
+    let keys = Rc::new(keys());
+
+    let (put_node, put_keys) = (node.clone(), keys.clone());
+    c.bench_function("put a value through the StorageManager", move |b| {
+        let mut node = put_node.borrow_mut();
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % KEYS;
+            let put = Put::from_value(put_keys[i].clone(), &vec![0u8; VALUE_SIZE]).unwrap();
+            let storage_manager = node.storage_manager.clone();
+            node.system
+                .block_on(storage_manager.send(put))
+                .unwrap()
+                .unwrap()
+        })
+    });
+
+    // Every key has been written by the previous benchmark, so the values read are in the read
+    // cache of the StorageManager
+    c.bench_function("get a value through the StorageManager", move |b| {
+        let mut node = node.borrow_mut();
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % KEYS;
+            let storage_manager = node.storage_manager.clone();
+            node.system
+                .block_on(storage_manager.send(Get::<Vec<u8>>::new(keys[i].clone())))
+                .unwrap()
+                .unwrap()
+                .unwrap()
+        })
+    });
+}
+
+fn backends(c: &mut Criterion) {
+    let value = vec![0u8; VALUE_SIZE];
+    c.bench_function("put and get a value in the in-memory backend", move |b| {
+        let keys = keys();
+        let mut storage = InMemoryStorage::new(()).unwrap();
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % KEYS;
+            storage.put(&keys[i], value.clone()).unwrap();
+            storage.get(&keys[i]).unwrap().unwrap()
+        })
+    });
+
+    let value = vec![0u8; VALUE_SIZE];
+    let keys = keys();
+    let _ = std::fs::remove_dir_all(ROCKS_DB_PATH);
+    let mut storage = RocksStorage::new(ROCKS_DB_PATH.to_string()).unwrap();
+    c.bench_function("put and get a value in the RocksDB backend", move |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % KEYS;
+            storage.put(&keys[i], value.clone()).unwrap();
+            storage.get(&keys[i]).unwrap().unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, storage_manager, backends);
+criterion_main!(benches);
@@ -0,0 +1,109 @@
+//! Node started for the actor-level benchmarks
+//!
+//! Only the actors exercised by the benchmarks (and the ones they depend
+//! on) are started, with the configuration of `benches/fixtures/bench.toml`
+//! and an empty storage, and requests are sent to them through their
+//! addresses as the rest of the node does.
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix::{Actor, Addr, System, SystemRunner};
+use futures::Future;
+use tokio::timer::Delay;
+
+use witnet_config::config::Config;
+use witnet_core::actors::{
+    blocks_manager::{messages::GetHighestCheckpointBeacon, BlocksManager},
+    config_manager::{messages::GetConfig, ConfigManager},
+    epoch_manager::{messages::GetEpoch, EpochManager},
+    mempool_manager::MempoolManager,
+    storage_manager::StorageManager,
+};
+use witnet_data_structures::chain::Epoch;
+
+/// Configuration of the node (checkpoint zero far in the past and the longest checkpoints period,
+/// so the current epoch does not change while the benchmarks run)
+const CONFIG_FILE: &str = "benches/fixtures/bench.toml";
+
+/// Storage of the node, as set in the configuration file
+const DB_PATH: &str = "../target/bench-storage";
+
+/// Time between the checks of whether the actors have finished starting
+const STARTUP_POLL_PERIOD: Duration = Duration::from_millis(10);
+
+/// Node started for the benchmarks
+pub struct BenchNode {
+    /// System running the actors, which runs the requests of the benchmarks to completion
+    pub system: SystemRunner,
+    /// Configuration of the node
+    pub config: Arc<Config>,
+    /// Current epoch
+    pub epoch: Epoch,
+    /// Address of the blocks manager
+    pub blocks_manager: Addr<BlocksManager>,
+    /// Address of the mempool manager
+    pub mempool_manager: Addr<MempoolManager>,
+    /// Address of the storage manager
+    pub storage_manager: Addr<StorageManager>,
+}
+
+impl BenchNode {
+    /// Start the actors of the node, returning once the chain info has been initialized
+    pub fn start(name: &str) -> BenchNode {
+        // Every run starts with an empty storage
+        let _ = fs::remove_dir_all(DB_PATH);
+        let mut system = System::new(name);
+
+        let registry = System::current().registry();
+        registry.set(ConfigManager::new(Some(PathBuf::from(CONFIG_FILE))).start());
+        let storage_manager = StorageManager::default().start();
+        registry.set(storage_manager.clone());
+        let epoch_manager = EpochManager::default().start();
+        registry.set(epoch_manager.clone());
+        let blocks_manager = BlocksManager::default().start();
+        registry.set(blocks_manager.clone());
+        let mempool_manager = MempoolManager::default().start();
+        registry.set(mempool_manager.clone());
+
+        let config = system
+            .block_on(registry.get::<ConfigManager>().send(GetConfig))
+            .unwrap()
+            .unwrap();
+        let epoch = system
+            .block_on(epoch_manager.send(GetEpoch))
+            .unwrap()
+            .unwrap();
+
+        // The chain info is read from the storage (or initialized) asynchronously
+        while system
+            .block_on(blocks_manager.send(GetHighestCheckpointBeacon))
+            .unwrap()
+            .is_err()
+        {
+            pause(&mut system);
+        }
+
+        BenchNode {
+            system,
+            config,
+            epoch,
+            blocks_manager,
+            mempool_manager,
+            storage_manager,
+        }
+    }
+
+    /// Let the actors process the messages sent to them for a while
+    pub fn pause(&mut self) {
+        pause(&mut self.system);
+    }
+}
+
+/// Run the system for a while
+fn pause(system: &mut SystemRunner) {
+    system
+        .block_on(Delay::new(Instant::now() + STARTUP_POLL_PERIOD).map_err(|_| ()))
+        .unwrap();
+}
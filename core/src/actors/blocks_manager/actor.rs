@@ -6,19 +6,32 @@ use crate::actors::epoch_manager::{
 };
 
 use crate::actors::blocks_manager::{
+    discard_inconsistent_superblocks, ensure_consistency,
     handlers::{EpochPayload, EveryEpochPayload},
-    BlocksManager,
+    orphaned_blocks,
+    partition::PartitionDetector,
+    stats::EpochOutcomes,
+    wal::{ChainMutation, Recovery},
+    BlockIndex, BlocksManager, BLOCK_DOWNLOAD_CHECK_PERIOD_SECONDS,
 };
 
 use crate::actors::{
     config_manager::send_get_config_request,
-    storage_keys::CHAIN_KEY,
+    sessions_manager::{messages::SubscribeSessionEvents, SessionsManager},
+    signature_verifier::SignatureVerifier,
+    storage_keys::{BLOCK_INDEX_KEY, CHAIN_KEY, CHAIN_STATS_KEY, CHAIN_WAL_KEY, SUPERBLOCKS_KEY},
     storage_manager::{messages::Get, StorageManager},
 };
 
-use witnet_data_structures::chain::{ChainInfo, CheckpointBeacon};
+use crate::watchdog::Watchdog;
 
-use log::{debug, error, info};
+use witnet_config::config::Role;
+use witnet_data_structures::chain::{ChainInfo, CheckpointBeacon, SuperBlock};
+use witnet_storage::error::{StorageErrorKind, StorageResult};
+
+use futures::Future;
+use log::{debug, error, info, warn};
+use std::time::{Duration, Instant};
 
 /// Implement Actor trait for `BlocksManager`
 impl Actor for BlocksManager {
@@ -57,8 +70,15 @@ impl Actor for BlocksManager {
                         ));
 
                         // Subscribe to all epochs with an EveryEpochPayload
+                        epoch_manager_addr.do_send(Subscribe::to_all(
+                            blocks_manager_addr.clone(),
+                            EveryEpochPayload,
+                        ));
+
+                        // Subscribe to the epochs skipped by the node, which are not recorded
+                        // as synced in the chain statistics
                         epoch_manager_addr
-                            .do_send(Subscribe::to_all(blocks_manager_addr, EveryEpochPayload));
+                            .do_send(Subscribe::to_missed_epochs(blocks_manager_addr));
                     }
                     _ => {
                         error!("Current epoch could not be retrieved from EpochManager");
@@ -72,6 +92,36 @@ impl Actor for BlocksManager {
 
         // Query ConfigManager for initial configuration and process response
         send_get_config_request(self, ctx, |act, ctx, config| {
+            // Bound the number of queued messages so senders can shed load when overwhelmed
+            ctx.set_mailbox_capacity(config.mailboxes.blocks_manager);
+
+            // Blocks older than the finality window cannot be rolled back
+            act.max_reorg_depth = config.node.max_reorg_depth;
+
+            // The node goes back to syncing when its tip lags too many epochs behind
+            act.max_epochs_behind = config.node.max_epochs_behind;
+
+            // Block candidates may be relayed before being fully validated
+            act.relay_first = config.node.relay_first;
+
+            // Only miners build block templates and accept the blocks submitted through them
+            act.mining = config.node.has_role(Role::Miner);
+
+            // The signatures of the blocks are verified by a pool of workers in other threads
+            act.signature_workers = config.node.signature_workers;
+            act.signature_verifier = Some(SignatureVerifier::start_pool(act.signature_workers));
+
+            // The handlers of the blocks received from the peers are timed against a budget
+            act.watchdog = Watchdog::new(&config.watchdog);
+
+            // The node is isolated after some epochs without outbound sessions or new blocks,
+            // so the number of outbound sessions is followed through the session events
+            act.partition = PartitionDetector::new(config.connections.isolation_epochs);
+            let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
+            sessions_manager_addr.do_send(SubscribeSessionEvents {
+                recipient: ctx.address().recipient(),
+            });
+
             // Get environment and consensus_constants parameters from config
             let environment = (&config.environment).clone();
             let consensus_constants = (&config.consensus_constants).clone();
@@ -81,6 +131,14 @@ impl Actor for BlocksManager {
             storage_manager_addr
                 // Send a message to read the chain_info from the storage
                 .send(Get::<ChainInfo>::new(CHAIN_KEY))
+                // Send a message to read the block index from the storage
+                .join(storage_manager_addr.send(Get::<BlockIndex>::new(BLOCK_INDEX_KEY)))
+                // Send a message to read the superblocks from the storage
+                .join(storage_manager_addr.send(Get::<Vec<SuperBlock>>::new(SUPERBLOCKS_KEY)))
+                // Send a message to read the outcomes of the most recent epochs from the storage
+                .join(storage_manager_addr.send(Get::<EpochOutcomes>::new(CHAIN_STATS_KEY)))
+                // Send a message to read the write-ahead log of the chain state from the storage
+                .join(storage_manager_addr.send(Get::<Option<ChainMutation>>::new(CHAIN_WAL_KEY)))
                 .into_actor(act)
                 // Process the response
                 .then(|res, _act, _ctx| match res {
@@ -89,16 +147,53 @@ impl Actor for BlocksManager {
                         error!("Unsuccessful communication with storage manager: {}", e);
                         actix::fut::err(())
                     }
-                    Ok(res) => match res {
-                        Err(e) => {
-                            // Storage error
-                            error!("Error while getting ChainInfo from storage: {}", e);
-                            actix::fut::err(())
+                    Ok(((((chain_info, block_index), superblocks), epoch_outcomes), chain_wal)) => {
+                        // Corrupted values are discarded so they can be recovered
+                        let chain_info = discard_corrupted(chain_info, "ChainInfo");
+                        let block_index = discard_corrupted(block_index, "block index");
+                        let superblocks = discard_corrupted(superblocks, "superblocks");
+                        let epoch_outcomes = discard_corrupted(epoch_outcomes, "chain statistics");
+                        let chain_wal = discard_corrupted(chain_wal, "chain write-ahead log");
+                        match (
+                            chain_info,
+                            block_index,
+                            superblocks,
+                            epoch_outcomes,
+                            chain_wal,
+                        ) {
+                            (Err(e), _, _, _, _)
+                            | (_, Err(e), _, _, _)
+                            | (_, _, Err(e), _, _)
+                            | (_, _, _, Err(e), _)
+                            | (_, _, _, _, Err(e)) => {
+                                // Storage error
+                                error!("Error while getting ChainInfo from storage: {}", e);
+                                actix::fut::err(())
+                            }
+                            (
+                                Ok(chain_info),
+                                Ok(block_index),
+                                Ok(superblocks),
+                                Ok(epoch_outcomes),
+                                Ok(chain_wal),
+                            ) => actix::fut::ok((
+                                chain_info,
+                                block_index.unwrap_or_default(),
+                                superblocks.unwrap_or_default(),
+                                epoch_outcomes.unwrap_or_default(),
+                                chain_wal.and_then(|mutation| mutation),
+                            )),
                         }
-                        Ok(res) => actix::fut::ok(res),
-                    },
+                    }
                 })
-                .and_then(move |chain_info_from_storage, act, _ctx| {
+                .and_then(move |from_storage, act, ctx| {
+                    let (
+                        chain_info_from_storage,
+                        mut block_index,
+                        mut superblocks,
+                        epoch_outcomes,
+                        chain_wal,
+                    ) = from_storage;
                     // chain_info_from_storage can be None if the storage does not contain that key
                     if let Some(chain_info_from_storage) = chain_info_from_storage {
                         if environment == chain_info_from_storage.environment {
@@ -136,24 +231,135 @@ impl Actor for BlocksManager {
                             "Uninitialized local chain (no ChainInfo in storage). Proceeding to
                         initialize and store a new chain."
                         );
-                        // Create a new ChainInfo
+                        // Create a new ChainInfo. If blocks were indexed, the ChainInfo was lost
+                        // and its tip is recovered from the last indexed block
                         let genesis_hash = consensus_constants.genesis_hash;
+                        let highest_block_checkpoint = block_index
+                            .iter()
+                            .next_back()
+                            .map(|(&checkpoint, &hash_prev_block)| CheckpointBeacon {
+                                checkpoint,
+                                hash_prev_block,
+                            })
+                            .unwrap_or(CheckpointBeacon {
+                                checkpoint: 0,
+                                hash_prev_block: genesis_hash,
+                            });
                         let chain_info = ChainInfo {
                             environment,
                             consensus_constants,
-                            highest_block_checkpoint: CheckpointBeacon {
-                                checkpoint: 0,
-                                hash_prev_block: genesis_hash,
-                            },
+                            highest_block_checkpoint,
                         };
                         act.chain_info = Some(chain_info);
                     }
+
+                    // Replay or roll back the mutation of the chain state interrupted by a crash,
+                    // if any. The state derived from the affected blocks is rescanned
+                    let mut orphaned = vec![];
+                    let mut rescan_from = None;
+                    if let (Some(mutation), Some(chain_info)) = (chain_wal, act.chain_info.as_mut())
+                    {
+                        let tip = &mut chain_info.highest_block_checkpoint;
+                        match mutation.recover(tip, &mut block_index) {
+                            Recovery::Replayed => warn!(
+                                "Replayed interrupted chain state mutation up to checkpoint {}",
+                                tip.checkpoint
+                            ),
+                            Recovery::RolledBack => {
+                                warn!(
+                                    "Rolled back interrupted chain state mutation to checkpoint {}",
+                                    tip.checkpoint
+                                );
+                                orphaned.extend(
+                                    mutation
+                                        .indexed
+                                        .iter()
+                                        .filter(|(checkpoint, hash)| {
+                                            block_index.get(checkpoint) != Some(hash)
+                                        })
+                                        .map(|(_, hash)| *hash),
+                                );
+                            }
+                        }
+                        rescan_from = mutation.first_checkpoint();
+                    }
+
+                    // Check that the tip of the chain and the block index are consistent
+                    if let Some(chain_info) = act.chain_info.as_mut() {
+                        let genesis_hash = chain_info.consensus_constants.genesis_hash;
+                        let tip = &mut chain_info.highest_block_checkpoint;
+                        let previous_index = block_index.clone();
+                        if ensure_consistency(tip, &mut block_index, genesis_hash) {
+                            warn!(
+                                "Inconsistent local chain, rolled back to checkpoint {}",
+                                tip.checkpoint
+                            );
+                            orphaned.extend(orphaned_blocks(&previous_index, &block_index));
+                        }
+                    }
+                    act.block_index = block_index;
+                    act.notify_reverted_blocks(&orphaned);
+                    act.mark_stale_blocks(orphaned);
+
+                    // Check that the superblocks are consistent with the tip of the chain
+                    if let Some(chain_info) = act.chain_info.as_ref() {
+                        let tip = chain_info.highest_block_checkpoint.checkpoint;
+                        if discard_inconsistent_superblocks(&mut superblocks, tip) {
+                            warn!(
+                                "Inconsistent superblocks, rolled back to {} superblocks",
+                                superblocks.len()
+                            );
+                        }
+                    }
+                    act.superblocks = superblocks;
+                    act.epoch_outcomes = epoch_outcomes;
+
+                    // The outputs of the genesis allocation are part of the UTXO set of any chain
+                    act.notify_genesis_allocation();
+
+                    // Persist block index, chain_info and superblocks into storage
+                    act.persist_block_index(ctx);
+                    act.persist_chain_info(ctx);
+                    act.persist_superblocks(ctx);
+
+                    // Build the superblocks that are missing, if any
+                    act.produce_superblocks(ctx);
+
+                    // The recovered mutation is no longer needed once the chain state is persisted
+                    if let Some(from_epoch) = rescan_from {
+                        act.persist_chain_wal(ctx, None);
+                        if let Err(e) = act.start_rescan(ctx, from_epoch) {
+                            error!("Error rescanning the recovered chain state: {:?}", e);
+                        }
+                    }
+
                     actix::fut::ok(())
                 })
                 .wait(ctx);
-
-            // Persist chain_info into storage
-            act.persist_chain_info(ctx);
         });
+
+        // Periodically give back the block ranges that stalled, so they are requested to
+        // other peers
+        ctx.run_interval(
+            Duration::from_secs(BLOCK_DOWNLOAD_CHECK_PERIOD_SECONDS),
+            |act, _ctx| {
+                let stalled = act.download.reassign_stalled(Instant::now());
+                if stalled > 0 {
+                    warn!("{} block ranges stalled, requesting them again", stalled);
+                    act.request_block_download();
+                }
+            },
+        );
+    }
+}
+
+/// Treat corrupted values read from storage as missing values, so they can be recovered
+fn discard_corrupted<T>(res: StorageResult<Option<T>>, name: &str) -> StorageResult<Option<T>> {
+    match res {
+        Err(ref e) if e.inner().kind() == &StorageErrorKind::Corrupted => {
+            warn!("Discarding corrupted {} from storage: {}", name, e);
+            Ok(None)
+        }
+        res => res,
     }
 }
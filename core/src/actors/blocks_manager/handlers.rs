@@ -1,23 +1,38 @@
-use actix::{Context, Handler, System};
+use actix::{ActorFuture, Context, Handler, ResponseActFuture, System, WrapFuture};
+use futures::Future;
+use std::time::Instant;
 
 use crate::actors::blocks_manager::{BlocksManager, BlocksManagerError};
-use crate::actors::epoch_manager::messages::EpochNotification;
+use crate::actors::epoch_manager::messages::{EpochNotification, MissedEpochs};
 
 use witnet_data_structures::{
     chain::{Block, CheckpointBeacon, Hash, InvVector},
-    error::{ChainInfoError, ChainInfoErrorKind, ChainInfoResult},
+    error::ChainInfoResult,
 };
 
-use witnet_util::error::WitnetError;
+use log::{debug, warn};
 
-use log::{debug, error};
+use witnet_crypto::hash::calculate_sha256;
 
 use super::messages::{
-    AddNewBlock, DiscardExistingInvVectors, GetBlock, GetBlocksEpochRange,
-    GetHighestCheckpointBeacon, InvVectorsResult,
+    AddNewBlock, AssignBlockRanges, BlockChain, BlockRangesResult, DiscardExistingInvVectors,
+    GetBlock, GetBlockChain, GetBlockHashByEpoch, GetBlockTemplate, GetBlocksEpochRange,
+    GetChainStats, GetHighestCheckpointBeacon, GetNodeStatus, GetRescanStatus, GetSupplyInfo,
+    GetSyncState, InvVectorsResult, PeerLastBeacon, ReleaseBlockRanges, ReportPeerLastEpoch,
+    Rescan, SubmitBlock, SubscribeBlocks, SupplyInfo,
 };
-use crate::actors::session::messages::AnnounceItems;
-use crate::actors::sessions_manager::{messages::Broadcast, SessionsManager};
+use super::mining::{build_block_template, BlockTemplate};
+use super::rescan::RescanStatus;
+use super::stats::ChainStats;
+use super::{NodeStatus, SyncState};
+use crate::actors::session::messages::RequestBlocks;
+use crate::actors::sessions_manager::{
+    messages::{Anycast, SessionEventNotification},
+    SessionsManager,
+};
+use crate::actors::supervision::Restarted;
+use crate::metrics;
+use crate::tracing::Trace;
 
 ////////////////////////////////////////////////////////////////////////////////////////
 // ACTOR MESSAGE HANDLERS
@@ -43,8 +58,55 @@ impl Handler<EpochNotification<EpochPayload>> for BlocksManager {
 impl Handler<EpochNotification<EveryEpochPayload>> for BlocksManager {
     type Result = ();
 
-    fn handle(&mut self, msg: EpochNotification<EveryEpochPayload>, _ctx: &mut Context<Self>) {
+    fn handle(&mut self, msg: EpochNotification<EveryEpochPayload>, ctx: &mut Context<Self>) {
         debug!("Periodic epoch notification received {:?}", msg.checkpoint);
+
+        // Consolidate the block candidates that are no longer accepted
+        let consolidated = self.consolidate_candidates(msg.checkpoint);
+        if !consolidated.is_empty() {
+            debug!("Consolidated {} block candidates", consolidated.len());
+        }
+
+        // Persist the consolidated blocks and move the tip of the local chain forward
+        self.index_consolidated_blocks(ctx, &consolidated, Trace::default());
+
+        // Report the consolidated blocks to the fee estimator and the wallet
+        self.notify_consolidated_blocks(&consolidated, Trace::default());
+
+        // Check if the node has fallen behind and request blocks while it is syncing (unless the
+        // sync is paused for lack of outbound sessions)
+        if self.update_sync_state(msg.checkpoint) == SyncState::Syncing && !self.sync_paused() {
+            let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
+            sessions_manager_addr.do_send(Anycast {
+                command: RequestBlocks,
+            });
+        }
+
+        // Check whether the node has been isolated from the network (or reconnected)
+        self.update_isolation(msg.checkpoint);
+
+        // Record whether a block was consolidated for the epochs whose candidates have expired
+        self.record_epoch_outcomes(ctx, msg.checkpoint);
+    }
+}
+
+/// Handler for MissedEpochs message
+impl Handler<MissedEpochs> for BlocksManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: MissedEpochs, _ctx: &mut Context<Self>) {
+        // The node did not follow the network during the skipped epochs, so their outcomes are
+        // not taken into account as observed while synced
+        self.epoch_outcomes.epochs_missed(msg.first, msg.last);
+    }
+}
+
+/// Handler for SessionEventNotification message
+impl Handler<SessionEventNotification> for BlocksManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: SessionEventNotification, _ctx: &mut Context<Self>) {
+        self.on_session_event(&msg.event, msg.num_outbound_consolidated);
     }
 }
 
@@ -57,51 +119,240 @@ impl Handler<GetHighestCheckpointBeacon> for BlocksManager {
         _msg: GetHighestCheckpointBeacon,
         _ctx: &mut Context<Self>,
     ) -> Self::Result {
-        if let Some(chain_info) = &self.chain_info {
-            Ok(chain_info.highest_block_checkpoint)
-        } else {
-            error!("No ChainInfo loaded in BlocksManager");
-            Err(WitnetError::from(ChainInfoError::new(
-                ChainInfoErrorKind::ChainInfoNotFound,
-                "No ChainInfo loaded in BlocksManager".to_string(),
-            )))
-        }
+        self.get_highest_checkpoint_beacon()
+    }
+}
+
+/// Handler for PeerLastBeacon message
+impl Handler<PeerLastBeacon> for BlocksManager {
+    type Result = Result<CheckpointBeacon, BlocksManagerError>;
+
+    fn handle(&mut self, msg: PeerLastBeacon, _ctx: &mut Context<Self>) -> Self::Result {
+        debug!("Peer reported last beacon {:?}", msg.beacon);
+        // Peers whose chain contradicts the final blocks are not taken into account
+        self.check_peer_finality(&msg.beacon)?;
+        self.report_peer_last_epoch(msg.peer, msg.beacon.checkpoint);
+
+        Ok(self.get_highest_checkpoint_beacon()?)
     }
 }
 
 /// Handler for AddNewBlock message
 impl Handler<AddNewBlock> for BlocksManager {
-    type Result = Result<Hash, BlocksManagerError>;
+    type Result = ResponseActFuture<Self, Hash, BlocksManagerError>;
 
-    fn handle(
-        &mut self,
-        msg: AddNewBlock,
-        _ctx: &mut Context<Self>,
-    ) -> Result<Hash, BlocksManagerError> {
-        let res = self.process_new_block(msg.block);
-        match res {
-            Ok(hash) => {
-                // Get SessionsManager's address
-                let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
-
-                // Tell SessionsManager to announce the new block through every consolidated Session
-                let items = vec![InvVector::Block(hash)];
-                sessions_manager_addr.do_send(Broadcast {
-                    command: AnnounceItems { items },
-                });
-            }
-            Err(BlocksManagerError::BlockAlreadyExists) => {
-                debug!("Block already exists");
-            }
-            Err(BlocksManagerError::StorageError(_)) => {
-                debug!("Error when serializing block");
+    fn handle(&mut self, msg: AddNewBlock, ctx: &mut Context<Self>) -> Self::Result {
+        let trace = msg.trace;
+        let _span = trace.span("BlocksManager::AddNewBlock");
+        // The handler is timed on behalf of the peer which sent the block, including the parts
+        // run once its signatures and tallies have been verified
+        let origin = msg.origin;
+        let _timer = self
+            .watchdog
+            .start_for("BlocksManager", "AddNewBlock", origin.clone());
+
+        // Blocks requested by the block download process are applied in order, once all the
+        // blocks before them have been received
+        if !self.download.is_empty() {
+            let hash = calculate_sha256(&msg.block.hashed_bytes());
+            if self.download.is_wanted(&hash) {
+                debug!("{}Received downloaded block {:?}", trace, hash);
+                if !self.must_verify_downloaded_block(&msg.block) {
+                    self.receive_downloaded_block(ctx, hash, msg.block, trace);
+
+                    return Box::new(actix::fut::ok(hash));
+                }
+
+                // Blocks with invalid signatures are never received, so their range stalls and
+                // it is requested to another peer
+                let block = msg.block;
+                let fut = self.verify_block_signatures(&block).into_actor(self).then(
+                    move |res, act, ctx| {
+                        let _timer = act
+                            .watchdog
+                            .start_for("BlocksManager", "AddNewBlock", origin);
+                        match &res {
+                            Ok(()) => act.receive_downloaded_block(ctx, hash, block, trace),
+                            Err(e) => {
+                                debug!("{}Discarding downloaded block {:?}: {:?}", trace, hash, e)
+                            }
+                        }
+
+                        actix::fut::result(res.map(|()| hash))
+                    },
+                );
+
+                return Box::new(fut);
             }
-            Err(_) => {
-                debug!("Unexpected error");
+        }
+
+        // Blocks passing the header checks can be relayed before being fully validated, so
+        // they propagate faster through the network
+        let relay_first = self.relay_first;
+        let received_at = msg.received_at;
+        let hash = match self.check_block_header(&msg.block) {
+            Ok(hash) => hash,
+            Err(e) => {
+                log_rejected_block(&e, trace);
+                return Box::new(actix::fut::err(e));
             }
         };
+        if relay_first {
+            self.relay_block(hash, received_at, trace);
+        }
+
+        // The signatures of the block are verified in the threads of the signature verifier
+        // pool, and its tallies by the DataRequestsManager, so the actor keeps processing other
+        // messages in the meantime
+        let block = msg.block;
+        let fut = self
+            .verify_block_signatures(&block)
+            .join(self.verify_block_tallies(&block))
+            .map(|((), ())| ())
+            .into_actor(self)
+            .then(move |res, act, _ctx| {
+                let _timer = act
+                    .watchdog
+                    .start_for("BlocksManager", "AddNewBlock", origin);
+                let res = res.and_then(|()| {
+                    // The same block may have been added while its signatures were verified
+                    if act.blocks.contains_key(&hash) {
+                        Err(BlocksManagerError::BlockAlreadyExists)
+                    } else {
+                        act.validate_and_add_block(hash, block)
+                    }
+                });
+                match &res {
+                    Ok(hash) => {
+                        debug!("{}Added block candidate {:?}", trace, hash);
+
+                        if !relay_first {
+                            act.relay_block(*hash, received_at, trace);
+                        }
+                    }
+                    Err(e) => {
+                        if relay_first {
+                            metrics::record_invalid_relayed_block();
+                            warn!(
+                                "{}Block candidate {:?} was relayed before failing validation: {:?}",
+                                trace, hash, e
+                            );
+                        }
+                        log_rejected_block(e, trace);
+                    }
+                }
+
+                actix::fut::result(res)
+            });
+
+        Box::new(fut)
+    }
+}
+
+/// Log the reason why a new block was rejected
+fn log_rejected_block(e: &BlocksManagerError, trace: Trace) {
+    match e {
+        BlocksManagerError::BlockAlreadyExists => {
+            debug!("{}Block already exists", trace);
+        }
+        BlocksManagerError::BlockCandidateOutOfRange => {
+            debug!("{}Block candidate out of range", trace);
+        }
+        BlocksManagerError::BlockFromTheFuture => {
+            debug!("{}Block from a checkpoint which has not started yet", trace);
+        }
+        BlocksManagerError::BlockCandidatePoolFull => {
+            debug!("{}Block candidate pool full", trace);
+        }
+        BlocksManagerError::InvalidUtxoCommitment => {
+            debug!("{}Block with invalid UTXO set commitment", trace);
+        }
+        BlocksManagerError::InvalidMintTransaction => {
+            debug!("{}Block with invalid mint transaction", trace);
+        }
+        BlocksManagerError::FinalityViolation => {
+            warn!(
+                "{}Block rolling back final blocks of the local chain",
+                trace
+            );
+        }
+        BlocksManagerError::SignatureVerifierError(e) => {
+            debug!("{}Block with invalid signatures: {:?}", trace, e);
+        }
+        BlocksManagerError::InvalidTally(e) => {
+            debug!("{}Block with invalid tally: {:?}", trace, e);
+        }
+        BlocksManagerError::StorageError(_) => {
+            debug!("{}Error when serializing block", trace);
+        }
+        _ => {
+            debug!("{}Unexpected error", trace);
+        }
+    };
+}
+
+/// Handler for SubscribeBlocks message
+impl Handler<SubscribeBlocks> for BlocksManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: SubscribeBlocks, _ctx: &mut Context<Self>) {
+        debug!("New subscription to the consolidated blocks");
+        self.block_subscribers.push(msg.sender);
+    }
+}
+
+/// Handler for GetBlockTemplate message
+impl Handler<GetBlockTemplate> for BlocksManager {
+    type Result = Result<BlockTemplate, BlocksManagerError>;
+
+    fn handle(&mut self, msg: GetBlockTemplate, _ctx: &mut Context<Self>) -> Self::Result {
+        if !self.mining {
+            return Err(BlocksManagerError::NotAMiner);
+        }
+        // Blocks built on top of a stale tip would never be consolidated
+        if self.sync_state == SyncState::Syncing {
+            return Err(BlocksManagerError::NotSynced);
+        }
+        // Blocks built while isolated would never reach the rest of the network
+        if self.partition.isolated().is_some() {
+            return Err(BlocksManagerError::Isolated);
+        }
+        let epoch = self.current_epoch.ok_or(BlocksManagerError::NotSynced)?;
+        let tip = self.get_highest_checkpoint_beacon()?;
+        let chain_info = self
+            .chain_info
+            .as_ref()
+            .ok_or(BlocksManagerError::NotSynced)?;
+
+        Ok(build_block_template(
+            epoch,
+            tip,
+            msg.transactions,
+            msg.miner,
+            &chain_info.consensus_constants,
+        ))
+    }
+}
+
+/// Handler for SubmitBlock message
+impl Handler<SubmitBlock> for BlocksManager {
+    type Result = ResponseActFuture<Self, Hash, BlocksManagerError>;
+
+    fn handle(&mut self, msg: SubmitBlock, ctx: &mut Context<Self>) -> Self::Result {
+        if !self.mining {
+            return Box::new(actix::fut::err(BlocksManagerError::NotAMiner));
+        }
 
-        res
+        <Self as Handler<AddNewBlock>>::handle(
+            self,
+            AddNewBlock {
+                block: msg.block,
+                received_at: Instant::now(),
+                trace: msg.trace,
+                origin: None,
+            },
+            ctx,
+        )
     }
 }
 
@@ -148,7 +399,143 @@ impl Handler<DiscardExistingInvVectors> for BlocksManager {
         msg: DiscardExistingInvVectors,
         _ctx: &mut Context<Self>,
     ) -> InvVectorsResult {
+        let _span = msg.trace.span("BlocksManager::DiscardExistingInvVectors");
+
         // Discard existing inventory vectors
-        self.discard_existing_inv_vectors(msg.inv_vectors)
+        let missing = self.discard_existing_inv_vectors(msg.inv_vectors)?;
+        debug!(
+            "{}{} inventory vectors are missing",
+            msg.trace,
+            missing.len()
+        );
+
+        // While syncing, the missing blocks are downloaded from several peers in parallel
+        Ok(self.schedule_block_download(missing))
+    }
+}
+
+/// Handler for AssignBlockRanges message
+impl Handler<AssignBlockRanges> for BlocksManager {
+    type Result = BlockRangesResult;
+
+    fn handle(&mut self, msg: AssignBlockRanges, _ctx: &mut Context<Self>) -> Self::Result {
+        let ranges = self
+            .download
+            .assign(msg.peer, Instant::now())
+            .into_iter()
+            .map(|range| range.into_iter().map(InvVector::Block).collect())
+            .collect();
+
+        Ok(ranges)
+    }
+}
+
+/// Handler for ReleaseBlockRanges message
+impl Handler<ReleaseBlockRanges> for BlocksManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: ReleaseBlockRanges, _ctx: &mut Context<Self>) {
+        if self.download.remove_peer(msg.peer) > 0 {
+            debug!("Block ranges requested to peer {} given back", msg.peer);
+            self.request_block_download();
+        }
+    }
+}
+
+/// Handler for ReportPeerLastEpoch message
+impl Handler<ReportPeerLastEpoch> for BlocksManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: ReportPeerLastEpoch, _ctx: &mut Context<Self>) {
+        debug!("Peer reported last epoch {}", msg.last_epoch);
+        self.report_peer_last_epoch(msg.peer, msg.last_epoch);
+    }
+}
+
+/// Handler for GetBlockHashByEpoch message
+impl Handler<GetBlockHashByEpoch> for BlocksManager {
+    type Result = Result<Option<Hash>, BlocksManagerError>;
+
+    fn handle(&mut self, msg: GetBlockHashByEpoch, _ctx: &mut Context<Self>) -> Self::Result {
+        self.get_block_hash_by_epoch(msg.epoch)
+    }
+}
+
+/// Handler for GetSyncState message
+impl Handler<GetSyncState> for BlocksManager {
+    type Result = Result<SyncState, BlocksManagerError>;
+
+    fn handle(&mut self, _msg: GetSyncState, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(self.sync_state)
+    }
+}
+
+/// Handler for GetNodeStatus message
+impl Handler<GetNodeStatus> for BlocksManager {
+    type Result = Result<NodeStatus, BlocksManagerError>;
+
+    fn handle(&mut self, _msg: GetNodeStatus, _ctx: &mut Context<Self>) -> Self::Result {
+        self.get_node_status()
+    }
+}
+
+/// Handler for GetSupplyInfo message
+impl Handler<GetSupplyInfo> for BlocksManager {
+    type Result = ChainInfoResult<SupplyInfo>;
+
+    fn handle(&mut self, _msg: GetSupplyInfo, _ctx: &mut Context<Self>) -> Self::Result {
+        self.get_supply_info()
+    }
+}
+
+/// Handler for GetChainStats message
+impl Handler<GetChainStats> for BlocksManager {
+    type Result = Result<ChainStats, BlocksManagerError>;
+
+    fn handle(&mut self, msg: GetChainStats, _ctx: &mut Context<Self>) -> Self::Result {
+        self.get_chain_stats(msg.from, msg.to)
+    }
+}
+
+/// Handler for GetBlockChain message
+impl Handler<GetBlockChain> for BlocksManager {
+    type Result = Result<BlockChain, BlocksManagerError>;
+
+    fn handle(&mut self, msg: GetBlockChain, _ctx: &mut Context<Self>) -> Self::Result {
+        self.get_block_chain(msg.from, msg.to)
+    }
+}
+
+/// Handler for Rescan message
+impl Handler<Rescan> for BlocksManager {
+    type Result = Result<RescanStatus, BlocksManagerError>;
+
+    fn handle(&mut self, msg: Rescan, ctx: &mut Context<Self>) -> Self::Result {
+        self.start_rescan(ctx, msg.from_epoch)
+    }
+}
+
+/// Handler for GetRescanStatus message
+impl Handler<GetRescanStatus> for BlocksManager {
+    type Result = Option<RescanStatus>;
+
+    fn handle(&mut self, _msg: GetRescanStatus, _ctx: &mut Context<Self>) -> Self::Result {
+        self.get_rescan_status()
+    }
+}
+
+/// Handler for Restarted message: the storage has been opened again after a failure, so the
+/// state of the chain is persisted again in case the last writes were lost
+impl Handler<Restarted> for BlocksManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: Restarted, ctx: &mut Context<Self>) {
+        debug!("{} has been restarted", msg.actor);
+        if self.chain_info.is_some() {
+            self.persist_block_index(ctx);
+            self.persist_chain_info(ctx);
+            self.persist_superblocks(ctx);
+            self.persist_epoch_outcomes(ctx);
+        }
     }
 }
@@ -1,9 +1,20 @@
-use actix::Message;
+use actix::{Message, Recipient};
+use futures::sync::mpsc::UnboundedSender;
+use serde_derive::{Deserialize, Serialize};
+use std::net::SocketAddr;
 use std::ops::RangeInclusive;
+use std::time::Instant;
 
-use crate::actors::blocks_manager::BlocksManagerError;
+use crate::actors::blocks_manager::{
+    mining::BlockTemplate, rescan::RescanStatus, stats::ChainStats, BlocksManagerError, NodeStatus,
+    SyncState,
+};
+use crate::actors::mempool_manager::MempoolTransaction;
+use crate::actors::session::messages::ReportSlowMessage;
+use crate::tracing::Trace;
 use witnet_data_structures::{
-    chain::{Block, CheckpointBeacon, Epoch, Hash, InvVector},
+    chain::{Block, CheckpointBeacon, Epoch, Hash, InvVector, PublicKeyHash},
+    emission::EmissionPeriod,
     error::ChainInfoResult,
 };
 
@@ -15,16 +26,74 @@ impl Message for GetHighestCheckpointBeacon {
     type Result = ChainInfoResult<CheckpointBeacon>;
 }
 
+/// Message sent when a peer announces its last beacon, to obtain the highest block checkpoint
+/// managed by the `BlocksManager` actor
+/// Fails with `FinalityViolation` if the chain of the peer contradicts the final blocks of the
+/// local chain
+pub struct PeerLastBeacon {
+    /// Socket address which identifies the peer
+    pub peer: SocketAddr,
+    /// Highest block checkpoint of the peer
+    pub beacon: CheckpointBeacon,
+}
+
+impl Message for PeerLastBeacon {
+    type Result = Result<CheckpointBeacon, BlocksManagerError>;
+}
+
 /// Add a new block
 pub struct AddNewBlock {
     /// Block
     pub block: Block,
+    /// Time at which the block was received, to measure how long it takes to relay it
+    pub received_at: Instant,
+    /// Correlation ID of the protocol message carrying the block
+    pub trace: Trace,
+    /// Session of the peer which sent the block, whose handlers exceeding their time budget
+    /// count against it (None for the blocks not received from a peer)
+    pub origin: Option<Recipient<ReportSlowMessage>>,
 }
 
 impl Message for AddNewBlock {
     type Result = Result<Hash, BlocksManagerError>;
 }
 
+/// Ask for the template of a block for the current epoch, to be completed by an external miner
+pub struct GetBlockTemplate {
+    /// Public key hash of the miner, to which the mint transaction pays
+    pub miner: PublicKeyHash,
+    /// Transactions to be included in the block (see `GetTransactionsToMine`)
+    pub transactions: Vec<MempoolTransaction>,
+}
+
+impl Message for GetBlockTemplate {
+    type Result = Result<BlockTemplate, BlocksManagerError>;
+}
+
+/// Submit a block completed by an external miner from a template (see `GetBlockTemplate`), which
+/// is added like any other new block if the node has the miner role
+pub struct SubmitBlock {
+    /// Block
+    pub block: Block,
+    /// Correlation ID of the request
+    pub trace: Trace,
+}
+
+impl Message for SubmitBlock {
+    type Result = Result<Hash, BlocksManagerError>;
+}
+
+/// Subscribe to the consolidated blocks: every block added to the local chain is sent through
+/// the channel, until its receiver is dropped
+pub struct SubscribeBlocks {
+    /// Sending half of the channel
+    pub sender: UnboundedSender<Block>,
+}
+
+impl Message for SubscribeBlocks {
+    type Result = ();
+}
+
 /// Ask for a block identified by its hash
 pub struct GetBlock {
     /// Block hash
@@ -35,6 +104,17 @@ impl Message for GetBlock {
     type Result = Result<Block, BlocksManagerError>;
 }
 
+/// Ask for the hash of the block consolidated for an epoch (None if the epoch has been
+/// consolidated without any block)
+pub struct GetBlockHashByEpoch {
+    /// Epoch
+    pub epoch: Epoch,
+}
+
+impl Message for GetBlockHashByEpoch {
+    type Result = Result<Option<Hash>, BlocksManagerError>;
+}
+
 /// Message to obtain a vector of block hashes using a range of epochs
 pub struct GetBlocksEpochRange {
     /// Range of Epochs
@@ -49,6 +129,8 @@ impl Message for GetBlocksEpochRange {
 pub struct DiscardExistingInvVectors {
     /// Vector of InvVectors
     pub inv_vectors: Vec<InvVector>,
+    /// Correlation ID of the protocol message announcing the inventory vectors
+    pub trace: Trace,
 }
 
 /// Result of the DiscardExistingInvVectors message handling
@@ -57,3 +139,127 @@ pub type InvVectorsResult = Result<Vec<InvVector>, BlocksManagerError>;
 impl Message for DiscardExistingInvVectors {
     type Result = InvVectorsResult;
 }
+
+/// Report the last epoch announced by a peer
+pub struct ReportPeerLastEpoch {
+    /// Socket address which identifies the peer
+    pub peer: SocketAddr,
+    /// Last epoch of the peer
+    pub last_epoch: Epoch,
+}
+
+impl Message for ReportPeerLastEpoch {
+    type Result = ();
+}
+
+/// Ask for the supply issued by the consolidated blocks of the local chain
+pub struct GetSupplyInfo;
+
+/// Supply issued by the consolidated blocks of the local chain and the remaining emission
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SupplyInfo {
+    /// Checkpoint of the tip of the local chain
+    pub epoch: Epoch,
+    /// Total reward issued by the consolidated blocks
+    pub issued: u64,
+    /// Reward issued by the consolidated blocks which has not matured yet
+    pub locked: u64,
+    /// Reward still to be issued, assuming there is a block for every epoch after the tip
+    pub remaining: u64,
+    /// Total reward that will ever be issued, assuming there is a block for every epoch
+    pub total: u64,
+    /// Ranges of epochs with the same block reward after the tip
+    pub emission_curve: Vec<EmissionPeriod>,
+}
+
+impl Message for GetSupplyInfo {
+    type Result = ChainInfoResult<SupplyInfo>;
+}
+
+/// Ask for the statistics of the outcomes of a range of epochs
+pub struct GetChainStats {
+    /// First epoch of the range
+    pub from: Epoch,
+    /// Last epoch of the range (inclusive)
+    pub to: Epoch,
+}
+
+impl Message for GetChainStats {
+    type Result = Result<ChainStats, BlocksManagerError>;
+}
+
+/// Ask for the consolidated blocks of the local chain in a range of epochs
+pub struct GetBlockChain {
+    /// First epoch of the range
+    pub from: Epoch,
+    /// Last epoch of the range (inclusive)
+    pub to: Epoch,
+}
+
+/// Consolidated blocks of the local chain in a range of epochs
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockChain {
+    /// Checkpoint of the tip of the local chain the blocks were read from
+    pub epoch: Epoch,
+    /// Epoch and hash of every block in the range, sorted by epoch
+    pub blocks: Vec<(Epoch, Hash)>,
+}
+
+impl Message for GetBlockChain {
+    type Result = Result<BlockChain, BlocksManagerError>;
+}
+
+/// Rebuild the UTXO set and the wallet history from the blocks consolidated since an epoch,
+/// replaying them through the UtxoManager and the WalletManager
+pub struct Rescan {
+    /// First epoch to rescan
+    pub from_epoch: Epoch,
+}
+
+impl Message for Rescan {
+    type Result = Result<RescanStatus, BlocksManagerError>;
+}
+
+/// Ask for the progress of the last rescan, if any
+pub struct GetRescanStatus;
+
+impl Message for GetRescanStatus {
+    type Result = Option<RescanStatus>;
+}
+
+/// Ask for the synchronization state of the node
+pub struct GetSyncState;
+
+impl Message for GetSyncState {
+    type Result = Result<SyncState, BlocksManagerError>;
+}
+
+/// Ask for the status of the node: synchronization state and isolation from the network
+pub struct GetNodeStatus;
+
+impl Message for GetNodeStatus {
+    type Result = Result<NodeStatus, BlocksManagerError>;
+}
+
+/// Ask for block ranges to be requested to a peer while the node is syncing
+pub struct AssignBlockRanges {
+    /// Address of the peer
+    pub peer: SocketAddr,
+}
+
+/// Result of the AssignBlockRanges message handling: the inventory vectors of every range
+pub type BlockRangesResult = Result<Vec<Vec<InvVector>>, BlocksManagerError>;
+
+impl Message for AssignBlockRanges {
+    type Result = BlockRangesResult;
+}
+
+/// Give back the block ranges requested to a peer (e.g. because its session was closed)
+pub struct ReleaseBlockRanges {
+    /// Address of the peer
+    pub peer: SocketAddr,
+}
+
+impl Message for ReleaseBlockRanges {
+    type Result = ();
+}
@@ -0,0 +1,258 @@
+//! # Block candidates
+//!
+//! The blocks mined by this node include the transactions selected from
+//! the mempool (see `GetTransactionsToMine`) and exactly one mint
+//! transaction, which pays the block reward set by the emission schedule
+//! plus the fees of the selected transactions to the miner.
+//!
+//! Blocks can also be built by an external process: the node hands out a
+//! `BlockTemplate` (see the `getBlockTemplate` JSON-RPC method) and the
+//! external miner returns the completed block (see `submitBlock`), which
+//! is validated like any other block.
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_crypto::merkle::merkle_root;
+use witnet_data_structures::{
+    chain::{
+        Block, BlockHeader, BlockHeaderWithProof, CheckpointBeacon, ConsensusConstants, Epoch,
+        Hash, LeadershipProof, MintTransaction, PublicKeyHash, ValueTransferOutput,
+    },
+    emission,
+};
+
+use super::utxo_set_merkle_root;
+use crate::actors::mempool_manager::MempoolTransaction;
+
+/// Version of the blocks built by this node
+pub const BLOCK_VERSION: u32 = 1;
+
+/// Build the mint transaction of the block of an epoch, paying the block reward plus the fees
+/// of the transactions of the block to the miner, locked until the reward matures
+pub fn build_mint_transaction(
+    epoch: Epoch,
+    miner: PublicKeyHash,
+    fees: u64,
+    consensus_constants: &ConsensusConstants,
+) -> MintTransaction {
+    MintTransaction {
+        epoch,
+        output: ValueTransferOutput {
+            pkh: miner,
+            value: emission::block_reward(epoch, consensus_constants).saturating_add(fees),
+            time_lock: emission::reward_time_lock(epoch, consensus_constants),
+        },
+    }
+}
+
+/// Build a block candidate for an epoch on top of the tip of the local chain, including the
+/// selected transactions and the mint transaction paying the miner
+// TODO: sign the block and include the leadership proof once the eligibility of the miner can be
+// proved
+pub fn build_block_candidate(
+    epoch: Epoch,
+    tip: CheckpointBeacon,
+    transactions: Vec<MempoolTransaction>,
+    miner: PublicKeyHash,
+    consensus_constants: &ConsensusConstants,
+) -> Block {
+    let fees = transactions
+        .iter()
+        .fold(0u64, |fees, mempool_tx| fees.saturating_add(mempool_tx.fee));
+    let hashes: Vec<Hash> = transactions
+        .iter()
+        .map(|mempool_tx| mempool_tx.hash)
+        .collect();
+    let txns: Vec<_> = transactions
+        .into_iter()
+        .map(|mempool_tx| mempool_tx.transaction)
+        .collect();
+
+    Block {
+        header: BlockHeaderWithProof {
+            block_header: BlockHeader {
+                version: BLOCK_VERSION,
+                beacon: CheckpointBeacon {
+                    checkpoint: epoch,
+                    hash_prev_block: tip.hash_prev_block,
+                },
+                hash_merkle_root: merkle_root(&hashes),
+                utxo_merkle_root: utxo_set_merkle_root(),
+            },
+            proof: LeadershipProof {
+                block_sig: None,
+                influence: 0,
+            },
+        },
+        txn_count: txns.len() as u32,
+        txns,
+        mint: build_mint_transaction(epoch, miner, fees, consensus_constants),
+    }
+}
+
+/// Transaction selected for a block template
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TemplateTransaction {
+    /// Hash of the transaction
+    pub hash: Hash,
+    /// Fee paid by the transaction
+    pub fee: u64,
+}
+
+/// Template of a block to be completed by an external miner
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockTemplate {
+    /// Beacon of the block: the current epoch and the hash of the tip of the local chain
+    pub beacon: CheckpointBeacon,
+    /// Transactions selected from the mempool, in the order they are included in the block
+    pub transactions: Vec<TemplateTransaction>,
+    /// Block reward set by the emission schedule for the epoch
+    pub reward: u64,
+    /// Sum of the fees of the selected transactions
+    pub fees: u64,
+    /// Block candidate including the transactions and the mint transaction paying the reward
+    /// plus the fees to the miner, which only lacks the signature and the leadership proof
+    pub block: Block,
+}
+
+/// Build the template of a block for an epoch on top of the tip of the local chain
+pub fn build_block_template(
+    epoch: Epoch,
+    tip: CheckpointBeacon,
+    transactions: Vec<MempoolTransaction>,
+    miner: PublicKeyHash,
+    consensus_constants: &ConsensusConstants,
+) -> BlockTemplate {
+    let selected: Vec<TemplateTransaction> = transactions
+        .iter()
+        .map(|mempool_tx| TemplateTransaction {
+            hash: mempool_tx.hash,
+            fee: mempool_tx.fee,
+        })
+        .collect();
+    let block = build_block_candidate(epoch, tip, transactions, miner, consensus_constants);
+    let reward = emission::block_reward(epoch, consensus_constants);
+
+    BlockTemplate {
+        beacon: block.header.block_header.beacon.clone(),
+        fees: block.mint.output.value.saturating_sub(reward),
+        transactions: selected,
+        reward,
+        block,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::verify_mint_transaction;
+    use super::*;
+    use witnet_data_structures::chain::Transaction;
+
+    fn consensus_constants() -> ConsensusConstants {
+        ConsensusConstants {
+            checkpoint_zero_timestamp: 0,
+            checkpoints_period: 90,
+            genesis_hash: Hash::SHA256([0; 32]),
+            reputation_demurrage: 0.0,
+            reputation_punishment: 0.0,
+            trusted_checkpoints: vec![],
+            initial_block_reward: 100,
+            halving_period: 10,
+            reward_maturity: 5,
+            genesis_allocation: vec![],
+        }
+    }
+
+    #[test]
+    fn mint_pays_reward_plus_fees() {
+        let constants = consensus_constants();
+        let tip = CheckpointBeacon {
+            checkpoint: 11,
+            hash_prev_block: Hash::SHA256([1; 32]),
+        };
+        let transactions = vec![
+            MempoolTransaction::new(Transaction, 3, 0).unwrap(),
+            MempoolTransaction::new(Transaction, 4, 0).unwrap(),
+        ];
+
+        let block = build_block_candidate(12, tip, transactions, [2; 20], &constants);
+        assert_eq!(block.header.block_header.beacon.checkpoint, 12);
+        assert_eq!(
+            block.header.block_header.beacon.hash_prev_block,
+            tip.hash_prev_block
+        );
+        assert_eq!(block.txn_count, 2);
+        assert_eq!(
+            block.mint,
+            MintTransaction {
+                epoch: 12,
+                output: ValueTransferOutput {
+                    pkh: [2; 20],
+                    value: 50 + 7,
+                    time_lock: 12 + 5,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn block_template() {
+        let constants = consensus_constants();
+        let tip = CheckpointBeacon {
+            checkpoint: 11,
+            hash_prev_block: Hash::SHA256([1; 32]),
+        };
+        let transactions = vec![
+            MempoolTransaction::new(Transaction, 3, 0).unwrap(),
+            MempoolTransaction::new(Transaction, 4, 0).unwrap(),
+        ];
+        let hash = transactions[0].hash;
+
+        let template = build_block_template(12, tip, transactions, [2; 20], &constants);
+        assert_eq!(
+            template.beacon,
+            CheckpointBeacon {
+                checkpoint: 12,
+                hash_prev_block: Hash::SHA256([1; 32]),
+            }
+        );
+        assert_eq!(
+            template.transactions,
+            vec![
+                TemplateTransaction { hash, fee: 3 },
+                TemplateTransaction { hash, fee: 4 },
+            ]
+        );
+        assert_eq!(template.reward, 50);
+        assert_eq!(template.fees, 7);
+        assert_eq!(template.block.header.block_header.beacon, template.beacon);
+
+        // The block of the template is valid once completed
+        assert!(verify_mint_transaction(&template.block, &constants));
+    }
+
+    #[test]
+    fn mint_verification() {
+        let constants = consensus_constants();
+        let tip = CheckpointBeacon {
+            checkpoint: 0,
+            hash_prev_block: Hash::SHA256([0; 32]),
+        };
+        let block = build_block_candidate(1, tip, vec![], [2; 20], &constants);
+        assert!(verify_mint_transaction(&block, &constants));
+
+        // The mint transaction must pay the exact reward
+        let mut other_value = block.clone();
+        other_value.mint.output.value = 99;
+        assert!(!verify_mint_transaction(&other_value, &constants));
+
+        // The mint transaction must belong to the epoch of the block
+        let mut other_epoch = block.clone();
+        other_epoch.mint.epoch = 2;
+        assert!(!verify_mint_transaction(&other_epoch, &constants));
+
+        // The reward must be locked until it matures
+        let mut unlocked = block.clone();
+        unlocked.mint.output.time_lock = 0;
+        assert!(!verify_mint_transaction(&unlocked, &constants));
+    }
+}
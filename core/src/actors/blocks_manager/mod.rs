@@ -13,27 +13,83 @@
 //! * Having a method for letting other components get the epoch of the current tip of the
 //! blockchain (e.g. the last epoch field required for the handshake in the Witnet network
 //! protocol).
+//! * Detecting when the node is isolated from the network (see `partition`), pausing mining and
+//! witnessing until it is reconnected.
 use actix::{
-    ActorFuture, Context, ContextFutureSpawner, Supervised, System, SystemService, WrapFuture,
+    ActorFuture, Addr, Context, ContextFutureSpawner, MailboxError, Supervised, System,
+    SystemService, WrapFuture,
 };
+use futures::{future, sync::mpsc::UnboundedSender, Future};
+use secp256k1::Secp256k1;
+use serde_derive::{Deserialize, Serialize};
 
-use witnet_data_structures::chain::ChainInfo;
+use witnet_data_structures::{
+    chain::{ChainInfo, CheckpointBeacon},
+    error::{ChainInfoError, ChainInfoErrorKind, ChainInfoResult},
+};
 
 use crate::actors::{
-    blocks_manager::messages::InvVectorsResult,
-    storage_keys::CHAIN_KEY,
-    storage_manager::{messages::Put, StorageManager},
+    blocks_manager::messages::{BlockChain, InvVectorsResult, SupplyInfo},
+    data_requests_manager::{
+        messages::{ProcessTally, ValidateTally},
+        DataRequestsManager, DataRequestsManagerError,
+    },
+    mempool_manager::{
+        messages::{RecordBlockFees, TransactionsConfirmed},
+        MempoolManager,
+    },
+    node,
+    session::messages::{AnnounceItems, DownloadBlocks, RequestBlocks},
+    sessions_manager::{
+        messages::{Anycast, Broadcast, SessionEvent, SetIsolation},
+        SessionsManager,
+    },
+    signature_verifier::{verify_in_parallel, SignatureVerifier, SignatureVerifierError},
+    storage_keys::{BLOCK_INDEX_KEY, CHAIN_KEY, CHAIN_STATS_KEY, CHAIN_WAL_KEY, SUPERBLOCKS_KEY},
+    storage_manager::{
+        messages::{Get, MarkStale, Put},
+        put_with_retry, StorageManager,
+    },
+    utxo_manager::{
+        messages::{AddBlockOutputs, DiscardOutputsSince, ReleaseSnapshot, TakeSnapshot},
+        UtxoManager,
+    },
+    wallet_manager::{
+        messages::{BlockConsolidated, BlockReverted, RevertConfirmationsSince},
+        WalletManager,
+    },
 };
+use crate::errors::{NodeError, STORAGE_RETRY_POLICY};
+use crate::metrics;
+use crate::tracing::Trace;
+use crate::watchdog::Watchdog;
 
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use witnet_data_structures::chain::{Block, Epoch, Hash, InvVector};
-
-use witnet_storage::{error::StorageError, storage::Storable};
+use std::net::SocketAddr;
+use std::time::Instant;
+use witnet_data_structures::{
+    chain::{Block, ConsensusConstants, Epoch, Hash, InvVector, SuperBlock, TrustedCheckpoint},
+    emission,
+};
 
-use witnet_crypto::hash::calculate_sha256;
+use self::partition::{IsolationChange, IsolationReason, PartitionDetector};
+use self::rescan::{Rescan, RescanStatus};
+use self::stats::ChainStats;
+use self::wal::ChainMutation;
+use witnet_p2p::download::{BlockDownload, Delivery};
+use witnet_p2p::sessions::SessionType;
+use witnet_storage::error::StorageError;
+
+use witnet_crypto::{
+    hash::calculate_sha256,
+    merkle::merkle_root,
+    signature::{verify_all, SignatureJob},
+};
 use witnet_util::error::WitnetError;
+use witnet_util::timestamp::get_timestamp;
 
 mod actor;
 mod handlers;
@@ -41,6 +97,21 @@ mod handlers;
 /// Messages for BlocksManager
 pub mod messages;
 
+/// Construction of the blocks mined by this node
+pub mod mining;
+
+/// Detection of the isolation of the node from the network
+pub mod partition;
+
+/// Rescan of the consolidated blocks
+pub mod rescan;
+
+/// Statistics of the outcomes of the epochs
+pub mod stats;
+
+/// Write-ahead log of the chain state mutations
+pub mod wal;
+
 /// Possible errors when interacting with BlocksManager
 #[derive(Debug)]
 pub enum BlocksManagerError {
@@ -48,8 +119,47 @@ pub enum BlocksManagerError {
     BlockAlreadyExists,
     /// A block does not exist
     BlockDoesNotExist,
+    /// A block candidate does not belong to the current or previous checkpoint
+    BlockCandidateOutOfRange,
+    /// A block belongs to a checkpoint which has not started yet
+    BlockFromTheFuture,
+    /// The candidate pool for a checkpoint is full of candidates with a higher influence
+    BlockCandidatePoolFull,
+    /// The UTXO set commitment of a block does not match the UTXO set resulting from applying it
+    InvalidUtxoCommitment,
+    /// The mint transaction of a block does not belong to its epoch or does not pay the reward set
+    /// by the emission schedule plus the fees of its transactions
+    InvalidMintTransaction,
+    /// A tally of a block does not match the commits and reveals of its data request
+    InvalidTally(DataRequestsManagerError),
+    /// The blocks of an epoch have not been consolidated yet
+    EpochNotConsolidated,
+    /// A block or the chain of a peer would roll back blocks beyond the finality window
+    FinalityViolation,
+    /// The node is not synced with the network, so it cannot build blocks
+    NotSynced,
+    /// The node is isolated from the network, so it cannot build blocks
+    Isolated,
+    /// The node does not have the miner role, so it cannot build or submit blocks
+    NotAMiner,
+    /// A range of epochs is empty or longer than allowed
+    InvalidEpochRange,
+    /// A rescan of the consolidated blocks is already in progress
+    RescanInProgress,
+    /// ChainInfoError
+    ChainInfoError(WitnetError<ChainInfoError>),
     /// StorageError
     StorageError(WitnetError<StorageError>),
+    /// SignatureVerifierError
+    SignatureVerifierError(SignatureVerifierError),
+    /// MailboxError
+    MailboxError(MailboxError),
+}
+
+impl From<WitnetError<ChainInfoError>> for BlocksManagerError {
+    fn from(x: WitnetError<ChainInfoError>) -> Self {
+        BlocksManagerError::ChainInfoError(x)
+    }
 }
 
 impl From<WitnetError<StorageError>> for BlocksManagerError {
@@ -58,6 +168,463 @@ impl From<WitnetError<StorageError>> for BlocksManagerError {
     }
 }
 
+impl From<SignatureVerifierError> for BlocksManagerError {
+    fn from(x: SignatureVerifierError) -> Self {
+        BlocksManagerError::SignatureVerifierError(x)
+    }
+}
+
+impl From<MailboxError> for BlocksManagerError {
+    fn from(x: MailboxError) -> Self {
+        BlocksManagerError::MailboxError(x)
+    }
+}
+
+/// Maximum number of block candidates retained for a single checkpoint
+pub const MAX_CANDIDATES_PER_CHECKPOINT: usize = 16;
+
+/// Maximum number of seconds the clock of a miner can be ahead of the local clock: blocks for a
+/// checkpoint which starts later than that are rejected
+pub const MAX_BLOCK_FUTURE_SECONDS: i64 = 10;
+
+/// Maximum number of epochs returned by a single request of the blocks of the local chain
+pub const MAX_BLOCK_CHAIN_RANGE: Epoch = 1_000;
+
+/// Period (in seconds) of the check for block ranges that stalled while downloading blocks
+pub const BLOCK_DOWNLOAD_CHECK_PERIOD_SECONDS: u64 = 5;
+
+/// Number of epochs summarized by every superblock
+pub const SUPERBLOCK_PERIOD: Epoch = 10;
+
+/// Synchronization state of the node
+/// Mining and witnessing must be paused while the node is syncing
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncState {
+    /// The local chain is behind the network and blocks are being requested
+    Syncing,
+    /// The local chain is up to date
+    Synced,
+}
+
+impl Default for SyncState {
+    fn default() -> Self {
+        SyncState::Syncing
+    }
+}
+
+/// Change of the synchronization process triggered by a session lifecycle event
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SyncTrigger {
+    /// The first outbound session has been consolidated while the node is syncing: the blocks
+    /// after the local chain tip are requested
+    Start,
+    /// The last consolidated outbound session has been closed: no blocks are requested until an
+    /// outbound session is consolidated again
+    Pause,
+}
+
+/// Calculate how the synchronization process reacts to a session lifecycle event, given the
+/// number of consolidated outbound sessions before and after the event
+fn sync_trigger(
+    event: &SessionEvent,
+    previous_outbound: usize,
+    num_outbound: usize,
+    sync_state: SyncState,
+) -> Option<SyncTrigger> {
+    match event {
+        SessionEvent::Consolidated {
+            session_type: SessionType::Outbound,
+            ..
+        } if previous_outbound == 0 && sync_state == SyncState::Syncing => Some(SyncTrigger::Start),
+        _ if previous_outbound > 0 && num_outbound == 0 => Some(SyncTrigger::Pause),
+        _ => None,
+    }
+}
+
+/// Status of the node: its synchronization state and whether it is isolated from the network
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeStatus {
+    /// Synchronization state of the local chain
+    pub sync_state: SyncState,
+    /// Reason why the node is isolated from the network, or None if it is not isolated
+    pub isolated: Option<IsolationReason>,
+    /// Last epoch notified by the EpochManager
+    pub current_epoch: Option<Epoch>,
+    /// Beacon of the tip of the local chain
+    pub tip: CheckpointBeacon,
+    /// Highest last epoch reported by the peers
+    pub peers_last_epoch: Option<Epoch>,
+    /// Number of consolidated outbound sessions
+    pub num_outbound_consolidated: usize,
+}
+
+/// Calculate the synchronization state given the checkpoint of the local chain tip, the epoch
+/// implied by the wall-clock time, the highest last epoch reported by peers and the number of
+/// epochs the tip can lag behind (`node.max_epochs_behind`)
+/// Peers cannot report epochs beyond the current one
+fn calculate_sync_state(
+    tip: Epoch,
+    current_epoch: Epoch,
+    peers_last_epoch: Option<Epoch>,
+    max_epochs_behind: Epoch,
+) -> SyncState {
+    let behind_clock = current_epoch.saturating_sub(tip) > max_epochs_behind;
+    let behind_peers = peers_last_epoch
+        .map(|peers_last_epoch| {
+            std::cmp::min(peers_last_epoch, current_epoch).saturating_sub(tip) > max_epochs_behind
+        })
+        .unwrap_or(false);
+
+    if behind_clock || behind_peers {
+        SyncState::Syncing
+    } else {
+        SyncState::Synced
+    }
+}
+
+/// Fee rates of the transactions included in a block
+/// Returns None if the fees of the transactions cannot be calculated, so the block is not
+/// taken into account by the fee estimator
+// FIXME(#99): transactions do not define inputs and outputs yet, so their fees cannot be
+// calculated. Reporting blocks as if they did not include any transaction would make the fee
+// estimator report a fee rate of 0 instead of no estimation
+fn block_fee_rates(_block: &Block) -> Option<Vec<u64>> {
+    None
+}
+
+/// Tallies included in a block, which finish their data requests and update the reputation of
+/// their witnesses once the block is consolidated
+// FIXME(#99): transactions do not define their kind yet, so blocks are processed as if they did
+// not include any tally
+fn block_tallies(_block: &Block) -> Vec<ProcessTally> {
+    vec![]
+}
+
+/// Signatures of the transactions included in a block, verified before validating the rest of it
+// FIXME(#99): transactions are not signed yet, and blocks do not carry the public key of their
+// miner, so blocks are processed as if they did not include any signature
+fn block_signature_jobs(_block: &Block) -> Vec<SignatureJob> {
+    vec![]
+}
+
+/// Index of the consolidated blocks of the local chain, relating each checkpoint with the hash of
+/// its block
+pub type BlockIndex = BTreeMap<Epoch, Hash>;
+
+/// Check that the tip of the local chain is consistent with the block index, rolling both of them
+/// back to the last checkpoint in which they are consistent:
+///
+/// * Blocks indexed beyond the tip are discarded
+/// * If the block of the tip is not indexed, the tip is moved back to the last indexed block (or
+/// to the genesis block if the index is empty)
+///
+/// Returns true if anything had to be rolled back
+// TODO: cross-check the checkpoint of the UTXO set once the UtxoManager persists it
+fn ensure_consistency(
+    tip: &mut CheckpointBeacon,
+    block_index: &mut BlockIndex,
+    genesis_hash: Hash,
+) -> bool {
+    let genesis = CheckpointBeacon {
+        checkpoint: 0,
+        hash_prev_block: genesis_hash,
+    };
+
+    let discarded = match tip.checkpoint.checked_add(1) {
+        Some(next_checkpoint) => block_index.split_off(&next_checkpoint),
+        None => BlockIndex::new(),
+    };
+    let tip_indexed =
+        *tip == genesis || block_index.get(&tip.checkpoint) == Some(&tip.hash_prev_block);
+
+    if !tip_indexed {
+        *tip = block_index
+            .iter()
+            .next_back()
+            .map(|(&checkpoint, &hash_prev_block)| CheckpointBeacon {
+                checkpoint,
+                hash_prev_block,
+            })
+            .unwrap_or(genesis);
+    }
+
+    !discarded.is_empty() || !tip_indexed
+}
+
+/// Get the hashes of the blocks which were indexed in `previous_index` but are no longer indexed
+/// in `block_index` (e.g. because they have been rolled back), so they can be deleted from storage
+fn orphaned_blocks(previous_index: &BlockIndex, block_index: &BlockIndex) -> Vec<Hash> {
+    previous_index
+        .iter()
+        .filter(|(checkpoint, hash)| block_index.get(checkpoint) != Some(hash))
+        .map(|(_, hash)| *hash)
+        .collect()
+}
+
+/// Get the hash of the block consolidated for an epoch, given the last consolidated epoch
+/// Returns None if the epoch has been consolidated without any block
+fn consolidated_block_hash(
+    block_index: &BlockIndex,
+    last_consolidated_epoch: Epoch,
+    epoch: Epoch,
+) -> Result<Option<Hash>, BlocksManagerError> {
+    if epoch > last_consolidated_epoch {
+        Err(BlocksManagerError::EpochNotConsolidated)
+    } else {
+        Ok(block_index.get(&epoch).cloned())
+    }
+}
+
+/// Get the consolidated blocks of the local chain in the range of epochs `[from, to]`, given the
+/// checkpoint of its tip, sorted by epoch
+fn block_chain_range(
+    block_index: &BlockIndex,
+    tip: Epoch,
+    from: Epoch,
+    to: Epoch,
+) -> Vec<(Epoch, Hash)> {
+    if from > tip {
+        return vec![];
+    }
+
+    block_index
+        .range(from..=std::cmp::min(to, tip))
+        .map(|(&checkpoint, &hash)| (checkpoint, hash))
+        .collect()
+}
+
+/// Get the last final checkpoint given the checkpoint of the tip of the local chain: the blocks
+/// up to it are older than the finality window and cannot be rolled back
+fn final_checkpoint(tip: Epoch, max_reorg_depth: Epoch) -> Epoch {
+    tip.saturating_sub(max_reorg_depth)
+}
+
+/// Check whether the last beacon of a peer contradicts the final blocks of the local chain, that
+/// is, it points to a final checkpoint with a different block than the local one (or with a block
+/// where the local chain has none)
+fn beacon_violates_finality(
+    block_index: &BlockIndex,
+    genesis_hash: Hash,
+    final_checkpoint: Epoch,
+    beacon: &CheckpointBeacon,
+) -> bool {
+    if beacon.checkpoint > final_checkpoint {
+        false
+    } else if beacon.checkpoint == 0 {
+        beacon.hash_prev_block != genesis_hash
+    } else {
+        block_index.get(&beacon.checkpoint) != Some(&beacon.hash_prev_block)
+    }
+}
+
+/// Check whether a block would roll back final blocks of the local chain, that is, it extends a
+/// block of the local chain which is followed by a final block
+fn block_violates_finality(
+    block_index: &BlockIndex,
+    genesis_hash: Hash,
+    final_checkpoint: Epoch,
+    hash_prev_block: Hash,
+) -> bool {
+    let parent = if hash_prev_block == genesis_hash {
+        Some(0)
+    } else {
+        block_index
+            .iter()
+            .rev()
+            .find(|(_, hash)| **hash == hash_prev_block)
+            .map(|(&checkpoint, _)| checkpoint)
+    };
+
+    match parent {
+        Some(parent) if parent < final_checkpoint => block_index
+            .range(parent + 1..=final_checkpoint)
+            .next()
+            .is_some(),
+        _ => false,
+    }
+}
+
+/// Check whether a block contradicts the trusted checkpoints, given the checkpoint of the tip it
+/// extends: either it is a block for a trusted checkpoint with a different hash, or it skips a
+/// trusted checkpoint
+fn contradicts_trusted_checkpoints(
+    trusted_checkpoints: &[TrustedCheckpoint],
+    tip: Epoch,
+    checkpoint: Epoch,
+    hash: Hash,
+) -> bool {
+    trusted_checkpoints.iter().any(|trusted| {
+        (trusted.checkpoint == checkpoint && trusted.hash != hash)
+            || (tip < trusted.checkpoint && trusted.checkpoint < checkpoint)
+    })
+}
+
+/// Check whether a block is vouched for by the trusted checkpoints, that is, it is not above the
+/// last trusted checkpoint. As blocks are chained by their hashes, these blocks only need to be
+/// validated against the checkpoint chain
+fn below_last_trusted_checkpoint(
+    trusted_checkpoints: &[TrustedCheckpoint],
+    checkpoint: Epoch,
+) -> bool {
+    trusted_checkpoints
+        .last()
+        .map(|last| checkpoint <= last.checkpoint)
+        .unwrap_or(false)
+}
+
+/// Range of epochs `[start, end)` summarized by a superblock
+/// Returns None if the range does not fit in an epoch
+fn superblock_epochs(index: u32) -> Option<(Epoch, Epoch)> {
+    let start = index.checked_mul(SUPERBLOCK_PERIOD)?;
+    let end = start.checked_add(SUPERBLOCK_PERIOD)?;
+
+    Some((start, end))
+}
+
+/// Merkle root of the UTXO set
+// FIXME(#99): transactions do not define outputs yet, so the UTXO set is always empty
+fn utxo_set_merkle_root() -> Hash {
+    merkle_root(&[])
+}
+
+/// Build a superblock summarizing the consolidated blocks of its range of epochs, chained to
+/// the previous superblock
+fn build_superblock(
+    index: u32,
+    block_index: &BlockIndex,
+    prev_superblock: Option<&SuperBlock>,
+    utxo_merkle_root: Hash,
+) -> SuperBlock {
+    let hashes: Vec<Hash> = match superblock_epochs(index) {
+        Some((start, end)) => block_index
+            .range(start..end)
+            .map(|(_, hash)| *hash)
+            .collect(),
+        None => vec![],
+    };
+    let hash_prev_superblock = match prev_superblock {
+        Some(prev_superblock) => calculate_sha256(&prev_superblock.hashed_bytes()),
+        None => Hash::SHA256([0; 32]),
+    };
+
+    SuperBlock {
+        index,
+        hash_prev_superblock,
+        num_blocks: hashes.len() as u32,
+        blocks_merkle_root: merkle_root(&hashes),
+        utxo_merkle_root,
+    }
+}
+
+/// Check if the range of epochs of a superblock is complete given the checkpoint of the tip of
+/// the local chain, that is, no more blocks can be consolidated for it
+fn is_superblock_complete(index: u32, tip: Epoch) -> bool {
+    superblock_epochs(index)
+        .map(|(_, end)| end <= tip.saturating_add(1))
+        .unwrap_or(false)
+}
+
+/// Discard the superblocks read from storage that are not consistent with the tip of the local
+/// chain (e.g. because it was rolled back), so they are built again
+/// Returns true if any superblock was discarded
+fn discard_inconsistent_superblocks(superblocks: &mut Vec<SuperBlock>, tip: Epoch) -> bool {
+    let consistent = superblocks
+        .iter()
+        .enumerate()
+        .take_while(|(i, superblock)| {
+            superblock.index == *i as u32 && is_superblock_complete(superblock.index, tip)
+        })
+        .count();
+    let discarded = consistent < superblocks.len();
+    superblocks.truncate(consistent);
+
+    discarded
+}
+
+/// Verify that a block commits to the UTXO set resulting from applying it to the local chain
+// FIXME(#99): the UTXO set is always empty until transactions define outputs, so the resulting
+// UTXO set does not depend on the block yet
+fn verify_utxo_commitment(block: &Block) -> bool {
+    block.header.block_header.utxo_merkle_root == utxo_set_merkle_root()
+}
+
+/// Check whether a checkpoint starts more than `MAX_BLOCK_FUTURE_SECONDS` after a timestamp, so
+/// its blocks cannot have been mined yet
+fn is_from_the_future(
+    checkpoint: Epoch,
+    now: i64,
+    consensus_constants: &ConsensusConstants,
+) -> bool {
+    let period = i64::from(std::cmp::max(consensus_constants.checkpoints_period, 1));
+    let checkpoint_timestamp = i64::from(checkpoint)
+        .saturating_mul(period)
+        .saturating_add(consensus_constants.checkpoint_zero_timestamp);
+
+    checkpoint_timestamp > now.saturating_add(MAX_BLOCK_FUTURE_SECONDS)
+}
+
+/// Fees paid by the transactions included in a block
+// FIXME(#99): transactions do not define inputs and outputs yet, so their fees cannot be
+// calculated and blocks are validated as if their transactions did not pay any fee
+fn block_fees(_block: &Block) -> u64 {
+    0
+}
+
+/// Verify the mint transaction of a block: it must belong to the epoch of the block, pay exactly
+/// the reward set by the emission schedule plus the fees of the transactions of the block, and be
+/// locked until the reward matures
+fn verify_mint_transaction(block: &Block, consensus_constants: &ConsensusConstants) -> bool {
+    let epoch = block.header.block_header.beacon.checkpoint;
+    let reward = emission::block_reward(epoch, consensus_constants);
+
+    block.mint.epoch == epoch
+        && reward.checked_add(block_fees(block)) == Some(block.mint.output.value)
+        && block.mint.output.time_lock == emission::reward_time_lock(epoch, consensus_constants)
+}
+
+/// Calculate the supply issued by the consolidated blocks of the local chain, given the
+/// checkpoint of its tip
+/// The rewards of the blocks which have not matured yet are locked
+fn calculate_supply_info(
+    block_index: &BlockIndex,
+    tip: Epoch,
+    consensus_constants: &ConsensusConstants,
+) -> SupplyInfo {
+    let (issued, locked) = block_index
+        .keys()
+        .take_while(|&&checkpoint| checkpoint <= tip)
+        .fold((0u64, 0u64), |(issued, locked), &checkpoint| {
+            let reward = emission::block_reward(checkpoint, consensus_constants);
+            let locked = if emission::reward_time_lock(checkpoint, consensus_constants) > tip {
+                locked.saturating_add(reward)
+            } else {
+                locked
+            };
+
+            (issued.saturating_add(reward), locked)
+        });
+    let total = emission::total_emission(consensus_constants);
+
+    SupplyInfo {
+        epoch: tip,
+        issued,
+        locked,
+        remaining: total.saturating_sub(emission::emission_until(tip, consensus_constants)),
+        total,
+        emission_curve: emission::emission_curve(tip.saturating_add(1), consensus_constants),
+    }
+}
+
+/// Hashes of the transactions included in a block
+fn block_transaction_hashes(block: &Block) -> Vec<Hash> {
+    block
+        .txns
+        .iter()
+        .map(|transaction| calculate_sha256(&transaction.hashed_bytes()))
+        .collect()
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////
 // ACTOR BASIC STRUCTURE
 ////////////////////////////////////////////////////////////////////////////////////////
@@ -71,6 +638,45 @@ pub struct BlocksManager {
     epoch_to_block_hash: HashMap<Epoch, HashSet<Hash>>,
     /// Map that stores blocks by their hash
     blocks: HashMap<Hash, Block>,
+    /// Index of the consolidated blocks
+    block_index: BlockIndex,
+    /// Block candidates (influence and hash) for the current and previous checkpoints, sorted
+    /// by influence in descending order
+    candidates: BTreeMap<Epoch, Vec<(u64, Hash)>>,
+    /// Last epoch notified by the EpochManager
+    current_epoch: Option<Epoch>,
+    /// Last epoch reported by every connected peer (the entries of a peer are removed when its
+    /// session is closed)
+    peers_last_epoch: BTreeMap<SocketAddr, Epoch>,
+    /// Maximum number of epochs the local chain tip can lag behind before going back to syncing
+    max_epochs_behind: Epoch,
+    /// Synchronization state
+    sync_state: SyncState,
+    /// Blocks being downloaded from peers while syncing
+    download: BlockDownload<Hash, Block>,
+    /// Superblocks summarizing the consolidated blocks, sorted by index
+    superblocks: Vec<SuperBlock>,
+    /// Maximum number of epochs the local chain can be rolled back (finality window)
+    max_reorg_depth: Epoch,
+    /// Channels through which the consolidated blocks are sent (see `SubscribeBlocks`)
+    block_subscribers: Vec<UnboundedSender<Block>>,
+    /// Outcomes of the most recent epochs
+    epoch_outcomes: stats::EpochOutcomes,
+    /// Last rescan of the consolidated blocks, if any
+    rescan: Option<Rescan>,
+    /// Whether block candidates are announced to the peers as soon as their header has been
+    /// checked, before being fully validated
+    relay_first: bool,
+    /// Whether the node has the miner role, so it builds blocks for external miners
+    mining: bool,
+    /// Pool verifying the signatures of the blocks in other threads (see `SignatureVerifier`)
+    signature_verifier: Option<Addr<SignatureVerifier>>,
+    /// Number of workers of the signature verifier pool
+    signature_workers: usize,
+    /// Detector of the isolation of the node from the network
+    partition: PartitionDetector,
+    /// Time budgets of the handlers
+    watchdog: Watchdog,
 }
 
 /// Required trait for being able to retrieve BlocksManager address from registry
@@ -83,9 +689,6 @@ impl SystemService for BlocksManager {}
 impl BlocksManager {
     /// Method to persist chain_info into storage
     fn persist_chain_info(&self, ctx: &mut Context<Self>) {
-        // Get StorageManager address
-        let storage_manager_addr = System::current().registry().get::<StorageManager>();
-
         let chain_info = match self.chain_info.as_ref() {
             Some(x) => x,
             None => {
@@ -97,18 +700,185 @@ impl BlocksManager {
         // Persist chain_info into storage. `AsyncContext::wait` registers
         // future within context, but context waits until this future resolves
         // before processing any other events.
-        let msg = Put::from_value(CHAIN_KEY, chain_info).unwrap();
-        storage_manager_addr
-            .send(msg)
+        let msg = match Put::from_value(CHAIN_KEY, chain_info) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error encoding chain_info: {}", e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+        self.persist(ctx, msg, "chain_info");
+    }
+
+    /// Method to persist the block index into storage
+    fn persist_block_index(&self, ctx: &mut Context<Self>) {
+        let msg = match Put::from_value(BLOCK_INDEX_KEY, &self.block_index) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error encoding block index: {}", e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+
+        self.persist(ctx, msg, "block index");
+    }
+
+    /// Method to persist the superblocks into storage
+    fn persist_superblocks(&self, ctx: &mut Context<Self>) {
+        let msg = match Put::from_value(SUPERBLOCKS_KEY, &self.superblocks) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error encoding superblocks: {}", e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+
+        self.persist(ctx, msg, "superblocks");
+    }
+
+    /// Method to persist the outcomes of the most recent epochs into storage
+    fn persist_epoch_outcomes(&self, ctx: &mut Context<Self>) {
+        let msg = match Put::from_value(CHAIN_STATS_KEY, &self.epoch_outcomes) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error encoding chain statistics: {}", e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+
+        self.persist(ctx, msg, "chain statistics");
+    }
+
+    /// Method to persist the write-ahead log of the chain state into storage: the mutation about
+    /// to be applied, or None once it has been persisted
+    fn persist_chain_wal(&self, ctx: &mut Context<Self>, mutation: Option<&ChainMutation>) {
+        let msg = match Put::from_value(CHAIN_WAL_KEY, &mutation) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error encoding chain write-ahead log: {}", e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+
+        self.persist(ctx, msg, "chain write-ahead log");
+    }
+
+    /// Method to record the outcomes of the epochs whose candidates are no longer accepted,
+    /// given the current epoch, persisting them into storage
+    fn record_epoch_outcomes(&mut self, ctx: &mut Context<Self>, current_epoch: Epoch) {
+        // The candidates for the current and previous epochs are still accepted
+        if current_epoch < 2 {
+            return;
+        }
+
+        let block_index = &self.block_index;
+        let recorded = self.epoch_outcomes.record(
+            current_epoch - 2,
+            self.sync_state == SyncState::Synced,
+            |epoch| block_index.contains_key(&epoch),
+        );
+        if recorded > 0 {
+            self.persist_epoch_outcomes(ctx);
+        }
+    }
+
+    /// Method to get the statistics of the outcomes of the epochs in the range `[from, to]`
+    fn get_chain_stats(&self, from: Epoch, to: Epoch) -> Result<ChainStats, BlocksManagerError> {
+        if from > to || to - from >= stats::MAX_CHAIN_STATS_RANGE {
+            return Err(BlocksManagerError::InvalidEpochRange);
+        }
+
+        Ok(ChainStats::from_outcomes(
+            self.epoch_outcomes.range(from, to),
+        ))
+    }
+
+    /// Method to get the consolidated blocks of the local chain in the range `[from, to]`, along
+    /// with the checkpoint of the tip they were read from
+    fn get_block_chain(&self, from: Epoch, to: Epoch) -> Result<BlockChain, BlocksManagerError> {
+        if from > to || to - from >= MAX_BLOCK_CHAIN_RANGE {
+            return Err(BlocksManagerError::InvalidEpochRange);
+        }
+        let tip = self.get_highest_checkpoint_beacon()?.checkpoint;
+
+        Ok(BlockChain {
+            epoch: tip,
+            blocks: block_chain_range(&self.block_index, tip, from, to),
+        })
+    }
+
+    /// Method to build the superblocks whose range of epochs has been completed by the tip of
+    /// the local chain, persisting them into storage
+    fn produce_superblocks(&mut self, ctx: &mut Context<Self>) {
+        let tip = match &self.chain_info {
+            Some(chain_info) => chain_info.highest_block_checkpoint.checkpoint,
+            None => return,
+        };
+
+        let mut produced = false;
+        while is_superblock_complete(self.superblocks.len() as u32, tip) {
+            let superblock = build_superblock(
+                self.superblocks.len() as u32,
+                &self.block_index,
+                self.superblocks.last(),
+                utxo_set_merkle_root(),
+            );
+            info!(
+                "Superblock {} summarizes {} blocks",
+                superblock.index, superblock.num_blocks
+            );
+            self.superblocks.push(superblock);
+            produced = true;
+        }
+
+        if produced {
+            self.persist_superblocks(ctx);
+        }
+    }
+
+    /// Method to persist a block into storage, using its hash as key
+    fn persist_block(&self, ctx: &mut Context<Self>, hash: Hash, trace: Trace) {
+        let block = match self.blocks.get(&hash) {
+            Some(block) => block,
+            None => {
+                error!("Trying to persist an unknown block {:?}", hash);
+                return;
+            }
+        };
+        let Hash::SHA256(key) = hash;
+        let msg = match Put::from_value(key.to_vec(), block) {
+            Ok(msg) => msg.with_trace(trace),
+            Err(e) => {
+                error!("{}Error encoding block {:?}: {}", trace, hash, e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+
+        self.persist(ctx, msg, "block");
+    }
+
+    /// Send a `Put` message to the storage manager, waiting until it is processed so values are
+    /// persisted in order
+    /// Transient storage failures are retried, and the node is stopped if the value cannot be
+    /// persisted
+    fn persist(&self, ctx: &mut Context<Self>, msg: Put, name: &'static str) {
+        put_with_retry(msg, STORAGE_RETRY_POLICY)
             .into_actor(self)
-            .then(|res, _act, _ctx| {
+            .then(move |res, _act, _ctx| {
                 match res {
-                    Ok(Ok(_)) => {
-                        info!("BlocksManager successfully persisted chain_info into storage")
-                    }
-                    _ => {
-                        error!("BlocksManager failed to persist chain_info into storage");
-                        // FIXME(#72): handle errors
+                    Ok(()) => debug!("BlocksManager successfully persisted {}", name),
+                    Err(e) => {
+                        error!(
+                            "BlocksManager failed to persist {} into storage: {}",
+                            name, e
+                        );
+                        node::escalate(&e);
                     }
                 }
                 actix::fut::ok(())
@@ -116,38 +886,1016 @@ impl BlocksManager {
             .wait(ctx);
     }
 
-    fn process_new_block(&mut self, block: Block) -> Result<Hash, BlocksManagerError> {
-        // Calculate the hash of the block
-        let hash = calculate_sha256(&block.to_bytes()?);
+    /// Method to add the consolidated blocks to the block index and move the tip of the local
+    /// chain forward. Blocks are persisted before the index, and the index before the chain
+    /// info, so the tip never points to a block missing from storage
+    fn index_consolidated_blocks(
+        &mut self,
+        ctx: &mut Context<Self>,
+        consolidated: &[Hash],
+        trace: Trace,
+    ) {
+        // The mutation is logged before persisting anything, so it can be recovered if the node
+        // crashes before the chain info is persisted
+        let mutation = self.consolidation_mutation(consolidated);
+        if let Some(mutation) = mutation.as_ref() {
+            self.persist_chain_wal(ctx, Some(mutation));
+        }
+
+        let mut orphaned = vec![];
+        for &hash in consolidated {
+            let checkpoint = match self.blocks.get(&hash) {
+                Some(block) => block.header.block_header.beacon.checkpoint,
+                None => continue,
+            };
+
+            self.persist_block(ctx, hash, trace);
+            if let Some(previous) = self.block_index.insert(checkpoint, hash) {
+                if previous != hash {
+                    orphaned.push(previous);
+                }
+            }
+
+            if let Some(chain_info) = self.chain_info.as_mut() {
+                if checkpoint >= chain_info.highest_block_checkpoint.checkpoint {
+                    chain_info.highest_block_checkpoint = CheckpointBeacon {
+                        checkpoint,
+                        hash_prev_block: hash,
+                    };
+                }
+            }
+        }
+
+        if !consolidated.is_empty() {
+            self.persist_block_index(ctx);
+            self.persist_chain_info(ctx);
+            self.produce_superblocks(ctx);
+        }
+        if mutation.is_some() {
+            self.persist_chain_wal(ctx, None);
+        }
+        self.notify_reverted_blocks(&orphaned);
+        self.mark_stale_blocks(orphaned);
+    }
+
+    /// Method to let the wallet know that some consolidated blocks have been replaced by a
+    /// reorganization of the local chain, so the transactions they included are reorged out
+    fn notify_reverted_blocks(&self, hashes: &[Hash]) {
+        if hashes.is_empty() {
+            return;
+        }
+        info!("{} consolidated blocks reverted", hashes.len());
+
+        let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+        for &block_hash in hashes {
+            wallet_manager_addr.do_send(BlockReverted { block_hash });
+        }
+    }
+
+    /// Method to describe the mutation of the chain state caused by indexing some consolidated
+    /// blocks, before it is applied
+    /// Returns None if no block would be indexed or the chain info has not been initialized
+    fn consolidation_mutation(&self, consolidated: &[Hash]) -> Option<ChainMutation> {
+        let previous_tip = self.chain_info.as_ref()?.highest_block_checkpoint;
+        let mut tip = previous_tip;
+        let mut indexed = vec![];
+        let mut replaced = vec![];
+        for &hash in consolidated {
+            let checkpoint = match self.blocks.get(&hash) {
+                Some(block) => block.header.block_header.beacon.checkpoint,
+                None => continue,
+            };
+
+            match self.block_index.get(&checkpoint) {
+                Some(&previous) if previous != hash => replaced.push((checkpoint, previous)),
+                _ => {}
+            }
+            indexed.push((checkpoint, hash));
+            if checkpoint >= tip.checkpoint {
+                tip = CheckpointBeacon {
+                    checkpoint,
+                    hash_prev_block: hash,
+                };
+            }
+        }
+
+        if indexed.is_empty() {
+            None
+        } else {
+            Some(ChainMutation {
+                previous_tip,
+                tip,
+                indexed,
+                replaced,
+            })
+        }
+    }
+
+    /// Method to let the storage manager know that some persisted blocks are no longer indexed,
+    /// so they are deleted once they have been stale for long enough
+    fn mark_stale_blocks(&self, hashes: Vec<Hash>) {
+        if hashes.is_empty() {
+            return;
+        }
+        let epoch = self
+            .current_epoch
+            .or_else(|| {
+                self.chain_info
+                    .as_ref()
+                    .map(|chain_info| chain_info.highest_block_checkpoint.checkpoint)
+            })
+            .unwrap_or(0);
+        debug!("{} persisted blocks are no longer indexed", hashes.len());
+
+        let keys = hashes
+            .into_iter()
+            .map(|hash| {
+                let Hash::SHA256(key) = hash;
+                key.to_vec()
+            })
+            .collect();
+        System::current()
+            .registry()
+            .get::<StorageManager>()
+            .do_send(MarkStale { epoch, keys });
+    }
+
+    /// Method to enqueue the missing blocks announced by a peer into the block download
+    /// process while the node is syncing, so they are requested to several peers in parallel
+    /// Returns the inventory vectors that still have to be requested to the announcing peer
+    fn schedule_block_download(&mut self, missing: Vec<InvVector>) -> Vec<InvVector> {
+        if self.sync_state != SyncState::Syncing {
+            return missing;
+        }
+
+        let (blocks, rest): (Vec<InvVector>, Vec<InvVector>) =
+            missing
+                .into_iter()
+                .partition(|inv_vector| match inv_vector {
+                    InvVector::Block(_) => true,
+                    _ => false,
+                });
+        let hashes = blocks
+            .into_iter()
+            .filter_map(|inv_vector| match inv_vector {
+                InvVector::Block(hash) => Some(hash),
+                _ => None,
+            });
+
+        let added = self.download.enqueue(hashes);
+        if added > 0 {
+            debug!("{} blocks added to the block download", added);
+            self.request_block_download();
+        }
+
+        rest
+    }
+
+    /// Method to ask the outbound sessions to request the pending block ranges to their peers
+    /// Nothing is requested while the sync is paused (there are no consolidated outbound sessions)
+    fn request_block_download(&self) {
+        if self.sync_paused() {
+            return;
+        }
+
+        let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
+        sessions_manager_addr.do_send(Broadcast {
+            command: DownloadBlocks,
+        });
+    }
+
+    /// Method to check whether the signatures of a downloaded block must be verified: the blocks
+    /// up to the last trusted checkpoint are only validated against the checkpoint chain
+    fn must_verify_downloaded_block(&self, block: &Block) -> bool {
+        self.chain_info.as_ref().map_or(true, |chain_info| {
+            !below_last_trusted_checkpoint(
+                &chain_info.consensus_constants.trusted_checkpoints,
+                block.header.block_header.beacon.checkpoint,
+            )
+        })
+    }
+
+    /// Method to hand a downloaded block to the block download process, applying the downloaded
+    /// blocks which are ready and requesting more ranges if the block completed one
+    fn receive_downloaded_block(
+        &mut self,
+        ctx: &mut Context<Self>,
+        hash: Hash,
+        block: Block,
+        trace: Trace,
+    ) {
+        let delivery = self.download.receive(hash, block);
+        let applied = self.apply_downloaded_blocks();
+        if !applied.is_empty() {
+            debug!("{}Applied {} downloaded blocks", trace, applied.len());
+            self.index_consolidated_blocks(ctx, &applied, trace);
+            self.notify_consolidated_blocks(&applied, trace);
+        }
+
+        // The window of the peer that completed the range has room for another one
+        if delivery == Delivery::RangeCompleted && self.download.has_pending() {
+            self.request_block_download();
+        }
+    }
+
+    /// Method to apply the downloaded blocks whose previous blocks have all been applied
+    /// Blocks that do not extend the local chain are discarded, and the whole download is
+    /// discarded as soon as a block contradicts the trusted checkpoints. The signatures of the
+    /// blocks after the last trusted checkpoint have already been verified when they were
+    /// received (see `receive_downloaded_block`)
+    /// Returns the hashes of the applied blocks, in order
+    fn apply_downloaded_blocks(&mut self) -> Vec<Hash> {
+        // Blocks cannot be applied until the chain info is loaded
+        let (mut tip, consensus_constants) = match &self.chain_info {
+            Some(chain_info) => (
+                chain_info.highest_block_checkpoint,
+                chain_info.consensus_constants.clone(),
+            ),
+            None => return vec![],
+        };
+        let trusted_checkpoints = &consensus_constants.trusted_checkpoints;
+        let now = get_timestamp();
+
+        let mut applied = vec![];
+        for (hash, block) in self.download.take_ready() {
+            let beacon = block.header.block_header.beacon;
+            if beacon.checkpoint <= tip.checkpoint || beacon.hash_prev_block != tip.hash_prev_block
+            {
+                debug!(
+                    "Discarding downloaded block {:?}: it does not extend the local chain",
+                    hash
+                );
+                continue;
+            }
+
+            if is_from_the_future(beacon.checkpoint, now, &consensus_constants) {
+                debug!(
+                    "Discarding downloaded block {:?}: checkpoint {} has not started yet",
+                    hash, beacon.checkpoint
+                );
+                continue;
+            }
+
+            // The peers are serving an invalid chain, and the rest of blocks being downloaded
+            // belong to it
+            if contradicts_trusted_checkpoints(
+                trusted_checkpoints,
+                tip.checkpoint,
+                beacon.checkpoint,
+                hash,
+            ) {
+                warn!(
+                    "Downloaded block {:?} for checkpoint {} contradicts the trusted checkpoints",
+                    hash, beacon.checkpoint
+                );
+                self.download.clear();
+                break;
+            }
+
+            if !verify_utxo_commitment(&block) {
+                debug!(
+                    "Discarding downloaded block {:?}: invalid UTXO set commitment",
+                    hash
+                );
+                continue;
+            }
+
+            if !verify_mint_transaction(&block, &consensus_constants) {
+                debug!(
+                    "Discarding downloaded block {:?}: invalid mint transaction",
+                    hash
+                );
+                continue;
+            }
+
+            self.epoch_to_block_hash
+                .entry(beacon.checkpoint)
+                .or_insert_with(HashSet::new)
+                .insert(hash);
+            self.blocks.insert(hash, block);
+            tip = CheckpointBeacon {
+                checkpoint: beacon.checkpoint,
+                hash_prev_block: hash,
+            };
+            applied.push(hash);
+        }
+
+        applied
+    }
+
+    /// Method to start a rescan of the blocks consolidated since an epoch: the outputs and the
+    /// wallet confirmations derived from those blocks are discarded, and the blocks are replayed
+    /// through the UtxoManager and the WalletManager
+    fn start_rescan(
+        &mut self,
+        ctx: &mut Context<Self>,
+        from_epoch: Epoch,
+    ) -> Result<RescanStatus, BlocksManagerError> {
+        if self
+            .rescan
+            .as_ref()
+            .map_or(false, |rescan| !rescan.is_finished())
+        {
+            return Err(BlocksManagerError::RescanInProgress);
+        }
+
+        let blocks = self
+            .block_index
+            .range(from_epoch..)
+            .map(|(&checkpoint, &hash)| (checkpoint, hash))
+            .collect();
+        let rescan = Rescan::new(from_epoch, blocks);
+        let status = rescan.status().clone();
+        info!(
+            "Rescanning {} consolidated blocks since epoch {}",
+            status.total, from_epoch
+        );
+        self.rescan = Some(rescan);
+
+        // The balances and UTXOs keep being reported as they were before the rescan until all
+        // the blocks have been replayed
+        let utxo_manager_addr = System::current().registry().get::<UtxoManager>();
+        utxo_manager_addr.do_send(TakeSnapshot);
+        utxo_manager_addr.do_send(DiscardOutputsSince {
+            checkpoint: from_epoch,
+        });
+        System::current()
+            .registry()
+            .get::<WalletManager>()
+            .do_send(RevertConfirmationsSince {
+                checkpoint: from_epoch,
+            });
+        if from_epoch == 0 {
+            self.notify_genesis_allocation();
+        }
+        self.rescan_next_block(ctx);
+
+        Ok(status)
+    }
+
+    /// Method to replay the next block of the rescan in progress, reading it from storage
+    /// The blocks are replayed one at a time, so the actor keeps processing other messages
+    fn rescan_next_block(&mut self, ctx: &mut Context<Self>) {
+        let rescan = match self.rescan.as_mut() {
+            Some(rescan) => rescan,
+            None => return,
+        };
+        let (checkpoint, hash) = match rescan.next_block() {
+            Some(block) => block,
+            None => {
+                info!(
+                    "Rescan finished: {} blocks replayed, {} skipped",
+                    rescan.status().replayed,
+                    rescan.status().skipped
+                );
+                System::current()
+                    .registry()
+                    .get::<UtxoManager>()
+                    .do_send(ReleaseSnapshot);
+                return;
+            }
+        };
+
+        let Hash::SHA256(key) = hash;
+        let storage_manager_addr = System::current().registry().get::<StorageManager>();
+        storage_manager_addr
+            .send(Get::<Block>::new(key.to_vec()))
+            .into_actor(self)
+            .then(move |res, act, ctx| {
+                let block = match res {
+                    // Blocks reverted during the rescan are not replayed
+                    Ok(Ok(Some(block))) => {
+                        if act.block_index.get(&checkpoint) == Some(&hash) {
+                            Some(block)
+                        } else {
+                            None
+                        }
+                    }
+                    Ok(Ok(None)) => {
+                        warn!("Consolidated block {:?} not found in storage", hash);
+                        None
+                    }
+                    Ok(Err(e)) => {
+                        error!("Error while getting block {:?} from storage: {}", hash, e);
+                        None
+                    }
+                    Err(e) => {
+                        error!("Unsuccessful communication with storage manager: {}", e);
+                        None
+                    }
+                };
+
+                if let Some(rescan) = act.rescan.as_mut() {
+                    match block {
+                        Some(block) => {
+                            System::current().registry().get::<UtxoManager>().do_send(
+                                AddBlockOutputs {
+                                    block_hash: hash,
+                                    checkpoint,
+                                    outputs: vec![block.mint.output.clone()],
+                                },
+                            );
+                            System::current().registry().get::<WalletManager>().do_send(
+                                BlockConsolidated {
+                                    block_hash: hash,
+                                    checkpoint,
+                                    transactions: block_transaction_hashes(&block),
+                                },
+                            );
+                            rescan.block_replayed(checkpoint);
+                        }
+                        None => rescan.block_skipped(),
+                    }
+                }
+                act.rescan_next_block(ctx);
+
+                actix::fut::ok(())
+            })
+            .spawn(ctx);
+    }
+
+    /// Method to get the progress of the last rescan, if any
+    fn get_rescan_status(&self) -> Option<RescanStatus> {
+        self.rescan.as_ref().map(|rescan| rescan.status().clone())
+    }
+
+    /// Method to report the outputs created by the genesis block to the UTXO manager
+    /// They are not included in any block, so they are reported every time the node starts
+    /// instead of when a block is consolidated
+    fn notify_genesis_allocation(&self) {
+        let consensus_constants = match self.chain_info.as_ref() {
+            Some(chain_info) => &chain_info.consensus_constants,
+            None => return,
+        };
+        if consensus_constants.genesis_allocation.is_empty() {
+            return;
+        }
+
+        info!(
+            "Adding {} outputs of the genesis allocation to the UTXO set",
+            consensus_constants.genesis_allocation.len()
+        );
+        System::current()
+            .registry()
+            .get::<UtxoManager>()
+            .do_send(AddBlockOutputs {
+                block_hash: consensus_constants.genesis_hash,
+                checkpoint: 0,
+                outputs: consensus_constants.genesis_allocation.clone(),
+            });
+    }
+
+    /// Method to report the consolidated blocks to the mempool (the fee rates and transactions
+    /// included in them), to the UTXO manager (the outputs created by them), to the wallet
+    /// (the transactions included in them) and to the block subscribers
+    fn notify_consolidated_blocks(&mut self, consolidated: &[Hash], trace: Trace) {
+        let mempool_manager_addr = System::current().registry().get::<MempoolManager>();
+        let utxo_manager_addr = System::current().registry().get::<UtxoManager>();
+        let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+        let data_requests_manager_addr = System::current().registry().get::<DataRequestsManager>();
+
+        // The outputs of several blocks are reported to the UTXO manager as a single update, so
+        // no query is answered while only some of them have been applied
+        let batch = consolidated.len() > 1;
+        if batch {
+            utxo_manager_addr.do_send(TakeSnapshot);
+        }
+        for hash in consolidated {
+            if let Some(block) = self.blocks.get(hash) {
+                let checkpoint = block.header.block_header.beacon.checkpoint;
+                let transactions = block_transaction_hashes(block);
+                if let Some(fee_rates) = block_fee_rates(block) {
+                    mempool_manager_addr.do_send(RecordBlockFees { fee_rates });
+                }
+                mempool_manager_addr.do_send(TransactionsConfirmed {
+                    block_hash: *hash,
+                    checkpoint,
+                    transactions: transactions.clone(),
+                    trace,
+                });
+                utxo_manager_addr.do_send(AddBlockOutputs {
+                    block_hash: *hash,
+                    checkpoint,
+                    outputs: vec![block.mint.output.clone()],
+                });
+                wallet_manager_addr.do_send(BlockConsolidated {
+                    block_hash: *hash,
+                    checkpoint,
+                    transactions,
+                });
+                // The tallies of block candidates were already validated before accepting them
+                // (see `verify_block_tallies`), but downloaded blocks are only checked here, so
+                // the DataRequestsManager validates them again and logs the ones it rejects
+                for tally in block_tallies(block) {
+                    data_requests_manager_addr.do_send(tally);
+                }
+            }
+        }
+        if batch {
+            utxo_manager_addr.do_send(ReleaseSnapshot);
+        }
+
+        // Send the consolidated blocks to the subscribers, forgetting the ones which have gone
+        // away
+        let blocks = &self.blocks;
+        self.block_subscribers.retain(|subscriber| {
+            consolidated
+                .iter()
+                .filter_map(|hash| blocks.get(hash))
+                .all(|block| subscriber.unbounded_send(block.clone()).is_ok())
+        });
+    }
+
+    /// Method to verify the signatures of a block whose header has already been checked, using
+    /// the signature verifier pool (or the current thread if the pool has not been started)
+    fn verify_block_signatures(
+        &self,
+        block: &Block,
+    ) -> Box<dyn Future<Item = (), Error = BlocksManagerError>> {
+        let jobs = block_signature_jobs(block);
+        if jobs.is_empty() {
+            return Box::new(future::ok(()));
+        }
+
+        match &self.signature_verifier {
+            Some(pool) => Box::new(
+                verify_in_parallel(pool, jobs, self.signature_workers)
+                    .map_err(BlocksManagerError::from),
+            ),
+            None => Box::new(future::result(
+                verify_all(&Secp256k1::verification_only(), &jobs).map_err(|(index, error)| {
+                    BlocksManagerError::from(SignatureVerifierError::InvalidSignature {
+                        index,
+                        error,
+                    })
+                }),
+            )),
+        }
+    }
+
+    /// Method to check the tallies of a block against the commits and reveals of their data
+    /// requests, which are kept by the DataRequestsManager, so blocks paying the wrong witnesses
+    /// are rejected before being added to the candidate pool
+    fn verify_block_tallies(
+        &self,
+        block: &Block,
+    ) -> Box<dyn Future<Item = (), Error = BlocksManagerError>> {
+        let tallies = block_tallies(block);
+        if tallies.is_empty() {
+            return Box::new(future::ok(()));
+        }
+
+        let data_requests_manager_addr = System::current().registry().get::<DataRequestsManager>();
+        let validations = tallies.into_iter().map(move |tally| {
+            data_requests_manager_addr
+                .send(ValidateTally {
+                    hash: tally.hash,
+                    liars: tally.liars,
+                    outputs: tally.outputs,
+                })
+                .map_err(BlocksManagerError::from)
+                .and_then(|res| res.map_err(BlocksManagerError::InvalidTally))
+        });
+
+        Box::new(future::join_all(validations).map(|_| ()))
+    }
+
+    /// Method to validate a new block and add it to the candidate pool (see `check_block_header`
+    /// and `validate_and_add_block`)
+    fn process_new_block(&mut self, block: Block) -> Result<Hash, BlocksManagerError> {
+        let hash = self.check_block_header(&block)?;
+
+        self.validate_and_add_block(hash, block)
+    }
+
+    /// Method to run the cheap checks of a new block, which only depend on its header: blocks
+    /// passing them can be announced to the peers before being fully validated
+    /// Returns the hash of the block
+    fn check_block_header(&self, block: &Block) -> Result<Hash, BlocksManagerError> {
+        // Calculate the hash of the block
+        let hash = calculate_sha256(&block.hashed_bytes());
+        let now = get_timestamp();
+        let beacon = &block.header.block_header.beacon;
+
+        // Check if we already have a block with that hash
+        if let Some(_block) = self.blocks.get(&hash) {
+            Err(BlocksManagerError::BlockAlreadyExists)
+        } else if self.chain_info.as_ref().map_or(false, |chain_info| {
+            is_from_the_future(beacon.checkpoint, now, &chain_info.consensus_constants)
+        }) {
+            // Blocks for checkpoints which have not started yet are rejected right away, whatever
+            // the current epoch notified by the EpochManager
+            Err(BlocksManagerError::BlockFromTheFuture)
+        } else if self.chain_info.as_ref().map_or(false, |chain_info| {
+            block_violates_finality(
+                &self.block_index,
+                chain_info.consensus_constants.genesis_hash,
+                final_checkpoint(
+                    chain_info.highest_block_checkpoint.checkpoint,
+                    self.max_reorg_depth,
+                ),
+                beacon.hash_prev_block,
+            )
+        }) {
+            // Blocks forking the local chain before its final blocks are never accepted
+            Err(BlocksManagerError::FinalityViolation)
+        } else {
+            // The candidate pool must have room for the block
+            self.check_candidate_range(beacon.checkpoint, now)?;
+            self.check_candidate_pool(beacon.checkpoint, block.header.proof.influence)?;
+
+            Ok(hash)
+        }
+    }
+
+    /// Method to fully validate a block whose header has already been checked and add it to
+    /// the candidate pool
+    /// Returns the hash of the block
+    fn validate_and_add_block(
+        &mut self,
+        hash: Hash,
+        block: Block,
+    ) -> Result<Hash, BlocksManagerError> {
+        if !verify_utxo_commitment(&block) {
+            // Blocks whose UTXO set diverges from the local one are rejected right away
+            Err(BlocksManagerError::InvalidUtxoCommitment)
+        } else if !self.chain_info.as_ref().map_or(true, |chain_info| {
+            verify_mint_transaction(&block, &chain_info.consensus_constants)
+        }) {
+            Err(BlocksManagerError::InvalidMintTransaction)
+        } else {
+            // Make room for the new block in the candidate pool
+            self.add_candidate(
+                block.header.block_header.beacon.checkpoint,
+                block.header.proof.influence,
+                hash,
+                get_timestamp(),
+            )?;
+
+            // This is a new block, insert it into the internal maps
+            {
+                // Insert the new block into the map that relates epochs to block hashes
+                let beacon = &block.header.block_header.beacon;
+                let hash_set = &mut self
+                    .epoch_to_block_hash
+                    .entry(beacon.checkpoint)
+                    .or_insert_with(HashSet::new);
+                hash_set.insert(hash);
+
+                debug!(
+                    "Checkpoint {} has {} blocks",
+                    beacon.checkpoint,
+                    hash_set.len()
+                );
+            }
+
+            // Insert the new block into the map of known blocks
+            self.blocks.insert(hash, block);
+
+            Ok(hash)
+        }
+    }
+
+    /// Method to announce a new block candidate to the peers, recording the time elapsed since
+    /// the block was received
+    fn relay_block(&self, hash: Hash, received_at: Instant, trace: Trace) {
+        let latency = received_at.elapsed();
+        metrics::record_block_relay(latency);
+        debug!(
+            "{}Relaying block candidate {:?} {:?} after receiving it",
+            trace, hash, latency
+        );
+
+        // Tell SessionsManager to announce the new block through every consolidated Session
+        System::current()
+            .registry()
+            .get::<SessionsManager>()
+            .do_send(Broadcast {
+                command: AnnounceItems {
+                    items: vec![InvVector::Block(hash)],
+                },
+            });
+    }
+
+    /// Method to check that a block candidate for a checkpoint can be accepted: only candidates
+    /// for the current and previous checkpoints are
+    fn check_candidate_range(&self, checkpoint: Epoch, now: i64) -> Result<(), BlocksManagerError> {
+        // Candidates can only be checked once the current epoch is known
+        if let Some(current_epoch) = self.current_epoch {
+            // Candidates for the next checkpoint are accepted too once it is about to start, as
+            // the clocks of the miners may be slightly ahead of the local clock
+            let next_checkpoint_started = Some(checkpoint) == current_epoch.checked_add(1)
+                && self.chain_info.as_ref().map_or(false, |chain_info| {
+                    !is_from_the_future(checkpoint, now, &chain_info.consensus_constants)
+                });
+            if (checkpoint > current_epoch && !next_checkpoint_started)
+                || checkpoint + 1 < current_epoch
+            {
+                return Err(BlocksManagerError::BlockCandidateOutOfRange);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Method to check that there is room in the candidate pool of a checkpoint for a candidate
+    /// with some influence, evicting the candidate with the lowest influence if needed
+    fn check_candidate_pool(
+        &self,
+        checkpoint: Epoch,
+        influence: u64,
+    ) -> Result<(), BlocksManagerError> {
+        match self.candidates.get(&checkpoint) {
+            Some(candidates) if candidates.len() >= MAX_CANDIDATES_PER_CHECKPOINT => {
+                match candidates.last() {
+                    Some(&(lowest_influence, _)) if lowest_influence < influence => Ok(()),
+                    _ => Err(BlocksManagerError::BlockCandidatePoolFull),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Method to insert a block candidate into the candidate pool
+    /// Only candidates for the current and previous checkpoints are accepted. If the pool for
+    /// the checkpoint is full, the candidate with the lowest influence is evicted (as long as
+    /// the new candidate has a higher influence)
+    fn add_candidate(
+        &mut self,
+        checkpoint: Epoch,
+        influence: u64,
+        hash: Hash,
+        now: i64,
+    ) -> Result<(), BlocksManagerError> {
+        self.check_candidate_range(checkpoint, now)?;
+        self.epoch_outcomes.candidate_received(checkpoint);
+
+        let candidates = self.candidates.entry(checkpoint).or_insert_with(Vec::new);
+        if candidates.len() >= MAX_CANDIDATES_PER_CHECKPOINT {
+            let lowest_influence = candidates
+                .last()
+                .map(|&(lowest_influence, _)| lowest_influence);
+            match lowest_influence {
+                Some(lowest_influence) if lowest_influence < influence => {
+                    // Evict the candidate with the lowest influence
+                    if let Some((_, evicted_hash)) = candidates.pop() {
+                        debug!("Evicting block candidate {:?}", evicted_hash);
+                        self.blocks.remove(&evicted_hash);
+                        if let Some(hash_set) = self.epoch_to_block_hash.get_mut(&checkpoint) {
+                            hash_set.remove(&evicted_hash);
+                        }
+                    }
+                }
+                _ => return Err(BlocksManagerError::BlockCandidatePoolFull),
+            }
+        }
+
+        // Keep the candidates sorted by influence in descending order
+        let position = candidates
+            .iter()
+            .position(|&(candidate_influence, _)| candidate_influence < influence)
+            .unwrap_or_else(|| candidates.len());
+        candidates.insert(position, (influence, hash));
+
+        Ok(())
+    }
+
+    /// Method to consolidate the block candidates of the checkpoints that are no longer
+    /// accepted (older than the previous checkpoint), given the current epoch
+    /// The candidate with the highest influence of each checkpoint is kept and the rest of
+    /// them are discarded
+    /// Returns the hashes of the consolidated blocks
+    fn consolidate_candidates(&mut self, current_epoch: Epoch) -> Vec<Hash> {
+        self.current_epoch = Some(current_epoch);
+
+        // Split the candidates that are still accepted from the expired ones
+        let accepted = self.candidates.split_off(&current_epoch.saturating_sub(1));
+        let expired = std::mem::replace(&mut self.candidates, accepted);
+
+        expired
+            .into_iter()
+            .filter_map(|(checkpoint, candidates)| {
+                let mut candidates = candidates.into_iter();
+                let consolidated = candidates.next().map(|(_, hash)| hash);
+
+                // Discard the rest of candidates
+                for (_, hash) in candidates {
+                    self.blocks.remove(&hash);
+                    if let Some(hash_set) = self.epoch_to_block_hash.get_mut(&checkpoint) {
+                        hash_set.remove(&hash);
+                    }
+                }
+
+                if let Some(hash) = consolidated {
+                    debug!(
+                        "Consolidated block {:?} for checkpoint {}",
+                        hash, checkpoint
+                    );
+                }
+
+                consolidated
+            })
+            .collect()
+    }
+
+    /// Method to update the synchronization state of the node given the current epoch
+    /// Returns the new synchronization state
+    fn update_sync_state(&mut self, current_epoch: Epoch) -> SyncState {
+        // The state cannot be calculated until the chain info is loaded
+        let tip = match &self.chain_info {
+            Some(chain_info) => chain_info.highest_block_checkpoint.checkpoint,
+            None => return self.sync_state,
+        };
+
+        let sync_state = calculate_sync_state(
+            tip,
+            current_epoch,
+            self.peers_last_epoch(),
+            self.max_epochs_behind,
+        );
+        if sync_state != self.sync_state {
+            match sync_state {
+                SyncState::Syncing => warn!(
+                    "The local chain (tip at checkpoint {}) has fallen behind, going back to \
+                     syncing state",
+                    tip
+                ),
+                SyncState::Synced => info!("The local chain is synced (tip at checkpoint {})", tip),
+            }
+            self.sync_state = sync_state;
+        }
+
+        sync_state
+    }
+
+    /// Method to check whether the sync is paused because there is no consolidated outbound session
+    fn sync_paused(&self) -> bool {
+        self.partition.num_outbound_consolidated() == 0
+    }
+
+    /// Method to react to a session lifecycle event published by the SessionsManager, starting
+    /// the sync when the first outbound session is consolidated and pausing it when the node
+    /// loses its last outbound session
+    fn on_session_event(&mut self, event: &SessionEvent, num_outbound: usize) {
+        let previous_outbound = self.partition.num_outbound_consolidated();
+        self.partition.set_num_outbound_consolidated(num_outbound);
+
+        // The epochs reported by the peers are only taken into account while they are connected
+        match event {
+            SessionEvent::Unregistered { address, .. } => {
+                self.peers_last_epoch.remove(address);
+            }
+            SessionEvent::Restarted { .. } => self.peers_last_epoch.clear(),
+            _ => {}
+        }
+
+        match sync_trigger(event, previous_outbound, num_outbound, self.sync_state) {
+            Some(SyncTrigger::Start) => {
+                info!("First outbound session consolidated, requesting the missing blocks");
+                let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
+                sessions_manager_addr.do_send(Anycast {
+                    command: RequestBlocks,
+                });
+                self.request_block_download();
+            }
+            Some(SyncTrigger::Pause) => {
+                warn!("No consolidated outbound sessions left, pausing the sync");
+            }
+            None => {}
+        }
+    }
+
+    /// Method to update the isolation of the node given the current epoch, pausing mining and
+    /// notifying the SessionsManager when it changes
+    fn update_isolation(&mut self, current_epoch: Epoch) {
+        // The tip is unknown until the chain info is loaded
+        let tip = match &self.chain_info {
+            Some(chain_info) => chain_info.highest_block_checkpoint.checkpoint,
+            None => return,
+        };
+
+        let isolated = match self
+            .partition
+            .on_epoch(current_epoch, tip, self.peers_last_epoch())
+        {
+            Some(IsolationChange::Isolated(reason)) => {
+                warn!(
+                    "The node is isolated from the network ({:?}), pausing mining and witnessing",
+                    reason
+                );
+                true
+            }
+            Some(IsolationChange::Reconnected) => {
+                info!("The node is no longer isolated from the network");
+                false
+            }
+            None => return,
+        };
+        metrics::record_isolation(isolated);
+
+        let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
+        sessions_manager_addr.do_send(SetIsolation { isolated });
+    }
+
+    /// Method to get the status of the node
+    fn get_node_status(&self) -> Result<NodeStatus, BlocksManagerError> {
+        Ok(NodeStatus {
+            sync_state: self.sync_state,
+            isolated: self.partition.isolated(),
+            current_epoch: self.current_epoch,
+            tip: self.get_highest_checkpoint_beacon()?,
+            peers_last_epoch: self.peers_last_epoch(),
+            num_outbound_consolidated: self.partition.num_outbound_consolidated(),
+        })
+    }
 
-        // Check if we already have a block with that hash
-        if let Some(_block) = self.blocks.get(&hash) {
-            Err(BlocksManagerError::BlockAlreadyExists)
+    /// Method to get the highest block checkpoint of the local chain
+    fn get_highest_checkpoint_beacon(&self) -> ChainInfoResult<CheckpointBeacon> {
+        if let Some(chain_info) = &self.chain_info {
+            Ok(chain_info.highest_block_checkpoint)
         } else {
-            // This is a new block, insert it into the internal maps
-            {
-                // Insert the new block into the map that relates epochs to block hashes
-                let beacon = &block.header.block_header.beacon;
-                let hash_set = &mut self
-                    .epoch_to_block_hash
-                    .entry(beacon.checkpoint)
-                    .or_insert_with(HashSet::new);
-                hash_set.insert(hash);
+            error!("No ChainInfo loaded in BlocksManager");
+            Err(WitnetError::from(ChainInfoError::new(
+                ChainInfoErrorKind::ChainInfoNotFound,
+                "No ChainInfo loaded in BlocksManager".to_string(),
+            )))
+        }
+    }
 
-                debug!(
-                    "Checkpoint {} has {} blocks",
-                    beacon.checkpoint,
-                    hash_set.len()
-                );
+    /// Method to get the supply issued by the consolidated blocks of the local chain
+    fn get_supply_info(&self) -> ChainInfoResult<SupplyInfo> {
+        let chain_info = self.chain_info.as_ref().ok_or_else(|| {
+            WitnetError::from(ChainInfoError::new(
+                ChainInfoErrorKind::ChainInfoNotFound,
+                "No ChainInfo loaded in BlocksManager".to_string(),
+            ))
+        })?;
+
+        Ok(calculate_supply_info(
+            &self.block_index,
+            chain_info.highest_block_checkpoint.checkpoint,
+            &chain_info.consensus_constants,
+        ))
+    }
+
+    /// Method to get the last epoch whose blocks have been consolidated: the tip of the local
+    /// chain or, if the node is synced, the last epoch whose candidates have expired
+    fn last_consolidated_epoch(&self) -> Result<Epoch, BlocksManagerError> {
+        let tip = self
+            .chain_info
+            .as_ref()
+            .map(|chain_info| chain_info.highest_block_checkpoint.checkpoint)
+            .ok_or(BlocksManagerError::EpochNotConsolidated)?;
+
+        Ok(match (self.sync_state, self.current_epoch) {
+            (SyncState::Synced, Some(current_epoch)) => {
+                std::cmp::max(tip, current_epoch.saturating_sub(2))
             }
+            _ => tip,
+        })
+    }
 
-            // Insert the new block into the map of known blocks
-            self.blocks.insert(hash, block);
+    /// Method to get the hash of the block consolidated for an epoch, or None if the epoch has
+    /// been consolidated without any block
+    fn get_block_hash_by_epoch(&self, epoch: Epoch) -> Result<Option<Hash>, BlocksManagerError> {
+        consolidated_block_hash(&self.block_index, self.last_consolidated_epoch()?, epoch)
+    }
 
-            Ok(hash)
+    /// Method to check that the last beacon reported by a peer does not contradict the final
+    /// blocks of the local chain
+    fn check_peer_finality(&self, beacon: &CheckpointBeacon) -> Result<(), BlocksManagerError> {
+        let chain_info = match &self.chain_info {
+            Some(chain_info) => chain_info,
+            None => return Ok(()),
+        };
+        let final_checkpoint = final_checkpoint(
+            chain_info.highest_block_checkpoint.checkpoint,
+            self.max_reorg_depth,
+        );
+
+        if beacon_violates_finality(
+            &self.block_index,
+            chain_info.consensus_constants.genesis_hash,
+            final_checkpoint,
+            beacon,
+        ) {
+            Err(BlocksManagerError::FinalityViolation)
+        } else {
+            Ok(())
         }
     }
 
+    /// Method to keep track of the last epoch reported by a peer
+    fn report_peer_last_epoch(&mut self, peer: SocketAddr, last_epoch: Epoch) {
+        let epoch = self.peers_last_epoch.entry(peer).or_insert(last_epoch);
+        *epoch = std::cmp::max(*epoch, last_epoch);
+    }
+
+    /// Method to get the highest last epoch reported by the connected peers
+    fn peers_last_epoch(&self) -> Option<Epoch> {
+        self.peers_last_epoch.values().max().cloned()
+    }
+
     fn try_to_get_block(&mut self, hash: Hash) -> Result<Block, BlocksManagerError> {
         // Check if we have a block with that hash
         self.blocks.get(&hash).map_or_else(
@@ -182,6 +1930,10 @@ impl BlocksManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use witnet_p2p::sessions::SessionStatus;
+
+    /// Number of epochs the local chain tip can lag behind in the tests
+    const MAX_EPOCHS_BEHIND: Epoch = 10;
 
     #[test]
     fn add_block() {
@@ -224,6 +1976,60 @@ mod tests {
         assert_eq!(bm.blocks.len(), 1);
     }
 
+    #[test]
+    fn add_block_invalid_utxo_commitment() {
+        let mut bm = BlocksManager::default();
+
+        // Build a block committing to a different UTXO set
+        let mut block = build_hardcoded_block(2, 99999);
+        block.header.block_header.utxo_merkle_root = Hash::SHA256([1; 32]);
+
+        match bm.process_new_block(block) {
+            Err(BlocksManagerError::InvalidUtxoCommitment) => {}
+            x => panic!("{:?}", x),
+        }
+        assert!(bm.blocks.is_empty());
+        assert!(bm.candidates.is_empty());
+    }
+
+    #[test]
+    fn check_block_header_before_validation() {
+        let mut bm = BlocksManager::default();
+
+        // The header checks do not validate the UTXO set commitment, nor change the state
+        let mut block = build_hardcoded_block(2, 99999);
+        block.header.block_header.utxo_merkle_root = Hash::SHA256([1; 32]);
+        let hash = bm.check_block_header(&block).unwrap();
+        assert!(bm.blocks.is_empty());
+        assert!(bm.candidates.is_empty());
+
+        match bm.validate_and_add_block(hash, block) {
+            Err(BlocksManagerError::InvalidUtxoCommitment) => {}
+            x => panic!("{:?}", x),
+        }
+        assert!(bm.blocks.is_empty());
+
+        // Candidates which do not fit in the pool are rejected by the header checks
+        for influence in 1..=MAX_CANDIDATES_PER_CHECKPOINT as u64 {
+            bm.process_new_block(build_hardcoded_block(2, influence))
+                .unwrap();
+        }
+        match bm.check_block_header(&build_hardcoded_block(2, 0)) {
+            Err(BlocksManagerError::BlockCandidatePoolFull) => {}
+            x => panic!("{:?}", x),
+        }
+        assert!(bm
+            .check_block_header(&build_hardcoded_block(2, 99999))
+            .is_ok());
+
+        // Candidates out of range are rejected by the header checks too
+        bm.consolidate_candidates(5);
+        match bm.check_block_header(&build_hardcoded_block(3, 1)) {
+            Err(BlocksManagerError::BlockCandidateOutOfRange) => {}
+            x => panic!("{:?}", x),
+        }
+    }
+
     #[test]
     fn add_blocks_same_epoch() {
         let mut bm = BlocksManager::default();
@@ -375,7 +2181,722 @@ mod tests {
         assert_eq!(missing_inv_vectors, inv_vectors);
     }
 
+    #[test]
+    fn add_candidate_out_of_range() {
+        let mut bm = BlocksManager::default();
+        bm.consolidate_candidates(5);
+
+        // Candidates for the current and previous checkpoints are accepted
+        assert!(bm.process_new_block(build_hardcoded_block(5, 1)).is_ok());
+        assert!(bm.process_new_block(build_hardcoded_block(4, 1)).is_ok());
+
+        // Candidates for older or future checkpoints are rejected
+        match bm.process_new_block(build_hardcoded_block(3, 1)) {
+            Err(BlocksManagerError::BlockCandidateOutOfRange) => {}
+            _ => panic!("Old block candidate should be rejected"),
+        }
+        match bm.process_new_block(build_hardcoded_block(6, 1)) {
+            Err(BlocksManagerError::BlockCandidateOutOfRange) => {}
+            _ => panic!("Future block candidate should be rejected"),
+        }
+        assert_eq!(bm.blocks.len(), 2);
+    }
+
+    #[test]
+    fn add_candidate_pool_full() {
+        let mut bm = BlocksManager::default();
+        let checkpoint = 2;
+
+        // Fill the pool for one checkpoint
+        for influence in 1..=MAX_CANDIDATES_PER_CHECKPOINT as u64 {
+            bm.process_new_block(build_hardcoded_block(checkpoint, influence))
+                .unwrap();
+        }
+
+        // A candidate with a lower influence than all the others is rejected
+        match bm.process_new_block(build_hardcoded_block(checkpoint, 0)) {
+            Err(BlocksManagerError::BlockCandidatePoolFull) => {}
+            _ => panic!("Block candidate should be rejected when the pool is full"),
+        }
+
+        // A candidate with a higher influence evicts the lowest one
+        let hash = bm
+            .process_new_block(build_hardcoded_block(checkpoint, 99999))
+            .unwrap();
+        let candidates = &bm.candidates[&checkpoint];
+        assert_eq!(candidates.len(), MAX_CANDIDATES_PER_CHECKPOINT);
+        assert_eq!(candidates[0], (99999, hash));
+        assert_eq!(candidates.last().unwrap().0, 2);
+        assert_eq!(bm.blocks.len(), MAX_CANDIDATES_PER_CHECKPOINT);
+        assert_eq!(
+            bm.epoch_to_block_hash[&checkpoint].len(),
+            MAX_CANDIDATES_PER_CHECKPOINT
+        );
+    }
+
+    #[test]
+    fn consolidate_candidates() {
+        let mut bm = BlocksManager::default();
+
+        // Add candidates for two checkpoints
+        let hash_a = bm.process_new_block(build_hardcoded_block(1, 10)).unwrap();
+        let hash_b = bm.process_new_block(build_hardcoded_block(1, 20)).unwrap();
+        let hash_c = bm.process_new_block(build_hardcoded_block(2, 10)).unwrap();
+
+        // Checkpoint 1 is still accepted while the current epoch is 2
+        assert!(bm.consolidate_candidates(2).is_empty());
+        assert_eq!(bm.blocks.len(), 3);
+
+        // When the current epoch is 3 the candidate with the highest influence for checkpoint
+        // 1 is consolidated and the rest of candidates for that checkpoint are discarded
+        assert_eq!(bm.consolidate_candidates(3), vec![hash_b]);
+        assert!(bm.try_to_get_block(hash_a).is_err());
+        assert!(bm.try_to_get_block(hash_b).is_ok());
+        assert!(bm.try_to_get_block(hash_c).is_ok());
+        assert_eq!(bm.epoch_to_block_hash[&1].len(), 1);
+        assert!(!bm.candidates.contains_key(&1));
+        assert!(bm.candidates.contains_key(&2));
+    }
+
+    #[test]
+    fn block_hash_by_epoch() {
+        let block_index: BlockIndex = vec![(1, Hash::SHA256([1; 32])), (3, Hash::SHA256([3; 32]))]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            consolidated_block_hash(&block_index, 4, 3).unwrap(),
+            Some(Hash::SHA256([3; 32]))
+        );
+        // Epochs consolidated without any block
+        assert_eq!(consolidated_block_hash(&block_index, 4, 2).unwrap(), None);
+        assert_eq!(consolidated_block_hash(&block_index, 4, 4).unwrap(), None);
+        // Epochs not consolidated yet
+        match consolidated_block_hash(&block_index, 4, 5) {
+            Err(BlocksManagerError::EpochNotConsolidated) => {}
+            x => panic!("{:?}", x),
+        }
+    }
+
+    #[test]
+    fn block_chain_up_to_tip() {
+        let block_index: BlockIndex = vec![
+            (1, Hash::SHA256([1; 32])),
+            (3, Hash::SHA256([3; 32])),
+            (5, Hash::SHA256([5; 32])),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            block_chain_range(&block_index, 5, 0, 3),
+            vec![(1, Hash::SHA256([1; 32])), (3, Hash::SHA256([3; 32]))]
+        );
+        assert_eq!(block_chain_range(&block_index, 5, 4, 4), vec![]);
+        // Blocks beyond the tip are not part of the local chain
+        assert_eq!(
+            block_chain_range(&block_index, 4, 2, 10),
+            vec![(3, Hash::SHA256([3; 32]))]
+        );
+        assert_eq!(block_chain_range(&block_index, 4, 5, 10), vec![]);
+    }
+
+    #[test]
+    fn sync_state_synced() {
+        // Tip within the allowed lag of the current epoch and peers
+        assert_eq!(
+            calculate_sync_state(10, 10, None, MAX_EPOCHS_BEHIND),
+            SyncState::Synced
+        );
+        assert_eq!(
+            calculate_sync_state(
+                10,
+                10 + MAX_EPOCHS_BEHIND,
+                Some(10 + MAX_EPOCHS_BEHIND),
+                MAX_EPOCHS_BEHIND
+            ),
+            SyncState::Synced
+        );
+    }
+
+    #[test]
+    fn sync_state_behind_clock() {
+        assert_eq!(
+            calculate_sync_state(10, 11 + MAX_EPOCHS_BEHIND, None, MAX_EPOCHS_BEHIND),
+            SyncState::Syncing
+        );
+    }
+
+    #[test]
+    fn sync_state_behind_peers() {
+        let current_epoch = 100;
+        let tip = current_epoch - MAX_EPOCHS_BEHIND;
+
+        // The node is not behind the current epoch, but it is behind its peers
+        assert_eq!(
+            calculate_sync_state(
+                tip - 1,
+                current_epoch,
+                Some(current_epoch),
+                MAX_EPOCHS_BEHIND
+            ),
+            SyncState::Syncing
+        );
+
+        // Peers reporting epochs in the future are capped to the current epoch
+        assert_eq!(
+            calculate_sync_state(tip, current_epoch, Some(std::u32::MAX), MAX_EPOCHS_BEHIND),
+            SyncState::Synced
+        );
+    }
+
+    #[test]
+    fn peers_last_epoch_of_connected_peers() {
+        let mut bm = BlocksManager::default();
+        let first = "127.0.0.1:21337".parse().unwrap();
+        let second = "127.0.0.1:21338".parse().unwrap();
+        assert_eq!(bm.peers_last_epoch(), None);
+
+        bm.report_peer_last_epoch(first, 20);
+        bm.report_peer_last_epoch(second, 10);
+        bm.report_peer_last_epoch(second, 30);
+        bm.report_peer_last_epoch(second, 25);
+        assert_eq!(bm.peers_last_epoch(), Some(30));
+
+        // The epochs of a disconnected peer are forgotten
+        bm.on_session_event(
+            &SessionEvent::Unregistered {
+                address: second,
+                session_type: SessionType::Inbound,
+                status: SessionStatus::Consolidated,
+            },
+            0,
+        );
+        assert_eq!(bm.peers_last_epoch(), Some(20));
+        bm.on_session_event(&SessionEvent::Restarted { removed: 1 }, 0);
+        assert_eq!(bm.peers_last_epoch(), None);
+    }
+
+    #[test]
+    fn node_status_json() {
+        let status = NodeStatus {
+            sync_state: SyncState::Synced,
+            isolated: Some(IsolationReason::NoOutboundSessions),
+            current_epoch: Some(12),
+            tip: CheckpointBeacon {
+                checkpoint: 11,
+                hash_prev_block: Hash::SHA256([0; 32]),
+            },
+            peers_last_epoch: None,
+            num_outbound_consolidated: 0,
+        };
+        let json = serde_json::to_value(&status).unwrap();
+
+        assert_eq!(json["sync_state"], "synced");
+        assert_eq!(json["isolated"], "no_outbound_sessions");
+        assert_eq!(json["tip"]["checkpoint"], 11);
+        assert_eq!(json["peers_last_epoch"], serde_json::Value::Null);
+    }
+
     #[cfg(test)]
+    #[test]
+    fn consistency_tip_indexed() {
+        let genesis_hash = Hash::SHA256([0; 32]);
+        let mut block_index: BlockIndex =
+            vec![(1, Hash::SHA256([1; 32])), (2, Hash::SHA256([2; 32]))]
+                .into_iter()
+                .collect();
+        let mut tip = CheckpointBeacon {
+            checkpoint: 2,
+            hash_prev_block: Hash::SHA256([2; 32]),
+        };
+
+        assert!(!ensure_consistency(
+            &mut tip,
+            &mut block_index,
+            genesis_hash
+        ));
+        assert_eq!(tip.checkpoint, 2);
+        assert_eq!(block_index.len(), 2);
+    }
+
+    #[test]
+    fn consistency_genesis() {
+        let genesis_hash = Hash::SHA256([0; 32]);
+        let mut block_index = BlockIndex::new();
+        let mut tip = CheckpointBeacon {
+            checkpoint: 0,
+            hash_prev_block: genesis_hash,
+        };
+
+        assert!(!ensure_consistency(
+            &mut tip,
+            &mut block_index,
+            genesis_hash
+        ));
+        assert_eq!(tip.hash_prev_block, genesis_hash);
+    }
+
+    #[test]
+    fn consistency_blocks_beyond_tip() {
+        let genesis_hash = Hash::SHA256([0; 32]);
+        let mut block_index: BlockIndex =
+            vec![(1, Hash::SHA256([1; 32])), (2, Hash::SHA256([2; 32]))]
+                .into_iter()
+                .collect();
+        let mut tip = CheckpointBeacon {
+            checkpoint: 1,
+            hash_prev_block: Hash::SHA256([1; 32]),
+        };
+
+        // The block of checkpoint 2 was indexed but the tip was never updated
+        assert!(ensure_consistency(&mut tip, &mut block_index, genesis_hash));
+        assert_eq!(tip.checkpoint, 1);
+        assert_eq!(block_index.len(), 1);
+    }
+
+    #[test]
+    fn consistency_tip_not_indexed() {
+        let genesis_hash = Hash::SHA256([0; 32]);
+        let mut block_index: BlockIndex =
+            vec![(1, Hash::SHA256([1; 32])), (2, Hash::SHA256([2; 32]))]
+                .into_iter()
+                .collect();
+        let mut tip = CheckpointBeacon {
+            checkpoint: 2,
+            hash_prev_block: Hash::SHA256([3; 32]),
+        };
+
+        // The tip does not match the indexed block, so it is rolled back to the previous one
+        assert!(ensure_consistency(&mut tip, &mut block_index, genesis_hash));
+        assert_eq!(
+            tip,
+            CheckpointBeacon {
+                checkpoint: 1,
+                hash_prev_block: Hash::SHA256([1; 32]),
+            }
+        );
+        assert_eq!(block_index.len(), 1);
+
+        // Without indexed blocks the tip goes back to the genesis block
+        let mut block_index = BlockIndex::new();
+        assert!(ensure_consistency(&mut tip, &mut block_index, genesis_hash));
+        assert_eq!(
+            tip,
+            CheckpointBeacon {
+                checkpoint: 0,
+                hash_prev_block: genesis_hash,
+            }
+        );
+    }
+
+    #[test]
+    fn orphaned_blocks_after_rollback() {
+        let genesis_hash = Hash::SHA256([0; 32]);
+        let mut block_index: BlockIndex = vec![
+            (1, Hash::SHA256([1; 32])),
+            (2, Hash::SHA256([2; 32])),
+            (3, Hash::SHA256([3; 32])),
+        ]
+        .into_iter()
+        .collect();
+        let previous_index = block_index.clone();
+        let mut tip = CheckpointBeacon {
+            checkpoint: 1,
+            hash_prev_block: Hash::SHA256([1; 32]),
+        };
+
+        assert!(ensure_consistency(&mut tip, &mut block_index, genesis_hash));
+        assert_eq!(
+            orphaned_blocks(&previous_index, &block_index),
+            vec![Hash::SHA256([2; 32]), Hash::SHA256([3; 32])]
+        );
+
+        // Blocks replaced by other blocks of the same checkpoint are orphaned too
+        let mut replaced_index = block_index.clone();
+        replaced_index.insert(1, Hash::SHA256([4; 32]));
+        assert_eq!(
+            orphaned_blocks(&block_index, &replaced_index),
+            vec![Hash::SHA256([1; 32])]
+        );
+        assert!(orphaned_blocks(&block_index, &block_index).is_empty());
+    }
+
+    #[test]
+    fn sync_trigger_on_session_events() {
+        let address = "127.0.0.1:21337".parse().unwrap();
+        let consolidated = |session_type| SessionEvent::Consolidated {
+            address,
+            session_type,
+        };
+        let outbound = consolidated(SessionType::Outbound);
+
+        // The first outbound session starts the sync, but only if the node is syncing
+        assert_eq!(
+            sync_trigger(&outbound, 0, 1, SyncState::Syncing),
+            Some(SyncTrigger::Start)
+        );
+        assert_eq!(sync_trigger(&outbound, 0, 1, SyncState::Synced), None);
+        assert_eq!(sync_trigger(&outbound, 1, 2, SyncState::Syncing), None);
+        assert_eq!(
+            sync_trigger(
+                &consolidated(SessionType::Inbound),
+                0,
+                0,
+                SyncState::Syncing
+            ),
+            None
+        );
+
+        // Losing the last outbound session pauses the sync
+        let unregistered = SessionEvent::Unregistered {
+            address,
+            session_type: SessionType::Outbound,
+            status: SessionStatus::Consolidated,
+        };
+        assert_eq!(
+            sync_trigger(&unregistered, 1, 0, SyncState::Syncing),
+            Some(SyncTrigger::Pause)
+        );
+        assert_eq!(sync_trigger(&unregistered, 2, 1, SyncState::Syncing), None);
+        let restarted = SessionEvent::Restarted { removed: 3 };
+        assert_eq!(
+            sync_trigger(&restarted, 3, 0, SyncState::Synced),
+            Some(SyncTrigger::Pause)
+        );
+    }
+
+    #[test]
+    fn schedule_block_download_synced() {
+        let mut bm = BlocksManager::default();
+        bm.sync_state = SyncState::Synced;
+        let missing = vec![
+            InvVector::Block(Hash::SHA256([1; 32])),
+            InvVector::Tx(Hash::SHA256([2; 32])),
+        ];
+
+        // Once synced, the missing blocks are requested to the announcing peer
+        assert_eq!(bm.schedule_block_download(missing.clone()), missing);
+        assert!(bm.download.is_empty());
+    }
+
+    #[test]
+    fn apply_downloaded_blocks_in_order() {
+        use witnet_data_structures::chain::{ConsensusConstants, Environment};
+
+        let genesis_hash = Hash::SHA256([4; 32]);
+        let mut bm = BlocksManager::default();
+        bm.chain_info = Some(ChainInfo {
+            environment: Environment::Testnet1,
+            consensus_constants: ConsensusConstants {
+                checkpoint_zero_timestamp: 0,
+                checkpoints_period: 90,
+                genesis_hash,
+                reputation_demurrage: 0.0,
+                reputation_punishment: 0.0,
+                trusted_checkpoints: vec![],
+                initial_block_reward: 0,
+                halving_period: 1,
+                reward_maturity: 0,
+                genesis_allocation: vec![],
+            },
+            highest_block_checkpoint: CheckpointBeacon {
+                checkpoint: 0,
+                hash_prev_block: genesis_hash,
+            },
+        });
+
+        // Blocks 1 and 2 extend the local chain, while the fork block does not
+        let block_1 = build_hardcoded_block(1, 0);
+        let hash_1 = calculate_sha256(&block_1.hashed_bytes());
+        let mut block_2 = build_hardcoded_block(2, 0);
+        block_2.header.block_header.beacon.hash_prev_block = hash_1;
+        let hash_2 = calculate_sha256(&block_2.hashed_bytes());
+        let mut block_fork = build_hardcoded_block(2, 1);
+        block_fork.header.block_header.beacon.hash_prev_block = Hash::SHA256([9; 32]);
+        let hash_fork = calculate_sha256(&block_fork.hashed_bytes());
+
+        bm.download.enqueue(vec![hash_1, hash_fork, hash_2]);
+
+        // Blocks cannot be applied before the first one is received
+        bm.download.receive(hash_2, block_2);
+        bm.download.receive(hash_fork, block_fork);
+        assert!(bm.apply_downloaded_blocks().is_empty());
+
+        bm.download.receive(hash_1, block_1);
+        assert_eq!(bm.apply_downloaded_blocks(), vec![hash_1, hash_2]);
+        assert!(bm.download.is_empty());
+        assert!(bm.blocks.contains_key(&hash_2));
+        assert!(!bm.blocks.contains_key(&hash_fork));
+    }
+
+    #[test]
+    fn trusted_checkpoints_contradiction() {
+        let trusted_checkpoints = vec![
+            TrustedCheckpoint {
+                checkpoint: 10,
+                hash: Hash::SHA256([1; 32]),
+            },
+            TrustedCheckpoint {
+                checkpoint: 20,
+                hash: Hash::SHA256([2; 32]),
+            },
+        ];
+        let contradicts = |tip, checkpoint, byte| {
+            contradicts_trusted_checkpoints(
+                &trusted_checkpoints,
+                tip,
+                checkpoint,
+                Hash::SHA256([byte; 32]),
+            )
+        };
+
+        // Blocks matching the trusted checkpoints or between them
+        assert!(!contradicts(9, 10, 1));
+        assert!(!contradicts(10, 15, 7));
+        assert!(!contradicts(20, 25, 7));
+
+        // Blocks with a different hash than the trusted checkpoint
+        assert!(contradicts(9, 10, 7));
+        // Blocks skipping a trusted checkpoint
+        assert!(contradicts(9, 11, 7));
+        assert!(contradicts(15, 21, 7));
+    }
+
+    #[test]
+    fn trusted_checkpoints_vouch() {
+        let trusted_checkpoints = vec![TrustedCheckpoint {
+            checkpoint: 10,
+            hash: Hash::SHA256([1; 32]),
+        }];
+
+        assert!(below_last_trusted_checkpoint(&trusted_checkpoints, 1));
+        assert!(below_last_trusted_checkpoint(&trusted_checkpoints, 10));
+        assert!(!below_last_trusted_checkpoint(&trusted_checkpoints, 11));
+        assert!(!below_last_trusted_checkpoint(&[], 1));
+    }
+
+    #[test]
+    fn finality_peer_beacon() {
+        let genesis_hash = Hash::SHA256([0; 32]);
+        let block_index: BlockIndex = vec![(1, Hash::SHA256([1; 32])), (3, Hash::SHA256([3; 32]))]
+            .into_iter()
+            .collect();
+        let violates = |checkpoint, byte| {
+            beacon_violates_finality(
+                &block_index,
+                genesis_hash,
+                final_checkpoint(13, 10),
+                &CheckpointBeacon {
+                    checkpoint,
+                    hash_prev_block: Hash::SHA256([byte; 32]),
+                },
+            )
+        };
+
+        // Peers behind the local chain, on the same chain
+        assert!(!violates(0, 0));
+        assert!(!violates(1, 1));
+        assert!(!violates(3, 3));
+        // Peers whose tip is still within the finality window
+        assert!(!violates(4, 7));
+        assert!(!violates(20, 7));
+
+        // Peers with a different genesis block or a different final block
+        assert!(violates(0, 7));
+        assert!(violates(1, 7));
+        // Peers with a block for an epoch which is final and empty in the local chain
+        assert!(violates(2, 7));
+    }
+
+    #[test]
+    fn finality_block() {
+        let genesis_hash = Hash::SHA256([0; 32]);
+        let block_index: BlockIndex = vec![
+            (1, Hash::SHA256([1; 32])),
+            (3, Hash::SHA256([3; 32])),
+            (5, Hash::SHA256([5; 32])),
+        ]
+        .into_iter()
+        .collect();
+        let violates = |max_reorg_depth, byte| {
+            block_violates_finality(
+                &block_index,
+                genesis_hash,
+                final_checkpoint(5, max_reorg_depth),
+                Hash::SHA256([byte; 32]),
+            )
+        };
+
+        // Blocks extending the tip or an unknown block
+        assert!(!violates(0, 5));
+        assert!(!violates(0, 9));
+        // Blocks rolling back blocks within the finality window
+        assert!(!violates(2, 3));
+        assert!(!violates(4, 1));
+        assert!(!violates(5, 0));
+
+        // Blocks rolling back final blocks
+        assert!(violates(0, 3));
+        assert!(violates(2, 1));
+        assert!(violates(4, 0));
+    }
+
+    #[test]
+    fn supply_info_from_block_index() {
+        let consensus_constants = ConsensusConstants {
+            checkpoint_zero_timestamp: 0,
+            checkpoints_period: 90,
+            genesis_hash: Hash::SHA256([0; 32]),
+            reputation_demurrage: 0.0,
+            reputation_punishment: 0.0,
+            trusted_checkpoints: vec![],
+            initial_block_reward: 100,
+            halving_period: 10,
+            reward_maturity: 3,
+            genesis_allocation: vec![],
+        };
+        // Epochs 3 and 11 are empty
+        let block_index: BlockIndex = [1, 2, 4, 9, 10, 12]
+            .iter()
+            .map(|&checkpoint| (checkpoint, Hash::SHA256([checkpoint as u8; 32])))
+            .collect();
+
+        let supply_info = calculate_supply_info(&block_index, 12, &consensus_constants);
+        assert_eq!(supply_info.epoch, 12);
+        assert_eq!(supply_info.issued, 4 * 100 + 2 * 50);
+        // Only the rewards of the epochs 10 and 12 have not matured yet
+        assert_eq!(supply_info.locked, 2 * 50);
+        assert_eq!(supply_info.total, 1870);
+        assert_eq!(supply_info.remaining, 1870 - 1050);
+        assert_eq!(
+            supply_info.emission_curve[0],
+            emission::EmissionPeriod {
+                start: 13,
+                end: 19,
+                reward: 50
+            }
+        );
+    }
+
+    #[test]
+    fn superblocks_summarize_consolidated_blocks() {
+        let mut block_index = BlockIndex::new();
+        block_index.insert(3, Hash::SHA256([3; 32]));
+        block_index.insert(7, Hash::SHA256([7; 32]));
+        block_index.insert(SUPERBLOCK_PERIOD, Hash::SHA256([10; 32]));
+        let utxo_root = utxo_set_merkle_root();
+
+        let first = build_superblock(0, &block_index, None, utxo_root);
+        assert_eq!(first.index, 0);
+        assert_eq!(first.hash_prev_superblock, Hash::SHA256([0; 32]));
+        assert_eq!(first.num_blocks, 2);
+        assert_eq!(
+            first.blocks_merkle_root,
+            merkle_root(&[Hash::SHA256([3; 32]), Hash::SHA256([7; 32])])
+        );
+
+        // Superblocks are chained by their hashes
+        let second = build_superblock(1, &block_index, Some(&first), utxo_root);
+        assert_eq!(
+            second.hash_prev_superblock,
+            calculate_sha256(&first.hashed_bytes())
+        );
+        assert_eq!(second.num_blocks, 1);
+        assert_eq!(
+            second.blocks_merkle_root,
+            merkle_root(&[Hash::SHA256([10; 32])])
+        );
+    }
+
+    #[test]
+    fn superblocks_complete_and_consistent() {
+        // The first superblock is complete once the tip reaches its last epoch
+        assert!(!is_superblock_complete(0, SUPERBLOCK_PERIOD - 2));
+        assert!(is_superblock_complete(0, SUPERBLOCK_PERIOD - 1));
+        assert!(!is_superblock_complete(1, SUPERBLOCK_PERIOD));
+        assert!(!is_superblock_complete(
+            u32::max_value(),
+            Epoch::max_value()
+        ));
+
+        let block_index = BlockIndex::new();
+        let utxo_root = utxo_set_merkle_root();
+        let first = build_superblock(0, &block_index, None, utxo_root);
+        let second = build_superblock(1, &block_index, Some(&first), utxo_root);
+
+        // Superblocks beyond the tip are discarded
+        let mut superblocks = vec![first.clone(), second.clone()];
+        assert!(!discard_inconsistent_superblocks(
+            &mut superblocks,
+            2 * SUPERBLOCK_PERIOD
+        ));
+        assert_eq!(superblocks.len(), 2);
+        assert!(discard_inconsistent_superblocks(
+            &mut superblocks,
+            SUPERBLOCK_PERIOD
+        ));
+        assert_eq!(superblocks, vec![first]);
+
+        // Superblocks after a gap are discarded
+        let mut superblocks = vec![second];
+        assert!(discard_inconsistent_superblocks(
+            &mut superblocks,
+            2 * SUPERBLOCK_PERIOD
+        ));
+        assert!(superblocks.is_empty());
+    }
+
+    #[test]
+    fn block_from_the_future() {
+        use witnet_data_structures::chain::{ConsensusConstants, Environment};
+
+        let genesis_hash = Hash::SHA256([4; 32]);
+        let consensus_constants = ConsensusConstants {
+            checkpoint_zero_timestamp: 1000,
+            checkpoints_period: 90,
+            genesis_hash,
+            reputation_demurrage: 0.0,
+            reputation_punishment: 0.0,
+            trusted_checkpoints: vec![],
+            initial_block_reward: 0,
+            halving_period: 1,
+            reward_maturity: 0,
+            genesis_allocation: vec![],
+        };
+
+        // Checkpoint 2 starts at 1180: its blocks are accepted 10 seconds before
+        assert!(!is_from_the_future(1, 1100, &consensus_constants));
+        assert!(is_from_the_future(2, 1169, &consensus_constants));
+        assert!(!is_from_the_future(2, 1170, &consensus_constants));
+        assert!(is_from_the_future(
+            u32::max_value(),
+            1170,
+            &consensus_constants
+        ));
+
+        // Blocks are rejected whatever the current epoch notified by the EpochManager
+        let now = get_timestamp();
+        let mut bm = BlocksManager::default();
+        bm.chain_info = Some(ChainInfo {
+            environment: Environment::Testnet1,
+            consensus_constants: ConsensusConstants {
+                checkpoint_zero_timestamp: now,
+                ..consensus_constants
+            },
+            highest_block_checkpoint: CheckpointBeacon {
+                checkpoint: 0,
+                hash_prev_block: genesis_hash,
+            },
+        });
+        match bm.process_new_block(build_hardcoded_block(5, 1)) {
+            Err(BlocksManagerError::BlockFromTheFuture) => {}
+            x => panic!("{:?}", x),
+        }
+        assert!(bm.process_new_block(build_hardcoded_block(0, 1)).is_ok());
+        assert_eq!(bm.blocks.len(), 1);
+    }
+
     fn build_hardcoded_block(checkpoint: u32, influence: u64) -> Block {
         use witnet_data_structures::chain::*;
         Block {
@@ -387,6 +2908,7 @@ mod tests {
                         hash_prev_block: Hash::SHA256([4; 32]),
                     },
                     hash_merkle_root: Hash::SHA256([3; 32]),
+                    utxo_merkle_root: Hash::SHA256([0; 32]),
                 },
                 proof: LeadershipProof {
                     block_sig: None,
@@ -395,6 +2917,14 @@ mod tests {
             },
             txn_count: 1,
             txns: vec![Transaction],
+            mint: MintTransaction {
+                epoch: checkpoint,
+                output: ValueTransferOutput {
+                    pkh: [0; 20],
+                    value: 0,
+                    time_lock: checkpoint,
+                },
+            },
         }
     }
 }
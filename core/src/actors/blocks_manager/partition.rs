@@ -0,0 +1,186 @@
+//! # Partition detection
+//!
+//! A node cut off from the network keeps running its epochs, but it can
+//! neither receive the blocks of the rest of the network nor get its own
+//! blocks and data request results accepted. The node considers itself
+//! isolated when, for more than `connections.isolation_epochs` epochs:
+//!
+//! * it has had no consolidated outbound session, or
+//! * its local chain has not moved forward while the peers report a
+//!   higher beacon.
+//!
+//! While isolated, the node pauses mining and witnessing and bootstraps
+//! new outbound sessions aggressively. It leaves the isolated state as
+//! soon as neither condition holds.
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_data_structures::chain::Epoch;
+
+/// Reason why the node considers itself isolated from the network
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IsolationReason {
+    /// The node has had no consolidated outbound session
+    NoOutboundSessions,
+    /// No new blocks have arrived while the peers report a higher beacon
+    NoNewBlocks,
+}
+
+/// Change in the isolation of the node
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IsolationChange {
+    /// The node has just become isolated
+    Isolated(IsolationReason),
+    /// The node is no longer isolated
+    Reconnected,
+}
+
+/// Detector of the isolation of the node, updated on every epoch
+#[derive(Clone, Debug, Default)]
+pub struct PartitionDetector {
+    /// Number of epochs after which the node is considered isolated
+    isolation_epochs: Epoch,
+    /// Number of consolidated outbound sessions, as notified by the SessionsManager
+    num_outbound_consolidated: usize,
+    /// Consecutive epochs without consolidated outbound sessions
+    epochs_without_outbound: Epoch,
+    /// Consecutive epochs in which the local chain did not move forward while the peers
+    /// reported a higher beacon
+    epochs_without_blocks: Epoch,
+    /// Tip of the local chain in the last epoch
+    last_tip: Option<Epoch>,
+    /// Reason of the isolation, if the node is isolated
+    isolated: Option<IsolationReason>,
+}
+
+impl PartitionDetector {
+    /// Create a detector which considers the node isolated after `isolation_epochs` epochs
+    pub fn new(isolation_epochs: Epoch) -> Self {
+        PartitionDetector {
+            isolation_epochs,
+            ..PartitionDetector::default()
+        }
+    }
+
+    /// Update the number of consolidated outbound sessions
+    pub fn set_num_outbound_consolidated(&mut self, num_outbound_consolidated: usize) {
+        self.num_outbound_consolidated = num_outbound_consolidated;
+    }
+
+    /// Number of consolidated outbound sessions
+    pub fn num_outbound_consolidated(&self) -> usize {
+        self.num_outbound_consolidated
+    }
+
+    /// Reason of the isolation, if the node is isolated
+    pub fn isolated(&self) -> Option<IsolationReason> {
+        self.isolated
+    }
+
+    /// Update the detector on a new epoch given the tip of the local chain and the highest last
+    /// epoch reported by the peers (which cannot be beyond the current epoch)
+    /// Returns the change in the isolation of the node, if any
+    pub fn on_epoch(
+        &mut self,
+        current_epoch: Epoch,
+        tip: Epoch,
+        peers_last_epoch: Option<Epoch>,
+    ) -> Option<IsolationChange> {
+        if self.num_outbound_consolidated == 0 {
+            self.epochs_without_outbound += 1;
+        } else {
+            self.epochs_without_outbound = 0;
+        }
+
+        let tip_moved = self.last_tip.map_or(true, |last_tip| tip > last_tip);
+        let peers_ahead = peers_last_epoch.map_or(false, |peers_last_epoch| {
+            peers_last_epoch.min(current_epoch) > tip
+        });
+        if tip_moved || !peers_ahead {
+            self.epochs_without_blocks = 0;
+        } else {
+            self.epochs_without_blocks += 1;
+        }
+        self.last_tip = Some(tip);
+
+        let isolated = if self.epochs_without_outbound > self.isolation_epochs {
+            Some(IsolationReason::NoOutboundSessions)
+        } else if self.epochs_without_blocks > self.isolation_epochs {
+            Some(IsolationReason::NoNewBlocks)
+        } else {
+            None
+        };
+
+        let change = match (self.isolated, isolated) {
+            (None, Some(reason)) => Some(IsolationChange::Isolated(reason)),
+            (Some(_), None) => Some(IsolationChange::Reconnected),
+            _ => None,
+        };
+        self.isolated = isolated;
+
+        change
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isolated_without_outbound_sessions() {
+        let mut detector = PartitionDetector::new(2);
+        assert_eq!(detector.on_epoch(1, 1, None), None);
+        assert_eq!(detector.on_epoch(2, 2, None), None);
+        assert_eq!(
+            detector.on_epoch(3, 3, None),
+            Some(IsolationChange::Isolated(
+                IsolationReason::NoOutboundSessions
+            ))
+        );
+        assert_eq!(detector.on_epoch(4, 4, None), None);
+        assert_eq!(
+            detector.isolated(),
+            Some(IsolationReason::NoOutboundSessions)
+        );
+
+        detector.set_num_outbound_consolidated(1);
+        assert_eq!(
+            detector.on_epoch(5, 5, None),
+            Some(IsolationChange::Reconnected)
+        );
+        assert_eq!(detector.isolated(), None);
+    }
+
+    #[test]
+    fn isolated_without_new_blocks_while_peers_are_ahead() {
+        let mut detector = PartitionDetector::new(2);
+        detector.set_num_outbound_consolidated(8);
+        assert_eq!(detector.on_epoch(10, 5, Some(10)), None);
+
+        // The local chain is stuck at checkpoint 5
+        assert_eq!(detector.on_epoch(11, 5, Some(11)), None);
+        assert_eq!(detector.on_epoch(12, 5, Some(12)), None);
+        assert_eq!(
+            detector.on_epoch(13, 5, Some(13)),
+            Some(IsolationChange::Isolated(IsolationReason::NoNewBlocks))
+        );
+
+        // New blocks arrive again
+        assert_eq!(
+            detector.on_epoch(14, 6, Some(14)),
+            Some(IsolationChange::Reconnected)
+        );
+    }
+
+    #[test]
+    fn not_isolated_when_the_network_is_stalled() {
+        let mut detector = PartitionDetector::new(2);
+        detector.set_num_outbound_consolidated(8);
+
+        // No blocks are being produced, but the peers are not ahead either
+        for epoch in 10..20 {
+            assert_eq!(detector.on_epoch(epoch, 5, Some(5)), None);
+        }
+        assert_eq!(detector.isolated(), None);
+    }
+}
@@ -0,0 +1,132 @@
+//! # Rescan of the consolidated blocks
+//!
+//! The UTXO set kept by the UtxoManager and the transaction history kept
+//! by the WalletManager are derived from the consolidated blocks. A rescan
+//! discards what was derived from the blocks consolidated since an epoch
+//! and replays those blocks through both actors, one at a time and in the
+//! order of the block index, so the derived indexes are rebuilt (e.g. after
+//! restoring the keys of the wallet, or after an index was corrupted).
+use std::collections::VecDeque;
+
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_data_structures::chain::{Epoch, Hash};
+
+/// Progress of a rescan
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RescanStatus {
+    /// First epoch rescanned
+    pub from_epoch: Epoch,
+    /// Checkpoint of the last block replayed, if any
+    pub last_epoch: Option<Epoch>,
+    /// Number of blocks replayed
+    pub replayed: usize,
+    /// Number of blocks skipped, because they could not be read from storage or they were
+    /// reverted during the rescan
+    pub skipped: usize,
+    /// Number of blocks to rescan
+    pub total: usize,
+    /// Whether every block has been replayed or skipped
+    pub finished: bool,
+}
+
+/// Rescan of the consolidated blocks
+#[derive(Debug)]
+pub struct Rescan {
+    /// Progress of the rescan
+    status: RescanStatus,
+    /// Checkpoints and hashes of the blocks not replayed yet, sorted by checkpoint
+    pending: VecDeque<(Epoch, Hash)>,
+}
+
+impl Rescan {
+    /// Start a rescan of some blocks, sorted by checkpoint
+    pub fn new(from_epoch: Epoch, blocks: Vec<(Epoch, Hash)>) -> Self {
+        Rescan {
+            status: RescanStatus {
+                from_epoch,
+                total: blocks.len(),
+                ..RescanStatus::default()
+            },
+            pending: blocks.into_iter().collect(),
+        }
+    }
+
+    /// Progress of the rescan
+    pub fn status(&self) -> &RescanStatus {
+        &self.status
+    }
+
+    /// Check whether every block has been replayed or skipped
+    pub fn is_finished(&self) -> bool {
+        self.status.finished
+    }
+
+    /// Take the next block to replay, or mark the rescan as finished if there are no more blocks
+    pub fn next_block(&mut self) -> Option<(Epoch, Hash)> {
+        let next = self.pending.pop_front();
+        if next.is_none() {
+            self.status.finished = true;
+        }
+
+        next
+    }
+
+    /// Count a replayed block
+    pub fn block_replayed(&mut self, checkpoint: Epoch) {
+        self.status.replayed += 1;
+        self.status.last_epoch = Some(checkpoint);
+    }
+
+    /// Count a skipped block
+    pub fn block_skipped(&mut self) {
+        self.status.skipped += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rescan_progress() {
+        let blocks = vec![(3, Hash::SHA256([3; 32])), (5, Hash::SHA256([5; 32]))];
+        let mut rescan = Rescan::new(2, blocks);
+        assert_eq!(
+            rescan.status(),
+            &RescanStatus {
+                from_epoch: 2,
+                total: 2,
+                ..RescanStatus::default()
+            }
+        );
+
+        assert_eq!(rescan.next_block(), Some((3, Hash::SHA256([3; 32]))));
+        rescan.block_skipped();
+        assert_eq!(rescan.next_block(), Some((5, Hash::SHA256([5; 32]))));
+        rescan.block_replayed(5);
+        assert!(!rescan.is_finished());
+
+        assert_eq!(rescan.next_block(), None);
+        assert!(rescan.is_finished());
+        assert_eq!(
+            rescan.status(),
+            &RescanStatus {
+                from_epoch: 2,
+                last_epoch: Some(5),
+                replayed: 1,
+                skipped: 1,
+                total: 2,
+                finished: true,
+            }
+        );
+    }
+
+    #[test]
+    fn rescan_without_blocks() {
+        let mut rescan = Rescan::new(10, vec![]);
+        assert_eq!(rescan.next_block(), None);
+        assert!(rescan.is_finished());
+        assert_eq!(rescan.status().total, 0);
+    }
+}
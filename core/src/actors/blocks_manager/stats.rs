@@ -0,0 +1,283 @@
+//! # Chain statistics
+//!
+//! The outcome of every epoch observed by this node (whether a block was
+//! consolidated for it and how many block candidates were received) is
+//! recorded once the candidates for the epoch are no longer accepted.
+//! The outcomes of the last `CHAIN_STATS_CAPACITY` epochs are kept in a
+//! ring buffer persisted into storage, so the liveness of the network can
+//! be measured over time across restarts of the node.
+use std::collections::{BTreeMap, VecDeque};
+
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_data_structures::chain::Epoch;
+
+/// Number of epochs whose outcome is kept
+pub const CHAIN_STATS_CAPACITY: usize = 10_000;
+
+/// Maximum number of epochs returned by a single request of chain statistics
+pub const MAX_CHAIN_STATS_RANGE: Epoch = 1_000;
+
+/// Outcome of an epoch
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EpochOutcome {
+    /// Epoch
+    pub epoch: Epoch,
+    /// Number of block candidates received for the epoch
+    pub candidates: u16,
+    /// Whether a block was consolidated for the epoch (false if the epoch was empty)
+    pub consolidated: bool,
+    /// Whether the node was synced when the epoch was recorded (the outcomes recorded while
+    /// syncing do not take into account the blocks downloaded afterwards), and was not paused
+    /// during the epoch
+    pub synced: bool,
+}
+
+/// Outcomes of the most recent epochs, sorted by epoch
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EpochOutcomes {
+    /// Outcomes of the last `CHAIN_STATS_CAPACITY` recorded epochs
+    outcomes: VecDeque<EpochOutcome>,
+    /// Number of block candidates received for the epochs not recorded yet
+    #[serde(skip)]
+    candidates: BTreeMap<Epoch, u16>,
+    /// Ranges of epochs not recorded yet which were skipped by the node (the process was paused
+    /// or the event loop was busy)
+    #[serde(skip)]
+    missed: Vec<(Epoch, Epoch)>,
+}
+
+impl EpochOutcomes {
+    /// Count a block candidate received for an epoch
+    pub fn candidate_received(&mut self, epoch: Epoch) {
+        if self.next_epoch().map_or(true, |next| epoch >= next) {
+            let candidates = self.candidates.entry(epoch).or_insert(0);
+            *candidates = candidates.saturating_add(1);
+        }
+    }
+
+    /// Mark the epochs in the range `[first, last]` as skipped by the node, so they are not
+    /// recorded as synced
+    pub fn epochs_missed(&mut self, first: Epoch, last: Epoch) {
+        if self.next_epoch().map_or(true, |next| last >= next) {
+            self.missed.push((first, last));
+        }
+    }
+
+    /// Get the first epoch which has not been recorded yet, if any epoch has been recorded
+    pub fn next_epoch(&self) -> Option<Epoch> {
+        self.outcomes.back().map(|outcome| outcome.epoch + 1)
+    }
+
+    /// Record the outcome of the epochs up to `last_epoch` which have not been recorded yet
+    /// (only `last_epoch` if no epoch has been recorded before), using `is_consolidated` to
+    /// check whether a block was consolidated for each one of them
+    /// Returns the number of epochs recorded
+    pub fn record<F>(&mut self, last_epoch: Epoch, synced: bool, is_consolidated: F) -> usize
+    where
+        F: Fn(Epoch) -> bool,
+    {
+        let first_epoch = match self.next_epoch() {
+            Some(next) if next > last_epoch => return 0,
+            // Older epochs are going to be dropped from the ring buffer anyway
+            Some(next) => std::cmp::max(
+                next,
+                last_epoch.saturating_sub(CHAIN_STATS_CAPACITY as Epoch - 1),
+            ),
+            None => last_epoch,
+        };
+
+        // The candidates received for the recorded epochs are no longer needed
+        let pending = self.candidates.split_off(&(last_epoch + 1));
+        let mut candidates = std::mem::replace(&mut self.candidates, pending);
+        for epoch in first_epoch..=last_epoch {
+            let missed = self
+                .missed
+                .iter()
+                .any(|&(first, last)| epoch >= first && epoch <= last);
+            self.outcomes.push_back(EpochOutcome {
+                epoch,
+                candidates: candidates.remove(&epoch).unwrap_or(0),
+                consolidated: is_consolidated(epoch),
+                synced: synced && !missed,
+            });
+        }
+        self.missed.retain(|&(_, last)| last > last_epoch);
+        while self.outcomes.len() > CHAIN_STATS_CAPACITY {
+            self.outcomes.pop_front();
+        }
+
+        (last_epoch - first_epoch + 1) as usize
+    }
+
+    /// Get the outcomes recorded for the epochs in the range `[from, to]`
+    pub fn range(&self, from: Epoch, to: Epoch) -> Vec<EpochOutcome> {
+        self.outcomes
+            .iter()
+            .filter(|outcome| outcome.epoch >= from && outcome.epoch <= to)
+            .cloned()
+            .collect()
+    }
+
+    /// Get the number of recorded epochs
+    pub fn len(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Check if no epoch has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.outcomes.is_empty()
+    }
+}
+
+/// Statistics of the outcomes of a range of epochs
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainStats {
+    /// Number of recorded epochs in the range
+    pub epochs: u32,
+    /// Number of epochs for which a block was consolidated
+    pub consolidated: u32,
+    /// Number of epochs without a consolidated block
+    pub empty: u32,
+    /// Number of epochs recorded while the node was not synced
+    pub unsynced: u32,
+    /// Average number of block candidates received per epoch
+    pub avg_candidates: f64,
+    /// Outcome of every recorded epoch in the range
+    pub outcomes: Vec<EpochOutcome>,
+}
+
+impl ChainStats {
+    /// Summarize the outcomes of some epochs
+    pub fn from_outcomes(outcomes: Vec<EpochOutcome>) -> Self {
+        let epochs = outcomes.len() as u32;
+        let consolidated = outcomes.iter().filter(|o| o.consolidated).count() as u32;
+        let unsynced = outcomes.iter().filter(|o| !o.synced).count() as u32;
+        let candidates: u64 = outcomes.iter().map(|o| u64::from(o.candidates)).sum();
+        let avg_candidates = if epochs == 0 {
+            0.0
+        } else {
+            candidates as f64 / f64::from(epochs)
+        };
+
+        ChainStats {
+            epochs,
+            consolidated,
+            empty: epochs - consolidated,
+            unsynced,
+            avg_candidates,
+            outcomes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_first_epoch() {
+        let mut outcomes = EpochOutcomes::default();
+        outcomes.candidate_received(5);
+        outcomes.candidate_received(5);
+        outcomes.candidate_received(6);
+
+        // Only the last epoch is recorded the first time
+        assert_eq!(outcomes.record(5, true, |_| true), 1);
+        assert_eq!(
+            outcomes.range(0, 10),
+            vec![EpochOutcome {
+                epoch: 5,
+                candidates: 2,
+                consolidated: true,
+                synced: true,
+            }]
+        );
+
+        // The candidates of later epochs are kept until they are recorded
+        assert_eq!(outcomes.record(5, true, |_| true), 0);
+        assert_eq!(outcomes.record(7, false, |epoch| epoch != 7), 2);
+        assert_eq!(
+            outcomes.range(6, 7),
+            vec![
+                EpochOutcome {
+                    epoch: 6,
+                    candidates: 1,
+                    consolidated: true,
+                    synced: false,
+                },
+                EpochOutcome {
+                    epoch: 7,
+                    candidates: 0,
+                    consolidated: false,
+                    synced: false,
+                },
+            ]
+        );
+
+        // Candidates for epochs already recorded are ignored
+        outcomes.candidate_received(7);
+        assert_eq!(outcomes.record(8, true, |_| false), 1);
+        assert_eq!(outcomes.range(7, 7)[0].candidates, 0);
+    }
+
+    #[test]
+    fn missed_epochs_are_not_synced() {
+        let mut outcomes = EpochOutcomes::default();
+        outcomes.record(0, true, |_| true);
+        outcomes.epochs_missed(2, 3);
+
+        assert_eq!(outcomes.record(4, true, |_| true), 4);
+        let synced: Vec<_> = outcomes
+            .range(1, 4)
+            .iter()
+            .map(|outcome| outcome.synced)
+            .collect();
+        assert_eq!(synced, vec![true, false, false, true]);
+
+        // The missed epochs are forgotten once recorded
+        assert_eq!(outcomes.record(6, true, |_| true), 2);
+        assert!(outcomes.range(5, 6).iter().all(|outcome| outcome.synced));
+    }
+
+    #[test]
+    fn ring_buffer_is_bounded() {
+        let mut outcomes = EpochOutcomes::default();
+        outcomes.record(0, true, |_| true);
+        let last_epoch = 3 * CHAIN_STATS_CAPACITY as Epoch;
+        assert_eq!(
+            outcomes.record(last_epoch, true, |_| true),
+            CHAIN_STATS_CAPACITY
+        );
+
+        assert_eq!(outcomes.len(), CHAIN_STATS_CAPACITY);
+        assert!(outcomes
+            .range(0, last_epoch - CHAIN_STATS_CAPACITY as Epoch)
+            .is_empty());
+        assert_eq!(outcomes.next_epoch(), Some(last_epoch + 1));
+    }
+
+    #[test]
+    fn chain_stats_summary() {
+        let outcome = |epoch, candidates, consolidated, synced| EpochOutcome {
+            epoch,
+            candidates,
+            consolidated,
+            synced,
+        };
+        let stats = ChainStats::from_outcomes(vec![
+            outcome(1, 3, true, true),
+            outcome(2, 0, false, true),
+            outcome(3, 1, true, false),
+            outcome(4, 0, false, true),
+        ]);
+
+        assert_eq!(stats.epochs, 4);
+        assert_eq!(stats.consolidated, 2);
+        assert_eq!(stats.empty, 2);
+        assert_eq!(stats.unsynced, 1);
+        assert!((stats.avg_candidates - 1.0).abs() < std::f64::EPSILON);
+
+        assert_eq!(ChainStats::from_outcomes(vec![]), ChainStats::default());
+    }
+}
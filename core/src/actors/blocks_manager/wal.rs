@@ -0,0 +1,163 @@
+//! # Write-ahead log of the chain state
+//!
+//! Consolidating blocks mutates several values of the chain state, which
+//! are persisted one after the other: the blocks themselves, the block
+//! index (where blocks indexed before are replaced when the chain is
+//! reorganized) and the tip of the chain info. The UTXO set and the
+//! transaction history of the wallet are derived from them afterwards.
+//!
+//! Every mutation is recorded in the write-ahead log before it is applied,
+//! and the log is cleared once the mutation has been persisted. When the
+//! node starts with a mutation in the log (because it crashed in the middle
+//! of it), the block index decides its outcome: if the block index was
+//! persisted the mutation is replayed, otherwise it is rolled back. In both
+//! cases the derived state is rebuilt by rescanning the affected blocks.
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_data_structures::chain::{CheckpointBeacon, Epoch, Hash};
+
+use super::BlockIndex;
+
+/// Mutation of the chain state recorded in the write-ahead log
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ChainMutation {
+    /// Tip of the local chain before the mutation
+    pub previous_tip: CheckpointBeacon,
+    /// Tip of the local chain after the mutation
+    pub tip: CheckpointBeacon,
+    /// Checkpoints and hashes of the blocks indexed by the mutation
+    pub indexed: Vec<(Epoch, Hash)>,
+    /// Checkpoints and hashes of the blocks replaced in the block index by the mutation
+    pub replaced: Vec<(Epoch, Hash)>,
+}
+
+/// Outcome of the recovery of an interrupted mutation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Recovery {
+    /// The block index had been persisted, so the mutation was completed
+    Replayed,
+    /// The block index had not been persisted, so the mutation was undone
+    RolledBack,
+}
+
+impl ChainMutation {
+    /// First checkpoint affected by the mutation, if any
+    pub fn first_checkpoint(&self) -> Option<Epoch> {
+        self.indexed
+            .iter()
+            .chain(&self.replaced)
+            .map(|&(checkpoint, _)| checkpoint)
+            .min()
+    }
+
+    /// Check whether the block index includes every block indexed by the mutation
+    pub fn is_indexed(&self, block_index: &BlockIndex) -> bool {
+        self.indexed
+            .iter()
+            .all(|(checkpoint, hash)| block_index.get(checkpoint) == Some(hash))
+    }
+
+    /// Recover the tip and the block index read from storage after the mutation was interrupted,
+    /// replaying the mutation if the block index was persisted or rolling it back otherwise
+    pub fn recover(&self, tip: &mut CheckpointBeacon, block_index: &mut BlockIndex) -> Recovery {
+        if self.is_indexed(block_index) {
+            *tip = self.tip;
+
+            Recovery::Replayed
+        } else {
+            for (checkpoint, hash) in &self.indexed {
+                if block_index.get(checkpoint) == Some(hash) {
+                    block_index.remove(checkpoint);
+                }
+            }
+            for &(checkpoint, hash) in &self.replaced {
+                block_index.insert(checkpoint, hash);
+            }
+            *tip = self.previous_tip;
+
+            Recovery::RolledBack
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn beacon(checkpoint: Epoch, hash: Hash) -> CheckpointBeacon {
+        CheckpointBeacon {
+            checkpoint,
+            hash_prev_block: hash,
+        }
+    }
+
+    /// Reorganization replacing the block of checkpoint 2 and extending the chain up to 3
+    fn reorg() -> ChainMutation {
+        ChainMutation {
+            previous_tip: beacon(2, Hash::SHA256([2; 32])),
+            tip: beacon(3, Hash::SHA256([3; 32])),
+            indexed: vec![(2, Hash::SHA256([22; 32])), (3, Hash::SHA256([3; 32]))],
+            replaced: vec![(2, Hash::SHA256([2; 32]))],
+        }
+    }
+
+    fn index_before() -> BlockIndex {
+        vec![(1, Hash::SHA256([1; 32])), (2, Hash::SHA256([2; 32]))]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn replay_when_block_index_was_persisted() {
+        let mutation = reorg();
+        let mut block_index = index_before();
+        block_index.extend(mutation.indexed.iter().cloned());
+        let expected_index = block_index.clone();
+        let mut tip = mutation.previous_tip;
+
+        assert_eq!(
+            mutation.recover(&mut tip, &mut block_index),
+            Recovery::Replayed
+        );
+        assert_eq!(tip, mutation.tip);
+        assert_eq!(block_index, expected_index);
+    }
+
+    #[test]
+    fn roll_back_when_block_index_was_not_persisted() {
+        let mutation = reorg();
+        let mut block_index = index_before();
+        let mut tip = mutation.previous_tip;
+
+        assert_eq!(
+            mutation.recover(&mut tip, &mut block_index),
+            Recovery::RolledBack
+        );
+        assert_eq!(tip, mutation.previous_tip);
+        assert_eq!(block_index, index_before());
+    }
+
+    #[test]
+    fn roll_back_discards_partially_indexed_blocks() {
+        let mutation = reorg();
+        let mut block_index = index_before();
+        block_index.insert(2, Hash::SHA256([22; 32]));
+        let mut tip = beacon(2, Hash::SHA256([22; 32]));
+
+        assert_eq!(
+            mutation.recover(&mut tip, &mut block_index),
+            Recovery::RolledBack
+        );
+        assert_eq!(tip, mutation.previous_tip);
+        assert_eq!(block_index, index_before());
+    }
+
+    #[test]
+    fn first_checkpoint_includes_replaced_blocks() {
+        let mut mutation = reorg();
+        assert_eq!(mutation.first_checkpoint(), Some(2));
+
+        mutation.replaced.push((1, Hash::SHA256([1; 32])));
+        assert_eq!(mutation.first_checkpoint(), Some(1));
+    }
+}
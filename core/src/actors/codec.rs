@@ -10,6 +10,15 @@ use tokio::codec::{Decoder, Encoder};
 
 const HEADER_SIZE: usize = 2; // bytes
 
+/// Flag of the frames containing an uncompressed message
+pub const FRAME_UNCOMPRESSED: u8 = 0;
+
+/// Flag of the frames containing a message compressed with Snappy
+pub const FRAME_SNAPPY: u8 = 1;
+
+/// Maximum length of a decompressed message, to reject decompression bombs
+pub const MAX_DECOMPRESSED_LENGTH: usize = 32 * 1024 * 1024;
+
 /// Type alias for BytesMut
 pub type BytesMut = bytes::BytesMut;
 
@@ -46,7 +55,7 @@ impl Decoder for P2PCodec {
         let msg_len = src.len();
         if msg_len >= HEADER_SIZE {
             let mut header_vec = Cursor::new(&src[0..HEADER_SIZE]);
-            let msg_size = header_vec.read_u16::<BigEndian>().unwrap() as usize;
+            let msg_size = header_vec.read_u16::<BigEndian>()? as usize;
             if msg_len >= msg_size + HEADER_SIZE {
                 src.split_to(HEADER_SIZE);
                 ftb = Some(src.split_to(msg_size));
@@ -88,3 +97,100 @@ impl Encoder for P2PCodec {
         Ok(())
     }
 }
+
+/// Compress a message with Snappy if it is at least `threshold` bytes long and compressing it
+/// actually reduces its size. The message is prepended with a flag telling whether it has been
+/// compressed:
+///
+/// ```norun
+/// Flag: u8 (0 = uncompressed, 1 = Snappy)
+/// Message: [u8; Frame size - 1]
+/// ```
+pub fn compress_message(message: Vec<u8>, threshold: usize) -> io::Result<Vec<u8>> {
+    if message.len() >= threshold {
+        let compressed = snap::Encoder::new()
+            .compress_vec(&message)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        if compressed.len() < message.len() {
+            let mut frame = Vec::with_capacity(1 + compressed.len());
+            frame.push(FRAME_SNAPPY);
+            frame.extend(compressed);
+
+            return Ok(frame);
+        }
+    }
+
+    let mut frame = Vec::with_capacity(1 + message.len());
+    frame.push(FRAME_UNCOMPRESSED);
+    frame.extend(message);
+
+    Ok(frame)
+}
+
+/// Decompress a message compressed with `compress_message`
+pub fn decompress_message(frame: &[u8]) -> io::Result<Vec<u8>> {
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+    match frame.split_first() {
+        Some((&FRAME_UNCOMPRESSED, message)) => Ok(message.to_vec()),
+        Some((&FRAME_SNAPPY, compressed)) => {
+            let len = snap::decompress_len(compressed).map_err(|e| invalid(e.to_string()))?;
+            if len > MAX_DECOMPRESSED_LENGTH {
+                return Err(invalid(format!(
+                    "Decompressed message size {} bytes too big",
+                    len
+                )));
+            }
+
+            snap::Decoder::new()
+                .decompress_vec(compressed)
+                .map_err(|e| invalid(e.to_string()))
+        }
+        Some((flag, _)) => Err(invalid(format!("Unknown compression flag {}", flag))),
+        None => Err(invalid("Empty frame".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn compress_message_above_threshold() {
+        let message = vec![7; 4096];
+        let frame = compress_message(message.clone(), 1024).unwrap();
+
+        assert_eq!(frame[0], FRAME_SNAPPY);
+        assert!(frame.len() < message.len());
+        assert_eq!(decompress_message(&frame).unwrap(), message);
+    }
+
+    #[test]
+    fn compress_message_below_threshold() {
+        let message = vec![7; 512];
+        let frame = compress_message(message.clone(), 1024).unwrap();
+
+        assert_eq!(frame[0], FRAME_UNCOMPRESSED);
+        assert_eq!(&frame[1..], &message[..]);
+        assert_eq!(decompress_message(&frame).unwrap(), message);
+    }
+
+    #[test]
+    fn compress_message_incompressible() {
+        // Messages which would grow when compressed are sent uncompressed
+        let mut message = vec![0; 2048];
+        thread_rng().fill(&mut message[..]);
+        let frame = compress_message(message.clone(), 0).unwrap();
+
+        assert_eq!(frame[0], FRAME_UNCOMPRESSED);
+        assert_eq!(decompress_message(&frame).unwrap(), message);
+    }
+
+    #[test]
+    fn decompress_message_invalid() {
+        assert!(decompress_message(&[]).is_err());
+        assert!(decompress_message(&[2, 0, 0]).is_err());
+        assert!(decompress_message(&[FRAME_SNAPPY, 0xff, 0xff, 0xff]).is_err());
+    }
+}
@@ -1,21 +1,18 @@
 use super::ConfigManager;
-use actix::{Actor, Context};
-use log::{debug, info};
-use std::sync::Arc;
-use witnet_config::config::Config;
-use witnet_config::loaders::toml;
+use actix::{Actor, Context, System};
+use log::{debug, error};
 
 impl Actor for ConfigManager {
     type Context = Context<Self>;
 
     fn started(&mut self, _ctx: &mut Self::Context) {
         debug!("Config Manager actor has been started!");
-        info!(
-            "Reading configuration from file: {}",
-            self.config_file.to_string_lossy()
-        );
-        self.config = Arc::new(Config::from_partial(
-            &toml::from_file(&self.config_file).unwrap(),
-        ))
+        // The configuration is usually loaded before starting the node (see `node::run`)
+        if !self.loaded {
+            if let Err(e) = self.load() {
+                error!("{}", e);
+                System::current().stop_with_code(1);
+            }
+        }
     }
 }
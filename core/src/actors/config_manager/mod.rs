@@ -3,10 +3,18 @@ use actix::{
     Supervised, System, SystemService, WrapFuture,
 };
 
-use log::error;
+use log::{error, info};
+use std::fmt;
 use std::path::PathBuf;
 use std::sync::Arc;
-use witnet_config::config::Config;
+use witnet_config::{
+    config::Config,
+    dirs,
+    loaders::toml,
+    validation::{self, Problem},
+};
+use witnet_crypto::hash::calculate_sha256;
+use witnet_data_structures::chain::{ConsensusConstants, Hash};
 
 // Internal Actor implementation for ConfigManager
 mod actor;
@@ -18,7 +26,7 @@ mod handlers;
 pub mod messages;
 
 /// Default configuration filename
-pub const CONFIG_DEFAULT_FILENAME: &str = "witnet.toml";
+pub const CONFIG_DEFAULT_FILENAME: &str = dirs::CONFIG_FILE_NAME;
 
 /// Config manager actor: manages the application configuration
 ///
@@ -31,31 +39,111 @@ pub struct ConfigManager {
     config: Arc<Config>,
 
     /// Configuration file from which to read the configuration when
-    /// the actor starts
-    config_file: PathBuf,
+    /// the actor starts, if any
+    config_file: Option<PathBuf>,
+
+    /// Whether the configuration has already been loaded
+    loaded: bool,
+}
+
+/// Error preventing the node from starting with the configuration
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The configuration file could not be read or parsed
+    Load(PathBuf, String),
+    /// The configuration is not consistent
+    Invalid(Vec<Problem>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Load(file, e) => write!(
+                f,
+                "The configuration file {} could not be loaded: {}",
+                file.display(),
+                e
+            ),
+            ConfigError::Invalid(problems) => {
+                write!(f, "The configuration is not valid:")?;
+                for problem in problems {
+                    write!(f, "\n  * {}", problem)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
 }
 
 impl Default for ConfigManager {
     fn default() -> Self {
-        Self {
-            config: Arc::new(Config::default()),
-            config_file: PathBuf::from(CONFIG_DEFAULT_FILENAME),
-        }
+        Self::new(None)
     }
 }
 
 impl ConfigManager {
     /// Create a new ConfigManager instance that will try to read the
-    /// given configuration file name.
+    /// given configuration file name, or else the default one (see
+    /// `find_config_file`).
     pub fn new(config_file: Option<PathBuf>) -> Self {
         Self {
             config: Arc::new(Config::default()),
-            config_file: match config_file {
-                Some(path) => path,
-                None => PathBuf::from(CONFIG_DEFAULT_FILENAME),
-            },
+            config_file: config_file.or_else(find_config_file),
+            loaded: false,
         }
     }
+
+    /// Read the configuration file, if any, and validate the
+    /// resulting configuration, so that the node can refuse to start
+    /// before any other actor uses an invalid configuration
+    pub fn load(&mut self) -> Result<(), ConfigError> {
+        let config = match &self.config_file {
+            Some(config_file) => {
+                info!(
+                    "Reading configuration from file: {}",
+                    config_file.to_string_lossy()
+                );
+                let partial = toml::from_file(config_file)
+                    .map_err(|e| ConfigError::Load(config_file.clone(), e.to_string()))?;
+                Config::from_partial(&partial)
+            }
+            None => {
+                info!("No configuration file found, using the default configuration");
+                Config::default()
+            }
+        };
+        validation::validate(&config).map_err(ConfigError::Invalid)?;
+
+        self.config = Arc::new(config);
+        self.loaded = true;
+
+        Ok(())
+    }
+
+    /// Get the configuration (the default one until it is loaded)
+    pub fn config(&self) -> Arc<Config> {
+        Arc::clone(&self.config)
+    }
+}
+
+/// Find the default configuration file: `witnet.toml` in the current
+/// working directory or, if there is none, `witnet.toml` in the
+/// configuration directory of the platform.
+pub fn find_config_file() -> Option<PathBuf> {
+    let local_file = PathBuf::from(CONFIG_DEFAULT_FILENAME);
+    if local_file.is_file() {
+        return Some(local_file);
+    }
+
+    dirs::config_file().filter(|file| file.is_file())
+}
+
+/// Hash of the consensus constants, which identifies the network of the
+/// node: the nodes whose constants differ cannot agree on the same chain,
+/// so it is checked in the handshake with every peer.
+pub fn consensus_constants_hash(consensus_constants: &ConsensusConstants) -> Hash {
+    calculate_sha256(&consensus_constants.hashed_bytes())
 }
 
 /// Required traits for being able to retrieve the actor address from
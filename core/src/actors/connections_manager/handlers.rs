@@ -1,12 +1,18 @@
 use actix::{
     actors::resolver::{ConnectAddr, Resolver},
-    ActorFuture, ContextFutureSpawner, Handler, SystemService, WrapFuture,
+    ActorFuture, ContextFutureSpawner, Handler, System, SystemService, WrapFuture,
+};
+use log::{info, warn};
+
+use crate::actors::{
+    peers_manager::{messages::IsBanned, PeersManager},
+    sessions_manager::{messages::SetExternalAddress, SessionsManager},
 };
 
 use witnet_p2p::sessions::SessionType;
 
 use super::{
-    messages::{InboundTcpConnect, OutboundTcpConnect},
+    messages::{InboundTcpConnect, OutboundTcpConnect, PortMapped},
     ConnectionsManager,
 };
 
@@ -16,9 +22,37 @@ impl Handler<InboundTcpConnect> for ConnectionsManager {
     type Result = ();
 
     /// Method to handle the InboundTcpConnect message
-    fn handle(&mut self, msg: InboundTcpConnect, _ctx: &mut Self::Context) {
-        // Request the creation of a new session actor from connection
-        ConnectionsManager::request_session_creation(msg.stream, SessionType::Inbound);
+    fn handle(&mut self, msg: InboundTcpConnect, ctx: &mut Self::Context) {
+        let address = match msg.stream.peer_addr() {
+            Ok(address) => address,
+            Err(e) => {
+                warn!("Dropping inbound connection without peer address: {}", e);
+                return;
+            }
+        };
+
+        // Refuse the connections of banned peers, dropping the stream
+        System::current()
+            .registry()
+            .get::<PeersManager>()
+            .send(IsBanned { address })
+            .into_actor(self)
+            .then(move |res, _act, _ctx| {
+                match res {
+                    Ok(true) => info!("Refusing inbound connection from banned peer {}", address),
+                    Ok(false) => {
+                        // Request the creation of a new session actor from connection
+                        ConnectionsManager::request_session_creation(
+                            msg.stream,
+                            SessionType::Inbound,
+                        )
+                    }
+                    Err(e) => warn!("Unsuccessful communication with peers manager: {}", e),
+                }
+
+                actix::fut::ok(())
+            })
+            .wait(ctx);
     }
 }
 
@@ -33,7 +67,34 @@ impl Handler<OutboundTcpConnect> for ConnectionsManager {
         Resolver::from_registry()
             .send(ConnectAddr(msg.address))
             .into_actor(self)
-            .then(|res, _act, _ctx| ConnectionsManager::process_connect_addr_response(res))
+            .then(move |res, _act, _ctx| {
+                ConnectionsManager::process_connect_addr_response(msg.address, res)
+            })
             .wait(ctx);
     }
 }
+
+/// Handler for PortMapped messages (sent when a port mapping attempt finishes)
+impl Handler<PortMapped> for ConnectionsManager {
+    /// Response for message, which is defined by `ResponseType` trait
+    type Result = ();
+
+    /// Method to handle the PortMapped message
+    fn handle(&mut self, msg: PortMapped, _ctx: &mut Self::Context) {
+        match msg.result {
+            Ok(address) => {
+                if self.external_address != Some(address) {
+                    info!("Server port mapped, external address is {}", address);
+                    self.external_address = Some(address);
+
+                    // Advertise the external address instead of the server address
+                    System::current()
+                        .registry()
+                        .get::<SessionsManager>()
+                        .do_send(SetExternalAddress { address });
+                }
+            }
+            Err(e) => warn!("Server port could not be mapped: {}", e),
+        }
+    }
+}
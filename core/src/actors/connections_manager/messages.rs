@@ -24,5 +24,13 @@ pub struct OutboundTcpConnect {
     pub address: SocketAddr,
 }
 
+/// Actor message holding the outcome of a port mapping attempt
+#[derive(Message)]
+pub struct PortMapped {
+    /// External address under which the server is reachable, or the reason why the port could
+    /// not be mapped
+    pub result: Result<SocketAddr, String>,
+}
+
 /// Returned type by the Resolver actor for the ConnectAddr message
 pub type ResolverResult = Result<TcpStream, ResolverError>;
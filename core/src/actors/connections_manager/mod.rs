@@ -1,24 +1,43 @@
-use actix::{fut::FutureResult, Actor, AsyncContext, MailboxError, System, SystemService};
+use actix::{
+    actors::resolver::ResolverError, fut::FutureResult, Actor, AsyncContext, MailboxError, System,
+    SystemService,
+};
 use futures::Stream;
 use log::{debug, error, info, warn};
-use tokio::net::{TcpListener, TcpStream};
+use net2::TcpBuilder;
+use std::{
+    io,
+    net::{SocketAddr, SocketAddrV4},
+    thread,
+    time::Duration,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    reactor::Handle,
+};
 
 use crate::actors::{
     config_manager::send_get_config_request,
+    peers_manager::{messages::RecordConnectionAttempt, PeersManager},
     sessions_manager::{messages::Create, SessionsManager},
 };
 
 use witnet_config::config::Config;
-use witnet_p2p::sessions::SessionType;
+use witnet_p2p::{peers::AttemptOutcome, sessions::SessionType};
 
 mod actor;
 mod handlers;
 /// Messages to hold the TCP stream from an inbound TCP connection
 pub mod messages;
+/// Automated opening of the server port in the router (UPnP or NAT-PMP)
+pub mod port_mapping;
 
 /// Connections manager actor
 #[derive(Default)]
-pub struct ConnectionsManager;
+pub struct ConnectionsManager {
+    /// External address of the server, learnt through port mapping
+    external_address: Option<SocketAddr>,
+}
 
 /// Required trait for being able to retrieve connections manager address from system registry
 impl actix::Supervised for ConnectionsManager {}
@@ -49,7 +68,9 @@ impl ConnectionsManager {
     }
 
     /// Method to process resolver ConnectAddr response
+    /// Failed connections are reported to the PeersManager, so the address is backed off
     fn process_connect_addr_response(
+        address: SocketAddr,
         response: Result<messages::ResolverResult, MailboxError>,
     ) -> FutureResult<(), (), Self> {
         // Process the Result<ResolverResult, MailboxError>
@@ -62,7 +83,24 @@ impl ConnectionsManager {
                 // Process the ResolverResult
                 match res {
                     Err(e) => {
-                        warn!("Error while trying to connect to the peer: {}", e);
+                        warn!(
+                            "Error while trying to connect to the peer {}: {}",
+                            address, e
+                        );
+                        let outcome = match e {
+                            ResolverError::Timeout => AttemptOutcome::Timeout,
+                            ResolverError::IoError(ref e)
+                                if e.kind() == io::ErrorKind::TimedOut =>
+                            {
+                                AttemptOutcome::Timeout
+                            }
+                            _ => AttemptOutcome::Refused,
+                        };
+                        System::current()
+                            .registry()
+                            .get::<PeersManager>()
+                            .do_send(RecordConnectionAttempt { address, outcome });
+
                         actix::fut::err(())
                     }
                     Ok(stream) => {
@@ -78,23 +116,76 @@ impl ConnectionsManager {
         }
     }
 
+    /// Method to bind a TCP listener to an address
+    /// IPv6 listeners only accept IPv6 connections, so that an IPv4 and an IPv6 listener can be
+    /// bound to the same port (dual-stack)
+    fn bind(addr: &SocketAddr) -> io::Result<TcpListener> {
+        let builder = match addr {
+            SocketAddr::V4(_) => TcpBuilder::new_v4()?,
+            SocketAddr::V6(_) => {
+                let builder = TcpBuilder::new_v6()?;
+                builder.only_v6(true)?;
+                builder
+            }
+        };
+        builder.reuse_address(true)?;
+        let listener = builder.bind(addr)?.listen(1024)?;
+
+        TcpListener::from_std(listener, &Handle::default())
+    }
+
     /// Method to process the configuration received from the ConfigManager
     fn process_config(&mut self, ctx: &mut <Self as Actor>::Context, config: &Config) {
-        // Bind TCP listener to this address
-        // FIXME(#72): decide what to do with actor when server cannot be started
-        let listener = TcpListener::bind(&config.connections.server_addr).unwrap();
-
-        // Add message stream which will return a InboundTcpConnect for each incoming TCP connection
-        ctx.add_message_stream(
-            listener
-                .incoming()
-                .map_err(|_| ())
-                .map(messages::InboundTcpConnect::new),
-        );
+        // Bind a TCP listener to every configured address
+        for addr in config.connections.bind_addrs() {
+            // FIXME(#72): decide what to do with actor when server cannot be started
+            let listener = match ConnectionsManager::bind(&addr) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("P2P server could not be started at {:?}: {}", addr, e);
+                    continue;
+                }
+            };
+
+            // Add message stream which will return a InboundTcpConnect for each incoming TCP
+            // connection
+            ctx.add_message_stream(
+                listener
+                    .incoming()
+                    .map_err(|_| ())
+                    .map(messages::InboundTcpConnect::new),
+            );
+
+            info!("P2P server has been started at {:?}", addr);
+        }
+
+        // Open the server port in the router, if enabled
+        if config.connections.port_mapping {
+            match config.connections.server_addr {
+                SocketAddr::V4(server_addr) => self.map_port_periodically(ctx, server_addr),
+                SocketAddr::V6(_) => warn!("Port mapping is only supported for IPv4 servers"),
+            }
+        }
+    }
+
+    /// Method to map the server port and renew the mapping before its lease expires
+    /// The blocking UPnP and NAT-PMP requests are run in a separate thread, which notifies the result back
+    /// with a PortMapped message
+    fn map_port_periodically(
+        &mut self,
+        ctx: &mut <Self as Actor>::Context,
+        server_addr: SocketAddrV4,
+    ) {
+        let addr = ctx.address();
+        thread::spawn(move || {
+            debug!("Trying to map the server port...");
+            let result = port_mapping::map_port(server_addr);
+            addr.do_send(messages::PortMapped { result });
+        });
 
-        info!(
-            "P2P server has been started at {:?}",
-            &config.connections.server_addr
+        ctx.run_later(
+            Duration::from_secs(port_mapping::PORT_MAPPING_RENEWAL_SECONDS),
+            move |act, ctx| act.map_port_periodically(ctx, server_addr),
         );
     }
 }
@@ -0,0 +1,344 @@
+//! # Port mapping
+//!
+//! Automated opening of the server port in the router of the local
+//! network through UPnP, or through NAT-PMP if no UPnP gateway is found,
+//! so that nodes behind a NAT can accept inbound connections without
+//! configuring the router by hand. The gateway also reports the external
+//! address of the node, which is advertised to the peers instead of the
+//! (meaningless) local address.
+//!
+//! The mapping is removed from the gateway when the node is closed, so the
+//! port is not left open until its lease expires.
+//!
+//! The UPnP and NAT-PMP requests are blocking, so they must not be run in
+//! the thread of an actor.
+use std::fs;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use igd::{search_gateway, Gateway, PortMappingProtocol, SearchOptions};
+use lazy_static::lazy_static;
+use log::{info, warn};
+
+/// Duration (in seconds) of the port mapping lease requested to the gateway
+pub const PORT_MAPPING_LEASE_SECONDS: u64 = 60 * 60;
+
+/// Period (in seconds) between two renewals of the port mapping lease
+pub const PORT_MAPPING_RENEWAL_SECONDS: u64 = PORT_MAPPING_LEASE_SECONDS / 2;
+
+/// Time (in seconds) to wait for a gateway to answer the search request
+const GATEWAY_SEARCH_TIMEOUT_SECONDS: u64 = 5;
+
+/// Description of the port mapping, as shown by the router
+const PORT_MAPPING_DESCRIPTION: &str = "witnet";
+
+/// Port where the gateways listen for NAT-PMP requests
+const NAT_PMP_PORT: u16 = 5351;
+
+/// Number of times a NAT-PMP request is sent before giving up, waiting twice as long for the
+/// answer after every attempt
+const NAT_PMP_ATTEMPTS: u32 = 4;
+
+/// Time (in milliseconds) to wait for the answer to the first attempt of a NAT-PMP request
+const NAT_PMP_INITIAL_TIMEOUT_MILLIS: u64 = 250;
+
+/// NAT-PMP operation code of the external address requests
+const NAT_PMP_OPCODE_EXTERNAL_ADDRESS: u8 = 0;
+
+/// NAT-PMP operation code of the TCP port mapping requests
+const NAT_PMP_OPCODE_MAP_TCP: u8 = 2;
+
+lazy_static! {
+    /// Port mapping currently open in the gateway, removed when the node is closed
+    static ref ACTIVE_PORT_MAPPING: Mutex<Option<PortMapping>> = Mutex::new(None);
+}
+
+/// Gateway through which the server port has been mapped
+#[derive(Clone, Debug)]
+enum MappingGateway {
+    /// UPnP gateway
+    Upnp(Gateway),
+    /// Address of the NAT-PMP gateway
+    NatPmp(Ipv4Addr),
+}
+
+/// Port mapping open in the gateway of the local network
+#[derive(Clone, Debug)]
+struct PortMapping {
+    /// Gateway where the port is mapped
+    gateway: MappingGateway,
+    /// Port of the server in the local network
+    internal_port: u16,
+    /// External address under which the server is reachable
+    external_address: SocketAddr,
+}
+
+/// Open the port of a (IPv4) server address in the gateway of the local network, or renew its
+/// mapping
+/// Returns the external address under which the server is reachable
+pub fn map_port(server_addr: SocketAddrV4) -> Result<SocketAddr, String> {
+    let mapping = match map_port_upnp(server_addr) {
+        Ok(mapping) => mapping,
+        Err(upnp_error) => map_port_nat_pmp(server_addr)
+            .map_err(|e| format!("{} (UPnP), {} (NAT-PMP)", upnp_error, e))?,
+    };
+    let external_address = mapping.external_address;
+
+    if let Ok(mut active) = ACTIVE_PORT_MAPPING.lock() {
+        *active = Some(mapping);
+    }
+
+    Ok(external_address)
+}
+
+/// Remove the port mapping opened by `map_port`, if any
+/// Failures are only logged, as the mapping expires anyway when its lease ends
+pub fn unmap_port() {
+    let mapping = match ACTIVE_PORT_MAPPING.lock() {
+        Ok(mut active) => active.take(),
+        Err(_) => None,
+    };
+    let mapping = match mapping {
+        Some(mapping) => mapping,
+        None => return,
+    };
+
+    let external_port = mapping.external_address.port();
+    let result = match &mapping.gateway {
+        MappingGateway::Upnp(gateway) => gateway
+            .remove_port(PortMappingProtocol::TCP, external_port)
+            .map_err(|e| e.to_string()),
+        // A mapping request with a lifetime of 0 removes the mapping of the internal port
+        MappingGateway::NatPmp(gateway) => {
+            nat_pmp_map_tcp(*gateway, mapping.internal_port, 0, 0).map(|_| ())
+        }
+    };
+
+    match result {
+        Ok(()) => info!("Removed the mapping of the server port {}", external_port),
+        Err(e) => warn!(
+            "The mapping of the server port {} could not be removed: {}",
+            external_port, e
+        ),
+    }
+}
+
+/// Map the server port through the UPnP gateway of the local network
+fn map_port_upnp(server_addr: SocketAddrV4) -> Result<PortMapping, String> {
+    let gateway = search_gateway(SearchOptions {
+        timeout: Some(Duration::from_secs(GATEWAY_SEARCH_TIMEOUT_SECONDS)),
+        ..Default::default()
+    })
+    .map_err(|e| format!("gateway not found: {}", e))?;
+
+    // The gateway needs the address of this node in the local network, which is unknown if
+    // the server is bound to all interfaces
+    let local_ip = if server_addr.ip().is_unspecified() {
+        match local_ip_towards(SocketAddr::V4(gateway.addr)) {
+            Some(IpAddr::V4(ip)) => ip,
+            _ => return Err("local address could not be determined".to_string()),
+        }
+    } else {
+        *server_addr.ip()
+    };
+
+    let external_ip = gateway
+        .get_external_ip()
+        .map_err(|e| format!("external address not available: {}", e))?;
+
+    gateway
+        .add_port(
+            PortMappingProtocol::TCP,
+            server_addr.port(),
+            SocketAddrV4::new(local_ip, server_addr.port()),
+            PORT_MAPPING_LEASE_SECONDS as u32,
+            PORT_MAPPING_DESCRIPTION,
+        )
+        .map_err(|e| format!("port could not be mapped: {}", e))?;
+
+    Ok(PortMapping {
+        gateway: MappingGateway::Upnp(gateway),
+        internal_port: server_addr.port(),
+        external_address: SocketAddr::new(IpAddr::V4(external_ip), server_addr.port()),
+    })
+}
+
+/// Map the server port through the NAT-PMP service of the default gateway
+/// The gateway maps the port for the address the requests come from, so the local address of the
+/// server is not needed. It may choose a different external port than the requested one
+fn map_port_nat_pmp(server_addr: SocketAddrV4) -> Result<PortMapping, String> {
+    let gateway = default_gateway().ok_or_else(|| "gateway not found".to_string())?;
+    let external_ip = nat_pmp_external_ip(gateway)?;
+    let external_port = nat_pmp_map_tcp(
+        gateway,
+        server_addr.port(),
+        server_addr.port(),
+        PORT_MAPPING_LEASE_SECONDS as u32,
+    )?;
+
+    Ok(PortMapping {
+        gateway: MappingGateway::NatPmp(gateway),
+        internal_port: server_addr.port(),
+        external_address: SocketAddr::new(IpAddr::V4(external_ip), external_port),
+    })
+}
+
+/// Ask a NAT-PMP gateway for its external address
+fn nat_pmp_external_ip(gateway: Ipv4Addr) -> Result<Ipv4Addr, String> {
+    let payload = nat_pmp_request(gateway, &[0, NAT_PMP_OPCODE_EXTERNAL_ADDRESS])?;
+    if payload.len() < 4 {
+        return Err("truncated external address response".to_string());
+    }
+
+    Ok(Ipv4Addr::new(
+        payload[0], payload[1], payload[2], payload[3],
+    ))
+}
+
+/// Ask a NAT-PMP gateway to map a TCP port for the given lifetime (in seconds), or to remove its
+/// mapping if the lifetime is 0
+/// Returns the external port chosen by the gateway
+fn nat_pmp_map_tcp(
+    gateway: Ipv4Addr,
+    internal_port: u16,
+    external_port: u16,
+    lifetime: u32,
+) -> Result<u16, String> {
+    let mut request = vec![0, NAT_PMP_OPCODE_MAP_TCP, 0, 0];
+    request.extend_from_slice(&internal_port.to_be_bytes());
+    request.extend_from_slice(&external_port.to_be_bytes());
+    request.extend_from_slice(&lifetime.to_be_bytes());
+
+    // The payload holds the internal port, the external port and the lifetime of the mapping
+    let payload = nat_pmp_request(gateway, &request)?;
+    if payload.len() < 8 {
+        return Err("truncated port mapping response".to_string());
+    }
+
+    Ok(u16::from_be_bytes([payload[2], payload[3]]))
+}
+
+/// Send a NAT-PMP request to a gateway, retrying it until it is answered
+/// Returns the payload of the response
+fn nat_pmp_request(gateway: Ipv4Addr, request: &[u8]) -> Result<Vec<u8>, String> {
+    let io_error = |e: io::Error| format!("NAT-PMP request failed: {}", e);
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(io_error)?;
+    socket
+        .connect(SocketAddrV4::new(gateway, NAT_PMP_PORT))
+        .map_err(io_error)?;
+
+    let mut timeout = Duration::from_millis(NAT_PMP_INITIAL_TIMEOUT_MILLIS);
+    for _ in 0..NAT_PMP_ATTEMPTS {
+        socket.send(request).map_err(io_error)?;
+        socket.set_read_timeout(Some(timeout)).map_err(io_error)?;
+
+        let mut response = [0; 16];
+        match socket.recv(&mut response) {
+            Ok(size) => {
+                return nat_pmp_response_payload(request[1], &response[..size])
+                    .map(|payload| payload.to_vec());
+            }
+            Err(ref e)
+                if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut =>
+            {
+                timeout *= 2;
+            }
+            Err(e) => return Err(io_error(e)),
+        }
+    }
+
+    Err("gateway did not answer the NAT-PMP request".to_string())
+}
+
+/// Check the header of the response to a NAT-PMP request with the given operation code
+/// Returns the payload of the response: the bytes after the result code and the time since the
+/// gateway was started
+fn nat_pmp_response_payload(opcode: u8, response: &[u8]) -> Result<&[u8], String> {
+    if response.len() < 8 || response[0] != 0 || response[1] != opcode | 0x80 {
+        return Err("unexpected NAT-PMP response".to_string());
+    }
+
+    match u16::from_be_bytes([response[2], response[3]]) {
+        0 => Ok(&response[8..]),
+        code => Err(format!("NAT-PMP request refused (result code {})", code)),
+    }
+}
+
+/// Get the address of the default gateway from the routing table
+/// The routing table can only be read on Linux, so NAT-PMP is not available on other systems
+fn default_gateway() -> Option<Ipv4Addr> {
+    fs::read_to_string("/proc/net/route")
+        .ok()
+        .and_then(|routes| default_gateway_from_routes(&routes))
+}
+
+/// Get the gateway of the default route (the one with destination 0.0.0.0) from the contents of
+/// `/proc/net/route`, where addresses are written as hexadecimal numbers in host byte order
+fn default_gateway_from_routes(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|route| {
+        // Fields: interface, destination, gateway, ...
+        let fields: Vec<&str> = route.split_whitespace().collect();
+        if fields.get(1) != Some(&"00000000") {
+            return None;
+        }
+
+        fields
+            .get(2)
+            .and_then(|gateway| u32::from_str_radix(gateway, 16).ok())
+            .map(|gateway| Ipv4Addr::from(gateway.to_ne_bytes()))
+            .filter(|gateway| !gateway.is_unspecified())
+    })
+}
+
+/// Get the local IP address used to reach a remote address
+/// No packet is sent: connecting an UDP socket only selects the route
+fn local_ip_towards(remote: SocketAddr) -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect(remote).ok()?;
+
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nat_pmp_responses() {
+        // External address response: 203.0.113.7
+        let response = [0, 128, 0, 0, 0, 0, 0, 9, 203, 0, 113, 7];
+        assert_eq!(
+            nat_pmp_response_payload(NAT_PMP_OPCODE_EXTERNAL_ADDRESS, &response),
+            Ok(&response[8..])
+        );
+
+        // Response to another request
+        assert!(nat_pmp_response_payload(NAT_PMP_OPCODE_MAP_TCP, &response).is_err());
+        // Refused request (result code 2, not authorized)
+        let refused = [0, 130, 0, 2, 0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert!(nat_pmp_response_payload(NAT_PMP_OPCODE_MAP_TCP, &refused).is_err());
+        // Truncated response
+        assert!(nat_pmp_response_payload(NAT_PMP_OPCODE_MAP_TCP, &refused[..4]).is_err());
+    }
+
+    #[test]
+    fn default_gateway_routes() {
+        let routes = format!(
+            "Iface\tDestination\tGateway\tFlags\tRefCnt\tUse\tMetric\tMask\n\
+             eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\n\
+             eth0\t00000000\t{:08X}\t0003\t0\t0\t0\t00000000\n",
+            u32::from_ne_bytes([192, 168, 1, 1])
+        );
+
+        assert_eq!(
+            default_gateway_from_routes(&routes),
+            Some(Ipv4Addr::new(192, 168, 1, 1))
+        );
+        assert_eq!(
+            default_gateway_from_routes("Iface\tDestination\tGateway\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use actix::{
+    Actor, ActorFuture, AsyncContext, Context, ContextFutureSpawner, Supervised, System,
+    SystemService, WrapFuture,
+};
+use log::{debug, error, info};
+
+use super::{
+    eligibility::WitnessIdentity, handlers::EveryEpochPayload, reputation::ReputationEngine,
+    DataRequestsManager,
+};
+use crate::actors::{
+    config_manager::send_get_config_request,
+    epoch_manager::{messages::Subscribe, EpochManager},
+    node,
+    storage_keys::{REPUTATION_KEY, WITNESS_IDENTITY_KEY},
+    storage_manager::{
+        messages::{Get, Put},
+        put_with_retry, StorageManager,
+    },
+};
+use crate::errors::{NodeError, STORAGE_RETRY_POLICY};
+use witnet_config::config::Role;
+use witnet_rad::sandbox::SandboxLimits;
+
+/// Implement Actor trait for `DataRequestsManager`
+impl Actor for DataRequestsManager {
+    /// Every actor has to provide execution `Context` in which it can run
+    type Context = Context<Self>;
+
+    /// Method to be executed when the actor is started
+    fn started(&mut self, ctx: &mut Self::Context) {
+        debug!("DataRequestsManager actor has been started!");
+
+        // Query ConfigManager for the parameters of the reputation and the witnessing
+        send_get_config_request(self, ctx, |act, ctx, config| {
+            act.reputation_demurrage = config.consensus_constants.reputation_demurrage;
+            act.reputation_punishment = config.consensus_constants.reputation_punishment;
+            act.witnessing
+                .set_max_concurrent_jobs(config.witnessing.max_concurrent_jobs);
+            act.sandbox_limits = SandboxLimits {
+                timeout: config.witnessing.sandbox_timeout,
+                max_retrieved_size: config.witnessing.max_retrieved_size,
+            };
+
+            // Only witnesses check their eligibility to witness the data requests
+            if config.node.has_role(Role::Witness) {
+                restore_witness_identity(act, ctx);
+
+                let epoch_manager_addr = System::current().registry().get::<EpochManager>();
+                epoch_manager_addr.do_send(Subscribe::to_all(ctx.address(), EveryEpochPayload));
+            }
+        });
+
+        // Restore the reputation from storage
+        let storage_manager_addr = System::current().registry().get::<StorageManager>();
+        storage_manager_addr
+            // Send a message to read the reputation from the storage
+            .send(Get::<ReputationEngine>::new(REPUTATION_KEY))
+            .into_actor(self)
+            // Process the response
+            .then(|res, act, _ctx| {
+                match res {
+                    Err(e) => {
+                        // Error when sending message
+                        error!("Unsuccessful communication with storage manager: {}", e);
+                    }
+                    Ok(Err(e)) => {
+                        // Storage error
+                        error!("Error while getting the reputation from storage: {}", e);
+                    }
+                    Ok(Ok(Some(reputation))) => {
+                        act.reputation = reputation;
+                        info!("Reputation successfully obtained from storage");
+                    }
+                    Ok(Ok(None)) => {
+                        // The storage does not contain that key
+                        debug!("No reputation in storage");
+                    }
+                }
+
+                actix::fut::ok(())
+            })
+            .wait(ctx);
+    }
+}
+
+/// Make the DataRequestsManager a Supervisor, which provides the ability to be restarted
+/// Restore the witness identity of the node, or create it the first time the node witnesses
+fn restore_witness_identity(act: &mut DataRequestsManager, ctx: &mut Context<DataRequestsManager>) {
+    let storage_manager_addr = System::current().registry().get::<StorageManager>();
+    storage_manager_addr
+        .send(Get::<WitnessIdentity>::new(WITNESS_IDENTITY_KEY))
+        .into_actor(act)
+        .then(|res, act, ctx| {
+            match res {
+                Ok(Ok(Some(identity))) => match identity.secret_key() {
+                    Some(secret_key) => {
+                        info!("Witness identity successfully obtained from storage");
+                        act.witness_key = Some(secret_key);
+                    }
+                    None => error!("Invalid witness identity in storage"),
+                },
+                Ok(Ok(None)) => {
+                    info!("No witness identity in storage, creating a new one");
+                    create_witness_identity(act, ctx);
+                }
+                // Never create a new identity if the storage could not be read, as it would
+                // replace the existing one
+                Ok(Err(e)) => error!("Error while getting witness identity from storage: {}", e),
+                Err(e) => error!("Unsuccessful communication with storage manager: {}", e),
+            }
+
+            actix::fut::ok(())
+        })
+        .wait(ctx);
+}
+
+/// Create a new witness identity for the node and persist it into storage
+fn create_witness_identity(act: &mut DataRequestsManager, ctx: &mut Context<DataRequestsManager>) {
+    let identity = WitnessIdentity::generate();
+    let msg = match Put::from_value(WITNESS_IDENTITY_KEY, &identity) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Error when serializing the witness identity: {}", e);
+            node::escalate(&NodeError::from(e));
+            return;
+        }
+    };
+    act.witness_key = identity.secret_key();
+
+    put_with_retry(msg, STORAGE_RETRY_POLICY)
+        .into_actor(act)
+        .then(|res, _act, _ctx| {
+            match res {
+                Ok(()) => info!("DataRequestsManager successfully persisted the witness identity"),
+                Err(e) => {
+                    error!(
+                        "DataRequestsManager failed to persist the witness identity: {}",
+                        e
+                    );
+                    node::escalate(&e);
+                }
+            }
+
+            actix::fut::ok(())
+        })
+        .spawn(ctx);
+}
+
+impl Supervised for DataRequestsManager {}
+
+/// Required trait for being able to retrieve DataRequestsManager address from registry
+impl SystemService for DataRequestsManager {}
@@ -0,0 +1,195 @@
+//! # Eligibility
+//!
+//! This module decides whether an identity is eligible to witness a data
+//! request in an epoch. The identity signs the hash of the data request
+//! along with the beacon of the epoch: the signature is deterministic, so
+//! it works as a verifiable random function (VRF) whose output is the hash
+//! of the signature. The identity is eligible if that output falls below a
+//! threshold, which grows with the number of witnesses requested and the
+//! eligibility given by the reputation of the identity. Every node can
+//! check the proof with the public key of the witness, and nobody can
+//! predict the output before the beacon is known.
+use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+use rand::{thread_rng, Rng};
+use secp256k1::{All, PublicKey, Secp256k1, SecretKey};
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_crypto::{
+    hash::{calculate_public_key_hash, calculate_sha256},
+    signature::{sign, verify, SignatureJob},
+};
+use witnet_data_structures::chain::{CheckpointBeacon, Hash, PublicKeyHash, Secp256k1Signature};
+
+/// Proof of the eligibility of an identity to witness a data request in an epoch
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct VrfProof {
+    /// Serialized public key of the witness
+    pub public_key: Vec<u8>,
+    /// Signature of the VRF message
+    pub signature: Secp256k1Signature,
+}
+
+impl VrfProof {
+    /// Prove the eligibility for a VRF message with a secret key
+    pub fn prove(secp: &Secp256k1<All>, secret_key: &SecretKey, message: &[u8; 32]) -> Self {
+        Self {
+            public_key: PublicKey::from_secret_key(secp, secret_key)
+                .serialize()
+                .to_vec(),
+            signature: sign(secp, secret_key, message),
+        }
+    }
+
+    /// Check that the proof is a valid signature of a VRF message
+    pub fn verify(&self, secp: &Secp256k1<All>, message: &[u8; 32]) -> bool {
+        verify(
+            secp,
+            &SignatureJob {
+                public_key: self.public_key.clone(),
+                message: *message,
+                signature: self.signature.clone(),
+            },
+        )
+        .is_ok()
+    }
+
+    /// Get the output of the VRF: the hash of the signature
+    pub fn output(&self) -> Hash {
+        let mut bytes = self.signature.r.to_vec();
+        bytes.extend_from_slice(&self.signature.s);
+
+        calculate_sha256(&bytes)
+    }
+
+    /// Get the public key hash of the witness
+    pub fn pkh(&self) -> PublicKeyHash {
+        calculate_public_key_hash(&self.public_key)
+    }
+}
+
+/// Secret key identifying the node as a witness
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WitnessIdentity {
+    /// Serialized secret key
+    pub secret_key: Vec<u8>,
+}
+
+impl WitnessIdentity {
+    /// Generate a new random identity
+    pub fn generate() -> Self {
+        let mut rng = thread_rng();
+        loop {
+            let mut bytes = [0; 32];
+            rng.fill(&mut bytes);
+            // Almost every 32-byte value is a valid secret key
+            if SecretKey::from_slice(&bytes).is_ok() {
+                return WitnessIdentity {
+                    secret_key: bytes.to_vec(),
+                };
+            }
+        }
+    }
+
+    /// Get the secret key, or None if it is not valid
+    pub fn secret_key(&self) -> Option<SecretKey> {
+        SecretKey::from_slice(&self.secret_key).ok()
+    }
+}
+
+/// Get the message signed to prove the eligibility to witness a data request in the epoch of a
+/// beacon
+pub fn vrf_message(data_request: &Hash, beacon: &CheckpointBeacon) -> [u8; 32] {
+    let mut bytes = vec![];
+    let Hash::SHA256(dr_hash) = data_request;
+    bytes.extend_from_slice(dr_hash);
+    // Writing into a vector cannot fail
+    let _ = bytes.write_u32::<BigEndian>(beacon.checkpoint);
+    let Hash::SHA256(prev_block_hash) = beacon.hash_prev_block;
+    bytes.extend_from_slice(&prev_block_hash);
+
+    let Hash::SHA256(message) = calculate_sha256(&bytes);
+
+    message
+}
+
+/// Check whether a VRF output makes an identity eligible to witness a data request asking for
+/// `witnesses` witnesses, given the eligibility of the identity (its share of the total
+/// eligibility weight, see `ReputationEngine::eligibility`)
+pub fn is_eligible(output: &Hash, witnesses: u16, eligibility: f64) -> bool {
+    let target = f64::from(witnesses) * eligibility;
+    if target >= 1.0 {
+        return true;
+    }
+    let Hash::SHA256(bytes) = output;
+
+    (BigEndian::read_u64(&bytes[..8]) as f64) < target * u64::max_value() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret_key(byte: u8) -> SecretKey {
+        SecretKey::from_slice(&[byte; 32]).unwrap()
+    }
+
+    fn beacon(checkpoint: u32) -> CheckpointBeacon {
+        CheckpointBeacon {
+            checkpoint,
+            hash_prev_block: Hash::SHA256([7; 32]),
+        }
+    }
+
+    #[test]
+    fn vrf_proof_is_deterministic_and_verifiable() {
+        let secp = Secp256k1::new();
+        let message = vrf_message(&Hash::SHA256([1; 32]), &beacon(10));
+
+        let proof = VrfProof::prove(&secp, &secret_key(1), &message);
+        assert_eq!(proof, VrfProof::prove(&secp, &secret_key(1), &message));
+        assert!(proof.verify(&secp, &message));
+
+        // The proof is bound to the data request and the beacon
+        let other_message = vrf_message(&Hash::SHA256([1; 32]), &beacon(11));
+        assert_ne!(message, other_message);
+        assert!(!proof.verify(&secp, &other_message));
+        assert_ne!(
+            proof.output(),
+            VrfProof::prove(&secp, &secret_key(1), &other_message).output()
+        );
+    }
+
+    #[test]
+    fn witness_identity_generate() {
+        let identity = WitnessIdentity::generate();
+        assert!(identity.secret_key().is_some());
+        assert_ne!(identity.secret_key, WitnessIdentity::generate().secret_key);
+
+        let invalid = WitnessIdentity {
+            secret_key: vec![0; 32],
+        };
+        assert!(invalid.secret_key().is_none());
+    }
+
+    #[test]
+    fn eligibility_threshold() {
+        let low = Hash::SHA256([0; 32]);
+        let high = Hash::SHA256([0xff; 32]);
+        let mut half = [0; 32];
+        half[0] = 0x80;
+        let half = Hash::SHA256(half);
+
+        // Everybody is eligible when the witnesses requested cover the whole eligibility
+        assert!(is_eligible(&low, 4, 0.25));
+        assert!(is_eligible(&half, 4, 0.25));
+        assert!(is_eligible(&high, 4, 0.25));
+
+        // An identity with half of the eligibility for a single witness
+        assert!(is_eligible(&low, 1, 0.5));
+        assert!(!is_eligible(&half, 1, 0.5));
+        assert!(!is_eligible(&high, 1, 0.5));
+
+        // Identities without eligibility are never eligible
+        assert!(!is_eligible(&low, 1, 0.0));
+    }
+}
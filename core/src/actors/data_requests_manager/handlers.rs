@@ -0,0 +1,147 @@
+use actix::{ActorFuture, Context, ContextFutureSpawner, Handler, System, WrapFuture};
+use log::{debug, info, warn};
+
+use super::{
+    messages::{
+        GetDataRequestResult, GetDataRequestStatus, GetReputation, PostDataRequest, ProcessTally,
+        Reputation, ValidateTally, WitnessingFinished,
+    },
+    pool::DataRequestStatus,
+    DataRequestsManager, DataRequestsManagerError,
+};
+use crate::actors::{
+    blocks_manager::{messages::GetNodeStatus, BlocksManager, SyncState},
+    epoch_manager::messages::EpochNotification,
+};
+use witnet_data_structures::chain::CheckpointBeacon;
+
+////////////////////////////////////////////////////////////////////////////////////////
+// ACTOR MESSAGE HANDLERS
+////////////////////////////////////////////////////////////////////////////////////////
+/// Payload for the notification for all epochs
+#[derive(Clone, Debug)]
+pub struct EveryEpochPayload;
+
+/// Handler for EpochNotification<EveryEpochPayload>
+impl Handler<EpochNotification<EveryEpochPayload>> for DataRequestsManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: EpochNotification<EveryEpochPayload>, ctx: &mut Context<Self>) {
+        debug!("Periodic epoch notification received {:?}", msg.checkpoint);
+
+        // The eligibility depends on the beacon of the epoch, which includes the hash of the tip
+        // of the chain
+        // Witnessing is paused while the node is syncing, as the tip of its chain is not the
+        // tip of the network, and while it is isolated from the network, as its commitments
+        // would not reach the rest of the network
+        let checkpoint = msg.checkpoint;
+        let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+        blocks_manager_addr
+            .send(GetNodeStatus)
+            .into_actor(self)
+            .then(move |res, act, ctx| {
+                match res {
+                    Ok(Ok(ref status)) if status.sync_state != SyncState::Synced => {
+                        info!(
+                            "The node is syncing, not witnessing in epoch {}",
+                            checkpoint
+                        )
+                    }
+                    Ok(Ok(ref status)) if status.isolated.is_some() => {
+                        info!(
+                            "The node is isolated, not witnessing in epoch {}",
+                            checkpoint
+                        )
+                    }
+                    Ok(Ok(status)) => act.check_eligibility(
+                        ctx,
+                        CheckpointBeacon {
+                            checkpoint,
+                            hash_prev_block: status.tip.hash_prev_block,
+                        },
+                    ),
+                    _ => warn!("Get node status in Blocks Manager failed"),
+                }
+
+                actix::fut::ok(())
+            })
+            .wait(ctx);
+    }
+}
+
+/// Handler for PostDataRequest message
+impl Handler<PostDataRequest> for DataRequestsManager {
+    type Result = Result<(), DataRequestsManagerError>;
+
+    fn handle(&mut self, msg: PostDataRequest, _ctx: &mut Context<Self>) -> Self::Result {
+        self.post_data_request(msg.hash, msg.data_request)
+    }
+}
+
+/// Handler for GetDataRequestStatus message
+impl Handler<GetDataRequestStatus> for DataRequestsManager {
+    type Result = Result<Option<DataRequestStatus>, DataRequestsManagerError>;
+
+    fn handle(&mut self, msg: GetDataRequestStatus, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(self.pool.status(&msg.hash))
+    }
+}
+
+/// Handler for GetDataRequestResult message
+impl Handler<GetDataRequestResult> for DataRequestsManager {
+    type Result = Result<Option<Vec<u8>>, DataRequestsManagerError>;
+
+    fn handle(&mut self, msg: GetDataRequestResult, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(self.pool.result(&msg.hash).map(|result| result.to_vec()))
+    }
+}
+
+/// Handler for ProcessTally message
+impl Handler<ProcessTally> for DataRequestsManager {
+    type Result = Result<(), DataRequestsManagerError>;
+
+    fn handle(&mut self, msg: ProcessTally, ctx: &mut Context<Self>) -> Self::Result {
+        let res = self.process_tally(ctx, msg.hash, msg.result, &msg.liars, &msg.outputs);
+        if let Err(e) = &res {
+            warn!("Rejected tally of data request {:?}: {:?}", msg.hash, e);
+        }
+
+        res
+    }
+}
+
+/// Handler for ValidateTally message
+impl Handler<ValidateTally> for DataRequestsManager {
+    type Result = Result<(), DataRequestsManagerError>;
+
+    fn handle(&mut self, msg: ValidateTally, _ctx: &mut Context<Self>) -> Self::Result {
+        self.validate_tally(&msg.hash, &msg.liars, &msg.outputs)
+            .map(|_outcomes| ())
+    }
+}
+
+/// Handler for GetReputation message
+impl Handler<GetReputation> for DataRequestsManager {
+    type Result = Result<Reputation, DataRequestsManagerError>;
+
+    fn handle(&mut self, msg: GetReputation, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(Reputation {
+            score: self.reputation.score(&msg.identity),
+            eligibility: self.reputation.eligibility(&msg.identity),
+        })
+    }
+}
+
+/// Handler for WitnessingFinished message
+impl Handler<WitnessingFinished> for DataRequestsManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: WitnessingFinished, ctx: &mut Context<Self>) {
+        if self.witnessing.finish(msg.hash, msg.result) {
+            debug!("Data request {:?} witnessed", msg.hash);
+        }
+
+        // Start the jobs waiting for the slot this job has freed
+        self.start_witnessing_jobs(ctx);
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use actix::Message;
+
+use crate::actors::data_requests_manager::{
+    pool::DataRequestStatus, tally::TallyOutputs, DataRequestsManagerError,
+};
+use witnet_data_structures::chain::{DataRequestOutput, Hash, PublicKeyHash};
+
+/// Post a data request created by a client of this node and announce it to the peers
+pub struct PostDataRequest {
+    /// Hash of the data request
+    pub hash: Hash,
+    /// Data request
+    pub data_request: DataRequestOutput,
+}
+
+impl Message for PostDataRequest {
+    type Result = Result<(), DataRequestsManagerError>;
+}
+
+/// Ask for the status of a data request
+pub struct GetDataRequestStatus {
+    /// Hash of the data request
+    pub hash: Hash,
+}
+
+impl Message for GetDataRequestStatus {
+    type Result = Result<Option<DataRequestStatus>, DataRequestsManagerError>;
+}
+
+/// Ask for the tallied result of a data request
+pub struct GetDataRequestResult {
+    /// Hash of the data request
+    pub hash: Hash,
+}
+
+impl Message for GetDataRequestResult {
+    type Result = Result<Option<Vec<u8>>, DataRequestsManagerError>;
+}
+
+/// Finish a data request with its tallied result, validating its value transfers and updating
+/// the reputation of its witnesses
+/// It is sent by the BlocksManager for every tally included in a consolidated block
+pub struct ProcessTally {
+    /// Hash of the data request
+    pub hash: Hash,
+    /// Tallied result, encoded using MessagePack
+    pub result: Vec<u8>,
+    /// Witnesses whose revealed result was out of the consensus
+    pub liars: HashSet<PublicKeyHash>,
+    /// Value transfers of the tally
+    pub outputs: TallyOutputs,
+}
+
+impl Message for ProcessTally {
+    type Result = Result<(), DataRequestsManagerError>;
+}
+
+/// Check a tally included in a block candidate against the commits and reveals of its data
+/// request, without applying it
+pub struct ValidateTally {
+    /// Hash of the data request
+    pub hash: Hash,
+    /// Witnesses whose revealed result was out of the consensus
+    pub liars: HashSet<PublicKeyHash>,
+    /// Value transfers of the tally
+    pub outputs: TallyOutputs,
+}
+
+impl Message for ValidateTally {
+    type Result = Result<(), DataRequestsManagerError>;
+}
+
+/// Ask for the reputation of an identity
+pub struct GetReputation {
+    /// Identity
+    pub identity: PublicKeyHash,
+}
+
+/// Reputation of an identity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Reputation {
+    /// Reputation score, in units of `1 / REPUTATION_UNIT` points
+    pub score: u64,
+    /// Share of the eligibility to witness data requests
+    pub eligibility: f64,
+}
+
+impl Message for GetReputation {
+    type Result = Result<Reputation, DataRequestsManagerError>;
+}
+
+/// Result of a witnessing job, sent by the thread which resolved the RAD request
+pub struct WitnessingFinished {
+    /// Hash of the data request
+    pub hash: Hash,
+    /// Result of the RAD request, encoded using MessagePack
+    pub result: Vec<u8>,
+}
+
+impl Message for WitnessingFinished {
+    type Result = ();
+}
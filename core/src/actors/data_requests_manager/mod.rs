@@ -0,0 +1,320 @@
+//! # DataRequestsManager actor
+//!
+//! This module contains the DataRequestsManager actor which is in charge
+//! of keeping track of the data requests being resolved by the network.
+//! Among its responsabilities are the following:
+//!
+//! * Validating the data requests posted by the clients of this node before they are announced to the peers, including the type checking of their RADON scripts and the split of their value into fees and rewards.
+//! * Following the resolution of every data request through its stages: the witnesses commit to their results, reveal them and the revealed results are tallied into the final result.
+//! * Serving the status (current stage, number of commits and reveals) and the tallied result of the data requests.
+//! * Validating the value transfers of the tallies: the witnesses which did not reveal or lied forfeit their collateral, which is shared along with the value of the data request among the witnesses which agreed with the consensus.
+//! * Keeping the reputation of the witnesses from the outcomes of the tallied data requests, with a demurrage so reputation has to be earned continuously, and persisting it into storage.
+//! * On every epoch, checking which data requests this node is eligible to witness and resolving their RAD requests, bounding the number of witnessing jobs running at the same time.
+
+use std::{collections::HashSet, sync::Arc, thread};
+
+use actix::{ActorFuture, AsyncContext, Context, ContextFutureSpawner, System, WrapFuture};
+use log::{debug, error, info, warn};
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+use crate::actors::{
+    node,
+    session::messages::AnnounceItems,
+    sessions_manager::{messages::Broadcast, SessionsManager},
+    storage_keys::REPUTATION_KEY,
+    storage_manager::{messages::Put, put_with_retry},
+};
+use crate::errors::{NodeError, STORAGE_RETRY_POLICY};
+use witnet_crypto::hash::{calculate_public_key_hash, calculate_sha256};
+use witnet_data_structures::{
+    chain::{CheckpointBeacon, DataRequestOutput, Epoch, Hash, InvVector, PublicKeyHash},
+    data_request::value_split,
+    error::DataRequestError,
+};
+use witnet_rad::{error::RadError, retrieval::cache::RetrievalCache, sandbox::SandboxLimits};
+use witnet_storage::error::StorageError;
+use witnet_util::error::WitnetError;
+
+mod actor;
+mod handlers;
+
+/// Eligibility to witness data requests
+pub mod eligibility;
+
+/// Messages for DataRequestsManager
+pub mod messages;
+
+/// Tracking of the resolution of the data requests
+pub mod pool;
+
+/// Reputation of the witnesses
+pub mod reputation;
+
+/// Value transfers of the tallies
+pub mod tally;
+
+/// Scheduling of the witnessing jobs
+pub mod witnessing;
+
+/// Possible errors when interacting with DataRequestsManager
+#[derive(Debug)]
+pub enum DataRequestsManagerError {
+    /// The data request does not have any source to retrieve the data from
+    NoRetrievals,
+    /// The data request does not ask for any witness
+    NoWitnesses,
+    /// The data request was already known to this node
+    DataRequestAlreadyExists,
+    /// The data request is not known to this node
+    DataRequestNotFound,
+    /// The data request is not in the stage required by the operation
+    InvalidStage,
+    /// The witness revealing a result has not committed to it
+    WitnessNotCommitted,
+    /// The value transfers of a tally are not the expected ones
+    InvalidTallyOutputs,
+    /// The value transfers of a tally overflow
+    ValueOverflow,
+    /// The value of the data request does not cover its fees and the rewards of its witnesses
+    InvalidValue(WitnetError<DataRequestError>),
+    /// A RADON script of the data request is not valid
+    InvalidScript(WitnetError<RadError>),
+    /// StorageError
+    StorageError(WitnetError<StorageError>),
+}
+
+impl From<WitnetError<DataRequestError>> for DataRequestsManagerError {
+    fn from(x: WitnetError<DataRequestError>) -> Self {
+        DataRequestsManagerError::InvalidValue(x)
+    }
+}
+
+impl From<WitnetError<RadError>> for DataRequestsManagerError {
+    fn from(x: WitnetError<RadError>) -> Self {
+        DataRequestsManagerError::InvalidScript(x)
+    }
+}
+
+impl From<WitnetError<StorageError>> for DataRequestsManagerError {
+    fn from(x: WitnetError<StorageError>) -> Self {
+        DataRequestsManagerError::StorageError(x)
+    }
+}
+
+/// Check that a data request is well formed and that its value covers its fees and the rewards
+/// of its witnesses
+pub fn validate_data_request(
+    data_request: &DataRequestOutput,
+) -> Result<(), DataRequestsManagerError> {
+    if data_request.data_request.retrieve.is_empty() {
+        return Err(DataRequestsManagerError::NoRetrievals);
+    }
+    if data_request.witnesses == 0 {
+        return Err(DataRequestsManagerError::NoWitnesses);
+    }
+    value_split(data_request)?;
+    witnet_rad::validate_request(&data_request.data_request)?;
+
+    Ok(())
+}
+
+/// Hash which identifies a data request
+pub fn data_request_hash(data_request: &DataRequestOutput) -> Hash {
+    calculate_sha256(&data_request.hashed_bytes())
+}
+
+////////////////////////////////////////////////////////////////////////////////////////
+// ACTOR BASIC STRUCTURE
+////////////////////////////////////////////////////////////////////////////////////////
+/// DataRequestsManager actor
+#[derive(Default)]
+pub struct DataRequestsManager {
+    /// Data requests being resolved
+    // FIXME(#99): feed the pool with the commits, reveals and tallies included in the
+    // consolidated blocks once Transaction is defined
+    pool: pool::DataRequestPool,
+    /// Reputation of the witnesses
+    reputation: reputation::ReputationEngine,
+    /// Decay of the reputation on every tally
+    reputation_demurrage: f64,
+    /// Fraction of the reputation lost by a witness which lies or does not reveal
+    reputation_punishment: f64,
+    /// Witnessing jobs of this node
+    witnessing: witnessing::WitnessingQueue,
+    /// Secret key identifying this node as a witness (None if the node does not witness data
+    /// requests or until it has been restored from storage)
+    witness_key: Option<SecretKey>,
+    /// Limits of the RAD requests run by the witnessing jobs, from the configuration
+    sandbox_limits: SandboxLimits,
+    /// Data retrieved by the witnessing jobs during the current epoch, so every source is
+    /// requested at most once per epoch
+    retrieval_cache: Arc<RetrievalCache>,
+    /// Epoch of the last eligibility check, in which the queued witnessing jobs are run
+    current_epoch: Epoch,
+}
+
+/// Auxiliary methods for DataRequestsManager actor
+impl DataRequestsManager {
+    /// Method to start tracking a data request posted by a client of this node and announce it
+    /// to the peers
+    fn post_data_request(
+        &mut self,
+        hash: Hash,
+        data_request: DataRequestOutput,
+    ) -> Result<(), DataRequestsManagerError> {
+        validate_data_request(&data_request)?;
+        if !self.pool.add(hash, data_request) {
+            return Err(DataRequestsManagerError::DataRequestAlreadyExists);
+        }
+        debug!("Data request {:?} posted", hash);
+
+        // Get SessionsManager address
+        let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
+        sessions_manager_addr.do_send(Broadcast {
+            command: AnnounceItems {
+                items: vec![InvVector::DataRequest(hash)],
+            },
+        });
+
+        Ok(())
+    }
+
+    /// Method to finish a data request with its tallied result, once its value transfers have
+    /// been validated, updating the reputation of its witnesses from their outcomes
+    fn process_tally(
+        &mut self,
+        ctx: &mut Context<Self>,
+        hash: Hash,
+        result: Vec<u8>,
+        liars: &HashSet<PublicKeyHash>,
+        outputs: &tally::TallyOutputs,
+    ) -> Result<(), DataRequestsManagerError> {
+        let outcomes = self.validate_tally(&hash, liars, outputs)?;
+
+        self.pool.add_tally(&hash, result, liars)?;
+        self.witnessing.forget(&hash);
+        self.reputation.record_tally(
+            &outcomes,
+            self.reputation_demurrage,
+            self.reputation_punishment,
+        );
+        debug!("Data request {:?} tallied", hash);
+
+        self.persist_reputation(ctx);
+
+        Ok(())
+    }
+
+    /// Method to check a tally against the commits and reveals of its data request, without
+    /// applying it
+    /// Returns the outcome of every witness of the data request
+    fn validate_tally(
+        &self,
+        hash: &Hash,
+        liars: &HashSet<PublicKeyHash>,
+        outputs: &tally::TallyOutputs,
+    ) -> Result<Vec<(PublicKeyHash, reputation::WitnessOutcome)>, DataRequestsManagerError> {
+        let outcomes = self.pool.outcomes(hash, liars)?;
+        let data_request = self
+            .pool
+            .get(hash)
+            .ok_or(DataRequestsManagerError::DataRequestNotFound)?;
+        tally::validate_tally_outputs(data_request, &outcomes, outputs)?;
+
+        Ok(outcomes)
+    }
+
+    /// Method to queue the data requests in the commit stage that this node is eligible to
+    /// witness in the epoch of a beacon, and start the witnessing jobs
+    fn check_eligibility(&mut self, ctx: &mut Context<Self>, beacon: CheckpointBeacon) {
+        self.current_epoch = beacon.checkpoint;
+        let secret_key = match &self.witness_key {
+            Some(secret_key) => secret_key,
+            None => return,
+        };
+        let secp = Secp256k1::new();
+        let pkh =
+            calculate_public_key_hash(&PublicKey::from_secret_key(&secp, secret_key).serialize());
+        let eligibility = self.reputation.eligibility(&pkh);
+
+        let eligible: Vec<Hash> = self
+            .pool
+            .in_stage(pool::DataRequestStage::Commit)
+            .into_iter()
+            .filter(|(hash, _)| !self.witnessing.contains(hash))
+            .filter(|(hash, data_request)| {
+                let message = eligibility::vrf_message(hash, &beacon);
+                let proof = eligibility::VrfProof::prove(&secp, secret_key, &message);
+
+                eligibility::is_eligible(&proof.output(), data_request.witnesses, eligibility)
+            })
+            .map(|(hash, _)| hash)
+            .collect();
+        for hash in eligible {
+            debug!("Eligible to witness data request {:?}", hash);
+            // FIXME(#99): commit to the result along with the eligibility proof once Transaction
+            // is defined
+            self.witnessing.enqueue(hash);
+        }
+
+        self.start_witnessing_jobs(ctx);
+    }
+
+    /// Method to start the queued witnessing jobs while there are free slots
+    /// The RAD request of every job is run in a separate thread, which notifies the result back
+    /// with a WitnessingFinished message. The jobs share the data retrieved during the epoch.
+    fn start_witnessing_jobs(&mut self, ctx: &mut Context<Self>) {
+        while let Some(hash) = self.witnessing.next_job() {
+            let request = match self.pool.get(&hash) {
+                Some(data_request) => data_request.data_request.clone(),
+                None => {
+                    warn!("Data request {:?} to be witnessed not found", hash);
+                    self.witnessing.forget(&hash);
+                    self.witnessing.finish(hash, vec![]);
+                    continue;
+                }
+            };
+            let limits = self.sandbox_limits.clone();
+            let cache = Arc::clone(&self.retrieval_cache);
+            let epoch = self.current_epoch;
+            let addr = ctx.address();
+            debug!("Witnessing data request {:?}", hash);
+            thread::spawn(move || {
+                let result = witnet_rad::witness_cached(&request, epoch, &limits, &cache);
+                addr.do_send(messages::WitnessingFinished {
+                    hash,
+                    result: witnet_rad::encode_result(&result),
+                });
+            });
+        }
+    }
+
+    /// Method to persist the reputation into storage
+    fn persist_reputation(&self, ctx: &mut Context<Self>) {
+        let msg = match Put::from_value(REPUTATION_KEY, &self.reputation) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error when serializing the reputation: {}", e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+
+        put_with_retry(msg, STORAGE_RETRY_POLICY)
+            .into_actor(self)
+            .then(|res, _act, _ctx| {
+                match res {
+                    Ok(()) => info!("DataRequestsManager successfully persisted the reputation"),
+                    Err(e) => {
+                        error!(
+                            "DataRequestsManager failed to persist the reputation into storage: {}",
+                            e
+                        );
+                        node::escalate(&e);
+                    }
+                }
+                actix::fut::ok(())
+            })
+            .wait(ctx);
+    }
+}
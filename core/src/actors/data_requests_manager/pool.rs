@@ -0,0 +1,347 @@
+//! # Data request pool
+//!
+//! This module keeps track of the data requests which are being
+//! resolved, from the moment they are posted until their result is
+//! tallied: the witnesses which have committed to a result, the results
+//! they have revealed and the final result.
+use std::collections::{HashMap, HashSet};
+
+use serde_derive::{Deserialize, Serialize};
+
+use super::{reputation::WitnessOutcome, DataRequestsManagerError};
+use witnet_data_structures::chain::{DataRequestOutput, Hash, PublicKeyHash};
+
+/// Stage of the resolution of a data request
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DataRequestStage {
+    /// The witnesses are committing to their results
+    #[serde(rename = "commit")]
+    Commit,
+    /// The witnesses which have committed are revealing their results
+    #[serde(rename = "reveal")]
+    Reveal,
+    /// The revealed results are waiting to be tallied
+    #[serde(rename = "tally")]
+    Tally,
+    /// The result has been tallied
+    #[serde(rename = "finished")]
+    Finished,
+}
+
+/// Status of a data request
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DataRequestStatus {
+    /// Current stage
+    pub stage: DataRequestStage,
+    /// Number of witnesses requested
+    pub witnesses: u16,
+    /// Number of witnesses which have committed to a result
+    pub commits: usize,
+    /// Number of witnesses which have revealed their result
+    pub reveals: usize,
+}
+
+/// Resolution of a data request
+#[derive(Debug)]
+struct DataRequestState {
+    /// Data request
+    data_request: DataRequestOutput,
+    /// Current stage
+    stage: DataRequestStage,
+    /// Witnesses which have committed to a result
+    commits: HashSet<PublicKeyHash>,
+    /// Results revealed by the witnesses, encoded using MessagePack
+    reveals: HashMap<PublicKeyHash, Vec<u8>>,
+    /// Tallied result, encoded using MessagePack
+    result: Option<Vec<u8>>,
+}
+
+/// Data requests being resolved
+#[derive(Debug, Default)]
+pub struct DataRequestPool {
+    /// Resolution of every data request
+    data_requests: HashMap<Hash, DataRequestState>,
+}
+
+impl DataRequestPool {
+    /// Start tracking a new data request, in the commit stage.
+    /// Returns false if the data request was already being tracked.
+    pub fn add(&mut self, hash: Hash, data_request: DataRequestOutput) -> bool {
+        if self.data_requests.contains_key(&hash) {
+            return false;
+        }
+        self.data_requests.insert(
+            hash,
+            DataRequestState {
+                data_request,
+                stage: DataRequestStage::Commit,
+                commits: HashSet::new(),
+                reveals: HashMap::new(),
+                result: None,
+            },
+        );
+
+        true
+    }
+
+    /// Record the commitment of a witness. Once the requested number of witnesses have
+    /// committed, the data request moves to the reveal stage.
+    pub fn add_commit(
+        &mut self,
+        hash: &Hash,
+        witness: PublicKeyHash,
+    ) -> Result<(), DataRequestsManagerError> {
+        let dr = self.get_in_stage(hash, DataRequestStage::Commit)?;
+        dr.commits.insert(witness);
+        if dr.commits.len() >= usize::from(dr.data_request.witnesses) {
+            dr.stage = DataRequestStage::Reveal;
+        }
+
+        Ok(())
+    }
+
+    /// Record the result revealed by a witness which has committed. Once every witness has
+    /// revealed its result, the data request moves to the tally stage.
+    pub fn add_reveal(
+        &mut self,
+        hash: &Hash,
+        witness: PublicKeyHash,
+        reveal: Vec<u8>,
+    ) -> Result<(), DataRequestsManagerError> {
+        let dr = self.get_in_stage(hash, DataRequestStage::Reveal)?;
+        if !dr.commits.contains(&witness) {
+            return Err(DataRequestsManagerError::WitnessNotCommitted);
+        }
+        dr.reveals.insert(witness, reveal);
+        if dr.reveals.len() == dr.commits.len() {
+            dr.stage = DataRequestStage::Tally;
+        }
+
+        Ok(())
+    }
+
+    /// Get the outcome of every witness which committed to the result of a data request ready to
+    /// be tallied: the ones which did not reveal it and the `liars` whose result was out of the
+    /// consensus are told apart from the ones which agreed with the consensus.
+    /// A data request is ready to be tallied once every witness has revealed its result, or
+    /// before if the reveal stage has timed out.
+    pub fn outcomes(
+        &self,
+        hash: &Hash,
+        liars: &HashSet<PublicKeyHash>,
+    ) -> Result<Vec<(PublicKeyHash, WitnessOutcome)>, DataRequestsManagerError> {
+        let dr = match self.data_requests.get(hash) {
+            Some(dr)
+                if dr.stage == DataRequestStage::Reveal || dr.stage == DataRequestStage::Tally =>
+            {
+                dr
+            }
+            Some(_) => return Err(DataRequestsManagerError::InvalidStage),
+            None => return Err(DataRequestsManagerError::DataRequestNotFound),
+        };
+
+        let mut outcomes: Vec<_> = dr
+            .commits
+            .iter()
+            .map(|witness| {
+                let outcome = if !dr.reveals.contains_key(witness) {
+                    WitnessOutcome::NotRevealed
+                } else if liars.contains(witness) {
+                    WitnessOutcome::Lied
+                } else {
+                    WitnessOutcome::Agreed
+                };
+
+                (*witness, outcome)
+            })
+            .collect();
+        // Sort the outcomes so they do not depend on the order of the hash set
+        outcomes.sort_by_key(|(witness, _)| *witness);
+
+        Ok(outcomes)
+    }
+
+    /// Record the tallied result, which finishes a data request ready to be tallied
+    /// Returns the outcome of every witness which committed to a result (see `outcomes`)
+    pub fn add_tally(
+        &mut self,
+        hash: &Hash,
+        result: Vec<u8>,
+        liars: &HashSet<PublicKeyHash>,
+    ) -> Result<Vec<(PublicKeyHash, WitnessOutcome)>, DataRequestsManagerError> {
+        let outcomes = self.outcomes(hash, liars)?;
+        if let Some(dr) = self.data_requests.get_mut(hash) {
+            dr.result = Some(result);
+            dr.stage = DataRequestStage::Finished;
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Get a data request
+    pub fn get(&self, hash: &Hash) -> Option<&DataRequestOutput> {
+        self.data_requests.get(hash).map(|dr| &dr.data_request)
+    }
+
+    /// Get the data requests in a stage
+    pub fn in_stage(&self, stage: DataRequestStage) -> Vec<(Hash, &DataRequestOutput)> {
+        self.data_requests
+            .iter()
+            .filter(|(_, dr)| dr.stage == stage)
+            .map(|(hash, dr)| (*hash, &dr.data_request))
+            .collect()
+    }
+
+    /// Get the status of a data request
+    pub fn status(&self, hash: &Hash) -> Option<DataRequestStatus> {
+        self.data_requests.get(hash).map(|dr| DataRequestStatus {
+            stage: dr.stage,
+            witnesses: dr.data_request.witnesses,
+            commits: dr.commits.len(),
+            reveals: dr.reveals.len(),
+        })
+    }
+
+    /// Get the tallied result of a data request, if it has finished
+    pub fn result(&self, hash: &Hash) -> Option<&[u8]> {
+        self.data_requests
+            .get(hash)
+            .and_then(|dr| dr.result.as_ref())
+            .map(|result| result.as_slice())
+    }
+
+    /// Get a data request, as long as it is in the given stage
+    fn get_in_stage(
+        &mut self,
+        hash: &Hash,
+        stage: DataRequestStage,
+    ) -> Result<&mut DataRequestState, DataRequestsManagerError> {
+        match self.data_requests.get_mut(hash) {
+            Some(dr) if dr.stage == stage => Ok(dr),
+            Some(_) => Err(DataRequestsManagerError::InvalidStage),
+            None => Err(DataRequestsManagerError::DataRequestNotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use witnet_data_structures::chain::{RADAggregate, RADConsensus, RADRequest};
+
+    fn data_request(witnesses: u16) -> DataRequestOutput {
+        DataRequestOutput {
+            data_request: RADRequest {
+                not_before: 0,
+                retrieve: vec![],
+                aggregate: RADAggregate { script: vec![] },
+                consensus: RADConsensus { script: vec![] },
+                deliver: vec![],
+            },
+            value: 1000,
+            witnesses,
+            commit_fee: 1,
+            reveal_fee: 1,
+            tally_fee: 1,
+            collateral: 0,
+        }
+    }
+
+    #[test]
+    fn data_request_lifecycle() {
+        let mut pool = DataRequestPool::default();
+        let hash = Hash::SHA256([1; 32]);
+
+        assert!(pool.add(hash, data_request(2)));
+        assert!(!pool.add(hash, data_request(2)));
+        assert_eq!(
+            pool.in_stage(DataRequestStage::Commit),
+            vec![(hash, &data_request(2))]
+        );
+
+        // Reveals are not accepted during the commit stage
+        match pool.add_reveal(&hash, [1; 20], vec![0]) {
+            Err(DataRequestsManagerError::InvalidStage) => {}
+            x => panic!("{:?}", x),
+        }
+
+        pool.add_commit(&hash, [1; 20]).unwrap();
+        assert_eq!(pool.status(&hash).unwrap().stage, DataRequestStage::Commit);
+        pool.add_commit(&hash, [2; 20]).unwrap();
+        assert_eq!(pool.in_stage(DataRequestStage::Commit), vec![]);
+        assert_eq!(pool.in_stage(DataRequestStage::Reveal).len(), 1);
+        assert_eq!(
+            pool.status(&hash),
+            Some(DataRequestStatus {
+                stage: DataRequestStage::Reveal,
+                witnesses: 2,
+                commits: 2,
+                reveals: 0,
+            })
+        );
+
+        // Only the witnesses which have committed can reveal
+        match pool.add_reveal(&hash, [3; 20], vec![0]) {
+            Err(DataRequestsManagerError::WitnessNotCommitted) => {}
+            x => panic!("{:?}", x),
+        }
+        pool.add_reveal(&hash, [1; 20], vec![0]).unwrap();
+        pool.add_reveal(&hash, [2; 20], vec![0]).unwrap();
+        assert_eq!(pool.status(&hash).unwrap().stage, DataRequestStage::Tally);
+        assert_eq!(pool.result(&hash), None);
+
+        let outcomes = pool
+            .add_tally(&hash, vec![7], &[[2; 20]].iter().cloned().collect())
+            .unwrap();
+        assert_eq!(
+            outcomes,
+            vec![
+                ([1; 20], WitnessOutcome::Agreed),
+                ([2; 20], WitnessOutcome::Lied)
+            ]
+        );
+        assert_eq!(
+            pool.status(&hash).unwrap().stage,
+            DataRequestStage::Finished
+        );
+        assert_eq!(pool.result(&hash), Some(&[7][..]));
+    }
+
+    #[test]
+    fn tally_before_every_reveal() {
+        let mut pool = DataRequestPool::default();
+        let hash = Hash::SHA256([1; 32]);
+
+        pool.add(hash, data_request(2));
+        // The tally cannot happen before the reveal stage
+        match pool.add_tally(&hash, vec![7], &HashSet::new()) {
+            Err(DataRequestsManagerError::InvalidStage) => {}
+            x => panic!("{:?}", x),
+        }
+
+        pool.add_commit(&hash, [1; 20]).unwrap();
+        pool.add_commit(&hash, [2; 20]).unwrap();
+        pool.add_reveal(&hash, [2; 20], vec![0]).unwrap();
+        let outcomes = pool.add_tally(&hash, vec![7], &HashSet::new()).unwrap();
+        assert_eq!(
+            outcomes,
+            vec![
+                ([1; 20], WitnessOutcome::NotRevealed),
+                ([2; 20], WitnessOutcome::Agreed)
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_data_request() {
+        let mut pool = DataRequestPool::default();
+        let hash = Hash::SHA256([1; 32]);
+
+        match pool.add_commit(&hash, [1; 20]) {
+            Err(DataRequestsManagerError::DataRequestNotFound) => {}
+            x => panic!("{:?}", x),
+        }
+        assert_eq!(pool.status(&hash), None);
+        assert_eq!(pool.result(&hash), None);
+    }
+}
@@ -0,0 +1,179 @@
+//! # Reputation
+//!
+//! This module keeps track of the reputation of the identities which
+//! take part as witnesses in the resolution of the data requests. Every
+//! tallied data request rewards the witnesses which agreed with the
+//! consensus and punishes those which lied or did not reveal their
+//! result, and every tally makes all the reputation decay a little, so
+//! the identities need to keep on participating honestly to keep it.
+//!
+//! The reputation weights the eligibility of the identities to witness
+//! future data requests.
+use std::collections::BTreeMap;
+
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_data_structures::chain::PublicKeyHash;
+
+/// Number of fixed-point units in one point of reputation: the reputation is kept as an integer
+/// number of millionths of a point, so every node computes exactly the same values
+pub const REPUTATION_UNIT: u64 = 1_000_000;
+
+/// Reputation gained by a witness which agrees with the consensus (one point)
+pub const REPUTATION_REWARD: u64 = REPUTATION_UNIT;
+
+/// Reputation below which an identity is forgotten (one unit)
+pub const MIN_REPUTATION: u64 = 1;
+
+/// Outcome of the participation of a witness in a data request
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum WitnessOutcome {
+    /// The revealed result agreed with the consensus
+    Agreed,
+    /// The revealed result was out of the consensus
+    Lied,
+    /// The witness committed to a result but never revealed it
+    NotRevealed,
+}
+
+/// Reputation of the identities, in units of `1 / REPUTATION_UNIT` points
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReputationEngine {
+    /// Reputation of every identity. Identities without reputation are not stored.
+    scores: BTreeMap<PublicKeyHash, u64>,
+}
+
+impl ReputationEngine {
+    /// Record the outcomes of the witnesses of a tallied data request
+    ///
+    /// The existing reputation decays first by the `demurrage` factor. Then the witnesses which
+    /// agreed with the consensus gain `REPUTATION_REWARD`, while the ones which lied or did not
+    /// reveal lose the `punishment` fraction of their reputation. Both fractions are rounded to
+    /// millionths before being applied.
+    pub fn record_tally(
+        &mut self,
+        outcomes: &[(PublicKeyHash, WitnessOutcome)],
+        demurrage: f64,
+        punishment: f64,
+    ) {
+        let retained = REPUTATION_UNIT - to_fixed_point(demurrage);
+        let punished = REPUTATION_UNIT - to_fixed_point(punishment);
+
+        for score in self.scores.values_mut() {
+            *score = scale(*score, retained);
+        }
+        for (identity, outcome) in outcomes {
+            let score = self.scores.entry(*identity).or_insert(0);
+            *score = match outcome {
+                WitnessOutcome::Agreed => score.saturating_add(REPUTATION_REWARD),
+                WitnessOutcome::Lied | WitnessOutcome::NotRevealed => scale(*score, punished),
+            };
+        }
+
+        self.scores.retain(|_, score| *score >= MIN_REPUTATION);
+    }
+
+    /// Get the reputation of an identity
+    pub fn score(&self, identity: &PublicKeyHash) -> u64 {
+        self.scores.get(identity).cloned().unwrap_or(0)
+    }
+
+    /// Get the total reputation of all the identities
+    pub fn total(&self) -> u64 {
+        self.scores
+            .values()
+            .fold(0, |total, score| total.saturating_add(*score))
+    }
+
+    /// Get the eligibility of an identity: its share of the total eligibility weight, where the
+    /// weight of every identity is one point plus its reputation, so identities without
+    /// reputation can still be selected to witness and earn it
+    /// The total weight is the same for every identity: the weight of the known identities plus
+    /// the weight of one identity without reputation
+    pub fn eligibility(&self, identity: &PublicKeyHash) -> f64 {
+        let weight = REPUTATION_UNIT.saturating_add(self.score(identity));
+        let total_weight = (self.scores.len() as u64 + 1)
+            .saturating_mul(REPUTATION_UNIT)
+            .saturating_add(self.total());
+
+        weight as f64 / total_weight as f64
+    }
+}
+
+/// Convert a fraction between 0 and 1 into millionths, clamping it to that range
+fn to_fixed_point(fraction: f64) -> u64 {
+    (fraction.max(0.0).min(1.0) * REPUTATION_UNIT as f64).round() as u64
+}
+
+/// Multiply a reputation by a fraction expressed in millionths, rounding down
+fn scale(score: u64, fraction: u64) -> u64 {
+    (u128::from(score) * u128::from(fraction) / u128::from(REPUTATION_UNIT)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reputation_reward_and_punishment() {
+        let mut reputation = ReputationEngine::default();
+
+        reputation.record_tally(
+            &[
+                ([1; 20], WitnessOutcome::Agreed),
+                ([2; 20], WitnessOutcome::Agreed),
+            ],
+            0.0,
+            0.5,
+        );
+        assert_eq!(reputation.score(&[1; 20]), REPUTATION_UNIT);
+        assert_eq!(reputation.score(&[2; 20]), REPUTATION_UNIT);
+
+        reputation.record_tally(
+            &[
+                ([1; 20], WitnessOutcome::Agreed),
+                ([2; 20], WitnessOutcome::Lied),
+                ([3; 20], WitnessOutcome::NotRevealed),
+            ],
+            0.0,
+            0.5,
+        );
+        assert_eq!(reputation.score(&[1; 20]), 2 * REPUTATION_UNIT);
+        assert_eq!(reputation.score(&[2; 20]), REPUTATION_UNIT / 2);
+        // Identities without reputation are not stored
+        assert_eq!(reputation.score(&[3; 20]), 0);
+        assert_eq!(reputation.scores.len(), 2);
+        assert_eq!(reputation.total(), 5 * REPUTATION_UNIT / 2);
+    }
+
+    #[test]
+    fn reputation_demurrage() {
+        let mut reputation = ReputationEngine::default();
+
+        reputation.record_tally(&[([1; 20], WitnessOutcome::Agreed)], 0.5, 0.0);
+        reputation.record_tally(&[([2; 20], WitnessOutcome::Agreed)], 0.5, 0.0);
+        assert_eq!(reputation.score(&[1; 20]), REPUTATION_UNIT / 2);
+        assert_eq!(reputation.score(&[2; 20]), REPUTATION_UNIT);
+
+        // The reputation of identities which stop participating vanishes
+        for _ in 0..30 {
+            reputation.record_tally(&[], 0.5, 0.0);
+        }
+        assert_eq!(reputation.total(), 0);
+        assert!(reputation.scores.is_empty());
+    }
+
+    #[test]
+    fn reputation_eligibility() {
+        let mut reputation = ReputationEngine::default();
+        reputation.record_tally(&[([1; 20], WitnessOutcome::Agreed)], 0.0, 0.0);
+
+        // Weights: 2 for [1; 20], plus 1 for an identity without reputation
+        assert_eq!(reputation.eligibility(&[1; 20]), 2.0 / 3.0);
+        assert_eq!(reputation.eligibility(&[2; 20]), 1.0 / 3.0);
+        assert_eq!(
+            reputation.eligibility(&[1; 20]) + reputation.eligibility(&[2; 20]),
+            1.0
+        );
+    }
+}
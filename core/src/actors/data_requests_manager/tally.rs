@@ -0,0 +1,183 @@
+//! # Tally
+//!
+//! This module computes the value transfers of the tally of a data
+//! request, which are the same for every node given the outcomes of the
+//! witnesses, so the tally built by a miner can be validated by every
+//! other node:
+//!
+//! * The witnesses which agreed with the consensus get back their
+//!   collateral, and share the rewards of the data request (its value
+//!   minus the fees of its commit, reveal and tally transactions) along
+//!   with the collateral forfeited by the other witnesses.
+//! * The witnesses which lied or did not reveal their result forfeit
+//!   their collateral.
+//! * If no witness agreed with the consensus, the rewards of the data
+//!   request and the forfeited collateral are returned to the requester,
+//!   along with the remainder of the division of the rewards.
+use serde_derive::{Deserialize, Serialize};
+
+use super::{reputation::WitnessOutcome, DataRequestsManagerError};
+use witnet_data_structures::{
+    chain::{DataRequestOutput, PublicKeyHash},
+    data_request::value_split,
+};
+
+/// Value transfers of the tally of a data request
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TallyOutputs {
+    /// Value paid to every witness which agreed with the consensus, sorted by witness
+    pub rewards: Vec<(PublicKeyHash, u64)>,
+    /// Value returned to the requester
+    // FIXME(#99): pay to the requester once Transaction is defined
+    pub change: u64,
+}
+
+/// Compute the value transfers of the tally of a data request given the outcome of every witness
+/// which committed to a result
+pub fn tally_outputs(
+    data_request: &DataRequestOutput,
+    outcomes: &[(PublicKeyHash, WitnessOutcome)],
+) -> Result<TallyOutputs, DataRequestsManagerError> {
+    let mut honest: Vec<PublicKeyHash> = outcomes
+        .iter()
+        .filter(|(_, outcome)| *outcome == WitnessOutcome::Agreed)
+        .map(|(witness, _)| *witness)
+        .collect();
+    honest.sort();
+    let forfeited = data_request
+        .collateral
+        .checked_mul((outcomes.len() - honest.len()) as u64)
+        .ok_or(DataRequestsManagerError::ValueOverflow)?;
+    let pot = value_split(data_request)?
+        .rewards
+        .checked_add(forfeited)
+        .ok_or(DataRequestsManagerError::ValueOverflow)?;
+
+    if honest.is_empty() {
+        return Ok(TallyOutputs {
+            rewards: vec![],
+            change: pot,
+        });
+    }
+
+    let honest_count = honest.len() as u64;
+    let share = pot / honest_count;
+    let reward = data_request
+        .collateral
+        .checked_add(share)
+        .ok_or(DataRequestsManagerError::ValueOverflow)?;
+
+    Ok(TallyOutputs {
+        rewards: honest
+            .into_iter()
+            .map(|witness| (witness, reward))
+            .collect(),
+        change: pot % honest_count,
+    })
+}
+
+/// Check that the value transfers of a tally built by a miner are the expected ones
+pub fn validate_tally_outputs(
+    data_request: &DataRequestOutput,
+    outcomes: &[(PublicKeyHash, WitnessOutcome)],
+    outputs: &TallyOutputs,
+) -> Result<(), DataRequestsManagerError> {
+    if tally_outputs(data_request, outcomes)? == *outputs {
+        Ok(())
+    } else {
+        Err(DataRequestsManagerError::InvalidTallyOutputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use witnet_data_structures::chain::{RADAggregate, RADConsensus, RADRequest};
+
+    fn data_request(value: u64, collateral: u64) -> DataRequestOutput {
+        DataRequestOutput {
+            data_request: RADRequest {
+                not_before: 0,
+                retrieve: vec![],
+                aggregate: RADAggregate { script: vec![] },
+                consensus: RADConsensus { script: vec![] },
+                deliver: vec![],
+            },
+            value,
+            witnesses: 3,
+            commit_fee: 1,
+            reveal_fee: 1,
+            tally_fee: 1,
+            collateral,
+        }
+    }
+
+    #[test]
+    fn tally_rewards_honest_witnesses() {
+        let outcomes = vec![
+            ([1; 20], WitnessOutcome::Agreed),
+            ([2; 20], WitnessOutcome::Lied),
+            ([3; 20], WitnessOutcome::Agreed),
+            ([4; 20], WitnessOutcome::NotRevealed),
+        ];
+
+        // The value minus the fees plus the collateral of the liar and the non-revealer:
+        // 1008 - 7 + 2 * 100
+        let outputs = tally_outputs(&data_request(1008, 100), &outcomes).unwrap();
+        assert_eq!(
+            outputs,
+            TallyOutputs {
+                rewards: vec![([1; 20], 100 + 600), ([3; 20], 100 + 600)],
+                change: 1,
+            }
+        );
+
+        validate_tally_outputs(&data_request(1008, 100), &outcomes, &outputs).unwrap();
+        let mut invalid = outputs.clone();
+        invalid.rewards[0].1 += 1;
+        match validate_tally_outputs(&data_request(1008, 100), &outcomes, &invalid) {
+            Err(DataRequestsManagerError::InvalidTallyOutputs) => {}
+            x => panic!("{:?}", x),
+        }
+    }
+
+    #[test]
+    fn tally_without_honest_witnesses() {
+        let outcomes = vec![
+            ([1; 20], WitnessOutcome::Lied),
+            ([2; 20], WitnessOutcome::NotRevealed),
+        ];
+
+        assert_eq!(
+            tally_outputs(&data_request(1007, 100), &outcomes).unwrap(),
+            TallyOutputs {
+                rewards: vec![],
+                change: 1200,
+            }
+        );
+    }
+
+    #[test]
+    fn tally_overflow() {
+        let outcomes = vec![
+            ([1; 20], WitnessOutcome::Agreed),
+            ([2; 20], WitnessOutcome::Lied),
+        ];
+
+        match tally_outputs(&data_request(u64::max_value(), 8), &outcomes) {
+            Err(DataRequestsManagerError::ValueOverflow) => {}
+            x => panic!("{:?}", x),
+        }
+    }
+
+    #[test]
+    fn tally_with_insufficient_value() {
+        let outcomes = vec![([1; 20], WitnessOutcome::Agreed)];
+
+        // The value does not cover the fees (7) and a reward for each of the 3 witnesses
+        match tally_outputs(&data_request(9, 0), &outcomes) {
+            Err(DataRequestsManagerError::InvalidValue(_)) => {}
+            x => panic!("{:?}", x),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! # Witnessing
+//!
+//! This module schedules the resolution of the data requests this node is
+//! eligible to witness. Every data request is witnessed at most once: it
+//! is queued when the node becomes eligible, and its RAD request is run
+//! as soon as there is a free slot, so the number of jobs running at the
+//! same time never exceeds the configured limit. The encoded result of
+//! every job is kept until the data request is finished.
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use witnet_data_structures::chain::Hash;
+
+/// Default maximum number of witnessing jobs running at the same time
+pub const DEFAULT_MAX_CONCURRENT_JOBS: usize = 4;
+
+/// Witnessing jobs of this node
+#[derive(Debug)]
+pub struct WitnessingQueue {
+    /// Maximum number of jobs running at the same time
+    max_concurrent_jobs: usize,
+    /// Data requests waiting for a free slot, in order of arrival
+    pending: VecDeque<Hash>,
+    /// Data requests being resolved
+    running: HashSet<Hash>,
+    /// Data requests being resolved whose result is to be discarded
+    discarded: HashSet<Hash>,
+    /// Results of the resolved data requests, encoded using MessagePack
+    results: HashMap<Hash, Vec<u8>>,
+}
+
+impl Default for WitnessingQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONCURRENT_JOBS)
+    }
+}
+
+impl WitnessingQueue {
+    /// Create a queue running at most `max_concurrent_jobs` jobs at the same time (at least one)
+    pub fn new(max_concurrent_jobs: usize) -> Self {
+        Self {
+            max_concurrent_jobs: std::cmp::max(max_concurrent_jobs, 1),
+            pending: VecDeque::new(),
+            running: HashSet::new(),
+            discarded: HashSet::new(),
+            results: HashMap::new(),
+        }
+    }
+
+    /// Set the maximum number of jobs running at the same time (at least one). The jobs already
+    /// running are not affected.
+    pub fn set_max_concurrent_jobs(&mut self, max_concurrent_jobs: usize) {
+        self.max_concurrent_jobs = std::cmp::max(max_concurrent_jobs, 1);
+    }
+
+    /// Check whether a data request is already queued, being resolved or resolved
+    pub fn contains(&self, hash: &Hash) -> bool {
+        self.pending.contains(hash)
+            || self.running.contains(hash)
+            || self.results.contains_key(hash)
+    }
+
+    /// Queue a data request to be witnessed.
+    /// Returns false if it was already known.
+    pub fn enqueue(&mut self, hash: Hash) -> bool {
+        if self.contains(&hash) {
+            return false;
+        }
+        self.pending.push_back(hash);
+
+        true
+    }
+
+    /// Take the next data request to be resolved, if there is a free slot
+    pub fn next_job(&mut self) -> Option<Hash> {
+        if self.running.len() >= self.max_concurrent_jobs {
+            return None;
+        }
+        let hash = self.pending.pop_front()?;
+        self.running.insert(hash);
+
+        Some(hash)
+    }
+
+    /// Record the result of a job, freeing its slot.
+    /// Returns false if the data request was not being resolved.
+    pub fn finish(&mut self, hash: Hash, result: Vec<u8>) -> bool {
+        if !self.running.remove(&hash) {
+            return false;
+        }
+        if !self.discarded.remove(&hash) {
+            self.results.insert(hash, result);
+        }
+
+        true
+    }
+
+    /// Get the result of a resolved data request
+    pub fn result(&self, hash: &Hash) -> Option<&[u8]> {
+        self.results.get(hash).map(|result| result.as_slice())
+    }
+
+    /// Forget a data request (e.g. because it has been finished). A job which is still running
+    /// keeps its slot until it finishes, but its result is discarded.
+    pub fn forget(&mut self, hash: &Hash) {
+        self.pending.retain(|pending| pending != hash);
+        self.results.remove(hash);
+        if self.running.contains(hash) {
+            self.discarded.insert(*hash);
+        }
+    }
+
+    /// Get the number of jobs running
+    pub fn num_running(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Get the number of data requests waiting for a free slot
+    pub fn num_pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> Hash {
+        Hash::SHA256([byte; 32])
+    }
+
+    #[test]
+    fn witnessing_respects_max_concurrent_jobs() {
+        let mut queue = WitnessingQueue::new(2);
+        for i in 1..=3 {
+            assert!(queue.enqueue(hash(i)));
+        }
+        assert!(!queue.enqueue(hash(1)));
+
+        // Only two jobs run at the same time, in order of arrival
+        assert_eq!(queue.next_job(), Some(hash(1)));
+        assert_eq!(queue.next_job(), Some(hash(2)));
+        assert_eq!(queue.next_job(), None);
+        assert_eq!(queue.num_running(), 2);
+        assert_eq!(queue.num_pending(), 1);
+
+        // Finishing a job frees its slot
+        assert!(queue.finish(hash(1), vec![1]));
+        assert!(!queue.finish(hash(1), vec![1]));
+        assert_eq!(queue.next_job(), Some(hash(3)));
+        assert_eq!(queue.result(&hash(1)), Some(&[1][..]));
+
+        // A resolved data request is not witnessed again
+        assert!(!queue.enqueue(hash(1)));
+    }
+
+    #[test]
+    fn witnessing_forget() {
+        let mut queue = WitnessingQueue::new(1);
+        queue.enqueue(hash(1));
+        queue.enqueue(hash(2));
+        assert_eq!(queue.next_job(), Some(hash(1)));
+
+        queue.forget(&hash(2));
+        assert_eq!(queue.num_pending(), 0);
+
+        // The result of a forgotten job is discarded once it finishes
+        queue.forget(&hash(1));
+        assert!(queue.finish(hash(1), vec![1]));
+        assert_eq!(queue.result(&hash(1)), None);
+        assert_eq!(queue.next_job(), None);
+    }
+}
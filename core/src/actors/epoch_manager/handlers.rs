@@ -3,11 +3,15 @@ use actix::Handler;
 use log::debug;
 
 use super::{
-    messages::{EpochResult, GetEpoch, SubscribeAll, SubscribeEpoch},
+    messages::{
+        EpochInfo, EpochResult, GetEpoch, GetEpochInfo, SubscribeAll, SubscribeEpoch,
+        SubscribeMissedEpochs,
+    },
     EpochManager,
 };
 
 use witnet_data_structures::chain::Epoch;
+use witnet_util::timestamp::get_timestamp;
 
 ////////////////////////////////////////////////////////////////////////////////////////
 // ACTOR MESSAGE HANDLERS
@@ -24,6 +28,15 @@ impl Handler<GetEpoch> for EpochManager {
     }
 }
 
+impl Handler<GetEpochInfo> for EpochManager {
+    type Result = EpochResult<EpochInfo>;
+
+    /// Method to get the current epoch and the timestamp of the next checkpoint
+    fn handle(&mut self, _msg: GetEpochInfo, _ctx: &mut Self::Context) -> EpochResult<EpochInfo> {
+        self.epoch_info_at(get_timestamp())
+    }
+}
+
 impl Handler<SubscribeEpoch> for EpochManager {
     type Result = ();
 
@@ -50,3 +63,15 @@ impl Handler<SubscribeAll> for EpochManager {
         self.subscriptions_all.push(msg.notification);
     }
 }
+
+impl Handler<SubscribeMissedEpochs> for EpochManager {
+    type Result = ();
+
+    /// Method to handle SubscribeMissedEpochs messages
+    fn handle(&mut self, msg: SubscribeMissedEpochs, _ctx: &mut Self::Context) {
+        debug!("New subscription to missed epochs");
+
+        // Store subscription to missed epochs
+        self.subscriptions_missed.push(msg.recipient);
+    }
+}
@@ -1,5 +1,6 @@
 use actix::dev::ToEnvelope;
-use actix::{Actor, Addr, Handler, Message};
+use actix::{Actor, Addr, Handler, Message, Recipient};
+use serde_derive::{Deserialize, Serialize};
 
 use super::{
     AllEpochSubscription, EpochManagerError, SendableNotification, SingleEpochSubscription,
@@ -20,6 +21,23 @@ impl Message for GetEpoch {
     type Result = EpochResult<Epoch>;
 }
 
+/// Returns the current epoch along with the timestamp of the next checkpoint
+pub struct GetEpochInfo;
+
+/// Current epoch and timestamp of the next checkpoint
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EpochInfo {
+    /// Current epoch, or None if checkpoint zero is still in the future
+    pub epoch: Option<Epoch>,
+
+    /// Timestamp of the next checkpoint (checkpoint zero if it is still in the future)
+    pub next_checkpoint: i64,
+}
+
+impl Message for GetEpochInfo {
+    type Result = EpochResult<EpochInfo>;
+}
+
 /// Subscribe
 pub struct Subscribe;
 
@@ -40,6 +58,13 @@ pub struct SubscribeAll {
     pub notification: Box<dyn SendableNotification>,
 }
 
+/// Subscribe to the notifications of missed epochs
+#[derive(Message)]
+pub struct SubscribeMissedEpochs {
+    /// Recipient of the notifications
+    pub recipient: Recipient<MissedEpochs>,
+}
+
 impl Subscribe {
     /// Subscribe to a specific checkpoint to get an EpochNotification
     // TODO: rename to to_checkpoint?
@@ -79,6 +104,18 @@ impl Subscribe {
             }),
         }
     }
+    /// Subscribe to get a MissedEpochs notification whenever some epochs are skipped
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_missed_epochs<U>(addr: Addr<U>) -> SubscribeMissedEpochs
+    where
+        U: Actor,
+        U: Handler<MissedEpochs>,
+        U::Context: ToEnvelope<U, MissedEpochs>,
+    {
+        SubscribeMissedEpochs {
+            recipient: addr.recipient(),
+        }
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////
@@ -93,3 +130,14 @@ pub struct EpochNotification<T: Send> {
     /// Payload for the epoch notification
     pub payload: T,
 }
+
+/// Message that the EpochManager sends to subscriber actors when some epochs were skipped (the
+/// process was paused or the event loop was busy), before notifying the current epoch
+#[derive(Clone, Copy, Debug, Message)]
+pub struct MissedEpochs {
+    /// First epoch skipped
+    pub first: Epoch,
+
+    /// Last epoch skipped
+    pub last: Epoch,
+}
@@ -6,9 +6,11 @@ use std::collections::BTreeMap;
 use std::time::Duration;
 
 use witnet_config::config::Config;
-use witnet_util::timestamp::get_timestamp;
+use witnet_util::timestamp::{get_timestamp, get_timestamp_nanos};
 
-use crate::actors::epoch_manager::messages::{EpochNotification, EpochResult};
+use crate::actors::epoch_manager::messages::{
+    EpochInfo, EpochNotification, EpochResult, MissedEpochs,
+};
 
 use witnet_data_structures::chain::Epoch;
 
@@ -18,6 +20,9 @@ mod handlers;
 /// Messages that are handled by the EpochManager
 pub mod messages;
 
+/// Delay (in milliseconds) of the checkpoint monitor process above which a warning is logged
+pub const MAX_DRIFT_MILLIS: i64 = 1000;
+
 /// Possible errors when getting the current epoch
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum EpochManagerError {
@@ -52,6 +57,9 @@ pub struct EpochManager {
     /// Subscriptions to all epochs
     subscriptions_all: Vec<Box<dyn SendableNotification>>,
 
+    /// Subscriptions to the epochs missed by the epoch monitor process
+    subscriptions_missed: Vec<Recipient<MissedEpochs>>,
+
     /// Last epoch that was checked by the epoch monitor process
     last_checked_epoch: Option<Epoch>,
 }
@@ -111,100 +119,197 @@ impl EpochManager {
             (_, None) => Err(EpochManagerError::UnknownCheckpointPeriod),
         }
     }
+    /// Calculate the timestamp of the next checkpoint at the supplied timestamp (checkpoint zero
+    /// if it is still in the future)
+    pub fn next_checkpoint_at(&self, timestamp: i64) -> messages::EpochResult<i64> {
+        match self.epoch_at(timestamp) {
+            Ok(epoch) => {
+                self.epoch_timestamp(epoch.checked_add(1).ok_or(EpochManagerError::Overflow)?)
+            }
+            Err(EpochManagerError::CheckpointZeroInTheFuture) => self.epoch_timestamp(0),
+            Err(e) => Err(e),
+        }
+    }
+    /// Get the current epoch (None if checkpoint zero is still in the future) and the timestamp
+    /// of the next checkpoint at the supplied timestamp
+    pub fn epoch_info_at(&self, timestamp: i64) -> messages::EpochResult<EpochInfo> {
+        let epoch = match self.epoch_at(timestamp) {
+            Ok(epoch) => Some(epoch),
+            Err(EpochManagerError::CheckpointZeroInTheFuture) => None,
+            Err(e) => return Err(e),
+        };
+
+        Ok(EpochInfo {
+            epoch,
+            next_checkpoint: self.next_checkpoint_at(timestamp)?,
+        })
+    }
     /// Method to process the configuration received from the config manager
     fn process_config(&mut self, ctx: &mut <Self as Actor>::Context, config: &Config) {
         self.set_checkpoint_zero(config.consensus_constants.checkpoint_zero_timestamp);
         self.set_period(config.consensus_constants.checkpoints_period);
         debug!(
             "Checkpoint zero timestamp: {}, checkpoints period: {}",
-            self.checkpoint_zero_timestamp.unwrap(),
-            self.checkpoints_period.unwrap()
+            config.consensus_constants.checkpoint_zero_timestamp,
+            config.consensus_constants.checkpoints_period
         );
 
         // Start checkpoint monitoring process
         self.checkpoint_monitor(ctx);
     }
-    /// Method to compute time remaining to next checkpoint
-    fn time_to_next_checkpoint(&self) -> EpochResult<Duration> {
-        // FIXME(#145): Improve time precision, use nanoseconds
-        // Get current timestamp and epoch
-        let now = get_timestamp();
-        let current_epoch = self.epoch_at(now)?;
+    /// Method to compute time remaining to next checkpoint (or to checkpoint zero, if it is still
+    /// in the future), along with the timestamp of that checkpoint
+    fn time_to_next_checkpoint(&self) -> EpochResult<(Duration, i64)> {
+        // Get current timestamp, with nanosecond precision so short checkpoint periods are not
+        // delayed by up to one second
+        let (now, now_nanos) = get_timestamp_nanos();
 
         // Get timestamp for the start of next checkpoint
-        let next_checkpoint = self.epoch_timestamp(
-            current_epoch
-                .checked_add(1)
-                .ok_or(EpochManagerError::Overflow)?,
-        )?;
+        let next_checkpoint = self.next_checkpoint_at(now)?;
 
         // Get number of seconds remaining to the next checkpoint
         let secs = next_checkpoint - now;
 
         // Check if number of seconds to next checkpoint is valid
-        // This number should never be negative with current implementation
-        if secs < 0 {
+        // This number should never be lower than one with current implementation
+        if secs < 1 {
             Err(EpochManagerError::Overflow)
         } else {
-            Ok(Duration::from_secs(secs as u64))
+            // The nanoseconds of the current second exceed one second during a leap second, in
+            // which case the next checkpoint is considered to be reached already
+            let wait = Duration::from_secs(secs as u64)
+                .checked_sub(Duration::from_nanos(u64::from(now_nanos)))
+                .unwrap_or_default();
+
+            Ok((wait, next_checkpoint))
         }
     }
     /// Method to monitor checkpoints and execute some actions on each
+    ///
+    /// Every wake up is scheduled for the absolute timestamp of the next checkpoint, instead of a
+    /// fixed interval after the previous one, so the delays of the event loop do not accumulate
     fn checkpoint_monitor(&self, ctx: &mut Context<Self>) {
-        // Wait until next checkpoint to execute the periodic function
-        ctx.run_later(
-            self.time_to_next_checkpoint().unwrap_or_else(|_| {
-                Duration::from_secs(u64::from(self.checkpoints_period.unwrap()))
-            }),
-            move |act, ctx| {
-                // Get current epoch
-                let current_epoch = match act.current_epoch() {
-                    Ok(epoch) => epoch,
-                    Err(_) => return,
-                };
-
-                // Send message to actors which subscribed to all epochs
-                for subscription in &mut act.subscriptions_all {
-                    subscription.send_notification(current_epoch);
+        // Wait until next checkpoint to execute the periodic function, but never longer than a
+        // checkpoint period: while checkpoint zero is far in the future, it is checked again once
+        // per period
+        let period = Duration::from_secs(u64::from(self.checkpoints_period.unwrap_or(1)));
+        let (wait, target) = match self.time_to_next_checkpoint() {
+            Ok((duration, target)) if duration <= period => (duration, Some(target)),
+            Ok(_) => (period, None),
+            Err(e) => {
+                warn!("Could not compute time to next checkpoint: {:?}", e);
+                (period, None)
+            }
+        };
+
+        ctx.run_later(wait, move |act, ctx| {
+            // Get current epoch
+            let current_epoch = match act.current_epoch() {
+                Ok(epoch) => epoch,
+                Err(EpochManagerError::CheckpointZeroInTheFuture) => {
+                    debug!("Waiting for checkpoint zero");
+                    act.checkpoint_monitor(ctx);
+                    return;
+                }
+                Err(e) => {
+                    error!("Could not get current epoch: {:?}", e);
+                    act.checkpoint_monitor(ctx);
+                    return;
+                }
+            };
+
+            // The timer may fire slightly before the checkpoint, so the same epoch is not
+            // notified twice
+            if act.last_checked_epoch == Some(current_epoch) {
+                act.checkpoint_monitor(ctx);
+                return;
+            }
+
+            // Log how late the checkpoint monitor woke up
+            if let Some(target) = target {
+                let drift = drift_millis(target, get_timestamp_nanos());
+                if drift > MAX_DRIFT_MILLIS {
+                    warn!(
+                        "Checkpoint monitor woke up {} ms after the start of epoch {}",
+                        drift, current_epoch
+                    );
+                } else {
+                    debug!("Checkpoint drift: {} ms", drift);
                 }
+            }
+
+            // Let the subscribers know about the epochs that were skipped because the process
+            // was paused or the event loop was busy: subscriptions to all epochs are only
+            // notified of the current epoch
+            if let Some((first, last)) = missed_epochs(act.last_checked_epoch, current_epoch) {
+                warn!("Missed epochs {} to {}", first, last);
+                for recipient in &act.subscriptions_missed {
+                    // TODO: ignore failure?
+                    let _ = recipient.do_send(MissedEpochs { first, last });
+                }
+            }
+
+            // Send message to actors which subscribed to all epochs
+            for subscription in &mut act.subscriptions_all {
+                subscription.send_notification(current_epoch);
+            }
 
-                // Get all the checkpoints that had some subscription but were skipped for some
-                // reason (process sent to background, checkpoint monitor process had no
-                // resources to execute in time...)
-                let epoch_checkpoints: Vec<_> = act
-                    .subscriptions_epoch
-                    .range(act.last_checked_epoch.unwrap_or(0)..=current_epoch)
-                    .map(|(k, _v)| *k)
-                    .collect();
-
-                // Send notifications for skipped checkpoints for subscriptions to a particular
-                // epoch
-                // Notifications for skipped checkpoints are not sent for subscriptions to all
-                // epochs
-                for checkpoint in epoch_checkpoints {
-                    // Get the subscriptions to the skipped checkpoint
-                    if let Some(subscriptions) = act.subscriptions_epoch.remove(&checkpoint) {
-                        // Send notifications to subscribers for skipped checkpoints
-                        for mut subscription in subscriptions {
-                            // TODO: should send messages or just drop?
-                            // TODO: send notifications also for subscriptions to all epochs?
-                            subscription.send_notification(checkpoint);
-                        }
+            // Get all the checkpoints that had some subscription but were skipped for some
+            // reason (process sent to background, checkpoint monitor process had no
+            // resources to execute in time...)
+            let epoch_checkpoints: Vec<_> = act
+                .subscriptions_epoch
+                .range(act.last_checked_epoch.unwrap_or(0)..=current_epoch)
+                .map(|(k, _v)| *k)
+                .collect();
+
+            // Send notifications for skipped checkpoints for subscriptions to a particular
+            // epoch
+            // Notifications for skipped checkpoints are not sent for subscriptions to all
+            // epochs
+            for checkpoint in epoch_checkpoints {
+                // Get the subscriptions to the skipped checkpoint
+                if let Some(subscriptions) = act.subscriptions_epoch.remove(&checkpoint) {
+                    // Send notifications to subscribers for skipped checkpoints
+                    for mut subscription in subscriptions {
+                        // TODO: should send messages or just drop?
+                        // TODO: send notifications also for subscriptions to all epochs?
+                        subscription.send_notification(checkpoint);
                     }
                 }
+            }
 
-                // Update last checked epoch
-                act.last_checked_epoch = Some(current_epoch);
+            // Update last checked epoch
+            act.last_checked_epoch = Some(current_epoch);
 
-                debug!("Current epoch: {:?}", current_epoch);
+            debug!("Current epoch: {:?}", current_epoch);
 
-                // Reschedule checkpoint monitor process
-                act.checkpoint_monitor(ctx);
-            },
-        );
+            // Reschedule checkpoint monitor process
+            act.checkpoint_monitor(ctx);
+        });
     }
 }
 
+/// Get the range (both ends included) of the epochs skipped between the last epoch checked by the
+/// epoch monitor process and the current one, if any
+pub fn missed_epochs(
+    last_checked_epoch: Option<Epoch>,
+    current_epoch: Epoch,
+) -> Option<(Epoch, Epoch)> {
+    let first = last_checked_epoch?.checked_add(1)?;
+
+    if first < current_epoch {
+        Some((first, current_epoch - 1))
+    } else {
+        None
+    }
+}
+
+/// Get the milliseconds elapsed since a timestamp (negative if it is in the future)
+fn drift_millis(target: i64, (now, now_nanos): (i64, u32)) -> i64 {
+    (now - target) * 1000 + i64::from(now_nanos / 1_000_000)
+}
+
 /// Trait that must follow all notifications that will be sent back to subscriber actors
 pub trait SendableNotification: Send {
     /// Send notification back to the subscriber
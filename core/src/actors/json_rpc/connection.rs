@@ -1,8 +1,10 @@
+use super::health::{self, HttpRequest};
 use super::newline_codec::NewLineCodec;
 use super::server::JsonRpcServer;
 use super::server::Unregister;
 use actix::{
-    io::FramedWrite, io::WriteHandler, Actor, Addr, AsyncContext, Context, Running, StreamHandler,
+    io::FramedWrite, io::WriteHandler, Actor, ActorFuture, Addr, AsyncContext, Context,
+    ContextFutureSpawner, Running, StreamHandler, WrapFuture,
 };
 use bytes;
 use bytes::BytesMut;
@@ -22,6 +24,33 @@ pub struct JsonRpc {
     pub parent: Addr<JsonRpcServer>,
     /// IoHandler
     pub jsonrpc_io: Rc<IoHandler<()>>,
+    /// HTTP request being received, whose headers are skipped until the empty line which
+    /// ends them
+    pub http_request: Option<HttpRequest>,
+}
+
+impl JsonRpc {
+    /// Answer an HTTP request and close the connection
+    fn respond_http(&mut self, request: HttpRequest, ctx: &mut <Self as Actor>::Context) {
+        match request {
+            HttpRequest::Health => {
+                self.framed.write(health::http_response("200 OK", "ok"));
+                self.framed.close();
+            }
+            HttpRequest::Ready => health::readiness()
+                .into_actor(self)
+                .map(|readiness, act, _ctx| {
+                    act.framed.write(health::ready_response(&readiness));
+                    act.framed.close();
+                })
+                .wait(ctx),
+            HttpRequest::NotFound => {
+                self.framed
+                    .write(health::http_response("404 Not Found", "not found"));
+                self.framed.close();
+            }
+        }
+    }
 }
 
 impl Actor for JsonRpc {
@@ -43,7 +72,23 @@ impl WriteHandler<io::Error> for JsonRpc {}
 /// Implement `StreamHandler` trait in order to use `Framed` with an actor
 impl StreamHandler<BytesMut, io::Error> for JsonRpc {
     /// This is main event loop for client requests
-    fn handle(&mut self, bytes: BytesMut, _ctx: &mut Self::Context) {
+    fn handle(&mut self, bytes: BytesMut, ctx: &mut Self::Context) {
+        // Skip the headers of an HTTP request, and answer it once they end
+        if let Some(request) = self.http_request {
+            if bytes.is_empty() || &bytes[..] == b"\r" {
+                self.respond_http(request, ctx);
+            }
+            return;
+        }
+        if let Some(request) = std::str::from_utf8(&bytes)
+            .ok()
+            .and_then(HttpRequest::from_request_line)
+        {
+            debug!("Got HTTP request: {:?}", request);
+            self.http_request = Some(request);
+            return;
+        }
+
         info!("Got JSON-RPC message");
         let msg = match String::from_utf8(bytes.to_vec()) {
             Ok(msg) => {
@@ -66,10 +111,16 @@ impl StreamHandler<BytesMut, io::Error> for JsonRpc {
             }
         };
 
-        // Handle response synchronously
-        let response = self.jsonrpc_io.handle_request_sync(&msg);
-        if let Some(response) = response {
-            self.framed.write(BytesMut::from(response));
-        }
+        // Handle response asynchronously, because some methods need to wait for the response
+        // of other actors running in this same thread
+        self.jsonrpc_io
+            .handle_request(&msg)
+            .into_actor(self)
+            .map(|response, act, _ctx| {
+                if let Some(response) = response {
+                    act.framed.write(BytesMut::from(response));
+                }
+            })
+            .wait(ctx);
     }
 }
@@ -0,0 +1,226 @@
+//! # Health and readiness checks
+//!
+//! Besides JSON-RPC requests, the JSON-RPC server answers two plain HTTP
+//! requests, so process supervisors and load balancers can probe the node:
+//!
+//! * `GET /health` is always answered with `200 OK` while the node is
+//!   running.
+//! * `GET /ready` is answered with `200 OK` only when the storage has been
+//!   opened, there is at least one consolidated outbound session and the
+//!   local chain is synced (at most `node.max_epochs_behind` epochs behind the
+//!   network tip). Otherwise it is answered with `503 Service Unavailable`
+//!   and the list of unmet conditions.
+//!
+//! The connection is closed after the response.
+use actix::System;
+use bytes::BytesMut;
+use futures::Future;
+
+use crate::actors::{
+    blocks_manager::{messages::GetSyncState, BlocksManager, SyncState},
+    sessions_manager::{messages::GetNumOutboundConsolidated, SessionsManager},
+    storage_manager::{messages::IsOpened, StorageManager},
+};
+
+/// Path of the health check
+pub const HEALTH_PATH: &str = "/health";
+
+/// Path of the readiness check
+pub const READY_PATH: &str = "/ready";
+
+/// HTTP request received through the JSON-RPC server
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HttpRequest {
+    /// `GET /health`
+    Health,
+    /// `GET /ready`
+    Ready,
+    /// Any other path
+    NotFound,
+}
+
+impl HttpRequest {
+    /// Parse the request line of an HTTP request (the trailing `\r` is ignored)
+    /// Returns `None` if the line is not an HTTP GET request, so it can be handled as JSON-RPC
+    pub fn from_request_line(line: &str) -> Option<HttpRequest> {
+        let mut parts = line.trim_end_matches('\r').split(' ');
+        if parts.next() != Some("GET") {
+            return None;
+        }
+        let path = parts.next()?;
+        if !parts.next()?.starts_with("HTTP/") {
+            return None;
+        }
+
+        // Ignore the query string
+        Some(match path.split('?').next() {
+            Some(HEALTH_PATH) => HttpRequest::Health,
+            Some(READY_PATH) => HttpRequest::Ready,
+            _ => HttpRequest::NotFound,
+        })
+    }
+}
+
+/// Conditions checked by the readiness check
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Readiness {
+    /// Whether the storage has been opened
+    pub storage_opened: bool,
+    /// Number of consolidated outbound sessions
+    pub num_outbound_consolidated: usize,
+    /// Synchronization state of the local chain, if known
+    pub sync_state: Option<SyncState>,
+}
+
+impl Readiness {
+    /// Get the conditions which are not met, so the node is ready if there are none
+    pub fn unmet_conditions(&self) -> Vec<&'static str> {
+        let mut unmet = vec![];
+        if !self.storage_opened {
+            unmet.push("storage not opened");
+        }
+        if self.num_outbound_consolidated == 0 {
+            unmet.push("no consolidated outbound sessions");
+        }
+        if self.sync_state != Some(SyncState::Synced) {
+            unmet.push("not synced");
+        }
+
+        unmet
+    }
+
+    /// Whether the node is ready to serve requests
+    pub fn is_ready(&self) -> bool {
+        self.unmet_conditions().is_empty()
+    }
+}
+
+/// Ask the StorageManager, the SessionsManager and the BlocksManager for the readiness
+/// conditions. An actor which does not answer fails its condition.
+pub fn readiness() -> impl Future<Item = Readiness, Error = ()> {
+    let registry = System::current().registry();
+
+    let storage_opened = registry
+        .get::<StorageManager>()
+        .send(IsOpened)
+        .then(|res| Ok::<_, ()>(res.unwrap_or(false)));
+    let num_outbound_consolidated = registry
+        .get::<SessionsManager>()
+        .send(GetNumOutboundConsolidated)
+        .then(|res| Ok::<_, ()>(res.unwrap_or(0)));
+    let sync_state = registry
+        .get::<BlocksManager>()
+        .send(GetSyncState)
+        .then(|res| Ok::<_, ()>(res.ok().and_then(Result::ok)));
+
+    storage_opened
+        .join3(num_outbound_consolidated, sync_state)
+        .map(
+            |(storage_opened, num_outbound_consolidated, sync_state)| Readiness {
+                storage_opened,
+                num_outbound_consolidated,
+                sync_state,
+            },
+        )
+}
+
+/// Build an HTTP response with a plain text body
+/// The response does not include the final newline of the body, which is appended by the
+/// `NewLineCodec` when it is written
+pub fn http_response(status: &str, body: &str) -> BytesMut {
+    BytesMut::from(format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len() + 1,
+        body
+    ))
+}
+
+/// Build the response to the readiness check
+pub fn ready_response(readiness: &Readiness) -> BytesMut {
+    let unmet = readiness.unmet_conditions();
+    if unmet.is_empty() {
+        http_response("200 OK", "ready")
+    } else {
+        http_response("503 Service Unavailable", &unmet.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_request_line() {
+        assert_eq!(
+            HttpRequest::from_request_line("GET /health HTTP/1.1\r"),
+            Some(HttpRequest::Health)
+        );
+        assert_eq!(
+            HttpRequest::from_request_line("GET /ready?verbose HTTP/1.0"),
+            Some(HttpRequest::Ready)
+        );
+        assert_eq!(
+            HttpRequest::from_request_line("GET / HTTP/1.1\r"),
+            Some(HttpRequest::NotFound)
+        );
+        // JSON-RPC requests and other HTTP methods are not handled
+        assert_eq!(
+            HttpRequest::from_request_line(r#"{"jsonrpc":"2.0","method":"getEpoch","id":1}"#),
+            None
+        );
+        assert_eq!(
+            HttpRequest::from_request_line("POST /health HTTP/1.1\r"),
+            None
+        );
+        assert_eq!(HttpRequest::from_request_line("GET /health"), None);
+    }
+
+    #[test]
+    fn readiness_conditions() {
+        let ready = Readiness {
+            storage_opened: true,
+            num_outbound_consolidated: 1,
+            sync_state: Some(SyncState::Synced),
+        };
+        assert!(ready.is_ready());
+
+        let not_ready = Readiness {
+            storage_opened: false,
+            num_outbound_consolidated: 0,
+            sync_state: Some(SyncState::Syncing),
+        };
+        assert_eq!(
+            not_ready.unmet_conditions(),
+            vec![
+                "storage not opened",
+                "no consolidated outbound sessions",
+                "not synced"
+            ]
+        );
+        assert!(!Readiness {
+            sync_state: None,
+            ..ready
+        }
+        .is_ready());
+    }
+
+    #[test]
+    fn ready_response_status() {
+        let mut response = ready_response(&Readiness {
+            storage_opened: true,
+            num_outbound_consolidated: 0,
+            sync_state: Some(SyncState::Synced),
+        });
+        // The codec appends the final newline of the body
+        response.extend_from_slice(b"\n");
+        assert_eq!(
+            response,
+            BytesMut::from(
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Type: text/plain\r\n\
+                 Content-Length: 34\r\nConnection: close\r\n\r\n\
+                 no consolidated outbound sessions\n"
+            )
+        );
+    }
+}
@@ -1,12 +1,67 @@
 #[cfg(test)]
 use self::mock_actix::System;
-use crate::actors::blocks_manager::{messages::AddNewBlock, BlocksManager};
+use crate::actors::blocks_manager::{
+    messages::{
+        AddNewBlock, GetBlockChain, GetBlockHashByEpoch, GetBlockTemplate, GetChainStats,
+        GetNodeStatus, GetRescanStatus, GetSupplyInfo, Rescan, SubmitBlock,
+    },
+    BlocksManager, BlocksManagerError,
+};
+use crate::actors::config_manager::{consensus_constants_hash, messages::GetConfig, ConfigManager};
+use crate::actors::data_requests_manager::{
+    messages::{GetDataRequestResult, GetDataRequestStatus, PostDataRequest},
+    validate_data_request, DataRequestsManager, DataRequestsManagerError,
+};
+use crate::actors::epoch_manager::{messages::GetEpochInfo, EpochManager};
+use crate::actors::mempool_manager::{
+    fee_estimator::transaction_weight,
+    messages::{
+        EstimateFee, GetMempool, GetMempoolEntry, GetTransactionStatus, GetTransactionsToMine,
+        ResubmitTransaction, SetFeePolicy,
+    },
+    MempoolManager, MempoolManagerError,
+};
+use crate::actors::peers_manager::{
+    messages::{BanPeer, GetBans, ImportBans, UnbanPeer},
+    PeersManager,
+};
+use crate::actors::signature_verifier::SignatureVerifierError;
+use crate::actors::storage_manager::{messages::Get, StorageManager};
+use crate::actors::utxo_manager::{
+    messages::{GetAddressBalance, GetBalance, GetUtxos},
+    UtxoManager, UtxoManagerError,
+};
+use crate::actors::wallet_manager::{
+    messages::{
+        CreateAccount, CreateAddress, CreateValueTransfer, CreateWallet, ExportMasterKey,
+        FundDataRequest, GetAccounts, GetAddresses, GetTransactionHistory, ImportAddress,
+        ImportMnemonic, SetWalletPassword, SignTransaction, UnlockWallet,
+    },
+    signer::SignerError,
+    PartiallySignedTransaction, WalletManager, WalletManagerError,
+};
+use crate::metrics;
+use crate::tracing::Trace;
 #[cfg(not(test))]
 use actix::System;
+use futures::{future, Future};
 use jsonrpc_core::{IoHandler, Params, Value};
 use log::info;
 use serde_derive::{Deserialize, Serialize};
-use witnet_data_structures::chain::Block;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use witnet_crypto::mnemonic::Mnemonic;
+use witnet_data_structures::{
+    chain::{
+        Block, ConsensusConstants, DataRequestOutput, Epoch, Hash, PublicKeyHash, RADAggregate,
+        RADConsensus, RADDeliver, RADRequest, RADRetrieve, Transaction, ValueTransferOutput,
+    },
+    data_request::{min_value, value_split, ValueSplit},
+};
+use witnet_p2p::bans::Ban;
+
+/// Result of a JSON-RPC method that needs to wait for the response of other actors
+pub type JsonRpcFutureResult = Box<dyn Future<Item = Value, Error = jsonrpc_core::Error> + Send>;
 
 /// Define the JSON-RPC interface:
 /// All the methods available through JSON-RPC
@@ -14,6 +69,250 @@ pub fn jsonrpc_io_handler() -> IoHandler<()> {
     let mut io = IoHandler::new();
 
     io.add_method("inventory", |params: Params| inventory(params.parse()?));
+    io.add_method("setFeePolicy", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok(params) => set_fee_policy(params),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("estimateFee", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((target_epochs,)) => estimate_fee(target_epochs),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("getMempool", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((pagination,)) => get_mempool(pagination),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("getMempoolEntry", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((hash,)) => get_mempool_entry(hash),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method(
+        "getTransactionStatus",
+        |params: Params| -> JsonRpcFutureResult {
+            match params.parse() {
+                Ok((hash,)) => get_transaction_status(hash),
+                Err(e) => Box::new(future::err(e)),
+            }
+        },
+    );
+    io.add_method(
+        "resubmitTransaction",
+        |params: Params| -> JsonRpcFutureResult {
+            match params.parse() {
+                Ok((hash, fee)) => resubmit_transaction(hash, fee),
+                Err(e) => Box::new(future::err(e)),
+            }
+        },
+    );
+    io.add_method("createAccount", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((name,)) => create_account(name),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("getAccounts", |_params: Params| -> JsonRpcFutureResult {
+        get_accounts()
+    });
+    io.add_method("createAddress", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((account,)) => create_address(account),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method(
+        "createValueTransfer",
+        |params: Params| -> JsonRpcFutureResult {
+            match params.parse() {
+                Ok(params) => create_value_transfer(params),
+                Err(e) => Box::new(future::err(e)),
+            }
+        },
+    );
+    io.add_method("importAddress", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok(params) => import_address(params),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("createWallet", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok(params) => create_wallet(params),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("importMnemonic", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok(params) => import_mnemonic(params),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("exportMasterKey", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((password,)) => export_master_key(password),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method(
+        "setWalletPassword",
+        |params: Params| -> JsonRpcFutureResult {
+            match params.parse() {
+                Ok(params) => set_wallet_password(params),
+                Err(e) => Box::new(future::err(e)),
+            }
+        },
+    );
+    io.add_method("unlockWallet", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((password,)) => unlock_wallet(password),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method(
+        "getTransactionHistory",
+        |params: Params| -> JsonRpcFutureResult {
+            match params.parse() {
+                Ok((account, pagination)) => get_transaction_history(account, pagination),
+                Err(e) => Box::new(future::err(e)),
+            }
+        },
+    );
+    io.add_method("getBalance", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((BalanceOf::Account(account),)) => get_balance(account),
+            Ok((BalanceOf::Address(address),)) => get_address_balance(address),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("getUtxos", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((address, include_unconfirmed)) => get_utxos(address, include_unconfirmed),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("signTransaction", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok(params) => sign_transaction(params),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("sendDataRequest", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok(params) => send_data_request(params),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method(
+        "createDataRequest",
+        |params: Params| -> JsonRpcFutureResult {
+            match params.parse() {
+                Ok(params) => create_data_request(params),
+                Err(e) => Box::new(future::err(e)),
+            }
+        },
+    );
+    io.add_method(
+        "getDataRequestStatus",
+        |params: Params| -> JsonRpcFutureResult {
+            match params.parse() {
+                Ok((hash,)) => get_data_request_status(hash),
+                Err(e) => Box::new(future::err(e)),
+            }
+        },
+    );
+    io.add_method(
+        "getDataRequestResult",
+        |params: Params| -> JsonRpcFutureResult {
+            match params.parse() {
+                Ok((hash,)) => get_data_request_result(hash),
+                Err(e) => Box::new(future::err(e)),
+            }
+        },
+    );
+    io.add_method("getBlockChain", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((from, to)) => get_block_chain(from, to),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("getBlockByEpoch", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((epoch,)) => get_block_by_epoch(epoch),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("getEpoch", |_params: Params| -> JsonRpcFutureResult {
+        get_epoch()
+    });
+    io.add_method("getSupplyInfo", |_params: Params| -> JsonRpcFutureResult {
+        get_supply_info()
+    });
+    io.add_method("getNodeStatus", |_params: Params| -> JsonRpcFutureResult {
+        get_node_status()
+    });
+    io.add_method("getMetrics", |_params: Params| get_metrics());
+    io.add_method(
+        "getConsensusConstants",
+        |_params: Params| -> JsonRpcFutureResult { get_consensus_constants() },
+    );
+    io.add_method("getChainStats", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((from, to)) => get_chain_stats(from, to),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("rescan", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((from_epoch,)) => rescan(from_epoch),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method(
+        "getRescanStatus",
+        |_params: Params| -> JsonRpcFutureResult { get_rescan_status() },
+    );
+    io.add_method(
+        "getBlockTemplate",
+        |params: Params| -> JsonRpcFutureResult {
+            match params.parse() {
+                Ok((miner,)) => get_block_template(miner),
+                Err(e) => Box::new(future::err(e)),
+            }
+        },
+    );
+    io.add_method("submitBlock", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((block,)) => submit_block(block),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("banPeer", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok(params) => ban_peer(params),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("unbanPeer", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((address,)) => unban_peer(address),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
+    io.add_method("getBans", |_params: Params| -> JsonRpcFutureResult {
+        get_bans()
+    });
+    io.add_method("importBans", |params: Params| -> JsonRpcFutureResult {
+        match params.parse() {
+            Ok((bans,)) => import_bans(bans),
+            Err(e) => Box::new(future::err(e)),
+        }
+    });
 
     io
 }
@@ -44,7 +343,7 @@ pub enum InventoryItem {
 ///
 /// Returns a boolean indicating success.
 /* Test string:
-{"jsonrpc": "2.0", "method": "inventory", "params": {"block":{"header":{"block_header":{"version":1,"beacon":{"checkpoint":2,"hash_prev_block":{"SHA256":[4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4]}},"hash_merkle_root":{"SHA256":[3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3]}},"proof":{"block_sig":null,"influence":99999}},"txn_count":1,"txns":[null]}}, "id": 1}
+{"jsonrpc": "2.0", "method": "inventory", "params": {"block":{"header":{"block_header":{"version":1,"beacon":{"checkpoint":2,"hash_prev_block":{"SHA256":[4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4]}},"hash_merkle_root":{"SHA256":[3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3]},"utxo_merkle_root":{"SHA256":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}},"proof":{"block_sig":null,"influence":99999}},"txn_count":1,"txns":[null],"mint":{"epoch":2,"output":{"pkh":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],"value":0,"time_lock":2}}}}, "id": 1}
 */
 pub fn inventory(inv_elem: InventoryItem) -> Result<Value, jsonrpc_core::Error> {
     match inv_elem {
@@ -55,7 +354,12 @@ pub fn inventory(inv_elem: InventoryItem) -> Result<Value, jsonrpc_core::Error>
             let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
             // If this function was called asynchronously, it could wait for the result
             // But it's not so we just assume success
-            blocks_manager_addr.do_send(AddNewBlock { block });
+            blocks_manager_addr.do_send(AddNewBlock {
+                block,
+                received_at: Instant::now(),
+                trace: Trace::start(),
+                origin: None,
+            });
 
             // Returns a boolean indicating success
             Ok(Value::Bool(true))
@@ -72,130 +376,3063 @@ pub fn inventory(inv_elem: InventoryItem) -> Result<Value, jsonrpc_core::Error>
     }
 }
 
-#[cfg(test)]
-mod mock_actix {
-    pub struct System;
+/// Fee policy parameters: the values which are not present are left unchanged
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct FeePolicyParams {
+    /// Minimum fee for a transaction to be accepted into the mempool and relayed
+    #[serde(default)]
+    pub min_relay_fee: Option<u64>,
+    /// Minimum fee for a transaction to be included in the blocks mined by this node
+    #[serde(default)]
+    pub mining_min_fee: Option<u64>,
+}
 
-    pub struct SystemRegistry;
+/// Update the fee policy of the node at runtime.
+///
+/// Input: the minimum relay fee and/or the minimum fee for mining
+///
+/// Returns the fee policy applied from now on.
+/* Test string:
+{"jsonrpc": "2.0", "method": "setFeePolicy", "params": {"min_relay_fee": 10, "mining_min_fee": 20}, "id": 1}
+*/
+pub fn set_fee_policy(params: FeePolicyParams) -> JsonRpcFutureResult {
+    info!("Got fee policy from JSON-RPC: {:?}", params);
 
-    pub struct Addr;
+    // Get MempoolManager's address
+    let mempool_manager_addr = System::current().registry().get::<MempoolManager>();
+    let fut = mempool_manager_addr
+        .send(SetFeePolicy {
+            min_relay_fee: params.min_relay_fee,
+            mining_min_fee: params.mining_min_fee,
+        })
+        .then(|res| match res {
+            Ok(Ok(fee_policy)) => {
+                serde_json::to_value(fee_policy).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(mempool_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
 
-    impl System {
-        pub fn current() -> Self {
-            System
-        }
-        pub fn registry(&self) -> &SystemRegistry {
-            &SystemRegistry
-        }
-    }
+    Box::new(fut)
+}
 
-    impl SystemRegistry {
-        pub fn get<T>(&self) -> Addr {
-            Addr
+/// Estimate the fee rate needed for a transaction to be included within a number of epochs.
+///
+/// Input: the target number of epochs
+///
+/// Returns the estimated fee rate, or null if there is not enough data yet.
+/* Test string:
+{"jsonrpc": "2.0", "method": "estimateFee", "params": [3], "id": 1}
+*/
+pub fn estimate_fee(target_epochs: Epoch) -> JsonRpcFutureResult {
+    info!(
+        "Got fee estimation request from JSON-RPC: {}",
+        target_epochs
+    );
+
+    // Get MempoolManager's address
+    let mempool_manager_addr = System::current().registry().get::<MempoolManager>();
+    let fut = mempool_manager_addr
+        .send(EstimateFee { target_epochs })
+        .then(|res| match res {
+            Ok(Ok(fee_rate)) => Ok(fee_rate.map_or(Value::Null, Value::from)),
+            _ => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Pagination parameters
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Pagination {
+    /// Number of entries to skip
+    #[serde(default)]
+    pub offset: usize,
+    /// Maximum number of entries to return
+    #[serde(default = "default_pagination_limit")]
+    pub limit: usize,
+}
+
+/// Default maximum number of entries returned in a page
+fn default_pagination_limit() -> usize {
+    50
+}
+
+/// Get a page of the hashes of the transactions in the mempool, sorted by arrival order.
+///
+/// Input: the pagination parameters
+///
+/// Returns the total number of transactions in the mempool and the hashes of the page.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getMempool", "params": [{"offset": 0, "limit": 10}], "id": 1}
+*/
+pub fn get_mempool(pagination: Pagination) -> JsonRpcFutureResult {
+    info!("Got mempool request from JSON-RPC: {:?}", pagination);
+
+    // Get MempoolManager's address
+    let mempool_manager_addr = System::current().registry().get::<MempoolManager>();
+    let fut = mempool_manager_addr
+        .send(GetMempool {
+            offset: pagination.offset,
+            limit: pagination.limit,
+        })
+        .then(|res| match res {
+            Ok(Ok(page)) => {
+                serde_json::to_value(page).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(mempool_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the details of a transaction in the mempool.
+///
+/// Input: the hash of the transaction
+///
+/// Returns the size, fee, fee rate, arrival time and dependencies of the transaction, or null if
+/// the transaction is not in the mempool.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getMempoolEntry", "params": [{"SHA256": [1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]}], "id": 1}
+*/
+pub fn get_mempool_entry(hash: Hash) -> JsonRpcFutureResult {
+    info!("Got mempool entry request from JSON-RPC: {:?}", hash);
+
+    // Get MempoolManager's address
+    let mempool_manager_addr = System::current().registry().get::<MempoolManager>();
+    let fut = mempool_manager_addr
+        .send(GetMempoolEntry { hash })
+        .then(|res| match res {
+            Ok(Ok(entry)) => {
+                serde_json::to_value(entry).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(mempool_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the status of a transaction originated by this node.
+///
+/// Input: the hash of the transaction
+///
+/// Returns the status of the transaction (pending, announced to some peers, confirmed at some
+/// depth or rejected for some reason), or null if the transaction was not originated by this
+/// node.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getTransactionStatus", "params": [{"SHA256": [1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]}], "id": 1}
+*/
+pub fn get_transaction_status(hash: Hash) -> JsonRpcFutureResult {
+    info!("Got transaction status request from JSON-RPC: {:?}", hash);
+
+    // Get MempoolManager's address
+    let mempool_manager_addr = System::current().registry().get::<MempoolManager>();
+    let fut = mempool_manager_addr
+        .send(GetTransactionStatus { hash })
+        .then(|res| match res {
+            Ok(Ok(status)) => {
+                serde_json::to_value(status).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(mempool_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Submit again a transaction originated by this node which has not been confirmed (e.g.
+/// because it expired from the mempool), paying a higher fee.
+///
+/// Input: the hash of the transaction and the new fee
+///
+/// Returns the hash of the transaction submitted again.
+/* Test string:
+{"jsonrpc": "2.0", "method": "resubmitTransaction", "params": [{"SHA256": [1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]}, 20], "id": 1}
+*/
+pub fn resubmit_transaction(hash: Hash, fee: u64) -> JsonRpcFutureResult {
+    info!(
+        "Got resubmit transaction request from JSON-RPC: {:?}, fee {}",
+        hash, fee
+    );
+
+    // Get MempoolManager's address
+    let mempool_manager_addr = System::current().registry().get::<MempoolManager>();
+    let fut = mempool_manager_addr
+        .send(ResubmitTransaction { hash, fee })
+        .then(|res| match res {
+            Ok(Ok(hash)) => {
+                serde_json::to_value(hash).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(mempool_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the transaction history of an account of the wallet, most recent transactions first.
+///
+/// Input: the account and the pagination parameters
+///
+/// Returns a list of transactions with their status and number of confirmations.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getTransactionHistory", "params": [0, {"offset": 0, "limit": 10}], "id": 1}
+*/
+pub fn get_transaction_history(account: u32, pagination: Pagination) -> JsonRpcFutureResult {
+    info!(
+        "Got transaction history request from JSON-RPC: account {}, {:?}",
+        account, pagination
+    );
+
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(GetTransactionHistory {
+            account,
+            offset: pagination.offset,
+            limit: pagination.limit,
+        })
+        .then(|res| match res {
+            Ok(Ok(history)) => {
+                serde_json::to_value(history).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the balance of an account of the wallet, that is, the balance of its derived and
+/// watch-only addresses.
+///
+/// Input: the account
+///
+/// Returns the value which can be spent (`available`) and the value whose time lock has not
+/// expired yet (`locked`), along with the checkpoint of the UTXO set they were read from
+/// (`epoch`).
+/* Test string:
+{"jsonrpc": "2.0", "method": "getBalance", "params": [0], "id": 1}
+*/
+pub fn get_balance(account: u32) -> JsonRpcFutureResult {
+    info!("Got balance request from JSON-RPC: account {}", account);
+
+    // Get WalletManager's and UtxoManager's addresses
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let utxo_manager_addr = System::current().registry().get::<UtxoManager>();
+    let fut = wallet_manager_addr
+        .send(GetAddresses { account })
+        .then(|res| match res {
+            Ok(Ok(addresses)) => Ok(addresses),
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        })
+        .and_then(move |addresses| {
+            utxo_manager_addr
+                .send(GetBalance { addresses })
+                .then(|res| match res {
+                    Ok(Ok(balance)) => serde_json::to_value(balance)
+                        .map_err(|_| jsonrpc_core::Error::internal_error()),
+                    Ok(Err(e)) => Err(utxo_manager_error(e)),
+                    Err(_) => Err(jsonrpc_core::Error::internal_error()),
+                })
+        });
+
+    Box::new(fut)
+}
+
+/// Target of a balance request: either an account of the wallet or any address
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BalanceOf {
+    /// Account of the wallet
+    Account(u32),
+    /// Address, which does not need to belong to the wallet
+    Address(PublicKeyHash),
+}
+
+/// Get the balance of any address, which does not need to belong to the wallet.
+///
+/// Input: the address
+///
+/// Returns the value which can be spent (`available`) and the value whose time lock has not
+/// expired yet (`locked`), along with the checkpoint of the UTXO set they were read from
+/// (`epoch`).
+/* Test string:
+{"jsonrpc": "2.0", "method": "getBalance", "params": [[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]], "id": 1}
+*/
+pub fn get_address_balance(address: PublicKeyHash) -> JsonRpcFutureResult {
+    info!("Got balance request from JSON-RPC: address {:?}", address);
+
+    // Get UtxoManager's address
+    let utxo_manager_addr = System::current().registry().get::<UtxoManager>();
+    let fut = utxo_manager_addr
+        .send(GetAddressBalance { address })
+        .then(|res| match res {
+            Ok(Ok(balance)) => {
+                serde_json::to_value(balance).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(utxo_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the unspent transaction outputs of any address, so external wallets can build
+/// transactions without importing their keys into the node.
+///
+/// Input: the address and whether to include the outputs which have not been confirmed yet
+///
+/// Returns the checkpoint of the UTXO set the outputs were read from (`epoch`) and the outputs
+/// (`utxos`), along with the block which created them, their value, their time lock and their
+/// number of confirmations.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getUtxos", "params": [[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1], false], "id": 1}
+*/
+pub fn get_utxos(address: PublicKeyHash, include_unconfirmed: bool) -> JsonRpcFutureResult {
+    info!(
+        "Got UTXOs request from JSON-RPC: address {:?}, include unconfirmed: {}",
+        address, include_unconfirmed
+    );
+
+    // Get UtxoManager's address
+    let utxo_manager_addr = System::current().registry().get::<UtxoManager>();
+    let fut = utxo_manager_addr
+        .send(GetUtxos {
+            address,
+            include_unconfirmed,
+        })
+        .then(|res| match res {
+            Ok(Ok(utxos)) => {
+                serde_json::to_value(utxos).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(utxo_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Transaction signing parameters
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SignTransactionParams {
+    /// Account whose key signs the transaction
+    #[serde(default)]
+    pub account: u32,
+    /// Index of the signing key in the account
+    #[serde(default)]
+    pub key_index: u32,
+    /// Transaction to sign, with the signatures collected so far
+    pub transaction: PartiallySignedTransaction,
+}
+
+/// Sign a transaction spending a multi-signature output with a key of an account of the wallet.
+/// Every owner of the output calls this method in turn with the transaction returned by the
+/// previous one, until it has as many signatures as required.
+///
+/// Input: the transaction with the signatures collected so far and, optionally, the account and
+/// the index of the signing key (0 by default)
+///
+/// Returns the transaction with the new signature and whether it has all the required signatures.
+/* Test string:
+{"jsonrpc": "2.0", "method": "signTransaction", "params": {"account": 0, "key_index": 0, "transaction": {"hash": {"SHA256": [1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]}, "condition": {"required": 1, "pkhs": [[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]]}, "signatures": []}}, "id": 1}
+*/
+pub fn sign_transaction(params: SignTransactionParams) -> JsonRpcFutureResult {
+    info!(
+        "Got transaction signing request from JSON-RPC: account {}, key {}, transaction {:?}",
+        params.account, params.key_index, params.transaction.hash
+    );
+
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(SignTransaction {
+            account: params.account,
+            key_index: params.key_index,
+            transaction: params.transaction,
+        })
+        .then(|res| match res {
+            Ok(Ok(signed)) => {
+                serde_json::to_value(signed).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Create a new account in the wallet, with its own key derivation branch.
+///
+/// Input: the name of the account
+///
+/// Returns the new account.
+/* Test string:
+{"jsonrpc": "2.0", "method": "createAccount", "params": ["savings"], "id": 1}
+*/
+pub fn create_account(name: String) -> JsonRpcFutureResult {
+    info!("Got account creation request from JSON-RPC: {}", name);
+
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(CreateAccount { name })
+        .then(|res| match res {
+            Ok(Ok(account)) => {
+                serde_json::to_value(account).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the list of accounts of the wallet.
+///
+/// Returns the list of accounts.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getAccounts", "id": 1}
+*/
+pub fn get_accounts() -> JsonRpcFutureResult {
+    info!("Got accounts request from JSON-RPC");
+
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr.send(GetAccounts).then(|res| match res {
+        Ok(Ok(accounts)) => {
+            serde_json::to_value(accounts).map_err(|_| jsonrpc_core::Error::internal_error())
         }
-    }
+        Ok(Err(e)) => Err(wallet_manager_error(e)),
+        Err(_) => Err(jsonrpc_core::Error::internal_error()),
+    });
 
-    impl Addr {
-        pub fn do_send<T>(&self, _msg: T) {}
-    }
+    Box::new(fut)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Derive a new address for an account of the wallet from the next signing key of the account
+/// (`m/44'/4919'/account'/0/key_index`). The balance of the account includes the outputs paid to
+/// its derived addresses.
+///
+/// Input: the account
+///
+/// Returns the new address.
+/* Test string:
+{"jsonrpc": "2.0", "method": "createAddress", "params": [0], "id": 1}
+*/
+pub fn create_address(account: u32) -> JsonRpcFutureResult {
+    info!(
+        "Got address creation request from JSON-RPC: account {}",
+        account
+    );
 
-    #[test]
-    fn empty_string_parse_error() {
-        // An empty message should return a parse error
-        let empty_string = "";
-        let parse_error =
-            r#"{"jsonrpc":"2.0","error":{"code":-32700,"message":"Parse error"},"id":null}"#
-                .to_string();
-        let io = jsonrpc_io_handler();
-        let response = io.handle_request_sync(empty_string);
-        assert_eq!(response, Some(parse_error));
-    }
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(CreateAddress { account })
+        .then(|res| match res {
+            Ok(Ok(address)) => {
+                serde_json::to_value(address).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
 
-    #[test]
-    fn inventory_method() {
-        // The expected behaviour of the inventory method
-        use witnet_data_structures::chain::*;
+    Box::new(fut)
+}
 
-        let block = Block {
-            header: BlockHeaderWithProof {
-                block_header: BlockHeader {
-                    version: 1,
-                    beacon: CheckpointBeacon {
-                        checkpoint: 2,
-                        hash_prev_block: Hash::SHA256([4; 32]),
-                    },
-                    hash_merkle_root: Hash::SHA256([3; 32]),
-                },
-                proof: LeadershipProof {
-                    block_sig: None,
-                    influence: 99999,
-                },
-            },
-            txn_count: 1,
-            txns: vec![Transaction],
-        };
+/// Value transfer parameters
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CreateValueTransferParams {
+    /// Account which pays for the value transfer
+    #[serde(default)]
+    pub account: u32,
+    /// Outputs of the value transfer
+    pub outputs: Vec<ValueTransferOutput>,
+    /// Fee of the value transfer
+    #[serde(default)]
+    pub fee: u64,
+}
 
-        let inv_elem = InventoryItem::Block(block);
-        let s = serde_json::to_string(&inv_elem).unwrap();
-        let msg = format!(
-            r#"{{"jsonrpc":"2.0","method":"inventory","params":{},"id":1}}"#,
-            s
-        );
+/// Create a value transfer paid by an account of the wallet, which is added to the history of
+/// the account as pending.
+///
+/// Input: the outputs of the value transfer and, optionally, the account which pays for it (0 by
+/// default) and its fee (0 by default)
+///
+/// Returns the hash of the value transfer.
+/* Test string:
+{"jsonrpc": "2.0", "method": "createValueTransfer", "params": {"account": 1, "outputs": [{"pkh": [1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1], "value": 1000}], "fee": 10}, "id": 1}
+*/
+pub fn create_value_transfer(params: CreateValueTransferParams) -> JsonRpcFutureResult {
+    info!(
+        "Got value transfer request from JSON-RPC: account {}, {} outputs, fee {}",
+        params.account,
+        params.outputs.len(),
+        params.fee
+    );
 
-        // Expected result: true
-        let expected = r#"{"jsonrpc":"2.0","result":true,"id":1}"#.to_string();
-        let io = jsonrpc_io_handler();
-        let response = io.handle_request_sync(&msg);
-        assert_eq!(response, Some(expected));
-    }
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(CreateValueTransfer {
+            account: params.account,
+            outputs: params.outputs,
+            fee: params.fee,
+        })
+        .then(|res| match res {
+            Ok(Ok(hash)) => {
+                serde_json::to_value(hash).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
 
-    #[test]
-    fn inventory_invalid_params() {
-        // What happens when the inventory method is called with an invalid parameter?
-        let msg = r#"{"jsonrpc":"2.0","method":"inventory","params":{ "header": 0 },"id":1}"#;
-        let expected = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params: unknown variant `header`, expected one of"#.to_string();
-        let io = jsonrpc_io_handler();
-        let response = io.handle_request_sync(&msg);
-        // Compare only the first N characters
-        let response =
-            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
-        assert_eq!(response, Some(expected));
-    }
+    Box::new(fut)
+}
 
-    #[test]
-    fn inventory_unimplemented_type() {
-        // What happens when the inventory method is called with an unimplemented type?
-        let msg = r#"{"jsonrpc":"2.0","method":"inventory","params":{ "tx": null },"id":1}"#;
-        let expected =
-            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Item type not implemented"#
-                .to_string();
-        let io = jsonrpc_io_handler();
-        let response = io.handle_request_sync(&msg);
-        // Compare only the first N characters
-        let response =
-            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
-        assert_eq!(response, Some(expected));
-    }
+/// Watch-only address parameters
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ImportAddressParams {
+    /// Address
+    pub address: PublicKeyHash,
+    /// Account to which the transactions of the address are assigned
+    #[serde(default)]
+    pub account: u32,
+    /// Label of the address
+    #[serde(default)]
+    pub label: String,
+}
 
-    #[test]
-    fn serialize_block() {
-        // Check that the serialization of `Block` doesn't change
-        use witnet_data_structures::chain::*;
+/// Import a watch-only address into the wallet, whose private key is held elsewhere.
+///
+/// Input: the address and, optionally, the account (0 by default) and a label
+///
+/// Returns a boolean indicating success.
+/* Test string:
+{"jsonrpc": "2.0", "method": "importAddress", "params": {"address": [1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1], "account": 0, "label": "exchange"}, "id": 1}
+*/
+pub fn import_address(params: ImportAddressParams) -> JsonRpcFutureResult {
+    info!("Got watch-only address from JSON-RPC: {:?}", params);
 
-        let block = Block {
-            header: BlockHeaderWithProof {
-                block_header: BlockHeader {
-                    version: 1,
-                    beacon: CheckpointBeacon {
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(ImportAddress {
+            address: params.address,
+            account: params.account,
+            label: params.label,
+        })
+        .then(|res| match res {
+            Ok(Ok(())) => Ok(Value::Bool(true)),
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Wallet creation parameters
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CreateWalletParams {
+    /// Password protecting the new keys
+    pub password: String,
+    /// Password of the current keys, required if they are protected by one
+    #[serde(default)]
+    pub current_password: Option<String>,
+    /// Confirmation to overwrite the current keys, required if they are not protected by a
+    /// password
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// Create a new wallet: the keys of the wallet are replaced with new keys derived from a new
+/// random mnemonic, encrypted with a password. The transaction history of the previous keys is
+/// discarded.
+///
+/// Input: the password of the new keys and, if the current keys are protected by a password,
+/// that password or else the confirmation to overwrite them
+///
+/// Returns the mnemonic of the new keys, which must be written down to restore them.
+/* Test string:
+{"jsonrpc": "2.0", "method": "createWallet", "params": {"password": "correct horse battery staple", "overwrite": true}, "id": 1}
+*/
+pub fn create_wallet(params: CreateWalletParams) -> JsonRpcFutureResult {
+    // The passwords must never be logged
+    info!("Got wallet creation request from JSON-RPC");
+
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(CreateWallet {
+            password: params.password,
+            current_password: params.current_password,
+            overwrite: params.overwrite,
+        })
+        .then(|res| match res {
+            Ok(Ok(mnemonic)) => Ok(Value::String(mnemonic)),
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Mnemonic import parameters
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ImportMnemonicParams {
+    /// Mnemonic of the keys to restore
+    pub mnemonic: String,
+    /// Password protecting the restored keys
+    pub password: String,
+    /// Password of the current keys, required if they are protected by one
+    #[serde(default)]
+    pub current_password: Option<String>,
+    /// Confirmation to overwrite the current keys, required if they are not protected by a
+    /// password
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// Restore a wallet from its mnemonic: the keys of the wallet are replaced with the keys derived
+/// from the mnemonic, encrypted with a password. The transaction history of the previous keys is
+/// discarded, and the consolidated blocks are rescanned to rebuild the history of the restored
+/// keys.
+///
+/// Input: the mnemonic, the password of the restored keys and, if the current keys are
+/// protected by a password, that password or else the confirmation to overwrite them
+///
+/// Returns a boolean indicating success.
+/* Test string:
+{"jsonrpc": "2.0", "method": "importMnemonic", "params": {"mnemonic": "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about", "password": "correct horse battery staple", "overwrite": true}, "id": 1}
+*/
+pub fn import_mnemonic(params: ImportMnemonicParams) -> JsonRpcFutureResult {
+    // The mnemonic and the passwords must never be logged
+    info!("Got mnemonic import request from JSON-RPC");
+
+    if Mnemonic::from_phrase(&params.mnemonic).is_err() {
+        return Box::new(future::err(jsonrpc_core::Error::invalid_params(
+            "Invalid mnemonic",
+        )));
+    }
+
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(ImportMnemonic {
+            mnemonic: params.mnemonic,
+            password: params.password,
+            current_password: params.current_password,
+            overwrite: params.overwrite,
+        })
+        .then(|res| match res {
+            Ok(Ok(())) => Ok(Value::Bool(true)),
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Export the keys of the wallet, so they can be backed up. The password of the keys is required
+/// even if it has already been entered.
+///
+/// Input: the password of the keys
+///
+/// Returns the mnemonic of the keys (null if the keys were generated before mnemonics were
+/// supported) and the master key (its chain code followed by its secret key, hex encoded).
+/* Test string:
+{"jsonrpc": "2.0", "method": "exportMasterKey", "params": ["correct horse battery staple"], "id": 1}
+*/
+pub fn export_master_key(password: String) -> JsonRpcFutureResult {
+    // The password must never be logged
+    info!("Got master key export request from JSON-RPC");
+
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(ExportMasterKey { password })
+        .then(|res| match res {
+            Ok(Ok(backup)) => {
+                serde_json::to_value(backup).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Wallet password parameters
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SetWalletPasswordParams {
+    /// New password of the keys
+    pub password: String,
+    /// Password of the current keys, required if they are protected by one
+    #[serde(default)]
+    pub current_password: Option<String>,
+}
+
+/// Set the password of the wallet: the current keys are encrypted with a new password, keeping
+/// their transaction history, so the keys created the first time the node is started can be
+/// protected and exported without replacing them.
+///
+/// Input: the new password and, if the current keys are protected by a password, that password
+///
+/// Returns a boolean indicating success.
+/* Test string:
+{"jsonrpc": "2.0", "method": "setWalletPassword", "params": {"password": "correct horse battery staple"}, "id": 1}
+*/
+pub fn set_wallet_password(params: SetWalletPasswordParams) -> JsonRpcFutureResult {
+    // The passwords must never be logged
+    info!("Got wallet password request from JSON-RPC");
+
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(SetWalletPassword {
+            password: params.password,
+            current_password: params.current_password,
+        })
+        .then(|res| match res {
+            Ok(Ok(())) => Ok(Value::Bool(true)),
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Unlock the keys of the wallet: keys encrypted with a password are locked when the node starts,
+/// and they cannot sign transactions until they are decrypted with their password.
+///
+/// Input: the password of the keys
+///
+/// Returns a boolean indicating success.
+/* Test string:
+{"jsonrpc": "2.0", "method": "unlockWallet", "params": ["correct horse battery staple"], "id": 1}
+*/
+pub fn unlock_wallet(password: String) -> JsonRpcFutureResult {
+    // The password must never be logged
+    info!("Got wallet unlock request from JSON-RPC");
+
+    // Get WalletManager's address
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let fut = wallet_manager_addr
+        .send(UnlockWallet { password })
+        .then(|res| match res {
+            Ok(Ok(())) => Ok(Value::Bool(true)),
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Data request parameters
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SendDataRequestParams {
+    /// Account which pays for the data request
+    #[serde(default)]
+    pub account: u32,
+    /// Data request
+    pub data_request: DataRequestOutput,
+}
+
+/// Post a new data request, funded by an account of the wallet, and announce it to the peers.
+///
+/// Input: the data request and, optionally, the account which pays for it (0 by default)
+///
+/// Returns the hash of the data request.
+/* Test string:
+{"jsonrpc": "2.0", "method": "sendDataRequest", "params": {"account": 0, "data_request": {"data_request": {"not_before": 0, "retrieve": [{"kind": "HTTP-GET", "url": "https://openweathermap.org/data/2.5/weather?id=2950159&appid=b6907d289e10d714a6e88b30761fae22", "script": [150,3,4,146,0,167,119,101,97,116,104,101,114,4,146,0,164,116,101,109,112,2]}], "aggregate": {"script": []}, "consensus": {"script": []}, "deliver": []}, "value": 1000, "witnesses": 2, "commit_fee": 1, "reveal_fee": 1, "tally_fee": 1}}, "id": 1}
+*/
+pub fn send_data_request(params: SendDataRequestParams) -> JsonRpcFutureResult {
+    info!("Got data request from JSON-RPC: {:?}", params);
+
+    // Reject malformed data requests before funding them
+    if let Err(e) = validate_data_request(&params.data_request) {
+        return Box::new(future::err(data_requests_manager_error(e)));
+    }
+
+    // Get WalletManager's and DataRequestsManager's addresses
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let data_requests_manager_addr = System::current().registry().get::<DataRequestsManager>();
+    let data_request = params.data_request.clone();
+    let fut = wallet_manager_addr
+        .send(FundDataRequest {
+            account: params.account,
+            data_request: params.data_request,
+        })
+        .then(|res| match res {
+            Ok(Ok(hash)) => Ok(hash),
+            Ok(Err(e)) => Err(wallet_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        })
+        .and_then(move |hash| {
+            data_requests_manager_addr
+                .send(PostDataRequest { hash, data_request })
+                .then(move |res| match res {
+                    Ok(Ok(())) => serde_json::to_value(hash)
+                        .map_err(|_| jsonrpc_core::Error::internal_error()),
+                    Ok(Err(e)) => Err(data_requests_manager_error(e)),
+                    Err(_) => Err(jsonrpc_core::Error::internal_error()),
+                })
+        });
+
+    Box::new(fut)
+}
+
+/// High-level description of a data request, whose value and fees are estimated by the node
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CreateDataRequestParams {
+    /// Account which pays for the data request
+    #[serde(default)]
+    pub account: u32,
+    /// Timestamp before which the data must not be retrieved
+    #[serde(default)]
+    pub not_before: u64,
+    /// Sources of the data
+    pub retrieve: Vec<RADRetrieve>,
+    /// Aggregation of the retrieved data
+    pub aggregate: RADAggregate,
+    /// Consensus on the results of the witnesses
+    pub consensus: RADConsensus,
+    /// Destinations of the result
+    #[serde(default)]
+    pub deliver: Vec<RADDeliver>,
+    /// Number of witnesses
+    pub witnesses: u16,
+    /// Collateral locked by every witness
+    #[serde(default)]
+    pub collateral: u64,
+    /// Number of epochs within which the transactions of the data request should be included
+    #[serde(default = "default_target_epochs")]
+    pub target_epochs: Epoch,
+}
+
+fn default_target_epochs() -> Epoch {
+    3
+}
+
+/// Fee rate used when the fee estimator does not have enough data yet
+pub const FALLBACK_FEE_RATE: u64 = 1;
+
+/// Data request built from a high-level description, along with its estimated cost
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CreatedDataRequest {
+    /// Hash of the data request
+    pub hash: Hash,
+    /// Data request, ready to be broadcast with `sendDataRequest`
+    pub data_request: DataRequestOutput,
+    /// Fee rate used to estimate the fees of the transactions of the data request
+    pub fee_rate: u64,
+    /// Split of the value of the data request into fees and rewards
+    pub cost: ValueSplit,
+}
+
+/// Build a data request from a high-level description, with the fees of its commit, reveal and
+/// tally transactions estimated from a fee rate and the minimum value which pays for those fees
+/// and the rewards of its witnesses, checking that its RADON scripts are valid
+pub fn build_data_request(
+    params: &CreateDataRequestParams,
+    fee_rate: u64,
+) -> Result<(DataRequestOutput, ValueSplit), DataRequestsManagerError> {
+    // FIXME(#99): estimate the weight of every kind of transaction once they are defined
+    let fee = fee_rate.saturating_mul(transaction_weight(&Transaction));
+    let value = min_value(params.witnesses, fee, fee, fee, params.collateral)
+        .ok_or(DataRequestsManagerError::ValueOverflow)?;
+    let data_request = DataRequestOutput {
+        data_request: RADRequest {
+            not_before: params.not_before,
+            retrieve: params.retrieve.clone(),
+            aggregate: params.aggregate.clone(),
+            consensus: params.consensus.clone(),
+            deliver: params.deliver.clone(),
+        },
+        value,
+        witnesses: params.witnesses,
+        commit_fee: fee,
+        reveal_fee: fee,
+        tally_fee: fee,
+        collateral: params.collateral,
+    };
+    validate_data_request(&data_request)?;
+    let cost = value_split(&data_request)?;
+
+    Ok((data_request, cost))
+}
+
+/// Build a data request from a high-level description, estimating its fees with the fee
+/// estimator and its value from the rewards needed by its witnesses, and fund it with an account
+/// of the wallet.
+///
+/// Input: the sources, aggregation, consensus and number of witnesses of the data request and,
+/// optionally, the account which pays for it (0 by default), the collateral of the witnesses
+/// (0 by default) and the number of epochs within which its transactions should be included (3 by
+/// default)
+///
+/// Returns the hash of the data request, the data request ready to be broadcast, the fee rate
+/// used and the split of its value into fees and rewards.
+/* Test string:
+{"jsonrpc": "2.0", "method": "createDataRequest", "params": {"account": 0, "retrieve": [{"kind": "HTTP-GET", "url": "https://openweathermap.org/data/2.5/weather?id=2950159&appid=b6907d289e10d714a6e88b30761fae22", "script": [150,3,4,146,0,167,119,101,97,116,104,101,114,4,146,0,164,116,101,109,112,2]}], "aggregate": {"script": []}, "consensus": {"script": []}, "witnesses": 2, "collateral": 1000, "target_epochs": 3}, "id": 1}
+*/
+pub fn create_data_request(params: CreateDataRequestParams) -> JsonRpcFutureResult {
+    info!("Got data request description from JSON-RPC: {:?}", params);
+
+    // Reject malformed data requests before asking for the fee rate
+    if let Err(e) = build_data_request(&params, FALLBACK_FEE_RATE) {
+        return Box::new(future::err(data_requests_manager_error(e)));
+    }
+
+    // Get MempoolManager's and WalletManager's addresses
+    let mempool_manager_addr = System::current().registry().get::<MempoolManager>();
+    let wallet_manager_addr = System::current().registry().get::<WalletManager>();
+    let account = params.account;
+    let fut = mempool_manager_addr
+        .send(EstimateFee {
+            target_epochs: params.target_epochs,
+        })
+        .then(move |res| match res {
+            Ok(Ok(fee_rate)) => {
+                let fee_rate = fee_rate.unwrap_or(FALLBACK_FEE_RATE);
+                build_data_request(&params, fee_rate)
+                    .map(|(data_request, cost)| (data_request, cost, fee_rate))
+                    .map_err(data_requests_manager_error)
+            }
+            Ok(Err(e)) => Err(mempool_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        })
+        .and_then(move |(data_request, cost, fee_rate)| {
+            wallet_manager_addr
+                .send(FundDataRequest {
+                    account,
+                    data_request: data_request.clone(),
+                })
+                .then(move |res| match res {
+                    Ok(Ok(hash)) => serde_json::to_value(CreatedDataRequest {
+                        hash,
+                        data_request,
+                        fee_rate,
+                        cost,
+                    })
+                    .map_err(|_| jsonrpc_core::Error::internal_error()),
+                    Ok(Err(e)) => Err(wallet_manager_error(e)),
+                    Err(_) => Err(jsonrpc_core::Error::internal_error()),
+                })
+        });
+
+    Box::new(fut)
+}
+
+/// Get the status of a data request.
+///
+/// Input: the hash of the data request
+///
+/// Returns the current stage of the data request (commit, reveal, tally or finished) and the
+/// number of witnesses requested, committed and revealed, or null if the data request is not
+/// known to this node.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getDataRequestStatus", "params": [{"SHA256": [1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]}], "id": 1}
+*/
+pub fn get_data_request_status(hash: Hash) -> JsonRpcFutureResult {
+    info!("Got data request status request from JSON-RPC: {:?}", hash);
+
+    // Get DataRequestsManager's address
+    let data_requests_manager_addr = System::current().registry().get::<DataRequestsManager>();
+    let fut = data_requests_manager_addr
+        .send(GetDataRequestStatus { hash })
+        .then(|res| match res {
+            Ok(Ok(status)) => {
+                serde_json::to_value(status).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(data_requests_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the result of a data request.
+///
+/// Input: the hash of the data request
+///
+/// Returns the tallied result, encoded using MessagePack, or null if the data request is not
+/// known to this node or it has not been tallied yet.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getDataRequestResult", "params": [{"SHA256": [1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]}], "id": 1}
+*/
+pub fn get_data_request_result(hash: Hash) -> JsonRpcFutureResult {
+    info!("Got data request result request from JSON-RPC: {:?}", hash);
+
+    // Get DataRequestsManager's address
+    let data_requests_manager_addr = System::current().registry().get::<DataRequestsManager>();
+    let fut = data_requests_manager_addr
+        .send(GetDataRequestResult { hash })
+        .then(|res| match res {
+            Ok(Ok(result)) => {
+                serde_json::to_value(result).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(data_requests_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the hashes of the blocks of the local chain consolidated in a range of epochs.
+///
+/// Input: the first and the last epoch of the range (at most 1000 epochs)
+///
+/// Returns the checkpoint of the tip of the local chain the blocks were read from (`epoch`) and
+/// the epoch and hash of every block in the range up to that checkpoint, sorted by epoch. The
+/// empty epochs are skipped.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getBlockChain", "params": [0, 99], "id": 1}
+*/
+pub fn get_block_chain(from: Epoch, to: Epoch) -> JsonRpcFutureResult {
+    info!("Got block chain request from JSON-RPC: {}-{}", from, to);
+
+    // Get BlocksManager's address
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    let fut = blocks_manager_addr
+        .send(GetBlockChain { from, to })
+        .then(|res| match res {
+            Ok(Ok(block_chain)) => {
+                serde_json::to_value(block_chain).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(blocks_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Block consolidated for an epoch
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EpochBlock {
+    /// Epoch
+    pub epoch: Epoch,
+    /// Hash of the block, or None if the epoch was empty
+    pub hash: Option<Hash>,
+    /// Block, or None if the epoch was empty
+    pub block: Option<Block>,
+}
+
+/// Get the block consolidated for an epoch.
+///
+/// Input: the epoch
+///
+/// Returns the epoch along with the hash and the block consolidated for it, which are null if the
+/// epoch was empty.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getBlockByEpoch", "params": [2], "id": 1}
+*/
+pub fn get_block_by_epoch(epoch: Epoch) -> JsonRpcFutureResult {
+    info!("Got block by epoch request from JSON-RPC: {}", epoch);
+
+    // Get BlocksManager's address
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    let fut = blocks_manager_addr
+        .send(GetBlockHashByEpoch { epoch })
+        .then(|res| match res {
+            Ok(Ok(hash)) => Ok(hash),
+            Ok(Err(e)) => Err(blocks_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        })
+        .and_then(move |hash| -> JsonRpcFutureResult {
+            let hash = match hash {
+                Some(hash) => hash,
+                None => {
+                    return Box::new(future::result(
+                        serde_json::to_value(EpochBlock {
+                            epoch,
+                            hash: None,
+                            block: None,
+                        })
+                        .map_err(|_| jsonrpc_core::Error::internal_error()),
+                    ));
+                }
+            };
+
+            // The consolidated blocks are read from storage
+            let Hash::SHA256(key) = hash;
+            let storage_manager_addr = System::current().registry().get::<StorageManager>();
+            let fut = storage_manager_addr
+                .send(Get::<Block>::new(key.to_vec()))
+                .then(move |res| match res {
+                    Ok(Ok(Some(block))) => serde_json::to_value(EpochBlock {
+                        epoch,
+                        hash: Some(hash),
+                        block: Some(block),
+                    })
+                    .map_err(|_| jsonrpc_core::Error::internal_error()),
+                    _ => Err(jsonrpc_core::Error::internal_error()),
+                });
+
+            Box::new(fut)
+        });
+
+    Box::new(fut)
+}
+
+/// Get the current epoch and the timestamp of the next checkpoint.
+///
+/// Returns the current epoch (null if checkpoint zero is still in the future) and the timestamp
+/// of the next checkpoint.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getEpoch", "id": 1}
+*/
+pub fn get_epoch() -> JsonRpcFutureResult {
+    info!("Got epoch request from JSON-RPC");
+
+    // Get EpochManager's address
+    let epoch_manager_addr = System::current().registry().get::<EpochManager>();
+    let fut = epoch_manager_addr.send(GetEpochInfo).then(|res| match res {
+        Ok(Ok(epoch_info)) => {
+            serde_json::to_value(epoch_info).map_err(|_| jsonrpc_core::Error::internal_error())
+        }
+        _ => Err(jsonrpc_core::Error::internal_error()),
+    });
+
+    Box::new(fut)
+}
+
+/// Get the supply issued by the consolidated blocks of the local chain.
+///
+/// Returns the checkpoint of the tip of the local chain, the issued supply, the part of it which
+/// is locked until the rewards mature, the supply still to be issued, the total supply and the
+/// remaining emission curve (ranges of epochs with the same block reward).
+/* Test string:
+{"jsonrpc": "2.0", "method": "getSupplyInfo", "id": 1}
+*/
+pub fn get_supply_info() -> JsonRpcFutureResult {
+    info!("Got supply info request from JSON-RPC");
+
+    // Get BlocksManager's address
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    let fut = blocks_manager_addr
+        .send(GetSupplyInfo)
+        .then(|res| match res {
+            Ok(Ok(supply_info)) => {
+                serde_json::to_value(supply_info).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            _ => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the status of the node: the synchronization state of the local chain and whether the node
+/// is isolated from the network.
+///
+/// Returns the synchronization state, the reason of the isolation (null if the node is not
+/// isolated), the current epoch, the beacon of the tip of the local chain, the highest epoch
+/// reported by the peers and the number of consolidated outbound sessions.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getNodeStatus", "id": 1}
+*/
+pub fn get_node_status() -> JsonRpcFutureResult {
+    info!("Got node status request from JSON-RPC");
+
+    // Get BlocksManager's address
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    let fut = blocks_manager_addr
+        .send(GetNodeStatus)
+        .then(|res| match res {
+            Ok(Ok(status)) => {
+                serde_json::to_value(status).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            _ => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the counters of the events that the node shed under load, of the storage read cache, of
+/// the relay of the block candidates, of the isolation of the node and of the handlers which
+/// exceeded their time budget.
+///
+/// Returns the current value of every counter since the node was started.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getMetrics", "id": 1}
+*/
+pub fn get_metrics() -> Result<Value, jsonrpc_core::Error> {
+    info!("Got metrics request from JSON-RPC");
+
+    serde_json::to_value(metrics::snapshot()).map_err(|_| jsonrpc_core::Error::internal_error())
+}
+
+/// Consensus constants of the node, along with their hash
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ConsensusConstantsInfo {
+    /// Consensus constants
+    #[serde(flatten)]
+    pub consensus_constants: ConsensusConstants,
+    /// Hash of the consensus constants, which the peers must match in the handshake
+    pub hash: Hash,
+}
+
+/// Get the consensus constants loaded by the node.
+///
+/// Returns every consensus constant and their hash, which is sent to the peers in the Version
+/// message: the peers whose hash does not match are rejected.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getConsensusConstants", "id": 1}
+*/
+pub fn get_consensus_constants() -> JsonRpcFutureResult {
+    info!("Got consensus constants request from JSON-RPC");
+
+    // Get ConfigManager's address
+    let config_manager_addr = System::current().registry().get::<ConfigManager>();
+    let fut = config_manager_addr.send(GetConfig).then(|res| match res {
+        Ok(Ok(config)) => {
+            let consensus_constants = config.consensus_constants.clone();
+            let hash = consensus_constants_hash(&consensus_constants);
+
+            serde_json::to_value(ConsensusConstantsInfo {
+                consensus_constants,
+                hash,
+            })
+            .map_err(|_| jsonrpc_core::Error::internal_error())
+        }
+        _ => Err(jsonrpc_core::Error::internal_error()),
+    });
+
+    Box::new(fut)
+}
+
+/// Get the statistics of the outcomes of a range of epochs observed by this node.
+///
+/// Input: the first and the last epoch of the range (at most 1000 epochs)
+///
+/// Returns the number of recorded epochs in the range, how many of them had a consolidated block,
+/// how many were empty and how many were recorded while the node was not synced, the average
+/// number of block candidates received per epoch and the outcome of every recorded epoch.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getChainStats", "params": [100, 199], "id": 1}
+*/
+pub fn get_chain_stats(from: Epoch, to: Epoch) -> JsonRpcFutureResult {
+    info!(
+        "Got chain statistics request from JSON-RPC: {}-{}",
+        from, to
+    );
+
+    // Get BlocksManager's address
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    let fut = blocks_manager_addr
+        .send(GetChainStats { from, to })
+        .then(|res| match res {
+            Ok(Ok(chain_stats)) => {
+                serde_json::to_value(chain_stats).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(blocks_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Rebuild the UTXO set and the wallet history from the consolidated blocks stored since an
+/// epoch, which are replayed in the background.
+///
+/// Input: the first epoch to rescan (0 to rescan the whole chain)
+///
+/// Returns the initial progress of the rescan, which can be followed with `getRescanStatus`.
+/* Test string:
+{"jsonrpc": "2.0", "method": "rescan", "params": [0], "id": 1}
+*/
+pub fn rescan(from_epoch: Epoch) -> JsonRpcFutureResult {
+    info!(
+        "Got rescan request from JSON-RPC: from epoch {}",
+        from_epoch
+    );
+
+    // Get BlocksManager's address
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    let fut = blocks_manager_addr
+        .send(Rescan { from_epoch })
+        .then(|res| match res {
+            Ok(Ok(status)) => {
+                serde_json::to_value(status).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(blocks_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the progress of the last rescan of the consolidated blocks.
+///
+/// Returns the first epoch rescanned, the checkpoint of the last block replayed, the number of
+/// blocks replayed, skipped and to rescan, and whether the rescan has finished (null if there has
+/// been no rescan since the node was started).
+/* Test string:
+{"jsonrpc": "2.0", "method": "getRescanStatus", "id": 1}
+*/
+pub fn get_rescan_status() -> JsonRpcFutureResult {
+    info!("Got rescan status request from JSON-RPC");
+
+    // Get BlocksManager's address
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    let fut = blocks_manager_addr
+        .send(GetRescanStatus)
+        .then(|res| match res {
+            Ok(status) => {
+                serde_json::to_value(status).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the template of a block for the current epoch, to be completed by an external miner.
+///
+/// Input: the public key hash of the miner, to which the block reward is paid
+///
+/// Returns the beacon of the block (the current epoch and the hash of the tip of the local chain),
+/// the transactions selected from the mempool along with their fees, the block reward and the
+/// sum of the fees, and the block candidate which only lacks the signature and the leadership
+/// proof of the miner.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getBlockTemplate", "params": [[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]], "id": 1}
+*/
+pub fn get_block_template(miner: PublicKeyHash) -> JsonRpcFutureResult {
+    info!("Got block template request from JSON-RPC: {:?}", miner);
+
+    // Get MempoolManager's and BlocksManager's addresses
+    let mempool_manager_addr = System::current().registry().get::<MempoolManager>();
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    let fut = mempool_manager_addr
+        .send(GetTransactionsToMine)
+        .then(|res| match res {
+            Ok(Ok(transactions)) => Ok(transactions),
+            Ok(Err(e)) => Err(mempool_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        })
+        .and_then(move |transactions| {
+            blocks_manager_addr
+                .send(GetBlockTemplate {
+                    miner,
+                    transactions,
+                })
+                .then(|res| match res {
+                    Ok(Ok(template)) => serde_json::to_value(template)
+                        .map_err(|_| jsonrpc_core::Error::internal_error()),
+                    Ok(Err(e)) => Err(blocks_manager_error(e)),
+                    Err(_) => Err(jsonrpc_core::Error::internal_error()),
+                })
+        });
+
+    Box::new(fut)
+}
+
+/// Submit a block completed by an external miner from a template (see `getBlockTemplate`).
+///
+/// Input: the completed block
+///
+/// The block is validated like the blocks received from the peers and, if it is valid, announced
+/// to the peers. Returns the hash of the block.
+/* Test string:
+{"jsonrpc": "2.0", "method": "submitBlock", "params": [{"header":{"block_header":{"version":1,"beacon":{"checkpoint":2,"hash_prev_block":{"SHA256":[4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4]}},"hash_merkle_root":{"SHA256":[3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3]},"utxo_merkle_root":{"SHA256":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}},"proof":{"block_sig":null,"influence":99999}},"txn_count":1,"txns":[null],"mint":{"epoch":2,"output":{"pkh":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],"value":0,"time_lock":2}}}], "id": 1}
+*/
+pub fn submit_block(block: Block) -> JsonRpcFutureResult {
+    let trace = Trace::start();
+    info!(
+        "{}Got block from JSON-RPC: {:?}",
+        trace, block.header.block_header.beacon
+    );
+
+    // Get BlocksManager's address
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    let fut = blocks_manager_addr
+        .send(SubmitBlock { block, trace })
+        .then(|res| match res {
+            Ok(Ok(hash)) => {
+                serde_json::to_value(hash).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            Ok(Err(e)) => Err(blocks_manager_error(e)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Ban parameters
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BanPeerParams {
+    /// IP address of the peer
+    pub address: IpAddr,
+    /// Duration of the ban in seconds, permanent if not given
+    #[serde(default)]
+    pub duration_seconds: Option<u64>,
+    /// Reason of the ban
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Ban a peer: its addresses are removed from the list of known peers and its connections are
+/// refused. The ban is persisted, so it survives restarts of the node.
+///
+/// Input: the IP address of the peer and, optionally, the duration of the ban in seconds
+/// (permanent by default) and its reason
+///
+/// Returns a boolean indicating success.
+/* Test string:
+{"jsonrpc": "2.0", "method": "banPeer", "params": {"address": "192.0.2.1", "duration_seconds": 86400, "reason": "invalid blocks"}, "id": 1}
+*/
+pub fn ban_peer(params: BanPeerParams) -> JsonRpcFutureResult {
+    info!("Got ban request from JSON-RPC: {:?}", params);
+
+    // Get PeersManager's address
+    let peers_manager_addr = System::current().registry().get::<PeersManager>();
+    let fut = peers_manager_addr
+        .send(BanPeer {
+            address: params.address,
+            duration: params.duration_seconds.map(Duration::from_secs),
+            reason: params.reason,
+        })
+        .then(|res| match res {
+            Ok(()) => Ok(Value::Bool(true)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Lift the ban of a peer.
+///
+/// Input: the IP address of the peer
+///
+/// Returns a boolean indicating whether the peer was banned.
+/* Test string:
+{"jsonrpc": "2.0", "method": "unbanPeer", "params": ["192.0.2.1"], "id": 1}
+*/
+pub fn unban_peer(address: IpAddr) -> JsonRpcFutureResult {
+    info!("Got unban request from JSON-RPC: {}", address);
+
+    // Get PeersManager's address
+    let peers_manager_addr = System::current().registry().get::<PeersManager>();
+    let fut = peers_manager_addr
+        .send(UnbanPeer { address })
+        .then(|res| match res {
+            Ok(unbanned) => Ok(Value::Bool(unbanned)),
+            Err(_) => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the bans in force, to be shared with other nodes (see `importBans`).
+///
+/// Returns the list of bans: the IP address of every banned peer, the timestamp until which it
+/// is banned (null if the ban is permanent) and the reason of the ban.
+/* Test string:
+{"jsonrpc": "2.0", "method": "getBans", "id": 1}
+*/
+pub fn get_bans() -> JsonRpcFutureResult {
+    info!("Got bans request from JSON-RPC");
+
+    // Get PeersManager's address
+    let peers_manager_addr = System::current().registry().get::<PeersManager>();
+    let fut = peers_manager_addr.send(GetBans).then(|res| match res {
+        Ok(Ok(bans)) => {
+            serde_json::to_value(bans).map_err(|_| jsonrpc_core::Error::internal_error())
+        }
+        _ => Err(jsonrpc_core::Error::internal_error()),
+    });
+
+    Box::new(fut)
+}
+
+/// Import a list of bans exported by another node (see `getBans`). Expired bans are ignored and
+/// the existing bans are never shortened.
+///
+/// Input: the list of bans
+///
+/// Returns the IP addresses of the peers banned or whose ban has been extended.
+/* Test string:
+{"jsonrpc": "2.0", "method": "importBans", "params": [[{"address": "192.0.2.1", "until": null, "reason": "invalid blocks"}]], "id": 1}
+*/
+pub fn import_bans(bans: Vec<Ban>) -> JsonRpcFutureResult {
+    info!("Got {} bans to import from JSON-RPC", bans.len());
+
+    // Get PeersManager's address
+    let peers_manager_addr = System::current().registry().get::<PeersManager>();
+    let fut = peers_manager_addr
+        .send(ImportBans { bans })
+        .then(|res| match res {
+            Ok(Ok(imported)) => {
+                serde_json::to_value(imported).map_err(|_| jsonrpc_core::Error::internal_error())
+            }
+            _ => Err(jsonrpc_core::Error::internal_error()),
+        });
+
+    Box::new(fut)
+}
+
+/// Convert a BlocksManager error into a JSON-RPC error
+fn blocks_manager_error(e: BlocksManagerError) -> jsonrpc_core::Error {
+    match e {
+        BlocksManagerError::EpochNotConsolidated => {
+            jsonrpc_core::Error::invalid_params("The epoch has not been consolidated yet")
+        }
+        BlocksManagerError::NotSynced => {
+            jsonrpc_core::Error::invalid_params("The node is not synced with the network")
+        }
+        BlocksManagerError::Isolated => {
+            jsonrpc_core::Error::invalid_params("The node is isolated from the network")
+        }
+        BlocksManagerError::NotAMiner => {
+            jsonrpc_core::Error::invalid_params("The node does not have the miner role")
+        }
+        BlocksManagerError::BlockAlreadyExists => {
+            jsonrpc_core::Error::invalid_params("Block already exists")
+        }
+        BlocksManagerError::BlockCandidateOutOfRange => {
+            jsonrpc_core::Error::invalid_params("The block does not belong to the current epoch")
+        }
+        BlocksManagerError::BlockFromTheFuture => {
+            jsonrpc_core::Error::invalid_params("The block belongs to a future epoch")
+        }
+        BlocksManagerError::BlockCandidatePoolFull => {
+            jsonrpc_core::Error::invalid_params("The block has a too low influence")
+        }
+        BlocksManagerError::InvalidUtxoCommitment => {
+            jsonrpc_core::Error::invalid_params("Invalid UTXO set commitment")
+        }
+        BlocksManagerError::InvalidMintTransaction => {
+            jsonrpc_core::Error::invalid_params("Invalid mint transaction")
+        }
+        BlocksManagerError::InvalidTally(_) => jsonrpc_core::Error::invalid_params("Invalid tally"),
+        BlocksManagerError::FinalityViolation => {
+            jsonrpc_core::Error::invalid_params("The block rolls back final blocks")
+        }
+        BlocksManagerError::InvalidEpochRange => {
+            jsonrpc_core::Error::invalid_params("Invalid range of epochs")
+        }
+        BlocksManagerError::RescanInProgress => {
+            jsonrpc_core::Error::invalid_params("A rescan is already in progress")
+        }
+        BlocksManagerError::SignatureVerifierError(SignatureVerifierError::InvalidSignature {
+            ..
+        }) => jsonrpc_core::Error::invalid_params("Invalid signature"),
+        _ => jsonrpc_core::Error::internal_error(),
+    }
+}
+
+/// Convert a MempoolManager error into a JSON-RPC error
+fn mempool_manager_error(e: MempoolManagerError) -> jsonrpc_core::Error {
+    match e {
+        MempoolManagerError::InvalidPageSize => {
+            jsonrpc_core::Error::invalid_params("Invalid page size")
+        }
+        MempoolManagerError::TimeLockedInputs => jsonrpc_core::Error::invalid_params(
+            "The transaction spends outputs which are still time-locked",
+        ),
+        MempoolManagerError::TransactionNotFound => {
+            jsonrpc_core::Error::invalid_params("Transaction not originated by this node")
+        }
+        MempoolManagerError::TransactionAlreadyConfirmed => {
+            jsonrpc_core::Error::invalid_params("Transaction already confirmed")
+        }
+        MempoolManagerError::FeeNotIncreased => {
+            jsonrpc_core::Error::invalid_params("The new fee must be higher than the previous one")
+        }
+        _ => jsonrpc_core::Error::internal_error(),
+    }
+}
+
+/// Convert a WalletManager error into a JSON-RPC error
+fn wallet_manager_error(e: WalletManagerError) -> jsonrpc_core::Error {
+    match e {
+        WalletManagerError::InvalidPageSize => {
+            jsonrpc_core::Error::invalid_params("Invalid page size")
+        }
+        WalletManagerError::AccountNotFound => {
+            jsonrpc_core::Error::invalid_params("Account not found")
+        }
+        WalletManagerError::DuplicatedAccountName => {
+            jsonrpc_core::Error::invalid_params("Duplicated account name")
+        }
+        WalletManagerError::TooManyAccounts => {
+            jsonrpc_core::Error::invalid_params("Too many accounts")
+        }
+        WalletManagerError::AddressAlreadyImported => {
+            jsonrpc_core::Error::invalid_params("Address already imported")
+        }
+        WalletManagerError::TooManyAddresses => {
+            jsonrpc_core::Error::invalid_params("Too many addresses")
+        }
+        WalletManagerError::InvalidValueTransfer => {
+            jsonrpc_core::Error::invalid_params("Invalid value transfer")
+        }
+        WalletManagerError::InvalidMultiSigCondition => {
+            jsonrpc_core::Error::invalid_params("Invalid multi-signature condition")
+        }
+        WalletManagerError::KeyNotInCondition => {
+            jsonrpc_core::Error::invalid_params("Key not in multi-signature condition")
+        }
+        WalletManagerError::InvalidSignature => {
+            jsonrpc_core::Error::invalid_params("Invalid signature from the signer")
+        }
+        WalletManagerError::SignerError(SignerError::Rejected) => {
+            jsonrpc_core::Error::invalid_params("Signing request rejected by the signer")
+        }
+        WalletManagerError::NoPassword => {
+            jsonrpc_core::Error::invalid_params("The wallet is not protected by a password")
+        }
+        WalletManagerError::WrongPassword => jsonrpc_core::Error::invalid_params("Wrong password"),
+        WalletManagerError::ReplaceNotConfirmed => jsonrpc_core::Error::invalid_params(
+            "The current keys are not protected by a password, confirm their replacement",
+        ),
+        WalletManagerError::ExternalSigner => {
+            jsonrpc_core::Error::invalid_params("The keys are held by an external signer")
+        }
+        WalletManagerError::InvalidMnemonic(_) => {
+            jsonrpc_core::Error::invalid_params("Invalid mnemonic")
+        }
+        WalletManagerError::NoSigner
+        | WalletManagerError::KeyError(_)
+        | WalletManagerError::SignerError(_)
+        | WalletManagerError::StorageError(_) => jsonrpc_core::Error::internal_error(),
+    }
+}
+
+/// Convert a UtxoManager error into a JSON-RPC error
+fn utxo_manager_error(e: UtxoManagerError) -> jsonrpc_core::Error {
+    match e {
+        UtxoManagerError::AddressNotWatched => {
+            jsonrpc_core::Error::invalid_params("Address not watched")
+        }
+    }
+}
+
+/// Convert a DataRequestsManager error into a JSON-RPC error
+fn data_requests_manager_error(e: DataRequestsManagerError) -> jsonrpc_core::Error {
+    match e {
+        DataRequestsManagerError::NoRetrievals => {
+            jsonrpc_core::Error::invalid_params("The data request has no retrievals")
+        }
+        DataRequestsManagerError::NoWitnesses => {
+            jsonrpc_core::Error::invalid_params("The data request has no witnesses")
+        }
+        DataRequestsManagerError::InvalidValue(e) => {
+            jsonrpc_core::Error::invalid_params(format!("Invalid value: {}", e))
+        }
+        DataRequestsManagerError::InvalidScript(e) => {
+            jsonrpc_core::Error::invalid_params(format!("Invalid RADON script: {}", e))
+        }
+        DataRequestsManagerError::DataRequestAlreadyExists => {
+            jsonrpc_core::Error::invalid_params("Data request already exists")
+        }
+        DataRequestsManagerError::ValueOverflow => {
+            jsonrpc_core::Error::invalid_params("The value of the data request overflows")
+        }
+        _ => jsonrpc_core::Error::internal_error(),
+    }
+}
+
+#[cfg(test)]
+mod mock_actix {
+    use std::any::{Any, TypeId};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    pub struct System;
+
+    pub struct SystemRegistry;
+
+    pub struct Addr;
+
+    /// Function answering the requests of a type
+    type Responder<T> = Box<dyn Fn(T) -> <T as actix::Message>::Result>;
+
+    thread_local! {
+        /// Responders of the mocked actors, by type of request
+        static RESPONDERS: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+    }
+
+    /// Make the mocked actors answer the requests of type `T` with the given function
+    pub fn respond<T, F>(responder: F)
+    where
+        T: actix::Message + 'static,
+        T::Result: 'static,
+        F: Fn(T) -> T::Result + 'static,
+    {
+        let responder: Responder<T> = Box::new(responder);
+        RESPONDERS.with(|responders| {
+            responders
+                .borrow_mut()
+                .insert(TypeId::of::<T>(), Box::new(responder))
+        });
+    }
+
+    impl System {
+        pub fn current() -> Self {
+            System
+        }
+        pub fn registry(&self) -> &SystemRegistry {
+            &SystemRegistry
+        }
+    }
+
+    impl SystemRegistry {
+        pub fn get<T>(&self) -> Addr {
+            Addr
+        }
+    }
+
+    impl Addr {
+        pub fn do_send<T>(&self, _msg: T) {}
+
+        // The requests without a responder fail as if the actor was not running
+        pub fn send<T>(
+            &self,
+            msg: T,
+        ) -> futures::future::FutureResult<T::Result, actix::MailboxError>
+        where
+            T: actix::Message + 'static,
+            T::Result: 'static,
+        {
+            RESPONDERS.with(|responders| {
+                match responders
+                    .borrow()
+                    .get(&TypeId::of::<T>())
+                    .and_then(|responder| responder.downcast_ref::<Responder<T>>())
+                {
+                    Some(responder) => futures::future::ok(responder(msg)),
+                    None => futures::future::err(actix::MailboxError::Closed),
+                }
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock_actix::respond;
+    use super::*;
+    use crate::actors::blocks_manager::{
+        messages::BlockChain,
+        partition::IsolationReason,
+        rescan::RescanStatus,
+        stats::{ChainStats, EpochOutcome},
+        NodeStatus, SyncState,
+    };
+    use crate::actors::data_requests_manager::pool::{DataRequestStage, DataRequestStatus};
+    use crate::actors::epoch_manager::messages::EpochInfo;
+    use crate::actors::mempool_manager::{
+        local_transactions::LocalTransactionStatus, FeePolicy, MempoolPage,
+    };
+    use crate::actors::utxo_manager::{Balance, BalanceSnapshot, UtxoInfo, UtxosSnapshot};
+    use crate::actors::wallet_manager::{
+        Account, HistoryItem, KeysBackup, SignedTransaction, TransactionStatus,
+    };
+    use serde_json::json;
+    use std::sync::Arc;
+    use witnet_config::config::Config;
+    use witnet_crypto::signature::SignatureError;
+    use witnet_data_structures::chain::CheckpointBeacon;
+
+    /// Handle a request with the mocked actors and parse the response
+    fn handle_request(msg: &str) -> Value {
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(msg).unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    /// Successful response with the given result
+    fn result(result: Value) -> Value {
+        json!({"jsonrpc": "2.0", "result": result, "id": 1})
+    }
+
+    /// Response rejecting the parameters of a request with the given message
+    fn invalid_params(message: &str) -> Value {
+        json!({"jsonrpc": "2.0", "error": {"code": -32602, "message": message}, "id": 1})
+    }
+
+    /// Block used as the response of the mocked actors
+    fn test_block() -> Block {
+        serde_json::from_str(r#"{"header":{"block_header":{"version":1,"beacon":{"checkpoint":2,"hash_prev_block":{"SHA256":[4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4]}},"hash_merkle_root":{"SHA256":[3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3]},"utxo_merkle_root":{"SHA256":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}},"proof":{"block_sig":null,"influence":99999}},"txn_count":1,"txns":[null],"mint":{"epoch":2,"output":{"pkh":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],"value":0,"time_lock":2}}}"#).unwrap()
+    }
+
+    #[test]
+    fn empty_string_parse_error() {
+        // An empty message should return a parse error
+        let empty_string = "";
+        let parse_error =
+            r#"{"jsonrpc":"2.0","error":{"code":-32700,"message":"Parse error"},"id":null}"#
+                .to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(empty_string);
+        assert_eq!(response, Some(parse_error));
+    }
+
+    #[test]
+    fn inventory_method() {
+        // The expected behaviour of the inventory method
+        use witnet_data_structures::chain::*;
+
+        let block = Block {
+            header: BlockHeaderWithProof {
+                block_header: BlockHeader {
+                    version: 1,
+                    beacon: CheckpointBeacon {
+                        checkpoint: 2,
+                        hash_prev_block: Hash::SHA256([4; 32]),
+                    },
+                    hash_merkle_root: Hash::SHA256([3; 32]),
+                    utxo_merkle_root: Hash::SHA256([0; 32]),
+                },
+                proof: LeadershipProof {
+                    block_sig: None,
+                    influence: 99999,
+                },
+            },
+            txn_count: 1,
+            txns: vec![Transaction],
+            mint: MintTransaction {
+                epoch: 2,
+                output: ValueTransferOutput {
+                    pkh: [0; 20],
+                    value: 0,
+                    time_lock: 2,
+                },
+            },
+        };
+
+        let inv_elem = InventoryItem::Block(block);
+        let s = serde_json::to_string(&inv_elem).unwrap();
+        let msg = format!(
+            r#"{{"jsonrpc":"2.0","method":"inventory","params":{},"id":1}}"#,
+            s
+        );
+
+        // Expected result: true
+        let expected = r#"{"jsonrpc":"2.0","result":true,"id":1}"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn inventory_invalid_params() {
+        // What happens when the inventory method is called with an invalid parameter?
+        let msg = r#"{"jsonrpc":"2.0","method":"inventory","params":{ "header": 0 },"id":1}"#;
+        let expected = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params: unknown variant `header`, expected one of"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn inventory_unimplemented_type() {
+        // What happens when the inventory method is called with an unimplemented type?
+        let msg = r#"{"jsonrpc":"2.0","method":"inventory","params":{ "tx": null },"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Item type not implemented"#
+                .to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn set_fee_policy_method() {
+        // The fee policy applied by the MempoolManager is returned
+        respond(|msg: SetFeePolicy| {
+            assert_eq!(
+                (msg.min_relay_fee, msg.mining_min_fee),
+                (Some(10), Some(20))
+            );
+            Ok(FeePolicy {
+                min_relay_fee: 10,
+                mining_min_fee: 20,
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"setFeePolicy","params":{"min_relay_fee":10,"mining_min_fee":20},"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"min_relay_fee": 10, "mining_min_fee": 20}))
+        );
+
+        // Both values are optional
+        respond(|msg: SetFeePolicy| {
+            assert_eq!((msg.min_relay_fee, msg.mining_min_fee), (None, Some(20)));
+            Ok(FeePolicy {
+                min_relay_fee: 0,
+                mining_min_fee: 20,
+            })
+        });
+        let msg =
+            r#"{"jsonrpc":"2.0","method":"setFeePolicy","params":{"mining_min_fee":20},"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"min_relay_fee": 0, "mining_min_fee": 20}))
+        );
+    }
+
+    #[test]
+    fn set_fee_policy_invalid_params() {
+        // Fees must be unsigned integers
+        let msg =
+            r#"{"jsonrpc":"2.0","method":"setFeePolicy","params":{"min_relay_fee":-1},"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn estimate_fee_method() {
+        respond(|msg: EstimateFee| {
+            assert_eq!(msg.target_epochs, 3);
+            Ok(Some(12))
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"estimateFee","params":[3],"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!(12)));
+
+        // Null if the fee estimator does not have enough data yet
+        respond(|_: EstimateFee| Ok(None));
+        assert_eq!(handle_request(msg), result(Value::Null));
+    }
+
+    #[test]
+    fn actor_not_running() {
+        // The requests to an actor which is not running fail with an internal error
+        let msg = r#"{"jsonrpc":"2.0","method":"estimateFee","params":[3],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error"},"id":1}"#
+                .to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn estimate_fee_invalid_params() {
+        // The target number of epochs is mandatory
+        let msg = r#"{"jsonrpc":"2.0","method":"estimateFee","params":[],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn get_mempool_method() {
+        respond(|msg: GetMempool| {
+            assert_eq!((msg.offset, msg.limit), (5, 10));
+            Ok(MempoolPage {
+                total: 6,
+                transactions: vec![Hash::SHA256([2; 32])],
+            })
+        });
+        let msg =
+            r#"{"jsonrpc":"2.0","method":"getMempool","params":[{"offset":5,"limit":10}],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"total": 6, "transactions": [{"SHA256": vec![2; 32]}]}))
+        );
+
+        respond(|_: GetMempool| Err(MempoolManagerError::InvalidPageSize));
+        assert_eq!(handle_request(msg), invalid_params("Invalid page size"));
+    }
+
+    #[test]
+    fn get_mempool_entry_invalid_params() {
+        // The hash must be a valid hash
+        let msg =
+            r#"{"jsonrpc":"2.0","method":"getMempoolEntry","params":[{"SHA256":[1,1,1]}],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn get_transaction_status_method() {
+        respond(|msg: GetTransactionStatus| {
+            assert_eq!(msg.hash, Hash::SHA256([1; 32]));
+            Ok(Some(LocalTransactionStatus::Confirmed {
+                block_hash: Hash::SHA256([2; 32]),
+                depth: 3,
+            }))
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getTransactionStatus","params":[{"SHA256":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]}],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"confirmed": {"block_hash": {"SHA256": vec![2; 32]}, "depth": 3}}))
+        );
+
+        // Null if the transaction was not originated by this node
+        respond(|_: GetTransactionStatus| Ok(None));
+        assert_eq!(handle_request(msg), result(Value::Null));
+    }
+
+    #[test]
+    fn resubmit_transaction_method() {
+        respond(|msg: ResubmitTransaction| {
+            assert_eq!((msg.hash, msg.fee), (Hash::SHA256([1; 32]), 20));
+            Ok(Hash::SHA256([2; 32]))
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"resubmitTransaction","params":[{"SHA256":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]},20],"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!({"SHA256": vec![2; 32]})));
+
+        respond(|_: ResubmitTransaction| Err(MempoolManagerError::FeeNotIncreased));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("The new fee must be higher than the previous one")
+        );
+    }
+
+    #[test]
+    fn resubmit_transaction_invalid_params() {
+        // The new fee is mandatory
+        let msg = r#"{"jsonrpc":"2.0","method":"resubmitTransaction","params":[{"SHA256":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]}],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn get_transaction_history_method() {
+        respond(|msg: GetTransactionHistory| {
+            assert_eq!((msg.account, msg.offset, msg.limit), (1, 0, 10));
+            Ok(vec![HistoryItem {
+                hash: Hash::SHA256([2; 32]),
+                status: TransactionStatus::Pending,
+                confirmations: 0,
+            }])
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getTransactionHistory","params":[1,{"offset":0,"limit":10}],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!([{
+                "hash": {"SHA256": vec![2; 32]},
+                "status": "pending",
+                "confirmations": 0
+            }]))
+        );
+
+        respond(|_: GetTransactionHistory| Err(WalletManagerError::AccountNotFound));
+        assert_eq!(handle_request(msg), invalid_params("Account not found"));
+    }
+
+    #[test]
+    fn get_transaction_history_invalid_params() {
+        // The pagination parameters are mandatory, although they may be empty
+        let msg = r#"{"jsonrpc":"2.0","method":"getTransactionHistory","params":[0],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn get_balance_method() {
+        // The balance of an account is the balance of its derived and watch-only addresses
+        respond(|msg: GetAddresses| {
+            assert_eq!(msg.account, 1);
+            Ok(vec![[1; 20], [2; 20]])
+        });
+        respond(|msg: GetBalance| {
+            assert_eq!(msg.addresses, vec![[1; 20], [2; 20]]);
+            Ok(BalanceSnapshot {
+                epoch: 7,
+                balance: Balance {
+                    available: 100,
+                    locked: 20,
+                },
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getBalance","params":[1],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"epoch": 7, "available": 100, "locked": 20}))
+        );
+
+        respond(|_: GetAddresses| Err(WalletManagerError::AccountNotFound));
+        assert_eq!(handle_request(msg), invalid_params("Account not found"));
+    }
+
+    #[test]
+    fn get_address_balance_method() {
+        respond(|msg: GetAddressBalance| {
+            assert_eq!(msg.address, [1; 20]);
+            Ok(BalanceSnapshot {
+                epoch: 7,
+                balance: Balance {
+                    available: 100,
+                    locked: 0,
+                },
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getBalance","params":[[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"epoch": 7, "available": 100, "locked": 0}))
+        );
+
+        respond(|_: GetAddressBalance| Err(UtxoManagerError::AddressNotWatched));
+        assert_eq!(handle_request(msg), invalid_params("Address not watched"));
+    }
+
+    #[test]
+    fn balance_of_account_or_address() {
+        assert_eq!(
+            serde_json::from_str::<BalanceOf>("3").unwrap(),
+            BalanceOf::Account(3)
+        );
+        assert_eq!(
+            serde_json::from_str::<BalanceOf>("[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]").unwrap(),
+            BalanceOf::Address([1; 20])
+        );
+        assert!(serde_json::from_str::<BalanceOf>("[1,1,1]").is_err());
+    }
+
+    #[test]
+    fn get_utxos_method() {
+        respond(|msg: GetUtxos| {
+            assert_eq!(msg.address, [1; 20]);
+            assert!(msg.include_unconfirmed);
+            Ok(UtxosSnapshot {
+                epoch: 7,
+                utxos: vec![UtxoInfo {
+                    block_hash: Hash::SHA256([2; 32]),
+                    index: 1,
+                    value: 100,
+                    time_lock: 0,
+                    confirmations: 3,
+                }],
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getUtxos","params":[[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1],true],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"epoch": 7, "utxos": [{
+                "block_hash": {"SHA256": vec![2; 32]},
+                "index": 1,
+                "value": 100,
+                "time_lock": 0,
+                "confirmations": 3
+            }]}))
+        );
+    }
+
+    #[test]
+    fn get_utxos_invalid_params() {
+        // Whether to include the unconfirmed outputs is required
+        let msg = r#"{"jsonrpc":"2.0","method":"getUtxos","params":[[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn sign_transaction_method() {
+        respond(|msg: SignTransaction| {
+            assert_eq!((msg.account, msg.key_index), (0, 2));
+            Ok(SignedTransaction {
+                transaction: msg.transaction,
+                complete: false,
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"signTransaction","params":{"key_index":2,"transaction":{"hash":{"SHA256":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]},"condition":{"required":1,"pkhs":[[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]]},"signatures":[]}},"id":1}"#;
+        let response = handle_request(msg);
+        assert_eq!(response["result"]["complete"], json!(false));
+        assert_eq!(
+            response["result"]["transaction"]["hash"],
+            json!({"SHA256": vec![1; 32]})
+        );
+
+        respond(|_: SignTransaction| Err(WalletManagerError::KeyNotInCondition));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("Key not in multi-signature condition")
+        );
+    }
+
+    #[test]
+    fn create_account_method() {
+        respond(|msg: CreateAccount| {
+            Ok(Account {
+                index: 1,
+                name: msg.name,
+                addresses: vec![],
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"createAccount","params":["savings"],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"index": 1, "name": "savings", "addresses": []}))
+        );
+
+        respond(|_: CreateAccount| Err(WalletManagerError::DuplicatedAccountName));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("Duplicated account name")
+        );
+    }
+
+    #[test]
+    fn create_account_invalid_params() {
+        // The name of the account must be a string
+        let msg = r#"{"jsonrpc":"2.0","method":"createAccount","params":[1],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn get_accounts_method() {
+        respond(|_: GetAccounts| {
+            Ok(vec![
+                Account {
+                    index: 0,
+                    name: "default".to_string(),
+                    addresses: vec![[1; 20]],
+                },
+                Account {
+                    index: 1,
+                    name: "savings".to_string(),
+                    addresses: vec![],
+                },
+            ])
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getAccounts","id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!([
+                {"index": 0, "name": "default", "addresses": [vec![1; 20]]},
+                {"index": 1, "name": "savings", "addresses": []}
+            ]))
+        );
+    }
+
+    #[test]
+    fn create_address_method() {
+        respond(|msg: CreateAddress| {
+            assert_eq!(msg.account, 1);
+            Ok([1; 20])
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"createAddress","params":[1],"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!(vec![1; 20])));
+
+        respond(|_: CreateAddress| Err(WalletManagerError::ExternalSigner));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("The keys are held by an external signer")
+        );
+    }
+
+    #[test]
+    fn create_value_transfer_method() {
+        respond(|msg: CreateValueTransfer| {
+            assert_eq!(msg.account, 1);
+            assert_eq!(
+                msg.outputs,
+                vec![ValueTransferOutput {
+                    pkh: [1; 20],
+                    value: 1000,
+                    time_lock: 0,
+                }]
+            );
+            assert_eq!(msg.fee, 10);
+            Ok(Hash::SHA256([2; 32]))
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"createValueTransfer","params":{"account":1,"outputs":[{"pkh":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1],"value":1000}],"fee":10},"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!({"SHA256": vec![2; 32]})));
+
+        respond(|_: CreateValueTransfer| Err(WalletManagerError::InvalidValueTransfer));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("Invalid value transfer")
+        );
+    }
+
+    #[test]
+    fn import_address_method() {
+        respond(|msg: ImportAddress| {
+            assert_eq!(msg.address, [1; 20]);
+            assert_eq!(msg.account, 0);
+            assert_eq!(msg.label, "exchange");
+            Ok(())
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"importAddress","params":{"address":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1],"label":"exchange"},"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!(true)));
+
+        respond(|_: ImportAddress| Err(WalletManagerError::AddressAlreadyImported));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("Address already imported")
+        );
+    }
+
+    #[test]
+    fn import_address_invalid_params() {
+        // The address must be 20 bytes long
+        let msg =
+            r#"{"jsonrpc":"2.0","method":"importAddress","params":{"address":[1,1,1]},"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn create_wallet_method() {
+        // The mnemonic of the new keys is returned
+        respond(|msg: CreateWallet| {
+            assert_eq!(msg.password, "new");
+            assert_eq!(msg.current_password, Some("old".to_string()));
+            assert!(!msg.overwrite);
+            Ok("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string())
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"createWallet","params":{"password":"new","current_password":"old"},"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"))
+        );
+
+        respond(|_: CreateWallet| Err(WalletManagerError::WrongPassword));
+        assert_eq!(handle_request(msg), invalid_params("Wrong password"));
+
+        // Keys without password are only replaced after confirming it
+        respond(|_: CreateWallet| Err(WalletManagerError::ReplaceNotConfirmed));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params(
+                "The current keys are not protected by a password, confirm their replacement"
+            )
+        );
+    }
+
+    #[test]
+    fn create_wallet_invalid_params() {
+        // The password is required
+        let msg = r#"{"jsonrpc":"2.0","method":"createWallet","params":{},"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn import_mnemonic_method() {
+        respond(|msg: ImportMnemonic| {
+            assert_eq!(msg.password, "correct horse battery staple");
+            assert_eq!(msg.current_password, None);
+            assert!(msg.overwrite);
+            Ok(())
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"importMnemonic","params":{"mnemonic":"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about","password":"correct horse battery staple","overwrite":true},"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!(true)));
+
+        respond(|_: ImportMnemonic| Err(WalletManagerError::WrongPassword));
+        assert_eq!(handle_request(msg), invalid_params("Wrong password"));
+    }
+
+    #[test]
+    fn import_mnemonic_invalid_mnemonic() {
+        // Invalid mnemonics are rejected before being sent to the WalletManager
+        let msg = r#"{"jsonrpc":"2.0","method":"importMnemonic","params":{"mnemonic":"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon","password":"secret"},"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid mnemonic"},"id":1}"#
+                .to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn export_master_key_method() {
+        respond(|msg: ExportMasterKey| {
+            assert_eq!(msg.password, "correct horse battery staple");
+            Ok(KeysBackup {
+                mnemonic: None,
+                master_key: "00ff".to_string(),
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"exportMasterKey","params":["correct horse battery staple"],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"mnemonic": null, "master_key": "00ff"}))
+        );
+
+        respond(|_: ExportMasterKey| Err(WalletManagerError::NoPassword));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("The wallet is not protected by a password")
+        );
+    }
+
+    #[test]
+    fn set_wallet_password_method() {
+        respond(|msg: SetWalletPassword| {
+            assert_eq!(msg.password, "new");
+            assert_eq!(msg.current_password, None);
+            Ok(())
+        });
+        let msg =
+            r#"{"jsonrpc":"2.0","method":"setWalletPassword","params":{"password":"new"},"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!(true)));
+
+        respond(|msg: SetWalletPassword| {
+            assert_eq!(msg.current_password, Some("old".to_string()));
+            Err(WalletManagerError::WrongPassword)
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"setWalletPassword","params":{"password":"new","current_password":"old"},"id":1}"#;
+        assert_eq!(handle_request(msg), invalid_params("Wrong password"));
+    }
+
+    #[test]
+    fn unlock_wallet_method() {
+        respond(|msg: UnlockWallet| {
+            assert_eq!(msg.password, "correct horse battery staple");
+            Ok(())
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"unlockWallet","params":["correct horse battery staple"],"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!(true)));
+
+        respond(|_: UnlockWallet| Err(WalletManagerError::WrongPassword));
+        assert_eq!(handle_request(msg), invalid_params("Wrong password"));
+    }
+
+    #[test]
+    fn send_data_request_method() {
+        // The data request is funded by the wallet and then posted with the hash of the funding
+        respond(|msg: FundDataRequest| {
+            assert_eq!(msg.account, 1);
+            Ok(Hash::SHA256([2; 32]))
+        });
+        respond(|msg: PostDataRequest| {
+            assert_eq!(msg.hash, Hash::SHA256([2; 32]));
+            Ok(())
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"sendDataRequest","params":{"account":1,"data_request":{"data_request":{"not_before":0,"retrieve":[{"kind":"HTTP-GET","url":"https://openweathermap.org/data/2.5/weather?id=2950159&appid=b6907d289e10d714a6e88b30761fae22","script":[150,3,4,146,0,167,119,101,97,116,104,101,114,4,146,0,164,116,101,109,112,2]}],"aggregate":{"script":[]},"consensus":{"script":[]},"deliver":[]},"value":1000,"witnesses":2,"commit_fee":1,"reveal_fee":1,"tally_fee":1}},"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!({"SHA256": vec![2; 32]})));
+
+        respond(|_: PostDataRequest| Err(DataRequestsManagerError::DataRequestAlreadyExists));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("Data request already exists")
+        );
+    }
+
+    #[test]
+    fn send_data_request_without_retrievals() {
+        // Data requests without retrievals are rejected before being funded
+        let msg = r#"{"jsonrpc":"2.0","method":"sendDataRequest","params":{"data_request":{"data_request":{"not_before":0,"retrieve":[],"aggregate":{"script":[]},"consensus":{"script":[]},"deliver":[]},"value":1000,"witnesses":2,"commit_fee":1,"reveal_fee":1,"tally_fee":1}},"id":1}"#;
+        let expected = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":""#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn send_data_request_with_insufficient_value() {
+        // Data requests whose value does not cover their fees are rejected before being funded
+        let msg = r#"{"jsonrpc":"2.0","method":"sendDataRequest","params":{"data_request":{"data_request":{"not_before":0,"retrieve":[{"kind":"HTTP-GET","url":"https://example.com","script":[]}],"aggregate":{"script":[]},"consensus":{"script":[]},"deliver":[]},"value":5,"witnesses":2,"commit_fee":1,"reveal_fee":1,"tally_fee":1}},"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid value: "#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    fn create_data_request_params(witnesses: u16, collateral: u64) -> CreateDataRequestParams {
+        serde_json::from_str(&format!(
+            r#"{{"retrieve":[{{"kind":"HTTP-GET","url":"https://example.com","script":[]}}],"aggregate":{{"script":[]}},"consensus":{{"script":[]}},"witnesses":{},"collateral":{}}}"#,
+            witnesses, collateral
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn build_data_request_estimates_cost() {
+        let params = create_data_request_params(2, 1000);
+        assert_eq!(params.account, 0);
+        assert_eq!(params.target_epochs, 3);
+
+        let (data_request, cost) = build_data_request(&params, 10).unwrap();
+        assert_eq!(data_request.commit_fee, 10);
+        assert_eq!(data_request.reveal_fee, 10);
+        assert_eq!(data_request.tally_fee, 10);
+        // The reward of every witness must allow it to lock a collateral of 1000
+        assert_eq!(cost.witness_reward, 8);
+        assert_eq!(cost.fees(), 50);
+        assert_eq!(data_request.value, 50 + 2 * 8);
+        assert_eq!(value_split(&data_request).unwrap(), cost);
+    }
+
+    #[test]
+    fn build_data_request_without_witnesses() {
+        let params = create_data_request_params(0, 0);
+        match build_data_request(&params, 1) {
+            Err(DataRequestsManagerError::NoWitnesses) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn build_data_request_overflow() {
+        let params = create_data_request_params(2, 0);
+        match build_data_request(&params, u64::max_value()) {
+            Err(DataRequestsManagerError::ValueOverflow) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn create_data_request_method() {
+        // The fallback fee rate is used if the fee estimator does not have enough data yet
+        respond(|msg: EstimateFee| {
+            assert_eq!(msg.target_epochs, 3);
+            Ok(None)
+        });
+        respond(|_: FundDataRequest| Ok(Hash::SHA256([2; 32])));
+        let msg = r#"{"jsonrpc":"2.0","method":"createDataRequest","params":{"retrieve":[{"kind":"HTTP-GET","url":"https://openweathermap.org/data/2.5/weather?id=2950159&appid=b6907d289e10d714a6e88b30761fae22","script":[150,3,4,146,0,167,119,101,97,116,104,101,114,4,146,0,164,116,101,109,112,2]}],"aggregate":{"script":[]},"consensus":{"script":[]},"witnesses":2,"collateral":1000},"id":1}"#;
+        let params: CreateDataRequestParams =
+            serde_json::from_value(json!({
+                "retrieve": [{"kind": "HTTP-GET", "url": "https://openweathermap.org/data/2.5/weather?id=2950159&appid=b6907d289e10d714a6e88b30761fae22", "script": [150,3,4,146,0,167,119,101,97,116,104,101,114,4,146,0,164,116,101,109,112,2]}],
+                "aggregate": {"script": []},
+                "consensus": {"script": []},
+                "witnesses": 2,
+                "collateral": 1000
+            }))
+            .unwrap();
+        let (data_request, cost) = build_data_request(&params, FALLBACK_FEE_RATE).unwrap();
+        assert_eq!(
+            handle_request(msg),
+            result(
+                serde_json::to_value(CreatedDataRequest {
+                    hash: Hash::SHA256([2; 32]),
+                    data_request,
+                    fee_rate: FALLBACK_FEE_RATE,
+                    cost,
+                })
+                .unwrap()
+            )
+        );
+
+        // The fees grow with the estimated fee rate
+        respond(|_: EstimateFee| Ok(Some(10)));
+        let response = handle_request(msg);
+        assert_eq!(response["result"]["fee_rate"], json!(10));
+        assert_eq!(
+            response["result"]["data_request"]["commit_fee"],
+            json!(10 * transaction_weight(&Transaction))
+        );
+    }
+
+    #[test]
+    fn create_data_request_without_retrievals() {
+        // Data requests without retrievals are rejected before estimating their cost
+        let msg = r#"{"jsonrpc":"2.0","method":"createDataRequest","params":{"retrieve":[],"aggregate":{"script":[]},"consensus":{"script":[]},"witnesses":2},"id":1}"#;
+        let expected = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"The data request has no retrievals"},"id":1}"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn get_data_request_status_method() {
+        respond(|msg: GetDataRequestStatus| {
+            assert_eq!(msg.hash, Hash::SHA256([1; 32]));
+            Ok(Some(DataRequestStatus {
+                stage: DataRequestStage::Reveal,
+                witnesses: 2,
+                commits: 2,
+                reveals: 1,
+            }))
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getDataRequestStatus","params":[{"SHA256":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]}],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"stage": "reveal", "witnesses": 2, "commits": 2, "reveals": 1}))
+        );
+
+        // Null if the data request is not known to this node
+        respond(|_: GetDataRequestStatus| Ok(None));
+        assert_eq!(handle_request(msg), result(Value::Null));
+    }
+
+    #[test]
+    fn get_data_request_result_invalid_params() {
+        // The hash of the data request must be a valid hash
+        let msg = r#"{"jsonrpc":"2.0","method":"getDataRequestResult","params":[[1,1,1]],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn get_block_chain_method() {
+        respond(|msg: GetBlockChain| {
+            assert_eq!((msg.from, msg.to), (0, 99));
+            Ok(BlockChain {
+                epoch: 42,
+                blocks: vec![(3, Hash::SHA256([2; 32]))],
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getBlockChain","params":[0,99],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"epoch": 42, "blocks": [[3, {"SHA256": vec![2; 32]}]]}))
+        );
+
+        respond(|_: GetBlockChain| Err(BlocksManagerError::InvalidEpochRange));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("Invalid range of epochs")
+        );
+    }
+
+    #[test]
+    fn get_block_by_epoch_method() {
+        // The block is read from storage, using its hash as the key
+        respond(|msg: GetBlockHashByEpoch| {
+            assert_eq!(msg.epoch, 2);
+            Ok(Some(Hash::SHA256([2; 32])))
+        });
+        respond(|msg: Get<Block>| {
+            assert_eq!(&msg.key[..], &[2; 32][..]);
+            Ok(Some(test_block()))
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getBlockByEpoch","params":[2],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(
+                serde_json::to_value(EpochBlock {
+                    epoch: 2,
+                    hash: Some(Hash::SHA256([2; 32])),
+                    block: Some(test_block()),
+                })
+                .unwrap()
+            )
+        );
+
+        // The hash and the block are null if the epoch was empty
+        respond(|_: GetBlockHashByEpoch| Ok(None));
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"epoch": 2, "hash": null, "block": null}))
+        );
+
+        respond(|_: GetBlockHashByEpoch| Err(BlocksManagerError::EpochNotConsolidated));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("The epoch has not been consolidated yet")
+        );
+    }
+
+    #[test]
+    fn get_chain_stats_method() {
+        respond(|msg: GetChainStats| {
+            assert_eq!((msg.from, msg.to), (100, 199));
+            Ok(ChainStats::from_outcomes(vec![
+                EpochOutcome {
+                    epoch: 100,
+                    candidates: 3,
+                    consolidated: true,
+                    synced: true,
+                },
+                EpochOutcome {
+                    epoch: 101,
+                    candidates: 0,
+                    consolidated: false,
+                    synced: false,
+                },
+            ]))
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getChainStats","params":[100,199],"id":1}"#;
+        let response = handle_request(msg);
+        assert_eq!(response["result"]["epochs"], json!(2));
+        assert_eq!(response["result"]["consolidated"], json!(1));
+        assert_eq!(response["result"]["empty"], json!(1));
+        assert_eq!(response["result"]["unsynced"], json!(1));
+        assert_eq!(response["result"]["avg_candidates"], json!(1.5));
+    }
+
+    #[test]
+    fn get_chain_stats_invalid_params() {
+        // Both ends of the range are required
+        let msg = r#"{"jsonrpc":"2.0","method":"getChainStats","params":[100],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn rescan_method() {
+        respond(|msg: Rescan| {
+            assert_eq!(msg.from_epoch, 10);
+            Ok(RescanStatus {
+                from_epoch: 10,
+                total: 5,
+                ..RescanStatus::default()
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"rescan","params":[10],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({
+                "from_epoch": 10,
+                "last_epoch": null,
+                "replayed": 0,
+                "skipped": 0,
+                "total": 5,
+                "finished": false
+            }))
+        );
+
+        respond(|_: Rescan| Err(BlocksManagerError::RescanInProgress));
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("A rescan is already in progress")
+        );
+    }
+
+    #[test]
+    fn rescan_invalid_params() {
+        // The first epoch is required
+        let msg = r#"{"jsonrpc":"2.0","method":"rescan","params":[],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn get_rescan_status_method() {
+        // Null if there has been no rescan since the node was started
+        respond(|_: GetRescanStatus| None);
+        let msg = r#"{"jsonrpc":"2.0","method":"getRescanStatus","id":1}"#;
+        assert_eq!(handle_request(msg), result(Value::Null));
+
+        respond(|_: GetRescanStatus| {
+            Some(RescanStatus {
+                from_epoch: 0,
+                last_epoch: Some(4),
+                replayed: 4,
+                skipped: 1,
+                total: 5,
+                finished: true,
+            })
+        });
+        assert_eq!(
+            handle_request(msg),
+            result(json!({
+                "from_epoch": 0,
+                "last_epoch": 4,
+                "replayed": 4,
+                "skipped": 1,
+                "total": 5,
+                "finished": true
+            }))
+        );
+    }
+
+    #[test]
+    fn get_block_template_method() {
+        // The transactions selected by the mempool are passed on to the BlocksManager
+        respond(|_: GetTransactionsToMine| Ok(vec![]));
+        respond(|msg: GetBlockTemplate| {
+            assert_eq!(msg.miner, [1; 20]);
+            assert!(msg.transactions.is_empty());
+            Err(BlocksManagerError::NotSynced)
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getBlockTemplate","params":[[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1]],"id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            invalid_params("The node is not synced with the network")
+        );
+    }
+
+    #[test]
+    fn get_block_template_invalid_params() {
+        // The public key hash of the miner is required
+        let msg = r#"{"jsonrpc":"2.0","method":"getBlockTemplate","params":[],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn submit_block_method() {
+        respond(|msg: SubmitBlock| {
+            assert_eq!(msg.block, test_block());
+            Ok(Hash::SHA256([2; 32]))
+        });
+        let msg = format!(
+            r#"{{"jsonrpc":"2.0","method":"submitBlock","params":[{}],"id":1}}"#,
+            serde_json::to_string(&test_block()).unwrap()
+        );
+        assert_eq!(handle_request(&msg), result(json!({"SHA256": vec![2; 32]})));
+
+        respond(|_: SubmitBlock| Err(BlocksManagerError::InvalidUtxoCommitment));
+        assert_eq!(
+            handle_request(&msg),
+            invalid_params("Invalid UTXO set commitment")
+        );
+
+        respond(|_: SubmitBlock| {
+            Err(BlocksManagerError::SignatureVerifierError(
+                SignatureVerifierError::InvalidSignature {
+                    index: 0,
+                    error: SignatureError::VerificationFailed,
+                },
+            ))
+        });
+        assert_eq!(handle_request(&msg), invalid_params("Invalid signature"));
+
+        respond(|_: SubmitBlock| Err(BlocksManagerError::NotAMiner));
+        assert_eq!(
+            handle_request(&msg),
+            invalid_params("The node does not have the miner role")
+        );
+    }
+
+    #[test]
+    fn ban_peer_method() {
+        respond(|msg: BanPeer| {
+            assert_eq!(msg.address, "192.0.2.1".parse::<IpAddr>().unwrap());
+            assert_eq!(msg.duration, Some(Duration::from_secs(86400)));
+            assert_eq!(msg.reason, "invalid blocks");
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"banPeer","params":{"address":"192.0.2.1","duration_seconds":86400,"reason":"invalid blocks"},"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!(true)));
+    }
+
+    #[test]
+    fn ban_peer_invalid_params() {
+        // The address must be an IP address, without port
+        let msg =
+            r#"{"jsonrpc":"2.0","method":"banPeer","params":{"address":"192.0.2.1:21337"},"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn import_bans_method() {
+        respond(|msg: ImportBans| {
+            assert_eq!(msg.bans.len(), 1);
+            Ok(vec![msg.bans[0].address])
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"importBans","params":[[{"address":"192.0.2.1","until":null,"reason":"invalid blocks"}]],"id":1}"#;
+        assert_eq!(handle_request(msg), result(json!(["192.0.2.1"])));
+    }
+
+    #[test]
+    fn get_block_by_epoch_invalid_params() {
+        // The epoch must be a number
+        let msg = r#"{"jsonrpc":"2.0","method":"getBlockByEpoch","params":["two"],"id":1}"#;
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params"#.to_string();
+        let io = jsonrpc_io_handler();
+        let response = io.handle_request_sync(&msg);
+        // Compare only the first N characters
+        let response =
+            response.map(|s| s.chars().take(expected.chars().count()).collect::<String>());
+        assert_eq!(response, Some(expected));
+    }
+
+    #[test]
+    fn get_epoch_method() {
+        respond(|_: GetEpochInfo| {
+            Ok(EpochInfo {
+                epoch: Some(5),
+                next_checkpoint: 1_000_090,
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getEpoch","id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({"epoch": 5, "next_checkpoint": 1_000_090}))
+        );
+    }
+
+    #[test]
+    fn get_metrics_method() {
+        // Messages shed because the mailbox of a session was full are counted
+        let before = metrics::dropped_messages().session_mailbox_full;
+        metrics::record_session_mailbox_full();
+        let msg = r#"{"jsonrpc":"2.0","method":"getMetrics","id":1}"#;
+        let response = handle_request(msg);
+        let counted = response["result"]["dropped_messages"]["session_mailbox_full"]
+            .as_u64()
+            .unwrap();
+        assert!(counted as usize > before);
+        assert!(response["result"]["slow_handlers"]["max_elapsed"].is_object());
+
+        // The latency of the relayed blocks is reported
+        metrics::record_block_relay(Duration::from_millis(10));
+        let response = handle_request(msg);
+        let block_relay = &response["result"]["block_relay"];
+        assert!(block_relay["relayed"].as_u64().unwrap() > 0);
+        assert!(block_relay["avg_latency"].is_object());
+        assert!(block_relay["max_latency"].is_object());
+    }
+
+    #[test]
+    fn get_consensus_constants_method() {
+        // The consensus constants are returned along with the hash sent in the handshake
+        let config = Config::default();
+        let hash = consensus_constants_hash(&config.consensus_constants);
+        respond(|_: GetConfig| Ok(Arc::new(Config::default())));
+        let msg = r#"{"jsonrpc":"2.0","method":"getConsensusConstants","id":1}"#;
+        let response = handle_request(msg);
+        assert_eq!(
+            response["result"]["hash"],
+            serde_json::to_value(hash).unwrap()
+        );
+        assert_eq!(
+            serde_json::from_value::<ConsensusConstantsInfo>(response["result"].clone())
+                .unwrap()
+                .consensus_constants,
+            config.consensus_constants
+        );
+    }
+
+    #[test]
+    fn get_node_status_method() {
+        respond(|_: GetNodeStatus| {
+            Ok(NodeStatus {
+                sync_state: SyncState::Syncing,
+                isolated: Some(IsolationReason::NoOutboundSessions),
+                current_epoch: Some(12),
+                tip: CheckpointBeacon {
+                    checkpoint: 10,
+                    hash_prev_block: Hash::SHA256([2; 32]),
+                },
+                peers_last_epoch: None,
+                num_outbound_consolidated: 0,
+            })
+        });
+        let msg = r#"{"jsonrpc":"2.0","method":"getNodeStatus","id":1}"#;
+        assert_eq!(
+            handle_request(msg),
+            result(json!({
+                "sync_state": "syncing",
+                "isolated": "no_outbound_sessions",
+                "current_epoch": 12,
+                "tip": {"checkpoint": 10, "hash_prev_block": {"SHA256": vec![2; 32]}},
+                "peers_last_epoch": null,
+                "num_outbound_consolidated": 0
+            }))
+        );
+    }
+
+    #[test]
+    fn deserialize_pagination_defaults() {
+        let pagination: Pagination = serde_json::from_str("{}").unwrap();
+        assert_eq!(
+            pagination,
+            Pagination {
+                offset: 0,
+                limit: 50
+            }
+        );
+    }
+
+    #[test]
+    fn serialize_block() {
+        // Check that the serialization of `Block` doesn't change
+        use witnet_data_structures::chain::*;
+
+        let block = Block {
+            header: BlockHeaderWithProof {
+                block_header: BlockHeader {
+                    version: 1,
+                    beacon: CheckpointBeacon {
                         checkpoint: 2,
                         hash_prev_block: Hash::SHA256([4; 32]),
                     },
                     hash_merkle_root: Hash::SHA256([3; 32]),
+                    utxo_merkle_root: Hash::SHA256([0; 32]),
                 },
                 proof: LeadershipProof {
                     block_sig: None,
@@ -204,10 +3441,18 @@ mod tests {
             },
             txn_count: 1,
             txns: vec![Transaction],
+            mint: MintTransaction {
+                epoch: 2,
+                output: ValueTransferOutput {
+                    pkh: [0; 20],
+                    value: 0,
+                    time_lock: 2,
+                },
+            },
         };
         let inv_elem = InventoryItem::Block(block);
         let s = serde_json::to_string(&inv_elem);
-        let expected = r#"{"block":{"header":{"block_header":{"version":1,"beacon":{"checkpoint":2,"hash_prev_block":{"SHA256":[4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4]}},"hash_merkle_root":{"SHA256":[3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3]}},"proof":{"block_sig":null,"influence":99999}},"txn_count":1,"txns":[null]}}"#;
+        let expected = r#"{"block":{"header":{"block_header":{"version":1,"beacon":{"checkpoint":2,"hash_prev_block":{"SHA256":[4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4]}},"hash_merkle_root":{"SHA256":[3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3,3]},"utxo_merkle_root":{"SHA256":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}},"proof":{"block_sig":null,"influence":99999}},"txn_count":1,"txns":[null],"mint":{"epoch":2,"output":{"pkh":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],"value":0,"time_lock":2}}}}"#;
         assert_eq!(s.unwrap(), expected);
     }
 }
@@ -1,4 +1,6 @@
 mod connection;
+/// Health and readiness checks
+pub mod health;
 /// JSON-RPC methods
 pub mod json_rpc_methods;
 mod newline_codec;
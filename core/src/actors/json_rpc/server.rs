@@ -3,6 +3,8 @@ use super::json_rpc_methods::jsonrpc_io_handler;
 use super::newline_codec::NewLineCodec;
 use crate::actors::config_manager::send_get_config_request;
 use crate::actors::connections_manager::messages::InboundTcpConnect;
+use crate::actors::node;
+use crate::errors::NodeError;
 use actix::{
     io::FramedWrite, Actor, ActorContext, Addr, AsyncContext, Context, Handler, Message,
     StreamHandler,
@@ -54,11 +56,15 @@ impl JsonRpcServer {
         let listener = match TcpListener::bind(&server_addr) {
             Ok(listener) => listener,
             Err(e) => {
-                // Shutdown the entire system on error
+                // Stop the node on error
                 // For example, when the server_addr is already in use
-                // FIXME(#72): gracefully stop the system?
                 error!("Could not start JSON-RPC server: {:?}", e);
-                panic!("Could not start JSON-RPC server: {:?}", e);
+                node::escalate(&NodeError::Initialization(format!(
+                    "the JSON-RPC server could not be started: {}",
+                    e
+                )));
+                ctx.stop();
+                return;
             }
         };
 
@@ -90,6 +96,7 @@ impl JsonRpcServer {
                 framed: FramedWrite::new(w, NewLineCodec, ctx),
                 parent,
                 jsonrpc_io,
+                http_request: None,
             }
         });
 
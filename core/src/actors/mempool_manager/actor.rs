@@ -1,11 +1,102 @@
-use actix::{Actor, Context, Supervised, SystemService};
+use actix::{
+    Actor, ActorFuture, AsyncContext, Context, ContextFutureSpawner, Running, Supervised, System,
+    SystemService, WrapFuture,
+};
+use log::{debug, error, info};
 
-use super::MempoolManager;
+use super::{handlers::EveryEpochPayload, MempoolManager, MempoolTransaction};
+use crate::actors::{
+    config_manager::send_get_config_request,
+    epoch_manager::{messages::Subscribe, EpochManager},
+    storage_keys::MEMPOOL_KEY,
+    storage_manager::{
+        messages::{Get, Put},
+        StorageManager,
+    },
+};
+use witnet_config::config::Role;
 
 /// Implement Actor trait for `MempoolManager`
 impl Actor for MempoolManager {
     /// Every actor has to provide execution `Context` in which it can run
     type Context = Context<Self>;
+
+    /// Method to be executed when the actor is started
+    fn started(&mut self, ctx: &mut Self::Context) {
+        debug!("MempoolManager actor has been started!");
+
+        // Query ConfigManager for the fee policy
+        send_get_config_request(self, ctx, |act, ctx, config| {
+            act.set_fee_policy(
+                Some(config.mempool.min_relay_fee),
+                Some(config.mining.min_fee),
+            );
+            act.accept_transactions =
+                config.node.has_role(Role::Relay) || config.node.has_role(Role::Miner);
+
+            // The unconfirmed transactions expire after some epochs
+            act.expiry_epochs = config.mempool.expiry_epochs;
+            let epoch_manager_addr = System::current().registry().get::<EpochManager>();
+            epoch_manager_addr.do_send(Subscribe::to_all(ctx.address(), EveryEpochPayload));
+
+            // Reload the transactions persisted before the last shutdown, once the fee policy
+            // is known
+            let storage_manager_addr = System::current().registry().get::<StorageManager>();
+            storage_manager_addr
+                // Send a message to read the mempool from the storage
+                .send(Get::<Vec<MempoolTransaction>>::new(MEMPOOL_KEY))
+                .into_actor(act)
+                // Process the response
+                .then(|res, act, _ctx| {
+                    match res {
+                        Err(e) => {
+                            // Error when sending message
+                            error!("Unsuccessful communication with storage manager: {}", e);
+                        }
+                        Ok(Err(e)) => {
+                            // Storage error
+                            error!("Error while getting the mempool from storage: {}", e);
+                        }
+                        Ok(Ok(Some(transactions))) => {
+                            let persisted = transactions.len();
+                            let restored = act.restore_transactions(transactions);
+                            info!(
+                                "{} of {} transactions restored into the mempool from storage",
+                                restored, persisted
+                            );
+                        }
+                        Ok(Ok(None)) => {
+                            // The storage does not contain that key
+                            debug!("No mempool in storage");
+                        }
+                    }
+
+                    actix::fut::ok(())
+                })
+                .wait(ctx);
+
+            // Start the persist mempool process
+            act.persist_transactions_periodically(ctx, config.mempool.storage_period);
+        });
+
+        // Start the rebroadcast process of the local transactions
+        self.rebroadcast_periodically(ctx);
+    }
+
+    /// Method to be executed when the actor is stopping
+    fn stopping(&mut self, _ctx: &mut Self::Context) -> Running {
+        // Persist the mempool one last time, without waiting for the StorageManager to process
+        // it as the system could be shutting down
+        match Put::from_value(MEMPOOL_KEY, &self.transactions) {
+            Ok(msg) => System::current()
+                .registry()
+                .get::<StorageManager>()
+                .do_send(msg),
+            Err(e) => error!("Error encoding the mempool: {}", e),
+        }
+
+        Running::Stop
+    }
 }
 
 /// Make the MempoolManager a Supervisor, which provides the ability to be restarted
@@ -0,0 +1,112 @@
+//! # Fee estimator
+//!
+//! This module contains the fee estimator used by the MempoolManager actor.
+//! The estimator keeps the minimum fee rate included in each one of the
+//! most recent blocks and, given a target number of epochs, returns the fee
+//! rate needed for a transaction to be included within that many epochs.
+use std::collections::VecDeque;
+
+use witnet_data_structures::chain::Transaction;
+
+/// Number of recent blocks taken into account by the fee estimator
+pub const FEE_ESTIMATOR_WINDOW: usize = 100;
+
+/// Desired probability of being included within the target number of epochs
+pub const FEE_ESTIMATOR_CONFIDENCE: f64 = 0.95;
+
+/// Weight of a transaction, used to calculate its fee rate
+// FIXME(#99): calculate the weight from the inputs and outputs of the transaction once they are
+// defined (until then, all transactions have the same weight)
+pub fn transaction_weight(_transaction: &Transaction) -> u64 {
+    1
+}
+
+/// Fee rate of a transaction (fee per weight unit)
+pub fn fee_rate(transaction: &Transaction, fee: u64) -> u64 {
+    fee / std::cmp::max(transaction_weight(transaction), 1)
+}
+
+/// Fee estimator based on the fee rates included in recent blocks
+#[derive(Debug, Default)]
+pub struct FeeEstimator {
+    /// Minimum fee rate included in each one of the most recent blocks
+    min_fee_rates: VecDeque<u64>,
+}
+
+impl FeeEstimator {
+    /// Record the fee rates of the transactions included in a new block
+    /// A block without transactions is recorded with a fee rate of 0, because any transaction
+    /// would have been included in it
+    pub fn record_block(&mut self, fee_rates: &[u64]) {
+        let min_fee_rate = fee_rates.iter().cloned().min().unwrap_or(0);
+
+        self.min_fee_rates.push_back(min_fee_rate);
+        if self.min_fee_rates.len() > FEE_ESTIMATOR_WINDOW {
+            self.min_fee_rates.pop_front();
+        }
+    }
+
+    /// Estimate the fee rate needed to be included within `target_epochs` epochs
+    /// Returns `None` if no blocks have been recorded yet
+    pub fn estimate(&self, target_epochs: u32) -> Option<u64> {
+        if self.min_fee_rates.is_empty() {
+            return None;
+        }
+
+        let target_epochs = std::cmp::max(target_epochs, 1);
+
+        // A fee rate which would have been enough to be included in a fraction `p` of the
+        // recent blocks is not included within `target_epochs` blocks with probability
+        // (1 - p) ^ target_epochs
+        let p = 1.0 - (1.0 - FEE_ESTIMATOR_CONFIDENCE).powf(1.0 / f64::from(target_epochs));
+
+        let mut sorted: Vec<u64> = self.min_fee_rates.iter().cloned().collect();
+        sorted.sort();
+        let index = ((sorted.len() as f64 * p).ceil() as usize)
+            .saturating_sub(1)
+            .min(sorted.len() - 1);
+
+        Some(sorted[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_without_blocks() {
+        let estimator = FeeEstimator::default();
+
+        assert_eq!(estimator.estimate(1), None);
+    }
+
+    #[test]
+    fn estimate_decreases_with_target() {
+        let mut estimator = FeeEstimator::default();
+        for fee_rate in 1..=100 {
+            estimator.record_block(&[fee_rate, fee_rate + 1000]);
+        }
+
+        // Being included in the next epoch requires a high fee rate
+        assert_eq!(estimator.estimate(1), Some(95));
+        // A target of 0 epochs is treated as 1
+        assert_eq!(estimator.estimate(0), Some(95));
+        // Longer targets require lower fee rates
+        assert!(estimator.estimate(5).unwrap() < estimator.estimate(2).unwrap());
+        assert!(estimator.estimate(100).unwrap() <= estimator.estimate(5).unwrap());
+    }
+
+    #[test]
+    fn record_block_window() {
+        let mut estimator = FeeEstimator::default();
+        for _ in 0..FEE_ESTIMATOR_WINDOW {
+            estimator.record_block(&[1000]);
+        }
+        // Blocks without transactions are recorded with a fee rate of 0
+        estimator.record_block(&[]);
+
+        assert_eq!(estimator.min_fee_rates.len(), FEE_ESTIMATOR_WINDOW);
+        assert_eq!(estimator.min_fee_rates.back(), Some(&0));
+    }
+}
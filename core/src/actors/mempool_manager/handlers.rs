@@ -0,0 +1,196 @@
+use actix::{Context, Handler};
+use log::{debug, info};
+
+use super::{
+    local_transactions::LocalTransactionStatus,
+    messages::{
+        AddTransaction, EstimateFee, GetMempool, GetMempoolEntry, GetTransactionStatus,
+        GetTransactionsToMine, RecordBlockFees, ResubmitTransaction, SetFeePolicy,
+        SubmitTransaction, TransactionsAnnounced, TransactionsConfirmed,
+    },
+    FeePolicy, MempoolEntry, MempoolManager, MempoolManagerError, MempoolPage, MempoolTransaction,
+    MAX_MEMPOOL_PAGE_SIZE,
+};
+use crate::actors::epoch_manager::messages::EpochNotification;
+use witnet_data_structures::chain::Hash;
+
+////////////////////////////////////////////////////////////////////////////////////////
+// ACTOR MESSAGE HANDLERS
+////////////////////////////////////////////////////////////////////////////////////////
+/// Payload for the notification for all epochs
+#[derive(Clone, Debug)]
+pub struct EveryEpochPayload;
+
+/// Handler for EpochNotification<EveryEpochPayload>
+impl Handler<EpochNotification<EveryEpochPayload>> for MempoolManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: EpochNotification<EveryEpochPayload>, _ctx: &mut Context<Self>) {
+        let num_transactions = self.transactions.len();
+        let expired_local = self.expire_transactions(msg.checkpoint);
+        let expired = num_transactions - self.transactions.len();
+        if expired > 0 {
+            info!(
+                "{} unconfirmed transactions expired from the mempool ({} local)",
+                expired,
+                expired_local.len()
+            );
+        }
+
+        self.notify_expired_transactions(expired_local);
+    }
+}
+
+/// Handler for AddTransaction message
+impl Handler<AddTransaction> for MempoolManager {
+    type Result = Result<(), MempoolManagerError>;
+
+    fn handle(&mut self, msg: AddTransaction, _ctx: &mut Context<Self>) -> Self::Result {
+        if !self.accept_transactions {
+            debug!("Transaction rejected: the node neither relays nor mines transactions");
+            return Err(MempoolManagerError::TransactionsNotAccepted);
+        }
+
+        let res = self.add_transaction(msg.transaction, msg.fee);
+        match res {
+            Err(MempoolManagerError::FeeBelowMinRelayFee) => debug!(
+                "Transaction rejected: fee {} is below the minimum relay fee {}",
+                msg.fee, self.fee_policy.min_relay_fee
+            ),
+            Err(MempoolManagerError::TimeLockedInputs) => {
+                debug!("Transaction rejected: it spends outputs which are still time-locked")
+            }
+            _ => {}
+        }
+
+        res
+    }
+}
+
+/// Handler for SubmitTransaction message
+impl Handler<SubmitTransaction> for MempoolManager {
+    type Result = Result<Hash, MempoolManagerError>;
+
+    fn handle(&mut self, msg: SubmitTransaction, _ctx: &mut Context<Self>) -> Self::Result {
+        let hash = self.submit_transaction(msg.transaction, msg.fee)?;
+        debug!("Local transaction {:?} added to the mempool", hash);
+
+        // Announce the transaction right away, instead of waiting for the next rebroadcast
+        self.announce_transactions(vec![hash]);
+
+        Ok(hash)
+    }
+}
+
+/// Handler for ResubmitTransaction message
+impl Handler<ResubmitTransaction> for MempoolManager {
+    type Result = Result<Hash, MempoolManagerError>;
+
+    fn handle(&mut self, msg: ResubmitTransaction, _ctx: &mut Context<Self>) -> Self::Result {
+        let hash = self.resubmit_transaction(msg.hash, msg.fee)?;
+        debug!(
+            "Local transaction {:?} submitted again as {:?} with fee {}",
+            msg.hash, hash, msg.fee
+        );
+
+        self.notify_resubmitted_transaction(msg.hash, hash);
+        self.announce_transactions(vec![hash]);
+
+        Ok(hash)
+    }
+}
+
+/// Handler for TransactionsAnnounced message
+impl Handler<TransactionsAnnounced> for MempoolManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: TransactionsAnnounced, _ctx: &mut Context<Self>) {
+        self.local_transactions
+            .record_announcement(msg.peer, &msg.transactions);
+    }
+}
+
+/// Handler for TransactionsConfirmed message
+impl Handler<TransactionsConfirmed> for MempoolManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: TransactionsConfirmed, _ctx: &mut Context<Self>) {
+        let removed = self.confirm_transactions(msg.block_hash, msg.checkpoint, &msg.transactions);
+        if removed > 0 {
+            debug!(
+                "{}{} transactions included in block {:?} removed from the mempool",
+                msg.trace, removed, msg.block_hash
+            );
+        }
+    }
+}
+
+/// Handler for GetTransactionStatus message
+impl Handler<GetTransactionStatus> for MempoolManager {
+    type Result = Result<Option<LocalTransactionStatus>, MempoolManagerError>;
+
+    fn handle(&mut self, msg: GetTransactionStatus, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(self.local_transactions.status(&msg.hash))
+    }
+}
+
+/// Handler for GetTransactionsToMine message
+impl Handler<GetTransactionsToMine> for MempoolManager {
+    type Result = Result<Vec<MempoolTransaction>, MempoolManagerError>;
+
+    fn handle(&mut self, _msg: GetTransactionsToMine, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(self.get_transactions_to_mine())
+    }
+}
+
+/// Handler for SetFeePolicy message
+impl Handler<SetFeePolicy> for MempoolManager {
+    type Result = Result<FeePolicy, MempoolManagerError>;
+
+    fn handle(&mut self, msg: SetFeePolicy, _ctx: &mut Context<Self>) -> Self::Result {
+        let fee_policy = self.set_fee_policy(msg.min_relay_fee, msg.mining_min_fee);
+        debug!("Fee policy updated: {:?}", fee_policy);
+
+        Ok(fee_policy)
+    }
+}
+
+/// Handler for RecordBlockFees message
+impl Handler<RecordBlockFees> for MempoolManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: RecordBlockFees, _ctx: &mut Context<Self>) {
+        self.fee_estimator.record_block(&msg.fee_rates);
+    }
+}
+
+/// Handler for EstimateFee message
+impl Handler<EstimateFee> for MempoolManager {
+    type Result = Result<Option<u64>, MempoolManagerError>;
+
+    fn handle(&mut self, msg: EstimateFee, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(self.fee_estimator.estimate(msg.target_epochs))
+    }
+}
+
+/// Handler for GetMempool message
+impl Handler<GetMempool> for MempoolManager {
+    type Result = Result<MempoolPage, MempoolManagerError>;
+
+    fn handle(&mut self, msg: GetMempool, _ctx: &mut Context<Self>) -> Self::Result {
+        if msg.limit == 0 || msg.limit > MAX_MEMPOOL_PAGE_SIZE {
+            return Err(MempoolManagerError::InvalidPageSize);
+        }
+
+        Ok(self.get_mempool_page(msg.offset, msg.limit))
+    }
+}
+
+/// Handler for GetMempoolEntry message
+impl Handler<GetMempoolEntry> for MempoolManager {
+    type Result = Result<Option<MempoolEntry>, MempoolManagerError>;
+
+    fn handle(&mut self, msg: GetMempoolEntry, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(self.get_mempool_entry(&msg.hash))
+    }
+}
@@ -0,0 +1,293 @@
+//! # Local transactions
+//!
+//! This module keeps track of the transactions originated by this node,
+//! so they can be announced again until they are confirmed and wallets
+//! can query whether they have been announced to any peer, confirmed,
+//! rejected or expired. The transactions are kept along with the fee they
+//! pay, so the stuck ones can be submitted again with a higher fee.
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_data_structures::chain::{Epoch, Hash, Transaction};
+
+/// Period (in seconds) between two rebroadcasts of the unconfirmed local transactions
+pub const REBROADCAST_PERIOD_SECONDS: u64 = 60;
+
+/// Status of a transaction originated by this node
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum LocalTransactionStatus {
+    /// The transaction is in the mempool but it has not been announced to any peer yet
+    #[serde(rename = "pending")]
+    Pending,
+    /// The transaction has been announced to some peers
+    #[serde(rename = "announced")]
+    Announced {
+        /// Peers to which the transaction has been announced
+        peers: Vec<SocketAddr>,
+    },
+    /// The transaction has been included in a consolidated block
+    #[serde(rename = "confirmed")]
+    Confirmed {
+        /// Hash of the block which includes the transaction
+        block_hash: Hash,
+        /// Number of consolidated blocks since the one which includes the transaction (1 if it
+        /// is the last one)
+        depth: u32,
+    },
+    /// The transaction has not been accepted into the mempool
+    #[serde(rename = "rejected")]
+    Rejected {
+        /// Reason why the transaction was rejected
+        reason: String,
+    },
+    /// The transaction has been dropped from the mempool without being confirmed
+    #[serde(rename = "expired")]
+    Expired,
+}
+
+/// Tracking information of a transaction originated by this node
+#[derive(Debug, Default)]
+struct LocalTransaction {
+    /// Peers to which the transaction has been announced
+    announced_to: HashSet<SocketAddr>,
+    /// Hash and checkpoint of the block which includes the transaction
+    confirmed_in: Option<(Hash, Epoch)>,
+    /// Reason why the transaction was rejected
+    rejected: Option<String>,
+    /// Whether the transaction has been dropped from the mempool without being confirmed
+    expired: bool,
+    /// Transaction and fee it pays, as submitted by this node
+    submitted: Option<(Transaction, u64)>,
+}
+
+/// Transactions originated by this node
+#[derive(Debug, Default)]
+pub struct LocalTransactions {
+    /// Tracking information of every local transaction
+    transactions: HashMap<Hash, LocalTransaction>,
+    /// Checkpoint of the last consolidated block
+    tip_checkpoint: Option<Epoch>,
+}
+
+impl LocalTransactions {
+    /// Start tracking a transaction accepted into the mempool.
+    /// Returns false if the transaction was already being tracked.
+    pub fn track(&mut self, hash: Hash) -> bool {
+        if self.transactions.contains_key(&hash) {
+            return false;
+        }
+        self.transactions.insert(hash, LocalTransaction::default());
+
+        true
+    }
+
+    /// Mark a transaction as rejected
+    pub fn reject(&mut self, hash: Hash, reason: String) {
+        let local_tx = self.transactions.entry(hash).or_default();
+        local_tx.rejected = Some(reason);
+    }
+
+    /// Keep a transaction submitted by this node along with the fee it pays, so it can be
+    /// submitted again
+    pub fn record_submission(&mut self, hash: Hash, transaction: Transaction, fee: u64) {
+        let local_tx = self.transactions.entry(hash).or_default();
+        local_tx.submitted = Some((transaction, fee));
+    }
+
+    /// Get a transaction submitted by this node along with the fee it pays
+    pub fn submission(&self, hash: &Hash) -> Option<&(Transaction, u64)> {
+        self.transactions
+            .get(hash)
+            .and_then(|local_tx| local_tx.submitted.as_ref())
+    }
+
+    /// Mark a transaction as expired, unless it has been confirmed or rejected.
+    /// Returns false if the transaction is not being tracked or it could not be marked.
+    pub fn expire(&mut self, hash: &Hash) -> bool {
+        match self.transactions.get_mut(hash) {
+            Some(local_tx) if local_tx.confirmed_in.is_none() && local_tx.rejected.is_none() => {
+                local_tx.expired = true;
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Stop tracking a transaction (e.g. before submitting it again)
+    pub fn forget(&mut self, hash: &Hash) {
+        self.transactions.remove(hash);
+    }
+
+    /// Record that some transactions have been announced to a peer.
+    /// Returns the number of updated transactions.
+    pub fn record_announcement(&mut self, peer: SocketAddr, transactions: &[Hash]) -> usize {
+        let mut updated = 0;
+        for hash in transactions {
+            if let Some(local_tx) = self.transactions.get_mut(hash) {
+                if local_tx.announced_to.insert(peer) {
+                    updated += 1;
+                }
+            }
+        }
+
+        updated
+    }
+
+    /// Mark as confirmed the transactions included in a new consolidated block.
+    /// Returns the number of updated transactions.
+    pub fn confirm_block(
+        &mut self,
+        block_hash: Hash,
+        checkpoint: Epoch,
+        transactions: &[Hash],
+    ) -> usize {
+        self.tip_checkpoint = Some(
+            self.tip_checkpoint
+                .map_or(checkpoint, |tip| tip.max(checkpoint)),
+        );
+
+        let mut updated = 0;
+        for hash in transactions {
+            if let Some(local_tx) = self.transactions.get_mut(hash) {
+                if local_tx.confirmed_in.is_none() {
+                    local_tx.confirmed_in = Some((block_hash, checkpoint));
+                    updated += 1;
+                }
+            }
+        }
+
+        updated
+    }
+
+    /// Get the hashes of the transactions which must be announced again, that is, the ones that
+    /// have been neither confirmed, rejected nor expired
+    pub fn to_rebroadcast(&self) -> Vec<Hash> {
+        self.transactions
+            .iter()
+            .filter(|(_, local_tx)| {
+                local_tx.confirmed_in.is_none() && local_tx.rejected.is_none() && !local_tx.expired
+            })
+            .map(|(hash, _)| *hash)
+            .collect()
+    }
+
+    /// Get the status of a local transaction
+    pub fn status(&self, hash: &Hash) -> Option<LocalTransactionStatus> {
+        self.transactions.get(hash).map(|local_tx| {
+            match (&local_tx.rejected, local_tx.confirmed_in) {
+                (Some(reason), _) => LocalTransactionStatus::Rejected {
+                    reason: reason.clone(),
+                },
+                (None, Some((block_hash, checkpoint))) => LocalTransactionStatus::Confirmed {
+                    block_hash,
+                    depth: self
+                        .tip_checkpoint
+                        .map_or(0, |tip| tip.saturating_sub(checkpoint) + 1),
+                },
+                (None, None) if local_tx.expired => LocalTransactionStatus::Expired,
+                (None, None) if local_tx.announced_to.is_empty() => LocalTransactionStatus::Pending,
+                (None, None) => {
+                    let mut peers: Vec<SocketAddr> =
+                        local_tx.announced_to.iter().cloned().collect();
+                    peers.sort();
+
+                    LocalTransactionStatus::Announced { peers }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    #[test]
+    fn local_transaction_lifecycle() {
+        let mut local = LocalTransactions::default();
+        let tx_hash = Hash::SHA256([1; 32]);
+        let block_hash = Hash::SHA256([2; 32]);
+
+        assert!(local.track(tx_hash));
+        assert!(!local.track(tx_hash));
+        assert_eq!(
+            local.status(&tx_hash),
+            Some(LocalTransactionStatus::Pending)
+        );
+
+        assert_eq!(local.record_announcement(peer(2), &[tx_hash]), 1);
+        assert_eq!(local.record_announcement(peer(1), &[tx_hash]), 1);
+        assert_eq!(local.record_announcement(peer(1), &[tx_hash]), 0);
+        assert_eq!(
+            local.status(&tx_hash),
+            Some(LocalTransactionStatus::Announced {
+                peers: vec![peer(1), peer(2)]
+            })
+        );
+        assert_eq!(local.to_rebroadcast(), vec![tx_hash]);
+
+        assert_eq!(local.confirm_block(block_hash, 5, &[tx_hash]), 1);
+        local.confirm_block(Hash::SHA256([3; 32]), 7, &[]);
+        assert_eq!(
+            local.status(&tx_hash),
+            Some(LocalTransactionStatus::Confirmed {
+                block_hash,
+                depth: 3
+            })
+        );
+        assert!(local.to_rebroadcast().is_empty());
+    }
+
+    #[test]
+    fn rejected_local_transaction() {
+        let mut local = LocalTransactions::default();
+        let tx_hash = Hash::SHA256([1; 32]);
+
+        local.reject(tx_hash, "fee below the minimum relay fee".to_string());
+
+        assert_eq!(
+            local.status(&tx_hash),
+            Some(LocalTransactionStatus::Rejected {
+                reason: "fee below the minimum relay fee".to_string()
+            })
+        );
+        assert!(local.to_rebroadcast().is_empty());
+        assert_eq!(local.status(&Hash::SHA256([0; 32])), None);
+    }
+
+    #[test]
+    fn expired_local_transaction() {
+        let mut local = LocalTransactions::default();
+        let tx_hash = Hash::SHA256([1; 32]);
+        let confirmed_hash = Hash::SHA256([2; 32]);
+
+        local.track(tx_hash);
+        local.record_submission(tx_hash, Transaction, 5);
+        local.track(confirmed_hash);
+        local.confirm_block(Hash::SHA256([3; 32]), 1, &[confirmed_hash]);
+
+        assert!(local.expire(&tx_hash));
+        // Confirmed and unknown transactions do not expire
+        assert!(!local.expire(&confirmed_hash));
+        assert!(!local.expire(&Hash::SHA256([0; 32])));
+
+        assert_eq!(
+            local.status(&tx_hash),
+            Some(LocalTransactionStatus::Expired)
+        );
+        assert!(local.to_rebroadcast().is_empty());
+        // The transaction is kept so it can be submitted again
+        assert_eq!(local.submission(&tx_hash), Some(&(Transaction, 5)));
+
+        local.forget(&tx_hash);
+        assert_eq!(local.status(&tx_hash), None);
+        assert_eq!(local.submission(&tx_hash), None);
+    }
+}
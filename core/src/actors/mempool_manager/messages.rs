@@ -0,0 +1,146 @@
+use std::net::SocketAddr;
+
+use actix::Message;
+
+use crate::actors::mempool_manager::{
+    local_transactions::LocalTransactionStatus, FeePolicy, MempoolEntry, MempoolManagerError,
+    MempoolPage, MempoolTransaction,
+};
+use crate::tracing::Trace;
+use witnet_data_structures::chain::{Epoch, Hash, Transaction};
+
+/// Add a new transaction to the mempool
+pub struct AddTransaction {
+    /// Transaction
+    pub transaction: Transaction,
+    /// Fee paid by the transaction
+    pub fee: u64,
+}
+
+impl Message for AddTransaction {
+    type Result = Result<(), MempoolManagerError>;
+}
+
+/// Add a new transaction originated by this node to the mempool and announce it to the peers
+pub struct SubmitTransaction {
+    /// Transaction
+    pub transaction: Transaction,
+    /// Fee paid by the transaction
+    pub fee: u64,
+}
+
+impl Message for SubmitTransaction {
+    type Result = Result<Hash, MempoolManagerError>;
+}
+
+/// Submit again a transaction originated by this node which has not been confirmed, paying a
+/// higher fee, and announce it to the peers
+pub struct ResubmitTransaction {
+    /// Hash of the transaction
+    pub hash: Hash,
+    /// New fee paid by the transaction
+    pub fee: u64,
+}
+
+impl Message for ResubmitTransaction {
+    type Result = Result<Hash, MempoolManagerError>;
+}
+
+/// Notify that some transactions have been announced to a peer
+pub struct TransactionsAnnounced {
+    /// Socket address which identifies the peer
+    pub peer: SocketAddr,
+    /// Hashes of the announced transactions
+    pub transactions: Vec<Hash>,
+}
+
+impl Message for TransactionsAnnounced {
+    type Result = ();
+}
+
+/// Notify the transactions included in a new consolidated block
+pub struct TransactionsConfirmed {
+    /// Hash of the block
+    pub block_hash: Hash,
+    /// Checkpoint of the block
+    pub checkpoint: Epoch,
+    /// Hashes of the transactions included in the block
+    pub transactions: Vec<Hash>,
+    /// Correlation ID of the protocol message whose processing consolidated the block
+    pub trace: Trace,
+}
+
+impl Message for TransactionsConfirmed {
+    type Result = ();
+}
+
+/// Ask for the status of a transaction originated by this node
+pub struct GetTransactionStatus {
+    /// Hash of the transaction
+    pub hash: Hash,
+}
+
+impl Message for GetTransactionStatus {
+    type Result = Result<Option<LocalTransactionStatus>, MempoolManagerError>;
+}
+
+/// Ask for the transactions that pay at least the minimum fee for mining, along with their fees
+pub struct GetTransactionsToMine;
+
+impl Message for GetTransactionsToMine {
+    type Result = Result<Vec<MempoolTransaction>, MempoolManagerError>;
+}
+
+/// Update the fee policy (values set to `None` are left unchanged)
+pub struct SetFeePolicy {
+    /// Minimum fee for a transaction to be accepted into the mempool and relayed
+    pub min_relay_fee: Option<u64>,
+    /// Minimum fee for a transaction to be included in the blocks mined by this node
+    pub mining_min_fee: Option<u64>,
+}
+
+impl Message for SetFeePolicy {
+    type Result = Result<FeePolicy, MempoolManagerError>;
+}
+
+/// Record the fee rates of the transactions included in a new consolidated block
+pub struct RecordBlockFees {
+    /// Fee rates of the transactions included in the block
+    pub fee_rates: Vec<u64>,
+}
+
+impl Message for RecordBlockFees {
+    type Result = ();
+}
+
+/// Estimate the fee rate needed for a transaction to be included within a number of epochs
+pub struct EstimateFee {
+    /// Number of epochs
+    pub target_epochs: Epoch,
+}
+
+impl Message for EstimateFee {
+    type Result = Result<Option<u64>, MempoolManagerError>;
+}
+
+/// Ask for a page of the hashes of the transactions in the mempool
+pub struct GetMempool {
+    /// Number of transactions to skip
+    pub offset: usize,
+    /// Maximum number of transactions to return
+    pub limit: usize,
+}
+
+impl Message for GetMempool {
+    type Result = Result<MempoolPage, MempoolManagerError>;
+}
+
+/// Ask for the details of a transaction in the mempool
+pub struct GetMempoolEntry {
+    /// Hash of the transaction
+    pub hash: Hash,
+}
+
+impl Message for GetMempoolEntry {
+    type Result = Result<Option<MempoolEntry>, MempoolManagerError>;
+}
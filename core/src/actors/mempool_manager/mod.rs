@@ -11,12 +11,769 @@
 //! * Keeping valid transactions into memory. This in-memory transaction pool is what we call the _mempool_. Valid transactions are immediately appended to the mempool.
 //! * Receiving confirmation notifications from [BlocksManager](actors::blocks_manager::BlocksManager). This notifications tell that a certain transaction ID has been anchored into a new block and thus it can be removed from the mempool and persisted into local storage (for archival purposes, non-archival nodes can just drop them).
 //! * Notifying [UtxoManager](actors::utxo_manager::UtxoManager) for it to apply a valid transaction on the UTXO set.
+//! * Exposing the pending transactions and their details (fee, fee rate, arrival time, dependencies) for wallets and debugging.
+//! * Announcing the transactions originated by this node again until they are confirmed, and keeping track of their status (announced to which peers, confirmed, rejected or expired).
+//! * Dropping the transactions which have not been confirmed within `mempool.expiry_epochs` epochs, notifying the [WalletManager](actors::wallet_manager::WalletManager) of the expired transactions originated by this node, which can be submitted again with a higher fee.
+//! * Persisting the mempool into storage (periodically and on shutdown) and reloading it on startup, so pending transactions survive a restart of the node.
+
+use std::time::Duration;
+
+use actix::{ActorFuture, Context, ContextFutureSpawner, System, WrapFuture};
+use log::{error, info};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::actors::{
+    node,
+    session::messages::AnnounceTransactions,
+    sessions_manager::{messages::Broadcast, SessionsManager},
+    storage_keys::MEMPOOL_KEY,
+    storage_manager::{messages::Put, put_with_retry},
+    wallet_manager::{
+        messages::{TransactionResubmitted, TransactionsExpired},
+        WalletManager,
+    },
+};
+use crate::errors::{NodeError, STORAGE_RETRY_POLICY};
+use witnet_crypto::hash::calculate_sha256;
+use witnet_data_structures::chain::{Epoch, Hash, Transaction};
+use witnet_storage::{error::StorageError, storage::Storable};
+use witnet_util::{error::WitnetError, timestamp::get_timestamp};
+
+use self::local_transactions::LocalTransactionStatus;
 
 mod actor;
+mod handlers;
+
+/// Fee estimator used by the MempoolManager
+pub mod fee_estimator;
+
+/// Tracking of the transactions originated by this node
+pub mod local_transactions;
+
+/// Messages for MempoolManager
+pub mod messages;
+
+/// Possible errors when interacting with MempoolManager
+#[derive(Debug)]
+pub enum MempoolManagerError {
+    /// The fee paid by a transaction is below the minimum relay fee
+    FeeBelowMinRelayFee,
+    /// The requested page size is zero or bigger than `MAX_MEMPOOL_PAGE_SIZE`
+    InvalidPageSize,
+    /// The node neither relays nor mines the transactions of other nodes
+    TransactionsNotAccepted,
+    /// The transaction spends outputs which are time-locked until a future epoch
+    TimeLockedInputs,
+    /// The transaction was not originated by this node
+    TransactionNotFound,
+    /// The transaction has already been included in a consolidated block
+    TransactionAlreadyConfirmed,
+    /// The fee of a transaction submitted again is not higher than its previous fee
+    FeeNotIncreased,
+    /// StorageError
+    StorageError(WitnetError<StorageError>),
+}
+
+impl From<WitnetError<StorageError>> for MempoolManagerError {
+    fn from(x: WitnetError<StorageError>) -> Self {
+        MempoolManagerError::StorageError(x)
+    }
+}
+
+/// Maximum number of transaction hashes that can be returned in a single page of the mempool
+pub const MAX_MEMPOOL_PAGE_SIZE: usize = 1000;
+
+/// Fee policy applied to the transactions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeePolicy {
+    /// Minimum fee a transaction must pay to be accepted into the mempool and relayed
+    pub min_relay_fee: u64,
+    /// Minimum fee a transaction must pay to be included in the blocks mined by this node
+    pub mining_min_fee: u64,
+}
+
+/// Transaction stored in the mempool along with the fee it pays
+/// The mempools persisted by older versions of the node only contain the transaction and its fee,
+/// so the fields added after them must be the last ones and have a default value
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MempoolTransaction {
+    /// Transaction
+    pub transaction: Transaction,
+    /// Fee paid by the transaction
+    pub fee: u64,
+    /// Hash of the transaction (recalculated when the mempool is restored)
+    #[serde(default = "unknown_hash")]
+    pub hash: Hash,
+    /// Size of the serialized transaction in bytes (recalculated when the mempool is restored)
+    #[serde(default)]
+    pub size: usize,
+    /// Timestamp when the transaction was first received, or 0 if it is not known
+    #[serde(default)]
+    pub received_at: i64,
+    /// Epoch when the transaction was first received, once known
+    #[serde(default)]
+    pub received_epoch: Option<Epoch>,
+}
+
+impl MempoolTransaction {
+    /// Method to create a new mempool transaction, calculating its hash and size
+    pub fn new(
+        transaction: Transaction,
+        fee: u64,
+        received_at: i64,
+    ) -> Result<Self, MempoolManagerError> {
+        let size = transaction.to_bytes()?.len();
+
+        Ok(Self {
+            hash: calculate_sha256(&transaction.hashed_bytes()),
+            transaction,
+            size,
+            fee,
+            received_at,
+            received_epoch: None,
+        })
+    }
+
+    /// Method to recalculate the hash and the size of a transaction reloaded from storage, which
+    /// are not known if it was persisted by an older version of the node
+    /// Transactions with an unknown arrival time are considered to be received at `now`
+    fn restored(self, now: i64) -> Result<Self, MempoolManagerError> {
+        let received_at = if self.received_at == 0 {
+            now
+        } else {
+            self.received_at
+        };
+
+        Ok(Self {
+            received_epoch: self.received_epoch,
+            ..Self::new(self.transaction, self.fee, received_at)?
+        })
+    }
+}
+
+/// Hash of the transactions persisted by older versions of the node, which did not include it
+fn unknown_hash() -> Hash {
+    Hash::SHA256([0; 32])
+}
+
+/// Details of a transaction in the mempool
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MempoolEntry {
+    /// Hash of the transaction
+    pub hash: Hash,
+    /// Size of the serialized transaction (in bytes)
+    pub size: usize,
+    /// Fee paid by the transaction
+    pub fee: u64,
+    /// Fee rate of the transaction (fee per weight unit)
+    pub fee_rate: u64,
+    /// Timestamp when the transaction was first received
+    pub received_at: i64,
+    /// Hashes of the transactions in the mempool whose outputs are spent by this transaction
+    pub depends: Vec<Hash>,
+}
+
+/// Page of the hashes of the transactions in the mempool
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MempoolPage {
+    /// Total number of transactions in the mempool
+    pub total: usize,
+    /// Hashes of the transactions in the page, sorted by arrival order
+    pub transactions: Vec<Hash>,
+}
 
 ////////////////////////////////////////////////////////////////////////////////////////
 // ACTOR BASIC STRUCTURE
 ////////////////////////////////////////////////////////////////////////////////////////
 /// MempoolManager actor
 #[derive(Default)]
-pub struct MempoolManager;
+pub struct MempoolManager {
+    /// Fee policy for relaying and mining transactions
+    fee_policy: FeePolicy,
+    /// Transactions pool
+    transactions: Vec<MempoolTransaction>,
+    /// Fee estimator based on recent blocks
+    fee_estimator: fee_estimator::FeeEstimator,
+    /// Transactions originated by this node
+    local_transactions: local_transactions::LocalTransactions,
+    /// Whether the transactions of other nodes are accepted into the mempool (only if the node
+    /// has the relay or the miner role)
+    accept_transactions: bool,
+    /// Number of epochs after which the unconfirmed transactions are dropped (0 disables the
+    /// expiry)
+    expiry_epochs: Epoch,
+    /// Current epoch, once notified by the EpochManager
+    current_epoch: Option<Epoch>,
+}
+
+/// Auxiliary methods for MempoolManager actor
+impl MempoolManager {
+    /// Method to add a transaction to the mempool
+    /// Transactions paying less than the minimum relay fee are rejected
+    fn add_transaction(
+        &mut self,
+        transaction: Transaction,
+        fee: u64,
+    ) -> Result<(), MempoolManagerError> {
+        let mempool_tx = MempoolTransaction::new(transaction, fee, get_timestamp())?;
+
+        self.insert_transaction(mempool_tx)
+    }
+
+    /// Method to add a transaction originated by this node to the mempool and start tracking
+    /// its status (rejected transactions are tracked too, so the reason can be queried)
+    /// Returns the hash of the transaction
+    fn submit_transaction(
+        &mut self,
+        transaction: Transaction,
+        fee: u64,
+    ) -> Result<Hash, MempoolManagerError> {
+        let mempool_tx = MempoolTransaction::new(transaction.clone(), fee, get_timestamp())?;
+        let hash = mempool_tx.hash;
+        self.local_transactions
+            .record_submission(hash, transaction, fee);
+
+        match self.insert_transaction(mempool_tx) {
+            Ok(()) => {
+                self.local_transactions.track(hash);
+
+                Ok(hash)
+            }
+            Err(MempoolManagerError::FeeBelowMinRelayFee) => {
+                self.local_transactions.reject(
+                    hash,
+                    format!(
+                        "fee {} is below the minimum relay fee {}",
+                        fee, self.fee_policy.min_relay_fee
+                    ),
+                );
+
+                Err(MempoolManagerError::FeeBelowMinRelayFee)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Method to submit again a transaction originated by this node which has not been confirmed
+    /// (e.g. because it expired), paying a higher fee
+    /// Returns the hash of the new transaction
+    fn resubmit_transaction(&mut self, hash: Hash, fee: u64) -> Result<Hash, MempoolManagerError> {
+        match self.local_transactions.status(&hash) {
+            None => return Err(MempoolManagerError::TransactionNotFound),
+            Some(LocalTransactionStatus::Confirmed { .. }) => {
+                return Err(MempoolManagerError::TransactionAlreadyConfirmed)
+            }
+            Some(_) => {}
+        }
+        let (transaction, previous_fee) = self
+            .local_transactions
+            .submission(&hash)
+            .cloned()
+            .ok_or(MempoolManagerError::TransactionNotFound)?;
+        if fee <= previous_fee {
+            return Err(MempoolManagerError::FeeNotIncreased);
+        }
+
+        // FIXME(#99): transactions do not define inputs nor outputs yet, so the transaction is
+        // rebuilt spending the same inputs and it only differs in the fee it pays
+        self.transactions
+            .retain(|mempool_tx| mempool_tx.hash != hash);
+        self.local_transactions.forget(&hash);
+
+        self.submit_transaction(transaction, fee)
+    }
+
+    /// Method to drop from the mempool the transactions which have not been confirmed within
+    /// `expiry_epochs` epochs, given the current epoch
+    /// Returns the hashes of the dropped transactions originated by this node
+    fn expire_transactions(&mut self, current_epoch: Epoch) -> Vec<Hash> {
+        self.current_epoch = Some(current_epoch);
+        // The transactions received before the current epoch was known start expiring now
+        for mempool_tx in self.transactions.iter_mut() {
+            mempool_tx.received_epoch.get_or_insert(current_epoch);
+        }
+        if self.expiry_epochs == 0 {
+            return vec![];
+        }
+
+        let expiry_epochs = self.expiry_epochs;
+        let (expired, kept): (Vec<_>, Vec<_>) =
+            self.transactions.drain(..).partition(|mempool_tx| {
+                let received_epoch = mempool_tx.received_epoch.unwrap_or(current_epoch);
+                current_epoch.saturating_sub(received_epoch) >= expiry_epochs
+            });
+        self.transactions = kept;
+
+        let mut expired_local = vec![];
+        for mempool_tx in expired {
+            if self.local_transactions.expire(&mempool_tx.hash)
+                && !expired_local.contains(&mempool_tx.hash)
+            {
+                expired_local.push(mempool_tx.hash);
+            }
+        }
+
+        expired_local
+    }
+
+    /// Method to let the WalletManager know that some transactions originated by this node have
+    /// expired
+    fn notify_expired_transactions(&self, transactions: Vec<Hash>) {
+        if transactions.is_empty() {
+            return;
+        }
+
+        System::current()
+            .registry()
+            .get::<WalletManager>()
+            .do_send(TransactionsExpired { transactions });
+    }
+
+    /// Method to let the WalletManager know that a transaction originated by this node has been
+    /// submitted again
+    fn notify_resubmitted_transaction(&self, previous_hash: Hash, hash: Hash) {
+        System::current()
+            .registry()
+            .get::<WalletManager>()
+            .do_send(TransactionResubmitted {
+                previous_hash,
+                hash,
+            });
+    }
+
+    /// Method to announce transactions to the peers of all the consolidated outbound sessions
+    fn announce_transactions(&self, transactions: Vec<Hash>) {
+        if transactions.is_empty() {
+            return;
+        }
+
+        // Get SessionsManager address
+        let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
+        sessions_manager_addr.do_send(Broadcast {
+            command: AnnounceTransactions { transactions },
+        });
+    }
+
+    /// Method to periodically announce again the local transactions which have been neither
+    /// confirmed nor rejected
+    fn rebroadcast_periodically(&self, ctx: &mut Context<Self>) {
+        let period = Duration::from_secs(local_transactions::REBROADCAST_PERIOD_SECONDS);
+        ctx.run_later(period, |act, ctx| {
+            act.announce_transactions(act.local_transactions.to_rebroadcast());
+            act.rebroadcast_periodically(ctx);
+        });
+    }
+
+    /// Method to remove from the mempool the transactions included in a new consolidated block
+    /// Returns the number of removed transactions
+    fn confirm_transactions(
+        &mut self,
+        block_hash: Hash,
+        checkpoint: Epoch,
+        transactions: &[Hash],
+    ) -> usize {
+        let num_transactions = self.transactions.len();
+        self.transactions
+            .retain(|mempool_tx| !transactions.contains(&mempool_tx.hash));
+        self.local_transactions
+            .confirm_block(block_hash, checkpoint, transactions);
+
+        num_transactions - self.transactions.len()
+    }
+
+    /// Method to insert a transaction into the mempool, keeping its arrival time
+    /// Transactions paying less than the minimum relay fee or spending time-locked outputs are
+    /// rejected
+    fn insert_transaction(
+        &mut self,
+        mut mempool_tx: MempoolTransaction,
+    ) -> Result<(), MempoolManagerError> {
+        if mempool_tx.fee < self.fee_policy.min_relay_fee {
+            return Err(MempoolManagerError::FeeBelowMinRelayFee);
+        }
+        if spends_time_locked_outputs(&mempool_tx.transaction) {
+            return Err(MempoolManagerError::TimeLockedInputs);
+        }
+
+        if mempool_tx.received_epoch.is_none() {
+            mempool_tx.received_epoch = self.current_epoch;
+        }
+        self.transactions.push(mempool_tx);
+
+        Ok(())
+    }
+
+    /// Method to add the transactions reloaded from storage to the mempool
+    /// Transactions are validated again, as the UTXO set and the fee policy could have changed
+    /// since they were persisted
+    /// Returns the number of restored transactions
+    fn restore_transactions(&mut self, transactions: Vec<MempoolTransaction>) -> usize {
+        let now = get_timestamp();
+
+        transactions
+            .into_iter()
+            .filter_map(|mempool_tx| mempool_tx.restored(now).ok())
+            .filter(|mempool_tx| validate_transaction(&mempool_tx.transaction))
+            .filter(|mempool_tx| self.insert_transaction(mempool_tx.clone()).is_ok())
+            .count()
+    }
+
+    /// Method to persist the mempool into storage
+    fn persist_transactions(&self, ctx: &mut Context<Self>) {
+        // A mempool which cannot be encoded would never be persisted, so the node is stopped
+        let msg = match Put::from_value(MEMPOOL_KEY, &self.transactions) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error encoding the mempool: {}", e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+
+        // Persist transactions into storage. `AsyncContext::wait` registers
+        // future within context, but context waits until this future resolves
+        // before processing any other events.
+        put_with_retry(msg, STORAGE_RETRY_POLICY)
+            .into_actor(self)
+            .then(|res, _act, _ctx| {
+                match res {
+                    Ok(()) => info!("MempoolManager successfully persisted the mempool"),
+                    Err(e) => {
+                        error!(
+                            "MempoolManager failed to persist the mempool into storage: {}",
+                            e
+                        );
+                        node::escalate(&e);
+                    }
+                }
+                actix::fut::ok(())
+            })
+            .wait(ctx);
+    }
+
+    /// Method to periodically persist the mempool into storage
+    fn persist_transactions_periodically(&self, ctx: &mut Context<Self>, storage_period: Duration) {
+        ctx.run_later(storage_period, move |act, ctx| {
+            act.persist_transactions(ctx);
+            act.persist_transactions_periodically(ctx, storage_period);
+        });
+    }
+
+    /// Method to get the transactions that pay at least the minimum fee for mining, along with
+    /// their fees (which are paid to the miner in the mint transaction of the block)
+    fn get_transactions_to_mine(&self) -> Vec<MempoolTransaction> {
+        self.transactions
+            .iter()
+            .filter(|mempool_tx| mempool_tx.fee >= self.fee_policy.mining_min_fee)
+            .cloned()
+            .collect()
+    }
+
+    /// Method to get a page of the hashes of the transactions in the mempool, sorted by arrival
+    /// order
+    fn get_mempool_page(&self, offset: usize, limit: usize) -> MempoolPage {
+        MempoolPage {
+            total: self.transactions.len(),
+            transactions: self
+                .transactions
+                .iter()
+                .skip(offset)
+                .take(limit)
+                .map(|mempool_tx| mempool_tx.hash)
+                .collect(),
+        }
+    }
+
+    /// Method to get the details of a transaction in the mempool
+    fn get_mempool_entry(&self, hash: &Hash) -> Option<MempoolEntry> {
+        self.transactions
+            .iter()
+            .find(|mempool_tx| mempool_tx.hash == *hash)
+            .map(|mempool_tx| MempoolEntry {
+                hash: mempool_tx.hash,
+                size: mempool_tx.size,
+                fee: mempool_tx.fee,
+                fee_rate: fee_estimator::fee_rate(&mempool_tx.transaction, mempool_tx.fee),
+                received_at: mempool_tx.received_at,
+                depends: self.get_dependencies(&mempool_tx.transaction),
+            })
+    }
+
+    /// Method to get the hashes of the transactions in the mempool whose outputs are spent by
+    /// a transaction
+    // FIXME(#99): transactions do not define inputs yet, so they cannot depend on other
+    // transactions
+    fn get_dependencies(&self, _transaction: &Transaction) -> Vec<Hash> {
+        vec![]
+    }
+
+    /// Method to update the fee policy
+    /// Only the provided values are updated
+    fn set_fee_policy(
+        &mut self,
+        min_relay_fee: Option<u64>,
+        mining_min_fee: Option<u64>,
+    ) -> FeePolicy {
+        if let Some(min_relay_fee) = min_relay_fee {
+            self.fee_policy.min_relay_fee = min_relay_fee;
+        }
+        if let Some(mining_min_fee) = mining_min_fee {
+            self.fee_policy.mining_min_fee = mining_min_fee;
+        }
+
+        self.fee_policy
+    }
+}
+
+/// Check a transaction against the current UTXO set
+// FIXME(#99): transactions do not define inputs yet, so they cannot be checked against the UTXO
+// set and every transaction is considered valid
+fn validate_transaction(_transaction: &Transaction) -> bool {
+    true
+}
+
+/// Check whether a transaction spends outputs whose time lock has not expired yet, so it cannot be
+/// included in the next block
+// FIXME(#99): transactions do not define inputs yet, so they cannot spend time-locked outputs
+fn spends_time_locked_outputs(_transaction: &Transaction) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reject_below_min_relay_fee() {
+        let mut mm = MempoolManager::default();
+        mm.set_fee_policy(Some(10), None);
+
+        assert!(mm.add_transaction(Transaction, 9).is_err());
+        assert!(mm.add_transaction(Transaction, 10).is_ok());
+        assert_eq!(mm.transactions.len(), 1);
+    }
+
+    #[test]
+    fn skip_below_mining_min_fee() {
+        let mut mm = MempoolManager::default();
+        mm.add_transaction(Transaction, 5).unwrap();
+        mm.add_transaction(Transaction, 15).unwrap();
+
+        assert_eq!(mm.get_transactions_to_mine().len(), 2);
+
+        mm.set_fee_policy(None, Some(10));
+        let to_mine = mm.get_transactions_to_mine();
+        assert_eq!(to_mine.len(), 1);
+        assert_eq!(to_mine[0].fee, 15);
+    }
+
+    #[test]
+    fn restore_transactions_revalidated() {
+        let mut mm = MempoolManager::default();
+        mm.set_fee_policy(Some(10), None);
+        let persisted = vec![
+            MempoolTransaction::new(Transaction, 5, 100).unwrap(),
+            MempoolTransaction::new(Transaction, 15, 200).unwrap(),
+        ];
+
+        // The fee policy changed since the mempool was persisted
+        assert_eq!(mm.restore_transactions(persisted), 1);
+        assert_eq!(mm.transactions.len(), 1);
+        assert_eq!(mm.transactions[0].fee, 15);
+        // The arrival time is kept
+        assert_eq!(mm.transactions[0].received_at, 200);
+    }
+
+    #[test]
+    fn restore_transactions_persisted_by_older_versions() {
+        #[derive(Serialize)]
+        struct OldMempoolTransaction {
+            transaction: Transaction,
+            fee: u64,
+        }
+
+        let bytes = vec![OldMempoolTransaction {
+            transaction: Transaction,
+            fee: 15,
+        }]
+        .to_bytes()
+        .unwrap();
+        let persisted = Vec::<MempoolTransaction>::from_bytes(&bytes).unwrap();
+        assert_eq!(persisted[0].fee, 15);
+        assert_eq!(persisted[0].received_at, 0);
+
+        let mut mm = MempoolManager::default();
+        assert_eq!(mm.restore_transactions(persisted), 1);
+        // The hash, the size and the arrival time are recalculated
+        let expected = MempoolTransaction::new(Transaction, 15, 0).unwrap();
+        assert_eq!(mm.transactions[0].hash, expected.hash);
+        assert_eq!(mm.transactions[0].size, expected.size);
+        assert_ne!(mm.transactions[0].received_at, 0);
+    }
+
+    #[test]
+    fn get_mempool_page() {
+        let mut mm = MempoolManager::default();
+        for fee in 0..5 {
+            mm.add_transaction(Transaction, fee).unwrap();
+        }
+        let hash = mm.transactions[0].hash;
+
+        let page = mm.get_mempool_page(3, 10);
+        assert_eq!(page.total, 5);
+        assert_eq!(page.transactions, vec![hash, hash]);
+
+        assert!(mm.get_mempool_page(5, 10).transactions.is_empty());
+    }
+
+    #[test]
+    fn get_mempool_entry() {
+        let mut mm = MempoolManager::default();
+        let mempool_tx = MempoolTransaction::new(Transaction, 7, 100).unwrap();
+        let hash = mempool_tx.hash;
+        mm.insert_transaction(mempool_tx.clone()).unwrap();
+
+        assert_eq!(
+            mm.get_mempool_entry(&hash),
+            Some(MempoolEntry {
+                hash,
+                size: mempool_tx.size,
+                fee: 7,
+                fee_rate: 7,
+                received_at: 100,
+                depends: vec![],
+            })
+        );
+        assert_eq!(mm.get_mempool_entry(&Hash::SHA256([0; 32])), None);
+    }
+
+    #[test]
+    fn submit_local_transactions() {
+        use super::local_transactions::LocalTransactionStatus;
+
+        let mut mm = MempoolManager::default();
+        mm.set_fee_policy(Some(10), None);
+
+        let hash = mm.submit_transaction(Transaction, 10).unwrap();
+        assert_eq!(
+            mm.local_transactions.status(&hash),
+            Some(LocalTransactionStatus::Pending)
+        );
+
+        let block_hash = Hash::SHA256([2; 32]);
+        assert_eq!(mm.confirm_transactions(block_hash, 1, &[hash]), 1);
+        assert!(mm.transactions.is_empty());
+        assert_eq!(
+            mm.local_transactions.status(&hash),
+            Some(LocalTransactionStatus::Confirmed {
+                block_hash,
+                depth: 1
+            })
+        );
+    }
+
+    #[test]
+    fn submit_local_transaction_rejected() {
+        use super::local_transactions::LocalTransactionStatus;
+
+        let mut mm = MempoolManager::default();
+        mm.set_fee_policy(Some(10), None);
+        let hash = MempoolTransaction::new(Transaction, 9, 0).unwrap().hash;
+
+        assert!(mm.submit_transaction(Transaction, 9).is_err());
+        assert_eq!(
+            mm.local_transactions.status(&hash),
+            Some(LocalTransactionStatus::Rejected {
+                reason: "fee 9 is below the minimum relay fee 10".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn expire_unconfirmed_transactions() {
+        use super::local_transactions::LocalTransactionStatus;
+
+        let mut mm = MempoolManager::default();
+        mm.expiry_epochs = 10;
+        // Received before the current epoch was known
+        mm.add_transaction(Transaction, 1).unwrap();
+        assert!(mm.expire_transactions(5).is_empty());
+        assert_eq!(mm.transactions[0].received_epoch, Some(5));
+
+        let hash = mm.submit_transaction(Transaction, 2).unwrap();
+        assert!(mm.expire_transactions(14).is_empty());
+        assert_eq!(mm.transactions.len(), 2);
+
+        assert_eq!(mm.expire_transactions(15), vec![hash]);
+        assert!(mm.transactions.is_empty());
+        assert_eq!(
+            mm.local_transactions.status(&hash),
+            Some(LocalTransactionStatus::Expired)
+        );
+    }
+
+    #[test]
+    fn expiry_disabled() {
+        let mut mm = MempoolManager::default();
+        mm.add_transaction(Transaction, 1).unwrap();
+
+        assert!(mm.expire_transactions(1_000_000).is_empty());
+        assert_eq!(mm.transactions.len(), 1);
+    }
+
+    #[test]
+    fn resubmit_with_higher_fee() {
+        use super::local_transactions::LocalTransactionStatus;
+
+        let mut mm = MempoolManager::default();
+        mm.expiry_epochs = 10;
+        mm.expire_transactions(1);
+        let hash = mm.submit_transaction(Transaction, 5).unwrap();
+        mm.expire_transactions(11);
+        assert!(mm.transactions.is_empty());
+
+        match mm.resubmit_transaction(hash, 5) {
+            Err(MempoolManagerError::FeeNotIncreased) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match mm.resubmit_transaction(Hash::SHA256([0; 32]), 10) {
+            Err(MempoolManagerError::TransactionNotFound) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let new_hash = mm.resubmit_transaction(hash, 10).unwrap();
+        assert_eq!(mm.transactions.len(), 1);
+        assert_eq!(mm.transactions[0].fee, 10);
+        assert_eq!(mm.transactions[0].received_epoch, Some(11));
+        assert_eq!(
+            mm.local_transactions.status(&new_hash),
+            Some(LocalTransactionStatus::Pending)
+        );
+
+        mm.confirm_transactions(Hash::SHA256([2; 32]), 12, &[new_hash]);
+        match mm.resubmit_transaction(new_hash, 20) {
+            Err(MempoolManagerError::TransactionAlreadyConfirmed) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn set_fee_policy_partially() {
+        let mut mm = MempoolManager::default();
+
+        assert_eq!(
+            mm.set_fee_policy(Some(1), Some(2)),
+            FeePolicy {
+                min_relay_fee: 1,
+                mining_min_fee: 2
+            }
+        );
+        assert_eq!(
+            mm.set_fee_policy(None, Some(3)),
+            FeePolicy {
+                min_relay_fee: 1,
+                mining_min_fee: 3
+            }
+        );
+    }
+}
@@ -16,6 +16,9 @@ pub mod session;
 /// Storage manager actor module
 pub mod storage_manager;
 
+/// Restart policies of the supervised actors
+pub mod supervision;
+
 /// Config manager actor module
 pub mod config_manager;
 
@@ -40,5 +43,18 @@ pub mod utxo_manager;
 /// InventoryManager actor module
 pub mod inventory_manager;
 
+/// WalletManager actor module
+pub mod wallet_manager;
+
+/// DataRequestsManager actor module
+pub mod data_requests_manager;
+
+/// TelemetryManager actor module
+#[cfg(feature = "telemetry")]
+pub mod telemetry_manager;
+
 /// JSON RPC server
 pub mod json_rpc;
+
+/// SignatureVerifier actor module
+pub mod signature_verifier;
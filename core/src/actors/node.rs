@@ -1,30 +1,72 @@
 use std::{io, path::PathBuf, process::exit, result::Result};
 
-use actix::{Actor, System};
-use log::info;
+use actix::{Actor, Supervisor, System};
+use log::{error, info};
 
+use crate::errors::NodeError;
+use crate::tracing;
+
+use crate::actors::connections_manager::port_mapping;
+#[cfg(feature = "telemetry")]
+use crate::actors::telemetry_manager::TelemetryManager;
 use crate::actors::{
     blocks_manager::BlocksManager, config_manager::ConfigManager,
-    connections_manager::ConnectionsManager, epoch_manager::EpochManager,
-    inventory_manager::InventoryManager, json_rpc::JsonRpcServer, mempool_manager::MempoolManager,
-    peers_manager::PeersManager, sessions_manager::SessionsManager,
-    storage_manager::StorageManager, utxo_manager::UtxoManager,
+    connections_manager::ConnectionsManager, data_requests_manager::DataRequestsManager,
+    epoch_manager::EpochManager, inventory_manager::InventoryManager, json_rpc::JsonRpcServer,
+    mempool_manager::MempoolManager, peers_manager::PeersManager,
+    sessions_manager::SessionsManager, storage_manager::StorageManager, utxo_manager::UtxoManager,
+    wallet_manager::WalletManager,
 };
 
 /// Function to run the main system
 pub fn run(config: Option<PathBuf>, callback: fn()) -> Result<(), io::Error> {
+    let config_manager = load_config(config)?;
+
     // Init system
     let system = System::new("node");
 
     // Call cb function (register interrupt handlers)
     callback();
 
+    start_actors(config_manager);
+
+    // Run system
+    exit_status(system.run())
+}
+
+/// Function to load and validate the configuration before starting any actor, reporting all the
+/// problems found at once
+pub fn load_config(config: Option<PathBuf>) -> Result<ConfigManager, io::Error> {
+    let mut config_manager = ConfigManager::new(config);
+    if let Err(e) = config_manager.load() {
+        error!("{}", e);
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, e.to_string()));
+    }
+    tracing::set_enabled(config_manager.config().node.tracing);
+
+    Ok(config_manager)
+}
+
+/// Function to start every actor of the node and register it into the system registry
+/// It has to be called from inside a running system
+pub fn start_actors(config_manager: ConfigManager) {
+    // The telemetry manager is only included in the builds with the `telemetry` feature
+    #[cfg(not(feature = "telemetry"))]
+    {
+        if config_manager.config().telemetry.enabled {
+            log::warn!(
+                "Telemetry is enabled, but this build does not include the telemetry feature: \
+                 no statistics will be reported"
+            );
+        }
+    }
+
     // Start config manager actor
-    let config_manager_addr = ConfigManager::new(config).start();
+    let config_manager_addr = config_manager.start();
     System::current().registry().set(config_manager_addr);
 
-    // Start storage manager actor
-    let storage_manager_addr = StorageManager::default().start();
+    // Start storage manager actor (restarted if it fails, see `supervision`)
+    let storage_manager_addr = Supervisor::start(|_| StorageManager::default());
     System::current().registry().set(storage_manager_addr);
 
     // Start peers manager actor
@@ -35,8 +77,8 @@ pub fn run(config: Option<PathBuf>, callback: fn()) -> Result<(), io::Error> {
     let connections_manager_addr = ConnectionsManager::default().start();
     System::current().registry().set(connections_manager_addr);
 
-    // Start session manager actor
-    let sessions_manager_addr = SessionsManager::default().start();
+    // Start session manager actor (restarted if it fails, see `supervision`)
+    let sessions_manager_addr = Supervisor::start(|_| SessionsManager::default());
     System::current().registry().set(sessions_manager_addr);
 
     // Start epoch manager actor
@@ -59,19 +101,53 @@ pub fn run(config: Option<PathBuf>, callback: fn()) -> Result<(), io::Error> {
     let inventory_manager_addr = InventoryManager::start_default();
     System::current().registry().set(inventory_manager_addr);
 
+    // Start wallet manager actor
+    let wallet_manager_addr = WalletManager::start_default();
+    System::current().registry().set(wallet_manager_addr);
+
+    // Start data requests manager actor
+    let data_requests_manager_addr = DataRequestsManager::start_default();
+    System::current().registry().set(data_requests_manager_addr);
+
+    // Start telemetry manager actor (it does nothing unless the telemetry is enabled)
+    #[cfg(feature = "telemetry")]
+    {
+        let telemetry_manager_addr = TelemetryManager::start_default();
+        System::current().registry().set(telemetry_manager_addr);
+    }
+
     // Start JSON RPC server (this doesn't need to be in the registry)
     let _json_rpc_server_addr = JsonRpcServer::default().start();
+}
 
-    // Run system
-    system.run();
+/// Function to turn the exit code of the main system into a result
+pub fn exit_status(code: i32) -> Result<(), io::Error> {
+    if code != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            "The node was stopped after a fatal error",
+        ));
+    }
 
     Ok(())
 }
 
+/// Function to stop the main system in a controlled way if an error is fatal
+/// Retriable errors are ignored, since they are handled by the retries of each operation
+pub fn escalate(error: &NodeError) {
+    if error.is_fatal() {
+        error!("Fatal error, stopping the node: {}", error);
+        System::current().stop_with_code(1);
+    }
+}
+
 /// Function to close the main system
 pub fn close() {
     info!("Closing node");
 
+    // Do not leave the server port open in the router until the lease of its mapping expires
+    port_mapping::unmap_port();
+
     // FIXME(#72): find out how to gracefully stop the system
     // System::current().stop();
 
@@ -3,11 +3,15 @@ use log::{debug, error, info};
 
 use crate::actors::{
     config_manager::send_get_config_request,
-    storage_keys::PEERS_KEY,
+    storage_keys::{BANS_KEY, PEERS_KEY},
     storage_manager::{messages::Get, StorageManager},
 };
 
-use witnet_p2p::peers::Peers;
+use witnet_p2p::{
+    bans::Bans,
+    peers::{AddressFamilies, Peers},
+};
+use witnet_util::timestamp::get_timestamp;
 
 use super::PeersManager;
 
@@ -24,9 +28,19 @@ impl Actor for PeersManager {
             // Get known peers
             let known_peers: Vec<_> = config.connections.known_peers.iter().cloned().collect();
 
+            // Only select peers of the address families this node is listening on
+            act.families = AddressFamilies::from_listen_addresses(&config.connections.bind_addrs());
+            debug!("Peer address families: {:?}", act.families);
+
             // Get storage peers period
             let storage_peers_period = config.connections.storage_peers_period;
 
+            // Addresses are removed once they have not been reachable for some days
+            act.eviction_period = i64::from(config.connections.peer_eviction_days) * 24 * 60 * 60;
+
+            // DNS seeds are resolved when the node is isolated from the network
+            act.dns_seeds = config.connections.dns_seeds.clone();
+
             // Add all peers
             info!(
                 "Adding the following peer addresses from config: {:?}",
@@ -63,15 +77,14 @@ impl Actor for PeersManager {
                 .and_then(|peers_from_storage, act, _ctx| {
                     // peers_from_storage can be None if the storage does not contain that key
                     if let Some(peers_from_storage) = peers_from_storage {
-                        // Add all the peers from storage
-                        // The add method handles duplicates by overwriting the old values
-                        let peers = peers_from_storage.get_all().unwrap();
-                        info!(
-                            "Adding the following peer addresses from storage: {:?}",
-                            peers
-                        );
-                        match act.peers.add(peers) {
-                            Ok(_duplicated_peers) => {}
+                        // Restore all the peers from storage, along with the statistics of the
+                        // connection attempts
+                        // The restore method handles duplicates by overwriting the old values
+                        match act.peers.restore(peers_from_storage) {
+                            Ok(peers) => info!(
+                                "Adding the following peer addresses from storage: {:?}",
+                                peers
+                            ),
                             Err(e) => {
                                 error!("Error when adding peer addresses from storage: {}", e);
                             }
@@ -82,6 +95,28 @@ impl Actor for PeersManager {
                 })
                 .wait(ctx);
 
+            // Restore the bans from storage
+            storage_manager_addr
+                .send(Get::<Bans>::new(BANS_KEY))
+                .into_actor(act)
+                .then(|res, act, _ctx| {
+                    match res {
+                        Err(e) => error!("Unsuccessful communication with storage manager: {}", e),
+                        Ok(Err(e)) => error!("Error while getting bans from storage: {}", e),
+                        // The storage does not contain any ban
+                        Ok(Ok(None)) => {}
+                        Ok(Ok(Some(bans_from_storage))) => {
+                            let now = get_timestamp();
+                            let banned = act.bans.import(bans_from_storage.export(now), now);
+                            info!("Restored the bans of {} peers from storage", banned.len());
+                            act.remove_banned_peers();
+                        }
+                    }
+
+                    actix::fut::ok(())
+                })
+                .wait(ctx);
+
             // Start the storage peers process on SessionsManager start
             act.persist_peers(ctx, storage_peers_period);
         });
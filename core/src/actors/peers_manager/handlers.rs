@@ -1,11 +1,18 @@
-use actix::{Context, Handler};
-use log::{debug, info};
+use std::thread;
+
+use actix::{AsyncContext, Context, Handler};
+use log::{debug, info, warn};
+
+use witnet_util::timestamp::get_timestamp;
 
 use super::messages::{
-    AddPeers, GetPeers, GetRandomPeer, PeersSocketAddrResult, PeersSocketAddrsResult, RemovePeers,
+    AddPeers, AddReportedPeers, BanPeer, GetBans, GetDiversePeers, GetPeers, GetPeersWithLastSeen,
+    GetRandomPeer, ImportBans, IsBanned, PeersBansResult, PeersIpAddrsResult,
+    PeersSocketAddrResult, PeersSocketAddrsLastSeenResult, PeersSocketAddrsResult,
+    RecordConnectionAttempt, RemovePeers, ResolveDnsSeeds, UnbanPeer,
 };
 
-use super::PeersManager;
+use super::{resolve_dns_seeds, PeersManager};
 
 /// Handler for AddPeers message
 impl Handler<AddPeers> for PeersManager {
@@ -14,7 +21,57 @@ impl Handler<AddPeers> for PeersManager {
     fn handle(&mut self, msg: AddPeers, _: &mut Context<Self>) -> Self::Result {
         // Insert address
         info!("Add peer handle for addresses: {:?}", msg.addresses);
-        self.peers.add(msg.addresses)
+        let addresses = msg
+            .addresses
+            .into_iter()
+            .filter(|address| !self.is_banned(address))
+            .collect();
+        self.peers.add(addresses)
+    }
+}
+
+/// Handler for AddReportedPeers message
+impl Handler<AddReportedPeers> for PeersManager {
+    type Result = PeersSocketAddrsResult;
+
+    fn handle(&mut self, msg: AddReportedPeers, _: &mut Context<Self>) -> Self::Result {
+        debug!(
+            "Add {} peer addresses reported by {}",
+            msg.addresses.len(),
+            msg.source
+        );
+        let addresses = msg
+            .addresses
+            .into_iter()
+            .filter(|(address, _, _)| !self.is_banned(address))
+            .collect();
+        self.peers.add_from_source(msg.source, addresses)
+    }
+}
+
+/// Handler for ResolveDnsSeeds message
+impl Handler<ResolveDnsSeeds> for PeersManager {
+    type Result = ();
+
+    fn handle(&mut self, _msg: ResolveDnsSeeds, ctx: &mut Context<Self>) {
+        if self.dns_seeds.is_empty() {
+            debug!("No DNS seeds to resolve");
+            return;
+        }
+
+        // Resolving the names blocks, so it must not be done in the thread of the actor
+        let seeds = self.dns_seeds.clone();
+        let addr = ctx.address();
+        thread::spawn(move || {
+            let addresses = resolve_dns_seeds(&seeds);
+            info!(
+                "Resolved {} peer addresses from the DNS seeds",
+                addresses.len()
+            );
+            if !addresses.is_empty() {
+                addr.do_send(AddPeers { addresses });
+            }
+        });
     }
 }
 
@@ -29,13 +86,49 @@ impl Handler<RemovePeers> for PeersManager {
     }
 }
 
+/// Handler for RecordConnectionAttempt message
+impl Handler<RecordConnectionAttempt> for PeersManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: RecordConnectionAttempt, _: &mut Context<Self>) {
+        debug!(
+            "Connection attempt to peer {}: {:?}",
+            msg.address, msg.outcome
+        );
+        if let Err(e) = self
+            .peers
+            .record_attempt(msg.address, msg.outcome, get_timestamp())
+        {
+            warn!(
+                "Error when recording connection attempt to {}: {}",
+                msg.address, e
+            );
+        }
+    }
+}
+
 /// Handler for GetRandomPeer message
 impl Handler<GetRandomPeer> for PeersManager {
     type Result = PeersSocketAddrResult;
 
     fn handle(&mut self, _msg: GetRandomPeer, _: &mut Context<Self>) -> Self::Result {
         debug!("Get random peer");
-        self.peers.get_random()
+        self.peers.get_random_with_families(self.families)
+    }
+}
+
+/// Handler for GetDiversePeers message
+impl Handler<GetDiversePeers> for PeersManager {
+    type Result = PeersSocketAddrsResult;
+
+    fn handle(&mut self, msg: GetDiversePeers, _: &mut Context<Self>) -> Self::Result {
+        debug!(
+            "Get {} peers avoiding {} network groups",
+            msg.count,
+            msg.connected_groups.len()
+        );
+        self.peers
+            .get_diverse(msg.count, self.families, &msg.connected_groups)
     }
 }
 
@@ -48,3 +141,85 @@ impl Handler<GetPeers> for PeersManager {
         self.peers.get_all()
     }
 }
+
+/// Handler for GetPeersWithLastSeen message
+impl Handler<GetPeersWithLastSeen> for PeersManager {
+    type Result = PeersSocketAddrsLastSeenResult;
+
+    fn handle(&mut self, _msg: GetPeersWithLastSeen, _: &mut Context<Self>) -> Self::Result {
+        debug!("Get all peers with last seen timestamps");
+        self.peers.get_all_with_last_seen()
+    }
+}
+
+/// Handler for BanPeer message
+impl Handler<BanPeer> for PeersManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: BanPeer, ctx: &mut Context<Self>) {
+        warn!(
+            "Banning peer {} ({}) for {}",
+            msg.address,
+            msg.reason,
+            msg.duration
+                .map(|duration| format!("{} seconds", duration.as_secs()))
+                .unwrap_or_else(|| "ever".to_string())
+        );
+        let until = msg
+            .duration
+            .map(|duration| get_timestamp().saturating_add(duration.as_secs() as i64));
+        self.bans.ban(msg.address, until, msg.reason);
+        self.remove_banned_peers();
+        self.persist_bans(ctx);
+    }
+}
+
+/// Handler for UnbanPeer message
+impl Handler<UnbanPeer> for PeersManager {
+    type Result = bool;
+
+    fn handle(&mut self, msg: UnbanPeer, ctx: &mut Context<Self>) -> Self::Result {
+        let unbanned = self.bans.unban(msg.address);
+        if unbanned {
+            info!("Lifting the ban of peer {}", msg.address);
+            self.persist_bans(ctx);
+        }
+
+        unbanned
+    }
+}
+
+/// Handler for IsBanned message
+impl Handler<IsBanned> for PeersManager {
+    type Result = bool;
+
+    fn handle(&mut self, msg: IsBanned, _: &mut Context<Self>) -> Self::Result {
+        self.is_banned(&msg.address)
+    }
+}
+
+/// Handler for GetBans message
+impl Handler<GetBans> for PeersManager {
+    type Result = PeersBansResult;
+
+    fn handle(&mut self, _msg: GetBans, _: &mut Context<Self>) -> Self::Result {
+        debug!("Get all bans");
+        Ok(self.bans.export(get_timestamp()))
+    }
+}
+
+/// Handler for ImportBans message
+impl Handler<ImportBans> for PeersManager {
+    type Result = PeersIpAddrsResult;
+
+    fn handle(&mut self, msg: ImportBans, ctx: &mut Context<Self>) -> Self::Result {
+        let imported = self.bans.import(msg.bans, get_timestamp());
+        info!("Imported the bans of {} peers", imported.len());
+        if !imported.is_empty() {
+            self.remove_banned_peers();
+            self.persist_bans(ctx);
+        }
+
+        Ok(imported)
+    }
+}
@@ -1,13 +1,24 @@
-use std::net::SocketAddr;
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
 
 use actix::Message;
 
-use witnet_p2p::peers::error::PeersResult;
+use witnet_p2p::{
+    bans::Ban,
+    peers::{error::PeersResult, AttemptOutcome, NetworkGroup, ReportedAddress},
+};
 
 /// One peer
 pub type PeersSocketAddrResult = PeersResult<Option<SocketAddr>>;
 /// One or more peer addresses
 pub type PeersSocketAddrsResult = PeersResult<Vec<SocketAddr>>;
+/// One or more peer addresses along with their last seen timestamps and origin signatures
+pub type PeersSocketAddrsLastSeenResult = PeersResult<Vec<ReportedAddress>>;
+/// Bans of one or more peers
+pub type PeersBansResult = PeersResult<Vec<Ban>>;
+/// IP addresses of one or more peers
+pub type PeersIpAddrsResult = PeersResult<Vec<IpAddr>>;
 
 /// Message to add one or more peer addresses to the list
 pub struct AddPeers {
@@ -19,6 +30,19 @@ impl Message for AddPeers {
     type Result = PeersSocketAddrsResult;
 }
 
+/// Message to add one or more peer addresses reported by another peer to the list
+pub struct AddReportedPeers {
+    /// IP address of the peer that reported the addresses
+    pub source: IpAddr,
+    /// Addresses of the peers along with their last seen timestamps and the verified signatures
+    /// of their origins
+    pub addresses: Vec<ReportedAddress>,
+}
+
+impl Message for AddReportedPeers {
+    type Result = PeersSocketAddrsResult;
+}
+
 /// Message to remove one or more peer addresses from the list
 pub struct RemovePeers {
     /// Address of the peer
@@ -29,6 +53,19 @@ impl Message for RemovePeers {
     type Result = PeersSocketAddrsResult;
 }
 
+/// Message to record the outcome of an attempt to connect to a peer address, so failing
+/// addresses are backed off and eventually removed from the list
+pub struct RecordConnectionAttempt {
+    /// Address of the peer
+    pub address: SocketAddr,
+    /// Outcome of the attempt
+    pub outcome: AttemptOutcome,
+}
+
+impl Message for RecordConnectionAttempt {
+    type Result = ();
+}
+
 /// Message to get a (random) peer address from the list
 pub struct GetRandomPeer;
 
@@ -36,9 +73,92 @@ impl Message for GetRandomPeer {
     type Result = PeersSocketAddrResult;
 }
 
+/// Message to get up to `count` random peer addresses from the list, each one of them from a
+/// different network group and none of them from the network groups of the peers the node is
+/// already connected to
+pub struct GetDiversePeers {
+    /// Maximum number of addresses
+    pub count: usize,
+    /// Network groups of the peers the node is already connected to
+    pub connected_groups: HashSet<NetworkGroup>,
+}
+
+impl Message for GetDiversePeers {
+    type Result = PeersSocketAddrsResult;
+}
+
 /// Message to get all the peer addresses from the list
 pub struct GetPeers;
 
 impl Message for GetPeers {
     type Result = PeersSocketAddrsResult;
 }
+
+/// Message to get all the peer addresses from the list along with their last seen timestamps and
+/// the signatures of their origins
+pub struct GetPeersWithLastSeen;
+
+impl Message for GetPeersWithLastSeen {
+    type Result = PeersSocketAddrsLastSeenResult;
+}
+
+/// Message to ban a peer: its addresses are removed from the list and its connections refused
+pub struct BanPeer {
+    /// IP address of the peer
+    pub address: IpAddr,
+    /// Duration of the ban, or None if the ban is permanent
+    pub duration: Option<Duration>,
+    /// Reason of the ban
+    pub reason: String,
+}
+
+impl Message for BanPeer {
+    type Result = ();
+}
+
+/// Message to lift the ban of a peer
+pub struct UnbanPeer {
+    /// IP address of the peer
+    pub address: IpAddr,
+}
+
+impl Message for UnbanPeer {
+    /// Whether the peer was banned
+    type Result = bool;
+}
+
+/// Message to check if a peer is banned
+pub struct IsBanned {
+    /// Address of the peer
+    pub address: SocketAddr,
+}
+
+impl Message for IsBanned {
+    type Result = bool;
+}
+
+/// Message to resolve the DNS seeds of the configuration, adding the resolved addresses to the
+/// list of peers (the names are resolved in a separate thread, so the message returns right away)
+pub struct ResolveDnsSeeds;
+
+impl Message for ResolveDnsSeeds {
+    type Result = ();
+}
+
+/// Message to get the bans in force, to be exported
+pub struct GetBans;
+
+impl Message for GetBans {
+    type Result = PeersBansResult;
+}
+
+/// Message to import a list of bans (e.g. exported by another node)
+pub struct ImportBans {
+    /// Bans to import
+    pub bans: Vec<Ban>,
+}
+
+impl Message for ImportBans {
+    /// IP addresses of the peers banned or whose ban has been extended
+    type Result = PeersIpAddrsResult;
+}
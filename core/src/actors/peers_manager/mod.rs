@@ -1,16 +1,22 @@
+use std::net::{SocketAddr, ToSocketAddrs};
 use std::time::Duration;
 
 use crate::actors::{
-    storage_keys::PEERS_KEY,
-    storage_manager::{messages::Put, StorageManager},
+    node,
+    storage_keys::{BANS_KEY, PEERS_KEY},
+    storage_manager::{messages::Put, put_with_retry},
 };
+use crate::errors::{NodeError, STORAGE_RETRY_POLICY};
 use actix::{
-    ActorFuture, AsyncContext, Context, ContextFutureSpawner, Supervised, System, SystemService,
-    WrapFuture,
+    ActorFuture, AsyncContext, Context, ContextFutureSpawner, Supervised, SystemService, WrapFuture,
 };
-use log::{error, info};
+use log::{error, info, warn};
 
-use witnet_p2p::peers::Peers;
+use witnet_p2p::{
+    bans::Bans,
+    peers::{AddressFamilies, Peers},
+};
+use witnet_util::timestamp::get_timestamp;
 
 // Internal Actor implementation for PeersManager
 mod actor;
@@ -18,15 +24,23 @@ mod actor;
 /// Handlers to manage the previous messages using the `peers` library:
 /// * Add peers
 /// * Remove peers
+/// * Record connection attempts
 /// * Get random peer
 /// * Get all peers
+/// * Ban and unban peers
+/// * Export and import the bans
+/// * Resolve the DNS seeds
 mod handlers;
 
 /// Messages for peer management:
 /// * Add peers
 /// * Remove peers
+/// * Record connection attempts
 /// * Get random peer
 /// * Get all peers
+/// * Ban and unban peers
+/// * Export and import the bans
+/// * Resolve the DNS seeds
 pub mod messages;
 
 /// Peers manager actor: manages a list of available peers to connect
@@ -35,11 +49,43 @@ pub mod messages;
 ///   + PEERS message as response to GET_PEERS -> []addr
 ///   + Incoming connections to the node -> []addr
 ///
-/// In the future, there might be other additional means to retrieve peers, e.g. from trusted servers.
+/// When the node is isolated from the network, the DNS seeds of the configuration
+/// (`connections.dns_seeds`) are resolved to find new peers.
+///
+/// Banned peers are never added to the list nor accepted as inbound connections. The bans are
+/// persisted into storage as soon as they change, so they survive restarts.
+///
+/// The outcome of every attempt to connect to an address is recorded: addresses are not tried
+/// again for a time which doubles after every consecutive failure, and the addresses which have
+/// not been reachable for `connections.peer_eviction_days` are removed from the list.
 #[derive(Default)]
 pub struct PeersManager {
     /// Known peers
     peers: Peers,
+    /// Banned peers
+    bans: Bans,
+    /// Address families of the peers to which this node can connect
+    families: AddressFamilies,
+    /// Time (in seconds) after which the addresses which have not been reachable are removed
+    eviction_period: i64,
+    /// Host names (along with their port) resolved to find new peers
+    dns_seeds: Vec<String>,
+}
+
+/// Resolve the DNS seeds into socket addresses, skipping the ones which cannot be resolved
+/// This blocks until every name has been resolved (or has failed), so it must not be called
+/// from the thread of an actor
+fn resolve_dns_seeds(seeds: &[String]) -> Vec<SocketAddr> {
+    seeds
+        .iter()
+        .flat_map(|seed| match seed.to_socket_addrs() {
+            Ok(addresses) => addresses.collect(),
+            Err(e) => {
+                warn!("Failed to resolve DNS seed {}: {}", seed, e);
+                vec![]
+            }
+        })
+        .collect()
 }
 
 impl PeersManager {
@@ -47,21 +93,28 @@ impl PeersManager {
     fn persist_peers(&self, ctx: &mut Context<Self>, storage_peers_period: Duration) {
         // Schedule the discovery_peers with a given period
         ctx.run_later(storage_peers_period, move |act, ctx| {
-            // Get StorageManager address
-            let storage_manager_addr = System::current().registry().get::<StorageManager>();
+            act.remove_unreachable_peers();
+
+            let msg = match Put::from_value(PEERS_KEY, &act.peers) {
+                Ok(msg) => msg,
+                Err(e) => {
+                    error!("Error encoding peers: {}", e);
+                    node::escalate(&NodeError::from(e));
+                    return;
+                }
+            };
 
             // Persist peers into storage. `AsyncContext::wait` registers
             // future within context, but context waits until this future resolves
             // before processing any other events.
-            storage_manager_addr
-                .send(Put::from_value(PEERS_KEY, &act.peers).unwrap())
+            put_with_retry(msg, STORAGE_RETRY_POLICY)
                 .into_actor(act)
                 .then(|res, _act, _ctx| {
                     match res {
-                        Ok(Ok(_)) => info!("PeersManager successfully persist peers to storage"),
-                        _ => {
-                            error!("Peers manager persist peers to storage failed");
-                            // FIXME(#72): handle errors
+                        Ok(()) => info!("PeersManager successfully persist peers to storage"),
+                        Err(e) => {
+                            error!("Peers manager persist peers to storage failed: {}", e);
+                            node::escalate(&e);
                         }
                     }
                     actix::fut::ok(())
@@ -71,6 +124,71 @@ impl PeersManager {
             act.persist_peers(ctx, storage_peers_period);
         });
     }
+
+    /// Method to persist the bans into storage, dropping the expired ones
+    fn persist_bans(&mut self, ctx: &mut Context<Self>) {
+        self.bans.remove_expired(get_timestamp());
+
+        let msg = match Put::from_value(BANS_KEY, &self.bans) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error encoding bans: {}", e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+
+        put_with_retry(msg, STORAGE_RETRY_POLICY)
+            .into_actor(self)
+            .then(|res, _act, _ctx| {
+                match res {
+                    Ok(()) => info!("PeersManager successfully persist bans to storage"),
+                    Err(e) => {
+                        error!("Peers manager persist bans to storage failed: {}", e);
+                        node::escalate(&e);
+                    }
+                }
+                actix::fut::ok(())
+            })
+            .wait(ctx);
+    }
+
+    /// Method to remove the addresses of the banned peers from the list of peers
+    fn remove_banned_peers(&mut self) {
+        let now = get_timestamp();
+        let banned: Vec<_> = self
+            .peers
+            .get_all()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|address| self.bans.is_banned(address.ip(), now))
+            .collect();
+        if !banned.is_empty() {
+            info!("Removing the addresses of banned peers: {:?}", banned);
+            let _ = self.peers.remove(&banned);
+        }
+    }
+
+    /// Method to remove the addresses which have not been reachable for the eviction period
+    fn remove_unreachable_peers(&mut self) {
+        if self.eviction_period == 0 {
+            return;
+        }
+
+        match self
+            .peers
+            .remove_unreachable(get_timestamp(), self.eviction_period)
+        {
+            Ok(ref removed) if removed.is_empty() => {}
+            Ok(removed) => info!("Removing the addresses of unreachable peers: {:?}", removed),
+            Err(e) => error!("Error when removing unreachable peers: {}", e),
+        }
+    }
+
+    /// Method to check if a peer is banned
+    fn is_banned(&self, address: &SocketAddr) -> bool {
+        self.bans.is_banned(address.ip(), get_timestamp())
+    }
 }
 
 /// Required traits for being able to retrieve SessionsManager address from registry
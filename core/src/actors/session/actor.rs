@@ -1,18 +1,24 @@
+use std::time::Duration;
+
 use actix::{
     Actor, ActorContext, ActorFuture, AsyncContext, Context, ContextFutureSpawner, Running, System,
     WrapFuture,
 };
 use log::{debug, error, info};
 
-use crate::actors::sessions_manager::{
-    messages::{Register, Unregister},
-    SessionsManager,
+use crate::actors::{
+    blocks_manager::{messages::ReleaseBlockRanges, BlocksManager},
+    peers_manager::{messages::RecordConnectionAttempt, PeersManager},
+    sessions_manager::{
+        messages::{Register, Unregister},
+        SessionsManager,
+    },
 };
 
 use witnet_data_structures::types::Message as WitnetMessage;
-use witnet_p2p::sessions::{SessionStatus, SessionType};
+use witnet_p2p::{peers::AttemptOutcome, sessions::SessionType};
 
-use super::Session;
+use super::{state::SessionState, Session, PING_PERIOD_SECONDS};
 
 /// Implement actor trait for Session
 impl Actor for Session {
@@ -23,17 +29,20 @@ impl Actor for Session {
     fn started(&mut self, ctx: &mut Self::Context) {
         // Set Handshake timeout for stopping actor if session is still unconsolidated after given period of time
         ctx.run_later(self.handshake_timeout, |act, ctx| {
-            if act.status != SessionStatus::Consolidated {
+            if !act.state.is_consolidated() {
                 info!(
                     "Handshake timeout expired, disconnecting session with peer {:?}",
                     act.remote_addr
                 );
-                if let SessionStatus::Unconsolidated = act.status {
-                    ctx.stop();
-                }
+                ctx.stop();
             }
         });
 
+        // Periodically ping the peer to measure its latency
+        ctx.run_interval(Duration::from_secs(PING_PERIOD_SECONDS), |act, _ctx| {
+            act.ping();
+        });
+
         // Get SessionsManager address
         let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
 
@@ -64,18 +73,38 @@ impl Actor for Session {
                     }
                 }
             })
-            .and_then(|_, act, _ctx| {
+            .and_then(|_, act, ctx| {
                 // Send version if outbound session
-                if let SessionType::Outbound = act.session_type {
-                    // FIXME(#142): include the checkpoint of the current tip of the local blockchain
-                    let version_msg =
-                        WitnetMessage::build_version(act.server_addr, act.remote_addr, 0);
-                    act.send_message(version_msg);
-                    // Set HandshakeFlag of sent version message
-                    act.handshake_flags.version_tx = true;
-                }
+                let result = act.state.start_handshake().and_then(|_| {
+                    if let SessionType::Outbound = act.session_type {
+                        // FIXME(#142): include the checkpoint of the current tip of the local
+                        // blockchain
+                        let version_msg = WitnetMessage::build_version(
+                            act.server_addr,
+                            act.remote_addr,
+                            0,
+                            act.capabilities(),
+                            act.consensus_constants_hash,
+                        );
+                        act.send_message(version_msg);
+                        act.state.version_sent()?;
+                    }
+
+                    Ok(())
+                });
 
-                actix::fut::ok(())
+                match result {
+                    Ok(()) => actix::fut::ok(()),
+                    Err(e) => {
+                        error!(
+                            "Session with peer {} failed to start: {}",
+                            act.remote_addr, e
+                        );
+                        ctx.stop();
+
+                        actix::fut::err(())
+                    }
+                }
             })
             .wait(ctx);
     }
@@ -85,13 +114,38 @@ impl Actor for Session {
         // Get session manager address
         let session_manager_addr = System::current().registry().get::<SessionsManager>();
 
-        // Unregister session from SessionsManager
+        // Unregister session from SessionsManager, with the status it had before closing
         session_manager_addr.do_send(Unregister {
             address: self.remote_addr,
             session_type: self.session_type,
-            status: self.status,
+            status: self.state.status(),
         });
 
+        // An outbound session closed in the middle of the handshake (e.g. because the handshake
+        // timeout expired) counts as a failed attempt to connect to the peer
+        if let (SessionType::Outbound, SessionState::Handshaking(_)) =
+            (self.session_type, &self.state)
+        {
+            System::current()
+                .registry()
+                .get::<PeersManager>()
+                .do_send(RecordConnectionAttempt {
+                    address: self.remote_addr,
+                    outcome: AttemptOutcome::HandshakeFailed,
+                });
+        }
+        self.state.close();
+
+        // Give back the block ranges requested to the peer, so they are requested to others
+        if let SessionType::Outbound = self.session_type {
+            System::current()
+                .registry()
+                .get::<BlocksManager>()
+                .do_send(ReleaseBlockRanges {
+                    peer: self.remote_addr,
+                });
+        }
+
         Running::Stop
     }
 }
@@ -0,0 +1,157 @@
+//! # Signatures of the peer addresses
+//!
+//! Every node signs the address it advertises to its peers, along with the
+//! time it was seen, with a secret key of its own (the address identity).
+//! The signature and the public key travel with the address when it is
+//! relayed, so a relaying node cannot refresh the time an address was seen
+//! without invalidating its signature. The addresses whose signature is not
+//! valid are discarded, and once an address is known to be signed only its
+//! origin can refresh it (see `witnet_p2p::peers::Peers::add_from_source`).
+//!
+//! The address identity is not the witness identity of the node, so the
+//! addresses of a node cannot be linked to its witnessing activity.
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use rand::{thread_rng, Rng};
+use secp256k1::{All, PublicKey, Secp256k1, SecretKey};
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_crypto::{
+    hash::calculate_sha256,
+    signature::{sign, verify, SignatureJob},
+};
+use witnet_data_structures::{
+    builders::to_address,
+    chain::{Hash, KeyedSignature},
+    types::{Address, PeerAddress},
+};
+
+/// Secret key identifying the node as the origin of the addresses it advertises
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AddressIdentity {
+    /// Serialized secret key
+    pub secret_key: Vec<u8>,
+}
+
+impl AddressIdentity {
+    /// Generate a new random identity
+    pub fn generate() -> Self {
+        let mut rng = thread_rng();
+        loop {
+            let mut bytes = [0; 32];
+            rng.fill(&mut bytes);
+            // Almost every 32-byte value is a valid secret key
+            if SecretKey::from_slice(&bytes).is_ok() {
+                return AddressIdentity {
+                    secret_key: bytes.to_vec(),
+                };
+            }
+        }
+    }
+
+    /// Get the secret key, or None if it is not valid
+    pub fn secret_key(&self) -> Option<SecretKey> {
+        SecretKey::from_slice(&self.secret_key).ok()
+    }
+}
+
+/// Signer and verifier of the peer addresses, shared by the sessions
+#[derive(Clone)]
+pub struct AddressSignatures {
+    /// Context of the signatures
+    secp: Arc<Secp256k1<All>>,
+    /// Secret key of the address identity of the node (addresses are not signed if there is none)
+    secret_key: Option<SecretKey>,
+}
+
+impl Default for AddressSignatures {
+    fn default() -> Self {
+        AddressSignatures {
+            secp: Arc::new(Secp256k1::new()),
+            secret_key: None,
+        }
+    }
+}
+
+impl AddressSignatures {
+    /// Set the secret key of the address identity of the node
+    pub fn set_secret_key(&mut self, secret_key: SecretKey) {
+        self.secret_key = Some(secret_key);
+    }
+
+    /// Sign an address originated by this node and the last time it was seen
+    /// Returns None if the node has no address identity
+    pub fn sign(&self, address: SocketAddr, last_seen: i64) -> Option<KeyedSignature> {
+        self.secret_key.as_ref().map(|secret_key| KeyedSignature {
+            public_key: PublicKey::from_secret_key(&self.secp, secret_key)
+                .serialize()
+                .to_vec(),
+            signature: sign(
+                &self.secp,
+                secret_key,
+                &signed_message(&to_address(address), last_seen),
+            ),
+        })
+    }
+
+    /// Check the signature of an address shared by a peer
+    /// Returns true if the address is not signed
+    pub fn verify(&self, peer: &PeerAddress) -> bool {
+        match &peer.signature {
+            Some(keyed) => verify(
+                &self.secp,
+                &SignatureJob {
+                    public_key: keyed.public_key.clone(),
+                    message: signed_message(&peer.address, peer.last_seen),
+                    signature: keyed.signature.clone(),
+                },
+            )
+            .is_ok(),
+            None => true,
+        }
+    }
+}
+
+/// Get the message signed by the origin of an address: the hash of the address and the last time
+/// it was seen (see `PeerAddress::signed_bytes`)
+fn signed_message(address: &Address, last_seen: i64) -> [u8; 32] {
+    let Hash::SHA256(message) = calculate_sha256(&PeerAddress::signed_bytes(address, last_seen));
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer_address(
+        address: SocketAddr,
+        last_seen: i64,
+        signature: Option<KeyedSignature>,
+    ) -> PeerAddress {
+        PeerAddress {
+            address: to_address(address),
+            last_seen,
+            signature,
+        }
+    }
+
+    #[test]
+    fn signed_addresses_are_verified() {
+        let address: SocketAddr = "203.0.113.7:21337".parse().unwrap();
+        let mut signatures = AddressSignatures::default();
+        assert_eq!(signatures.sign(address, 100), None);
+
+        signatures.set_secret_key(AddressIdentity::generate().secret_key().unwrap());
+        let signature = signatures.sign(address, 100);
+        assert!(signature.is_some());
+        assert!(signatures.verify(&peer_address(address, 100, signature.clone())));
+        assert!(signatures.verify(&peer_address(address, 100, None)));
+
+        // The signature is bound to the address and the last time it was seen
+        assert!(!signatures.verify(&peer_address(address, 101, signature.clone())));
+        let other: SocketAddr = "203.0.113.7:21338".parse().unwrap();
+        assert!(!signatures.verify(&peer_address(other, 100, signature)));
+    }
+}
@@ -0,0 +1,136 @@
+//! # Batched announcements
+//!
+//! Instead of sending one Inv message per announced item, the session
+//! accumulates the items announced by the other actors during a short
+//! debounce window and then sends all of them in a single Inv message.
+//! During bursts of transactions this saves a lot of small packets.
+//!
+//! Items announced more than once during the window are sent only once.
+use std::collections::HashSet;
+
+use witnet_data_structures::chain::{Hash, InvVector};
+
+/// Maximum number of inventory vectors sent in a single Inv message
+pub const MAX_INV_VECTORS_PER_MESSAGE: usize = 500;
+
+/// Items waiting to be announced to the peer
+#[derive(Debug, Default)]
+pub struct PendingAnnouncements {
+    /// Items in announcement order
+    items: Vec<InvVector>,
+    /// Items already queued, used to skip duplicates
+    queued: HashSet<InvVector>,
+    /// Whether a flush has already been scheduled
+    flush_scheduled: bool,
+}
+
+impl PendingAnnouncements {
+    /// Queue some items, skipping the ones which are already queued
+    pub fn add<I: IntoIterator<Item = InvVector>>(&mut self, items: I) {
+        for item in items {
+            if self.queued.insert(item.clone()) {
+                self.items.push(item);
+            }
+        }
+    }
+
+    /// Number of queued items
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether there are no queued items
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Mark a flush as scheduled. Returns `false` if it was already scheduled, so only one
+    /// flush is scheduled per debounce window
+    pub fn schedule_flush(&mut self) -> bool {
+        !std::mem::replace(&mut self.flush_scheduled, true)
+    }
+
+    /// Take all the queued items, split into batches of at most
+    /// `MAX_INV_VECTORS_PER_MESSAGE` items, and start a new debounce window
+    pub fn take_batches(&mut self) -> Vec<Vec<InvVector>> {
+        self.flush_scheduled = false;
+        self.queued.clear();
+        let items = std::mem::replace(&mut self.items, vec![]);
+
+        items
+            .chunks(MAX_INV_VECTORS_PER_MESSAGE)
+            .map(<[InvVector]>::to_vec)
+            .collect()
+    }
+}
+
+/// Get the hashes of the transactions in a batch of items
+pub fn transaction_hashes(items: &[InvVector]) -> Vec<Hash> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            InvVector::Tx(hash) => Some(*hash),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> Hash {
+        Hash::SHA256([byte; 32])
+    }
+
+    #[test]
+    fn announcements_are_deduplicated() {
+        let mut pending = PendingAnnouncements::default();
+        pending.add(vec![InvVector::Tx(hash(1)), InvVector::Block(hash(2))]);
+        pending.add(vec![InvVector::Tx(hash(1)), InvVector::Tx(hash(3))]);
+        assert_eq!(pending.len(), 3);
+
+        let batches = pending.take_batches();
+        assert_eq!(
+            batches,
+            vec![vec![
+                InvVector::Tx(hash(1)),
+                InvVector::Block(hash(2)),
+                InvVector::Tx(hash(3))
+            ]]
+        );
+        assert_eq!(transaction_hashes(&batches[0]), vec![hash(1), hash(3)]);
+        assert!(pending.is_empty());
+
+        // Items can be announced again in the next window
+        pending.add(vec![InvVector::Tx(hash(1))]);
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn one_flush_per_window() {
+        let mut pending = PendingAnnouncements::default();
+        assert!(pending.schedule_flush());
+        assert!(!pending.schedule_flush());
+
+        pending.take_batches();
+        assert!(pending.schedule_flush());
+    }
+
+    #[test]
+    fn big_announcements_are_split() {
+        let mut pending = PendingAnnouncements::default();
+        pending.add((0..MAX_INV_VECTORS_PER_MESSAGE + 1).map(|i| {
+            let mut bytes = [0; 32];
+            bytes[0] = (i % 256) as u8;
+            bytes[1] = (i / 256) as u8;
+            InvVector::Tx(Hash::SHA256(bytes))
+        }));
+
+        let batches = pending.take_batches();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].len(), MAX_INV_VECTORS_PER_MESSAGE);
+        assert_eq!(batches[1].len(), 1);
+        assert!(pending.take_batches().is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! # Message compression
+//!
+//! Nodes supporting it (`CAPABILITY_COMPRESSION` in their Version message) prepend every message
+//! with a flag telling whether it has been compressed with Snappy (see
+//! [compress_message](crate::actors::codec::compress_message)). Only the messages bigger than the
+//! compression threshold are compressed, which in practice means the blocks sent during the
+//! synchronization.
+//!
+//! Both nodes switch to the compressed format at well defined points of the handshake, so there
+//! is no need for any extra message:
+//!
+//! * A node sends compressed messages once it has sent both its Version and Verack messages.
+//! * A node expects compressed messages once it has received both the Version and Verack messages
+//!   of the peer.
+//!
+//! The Version and Verack messages themselves, as well as the Noise handshake messages, are never
+//! compressed. When the connection is encrypted, the messages are compressed before being
+//! encrypted.
+use std::io;
+
+use witnet_data_structures::{
+    builders::CAPABILITY_COMPRESSION,
+    types::{Command, Version},
+};
+
+use crate::actors::codec::{compress_message, decompress_message};
+
+/// Compression of the messages of a session
+pub struct Compression {
+    /// Minimum size of the compressed messages, if compression is enabled
+    threshold: Option<usize>,
+    /// Whether the peer supports compression
+    peer_supports: bool,
+    /// Whether the Version message of this node has been sent
+    version_sent: bool,
+    /// Whether the Verack message of this node has been sent
+    verack_sent: bool,
+    /// Whether the Version message of the peer has been received
+    version_received: bool,
+    /// Whether the Verack message of the peer has been received
+    verack_received: bool,
+}
+
+impl Compression {
+    /// Create the compression of a session. If there is no threshold, the messages are never
+    /// compressed.
+    pub fn new(threshold: Option<usize>) -> Self {
+        Compression {
+            threshold,
+            peer_supports: false,
+            version_sent: false,
+            verack_sent: false,
+            version_received: false,
+            verack_received: false,
+        }
+    }
+
+    /// Capabilities to be advertised in the Version message of this node
+    pub fn capabilities(&self) -> u64 {
+        if self.threshold.is_some() {
+            CAPABILITY_COMPRESSION
+        } else {
+            0
+        }
+    }
+
+    /// Keep track of the handshake messages sent to the peer
+    pub fn message_sent(&mut self, command: &Command) {
+        match command {
+            Command::Version(_) => self.version_sent = true,
+            Command::Verack(_) => self.verack_sent = true,
+            _ => {}
+        }
+    }
+
+    /// Keep track of the handshake messages received from the peer
+    pub fn message_received(&mut self, command: &Command) {
+        match command {
+            Command::Version(Version { capabilities, .. }) => {
+                self.version_received = true;
+                self.peer_supports = capabilities & CAPABILITY_COMPRESSION != 0;
+            }
+            Command::Verack(_) => self.verack_received = true,
+            _ => {}
+        }
+    }
+
+    /// Compress a message to be sent to the peer, if compression has been negotiated
+    pub fn compress(&self, message: Vec<u8>) -> io::Result<Vec<u8>> {
+        match self.threshold {
+            Some(threshold) if self.peer_supports && self.version_sent && self.verack_sent => {
+                compress_message(message, threshold)
+            }
+            _ => Ok(message),
+        }
+    }
+
+    /// Decompress a message received from the peer, if compression has been negotiated
+    pub fn decompress(&self, message: Vec<u8>) -> io::Result<Vec<u8>> {
+        if self.threshold.is_some()
+            && self.peer_supports
+            && self.version_received
+            && self.verack_received
+        {
+            decompress_message(&message)
+        } else {
+            Ok(message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use witnet_data_structures::{
+        builders::{CAPABILITIES, USER_AGENT},
+        types::*,
+    };
+
+    fn version(capabilities: u64) -> Command {
+        let address = Address {
+            ip: IpAddress::Ipv4 { ip: 0 },
+            port: 0,
+        };
+
+        Command::Version(Version {
+            version: 0,
+            timestamp: 0,
+            capabilities,
+            sender_address: address.clone(),
+            receiver_address: address,
+            user_agent: USER_AGENT.to_string(),
+            last_epoch: 0,
+            genesis: 0,
+            nonce: 0,
+            consensus_constants_hash: None,
+        })
+    }
+
+    #[test]
+    fn compression_negotiated() {
+        let message = vec![7; 4096];
+        let mut outbound = Compression::new(Some(1024));
+        let mut inbound = Compression::new(Some(1024));
+
+        // Outbound side sends Version
+        outbound.message_sent(&version(CAPABILITIES | outbound.capabilities()));
+        inbound.message_received(&version(CAPABILITIES | outbound.capabilities()));
+        // Inbound side sends Verack and Version
+        inbound.message_sent(&Command::Verack(Verack));
+        outbound.message_received(&Command::Verack(Verack));
+        assert_eq!(inbound.compress(message.clone()).unwrap(), message);
+        inbound.message_sent(&version(CAPABILITIES | inbound.capabilities()));
+        outbound.message_received(&version(CAPABILITIES | inbound.capabilities()));
+
+        // From now on the inbound side sends compressed messages
+        let compressed = inbound.compress(message.clone()).unwrap();
+        assert!(compressed.len() < message.len());
+        assert_eq!(outbound.decompress(compressed).unwrap(), message);
+
+        // Outbound side sends Verack, and from then on compressed messages
+        assert_eq!(outbound.compress(message.clone()).unwrap(), message);
+        outbound.message_sent(&Command::Verack(Verack));
+        inbound.message_received(&Command::Verack(Verack));
+        let compressed = outbound.compress(message.clone()).unwrap();
+        assert!(compressed.len() < message.len());
+        assert_eq!(inbound.decompress(compressed).unwrap(), message);
+    }
+
+    #[test]
+    fn compression_not_negotiated() {
+        let message = vec![7; 4096];
+
+        // The peer does not support compression
+        let mut outbound = Compression::new(Some(1024));
+        outbound.message_sent(&version(CAPABILITIES));
+        outbound.message_sent(&Command::Verack(Verack));
+        outbound.message_received(&version(CAPABILITIES));
+        outbound.message_received(&Command::Verack(Verack));
+        assert_eq!(outbound.compress(message.clone()).unwrap(), message);
+        assert_eq!(outbound.decompress(message.clone()).unwrap(), message);
+
+        // This node does not support compression
+        let mut outbound = Compression::new(None);
+        assert_eq!(outbound.capabilities(), 0);
+        outbound.message_sent(&version(CAPABILITIES));
+        outbound.message_sent(&Command::Verack(Verack));
+        outbound.message_received(&version(CAPABILITIES | CAPABILITY_COMPRESSION));
+        outbound.message_received(&Command::Verack(Verack));
+        assert_eq!(outbound.compress(message.clone()).unwrap(), message);
+        assert_eq!(outbound.decompress(message.clone()).unwrap(), message);
+    }
+}
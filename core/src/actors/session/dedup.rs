@@ -0,0 +1,179 @@
+//! # Deduplication of the received payloads
+//!
+//! Peers may send the same block or inventory announcement more than once
+//! in a short time. The session remembers the hashes of the payloads
+//! received during the last `DEDUP_WINDOW_SECONDS`, so the repeated ones
+//! are dropped before they reach the actors validating them.
+//!
+//! Peers which keep repeating payloads are misbehaving: once a peer has
+//! sent more than `MAX_DUPLICATES_PER_WINDOW` repeated payloads within the
+//! window, the session reports it.
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use witnet_data_structures::chain::Hash;
+
+/// Time (in seconds) during which a received payload is remembered
+pub const DEDUP_WINDOW_SECONDS: u64 = 30;
+
+/// Maximum number of repeated payloads a peer may send within the window
+pub const MAX_DUPLICATES_PER_WINDOW: usize = 10;
+
+/// Maximum number of payloads remembered at the same time (the oldest ones are forgotten first)
+pub const MAX_RECENT_PAYLOADS: usize = 1000;
+
+/// Whether a received payload had been received before within the window
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Received {
+    /// The payload had not been received within the window
+    New,
+    /// The payload had already been received within the window
+    Duplicate,
+}
+
+/// Payloads received from the peer during the last window
+#[derive(Debug)]
+pub struct RecentPayloads {
+    /// Time during which a received payload is remembered
+    window: Duration,
+    /// Hashes of the remembered payloads
+    hashes: HashSet<Hash>,
+    /// Reception time and hash of the remembered payloads, oldest first
+    received: VecDeque<(Instant, Hash)>,
+    /// Reception time of the repeated payloads within the window, oldest first
+    duplicates: VecDeque<Instant>,
+}
+
+impl Default for RecentPayloads {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(DEDUP_WINDOW_SECONDS))
+    }
+}
+
+impl RecentPayloads {
+    /// Create an empty set of recent payloads which are remembered during `window`
+    pub fn new(window: Duration) -> Self {
+        RecentPayloads {
+            window,
+            hashes: HashSet::new(),
+            received: VecDeque::new(),
+            duplicates: VecDeque::new(),
+        }
+    }
+
+    /// Record the reception of a payload given its hash, checking whether it had been received
+    /// within the window
+    pub fn receive(&mut self, hash: Hash, now: Instant) -> Received {
+        self.forget_expired(now);
+
+        if self.hashes.contains(&hash) {
+            self.duplicates.push_back(now);
+
+            return Received::Duplicate;
+        }
+
+        if self.received.len() == MAX_RECENT_PAYLOADS {
+            if let Some((_, oldest)) = self.received.pop_front() {
+                self.hashes.remove(&oldest);
+            }
+        }
+        self.hashes.insert(hash);
+        self.received.push_back((now, hash));
+
+        Received::New
+    }
+
+    /// Whether the peer has sent more than `MAX_DUPLICATES_PER_WINDOW` repeated payloads within
+    /// the window
+    pub fn is_repeated_offender(&self) -> bool {
+        self.duplicates.len() > MAX_DUPLICATES_PER_WINDOW
+    }
+
+    /// Forget the payloads and the repetitions which are older than the window
+    fn forget_expired(&mut self, now: Instant) {
+        let window = self.window;
+        let expired = |received_at: Instant| now.duration_since(received_at) >= window;
+
+        while let Some(&(received_at, hash)) = self.received.front() {
+            if !expired(received_at) {
+                break;
+            }
+            self.received.pop_front();
+            self.hashes.remove(&hash);
+        }
+        while let Some(&received_at) = self.duplicates.front() {
+            if !expired(received_at) {
+                break;
+            }
+            self.duplicates.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> Hash {
+        Hash::SHA256([byte; 32])
+    }
+
+    #[test]
+    fn payloads_are_deduplicated_within_the_window() {
+        let mut recent = RecentPayloads::new(Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert_eq!(recent.receive(hash(1), start), Received::New);
+        assert_eq!(recent.receive(hash(2), start), Received::New);
+        assert_eq!(
+            recent.receive(hash(1), start + Duration::from_secs(5)),
+            Received::Duplicate
+        );
+
+        // Payloads are forgotten once the window has elapsed since they were first received
+        assert_eq!(
+            recent.receive(hash(1), start + Duration::from_secs(10)),
+            Received::New
+        );
+        assert_eq!(
+            recent.receive(hash(2), start + Duration::from_secs(11)),
+            Received::New
+        );
+    }
+
+    #[test]
+    fn repeated_offenders_are_detected() {
+        let mut recent = RecentPayloads::new(Duration::from_secs(10));
+        let start = Instant::now();
+        recent.receive(hash(1), start);
+
+        for _ in 0..MAX_DUPLICATES_PER_WINDOW {
+            assert_eq!(recent.receive(hash(1), start), Received::Duplicate);
+        }
+        assert!(!recent.is_repeated_offender());
+
+        recent.receive(hash(1), start);
+        assert!(recent.is_repeated_offender());
+
+        // The repetitions are forgotten along with the payloads
+        recent.receive(hash(2), start + Duration::from_secs(10));
+        assert!(!recent.is_repeated_offender());
+    }
+
+    #[test]
+    fn recent_payloads_are_bounded() {
+        let mut recent = RecentPayloads::default();
+        let now = Instant::now();
+        for i in 0..=MAX_RECENT_PAYLOADS {
+            let mut bytes = [0; 32];
+            bytes[0] = (i % 256) as u8;
+            bytes[1] = (i / 256) as u8;
+            recent.receive(Hash::SHA256(bytes), now);
+        }
+
+        assert_eq!(recent.received.len(), MAX_RECENT_PAYLOADS);
+        assert_eq!(recent.hashes.len(), MAX_RECENT_PAYLOADS);
+        // The oldest payload has been forgotten
+        assert_eq!(recent.receive(hash(0), now), Received::New);
+    }
+}
@@ -1,9 +1,11 @@
 use std::io::Error;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
 
 use actix::io::WriteHandler;
 use actix::{
-    ActorContext, ActorFuture, Context, ContextFutureSpawner, Handler, StreamHandler, System,
-    WrapFuture,
+    ActorContext, ActorFuture, ActorState, Context, ContextFutureSpawner, Handler, StreamHandler,
+    System, WrapFuture,
 };
 
 use log::{debug, error, info, warn};
@@ -11,145 +13,379 @@ use log::{debug, error, info, warn};
 use crate::actors::{
     blocks_manager::{
         messages::{
-            AddNewBlock, DiscardExistingInvVectors, GetBlocksEpochRange, GetHighestCheckpointBeacon,
+            AddNewBlock, AssignBlockRanges, DiscardExistingInvVectors, GetBlocksEpochRange,
+            GetHighestCheckpointBeacon, PeerLastBeacon, ReportPeerLastEpoch,
         },
-        BlocksManager,
+        BlocksManager, BlocksManagerError,
     },
     codec::BytesMut,
     peers_manager,
-    sessions_manager::{messages::Consolidate, SessionsManager},
+    sessions_manager::{
+        messages::{Consolidate, ReportExternalAddress, RequestDelivered, TrackRequests},
+        SessionsManager,
+    },
     storage_manager::{messages::Get, StorageManager},
 };
+use crate::metrics;
+use crate::tracing::Trace;
 
 use super::{
-    messages::{AnnounceItems, GetPeers, SessionUnitResult},
+    announcements::MAX_INV_VECTORS_PER_MESSAGE,
+    dedup::Received,
+    lanes::{Lane, MessageLanes},
+    messages::{
+        AnnounceItems, AnnounceTransactions, DownloadBlocks, GetPeers, PenalizePeer,
+        ProcessMessage, ReportSlowMessage, RequestBlocks, RequestItems, SessionUnitResult,
+    },
+    state::{IllegalTransition, TransitionResult},
     Session,
 };
+use witnet_crypto::hash::calculate_sha256;
 use witnet_data_structures::{
-    builders::from_address,
+    builders::{from_address, MAGIC},
     chain::{Block, CheckpointBeacon, Hash, InvVector},
     serializers::TryFrom,
-    types::{Address, Command, GetBlocks, GetData, Inv, Message as WitnetMessage, Peers, Version},
+    types::{
+        Address, Command, GetBlocks, GetData, Inv, LastBeacon, Message as WitnetMessage,
+        PeerAddress, Peers, Ping, Pong, Version,
+    },
 };
-use witnet_p2p::sessions::{SessionStatus, SessionType};
+use witnet_p2p::{peers::is_advertisable, sessions::SessionType};
+use witnet_util::timestamp::get_timestamp;
+
+/// Reason for penalizing the peers whose chain contradicts the final blocks of the local chain
+const FINALITY_VIOLATION: &str = "its chain rolls back blocks beyond the finality window";
+
+/// Reason for rejecting the peers whose consensus constants differ from the ones of this node
+const CONSENSUS_CONSTANTS_MISMATCH: &str = "its consensus constants do not match the local ones";
+
+/// Reason for reporting the peers which keep sending the same payloads
+const DUPLICATE_PAYLOADS: &str = "it keeps sending the same payloads";
+
+/// Time during which the peers which keep sending the same payloads are banned
+const DUPLICATE_PAYLOADS_BAN: Duration = Duration::from_secs(3600);
+
+/// Reason for disconnecting the peers whose messages keep exceeding the budget of their handler
+const SLOW_MESSAGES: &str = "its messages keep exceeding the time budget of their handler";
 
 /// Implement WriteHandler for Session
 impl WriteHandler<Error> for Session {}
 
 /// Implement `StreamHandler` trait in order to use `Framed` with an actor
 impl StreamHandler<BytesMut, Error> for Session {
-    /// This is main event loop for client requests
+    /// This is main event loop for client requests: the received messages are queued in their
+    /// lane and processed later on, one per ProcessMessage notification
     fn handle(&mut self, bytes: BytesMut, ctx: &mut Self::Context) {
-        let result = WitnetMessage::try_from(bytes.to_vec());
+        // Frames received during the Noise handshake are handshake messages
+        if self.encryption.is_handshaking() {
+            noise_handshake(self, ctx, &bytes);
+
+            return;
+        }
+
+        let bytes = match self.encryption.decrypt(bytes.to_vec()) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!(
+                    "Closing session with peer {}: error decrypting message: {:?}",
+                    self.remote_addr, e
+                );
+                ctx.stop();
+
+                return;
+            }
+        };
+
+        let bytes = match self.compression.decompress(bytes) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!(
+                    "Closing session with peer {}: error decompressing message: {}",
+                    self.remote_addr, e
+                );
+                ctx.stop();
+
+                return;
+            }
+        };
+
+        let result = WitnetMessage::try_from(bytes.clone());
         match result {
             Err(err) => error!("Error decoding message: {:?}", err),
+            // Messages of other networks are never valid, the peer is not a Witnet node
+            Ok(msg) if msg.magic != MAGIC => {
+                warn!(
+                    "Closing session with peer {}: invalid magic number {:#x}",
+                    self.remote_addr, msg.magic
+                );
+                ctx.stop();
+            }
             Ok(msg) => {
+                // Tag the message so it can be followed through the actors processing it
+                let trace = Trace::start();
                 info!(
-                    "<----- Session ({}) received message: {}",
-                    self.remote_addr, msg.kind
+                    "{}<----- Session ({}) received message: {}",
+                    trace, self.remote_addr, msg.kind
                 );
-                match (self.session_type, self.status, msg.kind) {
-                    ////////////////////
-                    //   HANDSHAKE    //
-                    ////////////////////
-                    // Handle Version message
-                    (
-                        _,
-                        SessionStatus::Unconsolidated,
-                        Command::Version(Version { sender_address, .. }),
-                    ) => {
-                        let msgs = handshake_version(self, &sender_address);
-                        for msg in msgs {
-                            self.send_message(msg);
+                // Keep track of the throughput of the peer
+                if let Command::Block(_) = msg.kind {
+                    self.block_bytes += bytes.len();
+                }
+
+                // The Noise handshake starts as soon as the Version and Verack messages of the
+                // peer are received, so the next frames have to be handled as Noise messages
+                // even if these messages have not been processed yet
+                if let Err(e) = self.encryption.message_received(&msg.kind, &bytes) {
+                    warn!(
+                        "Closing session with peer {}: error starting encryption: {:?}",
+                        self.remote_addr, e
+                    );
+                    ctx.stop();
+
+                    return;
+                }
+                self.compression.message_received(&msg.kind);
+
+                // Blocks and announcements already received within the deduplication window are
+                // dropped before they are validated again
+                if let Command::Block(_) | Command::Inv(_) = msg.kind {
+                    let hash = calculate_sha256(&bytes);
+                    if self.recent_payloads.receive(hash, Instant::now()) == Received::Duplicate {
+                        debug!(
+                            "{}Dropping repeated {} from peer {}",
+                            trace, msg.kind, self.remote_addr
+                        );
+                        metrics::record_duplicate_payload();
+                        if self.recent_payloads.is_repeated_offender() {
+                            System::current()
+                                .registry()
+                                .get::<peers_manager::PeersManager>()
+                                .do_send(peers_manager::messages::BanPeer {
+                                    address: self.remote_addr.ip(),
+                                    duration: Some(DUPLICATE_PAYLOADS_BAN),
+                                    reason: DUPLICATE_PAYLOADS.to_string(),
+                                });
+                            self.penalize(ctx, &DUPLICATE_PAYLOADS);
                         }
-                        try_consolidate_session(self, ctx);
-                    }
-                    // Handler Verack message
-                    (_, SessionStatus::Unconsolidated, Command::Verack(_)) => {
-                        handshake_verack(self);
-                        try_consolidate_session(self, ctx);
-                    }
-                    ////////////////////
-                    // PEER DISCOVERY //
-                    ////////////////////
-                    // Handle GetPeers message
-                    (SessionType::Outbound, SessionStatus::Consolidated, Command::GetPeers(_)) => {
-                        peer_discovery_get_peers(self, ctx);
-                    }
-                    // Handle Peers message
-                    (
-                        SessionType::Outbound,
-                        SessionStatus::Consolidated,
-                        Command::Peers(Peers { peers }),
-                    ) => {
-                        peer_discovery_peers(&peers);
+
+                        return;
                     }
-                    //////////////
-                    // GET DATA //
-                    //////////////
-                    (_, SessionStatus::Consolidated, Command::GetData(GetData { inventory })) => {
-                        for elem in inventory {
-                            match elem {
-                                InvVector::Block(hash)
-                                | InvVector::Tx(hash)
-                                | InvVector::DataRequest(hash)
-                                | InvVector::DataResult(hash) => {
-                                    send_block_msg(self, ctx, &hash);
-                                }
-                                InvVector::Error(_) => warn!("Error InvElem received"),
-                            }
+                }
+
+                self.lanes.push(Lane::of(&msg.kind), (msg, trace));
+                ctx.notify(ProcessMessage);
+            }
+        }
+    }
+}
+
+/// Handler for ProcessMessage message (sent by the session to itself)
+impl Handler<ProcessMessage> for Session {
+    type Result = SessionUnitResult;
+
+    fn handle(&mut self, _msg: ProcessMessage, ctx: &mut Context<Self>) {
+        let (msg, trace) = match self.lanes.next() {
+            Some(next) => next,
+            None => return,
+        };
+        let _span = trace.span("Session::ProcessMessage");
+
+        // Peers whose messages keep exceeding the budget of their handler are disconnected, and
+        // their queued messages are discarded
+        let timer = self.watchdog.start("Session", msg.kind.name());
+        self.process_message(ctx, msg, trace);
+        if timer.stop().is_some() {
+            self.record_slow_message(ctx);
+        }
+    }
+}
+
+/// Handler for ReportSlowMessage message (sent by the actors processing the messages of the peer)
+impl Handler<ReportSlowMessage> for Session {
+    type Result = SessionUnitResult;
+
+    fn handle(&mut self, msg: ReportSlowMessage, ctx: &mut Context<Self>) {
+        debug!(
+            "Handler {}::{} of a message of peer {} took {:?}",
+            msg.actor, msg.message, self.remote_addr, msg.elapsed
+        );
+        self.record_slow_message(ctx);
+    }
+}
+
+/// Processing of the messages received from the peer
+impl Session {
+    /// Record a message of the peer whose handler exceeded its time budget, and disconnect the
+    /// peer if its messages keep exceeding it
+    fn record_slow_message(&mut self, ctx: &mut Context<Self>) {
+        if self.slow_messages.record(Instant::now()) && ctx.state() == ActorState::Running {
+            metrics::record_slow_session_closed();
+            self.lanes = MessageLanes::default();
+            self.penalize(ctx, &SLOW_MESSAGES);
+        }
+    }
+
+    /// Process a message received from the peer, taken from its lane
+    fn process_message(&mut self, ctx: &mut Context<Self>, msg: WitnetMessage, trace: Trace) {
+        // Every state of the session only accepts its own set of messages
+        if !self.state.accepts(&msg.kind) {
+            let transition = IllegalTransition {
+                state: self.state.name(),
+                event: format!("Message of type \"{}\"", msg.kind),
+            };
+            self.penalize(ctx, &transition);
+
+            return;
+        }
+
+        match (self.session_type, msg.kind) {
+            ////////////////////
+            //   HANDSHAKE    //
+            ////////////////////
+            // Handle Version message
+            (
+                _,
+                Command::Version(Version {
+                    sender_address,
+                    receiver_address,
+                    last_epoch,
+                    consensus_constants_hash,
+                    ..
+                }),
+            ) => {
+                // The peers with other consensus constants belong to another network, so the
+                // session is never consolidated
+                if !self.consensus_constants_match(consensus_constants_hash) {
+                    self.penalize(ctx, &CONSENSUS_CONSTANTS_MISMATCH);
+
+                    return;
+                }
+
+                // Report the address under which the peer sees this node
+                System::current()
+                    .registry()
+                    .get::<SessionsManager>()
+                    .do_send(ReportExternalAddress {
+                        reporter: self.remote_addr.ip(),
+                        address: from_address(&receiver_address).ip(),
+                    });
+
+                // Report the last epoch of the peer to detect if the node falls behind
+                System::current()
+                    .registry()
+                    .get::<BlocksManager>()
+                    .do_send(ReportPeerLastEpoch {
+                        peer: self.remote_addr,
+                        last_epoch,
+                    });
+
+                match handshake_version(self, &sender_address) {
+                    Ok(()) => try_consolidate_session(self, ctx),
+                    Err(e) => self.penalize(ctx, &e),
+                }
+            }
+            // Handler Verack message
+            (_, Command::Verack(_)) => match self.state.verack_received() {
+                Ok(()) => try_consolidate_session(self, ctx),
+                Err(e) => self.penalize(ctx, &e),
+            },
+            ////////////////////
+            // PEER DISCOVERY //
+            ////////////////////
+            // Handle GetPeers message
+            (SessionType::Outbound, Command::GetPeers(_)) => {
+                peer_discovery_get_peers(self, ctx);
+            }
+            // Handle Peers message
+            (SessionType::Outbound, Command::Peers(Peers { peers })) => {
+                peer_discovery_peers(self, &peers);
+            }
+            //////////////
+            // GET DATA //
+            //////////////
+            (_, Command::GetData(GetData { inventory })) => {
+                for elem in inventory {
+                    match elem {
+                        InvVector::Block(hash)
+                        | InvVector::Tx(hash)
+                        | InvVector::DataRequest(hash)
+                        | InvVector::DataResult(hash) => {
+                            send_block_msg(self, ctx, &hash);
                         }
+                        InvVector::Error(_) => warn!("Error InvElem received"),
                     }
-                    ////////////////////
-                    // BLOCK RECEIVED //
-                    ////////////////////
-                    // Handle Block
-                    (_, SessionStatus::Consolidated, Command::Block(block)) => {
-                        inventory_process_block(self, ctx, block);
-                    }
+                }
+            }
+            ////////////////////
+            // BLOCK RECEIVED //
+            ////////////////////
+            // Handle Block
+            (_, Command::Block(block)) => {
+                inventory_process_block(self, ctx, block, trace);
+            }
 
-                    ////////////////
-                    // GET BLOCKS //
-                    ////////////////
-                    (
-                        SessionType::Inbound,
-                        SessionStatus::Consolidated,
-                        Command::GetBlocks(GetBlocks {
-                            highest_block_checkpoint,
-                        }),
-                    ) => {
-                        todo_inbound_session_getblocks(self, ctx, highest_block_checkpoint);
-                    }
-                    (
-                        SessionType::Outbound,
-                        SessionStatus::Consolidated,
-                        Command::GetBlocks(GetBlocks {
-                            highest_block_checkpoint,
-                        }),
-                    ) => {
-                        todo_outbound_session_getblocks(self, ctx, highest_block_checkpoint);
-                    }
+            //////////////////
+            //  PING/PONG   //
+            //////////////////
+            (_, Command::Ping(Ping { nonce })) => {
+                self.send_message(WitnetMessage::build_pong(nonce));
+            }
+            (_, Command::Pong(Pong { nonce })) => {
+                self.pong(nonce);
+            }
 
-                    ////////////////////
-                    // INVENTORY      //
-                    ////////////////////
-                    // Handle Inv message
-                    (_, SessionStatus::Consolidated, Command::Inv(inv)) => {
-                        inventory_process_inv(self, ctx, &inv);
-                    }
-                    /////////////////////
-                    // NOT SUPPORTED   //
-                    /////////////////////
-                    (session_type, session_status, msg_type) => {
-                        warn!(
-                            "Message of type \"{:?}\" for session (type: {:?}, status: {:?}) is \
-                             not supported",
-                            msg_type, session_type, session_status
-                        );
-                    }
-                };
+            ////////////////
+            // GET BLOCKS //
+            ////////////////
+            (
+                SessionType::Inbound,
+                Command::GetBlocks(GetBlocks {
+                    highest_block_checkpoint,
+                }),
+            ) => {
+                todo_inbound_session_getblocks(self, ctx, highest_block_checkpoint);
             }
-        }
+            (
+                SessionType::Outbound,
+                Command::GetBlocks(GetBlocks {
+                    highest_block_checkpoint,
+                }),
+            ) => {
+                todo_outbound_session_getblocks(self, ctx, highest_block_checkpoint);
+            }
+
+            /////////////////
+            // LAST BEACON //
+            /////////////////
+            (
+                session_type,
+                Command::LastBeacon(LastBeacon {
+                    highest_block_checkpoint,
+                }),
+            ) => {
+                session_last_beacon(self, ctx, session_type, highest_block_checkpoint);
+            }
+
+            ////////////////////
+            // INVENTORY      //
+            ////////////////////
+            // Handle Inv message
+            (_, Command::Inv(inv)) => {
+                inventory_process_inv(self, ctx, &inv, trace);
+            }
+            /////////////////////
+            // NOT SUPPORTED   //
+            /////////////////////
+            (session_type, msg_type) => {
+                warn!(
+                    "Message of type \"{:?}\" for session (type: {:?}, state: {}) is not \
+                     supported",
+                    msg_type,
+                    session_type,
+                    self.state.name()
+                );
+            }
+        };
     }
 }
 
@@ -166,33 +402,117 @@ impl Handler<GetPeers> for Session {
     }
 }
 
+/// Handler for RequestBlocks message (sent by other actors)
+impl Handler<RequestBlocks> for Session {
+    type Result = SessionUnitResult;
+
+    fn handle(&mut self, _msg: RequestBlocks, ctx: &mut Context<Self>) {
+        debug!("Requesting blocks to peer at {:?}", self.remote_addr);
+        inventory_get_blocks(self, ctx);
+    }
+}
+
+/// Handler for DownloadBlocks message (sent by other actors)
+impl Handler<DownloadBlocks> for Session {
+    type Result = SessionUnitResult;
+
+    fn handle(&mut self, _msg: DownloadBlocks, ctx: &mut Context<Self>) {
+        inventory_download_blocks(self, ctx);
+    }
+}
+
 /// Handler for AnnounceItems message (sent by other actors)
 impl Handler<AnnounceItems> for Session {
     type Result = SessionUnitResult;
 
-    fn handle(&mut self, msg: AnnounceItems, _: &mut Context<Self>) {
+    fn handle(&mut self, msg: AnnounceItems, ctx: &mut Context<Self>) {
         debug!(
-            "Sending AnnounceItems message to peer at {:?}",
+            "Queueing {} items to be announced to peer at {:?}",
+            msg.items.len(),
             self.remote_addr
         );
-        // Try to create Inv protocol message with items to be announced
-        if let Ok(inv_msg) = WitnetMessage::build_inv(msg.items) {
-            // Send message through the session network connection
-            self.send_message(inv_msg);
-        };
+        self.announce(ctx, msg.items);
     }
 }
 
-/// Function to try to consolidate session if handshake conditions are met
+/// Handler for AnnounceTransactions message (sent by other actors)
+impl Handler<AnnounceTransactions> for Session {
+    type Result = SessionUnitResult;
+
+    fn handle(&mut self, msg: AnnounceTransactions, ctx: &mut Context<Self>) {
+        debug!(
+            "Queueing {} transactions to be announced to peer at {:?}",
+            msg.transactions.len(),
+            self.remote_addr
+        );
+        let items = msg.transactions.into_iter().map(InvVector::Tx).collect();
+        self.announce(ctx, items);
+    }
+}
+
+/// Handler for RequestItems message (sent by other actors)
+impl Handler<RequestItems> for Session {
+    type Result = SessionUnitResult;
+
+    fn handle(&mut self, msg: RequestItems, _: &mut Context<Self>) {
+        debug!(
+            "Requesting {} items to peer at {:?}",
+            msg.items.len(),
+            self.remote_addr
+        );
+        if let Ok(get_data_msg) = WitnetMessage::build_get_data(msg.items) {
+            self.send_message(get_data_msg);
+        }
+    }
+}
+
+/// Handler for PenalizePeer message (sent by other actors)
+impl Handler<PenalizePeer> for Session {
+    type Result = SessionUnitResult;
+
+    fn handle(&mut self, msg: PenalizePeer, ctx: &mut Context<Self>) {
+        self.penalize(ctx, &msg.reason);
+    }
+}
+
+/// Function to try to consolidate session if the handshake has been completed
 fn try_consolidate_session(session: &mut Session, ctx: &mut Context<Session>) {
-    // Check if HandshakeFlags are all set to true
-    if session.handshake_flags.all_true() && session.remote_sender_addr.is_some() {
+    // The session is not consolidated until the connection is encrypted
+    if session.encryption.is_handshaking() {
+        return;
+    }
+
+    if let Some(peer_sender_addr) = session.state.handshake_completed() {
         // Update session to consolidate status
-        update_consolidate(session, ctx);
+        update_consolidate(session, ctx, peer_sender_addr);
 
-        // If session type is Outbound, start initial block synchronization
+        // If session type is Outbound, start initial block synchronization by announcing the
+        // last beacon to the peer
         if let SessionType::Outbound = session.session_type {
-            inventory_get_blocks(session, ctx);
+            send_last_beacon(session, ctx);
+        }
+    }
+}
+
+/// Function called when a Noise handshake message is received: the next handshake message is
+/// sent in response, and the session is consolidated once the handshake is finished
+fn noise_handshake(session: &mut Session, ctx: &mut Context<Session>, message: &[u8]) {
+    match session.encryption.handshake_message_received(message) {
+        Ok(response) => {
+            if let Some(response) = response {
+                session.send_frame(response);
+            }
+            if session.encryption.is_transport() {
+                debug!("Connection with peer {} encrypted", session.remote_addr);
+                try_consolidate_session(session, ctx);
+            }
+        }
+        Err(e) => {
+            warn!(
+                "Closing session with peer {}: Noise handshake failed: {:?}",
+                session.remote_addr, e
+            );
+            ctx.stop();
         }
     }
 }
@@ -228,8 +548,143 @@ fn inventory_get_blocks(session: &Session, ctx: &mut Context<Session>) {
         .wait(ctx);
 }
 
+/// Function to request to the peer the block ranges assigned to it by the block download
+/// process, one GetData message per range
+fn inventory_download_blocks(session: &Session, ctx: &mut Context<Session>) {
+    // Get BlocksManager address from registry
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    // Send AssignBlockRanges message to BlocksManager
+    blocks_manager_addr
+        .send(AssignBlockRanges {
+            peer: session.remote_addr,
+        })
+        .into_actor(session)
+        .then(|res, act, _ctx| match res {
+            Ok(Ok(ranges)) => {
+                for range in ranges {
+                    if let Ok(get_data_msg) = WitnetMessage::build_get_data(range) {
+                        act.send_message(get_data_msg);
+                    }
+                }
+
+                actix::fut::ok(())
+            }
+            _ => {
+                warn!("Assign block ranges in Blocks Manager failed");
+
+                actix::fut::err(())
+            }
+        })
+        .spawn(ctx);
+}
+
+/// Function to retrieve highest CheckpointBeacon and send LastBeacon message in Session
+fn send_last_beacon(session: &Session, ctx: &mut Context<Session>) {
+    // Get BlocksManager address from registry
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    // Send GetHighestCheckpointBeacon message to BlocksManager
+    blocks_manager_addr
+        .send(GetHighestCheckpointBeacon)
+        .into_actor(session)
+        .then(|res, act, ctx| {
+            match res {
+                Ok(Ok(beacon)) => {
+                    // Create last beacon message
+                    let last_beacon_msg = WitnetMessage::build_last_beacon(beacon);
+                    // Write last beacon message in session
+                    act.send_message(last_beacon_msg);
+
+                    actix::fut::ok(())
+                }
+                _ => {
+                    warn!("Get highest checkpoint beacon in Blocks Manager failed");
+                    // FIXME(#72): a full stop of the session is not correct (unregister should
+                    // be skipped)
+                    ctx.stop();
+
+                    actix::fut::err(())
+                }
+            }
+        })
+        .wait(ctx);
+}
+
+/// Function called when LastBeacon message is received
+/// The beacon of the peer is forwarded to the BlocksManager, which replies with the highest
+/// CheckpointBeacon of the local chain:
+/// * Inbound sessions reply with their own LastBeacon message
+/// * Outbound sessions (the ones which sent the first LastBeacon) request the missing blocks if
+/// the peer is ahead
+fn session_last_beacon(
+    session: &Session,
+    ctx: &mut Context<Session>,
+    session_type: SessionType,
+    peer_beacon: CheckpointBeacon,
+) {
+    // Get BlocksManager address from registry
+    let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+    // Send PeerLastBeacon message to BlocksManager
+    blocks_manager_addr
+        .send(PeerLastBeacon {
+            peer: session.remote_addr,
+            beacon: peer_beacon,
+        })
+        .into_actor(session)
+        .then(move |res, act, ctx| {
+            match res {
+                Ok(Ok(beacon)) => {
+                    match session_type {
+                        SessionType::Inbound => {
+                            act.send_message(WitnetMessage::build_last_beacon(beacon));
+                        }
+                        SessionType::Outbound => {
+                            if peer_is_ahead(&beacon, &peer_beacon) {
+                                debug!(
+                                    "Peer at {:?} is ahead (checkpoint {} > {}), requesting blocks",
+                                    act.remote_addr, peer_beacon.checkpoint, beacon.checkpoint
+                                );
+                                act.send_message(WitnetMessage::build_get_blocks(beacon));
+                            }
+                        }
+                    }
+
+                    actix::fut::ok(())
+                }
+                Ok(Err(BlocksManagerError::FinalityViolation)) => {
+                    act.penalize(ctx, &FINALITY_VIOLATION);
+
+                    actix::fut::err(())
+                }
+                _ => {
+                    warn!("Get highest checkpoint beacon in Blocks Manager failed");
+                    // FIXME(#72): a full stop of the session is not correct (unregister should
+                    // be skipped)
+                    ctx.stop();
+
+                    actix::fut::err(())
+                }
+            }
+        })
+        .wait(ctx);
+}
+
+/// Compare the beacon of the local chain with the beacon of a peer to decide whether blocks need
+/// to be downloaded from that peer
+fn peer_is_ahead(local_beacon: &CheckpointBeacon, peer_beacon: &CheckpointBeacon) -> bool {
+    if peer_beacon.checkpoint == local_beacon.checkpoint
+        && peer_beacon.hash_prev_block != local_beacon.hash_prev_block
+    {
+        warn!(
+            "Peer chain forks at checkpoint {}: {:?} != {:?}",
+            peer_beacon.checkpoint, peer_beacon.hash_prev_block, local_beacon.hash_prev_block
+        );
+    }
+
+    peer_beacon.checkpoint > local_beacon.checkpoint
+}
+
 // Function to notify the SessionsManager that the session has been consolidated
-fn update_consolidate(session: &Session, ctx: &mut Context<Session>) {
+fn update_consolidate(session: &Session, ctx: &mut Context<Session>, peer_sender_addr: SocketAddr) {
     // Get session manager address
     let session_manager_addr = System::current().registry().get::<SessionsManager>();
 
@@ -239,7 +694,7 @@ fn update_consolidate(session: &Session, ctx: &mut Context<Session>) {
     session_manager_addr
         .send(Consolidate {
             address: session.remote_addr,
-            potential_new_peer: session.remote_sender_addr.unwrap(),
+            potential_new_peer: peer_sender_addr,
             session_type: session.session_type,
         })
         .into_actor(session)
@@ -247,10 +702,19 @@ fn update_consolidate(session: &Session, ctx: &mut Context<Session>) {
             match res {
                 Ok(Ok(_)) => {
                     debug!("Session successfully consolidated in the Session Manager");
-                    // Set status to consolidate
-                    act.status = SessionStatus::Consolidated;
+                    // Set state to consolidated
+                    match act.state.consolidate() {
+                        Ok(()) => actix::fut::ok(()),
+                        Err(e) => {
+                            error!(
+                                "Session with peer {} failed to consolidate: {}",
+                                act.remote_addr, e
+                            );
+                            ctx.stop();
 
-                    actix::fut::ok(())
+                            actix::fut::err(())
+                        }
+                    }
                 }
                 _ => {
                     warn!("Session consolidate in Session Manager failed");
@@ -274,9 +738,9 @@ fn peer_discovery_get_peers(session: &mut Session, ctx: &mut Context<Session>) {
 
     // Start chain of actions
     peers_manager_addr
-        // Send GetPeer message to PeersManager actor
+        // Send GetPeersWithLastSeen message to PeersManager actor
         // This returns a Request Future, representing an asynchronous message sending process
-        .send(peers_manager::messages::GetPeers)
+        .send(peers_manager::messages::GetPeersWithLastSeen)
         // Convert a normal future into an ActorFuture
         .into_actor(session)
         // Process the response from PeersManager
@@ -288,6 +752,14 @@ fn peer_discovery_get_peers(session: &mut Session, ctx: &mut Context<Session>) {
                         "Received ({:?}) peer addresses from PeersManager",
                         addresses.len()
                     );
+                    // Advertise the address of this node as well, if it is reachable, signed
+                    // with the address identity of the node
+                    let mut addresses = addresses;
+                    if is_advertisable(&act.server_addr) {
+                        let last_seen = get_timestamp();
+                        let signature = act.address_signatures.sign(act.server_addr, last_seen);
+                        addresses.push((act.server_addr, last_seen, signature));
+                    }
                     let peers_msg = WitnetMessage::build_peers(&addresses);
                     act.send_message(peers_msg);
                 }
@@ -304,35 +776,122 @@ fn peer_discovery_get_peers(session: &mut Session, ctx: &mut Context<Session>) {
 }
 
 /// Function called when Peers message is received
-fn peer_discovery_peers(peers: &[Address]) {
+fn peer_discovery_peers(session: &Session, peers: &[PeerAddress]) {
     // Get peers manager address
     let peers_manager_addr = System::current()
         .registry()
         .get::<peers_manager::PeersManager>();
 
-    // Convert array of address to vector of socket addresses
-    let addresses = peers.iter().map(from_address).collect();
+    // Convert array of peer addresses to vector of socket addresses, last seen timestamps and
+    // origin signatures, discarding the addresses whose signature is not valid
+    let addresses: Vec<_> = peers
+        .iter()
+        .filter(|peer| session.address_signatures.verify(peer))
+        .map(|peer| {
+            (
+                from_address(&peer.address),
+                peer.last_seen,
+                peer.signature.clone(),
+            )
+        })
+        .collect();
+    if addresses.len() < peers.len() {
+        debug!(
+            "Discarded {} peer addresses with an invalid signature from peer {}",
+            peers.len() - addresses.len(),
+            session.remote_addr
+        );
+    }
 
-    // Send AddPeers message to the peers manager
-    peers_manager_addr.do_send(peers_manager::messages::AddPeers {
-        // TODO: convert Vec<Address> to Vec<SocketAddr>
+    // Send AddReportedPeers message to the peers manager, using the remote address of this
+    // session as the source of the reported addresses
+    peers_manager_addr.do_send(peers_manager::messages::AddReportedPeers {
+        source: session.remote_addr.ip(),
         addresses,
     });
 }
 
 /// Function called when Block message is received
-fn inventory_process_block(_session: &mut Session, _ctx: &mut Context<Session>, block: Block) {
+/// The peer is penalized if the block would roll back the final blocks of the local chain
+fn inventory_process_block(
+    session: &mut Session,
+    ctx: &mut Context<Session>,
+    block: Block,
+    trace: Trace,
+) {
+    let received_at = Instant::now();
+
+    // Stop tracking the block if it had been requested
+    System::current()
+        .registry()
+        .get::<SessionsManager>()
+        .do_send(RequestDelivered {
+            address: session.remote_addr,
+            item: InvVector::Block(calculate_sha256(&block.hashed_bytes())),
+        });
+
     // Get BlocksManager address
     let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
 
     // Send a message to the BlocksManager to try to add a new block
-    blocks_manager_addr.do_send(AddNewBlock { block });
+    blocks_manager_addr
+        .send(AddNewBlock {
+            block,
+            received_at,
+            trace,
+            origin: Some(ctx.address().recipient()),
+        })
+        .into_actor(session)
+        .then(move |res, act, ctx| {
+            if let Ok(Err(BlocksManagerError::FinalityViolation)) = res {
+                info!("{}Penalizing peer {}", trace, act.remote_addr);
+                act.penalize(ctx, &FINALITY_VIOLATION);
+            }
+
+            actix::fut::ok(())
+        })
+        .spawn(ctx);
 }
 
 /// Function to process an Inv message
-fn inventory_process_inv(session: &mut Session, ctx: &mut Context<Session>, inv: &Inv) {
-    // Check how many of the received inventory vectors need to be requested
-    let inv_vectors = &inv.inventory;
+fn inventory_process_inv(
+    session: &mut Session,
+    ctx: &mut Context<Session>,
+    inv: &Inv,
+    trace: Trace,
+) {
+    // This node never announces more items in a single message, so bigger messages can only be
+    // meant to flood the BlocksManager
+    if inv.inventory.len() > MAX_INV_VECTORS_PER_MESSAGE {
+        let reason = format!(
+            "its Inv message announces {} items (the maximum is {})",
+            inv.inventory.len(),
+            MAX_INV_VECTORS_PER_MESSAGE
+        );
+        session.penalize(ctx, &reason);
+
+        return;
+    }
+
+    // Shed the inventory vectors already being processed: repeated announcements would
+    // only queue redundant messages in the BlocksManager mailbox
+    let inv_vectors: Vec<InvVector> = inv
+        .inventory
+        .iter()
+        .filter(|inv_vector| session.pending_inv_vectors.insert((*inv_vector).clone()))
+        .cloned()
+        .collect();
+    let duplicates = inv.inventory.len() - inv_vectors.len();
+    if duplicates > 0 {
+        debug!(
+            "{}Discarding {} duplicate inventory vectors",
+            trace, duplicates
+        );
+        metrics::record_duplicate_inv_vectors(duplicates);
+    }
+    if inv_vectors.is_empty() {
+        return;
+    }
 
     // Get BlocksManager address
     let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
@@ -342,22 +901,31 @@ fn inventory_process_inv(session: &mut Session, ctx: &mut Context<Session>, inv:
         // Send GetConfig message to config manager actor
         // This returns a Request Future, representing an asynchronous message sending process
         .send(DiscardExistingInvVectors {
-            inv_vectors: inv_vectors.to_vec(),
+            inv_vectors: inv_vectors.clone(),
+            trace,
         })
         // Convert a normal future into an ActorFuture
         .into_actor(session)
         // Process the response from the blocks manager
         // This returns a FutureResult containing the socket address if present
-        .then(|res, _act, _ctx| {
+        .then(move |res, act, _ctx| {
+            // The inventory vectors are no longer being processed
+            for inv_vector in &inv_vectors {
+                act.pending_inv_vectors.remove(inv_vector);
+            }
+
             // Process the Result<InvVectorsResult, MailboxError>
             match res {
                 Err(e) => {
-                    error!("Unsuccessful communication with blocks manager: {}", e);
+                    error!(
+                        "{}Unsuccessful communication with blocks manager: {}",
+                        trace, e
+                    );
                     actix::fut::err(())
                 }
                 Ok(res) => match res {
                     Err(_) => {
-                        error!("Error while filtering inventory vectors");
+                        error!("{}Error while filtering inventory vectors", trace);
                         actix::fut::err(())
                     }
                     Ok(res) => actix::fut::ok(res),
@@ -366,57 +934,77 @@ fn inventory_process_inv(session: &mut Session, ctx: &mut Context<Session>, inv:
         })
         // Process the received filtered inv elems
         // This returns a FutureResult containing a success
-        .and_then(|missing_inv_vectors, act, _ctx| {
+        .and_then(move |missing_inv_vectors, act, _ctx| {
+            debug!(
+                "{}Requesting {} missing inventory vectors",
+                trace,
+                missing_inv_vectors.len()
+            );
             // Try to create GetData protocol message to request missing inventory vectors
             if let Ok(get_data_msg) = WitnetMessage::build_get_data(missing_inv_vectors.to_vec()) {
                 // Send GetData message through the session network connection
                 act.send_message(get_data_msg);
+
+                // Track the requested blocks, so they are requested to another peer if this one
+                // does not deliver them in time (only blocks are delivered by the peers so far)
+                let items: Vec<InvVector> = missing_inv_vectors
+                    .into_iter()
+                    .filter(|inv_vector| match inv_vector {
+                        InvVector::Block(_) => true,
+                        _ => false,
+                    })
+                    .collect();
+                if !items.is_empty() {
+                    System::current()
+                        .registry()
+                        .get::<SessionsManager>()
+                        .do_send(TrackRequests {
+                            address: act.remote_addr,
+                            items,
+                        });
+                }
             }
 
             actix::fut::ok(())
         })
-        .wait(ctx);
-}
-
-/// Function called when Verack message is received
-fn handshake_verack(session: &mut Session) {
-    let flags = &mut session.handshake_flags;
-
-    if flags.verack_rx {
-        debug!("Verack message already received");
-    }
-
-    // Set verack_rx flag
-    flags.verack_rx = true;
+        // Do not stall the session while the BlocksManager is busy: further duplicate
+        // announcements will be shed
+        .spawn(ctx);
 }
 
-/// Function called when Version message is received
-fn handshake_version(session: &mut Session, sender_address: &Address) -> Vec<WitnetMessage> {
-    let flags = &mut session.handshake_flags;
-
-    if flags.version_rx {
-        debug!("Version message already received");
-    }
-
+/// Function called when Version message is received: the Verack message is sent in response,
+/// along with the Version message of this node if it has not been sent yet
+fn handshake_version(session: &mut Session, sender_address: &Address) -> TransitionResult<()> {
     // Placeholder for version fields verification
-    session.remote_sender_addr = Some(from_address(sender_address));
-
-    // Set version_rx flag, indicating reception of a version message from the peer
-    flags.version_rx = true;
+    let send_version = session
+        .state
+        .version_received(from_address(sender_address))?;
 
-    let mut responses: Vec<WitnetMessage> = vec![];
-    if !flags.verack_tx {
-        flags.verack_tx = true;
-        let verack = WitnetMessage::build_verack();
-        responses.push(verack);
+    session.send_message(WitnetMessage::build_verack());
+    if send_version {
+        let version = WitnetMessage::build_version(
+            session.server_addr,
+            session.remote_addr,
+            0,
+            session.capabilities(),
+            session.consensus_constants_hash,
+        );
+        session.send_message(version);
+        session.state.version_sent()?;
     }
-    if !flags.version_tx {
-        flags.version_tx = true;
-        let version = WitnetMessage::build_version(session.server_addr, session.remote_addr, 0);
-        responses.push(version);
+
+    // Start the Noise handshake right after the Verack message if this node initiated the
+    // connection and both nodes support encryption
+    match session.encryption.initiate() {
+        Ok(Some(message)) => session.send_frame(message),
+        Ok(None) => {}
+        Err(e) => error!(
+            "Error starting Noise handshake with peer {}: {:?}",
+            session.remote_addr, e
+        ),
     }
 
-    responses
+    Ok(())
 }
 
 /// Function called when GetData message is received
@@ -452,9 +1040,10 @@ fn send_block_msg(session: &mut Session, ctx: &mut Context<Session>, hash: &Hash
             if let Some(block_from_storage) = block_from_storage {
                 let header = block_from_storage.header;
                 let txns = block_from_storage.txns;
+                let mint = block_from_storage.mint;
 
                 // Build Block msg
-                let block_msg = WitnetMessage::build_block(header, txns);
+                let block_msg = WitnetMessage::build_block(header, txns, mint);
 
                 // Send Block msg
                 act.send_message(block_msg);
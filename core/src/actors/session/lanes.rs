@@ -0,0 +1,136 @@
+//! # Message lanes
+//!
+//! The messages received from the peer are queued in two lanes before
+//! being processed: the control lane (handshake and ping/pong messages)
+//! and the data lane (everything else, including the large block
+//! payloads received while syncing). The control lane has priority, but
+//! the data lane is still served after every burst of control messages,
+//! so neither of them can starve the other.
+use std::collections::VecDeque;
+
+use witnet_data_structures::types::Command;
+
+/// Maximum number of consecutive control messages processed while there are data messages
+/// waiting
+pub const CONTROL_BURST: usize = 8;
+
+/// Lane of a message
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Lane {
+    /// Handshake and ping/pong messages
+    Control,
+    /// Any other message
+    Data,
+}
+
+impl Lane {
+    /// Get the lane of a message
+    pub fn of(command: &Command) -> Lane {
+        match command {
+            Command::Version(_) | Command::Verack(_) | Command::Ping(_) | Command::Pong(_) => {
+                Lane::Control
+            }
+            _ => Lane::Data,
+        }
+    }
+}
+
+/// Queues of the messages waiting to be processed
+#[derive(Debug)]
+pub struct MessageLanes<T> {
+    /// Control messages
+    control: VecDeque<T>,
+    /// Data messages
+    data: VecDeque<T>,
+    /// Number of consecutive control messages taken while there were data messages waiting
+    control_streak: usize,
+}
+
+impl<T> Default for MessageLanes<T> {
+    fn default() -> Self {
+        MessageLanes {
+            control: VecDeque::new(),
+            data: VecDeque::new(),
+            control_streak: 0,
+        }
+    }
+}
+
+impl<T> MessageLanes<T> {
+    /// Queue a message in a lane
+    pub fn push(&mut self, lane: Lane, item: T) {
+        match lane {
+            Lane::Control => self.control.push_back(item),
+            Lane::Data => self.data.push_back(item),
+        }
+    }
+
+    /// Take the next message to be processed: control messages go first, except that a data
+    /// message is taken after every `CONTROL_BURST` consecutive control messages
+    pub fn next(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            self.control_streak = 0;
+            return self.control.pop_front();
+        }
+        if self.control.is_empty() || self.control_streak >= CONTROL_BURST {
+            self.control_streak = 0;
+            return self.data.pop_front();
+        }
+
+        self.control_streak += 1;
+        self.control.pop_front()
+    }
+
+    /// Number of messages waiting to be processed
+    pub fn len(&self) -> usize {
+        self.control.len() + self.data.len()
+    }
+
+    /// Whether there are no messages waiting to be processed
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use witnet_data_structures::types::{GetPeers, Ping, Verack};
+
+    #[test]
+    fn lane_of_command() {
+        assert_eq!(Lane::of(&Command::Verack(Verack)), Lane::Control);
+        assert_eq!(Lane::of(&Command::Ping(Ping { nonce: 1 })), Lane::Control);
+        assert_eq!(Lane::of(&Command::GetPeers(GetPeers)), Lane::Data);
+    }
+
+    #[test]
+    fn control_messages_first() {
+        let mut lanes = MessageLanes::default();
+        lanes.push(Lane::Data, "block 1");
+        lanes.push(Lane::Data, "block 2");
+        lanes.push(Lane::Control, "ping");
+
+        assert_eq!(lanes.len(), 3);
+        assert_eq!(lanes.next(), Some("ping"));
+        assert_eq!(lanes.next(), Some("block 1"));
+        assert_eq!(lanes.next(), Some("block 2"));
+        assert_eq!(lanes.next(), None);
+        assert!(lanes.is_empty());
+    }
+
+    #[test]
+    fn data_messages_are_not_starved() {
+        let mut lanes = MessageLanes::default();
+        lanes.push(Lane::Data, 0);
+        for i in 1..=CONTROL_BURST + 1 {
+            lanes.push(Lane::Control, i);
+        }
+
+        let order: Vec<_> = std::iter::from_fn(|| lanes.next()).collect();
+        let mut expected: Vec<_> = (1..=CONTROL_BURST).collect();
+        expected.push(0);
+        expected.push(CONTROL_BURST + 1);
+        assert_eq!(order, expected);
+    }
+}
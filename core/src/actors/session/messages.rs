@@ -1,19 +1,84 @@
+use std::time::Duration;
+
 use actix::Message;
-use witnet_data_structures::chain::InvVector;
+use witnet_data_structures::chain::{Hash, InvVector};
 
 /// Message result of unit
 pub type SessionUnitResult = ();
 
 /// Message to indicate that the session needs to send a GetPeers message through the network
+#[derive(Clone)]
 pub struct GetPeers;
 
 impl Message for GetPeers {
     type Result = SessionUnitResult;
 }
 
+/// Message to indicate that the session needs to request the blocks after the local chain tip
+/// through the network (used when the node falls behind and needs to re-sync)
+#[derive(Clone)]
+pub struct RequestBlocks;
+
+impl Message for RequestBlocks {
+    type Result = SessionUnitResult;
+}
+
+/// Message to indicate that the session needs to request to its peer the block ranges assigned
+/// by the block download process (used while the node is syncing)
+#[derive(Clone)]
+pub struct DownloadBlocks;
+
+impl Message for DownloadBlocks {
+    type Result = SessionUnitResult;
+}
+
+/// Message to indicate that the session needs to process the next message received from the
+/// peer, taken from the message lanes
+#[derive(Clone)]
+pub struct ProcessMessage;
+
+impl Message for ProcessMessage {
+    type Result = SessionUnitResult;
+}
+
 /// Message to announce new inventory items through the network
 #[derive(Clone, Message)]
 pub struct AnnounceItems {
     /// Inventory items
     pub items: Vec<InvVector>,
 }
+
+/// Message to announce transactions through the network (the MempoolManager is notified once
+/// they have been sent to the peer)
+#[derive(Clone, Message)]
+pub struct AnnounceTransactions {
+    /// Hashes of the transactions
+    pub transactions: Vec<Hash>,
+}
+
+/// Message to request inventory items to the peer through the network (used to retry the items
+/// that other peers did not deliver in time)
+#[derive(Clone, Message)]
+pub struct RequestItems {
+    /// Inventory items
+    pub items: Vec<InvVector>,
+}
+
+/// Message to report that the handler of a message of the peer, processed by another actor,
+/// exceeded its time budget, so it counts against the peer (see `watchdog`)
+#[derive(Clone, Message)]
+pub struct ReportSlowMessage {
+    /// Actor running the handler
+    pub actor: &'static str,
+    /// Message type handled
+    pub message: &'static str,
+    /// Time taken by the handler
+    pub elapsed: Duration,
+}
+
+/// Message to indicate that the session needs to be penalized (closed and its peer removed)
+#[derive(Clone, Message)]
+pub struct PenalizePeer {
+    /// Reason of the penalty
+    pub reason: String,
+}
@@ -1,46 +1,85 @@
+use std::collections::HashSet;
+use std::fmt;
 use std::net::SocketAddr;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use actix::io::FramedWrite;
+use actix::{io::FramedWrite, ActorContext, AsyncContext, Context, System};
 
-use log::info;
+use log::{debug, error, info, warn};
 use tokio::io::WriteHalf;
 use tokio::net::TcpStream;
 
-use crate::actors::codec::P2PCodec;
-use witnet_data_structures::types::Message as WitnetMessage;
-use witnet_p2p::sessions::{SessionStatus, SessionType};
+use crate::actors::{
+    codec::P2PCodec,
+    mempool_manager::{messages::TransactionsAnnounced, MempoolManager},
+    peers_manager::{messages::RemovePeers, PeersManager},
+    sessions_manager::{
+        messages::{RecordPeerLatency, RecordPeerThroughput},
+        SessionsManager,
+    },
+};
+use crate::tracing::Trace;
+use crate::watchdog::{SlowMessages, Watchdog};
+use witnet_data_structures::{
+    chain::{Hash, InvVector},
+    types::{Command, Message as WitnetMessage, Ping},
+};
+use witnet_p2p::sessions::SessionType;
+
+use self::addresses::AddressSignatures;
+use self::announcements::PendingAnnouncements;
+use self::compression::Compression;
+use self::dedup::RecentPayloads;
+use self::lanes::MessageLanes;
+use self::noise::{Encryption, NodeIdentity};
+use self::state::SessionState;
 
 mod actor;
 
+/// Signatures of the peer addresses
+pub mod addresses;
+/// Batched announcements of inventory items
+pub mod announcements;
+/// Compression of the messages
+pub mod compression;
+/// Deduplication of the payloads received from the peer
+pub mod dedup;
+
 mod handlers;
+/// Priority lanes of the messages received from the peer
+pub mod lanes;
 /// Messages for session
 pub mod messages;
+/// Encryption of the connection with the Noise protocol
+pub mod noise;
 
-/// HandshakeFlags
-#[derive(Default)]
-struct HandshakeFlags {
-    /// Flag to indicate that a version message was sent
-    version_tx: bool,
-    /// Flag to indicate that a version message was received
-    version_rx: bool,
-    /// Flag to indicate that a verack message was sent
-    verack_tx: bool,
-    /// Flag to indicate that a verack message was received
-    verack_rx: bool,
-}
+/// State machine of the session
+pub mod state;
 
-/// HandshakeFlags helper methods
-impl HandshakeFlags {
-    // Auxiliary function to check if all flags are set to true
-    fn all_true(&self) -> bool {
-        self.verack_tx && self.verack_rx && self.version_tx && self.version_rx
-    }
+/// Period (in seconds) between two Ping messages sent to the peer of a consolidated session
+pub const PING_PERIOD_SECONDS: u64 = 30;
+
+/// Settings of the transport of the messages of the sessions
+#[derive(Clone, Default)]
+pub struct TransportSettings {
+    /// Identity of the node, to encrypt the connections (not encrypted if there is none)
+    pub identity: Option<Arc<NodeIdentity>>,
+    /// Minimum size of the compressed messages (not compressed if there is none)
+    pub compression_threshold: Option<usize>,
+    /// Hash of the consensus constants of the node, exchanged in the handshake so the peers of
+    /// other networks are rejected (not checked if there is none)
+    pub consensus_constants_hash: Option<Hash>,
+    /// Time budgets of the handlers of the messages received from the peers
+    pub watchdog: Watchdog,
+    /// Signer and verifier of the addresses shared through peer discovery (the addresses of this
+    /// node are not signed until its address identity is set)
+    pub address_signatures: AddressSignatures,
 }
 
 /// Session representing a TCP connection
 pub struct Session {
-    /// Server socket address (local peer)
+    /// Server socket address advertised to the remote peer (local peer)
     server_addr: SocketAddr,
 
     /// Remote socket address (remote server address only if outbound session)
@@ -55,14 +94,51 @@ pub struct Session {
     /// Handshake timeout
     handshake_timeout: Duration,
 
-    /// Session status
-    status: SessionStatus,
+    /// Time during which announcements are accumulated before being sent in a single Inv
+    /// message
+    announcement_debounce: Duration,
+
+    /// Items waiting to be announced to the peer
+    pending_announcements: PendingAnnouncements,
+
+    /// Session state
+    state: SessionState,
+
+    /// Encryption of the connection
+    encryption: Encryption,
+
+    /// Compression of the messages
+    compression: Compression,
+
+    /// Hash of the consensus constants of the node (peers are not checked if there is none)
+    consensus_constants_hash: Option<Hash>,
+
+    /// Messages received from the peer waiting to be processed, along with their correlation ID
+    lanes: MessageLanes<(WitnetMessage, Trace)>,
 
-    /// HandshakeFlags
-    handshake_flags: HandshakeFlags,
+    /// Inventory vectors announced by the peer that are still being processed
+    pending_inv_vectors: HashSet<InvVector>,
 
-    /// Remote sender address
-    remote_sender_addr: Option<SocketAddr>,
+    /// Payloads received from the peer during the deduplication window
+    recent_payloads: RecentPayloads,
+
+    /// Time budgets of the handlers of the messages received from the peer
+    watchdog: Watchdog,
+
+    /// Signer and verifier of the addresses shared with the peer
+    address_signatures: AddressSignatures,
+
+    /// Messages of the peer whose handler exceeded its budget during the last window
+    slow_messages: SlowMessages,
+
+    /// Nonce and sending time of the last Ping message not answered yet
+    pending_ping: Option<(u64, Instant)>,
+
+    /// Bytes of the blocks received since the last throughput report
+    block_bytes: usize,
+
+    /// Time of the last throughput report
+    block_bytes_since: Instant,
 }
 
 /// Session helper methods
@@ -74,16 +150,135 @@ impl Session {
         session_type: SessionType,
         framed: FramedWrite<WriteHalf<TcpStream>, P2PCodec>,
         handshake_timeout: Duration,
+        announcement_debounce: Duration,
+        transport: TransportSettings,
     ) -> Session {
+        let initiator = match session_type {
+            SessionType::Outbound => true,
+            SessionType::Inbound => false,
+        };
+
         Session {
             server_addr,
             remote_addr,
             session_type,
             framed,
             handshake_timeout,
-            status: SessionStatus::Unconsolidated,
-            handshake_flags: HandshakeFlags::default(),
-            remote_sender_addr: None,
+            announcement_debounce,
+            pending_announcements: PendingAnnouncements::default(),
+            state: SessionState::default(),
+            encryption: Encryption::new(transport.identity, initiator),
+            compression: Compression::new(transport.compression_threshold),
+            consensus_constants_hash: transport.consensus_constants_hash,
+            lanes: MessageLanes::default(),
+            pending_inv_vectors: HashSet::new(),
+            recent_payloads: RecentPayloads::default(),
+            slow_messages: transport.watchdog.slow_messages(),
+            watchdog: transport.watchdog,
+            address_signatures: transport.address_signatures,
+            pending_ping: None,
+            block_bytes: 0,
+            block_bytes_since: Instant::now(),
+        }
+    }
+    /// Method to send a Ping message to the peer (used to measure its latency) and to report
+    /// the throughput of the blocks received from the peer since the last report
+    fn ping(&mut self) {
+        if !self.state.is_consolidated() {
+            return;
+        }
+        let now = Instant::now();
+        let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
+
+        if self.block_bytes > 0 {
+            sessions_manager_addr.do_send(RecordPeerThroughput {
+                address: self.remote_addr,
+                bytes: self.block_bytes,
+                elapsed: now.duration_since(self.block_bytes_since),
+            });
+        }
+        self.block_bytes = 0;
+        self.block_bytes_since = now;
+
+        let ping_msg = WitnetMessage::build_ping();
+        if let Command::Ping(Ping { nonce }) = ping_msg.kind {
+            self.pending_ping = Some((nonce, now));
+        }
+        self.send_message(ping_msg);
+    }
+    /// Method to process the Pong message sent by the peer as a response to a Ping message
+    /// Pong messages which do not match the last Ping message are ignored
+    fn pong(&mut self, nonce: u64) {
+        match self.pending_ping {
+            Some((ping_nonce, sent_at)) if ping_nonce == nonce => {
+                self.pending_ping = None;
+                System::current()
+                    .registry()
+                    .get::<SessionsManager>()
+                    .do_send(RecordPeerLatency {
+                        address: self.remote_addr,
+                        latency: sent_at.elapsed(),
+                    });
+            }
+            _ => debug!("Unexpected Pong message from peer {}", self.remote_addr),
+        }
+    }
+    /// Method to penalize the peer for misbehaving (e.g. an illegal transition of the state of
+    /// the session): the session is closed and, if it is outbound, the address of the peer is
+    /// removed from the known peers so it is not tried again
+    fn penalize(&mut self, ctx: &mut Context<Self>, reason: &dyn fmt::Display) {
+        warn!("Closing session with peer {}: {}", self.remote_addr, reason);
+
+        if let SessionType::Outbound = self.session_type {
+            System::current()
+                .registry()
+                .get::<PeersManager>()
+                .do_send(RemovePeers {
+                    addresses: vec![self.remote_addr],
+                });
+        }
+
+        ctx.stop();
+    }
+    /// Method to queue some items to be announced to the peer. They are sent along with the
+    /// other items announced during the debounce window (or right away if it is zero)
+    fn announce(&mut self, ctx: &mut Context<Self>, items: Vec<InvVector>) {
+        self.pending_announcements.add(items);
+        if self.announcement_debounce == Duration::from_secs(0) {
+            self.flush_announcements();
+        } else if self.pending_announcements.schedule_flush() {
+            ctx.run_later(self.announcement_debounce, |act, _ctx| {
+                act.flush_announcements();
+            });
+        }
+    }
+    /// Method to send the queued announcements to the peer in as few Inv messages as possible
+    /// The MempoolManager is notified of the transactions which have been announced
+    fn flush_announcements(&mut self) {
+        let mut transactions = vec![];
+        for items in self.pending_announcements.take_batches() {
+            debug!(
+                "Announcing {} items to peer at {:?}",
+                items.len(),
+                self.remote_addr
+            );
+            transactions.extend(announcements::transaction_hashes(&items));
+            // Try to create Inv protocol message with the items to be announced
+            if let Ok(inv_msg) = WitnetMessage::build_inv(items) {
+                // Send message through the session network connection
+                self.send_message(inv_msg);
+            }
+        }
+
+        // Let the MempoolManager know which peer the transactions were announced to
+        if !transactions.is_empty() {
+            System::current()
+                .registry()
+                .get::<MempoolManager>()
+                .do_send(TransactionsAnnounced {
+                    peer: self.remote_addr,
+                    transactions,
+                });
         }
     }
     /// Method to send a Witnet message to the remote peer
@@ -92,8 +287,56 @@ impl Session {
             "-----> Session ({:?}) sending message: {:?}",
             self.remote_addr, msg
         );
+        // Keep track of the handshake messages, which decide when compression and encryption
+        // start
+        let handshake_command = match msg.kind {
+            Command::Version(_) | Command::Verack(_) => Some(msg.kind.clone()),
+            _ => None,
+        };
         // Convert WitnetMessage into a vector of bytes
         let bytes: Vec<u8> = msg.into();
+        if let Some(command) = &handshake_command {
+            self.encryption.message_sent(command, &bytes);
+        }
+        // Compress the bytes if compression has been negotiated, and then encrypt them if the
+        // connection is encrypted. The Version and Verack messages are always sent in plain
+        // text, as they are exchanged before (or while) the encryption is negotiated.
+        match self.compression.compress(bytes) {
+            Ok(bytes) => {
+                let bytes = if handshake_command.is_some() {
+                    Ok(bytes)
+                } else {
+                    self.encryption.encrypt(bytes)
+                };
+                match bytes {
+                    Ok(bytes) => self.send_frame(bytes),
+                    Err(e) => error!(
+                        "Error encrypting message for peer {}: {:?}",
+                        self.remote_addr, e
+                    ),
+                }
+            }
+            Err(e) => error!(
+                "Error compressing message for peer {}: {}",
+                self.remote_addr, e
+            ),
+        }
+        if let Some(command) = handshake_command {
+            self.compression.message_sent(&command);
+        }
+    }
+    /// Method to check whether the hash of the consensus constants sent by the peer in its
+    /// Version message matches the one of this node (peers not sending any hash do not match)
+    fn consensus_constants_match(&self, peer_hash: Option<Hash>) -> bool {
+        self.consensus_constants_hash
+            .map_or(true, |hash| peer_hash == Some(hash))
+    }
+    /// Capabilities to be advertised in the Version message of this node
+    fn capabilities(&self) -> u64 {
+        self.encryption.capabilities() | self.compression.capabilities()
+    }
+    /// Method to send a raw frame to the remote peer
+    fn send_frame(&mut self, bytes: Vec<u8>) {
         // Convert bytes into BytestMut and send them
         self.framed.write(bytes.into());
     }
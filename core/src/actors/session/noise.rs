@@ -0,0 +1,465 @@
+//! # Encrypted transport
+//!
+//! The connections between nodes supporting it (`CAPABILITY_NOISE` in their Version message) are
+//! encrypted and authenticated with the [Noise protocol](https://noiseprotocol.org), using the
+//! `XX` handshake pattern keyed by the static identity keypair of every node.
+//!
+//! The Version/Verack handshake is always performed in plain text. Once the Version and the
+//! Verack messages of the peer have been received, and if both nodes support encryption:
+//!
+//! 1. The outbound side (initiator) sends the first Noise handshake message right after its
+//!    Verack message.
+//! 2. The inbound side (responder) answers with the second one.
+//! 3. The initiator sends the third one and the handshake is finished.
+//!
+//! The Noise handshake messages are sent as raw frames of the P2P codec. From then on, every frame
+//! contains a protocol message encrypted with the transport keys. The session is not consolidated
+//! until the Noise handshake is finished, and no message other than Version and Verack can be
+//! sent while it is in progress, so the peer never receives any other message in plain text.
+//!
+//! The prologue of the Noise handshake is the hash of the Version messages of both nodes (the one
+//! of the initiator first), so the handshake fails if any of them, including their capabilities,
+//! was tampered with on the way. A peer which does not advertise `CAPABILITY_NOISE` is still
+//! served in plain text, as encryption is optional for the nodes.
+use std::sync::Arc;
+
+use serde_derive::{Deserialize, Serialize};
+use snow::{params::NoiseParams, Builder, Session};
+
+use witnet_crypto::hash::calculate_sha256;
+use witnet_data_structures::{
+    builders::{CAPABILITIES, CAPABILITY_NOISE},
+    chain::Hash,
+    types::{Command, Version},
+};
+
+/// Noise protocol used to encrypt the connections
+pub const NOISE_PARAMS: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+
+/// Maximum length of a Noise message, which is also the maximum length of a P2P codec frame
+pub const MAX_NOISE_MESSAGE_LENGTH: usize = 65535;
+
+/// Length of the authentication tag appended to every encrypted message
+pub const TAG_LENGTH: usize = 16;
+
+/// Possible errors of the encrypted transport
+#[derive(Debug)]
+pub enum NoiseError {
+    /// The message is too long to be encrypted in a single frame
+    MessageTooLong(usize),
+    /// A frame was received when no Noise message was expected
+    Unexpected,
+    /// A message was encrypted before the Noise handshake finished
+    HandshakeInProgress,
+    /// The Noise handshake was started before the Version message of this node was sent
+    VersionNotSent,
+    /// Error of the Noise protocol: invalid handshake message, decryption failure, etc.
+    Protocol(snow::SnowError),
+}
+
+impl From<snow::SnowError> for NoiseError {
+    fn from(x: snow::SnowError) -> Self {
+        NoiseError::Protocol(x)
+    }
+}
+
+/// Static keypair identifying the node in the encrypted connections
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeIdentity {
+    /// Private key
+    pub private_key: Vec<u8>,
+    /// Public key
+    pub public_key: Vec<u8>,
+}
+
+impl NodeIdentity {
+    /// Generate a new random identity
+    pub fn generate() -> Result<Self, NoiseError> {
+        let keypair = Builder::new(noise_params()).generate_keypair()?;
+
+        Ok(NodeIdentity {
+            private_key: keypair.private,
+            public_key: keypair.public,
+        })
+    }
+}
+
+/// Noise protocol parameters
+fn noise_params() -> NoiseParams {
+    // The parameters are a valid constant
+    NOISE_PARAMS.parse().unwrap()
+}
+
+/// Hash of the serialized Version message of a node, as included in the prologue of the Noise
+/// handshake
+fn version_hash(bytes: &[u8]) -> [u8; 32] {
+    let Hash::SHA256(hash) = calculate_sha256(bytes);
+
+    hash
+}
+
+/// State of the encryption of a session
+enum State {
+    /// Messages are sent in plain text: the encryption has not been negotiated (yet)
+    Plaintext,
+    /// The Noise handshake is in progress
+    Handshake {
+        /// Noise session in handshake mode
+        noise: Box<Session>,
+        /// Whether the first handshake message still has to be sent (initiator only)
+        pending_initiation: bool,
+    },
+    /// The Noise handshake is finished and every message is encrypted
+    Transport(Box<Session>),
+}
+
+/// Encryption of the connection of a session
+pub struct Encryption {
+    /// Identity of the node, if encryption is enabled
+    identity: Option<Arc<NodeIdentity>>,
+    /// Whether this node initiated the connection
+    initiator: bool,
+    /// Whether the peer supports encryption, once its Version message has been received
+    peer_supports: Option<bool>,
+    /// Hash of the Version message sent by this node
+    local_version: Option<[u8; 32]>,
+    /// Hash of the Version message received from the peer
+    remote_version: Option<[u8; 32]>,
+    /// Whether the Verack message of the peer has been received
+    verack_received: bool,
+    /// Current state
+    state: State,
+}
+
+impl Encryption {
+    /// Create the encryption of a session. If there is no identity, the connection is never
+    /// encrypted.
+    pub fn new(identity: Option<Arc<NodeIdentity>>, initiator: bool) -> Self {
+        Encryption {
+            identity,
+            initiator,
+            peer_supports: None,
+            local_version: None,
+            remote_version: None,
+            verack_received: false,
+            state: State::Plaintext,
+        }
+    }
+
+    /// Capabilities to be advertised in the Version message of this node
+    pub fn capabilities(&self) -> u64 {
+        if self.identity.is_some() {
+            CAPABILITIES | CAPABILITY_NOISE
+        } else {
+            CAPABILITIES
+        }
+    }
+
+    /// Whether the Noise handshake is in progress, so the session cannot be consolidated yet
+    pub fn is_handshaking(&self) -> bool {
+        match self.state {
+            State::Handshake { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the messages are encrypted
+    pub fn is_transport(&self) -> bool {
+        match self.state {
+            State::Transport(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Public key of the peer, once the Noise handshake is finished
+    pub fn remote_public_key(&self) -> Option<&[u8]> {
+        match &self.state {
+            State::Transport(noise) => noise.get_remote_static(),
+            _ => None,
+        }
+    }
+
+    /// Keep track of the Version message sent by this node, given along with its serialized
+    /// bytes, which are part of the prologue of the Noise handshake
+    pub fn message_sent(&mut self, command: &Command, bytes: &[u8]) {
+        if let Command::Version(_) = command {
+            self.local_version = Some(version_hash(bytes));
+        }
+    }
+
+    /// Keep track of the plain text messages received from the peer, given along with their
+    /// serialized bytes, and start the Noise handshake once both its Version and Verack messages
+    /// have been received, if both nodes support encryption
+    pub fn message_received(&mut self, command: &Command, bytes: &[u8]) -> Result<(), NoiseError> {
+        match command {
+            Command::Version(Version { capabilities, .. }) => {
+                self.peer_supports = Some(capabilities & CAPABILITY_NOISE != 0);
+                self.remote_version = Some(version_hash(bytes));
+            }
+            Command::Verack(_) => self.verack_received = true,
+            _ => return Ok(()),
+        }
+
+        let identity = match &self.identity {
+            Some(identity) => identity,
+            None => return Ok(()),
+        };
+        if let (State::Plaintext, Some(true), true) =
+            (&self.state, self.peer_supports, self.verack_received)
+        {
+            let (local_version, remote_version) = match (self.local_version, self.remote_version) {
+                (Some(local_version), Some(remote_version)) => (local_version, remote_version),
+                _ => return Err(NoiseError::VersionNotSent),
+            };
+            // Both nodes must use the same prologue, so the Version of the initiator goes first
+            let prologue = if self.initiator {
+                [local_version, remote_version].concat()
+            } else {
+                [remote_version, local_version].concat()
+            };
+            let builder = Builder::new(noise_params())
+                .local_private_key(&identity.private_key)
+                .prologue(&prologue);
+            let noise = if self.initiator {
+                builder.build_initiator()?
+            } else {
+                builder.build_responder()?
+            };
+            self.state = State::Handshake {
+                noise: Box::new(noise),
+                pending_initiation: self.initiator,
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Get the first Noise handshake message, if this node is the initiator and it has not been
+    /// sent yet. It must be sent right after the Verack message.
+    pub fn initiate(&mut self) -> Result<Option<Vec<u8>>, NoiseError> {
+        match &mut self.state {
+            State::Handshake {
+                noise,
+                pending_initiation,
+            } if *pending_initiation => {
+                *pending_initiation = false;
+                let mut message = vec![0; MAX_NOISE_MESSAGE_LENGTH];
+                let len = noise.write_message(&[], &mut message)?;
+                message.truncate(len);
+
+                Ok(Some(message))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Process a Noise handshake message received from the peer, returning the handshake message
+    /// to be sent in response, if any
+    pub fn handshake_message_received(
+        &mut self,
+        message: &[u8],
+    ) -> Result<Option<Vec<u8>>, NoiseError> {
+        let noise = match &mut self.state {
+            State::Handshake { noise, .. } => noise,
+            _ => return Err(NoiseError::Unexpected),
+        };
+
+        let mut payload = vec![0; MAX_NOISE_MESSAGE_LENGTH];
+        noise.read_message(message, &mut payload)?;
+
+        let response = if noise.is_handshake_finished() {
+            None
+        } else {
+            let mut response = vec![0; MAX_NOISE_MESSAGE_LENGTH];
+            let len = noise.write_message(&[], &mut response)?;
+            response.truncate(len);
+
+            Some(response)
+        };
+
+        if noise.is_handshake_finished() {
+            if let State::Handshake { noise, .. } =
+                std::mem::replace(&mut self.state, State::Plaintext)
+            {
+                self.state = State::Transport(Box::new(noise.into_transport_mode()?));
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Encrypt a message to be sent to the peer, if the Noise handshake is finished. No message
+    /// can be encrypted while it is in progress: only the Version and Verack messages, which are
+    /// never encrypted, can be sent in the meantime.
+    pub fn encrypt(&mut self, message: Vec<u8>) -> Result<Vec<u8>, NoiseError> {
+        match &mut self.state {
+            State::Transport(noise) => {
+                if message.len() + TAG_LENGTH > MAX_NOISE_MESSAGE_LENGTH {
+                    return Err(NoiseError::MessageTooLong(message.len()));
+                }
+                let mut encrypted = vec![0; message.len() + TAG_LENGTH];
+                let len = noise.write_message(&message, &mut encrypted)?;
+                encrypted.truncate(len);
+
+                Ok(encrypted)
+            }
+            State::Handshake { .. } => Err(NoiseError::HandshakeInProgress),
+            State::Plaintext => Ok(message),
+        }
+    }
+
+    /// Decrypt a message received from the peer, if the Noise handshake is finished
+    pub fn decrypt(&mut self, message: Vec<u8>) -> Result<Vec<u8>, NoiseError> {
+        match &mut self.state {
+            State::Transport(noise) => {
+                let mut decrypted = vec![0; message.len()];
+                let len = noise.read_message(&message, &mut decrypted)?;
+                decrypted.truncate(len);
+
+                Ok(decrypted)
+            }
+            _ => Ok(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use witnet_data_structures::{builders::USER_AGENT, types::*};
+
+    fn version(capabilities: u64) -> Command {
+        let address = Address {
+            ip: IpAddress::Ipv4 { ip: 0 },
+            port: 0,
+        };
+
+        Command::Version(Version {
+            version: 0,
+            timestamp: 0,
+            capabilities,
+            sender_address: address.clone(),
+            receiver_address: address,
+            user_agent: USER_AGENT.to_string(),
+            last_epoch: 0,
+            genesis: 0,
+            nonce: 0,
+            consensus_constants_hash: None,
+        })
+    }
+
+    fn encryption(initiator: bool) -> Encryption {
+        Encryption::new(Some(Arc::new(NodeIdentity::generate().unwrap())), initiator)
+    }
+
+    /// Exchange the Version and Verack messages of two nodes, where the Version of the outbound
+    /// node is received by the inbound node as `received_version`
+    fn exchange_versions(
+        outbound: &mut Encryption,
+        inbound: &mut Encryption,
+        received_version: &[u8],
+    ) {
+        let outbound_version = version(outbound.capabilities());
+        let inbound_version = version(inbound.capabilities());
+        outbound.message_sent(&outbound_version, b"outbound version");
+
+        // Inbound side receives Version and sends Verack and Version
+        inbound
+            .message_received(&outbound_version, received_version)
+            .unwrap();
+        assert!(!inbound.is_handshaking());
+        inbound.message_sent(&inbound_version, b"inbound version");
+        // Outbound side receives Verack and Version, and sends Verack
+        outbound
+            .message_received(&Command::Verack(Verack), &[])
+            .unwrap();
+        outbound
+            .message_received(&inbound_version, b"inbound version")
+            .unwrap();
+        assert!(outbound.is_handshaking());
+        // Inbound side receives Verack
+        inbound
+            .message_received(&Command::Verack(Verack), &[])
+            .unwrap();
+        assert!(inbound.is_handshaking());
+    }
+
+    #[test]
+    fn noise_handshake_and_transport() {
+        let mut outbound = encryption(true);
+        let mut inbound = encryption(false);
+        exchange_versions(&mut outbound, &mut inbound, b"outbound version");
+
+        // No message can be encrypted until the handshake is finished
+        assert!(outbound.encrypt(vec![1, 2, 3]).is_err());
+
+        // Only the initiator sends the first message, and only once
+        assert_eq!(inbound.initiate().unwrap(), None);
+        let first = outbound.initiate().unwrap().unwrap();
+        assert_eq!(outbound.initiate().unwrap(), None);
+
+        let second = inbound.handshake_message_received(&first).unwrap().unwrap();
+        let third = outbound
+            .handshake_message_received(&second)
+            .unwrap()
+            .unwrap();
+        assert!(outbound.is_transport());
+        assert_eq!(inbound.handshake_message_received(&third).unwrap(), None);
+        assert!(inbound.is_transport());
+
+        // Both sides know the identity of the other one
+        assert_eq!(
+            outbound.remote_public_key(),
+            Some(&inbound.identity.as_ref().unwrap().public_key[..])
+        );
+
+        let encrypted = outbound.encrypt(b"witnet".to_vec()).unwrap();
+        assert_ne!(&encrypted[..6], b"witnet");
+        assert_eq!(inbound.decrypt(encrypted).unwrap(), b"witnet".to_vec());
+
+        // Tampered messages are rejected
+        let mut encrypted = inbound.encrypt(b"witnet".to_vec()).unwrap();
+        encrypted[0] ^= 1;
+        assert!(outbound.decrypt(encrypted).is_err());
+    }
+
+    #[test]
+    fn noise_handshake_bound_to_versions() {
+        // The Version message of the outbound side was tampered with on the way
+        let mut outbound = encryption(true);
+        let mut inbound = encryption(false);
+        exchange_versions(&mut outbound, &mut inbound, b"tampered version");
+
+        let first = outbound.initiate().unwrap().unwrap();
+        let second = inbound.handshake_message_received(&first).unwrap().unwrap();
+        assert!(outbound.handshake_message_received(&second).is_err());
+    }
+
+    #[test]
+    fn noise_not_negotiated() {
+        // The peer does not support encryption
+        let mut outbound = encryption(true);
+        outbound.message_sent(&version(outbound.capabilities()), &[]);
+        outbound
+            .message_received(&Command::Verack(Verack), &[])
+            .unwrap();
+        outbound
+            .message_received(&version(CAPABILITIES), &[])
+            .unwrap();
+        assert!(!outbound.is_handshaking());
+        assert_eq!(outbound.initiate().unwrap(), None);
+        assert_eq!(outbound.encrypt(vec![1, 2, 3]).unwrap(), vec![1, 2, 3]);
+
+        // This node does not support encryption
+        let mut outbound = Encryption::new(None, true);
+        assert_eq!(outbound.capabilities(), CAPABILITIES);
+        outbound
+            .message_received(&Command::Verack(Verack), &[])
+            .unwrap();
+        outbound
+            .message_received(&version(CAPABILITIES | CAPABILITY_NOISE), &[])
+            .unwrap();
+        assert!(!outbound.is_handshaking());
+        assert!(outbound.handshake_message_received(&[]).is_err());
+    }
+}
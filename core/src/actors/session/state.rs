@@ -0,0 +1,275 @@
+//! # Session state machine
+//!
+//! A session goes through these states:
+//!
+//! `Connecting` → `Handshaking` → `Consolidated` → `Closing`
+//!
+//! Every state only accepts its own set of messages from the peer: the
+//! handshake messages (`Version` and `Verack`) while handshaking, and
+//! every other message once consolidated. Any other message, as well as
+//! a repeated handshake message, is an illegal transition and the peer
+//! is penalized for it.
+use std::fmt;
+use std::net::SocketAddr;
+
+use witnet_data_structures::types::Command;
+use witnet_p2p::sessions::SessionStatus;
+
+/// Progress of the handshake with the peer
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Handshake {
+    /// Whether the Version message of this node has been sent
+    version_sent: bool,
+    /// Sender address announced by the peer in its Version message, once received
+    /// (the Verack message is sent as soon as it is received)
+    peer_sender_addr: Option<SocketAddr>,
+    /// Whether the Verack message of the peer has been received
+    verack_received: bool,
+}
+
+impl Handshake {
+    /// Get the sender address of the peer, as long as the handshake has been completed
+    fn completed(&self) -> Option<SocketAddr> {
+        if self.version_sent && self.verack_received {
+            self.peer_sender_addr
+        } else {
+            None
+        }
+    }
+}
+
+/// State of a session
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionState {
+    /// The session is being registered into the SessionsManager
+    Connecting,
+    /// The handshake with the peer is in progress
+    Handshaking(Handshake),
+    /// The handshake has been completed and the session is consolidated
+    Consolidated,
+    /// The session is being stopped
+    Closing,
+}
+
+/// Illegal transition of the state of a session
+#[derive(Clone, Debug, PartialEq)]
+pub struct IllegalTransition {
+    /// State of the session
+    pub state: &'static str,
+    /// Event which is not legal in that state
+    pub event: String,
+}
+
+impl fmt::Display for IllegalTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not legal in state {}", self.event, self.state)
+    }
+}
+
+/// Result of a transition of the state of a session
+pub type TransitionResult<T> = Result<T, IllegalTransition>;
+
+impl Default for SessionState {
+    fn default() -> Self {
+        SessionState::Connecting
+    }
+}
+
+impl SessionState {
+    /// Name of the state
+    pub fn name(&self) -> &'static str {
+        match self {
+            SessionState::Connecting => "Connecting",
+            SessionState::Handshaking(_) => "Handshaking",
+            SessionState::Consolidated => "Consolidated",
+            SessionState::Closing => "Closing",
+        }
+    }
+
+    /// Status of the session, as known by the SessionsManager
+    pub fn status(&self) -> SessionStatus {
+        match self {
+            SessionState::Consolidated => SessionStatus::Consolidated,
+            _ => SessionStatus::Unconsolidated,
+        }
+    }
+
+    /// Whether the session is consolidated
+    pub fn is_consolidated(&self) -> bool {
+        *self == SessionState::Consolidated
+    }
+
+    /// Whether a message from the peer is legal in the current state
+    pub fn accepts(&self, command: &Command) -> bool {
+        let handshake_message = match command {
+            Command::Version(_) | Command::Verack(_) => true,
+            _ => false,
+        };
+
+        match self {
+            SessionState::Handshaking(_) => handshake_message,
+            SessionState::Consolidated => !handshake_message,
+            SessionState::Connecting | SessionState::Closing => false,
+        }
+    }
+
+    /// Connecting → Handshaking, once the session has been registered
+    pub fn start_handshake(&mut self) -> TransitionResult<()> {
+        match self {
+            SessionState::Connecting => {
+                *self = SessionState::Handshaking(Handshake::default());
+
+                Ok(())
+            }
+            _ => Err(self.illegal("start handshake")),
+        }
+    }
+
+    /// Record that the Version message of this node has been sent
+    pub fn version_sent(&mut self) -> TransitionResult<()> {
+        if let SessionState::Handshaking(ref mut handshake) = self {
+            if !handshake.version_sent {
+                handshake.version_sent = true;
+
+                return Ok(());
+            }
+        }
+
+        Err(self.illegal("Version sent"))
+    }
+
+    /// Record the Version message of the peer
+    /// Returns whether the Version message of this node has to be sent as well (the Verack
+    /// message is always sent in response)
+    pub fn version_received(&mut self, sender_addr: SocketAddr) -> TransitionResult<bool> {
+        if let SessionState::Handshaking(ref mut handshake) = self {
+            if handshake.peer_sender_addr.is_none() {
+                handshake.peer_sender_addr = Some(sender_addr);
+
+                return Ok(!handshake.version_sent);
+            }
+        }
+
+        Err(self.illegal("Version received"))
+    }
+
+    /// Record the Verack message of the peer
+    pub fn verack_received(&mut self) -> TransitionResult<()> {
+        if let SessionState::Handshaking(ref mut handshake) = self {
+            if !handshake.verack_received {
+                handshake.verack_received = true;
+
+                return Ok(());
+            }
+        }
+
+        Err(self.illegal("Verack received"))
+    }
+
+    /// Get the sender address of the peer if the handshake has been completed, so the session
+    /// can be consolidated
+    pub fn handshake_completed(&self) -> Option<SocketAddr> {
+        match self {
+            SessionState::Handshaking(handshake) => handshake.completed(),
+            _ => None,
+        }
+    }
+
+    /// Handshaking → Consolidated, once the handshake has been completed
+    pub fn consolidate(&mut self) -> TransitionResult<()> {
+        if self.handshake_completed().is_some() {
+            *self = SessionState::Consolidated;
+
+            Ok(())
+        } else {
+            Err(self.illegal("consolidate"))
+        }
+    }
+
+    /// Any state → Closing
+    pub fn close(&mut self) {
+        *self = SessionState::Closing;
+    }
+
+    /// Build the error for an illegal transition from the current state
+    fn illegal(&self, event: &str) -> IllegalTransition {
+        IllegalTransition {
+            state: self.name(),
+            event: event.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use witnet_data_structures::types::{GetPeers, Verack};
+
+    fn addr() -> SocketAddr {
+        "127.0.0.1:21337".parse().unwrap()
+    }
+
+    #[test]
+    fn outbound_handshake() {
+        let mut state = SessionState::default();
+        assert!(!state.accepts(&Command::Verack(Verack)));
+
+        state.start_handshake().unwrap();
+        state.version_sent().unwrap();
+        assert!(state.accepts(&Command::Verack(Verack)));
+        assert!(!state.accepts(&Command::GetPeers(GetPeers)));
+
+        state.verack_received().unwrap();
+        assert_eq!(state.handshake_completed(), None);
+        // The Version message of this node has already been sent
+        assert_eq!(state.version_received(addr()), Ok(false));
+        assert_eq!(state.handshake_completed(), Some(addr()));
+
+        state.consolidate().unwrap();
+        assert_eq!(state.status(), SessionStatus::Consolidated);
+        assert!(state.accepts(&Command::GetPeers(GetPeers)));
+        assert!(!state.accepts(&Command::Verack(Verack)));
+
+        state.close();
+        assert_eq!(state.status(), SessionStatus::Unconsolidated);
+        assert!(!state.accepts(&Command::GetPeers(GetPeers)));
+    }
+
+    #[test]
+    fn inbound_handshake() {
+        let mut state = SessionState::default();
+        state.start_handshake().unwrap();
+
+        // The Version message of this node is sent in response
+        assert_eq!(state.version_received(addr()), Ok(true));
+        state.version_sent().unwrap();
+        assert_eq!(state.handshake_completed(), None);
+        state.verack_received().unwrap();
+
+        state.consolidate().unwrap();
+        assert!(state.is_consolidated());
+    }
+
+    #[test]
+    fn illegal_transitions() {
+        let mut state = SessionState::default();
+        assert!(state.version_received(addr()).is_err());
+        assert!(state.consolidate().is_err());
+
+        state.start_handshake().unwrap();
+        assert!(state.start_handshake().is_err());
+        // Handshake messages cannot be repeated
+        state.verack_received().unwrap();
+        assert_eq!(
+            state.verack_received(),
+            Err(IllegalTransition {
+                state: "Handshaking",
+                event: "Verack received".to_string(),
+            })
+        );
+        state.version_received(addr()).unwrap();
+        assert!(state.version_received(addr()).is_err());
+        // The handshake is not completed until the Version message of this node is sent
+        assert!(state.consolidate().is_err());
+    }
+}
@@ -1,7 +1,21 @@
-use actix::{Actor, Context};
-use log::debug;
+use std::sync::Arc;
 
-use crate::actors::config_manager::send_get_config_request;
+use actix::{Actor, ActorFuture, Context, ContextFutureSpawner, System, WrapFuture};
+use log::{debug, error, info};
+
+use crate::actors::{
+    config_manager::{consensus_constants_hash, send_get_config_request},
+    node,
+    session::{addresses::AddressIdentity, noise::NodeIdentity},
+    storage_keys::{ADDRESS_IDENTITY_KEY, NODE_IDENTITY_KEY},
+    storage_manager::{
+        messages::{Get, Put},
+        put_with_retry, StorageManager,
+    },
+    supervision::RestartPolicy,
+};
+use crate::errors::{NodeError, STORAGE_RETRY_POLICY};
+use crate::watchdog::Watchdog;
 
 use super::SessionsManager;
 
@@ -30,11 +44,192 @@ impl Actor for SessionsManager {
             act.sessions
                 .set_handshake_timeout(config.connections.handshake_timeout);
 
+            // Set the mailbox capacity of the sessions to be created
+            act.session_mailbox_capacity = Some(config.mailboxes.session);
+
+            // Set the announcement debounce window of the sessions to be created
+            act.announcement_debounce = config.connections.announcement_debounce;
+
+            // Set the policy bounding the restarts of the actor
+            act.restarts.set_policy(RestartPolicy::from_config(config));
+
+            // Set the compression threshold of the sessions to be created
+            if config.connections.compression {
+                act.transport.compression_threshold =
+                    Some(config.connections.compression_threshold);
+            }
+
+            // Set the time budgets of the handlers of the sessions to be created
+            act.transport.watchdog = Watchdog::new(&config.watchdog);
+
+            // Set the hash of the consensus constants exchanged in the handshake of the sessions
+            act.transport.consensus_constants_hash =
+                Some(consensus_constants_hash(&config.consensus_constants));
+
+            // Restore the identity of the node if the connections are to be encrypted
+            if config.connections.encryption {
+                restore_identity(act, ctx);
+            }
+
+            // Restore the identity signing the addresses advertised by the node
+            restore_address_identity(act, ctx);
+
             // The peers bootstrapping process begins upon SessionsManager's start
             act.bootstrap_peers(ctx, bootstrap_peers_period);
 
             // The peers discovery process begins upon SessionsManager's start
             act.discovery_peers(ctx, discovery_peers_period);
+
+            // The requested items begin to be checked upon SessionsManager's start
+            act.check_requests(ctx);
         });
     }
 }
+
+/// Restore the identity of the node, or create it the first time the node is started
+fn restore_identity(act: &mut SessionsManager, ctx: &mut Context<SessionsManager>) {
+    let storage_manager_addr = System::current().registry().get::<StorageManager>();
+    storage_manager_addr
+        .send(Get::<NodeIdentity>::new(NODE_IDENTITY_KEY))
+        .into_actor(act)
+        .then(|res, act, ctx| {
+            match res {
+                Ok(Ok(Some(identity))) => {
+                    info!("Node identity successfully obtained from storage");
+                    act.transport.identity = Some(Arc::new(identity));
+                }
+                Ok(Ok(None)) => {
+                    info!("No node identity in storage, creating a new one");
+                    create_identity(act, ctx);
+                }
+                // Never create a new identity if the storage could not be read, as it would
+                // replace the existing one, and never go on without it either, as the
+                // connections would not be encrypted: the node is stopped instead
+                Ok(Err(e)) => {
+                    error!("Error while getting node identity from storage: {}", e);
+                    node::escalate(&NodeError::Initialization(format!(
+                        "the node identity could not be read: {}",
+                        e
+                    )));
+                }
+                Err(e) => {
+                    error!("Unsuccessful communication with storage manager: {}", e);
+                    node::escalate(&NodeError::Initialization(format!(
+                        "the node identity could not be read: {}",
+                        e
+                    )));
+                }
+            }
+
+            actix::fut::ok(())
+        })
+        // Do not create any session until the identity is known
+        .wait(ctx);
+}
+
+/// Create a new identity for the node and persist it into storage
+fn create_identity(act: &mut SessionsManager, ctx: &mut Context<SessionsManager>) {
+    let identity = match NodeIdentity::generate() {
+        Ok(identity) => identity,
+        Err(e) => {
+            error!("Error generating node identity: {:?}", e);
+            node::escalate(&NodeError::Initialization(format!(
+                "the node identity could not be generated: {:?}",
+                e
+            )));
+            return;
+        }
+    };
+    let msg = match Put::from_value(NODE_IDENTITY_KEY, &identity) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Error when serializing the node identity: {}", e);
+            node::escalate(&NodeError::from(e));
+            return;
+        }
+    };
+    act.transport.identity = Some(Arc::new(identity));
+
+    put_with_retry(msg, STORAGE_RETRY_POLICY)
+        .into_actor(act)
+        .then(|res, _act, _ctx| {
+            match res {
+                Ok(()) => info!("SessionsManager successfully persisted the node identity"),
+                Err(e) => {
+                    error!(
+                        "SessionsManager failed to persist the node identity into storage: {}",
+                        e
+                    );
+                    node::escalate(&e);
+                }
+            }
+
+            actix::fut::ok(())
+        })
+        .spawn(ctx);
+}
+
+/// Restore the address identity of the node, or create it the first time the node is started
+fn restore_address_identity(act: &mut SessionsManager, ctx: &mut Context<SessionsManager>) {
+    let storage_manager_addr = System::current().registry().get::<StorageManager>();
+    storage_manager_addr
+        .send(Get::<AddressIdentity>::new(ADDRESS_IDENTITY_KEY))
+        .into_actor(act)
+        .then(|res, act, ctx| {
+            match res {
+                Ok(Ok(Some(identity))) => match identity.secret_key() {
+                    Some(secret_key) => {
+                        info!("Address identity successfully obtained from storage");
+                        act.transport.address_signatures.set_secret_key(secret_key);
+                    }
+                    None => error!("Invalid address identity in storage"),
+                },
+                Ok(Ok(None)) => {
+                    info!("No address identity in storage, creating a new one");
+                    create_address_identity(act, ctx);
+                }
+                // Never create a new identity if the storage could not be read, as it would
+                // replace the existing one
+                Ok(Err(e)) => error!("Error while getting address identity from storage: {}", e),
+                Err(e) => error!("Unsuccessful communication with storage manager: {}", e),
+            }
+
+            actix::fut::ok(())
+        })
+        // Do not create any session until the identity is known
+        .wait(ctx);
+}
+
+/// Create a new address identity for the node and persist it into storage
+fn create_address_identity(act: &mut SessionsManager, ctx: &mut Context<SessionsManager>) {
+    let identity = AddressIdentity::generate();
+    let msg = match Put::from_value(ADDRESS_IDENTITY_KEY, &identity) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Error when serializing the address identity: {}", e);
+            node::escalate(&NodeError::from(e));
+            return;
+        }
+    };
+    if let Some(secret_key) = identity.secret_key() {
+        act.transport.address_signatures.set_secret_key(secret_key);
+    }
+
+    put_with_retry(msg, STORAGE_RETRY_POLICY)
+        .into_actor(act)
+        .then(|res, _act, _ctx| {
+            match res {
+                Ok(()) => info!("SessionsManager successfully persisted the address identity"),
+                Err(e) => {
+                    error!(
+                        "SessionsManager failed to persist the address identity into storage: {}",
+                        e
+                    );
+                    node::escalate(&e);
+                }
+            }
+
+            actix::fut::ok(())
+        })
+        .spawn(ctx);
+}
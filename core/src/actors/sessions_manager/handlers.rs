@@ -1,20 +1,35 @@
-use std::marker::Send;
+use std::{marker::Send, time::Instant};
 
 use actix::{
-    io::FramedWrite, Actor, ActorFuture, Context, ContextFutureSpawner, Handler, Message,
-    StreamHandler, System, WrapFuture,
+    io::FramedWrite, prelude::SendError, Actor, Context, Handler, Message, StreamHandler, System,
 };
 use log::{debug, info, warn};
 use tokio::{codec::FramedRead, io::AsyncRead};
 
-use crate::actors::{
-    codec::P2PCodec,
-    peers_manager::{messages::AddPeers, PeersManager},
-    session::Session,
+use crate::{
+    actors::{
+        codec::P2PCodec,
+        peers_manager::{
+            messages::{AddPeers, RecordConnectionAttempt},
+            PeersManager,
+        },
+        session::Session,
+    },
+    metrics,
+};
+
+use witnet_p2p::{
+    peers::{normalize_address, AttemptOutcome},
+    sessions::SessionType,
 };
 
 use super::{
-    messages::{Anycast, Broadcast, Consolidate, Create, Register, SessionsUnitResult, Unregister},
+    messages::{
+        Anycast, Broadcast, Consolidate, Create, GetNumOutboundConsolidated, IsIdle,
+        RecordPeerLatency, RecordPeerThroughput, Register, ReportExternalAddress, RequestDelivered,
+        SessionEvent, SessionsUnitResult, SetExternalAddress, SetIsolation, SubscribeSessionEvents,
+        TrackRequests, Unregister,
+    },
     SessionsManager,
 };
 
@@ -26,16 +41,39 @@ impl Handler<Create> for SessionsManager {
         // Get handshake timeout
         let handshake_timeout = self.sessions.handshake_timeout;
 
-        // Get server address
-        let server_addr = self.sessions.server_address;
+        // Get the address to be advertised to the peer
+        let server_addr = self.sessions.advertised_address();
+
+        // Get announcement debounce window
+        let announcement_debounce = self.announcement_debounce;
+
+        // Get session mailbox capacity
+        let mailbox_capacity = self.session_mailbox_capacity;
+
+        // Get transport settings for the encryption and compression of the connection
+        let transport = self.transport.clone();
+
+        // Get the addresses of the connection, which cannot be read if it has already been closed
+        let (local_addr, peer_addr) = match (msg.stream.local_addr(), msg.stream.peer_addr()) {
+            (Ok(local_addr), Ok(peer_addr)) => (local_addr, peer_addr),
+            (Err(e), _) | (_, Err(e)) => {
+                warn!("Dropped a connection with unknown addresses: {}", e);
+                return;
+            }
+        };
 
         // Create a Session actor
         Session::create(move |ctx| {
+            // Bound the number of queued messages so broadcasts can be shed when overwhelmed
+            if let Some(capacity) = mailbox_capacity {
+                ctx.set_mailbox_capacity(capacity);
+            }
+
             // Get server address (if not present, send local address instead)
-            let server_addr = server_addr.unwrap_or_else(|| msg.stream.local_addr().unwrap());
+            let server_addr = server_addr.unwrap_or(local_addr);
 
-            // Get remote peer address
-            let remote_addr = msg.stream.peer_addr().unwrap();
+            // Get remote peer address (IPv4-mapped IPv6 addresses are stored as IPv4 addresses)
+            let remote_addr = normalize_address(peer_addr);
 
             // Split TCP stream into read and write parts
             let (r, w) = msg.stream.split();
@@ -50,6 +88,8 @@ impl Handler<Create> for SessionsManager {
                 msg.session_type,
                 FramedWrite::new(w, P2PCodec, ctx),
                 handshake_timeout,
+                announcement_debounce,
+                transport,
             )
         });
     }
@@ -66,10 +106,16 @@ impl Handler<Register> for SessionsManager {
             .register_session(msg.session_type, msg.address, msg.actor);
 
         match &result {
-            Ok(_) => info!(
-                "Session (type {:?}) registered for peer {}",
-                msg.session_type, msg.address
-            ),
+            Ok(_) => {
+                info!(
+                    "Session (type {:?}) registered for peer {}",
+                    msg.session_type, msg.address
+                );
+                self.publish_event(SessionEvent::Registered {
+                    address: msg.address,
+                    session_type: msg.session_type,
+                });
+            }
             Err(error) => warn!(
                 "Error while registering peer {} (session type {:?}): {}",
                 msg.address, msg.session_type, error
@@ -91,10 +137,18 @@ impl Handler<Unregister> for SessionsManager {
             .unregister_session(msg.session_type, msg.status, msg.address);
 
         match &result {
-            Ok(_) => info!(
-                "Session (type {:?}) unregistered for peer {}",
-                msg.session_type, msg.address
-            ),
+            Ok(_) => {
+                info!(
+                    "Session (type {:?}) unregistered for peer {}",
+                    msg.session_type, msg.address
+                );
+                self.requests.remove_peer(msg.address);
+                self.publish_event(SessionEvent::Unregistered {
+                    address: msg.address,
+                    session_type: msg.session_type,
+                    status: msg.status,
+                });
+            }
             Err(error) => warn!(
                 "Error while unregistering peer {} (session type {:?}): {}",
                 msg.address, msg.session_type, error
@@ -127,10 +181,23 @@ impl Handler<Consolidate> for SessionsManager {
         });
 
         match &result {
-            Ok(_) => info!(
-                "Session (type {:?}) status consolidated for peer {}",
-                msg.session_type, msg.address
-            ),
+            Ok(_) => {
+                info!(
+                    "Session (type {:?}) status consolidated for peer {}",
+                    msg.session_type, msg.address
+                );
+                // The attempt to connect to the peer succeeded
+                if let SessionType::Outbound = msg.session_type {
+                    peers_manager_addr.do_send(RecordConnectionAttempt {
+                        address: msg.address,
+                        outcome: AttemptOutcome::Success,
+                    });
+                }
+                self.publish_event(SessionEvent::Consolidated {
+                    address: msg.address,
+                    session_type: msg.session_type,
+                });
+            }
             Err(error) => warn!(
                 "Error while consolidating peer {} (session type {:?}): {}",
                 msg.address, msg.session_type, error
@@ -141,40 +208,156 @@ impl Handler<Consolidate> for SessionsManager {
     }
 }
 
+/// Handler for SubscribeSessionEvents message
+impl Handler<SubscribeSessionEvents> for SessionsManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: SubscribeSessionEvents, _: &mut Context<Self>) {
+        debug!("New subscription to session lifecycle events");
+        self.subscribers.push(msg.recipient);
+    }
+}
+
+/// Handler for SetIsolation message
+impl Handler<SetIsolation> for SessionsManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetIsolation, ctx: &mut Context<Self>) {
+        let was_isolated = self.isolated;
+        self.isolated = msg.isolated;
+        if msg.isolated && !was_isolated {
+            info!("The node is isolated, bootstrapping outbound sessions aggressively");
+            self.rebootstrap(ctx);
+        }
+    }
+}
+
+/// Handler for GetNumOutboundConsolidated message
+impl Handler<GetNumOutboundConsolidated> for SessionsManager {
+    type Result = usize;
+
+    fn handle(&mut self, _msg: GetNumOutboundConsolidated, _: &mut Context<Self>) -> usize {
+        self.sessions.get_num_outbound_consolidated_sessions()
+    }
+}
+
+/// Handler for IsIdle message
+impl Handler<IsIdle> for SessionsManager {
+    type Result = bool;
+
+    fn handle(&mut self, msg: IsIdle, _: &mut Context<Self>) -> bool {
+        self.requests.is_empty()
+            && self
+                .last_activity
+                .map_or(true, |last_activity| last_activity.elapsed() >= msg.period)
+    }
+}
+
+/// Handler for RecordPeerLatency message
+impl Handler<RecordPeerLatency> for SessionsManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: RecordPeerLatency, _: &mut Context<Self>) {
+        if !self.sessions.record_latency(msg.address, msg.latency) {
+            debug!("Latency reported for unknown session {}", msg.address);
+        }
+    }
+}
+
+/// Handler for RecordPeerThroughput message
+impl Handler<RecordPeerThroughput> for SessionsManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: RecordPeerThroughput, _: &mut Context<Self>) {
+        if !self
+            .sessions
+            .record_throughput(msg.address, msg.bytes, msg.elapsed)
+        {
+            debug!("Throughput reported for unknown session {}", msg.address);
+        }
+    }
+}
+
+/// Handler for TrackRequests message
+impl Handler<TrackRequests> for SessionsManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: TrackRequests, _: &mut Context<Self>) {
+        self.last_activity = Some(Instant::now());
+        let tracked = self
+            .requests
+            .request(msg.address, msg.items, Instant::now());
+        debug!(
+            "Tracking {} items requested to peer {}",
+            tracked, msg.address
+        );
+    }
+}
+
+/// Handler for RequestDelivered message
+impl Handler<RequestDelivered> for SessionsManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: RequestDelivered, _: &mut Context<Self>) {
+        self.last_activity = Some(Instant::now());
+        if self.requests.deliver(msg.address, &msg.item) {
+            debug!(
+                "Requested item {:?} delivered by peer {}",
+                msg.item, msg.address
+            );
+        }
+    }
+}
+
+/// Handler for SetExternalAddress message
+impl Handler<SetExternalAddress> for SessionsManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetExternalAddress, _: &mut Context<Self>) {
+        info!(
+            "Advertising external address {} to new sessions",
+            msg.address
+        );
+        self.sessions.set_external_address(msg.address);
+    }
+}
+
+/// Handler for ReportExternalAddress message
+impl Handler<ReportExternalAddress> for SessionsManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: ReportExternalAddress, _: &mut Context<Self>) {
+        debug!(
+            "Peer {} reports {} as the external address of this node",
+            msg.reporter, msg.address
+        );
+        if self
+            .sessions
+            .record_external_address(msg.reporter, msg.address)
+        {
+            if let Some(address) = self.sessions.advertised_address() {
+                info!("Advertising address {} to new sessions", address);
+            }
+        }
+    }
+}
+
 /// Handler for Anycast message
 impl<T: 'static> Handler<Anycast<T>> for SessionsManager
 where
-    T: Message + Send,
+    T: Clone + Message + Send,
     T::Result: Send,
     Session: Handler<T>,
 {
     type Result = ();
 
     fn handle(&mut self, msg: Anycast<T>, ctx: &mut Context<Self>) {
-        debug!("Received a message to send to a random session");
+        debug!("Received a message to send to the best ranked session");
+        self.last_activity = Some(Instant::now());
 
-        // Request a random consolidated outbound session
-        self.sessions
-            .get_random_anycast_session()
-            .map(|session_addr| {
-                // Send message to session and await for response
-                session_addr
-                    // Send SendMessage message to session actor
-                    // This returns a Request Future, representing an asynchronous message sending process
-                    .send(msg.command)
-                    // Convert a normal future into an ActorFuture
-                    .into_actor(self)
-                    // Process the response from the session
-                    // This returns a FutureResult containing the socket address if present
-                    .then(|res, act, _ctx| {
-                        // Process the response from session
-                        act.process_command_response(&res)
-                    })
-                    .wait(ctx);
-            })
-            .unwrap_or_else(|| {
-                warn!("No consolidated outbound session was found");
-            });
+        // Request the consolidated outbound sessions, best ranked first
+        let sessions = self.sessions.get_ranked_consolidated_outbound_sessions();
+        self.anycast_ranked(ctx, msg.command, sessions);
     }
 }
 
@@ -189,12 +372,19 @@ where
 
     fn handle(&mut self, msg: Broadcast<T>, _ctx: &mut Context<Self>) {
         debug!("Received a message to send to all the sessions");
+        self.last_activity = Some(Instant::now());
 
+        // Best ranked sessions first, so they are the first ones to ask for work (e.g. the block
+        // ranges to be downloaded)
         self.sessions
-            .get_all_consolidated_outbound_sessions()
+            .get_ranked_consolidated_outbound_sessions()
+            .iter()
             .for_each(|session_addr| {
-                // Send message to session and ignore errors
-                session_addr.do_send(msg.command.clone());
+                // Send message to session, shedding it if the session is overwhelmed
+                if let Err(SendError::Full(_)) = session_addr.try_send(msg.command.clone()) {
+                    debug!("Broadcast message dropped (session mailbox full)");
+                    metrics::record_session_mailbox_full();
+                }
             });
     }
 }
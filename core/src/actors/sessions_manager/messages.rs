@@ -1,8 +1,13 @@
-use std::{marker::Send, net::SocketAddr};
+use std::{
+    marker::Send,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
 
-use actix::{Addr, Handler, Message};
+use actix::{Addr, Handler, Message, Recipient};
 use tokio::net::TcpStream;
 
+use witnet_data_structures::chain::InvVector;
 use witnet_p2p::sessions::{error::SessionsResult, SessionStatus, SessionType};
 
 use crate::actors::session::Session;
@@ -73,7 +78,89 @@ impl Message for Consolidate {
     type Result = SessionsUnitResult;
 }
 
-/// Message indicating a message is to be forwarded to a random consolidated outbound session
+/// Message indicating the round trip time of a ping/pong exchange with a peer
+pub struct RecordPeerLatency {
+    /// Socket address which identifies the peer
+    pub address: SocketAddr,
+
+    /// Round trip time
+    pub latency: Duration,
+}
+
+impl Message for RecordPeerLatency {
+    type Result = ();
+}
+
+/// Message indicating the number of bytes of the blocks received from a peer in a period of time
+pub struct RecordPeerThroughput {
+    /// Socket address which identifies the peer
+    pub address: SocketAddr,
+
+    /// Number of bytes received
+    pub bytes: usize,
+
+    /// Period of time in which the bytes were received
+    pub elapsed: Duration,
+}
+
+impl Message for RecordPeerThroughput {
+    type Result = ();
+}
+
+/// Message indicating the external address under which the server is reachable (e.g. learnt
+/// through port mapping), to be advertised to the peers instead of the server address
+pub struct SetExternalAddress {
+    /// External socket address of the server
+    pub address: SocketAddr,
+}
+
+impl Message for SetExternalAddress {
+    type Result = ();
+}
+
+/// Message indicating the external IP address of this node as reported by a peer in its
+/// `Version` message
+pub struct ReportExternalAddress {
+    /// IP address of the peer
+    pub reporter: IpAddr,
+
+    /// External IP address of this node, as seen by the peer
+    pub address: IpAddr,
+}
+
+impl Message for ReportExternalAddress {
+    type Result = ();
+}
+
+/// Message indicating the inventory items requested to a peer, to be requested to another peer
+/// if they are not delivered in time
+pub struct TrackRequests {
+    /// Socket address which identifies the peer
+    pub address: SocketAddr,
+
+    /// Requested inventory items
+    pub items: Vec<InvVector>,
+}
+
+impl Message for TrackRequests {
+    type Result = ();
+}
+
+/// Message indicating an inventory item delivered by a peer
+pub struct RequestDelivered {
+    /// Socket address which identifies the peer
+    pub address: SocketAddr,
+
+    /// Delivered inventory item
+    pub item: InvVector,
+}
+
+impl Message for RequestDelivered {
+    type Result = ();
+}
+
+/// Message indicating a message is to be forwarded to the best ranked consolidated outbound
+/// session (falling back to the next ones if it does not process it in time)
 pub struct Anycast<T> {
     /// Command to be sent to the session
     pub command: T,
@@ -81,7 +168,7 @@ pub struct Anycast<T> {
 
 impl<T> Message for Anycast<T>
 where
-    T: Message + Send,
+    T: Clone + Message + Send,
     T::Result: Send,
     Session: Handler<T>,
 {
@@ -89,6 +176,7 @@ where
 }
 
 /// Message indicating a message is to be forwarded to all the consolidated outbound sessions
+/// (best ranked first)
 pub struct Broadcast<T> {
     /// Command to be sent to all the sessions
     pub command: T,
@@ -102,3 +190,79 @@ where
 {
     type Result = ();
 }
+
+/// Session lifecycle events published by the SessionsManager
+#[derive(Clone, Debug)]
+pub enum SessionEvent {
+    /// A session has been registered
+    Registered {
+        /// Socket address which identifies the peer
+        address: SocketAddr,
+        /// Session type
+        session_type: SessionType,
+    },
+    /// A session has been unregistered
+    Unregistered {
+        /// Socket address which identifies the peer
+        address: SocketAddr,
+        /// Session type
+        session_type: SessionType,
+        /// Session status before being unregistered
+        status: SessionStatus,
+    },
+    /// The SessionsManager has been restarted after a failure
+    Restarted {
+        /// Number of sessions removed from the registry because they were no longer alive
+        removed: usize,
+    },
+    /// A session has been consolidated
+    Consolidated {
+        /// Socket address which identifies the peer
+        address: SocketAddr,
+        /// Session type
+        session_type: SessionType,
+    },
+}
+
+/// Message that the SessionsManager sends to the actors subscribed to session lifecycle events
+#[derive(Clone, Debug, Message)]
+pub struct SessionEventNotification {
+    /// Event that has just happened
+    pub event: SessionEvent,
+
+    /// Number of consolidated outbound sessions after the event (the node is isolated when
+    /// there are no consolidated outbound sessions)
+    pub num_outbound_consolidated: usize,
+}
+
+/// Message to notify that the node has been isolated from the network or reconnected to it
+#[derive(Message)]
+pub struct SetIsolation {
+    /// Whether the node is isolated
+    pub isolated: bool,
+}
+
+/// Message to get the number of consolidated outbound sessions
+pub struct GetNumOutboundConsolidated;
+
+impl Message for GetNumOutboundConsolidated {
+    type Result = usize;
+}
+
+/// Message to check whether the node is idle: no inventory items are being requested to the
+/// peers and no message has been sent to the sessions for some time
+pub struct IsIdle {
+    /// Minimum time without network activity
+    pub period: Duration,
+}
+
+impl Message for IsIdle {
+    type Result = bool;
+}
+
+/// Message to subscribe to the session lifecycle events
+#[derive(Message)]
+pub struct SubscribeSessionEvents {
+    /// Recipient of the notifications
+    pub recipient: Recipient<SessionEventNotification>,
+}
@@ -1,39 +1,116 @@
 use log::{debug, error, info, warn};
-use std::{net::SocketAddr, time::Duration};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
 
 use actix::{
-    fut::FutureResult, ActorFuture, Addr, AsyncContext, Context, ContextFutureSpawner, Handler,
-    MailboxError, Message, System, SystemService, WrapFuture,
+    fut::FutureResult, prelude::SendError, ActorFuture, Addr, AsyncContext, Context,
+    ContextFutureSpawner, Handler, MailboxError, Message, Recipient, System, SystemService,
+    WrapFuture,
 };
 
 use crate::actors::{
     connections_manager::{messages::OutboundTcpConnect, ConnectionsManager},
     peers_manager::{
-        messages::{GetRandomPeer, PeersSocketAddrResult},
+        messages::{GetDiversePeers, PeersSocketAddrsResult, ResolveDnsSeeds},
         PeersManager,
     },
-    session::{messages::GetPeers, Session},
+    session::{
+        messages::{GetPeers, PenalizePeer, RequestItems},
+        Session, TransportSettings,
+    },
+    supervision::Restarts,
 };
+use crate::metrics;
 
-use witnet_p2p::sessions::Sessions;
+use witnet_data_structures::chain::InvVector;
+use witnet_p2p::{requests::RequestTracker, sessions::Sessions};
 
 mod actor;
 mod handlers;
 /// Messages for sessions manager
 pub mod messages;
 
+/// Time (in seconds) that a session has to process an anycast message before it is sent to the
+/// next session
+pub const ANYCAST_TIMEOUT_SECONDS: u64 = 5;
+
+/// Period (in seconds) of the check of the inventory items requested to the peers and not
+/// delivered in time
+pub const REQUESTS_CHECK_PERIOD_SECONDS: u64 = 5;
+
+/// Period (in seconds) of the bootstrap of outbound sessions while the node is isolated from the
+/// network, if shorter than the configured period
+pub const ISOLATED_BOOTSTRAP_PERIOD_SECONDS: u64 = 5;
+
+/// Reason for penalizing the peers which repeatedly fail to deliver the requested items
+const UNDELIVERED_REQUESTS: &str = "it repeatedly failed to deliver the requested items";
+
 /// SessionsManager actor
 #[derive(Default)]
 pub struct SessionsManager {
     // Registered Sessions
     sessions: Sessions<Addr<Session>>,
+    // Subscribers to the session lifecycle events
+    subscribers: Vec<Recipient<messages::SessionEventNotification>>,
+    // Mailbox capacity of the created sessions (actix default until configured)
+    session_mailbox_capacity: Option<usize>,
+    // Debounce window of the announcements of the created sessions (sent right away until
+    // configured)
+    announcement_debounce: Duration,
+    // Transport settings of the created sessions: identity of the node in the encrypted
+    // connections, compression threshold and hash of the consensus constants exchanged in the
+    // handshake (neither encrypted, compressed nor checked until configured)
+    transport: TransportSettings,
+    // Inventory items requested to the peers and not delivered yet
+    requests: RequestTracker<InvVector>,
+    // Last time some inventory items were requested or delivered, or a message was sent to the
+    // sessions (None if there has been no activity yet)
+    last_activity: Option<Instant>,
+    // Restarts of the actor
+    restarts: Restarts,
+    // Whether the node is isolated from the network, so outbound sessions are bootstrapped
+    // aggressively
+    isolated: bool,
 }
 
 impl SessionsManager {
+    /// Method to notify a session lifecycle event to all the subscribers
+    /// Subscribers whose actor has been stopped are removed
+    fn publish_event(&mut self, event: messages::SessionEvent) {
+        let notification = messages::SessionEventNotification {
+            event,
+            num_outbound_consolidated: self.sessions.get_num_outbound_consolidated_sessions(),
+        };
+
+        self.subscribers.retain(
+            |subscriber| match subscriber.do_send(notification.clone()) {
+                Err(SendError::Closed(_)) => false,
+                Err(SendError::Full(_)) => {
+                    warn!("Session event could not be delivered to a subscriber (mailbox full)");
+                    true
+                }
+                Ok(()) => true,
+            },
+        );
+    }
+
     /// Method to periodically bootstrap outbound Sessions
+    /// While the node is isolated, the bootstrap runs every `ISOLATED_BOOTSTRAP_PERIOD_SECONDS`
     fn bootstrap_peers(&self, ctx: &mut Context<Self>, bootstrap_peers_period: Duration) {
+        let period = if self.isolated {
+            std::cmp::min(
+                bootstrap_peers_period,
+                Duration::from_secs(ISOLATED_BOOTSTRAP_PERIOD_SECONDS),
+            )
+        } else {
+            bootstrap_peers_period
+        };
+
         // Schedule the bootstrap with a given period
-        ctx.run_later(bootstrap_peers_period, move |act, ctx| {
+        ctx.run_later(period, move |act, ctx| {
             info!(
                 "Number of outbound sessions {}",
                 act.sessions.get_num_outbound_sessions()
@@ -43,44 +120,67 @@ impl SessionsManager {
                 act.sessions.get_num_inbound_sessions()
             );
 
-            // Check if bootstrap is needed
-            if act.sessions.is_outbound_bootstrap_needed() {
-                // Get peers manager address
-                let peers_manager_addr = System::current().registry().get::<PeersManager>();
-
-                // Start chain of actions
-                peers_manager_addr
-                    // Send GetPeer message to peers manager actor
-                    // This returns a Request Future, representing an asynchronous message sending process
-                    .send(GetRandomPeer)
-                    // Convert a normal future into an ActorFuture
-                    .into_actor(act)
-                    // Process the response from the peers manager
-                    // This returns a FutureResult containing the socket address if present
-                    .then(|res, act, _ctx| {
-                        // Process the response from peers manager
-                        act.process_get_peer_response(res)
-                    })
-                    // Process the socket address received
-                    // This returns a FutureResult containing a success or error
-                    .and_then(|address, _act, _ctx| {
-                        debug!("Trying to create a new outbound connection to {}", address);
-
-                        // Get ConnectionsManager from registry and send an OutboundTcpConnect message to it
-                        let connections_manager_addr =
-                            System::current().registry().get::<ConnectionsManager>();
-                        connections_manager_addr.do_send(OutboundTcpConnect { address });
-
-                        actix::fut::ok(())
-                    })
-                    .wait(ctx);
-            }
+            act.bootstrap_outbound_sessions(ctx);
 
             // Reschedule the bootstrap peers task
             act.bootstrap_peers(ctx, bootstrap_peers_period);
         });
     }
 
+    /// Method to open outbound sessions with new peers if there are missing outbound sessions
+    fn bootstrap_outbound_sessions(&mut self, ctx: &mut Context<Self>) {
+        // Check if bootstrap is needed
+        if !self.sessions.is_outbound_bootstrap_needed() {
+            return;
+        }
+
+        // Get peers manager address
+        let peers_manager_addr = System::current().registry().get::<PeersManager>();
+
+        // Start chain of actions
+        peers_manager_addr
+            // Send GetDiversePeers message to peers manager actor, asking for as many
+            // peers as outbound sessions are missing, outside the network groups of the
+            // existing outbound sessions
+            // This returns a Request Future, representing an asynchronous message sending process
+            .send(GetDiversePeers {
+                count: self.sessions.get_num_missing_outbound_sessions(),
+                connected_groups: self.sessions.get_outbound_network_groups(),
+            })
+            // Convert a normal future into an ActorFuture
+            .into_actor(self)
+            // Process the response from the peers manager
+            // This returns a FutureResult containing the socket addresses
+            .then(|res, act, _ctx| {
+                // Process the response from peers manager
+                act.process_get_peers_response(res)
+            })
+            // Process the socket addresses received
+            // This returns a FutureResult containing a success or error
+            .and_then(|addresses, _act, _ctx| {
+                // Get ConnectionsManager from registry and send an OutboundTcpConnect
+                // message to it for every address
+                let connections_manager_addr =
+                    System::current().registry().get::<ConnectionsManager>();
+                for address in addresses {
+                    debug!("Trying to create a new outbound connection to {}", address);
+                    connections_manager_addr.do_send(OutboundTcpConnect { address });
+                }
+
+                actix::fut::ok(())
+            })
+            .wait(ctx);
+    }
+
+    /// Method to look for new peers as soon as the node is isolated from the network: the DNS
+    /// seeds are resolved and outbound sessions are bootstrapped right away
+    fn rebootstrap(&mut self, ctx: &mut Context<Self>) {
+        let peers_manager_addr = System::current().registry().get::<PeersManager>();
+        peers_manager_addr.do_send(ResolveDnsSeeds);
+
+        self.bootstrap_outbound_sessions(ctx);
+    }
+
     /// Method to periodically discover peers
     fn discovery_peers(&self, ctx: &mut Context<Self>, discovery_peers_period: Duration) {
         // Schedule the discovery_peers with a given period
@@ -93,39 +193,129 @@ impl SessionsManager {
         });
     }
 
-    /// Method to process peers manager GetPeer response
-    fn process_get_peer_response(
+    /// Method to periodically check the inventory items requested to the peers: the items not
+    /// delivered in time are requested to another peer, and the peers which repeatedly fail to
+    /// deliver them are penalized
+    fn check_requests(&self, ctx: &mut Context<Self>) {
+        ctx.run_interval(
+            Duration::from_secs(REQUESTS_CHECK_PERIOD_SECONDS),
+            |act, _ctx| {
+                let now = Instant::now();
+
+                // Group the expired items by the peer they are requested to again
+                let mut retries: HashMap<SocketAddr, (Addr<Session>, Vec<InvVector>)> =
+                    HashMap::new();
+                for expired in act.requests.expire(now) {
+                    let session = act
+                        .sessions
+                        .get_random_anycast_session_excluding(&expired.failed_peers);
+                    match session {
+                        Some((address, session_addr)) => {
+                            retries
+                                .entry(address)
+                                .or_insert_with(|| (session_addr, vec![]))
+                                .1
+                                .push(expired.item.clone());
+                            act.requests.retry(address, expired, now);
+                        }
+                        None => debug!(
+                            "No other peer to request {:?} to, giving it up",
+                            expired.item
+                        ),
+                    }
+                }
+                for (address, (session_addr, items)) in retries {
+                    debug!(
+                        "Requesting {} undelivered items to peer {}",
+                        items.len(),
+                        address
+                    );
+                    // The items are requested again when they expire if the session is
+                    // overwhelmed
+                    if let Err(SendError::Full(_)) = session_addr.try_send(RequestItems { items }) {
+                        debug!("Request of undelivered items dropped (session mailbox full)");
+                        metrics::record_session_mailbox_full();
+                    }
+                }
+
+                for address in act.requests.take_failing_peers() {
+                    if let Some(session_addr) = act.sessions.get_consolidated_session(address) {
+                        session_addr.do_send(PenalizePeer {
+                            reason: UNDELIVERED_REQUESTS.to_string(),
+                        });
+                    }
+                }
+            },
+        );
+    }
+
+    /// Method to process peers manager GetDiversePeers response
+    fn process_get_peers_response(
         &mut self,
-        response: Result<PeersSocketAddrResult, MailboxError>,
-    ) -> FutureResult<SocketAddr, (), Self> {
-        response
-            // Unwrap the Result<PeersSocketAddrResult, MailboxError>
+        response: Result<PeersSocketAddrsResult, MailboxError>,
+    ) -> FutureResult<Vec<SocketAddr>, (), Self> {
+        let addresses = response
+            // Unwrap the Result<PeersSocketAddrsResult, MailboxError>
             .unwrap_or_else(|_| {
                 error!("Unsuccessful communication with peers manager");
-                Ok(None)
+                Ok(vec![])
             })
-            // Unwrap the PeersSocketAddrResult
+            // Unwrap the PeersSocketAddrsResult
             .unwrap_or_else(|_| {
-                error!("An error happened in peers manager when getting a peer");
-                None
-            })
-            // Check if PeersSocketAddrResult returned `None`
-            .or_else(|| {
-                warn!("No peer obtained from peers manager");
-                None
-            })
-            // Filter the result checking if outbound address is eligible as new peer
-            .filter(|address: &SocketAddr| {
-                self.sessions.is_outbound_address_eligible(address.clone())
-            })
-            // Check if there is a peer after filter
-            .or_else(|| {
-                warn!("No eligible peer obtained from peers manager");
-                None
+                error!("An error happened in peers manager when getting peers");
+                vec![]
+            });
+        if addresses.is_empty() {
+            warn!("No peer obtained from peers manager");
+            return actix::fut::err(());
+        }
+
+        // Filter the result checking if outbound addresses are eligible as new peers
+        let eligible: Vec<SocketAddr> = addresses
+            .into_iter()
+            .filter(|address| self.sessions.is_outbound_address_eligible(*address))
+            .collect();
+        if eligible.is_empty() {
+            warn!("No eligible peer obtained from peers manager");
+            return actix::fut::err(());
+        }
+
+        actix::fut::ok(eligible)
+    }
+
+    /// Method to send a command to the first of the given sessions, falling back to the next
+    /// one if the session fails to process it in time
+    fn anycast_ranked<T>(
+        &mut self,
+        ctx: &mut Context<Self>,
+        command: T,
+        mut sessions: Vec<Addr<Session>>,
+    ) where
+        T: Clone + Message + Send + 'static,
+        T::Result: Send,
+        Session: Handler<T>,
+    {
+        if sessions.is_empty() {
+            warn!("No consolidated outbound session was found");
+            return;
+        }
+        let session_addr = sessions.remove(0);
+
+        session_addr
+            // Send SendMessage message to session actor
+            // This returns a Request Future, representing an asynchronous message sending process
+            .send(command.clone())
+            .timeout(Duration::from_secs(ANYCAST_TIMEOUT_SECONDS))
+            // Convert a normal future into an ActorFuture
+            .into_actor(self)
+            // Process the response from the session
+            .then(|res, act, _ctx| act.process_command_response(&res))
+            // Try with the next session if this one failed
+            .map_err(move |_, act, ctx| {
+                debug!("Anycast message not processed in time, trying with the next session");
+                act.anycast_ranked(ctx, command, sessions);
             })
-            // Convert Some(SocketAddr) or None to FutureResult<SocketAddr, (), Self>
-            .map(actix::fut::ok)
-            .unwrap_or_else(|| actix::fut::err(()))
+            .spawn(ctx);
     }
 
     /// Method to process Session SendMessage response
@@ -144,7 +334,16 @@ impl SessionsManager {
     }
 }
 
-/// Required traits for being able to retrieve SessionsManager address from registry
-impl actix::Supervised for SessionsManager {}
+/// The sessions manager is restarted when it stops: the sessions registry is rebuilt with the
+/// sessions which are still alive (the rest of its state is restored from the configuration when
+/// the actor is started again) and the subscribers are notified
+impl actix::Supervised for SessionsManager {
+    fn restarting(&mut self, _ctx: &mut Context<Self>) {
+        self.restarts.restarting("SessionsManager");
+
+        let removed = self.sessions.retain(|session| session.connected());
+        self.publish_event(messages::SessionEvent::Restarted { removed });
+    }
+}
 
 impl SystemService for SessionsManager {}
@@ -0,0 +1,9 @@
+use actix::{Actor, SyncContext};
+
+use super::SignatureVerifier;
+
+/// Make actor from `SignatureVerifier`
+impl Actor for SignatureVerifier {
+    /// Every worker of the pool runs in its own thread
+    type Context = SyncContext<Self>;
+}
@@ -0,0 +1,19 @@
+use actix::{Handler, SyncContext};
+
+use witnet_crypto::signature::verify_all;
+
+use super::{messages::VerifySignatures, SignatureVerifier, SignatureVerifierError};
+
+/// Handler for VerifySignatures message
+impl Handler<VerifySignatures> for SignatureVerifier {
+    type Result = Result<(), SignatureVerifierError>;
+
+    fn handle(&mut self, msg: VerifySignatures, _ctx: &mut SyncContext<Self>) -> Self::Result {
+        verify_all(&self.secp, &msg.jobs).map_err(|(index, error)| {
+            SignatureVerifierError::InvalidSignature {
+                index: msg.offset + index,
+                error,
+            }
+        })
+    }
+}
@@ -0,0 +1,18 @@
+use actix::Message;
+
+use witnet_crypto::signature::SignatureJob;
+
+use super::SignatureVerifierError;
+
+/// Verify a batch of signatures
+pub struct VerifySignatures {
+    /// Signatures to verify
+    pub jobs: Vec<SignatureJob>,
+    /// Index of the first signature of the batch in the whole list, used to report the index of
+    /// the invalid signatures
+    pub offset: usize,
+}
+
+impl Message for VerifySignatures {
+    type Result = Result<(), SignatureVerifierError>;
+}
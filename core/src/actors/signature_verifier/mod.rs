@@ -0,0 +1,108 @@
+//! # Signature Verifier
+//!
+//! The __signature verifier__ is a pool of actors running in their own threads (a `SyncArbiter`),
+//! so the signatures of the blocks with many transactions are not verified in the thread of the
+//! actor validating them. The signatures of a block are split in as many batches as workers in
+//! the pool, the batches are verified in parallel and their results are aggregated into a single
+//! one.
+use std::cmp;
+
+use actix::{Addr, MailboxError, SyncArbiter};
+use futures::{future, Future};
+use secp256k1::{Secp256k1, VerifyOnly};
+
+use witnet_crypto::signature::{SignatureError, SignatureJob};
+
+mod actor;
+mod handlers;
+
+/// Messages for SignatureVerifier
+pub mod messages;
+
+/// Possible errors when verifying the signatures of a block
+#[derive(Debug)]
+pub enum SignatureVerifierError {
+    /// A signature is not valid
+    InvalidSignature {
+        /// Index of the first invalid signature
+        index: usize,
+        /// Reason why the signature is not valid
+        error: SignatureError,
+    },
+    /// A batch could not be sent to the pool
+    Mailbox(MailboxError),
+}
+
+////////////////////////////////////////////////////////////////////////////////////////
+// ACTOR BASIC STRUCTURE
+////////////////////////////////////////////////////////////////////////////////////////
+/// SignatureVerifier actor, one per worker of the pool
+pub struct SignatureVerifier {
+    /// Context used to verify the signatures
+    secp: Secp256k1<VerifyOnly>,
+}
+
+impl Default for SignatureVerifier {
+    fn default() -> Self {
+        SignatureVerifier {
+            secp: Secp256k1::verification_only(),
+        }
+    }
+}
+
+impl SignatureVerifier {
+    /// Start a pool of `workers` verifiers
+    pub fn start_pool(workers: usize) -> Addr<Self> {
+        SyncArbiter::start(cmp::max(workers, 1), SignatureVerifier::default)
+    }
+}
+
+/// Size of the batches the signatures are split into, so every worker gets at most one of them
+pub fn batch_size(signatures: usize, workers: usize) -> usize {
+    let workers = cmp::max(workers, 1);
+
+    cmp::max((signatures + workers - 1) / workers, 1)
+}
+
+/// Verify a list of signatures in parallel using a pool of `workers` verifiers
+/// The error points to the first invalid signature of the list
+pub fn verify_in_parallel(
+    pool: &Addr<SignatureVerifier>,
+    jobs: Vec<SignatureJob>,
+    workers: usize,
+) -> impl Future<Item = (), Error = SignatureVerifierError> {
+    let batch_size = batch_size(jobs.len(), workers);
+    let batches: Vec<_> = jobs
+        .chunks(batch_size)
+        .enumerate()
+        .map(|(i, batch)| {
+            pool.send(messages::VerifySignatures {
+                jobs: batch.to_vec(),
+                offset: i * batch_size,
+            })
+        })
+        .collect();
+
+    future::join_all(batches)
+        .map_err(SignatureVerifierError::Mailbox)
+        .and_then(|results| {
+            // The batches are in order, so the first error is the one of the first invalid
+            // signature
+            results.into_iter().collect::<Result<Vec<()>, _>>()
+        })
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_per_worker() {
+        assert_eq!(batch_size(100, 4), 25);
+        assert_eq!(batch_size(101, 4), 26);
+        assert_eq!(batch_size(3, 4), 1);
+        assert_eq!(batch_size(0, 4), 1);
+        assert_eq!(batch_size(10, 0), 10);
+    }
+}
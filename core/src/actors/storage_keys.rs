@@ -3,3 +3,62 @@ pub static PEERS_KEY: &'static [u8] = b"peers";
 
 /// Constant to specify the chain key for the storage
 pub static CHAIN_KEY: &'static [u8] = b"chain";
+
+/// Constant to specify the block index key for the storage
+pub static BLOCK_INDEX_KEY: &'static [u8] = b"block_index";
+
+/// Constant to specify the superblocks key for the storage
+pub static SUPERBLOCKS_KEY: &'static [u8] = b"superblocks";
+
+/// Constant to specify the wallet transaction history key for the storage, as used by older
+/// versions of the node (see `WALLET_HISTORY_HEAD_KEY`)
+pub static WALLET_HISTORY_KEY: &'static [u8] = b"wallet_history";
+
+/// Constant to specify the wallet transaction history head key for the storage
+pub static WALLET_HISTORY_HEAD_KEY: &'static [u8] = b"wallet_history_head";
+
+/// Constant to specify the prefix of the keys of the wallet transaction history entries
+pub static WALLET_HISTORY_ENTRY_PREFIX: &'static [u8] = b"wallet_history_entry/";
+
+/// Constant to specify the wallet accounts key for the storage
+pub static WALLET_ACCOUNTS_KEY: &'static [u8] = b"wallet_accounts";
+
+/// Constant to specify the wallet watch-only addresses key for the storage
+pub static WALLET_WATCHED_ADDRESSES_KEY: &'static [u8] = b"wallet_watched_addresses";
+
+/// Constant to specify the wallet seed key for the storage, as used by older versions of the node
+/// (see `WALLET_KEYS_KEY`)
+pub static WALLET_SEED_KEY: &'static [u8] = b"wallet_seed";
+
+/// Constant to specify the wallet keys key for the storage
+pub static WALLET_KEYS_KEY: &'static [u8] = b"wallet_keys";
+
+/// Constant to specify the reputation key for the storage
+pub static REPUTATION_KEY: &'static [u8] = b"reputation";
+
+/// Constant to specify the mempool key for the storage
+pub static MEMPOOL_KEY: &'static [u8] = b"mempool";
+
+/// Constant to specify the storage schema version key for the storage
+pub static SCHEMA_VERSION_KEY: &'static [u8] = b"schema_version";
+
+/// Constant to specify the node identity key for the storage
+pub static NODE_IDENTITY_KEY: &'static [u8] = b"node_identity";
+
+/// Constant to specify the address identity key for the storage
+pub static ADDRESS_IDENTITY_KEY: &'static [u8] = b"address_identity";
+
+/// Constant to specify the witness identity key for the storage
+pub static WITNESS_IDENTITY_KEY: &'static [u8] = b"witness_identity";
+
+/// Constant to specify the banned peers key for the storage
+pub static BANS_KEY: &'static [u8] = b"bans";
+
+/// Constant to specify the stale entries key for the storage
+pub static STALE_ENTRIES_KEY: &'static [u8] = b"stale_entries";
+
+/// Constant to specify the chain statistics key for the storage
+pub static CHAIN_STATS_KEY: &'static [u8] = b"chain_stats";
+
+/// Constant to specify the chain write-ahead log key for the storage
+pub static CHAIN_WAL_KEY: &'static [u8] = b"chain_wal";
@@ -1,9 +1,20 @@
-use actix::{Actor, ActorContext, Context};
-use log::{debug, error};
+use actix::{Actor, ActorContext, Context, System};
+use log::{debug, error, info};
+use std::fs;
 
-use crate::actors::config_manager::send_get_config_request;
+use witnet_storage::migrations::migrate;
 
-use super::StorageManager;
+use crate::actors::{
+    blocks_manager::BlocksManager,
+    config_manager::send_get_config_request,
+    storage_keys::SCHEMA_VERSION_KEY,
+    supervision::{RestartPolicy, Restarted},
+};
+
+use super::{
+    migrations::{migrations, CURRENT_SCHEMA_VERSION},
+    StorageManager,
+};
 
 /// Make actor from `StorageManager`
 impl Actor for StorageManager {
@@ -19,14 +30,60 @@ impl Actor for StorageManager {
             // Get db path from configuration
             let db_path = &config.storage.db_path;
 
-            // Override actor
-            *s = Self::new(&db_path.to_string_lossy());
+            // The default db path is inside the data directory of the platform, which may not
+            // exist yet
+            if let Some(parent) = db_path.parent() {
+                if let Err(e) = fs::create_dir_all(parent) {
+                    error!("Error creating directory {}: {}", parent.display(), e);
+                }
+            }
+
+            // Open the storage, keeping the restarts of the actor
+            s.open(&db_path.to_string_lossy(), config.storage.cache_size);
+            s.restarts.set_policy(RestartPolicy::from_config(config));
+            s.stale_retention_epochs = config.storage.stale_retention_epochs;
+
+            // Bound the number of queued messages so senders can shed load when overwhelmed
+            ctx.set_mailbox_capacity(config.mailboxes.storage_manager);
+
+            // Stop the actor if the storage is not properly initialized, so it is restarted (or
+            // the node is stopped if it keeps failing)
+            match s.storage.as_mut() {
+                None => {
+                    error!("Error initializing storage");
+                    ctx.stop();
+                }
+                // Upgrade the values persisted by previous versions before serving any request
+                Some(storage) => match migrate(storage, SCHEMA_VERSION_KEY, &migrations()) {
+                    Ok(version) => {
+                        debug_assert_eq!(version, CURRENT_SCHEMA_VERSION);
+                        info!("Storage schema version: {}", version);
+
+                        // Restore the entries to be deleted by the maintenance task
+                        if let Err(e) = s.restore_stale_entries() {
+                            error!("Error restoring the stale entries of the storage: {}", e);
+                        }
+                        s.schedule_maintenance(
+                            ctx,
+                            config.storage.maintenance_period,
+                            config.storage.maintenance_idle,
+                        );
 
-            // Stop context if the storage is not properly initialized
-            // FIXME(#72): check error handling
-            if s.storage.is_none() {
-                error!("Error initializing storage");
-                ctx.stop();
+                        // Let the dependents know that the storage has been opened again
+                        if s.restarted {
+                            s.restarted = false;
+                            System::current().registry().get::<BlocksManager>().do_send(
+                                Restarted {
+                                    actor: "StorageManager",
+                                },
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        error!("Error migrating storage: {}", e);
+                        ctx.stop();
+                    }
+                },
             }
         });
     }
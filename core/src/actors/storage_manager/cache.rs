@@ -0,0 +1,178 @@
+//! # Read cache
+//!
+//! The StorageManager keeps the most recently used values in memory, so
+//! the hot keys (recent blocks, the chain info, the peers...) are not read
+//! from the backend over and over while syncing or serving JSON-RPC
+//! requests. The size of the cache is bounded by a memory budget: the
+//! least recently used values are evicted once the total size of the keys
+//! and values goes over it.
+//!
+//! Writes go through the cache, so it never holds stale values.
+use std::collections::{BTreeMap, HashMap};
+
+/// Cached value
+#[derive(Debug)]
+struct Entry {
+    /// Raw bytes of the value
+    value: Vec<u8>,
+    /// Last time the value was used
+    last_used: u64,
+}
+
+/// Least recently used cache of raw values, bounded by the total size of its keys and values
+#[derive(Debug, Default)]
+pub struct StorageCache {
+    /// Maximum total size (in bytes) of the keys and values
+    capacity: usize,
+    /// Current total size (in bytes) of the keys and values
+    size: usize,
+    /// Counter incremented on every use, used to sort the values by recency
+    clock: u64,
+    /// Cached values
+    entries: HashMap<Vec<u8>, Entry>,
+    /// Keys sorted by the last time their value was used
+    recency: BTreeMap<u64, Vec<u8>>,
+}
+
+impl StorageCache {
+    /// Create a cache with the given capacity (in bytes). A capacity of 0 disables the cache.
+    pub fn new(capacity: usize) -> Self {
+        StorageCache {
+            capacity,
+            ..StorageCache::default()
+        }
+    }
+
+    /// Get a value, marking it as the most recently used one
+    pub fn get(&mut self, key: &[u8]) -> Option<&[u8]> {
+        self.clock += 1;
+        let clock = self.clock;
+        let entry = self.entries.get_mut(key)?;
+        if let Some(recent_key) = self.recency.remove(&entry.last_used) {
+            self.recency.insert(clock, recent_key);
+        }
+        entry.last_used = clock;
+
+        Some(entry.value.as_slice())
+    }
+
+    /// Insert or update a value, evicting the least recently used values if needed
+    /// Values bigger than the capacity are not cached
+    pub fn insert(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        self.remove(&key);
+        let entry_size = key.len() + value.len();
+        if entry_size > self.capacity {
+            return;
+        }
+
+        while self.size + entry_size > self.capacity {
+            self.evict();
+        }
+
+        self.clock += 1;
+        self.size += entry_size;
+        self.recency.insert(self.clock, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                last_used: self.clock,
+            },
+        );
+    }
+
+    /// Remove a value
+    pub fn remove(&mut self, key: &[u8]) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+            self.size -= key.len() + entry.value.len();
+        }
+    }
+
+    /// Current total size (in bytes) of the cached keys and values
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of cached values
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no cached values
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Evict the least recently used value
+    fn evict(&mut self) {
+        let oldest = self.recency.keys().next().cloned();
+        if let Some(key) = oldest.and_then(|oldest| self.recency.remove(&oldest)) {
+            if let Some(entry) = self.entries.remove(&key) {
+                self.size -= key.len() + entry.value.len();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_get_and_update() {
+        let mut cache = StorageCache::new(100);
+        assert_eq!(cache.get(b"chain"), None);
+
+        cache.insert(b"chain".to_vec(), vec![1, 2, 3]);
+        assert_eq!(cache.get(b"chain"), Some(&[1, 2, 3][..]));
+        assert_eq!(cache.size(), 8);
+
+        // Writes replace the cached value
+        cache.insert(b"chain".to_vec(), vec![4]);
+        assert_eq!(cache.get(b"chain"), Some(&[4][..]));
+        assert_eq!(cache.size(), 6);
+
+        cache.remove(b"chain");
+        assert_eq!(cache.get(b"chain"), None);
+        assert!(cache.is_empty());
+        assert_eq!(cache.size(), 0);
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        // Every entry takes 10 bytes
+        let mut cache = StorageCache::new(30);
+        cache.insert(b"key1".to_vec(), vec![0; 6]);
+        cache.insert(b"key2".to_vec(), vec![0; 6]);
+        cache.insert(b"key3".to_vec(), vec![0; 6]);
+        assert_eq!(cache.len(), 3);
+
+        // key1 is used again, so key2 is the least recently used one
+        cache.get(b"key1");
+        cache.insert(b"key4".to_vec(), vec![0; 6]);
+        assert_eq!(cache.len(), 3);
+        assert!(cache.get(b"key2").is_none());
+        assert!(cache.get(b"key1").is_some());
+
+        // A bigger value evicts as many values as needed
+        cache.insert(b"key5".to_vec(), vec![0; 16]);
+        assert_eq!(cache.size(), 30);
+        assert!(cache.get(b"key5").is_some());
+        assert!(cache.get(b"key1").is_some());
+        assert!(cache.get(b"key3").is_none());
+        assert!(cache.get(b"key4").is_none());
+    }
+
+    #[test]
+    fn cache_disabled() {
+        let mut cache = StorageCache::new(0);
+        cache.insert(b"chain".to_vec(), vec![1]);
+        assert!(cache.is_empty());
+
+        // Values bigger than the capacity are not cached either
+        let mut cache = StorageCache::new(10);
+        cache.insert(b"block".to_vec(), vec![0; 6]);
+        assert!(cache.is_empty());
+    }
+}
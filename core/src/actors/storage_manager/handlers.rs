@@ -1,11 +1,14 @@
 use actix::{Context, Handler};
+use log::debug;
 
-use witnet_storage::error::{StorageError, StorageErrorKind};
-use witnet_storage::storage::{Storable, Storage, StorageHelper};
+use witnet_storage::error::{StorageError, StorageErrorKind, StorageResult};
+use witnet_storage::storage::{Storable, Storage};
 use witnet_util::error::WitnetError;
 
+use crate::metrics;
+
 use super::{
-    messages::{Delete, Get, Put},
+    messages::{Delete, Get, IsOpened, MarkStale, Put},
     StorageManager, UnitStorageResult, ValueStorageResult,
 };
 
@@ -14,30 +17,71 @@ impl<T: Storable + 'static> Handler<Get<T>> for StorageManager {
     type Result = ValueStorageResult<T>;
 
     fn handle(&mut self, msg: Get<T>, _: &mut Context<Self>) -> Self::Result {
-        self.storage.as_ref().map_or(
-            Err(WitnetError::from(StorageError::new(
+        let storage = self.storage.as_ref().ok_or_else(|| {
+            WitnetError::from(StorageError::new(
                 StorageErrorKind::Get,
                 String::from_utf8(msg.key.to_vec()).unwrap(),
                 "Storage was not properly initialised".to_string(),
-            ))),
-            |storage| storage.get_t(&msg.key),
-        )
+            ))
+        })?;
+
+        // Read the value from the cache, or from the backend on a miss
+        if let Some(value) = self.cache.get(&msg.key) {
+            metrics::record_storage_cache_hit();
+            return decode(&msg.key, value).map(Some);
+        }
+        metrics::record_storage_cache_miss();
+
+        match storage.get(&msg.key)? {
+            Some(value) => {
+                let decoded = decode(&msg.key, &value)?;
+                self.cache.insert(msg.key.to_vec(), value);
+
+                Ok(Some(decoded))
+            }
+            None => Ok(None),
+        }
     }
 }
 
+/// Create a value from the bytes read from the storage, keeping the kind of the error so
+/// callers can tell corrupted values apart
+fn decode<T: Storable>(key: &[u8], value: &[u8]) -> StorageResult<T> {
+    T::from_bytes(value).map_err(|e| {
+        WitnetError::from(StorageError::new(
+            *e.inner().kind(),
+            format!("Key: {:?}", key),
+            format!("Failed to create value from bytes: {:?}", e),
+        ))
+    })
+}
+
 /// Handler for Put message.
 impl Handler<Put> for StorageManager {
     type Result = UnitStorageResult;
 
     fn handle(&mut self, msg: Put, _: &mut Context<Self>) -> Self::Result {
-        self.storage.as_mut().map_or(
-            Err(WitnetError::from(StorageError::new(
+        let storage = self.storage.as_mut().ok_or_else(|| {
+            WitnetError::from(StorageError::new(
                 StorageErrorKind::Put,
                 String::from_utf8(msg.key.to_vec()).unwrap(),
                 "Storage was not properly initialised".to_string(),
-            ))),
-            |storage| storage.put(&msg.key, msg.value),
-        )
+            ))
+        })?;
+
+        // Write through the cache, so it never holds a stale value
+        storage.put(&msg.key, msg.value.clone())?;
+        if msg.trace.id().is_some() {
+            debug!("{}Stored {} bytes", msg.trace, msg.value.len());
+        }
+        self.cache.insert(msg.key.to_vec(), msg.value);
+
+        // An entry written again is no longer stale
+        if self.stale.unmark(&msg.key) {
+            self.persist_stale_entries()?;
+        }
+
+        Ok(())
     }
 }
 
@@ -46,13 +90,42 @@ impl Handler<Delete> for StorageManager {
     type Result = UnitStorageResult;
 
     fn handle(&mut self, msg: Delete, _: &mut Context<Self>) -> Self::Result {
-        self.storage.as_mut().map_or(
-            Err(WitnetError::from(StorageError::new(
+        let storage = self.storage.as_mut().ok_or_else(|| {
+            WitnetError::from(StorageError::new(
                 StorageErrorKind::Delete,
                 String::from_utf8(msg.key.to_vec()).unwrap(),
                 "Storage was not properly initialised".to_string(),
-            ))),
-            |storage| storage.delete(&msg.key),
-        )
+            ))
+        })?;
+
+        storage.delete(&msg.key)?;
+        self.cache.remove(&msg.key);
+
+        Ok(())
+    }
+}
+
+/// Handler for IsOpened message.
+impl Handler<IsOpened> for StorageManager {
+    type Result = bool;
+
+    fn handle(&mut self, _msg: IsOpened, _: &mut Context<Self>) -> Self::Result {
+        self.storage.is_some()
+    }
+}
+
+/// Handler for MarkStale message.
+impl Handler<MarkStale> for StorageManager {
+    type Result = UnitStorageResult;
+
+    fn handle(&mut self, msg: MarkStale, _: &mut Context<Self>) -> Self::Result {
+        debug!(
+            "Marking {} storage entries as stale in epoch {}",
+            msg.keys.len(),
+            msg.epoch
+        );
+        self.stale.mark(msg.epoch, msg.keys);
+
+        self.persist_stale_entries()
     }
 }
@@ -0,0 +1,177 @@
+//! # Storage maintenance
+//!
+//! Entries that are no longer needed (e.g. blocks orphaned by a rollback
+//! of the local chain) are marked as stale along with the epoch in which
+//! they became stale. They are kept for some epochs, in case they are
+//! needed again, and then deleted by the maintenance task, oldest epochs
+//! first. Writing a stale entry again makes it live. Once the entries are
+//! deleted, the storage backend is compacted to reclaim their space.
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_data_structures::chain::Epoch;
+
+/// Maximum number of stale entries deleted by a single run of the maintenance task, so that the
+/// storage manager is not blocked for too long
+pub const MAX_DELETIONS_PER_RUN: usize = 10_000;
+
+/// Stale entries of the storage, along with the epoch in which they became stale
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StaleEntries {
+    /// Epoch in which every entry became stale, indexed by its key
+    entries: HashMap<Vec<u8>, Epoch>,
+}
+
+impl StaleEntries {
+    /// Mark some entries as stale in an epoch
+    /// Entries which were already stale keep their original epoch
+    pub fn mark(&mut self, epoch: Epoch, keys: Vec<Vec<u8>>) {
+        for key in keys {
+            self.entries.entry(key).or_insert(epoch);
+        }
+    }
+
+    /// Stop considering an entry stale (e.g. because it has been written again)
+    /// Returns false if the entry was not stale
+    pub fn unmark(&mut self, key: &[u8]) -> bool {
+        self.entries.remove(key).is_some()
+    }
+
+    /// Take the entries that have been stale for at least `retention` epochs at `current_epoch`,
+    /// oldest epochs first, up to `max` entries
+    pub fn take_expired(
+        &mut self,
+        current_epoch: Epoch,
+        retention: Epoch,
+        max: usize,
+    ) -> Vec<Vec<u8>> {
+        let mut expired: Vec<(Epoch, Vec<u8>)> = self
+            .entries
+            .iter()
+            .filter(|(_, &epoch)| current_epoch.saturating_sub(epoch) >= retention)
+            .map(|(key, &epoch)| (epoch, key.clone()))
+            .collect();
+        expired.sort();
+        expired.truncate(max);
+
+        expired
+            .into_iter()
+            .map(|(_, key)| {
+                self.entries.remove(&key);
+                key
+            })
+            .collect()
+    }
+
+    /// Get the number of stale entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if there are no stale entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Result of a run of the maintenance task
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MaintenanceReport {
+    /// Number of stale entries deleted
+    pub deleted: usize,
+    /// Size (in bytes) of the storage files before the maintenance
+    pub size_before: u64,
+    /// Size (in bytes) of the storage files after the maintenance
+    pub size_after: u64,
+}
+
+impl MaintenanceReport {
+    /// Get the space (in bytes) reclaimed by the maintenance
+    pub fn reclaimed(&self) -> u64 {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
+/// Get the total size (in bytes) of the files inside a directory and its subdirectories
+pub fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> Vec<u8> {
+        vec![byte; 32]
+    }
+
+    #[test]
+    fn stale_entries_expire_oldest_first() {
+        let mut stale = StaleEntries::default();
+        stale.mark(10, vec![key(1), key(2)]);
+        stale.mark(5, vec![key(3)]);
+        // Entries already stale keep their epoch
+        stale.mark(20, vec![key(1)]);
+
+        // Nothing has been stale for 10 epochs yet
+        assert!(stale.take_expired(14, 10, 10).is_empty());
+
+        // Only the oldest epoch has expired
+        assert_eq!(stale.take_expired(15, 10, 10), vec![key(3)]);
+
+        // The number of entries taken at once is bounded
+        assert_eq!(stale.take_expired(30, 10, 1).len(), 1);
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale.take_expired(30, 10, 1).len(), 1);
+        assert!(stale.is_empty());
+    }
+
+    #[test]
+    fn stale_entries_unmark() {
+        let mut stale = StaleEntries::default();
+        stale.mark(1, vec![key(1)]);
+
+        assert!(stale.unmark(&key(1)));
+        assert!(!stale.unmark(&key(1)));
+        assert!(stale.take_expired(100, 10, 10).is_empty());
+    }
+
+    #[test]
+    fn maintenance_report_reclaimed() {
+        let report = MaintenanceReport {
+            deleted: 1,
+            size_before: 100,
+            size_after: 40,
+        };
+        assert_eq!(report.reclaimed(), 60);
+
+        // Compaction may temporarily grow the files
+        let report = MaintenanceReport {
+            deleted: 0,
+            size_before: 100,
+            size_after: 120,
+        };
+        assert_eq!(report.reclaimed(), 0);
+    }
+
+    #[test]
+    fn dir_size_of_missing_dir() {
+        let missing = std::env::temp_dir().join("witnet-missing-storage-dir");
+        assert!(dir_size(&missing).is_err());
+    }
+}
@@ -2,10 +2,12 @@ use actix::Message;
 use std::borrow::Cow;
 
 use std::marker::PhantomData;
+use witnet_data_structures::chain::Epoch;
 use witnet_storage::error::StorageResult;
 use witnet_storage::storage::Storable;
 
 use super::{UnitStorageResult, ValueStorageResult};
+use crate::tracing::Trace;
 /// Message to indicate that a value is requested from the storage
 pub struct Get<T> {
     /// Requested key
@@ -30,19 +32,27 @@ impl<T: Storable + 'static> Message for Get<T> {
 }
 
 /// Message to indicate that a key-value pair needs to be inserted in the storage
+#[derive(Clone)]
 pub struct Put {
     /// Key to be inserted
     pub key: Cow<'static, [u8]>,
 
     /// Value to be inserted
     pub value: Vec<u8>,
+
+    /// Correlation ID of the protocol message whose processing wrote the value
+    pub trace: Trace,
 }
 
 impl Put {
     /// Create a `Put` message from raw bytes
     pub fn new<K: Into<Cow<'static, [u8]>>>(key: K, value: Vec<u8>) -> Self {
         let key = key.into();
-        Put { key, value }
+        Put {
+            key,
+            value,
+            trace: Trace::default(),
+        }
     }
     /// Create a `Put` message by converting the value into bytes
     pub fn from_value<T, K>(key: K, value: &T) -> StorageResult<Self>
@@ -52,7 +62,17 @@ impl Put {
     {
         let value = value.to_bytes()?;
         let key = key.into();
-        Ok(Put { key, value })
+        Ok(Put {
+            key,
+            value,
+            trace: Trace::default(),
+        })
+    }
+
+    /// Tag the `Put` message with the correlation ID of the protocol message being processed
+    pub fn with_trace(mut self, trace: Trace) -> Self {
+        self.trace = trace;
+        self
     }
 }
 
@@ -77,3 +97,23 @@ impl Delete {
 impl Message for Delete {
     type Result = UnitStorageResult;
 }
+
+/// Message to check whether the storage has been opened
+pub struct IsOpened;
+
+impl Message for IsOpened {
+    type Result = bool;
+}
+
+/// Message to mark some entries as stale, so that they are deleted by the maintenance task once
+/// they have been stale for long enough (unless they are written again before)
+pub struct MarkStale {
+    /// Epoch in which the entries became stale
+    pub epoch: Epoch,
+    /// Keys of the entries
+    pub keys: Vec<Vec<u8>>,
+}
+
+impl Message for MarkStale {
+    type Result = UnitStorageResult;
+}
@@ -0,0 +1,99 @@
+use witnet_storage::{
+    error::{StorageError, StorageErrorKind, StorageResult},
+    migrations::{Migration, SchemaVersion},
+    storage::{checksum, CHECKSUM_SIZE},
+};
+use witnet_util::error::WitnetError;
+
+use crate::actors::storage_keys::{
+    CHAIN_KEY, PEERS_KEY, WALLET_ACCOUNTS_KEY, WALLET_HISTORY_KEY, WALLET_WATCHED_ADDRESSES_KEY,
+};
+
+/// Schema version of the storage after applying all the migrations
+pub const CURRENT_SCHEMA_VERSION: SchemaVersion = 2;
+
+/// List of migrations to be applied at startup, in ascending order of version.
+/// New migrations must be appended at the end and update `CURRENT_SCHEMA_VERSION`.
+pub fn migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            version: 1,
+            description: "Prefix stored values with the encoding version",
+            keys: persisted_keys(),
+            migrate: add_encoding_version,
+        },
+        Migration {
+            version: 2,
+            description: "Append a checksum to stored values",
+            keys: persisted_keys(),
+            migrate: add_checksum,
+        },
+    ]
+}
+
+/// Keys of the values persisted by the node
+fn persisted_keys() -> Vec<&'static [u8]> {
+    vec![
+        PEERS_KEY,
+        CHAIN_KEY,
+        WALLET_HISTORY_KEY,
+        WALLET_ACCOUNTS_KEY,
+        WALLET_WATCHED_ADDRESSES_KEY,
+    ]
+}
+
+/// Values stored before the encoding was versioned are raw MessagePack
+fn add_encoding_version(value: &[u8]) -> StorageResult<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len() + 1);
+    bytes.push(1);
+    bytes.extend_from_slice(value);
+
+    Ok(bytes)
+}
+
+/// Values encoded with version 1 are not followed by a checksum
+fn add_checksum(value: &[u8]) -> StorageResult<Vec<u8>> {
+    match value.split_first() {
+        Some((&1, encoded)) => {
+            let mut bytes = Vec::with_capacity(value.len() + CHECKSUM_SIZE);
+            bytes.push(2);
+            bytes.extend_from_slice(encoded);
+            let checksum = checksum(&bytes);
+            bytes.extend_from_slice(&checksum);
+
+            Ok(bytes)
+        }
+        _ => Err(WitnetError::from(StorageError::new(
+            StorageErrorKind::Version,
+            "Error when migrating value".to_string(),
+            "Expected a value encoded with version 1".to_string(),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use witnet_storage::storage::Storable;
+
+    #[test]
+    fn migrations_sorted_up_to_current_version() {
+        let versions: Vec<SchemaVersion> = migrations().iter().map(|m| m.version).collect();
+        let expected: Vec<SchemaVersion> = (1..=CURRENT_SCHEMA_VERSION).collect();
+
+        assert_eq!(versions, expected);
+    }
+
+    #[test]
+    fn migrations_legacy_value_decodes() {
+        let value = 1234u32;
+        let encoded = value.to_bytes().unwrap();
+        // Raw MessagePack, without encoding version and checksum
+        let legacy = &encoded[1..encoded.len() - CHECKSUM_SIZE];
+
+        let migrated = add_checksum(&add_encoding_version(legacy).unwrap()).unwrap();
+
+        assert_eq!(migrated, encoded);
+        assert_eq!(u32::from_bytes(&migrated).unwrap(), value);
+    }
+}
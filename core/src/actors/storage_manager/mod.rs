@@ -1,39 +1,236 @@
-use actix::{Context, Supervised, SystemService};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+use actix::{
+    ActorFuture, AsyncContext, Context, ContextFutureSpawner, Supervised, System, SystemService,
+    WrapFuture,
+};
+use futures::future::{self, Future, Loop};
+use log::{debug, error, info, warn};
+use tokio::timer::Delay;
+
+use witnet_data_structures::chain::Epoch;
 use witnet_storage::backends::rocks::RocksStorage;
-use witnet_storage::error::StorageResult;
-use witnet_storage::storage::Storage;
+use witnet_storage::error::{StorageError, StorageErrorKind, StorageResult};
+use witnet_storage::storage::{Storable, Storage};
+use witnet_util::error::WitnetError;
+
+use crate::actors::{
+    epoch_manager::{messages::GetEpoch, EpochManager},
+    sessions_manager::{messages::IsIdle, SessionsManager},
+    storage_keys::STALE_ENTRIES_KEY,
+    supervision::Restarts,
+};
+use crate::errors::{NodeError, RetryPolicy, Severity};
 
 /// Type aliases for the storage manager results returned
 type ValueStorageResult<T> = StorageResult<Option<T>>;
 type UnitStorageResult = StorageResult<()>;
 
 mod actor;
+/// Read cache of the storage
+pub mod cache;
 mod handlers;
+/// Deletion of stale entries and compaction of the storage
+pub mod maintenance;
 /// Messages for StorageManager
 pub mod messages;
+mod migrations;
+
+use self::{
+    cache::StorageCache,
+    maintenance::{dir_size, MaintenanceReport, StaleEntries, MAX_DELETIONS_PER_RUN},
+    messages::Put,
+};
 
 /// Storage manager actor
 #[derive(Default)]
 pub struct StorageManager {
     /// DB storage
     storage: Option<RocksStorage>,
+    /// Most recently used values
+    cache: StorageCache,
+    /// Restarts of the actor
+    restarts: Restarts,
+    /// Whether the actor has been restarted and its dependents have to be notified once the
+    /// storage is open again
+    restarted: bool,
+    /// Path of the directory containing the database files
+    db_path: PathBuf,
+    /// Entries to be deleted by the maintenance task
+    stale: StaleEntries,
+    /// Number of epochs during which the stale entries are kept
+    stale_retention_epochs: Epoch,
 }
 
 impl StorageManager {
-    /// Method to create a new storage manager
-    pub fn new(db_root: &str) -> StorageManager {
+    /// Method to create a new storage manager with a read cache of `cache_size` bytes
+    pub fn new(db_root: &str, cache_size: usize) -> StorageManager {
+        let mut storage_manager = StorageManager::default();
+        storage_manager.open(db_root, cache_size);
+
+        storage_manager
+    }
+
+    /// Method to open the storage backend with a read cache of `cache_size` bytes
+    fn open(&mut self, db_root: &str, cache_size: usize) {
         // Build rocks db storage
-        match RocksStorage::new(db_root.to_string()) {
-            Ok(db) => StorageManager { storage: Some(*db) },
-            Err(_) => StorageManager { storage: None },
+        self.storage = RocksStorage::new(db_root.to_string()).ok().map(|db| *db);
+        self.cache = StorageCache::new(cache_size);
+        self.db_path = PathBuf::from(db_root);
+    }
+
+    /// Method to read the stale entries from the storage backend
+    fn restore_stale_entries(&mut self) -> StorageResult<()> {
+        let storage = self.storage.as_ref().ok_or_else(not_opened)?;
+        self.stale = match storage.get(STALE_ENTRIES_KEY)? {
+            Some(bytes) => StaleEntries::from_bytes(&bytes)?,
+            None => StaleEntries::default(),
+        };
+
+        Ok(())
+    }
+
+    /// Method to write the stale entries into the storage backend
+    fn persist_stale_entries(&mut self) -> StorageResult<()> {
+        let bytes = self.stale.to_bytes()?;
+        let storage = self.storage.as_mut().ok_or_else(not_opened)?;
+
+        storage.put(STALE_ENTRIES_KEY, bytes)
+    }
+
+    /// Method to periodically run the maintenance task, as long as the node is idle (there has
+    /// been no network activity for `idle` time)
+    fn schedule_maintenance(&self, ctx: &mut Context<Self>, period: Duration, idle: Duration) {
+        ctx.run_interval(period, move |act, ctx| {
+            let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
+            let epoch_manager_addr = System::current().registry().get::<EpochManager>();
+            sessions_manager_addr
+                .send(IsIdle { period: idle })
+                .join(epoch_manager_addr.send(GetEpoch))
+                .into_actor(act)
+                .then(|res, act, _ctx| {
+                    match res {
+                        Ok((true, Ok(current_epoch))) => match act.maintain(current_epoch) {
+                            Ok(report) => info!(
+                                "Storage maintenance deleted {} stale entries and reclaimed {} \
+                                 bytes ({} stale entries left)",
+                                report.deleted,
+                                report.reclaimed(),
+                                act.stale.len()
+                            ),
+                            Err(e) => error!("Storage maintenance failed: {}", e),
+                        },
+                        Ok((false, _)) => {
+                            debug!("Storage maintenance postponed: the node is not idle")
+                        }
+                        Ok((true, Err(e))) => {
+                            debug!("Storage maintenance postponed: no current epoch ({:?})", e)
+                        }
+                        Err(e) => {
+                            error!("Unsuccessful communication for storage maintenance: {}", e)
+                        }
+                    }
+
+                    actix::fut::ok(())
+                })
+                .wait(ctx);
+        });
+    }
+
+    /// Method to delete the entries that have been stale for long enough and compact the
+    /// storage backend
+    fn maintain(&mut self, current_epoch: Epoch) -> StorageResult<MaintenanceReport> {
+        let size_before = dir_size(&self.db_path).unwrap_or(0);
+
+        let expired = self.stale.take_expired(
+            current_epoch,
+            self.stale_retention_epochs,
+            MAX_DELETIONS_PER_RUN,
+        );
+        let deleted = expired.len();
+        {
+            let storage = self.storage.as_mut().ok_or_else(not_opened)?;
+            for key in expired {
+                storage.delete(&key)?;
+                self.cache.remove(&key);
+            }
+            storage.compact();
         }
+        if deleted > 0 {
+            self.persist_stale_entries()?;
+        }
+
+        Ok(MaintenanceReport {
+            deleted,
+            size_before,
+            size_after: dir_size(&self.db_path).unwrap_or(0),
+        })
     }
 }
 
-/// Required traits for being able to retrieve storage manager address from registry
-impl Supervised for StorageManager {}
+/// Error returned when the storage backend has not been opened
+fn not_opened() -> WitnetError<StorageError> {
+    WitnetError::from(StorageError::new(
+        StorageErrorKind::Connection,
+        "maintenance".to_string(),
+        "Storage was not properly initialised".to_string(),
+    ))
+}
+
+/// The storage manager is restarted when it stops: the storage backend is closed and opened
+/// again (along with an empty cache) when the actor is started again
+impl Supervised for StorageManager {
+    fn restarting(&mut self, _ctx: &mut Context<Self>) {
+        self.restarts.restarting("StorageManager");
+        self.storage = None;
+        self.cache = StorageCache::default();
+        self.restarted = true;
+    }
+}
 
 impl SystemService for StorageManager {
     fn service_started(&mut self, _ctx: &mut Context<Self>) {}
 }
+
+/// Send a `Put` message to the storage manager, retrying it with the given policy while it fails
+/// with a retriable error
+/// Returns a fatal error if the error is not retriable or there are no retries left
+pub fn put_with_retry(msg: Put, policy: RetryPolicy) -> impl Future<Item = (), Error = NodeError> {
+    future::loop_fn(1, move |attempt| {
+        let key = String::from_utf8_lossy(&msg.key).into_owned();
+        let storage_manager_addr = System::current().registry().get::<StorageManager>();
+        storage_manager_addr
+            .send(msg.clone())
+            .map_err(NodeError::from)
+            .and_then(|res| res.map_err(NodeError::from))
+            .then(
+                move |res| -> Box<dyn Future<Item = Loop<(), u32>, Error = NodeError>> {
+                    let e = match res {
+                        Ok(()) => return Box::new(future::ok(Loop::Break(()))),
+                        Err(e) => e,
+                    };
+
+                    match (e.severity(), policy.backoff(attempt)) {
+                        (Severity::Retriable, Some(backoff)) => {
+                            warn!(
+                                "Failed to persist {} into storage ({}), retrying in {:?}",
+                                key, e, backoff
+                            );
+                            Box::new(
+                                Delay::new(Instant::now() + backoff)
+                                    .then(move |_| Ok(Loop::Continue(attempt + 1))),
+                            )
+                        }
+                        (Severity::Retriable, None) => {
+                            Box::new(future::err(NodeError::RetriesExhausted {
+                                attempts: attempt,
+                                last: Box::new(e),
+                            }))
+                        }
+                        (Severity::Fatal, _) => Box::new(future::err(e)),
+                    }
+                },
+            )
+    })
+}
@@ -0,0 +1,168 @@
+//! # Supervision
+//!
+//! The actors whose failure would leave the node half alive (the `StorageManager` and the
+//! `SessionsManager`) are started under an `actix::Supervisor`, which restarts them when they
+//! stop. Each of them restores its state in its `Supervised::restarting` method and notifies its
+//! dependents.
+//!
+//! A failure which persists after a restart usually persists after the next one too, so the
+//! restarts are bounded by a [RestartPolicy]: when an actor is restarted more than
+//! `node.max_restarts` times within `node.restart_window_seconds`, the node is stopped with a
+//! [RestartsExhausted](NodeError::RestartsExhausted) error.
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use actix::Message;
+use log::warn;
+
+use witnet_config::config::Config;
+
+use crate::actors::node;
+use crate::errors::NodeError;
+
+/// Maximum number of restarts of an actor within a window of time
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RestartPolicy {
+    /// Maximum number of restarts within the window
+    pub max_restarts: u32,
+    /// Window in which the restarts are counted
+    pub window: Duration,
+}
+
+/// Default policy, used until the configuration is available: up to 3 restarts per minute
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy {
+            max_restarts: 3,
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Get the restart policy from the configuration
+    pub fn from_config(config: &Config) -> Self {
+        RestartPolicy {
+            max_restarts: config.node.max_restarts,
+            window: config.node.restart_window,
+        }
+    }
+}
+
+/// Restarts of a supervised actor
+#[derive(Debug, Default)]
+pub struct Restarts {
+    /// Policy bounding the restarts
+    policy: RestartPolicy,
+    /// Times of the restarts within the window
+    history: VecDeque<Instant>,
+}
+
+impl Restarts {
+    /// Set the policy bounding the restarts, keeping the restarts recorded so far
+    pub fn set_policy(&mut self, policy: RestartPolicy) {
+        self.policy = policy;
+    }
+
+    /// Record a restart of the actor, failing if the policy does not allow it
+    pub fn record(&mut self, actor: &'static str, now: Instant) -> Result<usize, NodeError> {
+        let window = self.policy.window;
+        while self
+            .history
+            .front()
+            .map_or(false, |restart| now.duration_since(*restart) >= window)
+        {
+            self.history.pop_front();
+        }
+        self.history.push_back(now);
+
+        let restarts = self.history.len();
+        if restarts > self.policy.max_restarts as usize {
+            Err(NodeError::RestartsExhausted { actor, restarts })
+        } else {
+            Ok(restarts)
+        }
+    }
+
+    /// Record a restart of the actor, stopping the node if the policy does not allow it
+    pub fn restarting(&mut self, actor: &'static str) {
+        match self.record(actor, Instant::now()) {
+            Ok(restarts) => warn!(
+                "{} stopped unexpectedly, restarting it ({} restarts in the last {:?})",
+                actor, restarts, self.policy.window
+            ),
+            Err(e) => node::escalate(&e),
+        }
+    }
+}
+
+/// Message notifying that a supervised actor has been restarted, so its dependents can restore
+/// whatever depends on its state
+#[derive(Clone, Copy, Debug)]
+pub struct Restarted {
+    /// Name of the restarted actor
+    pub actor: &'static str,
+}
+
+impl Message for Restarted {
+    type Result = ();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restarts_within_window() {
+        let mut restarts = Restarts::default();
+        restarts.set_policy(RestartPolicy {
+            max_restarts: 2,
+            window: Duration::from_secs(60),
+        });
+        let start = Instant::now();
+
+        assert_eq!(restarts.record("StorageManager", start).unwrap(), 1);
+        assert_eq!(
+            restarts
+                .record("StorageManager", start + Duration::from_secs(10))
+                .unwrap(),
+            2
+        );
+        match restarts.record("StorageManager", start + Duration::from_secs(20)) {
+            Err(e @ NodeError::RestartsExhausted { .. }) => assert!(e.is_fatal()),
+            x => panic!("Unexpected result: {:?}", x),
+        }
+    }
+
+    #[test]
+    fn restarts_outside_window_are_forgotten() {
+        let mut restarts = Restarts::default();
+        restarts.set_policy(RestartPolicy {
+            max_restarts: 1,
+            window: Duration::from_secs(60),
+        });
+        let start = Instant::now();
+
+        assert_eq!(restarts.record("SessionsManager", start).unwrap(), 1);
+        assert_eq!(
+            restarts
+                .record("SessionsManager", start + Duration::from_secs(60))
+                .unwrap(),
+            1
+        );
+        assert!(restarts
+            .record("SessionsManager", start + Duration::from_secs(61))
+            .is_err());
+    }
+
+    #[test]
+    fn no_restarts_allowed() {
+        let mut restarts = Restarts::default();
+        restarts.set_policy(RestartPolicy {
+            max_restarts: 0,
+            window: Duration::from_secs(60),
+        });
+
+        assert!(restarts.record("StorageManager", Instant::now()).is_err());
+    }
+}
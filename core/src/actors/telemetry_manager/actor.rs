@@ -0,0 +1,74 @@
+use actix::{
+    Actor, ActorFuture, AsyncContext, Context, ContextFutureSpawner, Supervised, System,
+    SystemService, WrapFuture,
+};
+use futures::Future;
+use log::{debug, info, warn};
+
+use super::{post_report, TelemetryManager, TelemetryReport};
+use crate::actors::{
+    blocks_manager::{messages::GetHighestCheckpointBeacon, BlocksManager},
+    config_manager::send_get_config_request,
+    sessions_manager::{messages::GetNumOutboundConsolidated, SessionsManager},
+};
+
+/// Make actor from `TelemetryManager`
+impl Actor for TelemetryManager {
+    /// Every actor has to provide execution `Context` in which it can run
+    type Context = Context<Self>;
+
+    /// Method to be executed when the actor is started
+    fn started(&mut self, ctx: &mut Self::Context) {
+        debug!("TelemetryManager actor has been started!");
+
+        send_get_config_request(self, ctx, |act, ctx, config| {
+            if !config.telemetry.enabled {
+                debug!("Telemetry is disabled");
+                return;
+            }
+            act.endpoint = config.telemetry.endpoint.clone();
+            if let Some(endpoint) = &act.endpoint {
+                info!(
+                    "Reporting anonymized statistics to {} every {:?}",
+                    endpoint, config.telemetry.period
+                );
+                ctx.run_interval(config.telemetry.period, |act, ctx| act.report(ctx));
+            }
+        });
+    }
+}
+
+impl TelemetryManager {
+    /// Method to collect the statistics of the node and post them to the endpoint
+    fn report(&mut self, ctx: &mut Context<Self>) {
+        let endpoint = match &self.endpoint {
+            Some(endpoint) => endpoint.clone(),
+            None => return,
+        };
+
+        let blocks_manager_addr = System::current().registry().get::<BlocksManager>();
+        let sessions_manager_addr = System::current().registry().get::<SessionsManager>();
+        blocks_manager_addr
+            .send(GetHighestCheckpointBeacon)
+            .join(sessions_manager_addr.send(GetNumOutboundConsolidated))
+            .into_actor(self)
+            .then(move |res, _act, _ctx| {
+                match res {
+                    Ok((Ok(beacon), peers)) => {
+                        post_report(endpoint, TelemetryReport::new(beacon.checkpoint, peers))
+                    }
+                    Ok((Err(e), _)) => warn!("Failed to get the tip of the chain: {}", e),
+                    Err(e) => warn!("Failed to collect the telemetry statistics: {}", e),
+                }
+
+                actix::fut::ok(())
+            })
+            .spawn(ctx);
+    }
+}
+
+/// Required trait to be able to be managed by a Supervisor
+impl Supervised for TelemetryManager {}
+
+/// Required trait to be able to be registered to the System as a unique Service
+impl SystemService for TelemetryManager {}
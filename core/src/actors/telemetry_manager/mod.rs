@@ -0,0 +1,107 @@
+//! # TelemetryManager actor
+//!
+//! This module contains the TelemetryManager actor which is in charge
+//! of reporting anonymized statistics of the node, to help the network
+//! operators understand the distribution of the clients. It is fully
+//! disabled by default: nothing is reported unless the operator sets
+//! `telemetry.enabled` and a `telemetry.endpoint` in the configuration.
+//!
+//! The module, along with the HTTP client posting the reports, is only
+//! built with the `telemetry` cargo feature, so the default builds never
+//! include any code able to report the statistics of the node.
+//!
+//! Every `telemetry.period_seconds` it:
+//!
+//! * Collects the version of the client, the operating system and architecture it runs on, the
+//!   epoch of the tip of its chain and its number of peers.
+//! * Posts them as a JSON object to the configured endpoint, from a separate thread so that a slow
+//!   endpoint never blocks the node. Failed reports are not retried.
+//!
+//! The reports never include the addresses, identities or keys of the node, nor anything that
+//! could link two reports to the same node.
+
+use std::thread;
+use std::time::Duration;
+
+use log::{debug, warn};
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_data_structures::chain::Epoch;
+
+mod actor;
+
+/// Timeout of the requests posting the reports
+const REPORT_TIMEOUT_SECONDS: u64 = 30;
+
+/// Anonymized statistics of the node
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryReport {
+    /// Version of the client
+    pub version: String,
+    /// Operating system the node runs on
+    pub os: String,
+    /// Architecture of the processor the node runs on
+    pub arch: String,
+    /// Epoch of the tip of the local chain
+    pub sync_height: Epoch,
+    /// Number of consolidated outbound peers
+    pub peers: usize,
+}
+
+impl TelemetryReport {
+    /// Create a report of this client with the state of the node
+    pub fn new(sync_height: Epoch, peers: usize) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            sync_height,
+            peers,
+        }
+    }
+}
+
+/// TelemetryManager actor
+#[derive(Debug, Default)]
+pub struct TelemetryManager {
+    /// URL to which the reports are posted, if the telemetry is enabled
+    endpoint: Option<String>,
+}
+
+/// Post a report to an endpoint, in a separate thread
+fn post_report(endpoint: String, report: TelemetryReport) {
+    thread::spawn(move || {
+        let res = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REPORT_TIMEOUT_SECONDS))
+            .build()
+            .and_then(|client| client.post(&endpoint).json(&report).send())
+            .and_then(|response| response.error_for_status());
+        match res {
+            Ok(_) => debug!("Telemetry report posted to {}", endpoint),
+            Err(e) => warn!("Failed to post the telemetry report to {}: {}", endpoint, e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn telemetry_report_contents() {
+        let report = TelemetryReport::new(100, 8);
+        assert_eq!(report.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(report.os, std::env::consts::OS);
+
+        // Only the anonymized statistics are reported
+        let json = serde_json::to_value(&report).unwrap();
+        let mut fields: Vec<_> = json.as_object().unwrap().keys().cloned().collect();
+        fields.sort();
+        assert_eq!(
+            fields,
+            vec!["arch", "os", "peers", "sync_height", "version"]
+        );
+        assert_eq!(json["sync_height"], 100);
+        assert_eq!(json["peers"], 8);
+    }
+}
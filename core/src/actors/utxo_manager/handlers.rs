@@ -0,0 +1,117 @@
+use actix::{Context, Handler};
+use log::debug;
+
+use super::{
+    messages::{
+        AddBlockOutputs, DiscardOutputsSince, GetAddressBalance, GetBalance, GetUtxos,
+        ReleaseSnapshot, TakeSnapshot, WatchAddress,
+    },
+    BalanceSnapshot, UtxoManager, UtxoManagerError, UtxosSnapshot,
+};
+
+////////////////////////////////////////////////////////////////////////////////////////
+// ACTOR MESSAGE HANDLERS
+////////////////////////////////////////////////////////////////////////////////////////
+/// Handler for WatchAddress message
+impl Handler<WatchAddress> for UtxoManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: WatchAddress, _ctx: &mut Context<Self>) {
+        if self.watched_addresses.insert(msg.address) {
+            debug!("Watching address {:?}", msg.address);
+        }
+    }
+}
+
+/// Handler for AddBlockOutputs message
+impl Handler<AddBlockOutputs> for UtxoManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: AddBlockOutputs, _ctx: &mut Context<Self>) {
+        // FIXME(#99): add the outputs of every transaction to the UTXO set, and remove the ones
+        // spent by their inputs, once transaction inputs and outputs are defined
+        let tracked = self.add_block_outputs(msg.block_hash, msg.checkpoint, msg.outputs);
+        if tracked > 0 {
+            debug!(
+                "Block {:?} pays {} outputs to watched addresses",
+                msg.block_hash, tracked
+            );
+        }
+    }
+}
+
+/// Handler for DiscardOutputsSince message
+impl Handler<DiscardOutputsSince> for UtxoManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: DiscardOutputsSince, _ctx: &mut Context<Self>) {
+        let discarded = self.discard_outputs_since(msg.checkpoint);
+        debug!(
+            "Discarded {} outputs created since checkpoint {}",
+            discarded, msg.checkpoint
+        );
+    }
+}
+
+/// Handler for TakeSnapshot message
+impl Handler<TakeSnapshot> for UtxoManager {
+    type Result = ();
+
+    fn handle(&mut self, _msg: TakeSnapshot, _ctx: &mut Context<Self>) {
+        self.take_snapshot();
+        debug!(
+            "Answering queries from the UTXO set at checkpoint {}",
+            self.snapshot_epoch()
+        );
+    }
+}
+
+/// Handler for ReleaseSnapshot message
+impl Handler<ReleaseSnapshot> for UtxoManager {
+    type Result = ();
+
+    fn handle(&mut self, _msg: ReleaseSnapshot, _ctx: &mut Context<Self>) {
+        if self.release_snapshot() {
+            debug!(
+                "Answering queries from the up-to-date UTXO set at checkpoint {}",
+                self.snapshot_epoch()
+            );
+        }
+    }
+}
+
+/// Handler for GetBalance message
+impl Handler<GetBalance> for UtxoManager {
+    type Result = Result<BalanceSnapshot, UtxoManagerError>;
+
+    fn handle(&mut self, msg: GetBalance, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(BalanceSnapshot {
+            epoch: self.snapshot_epoch(),
+            balance: self.balance(&msg.addresses)?,
+        })
+    }
+}
+
+/// Handler for GetAddressBalance message
+impl Handler<GetAddressBalance> for UtxoManager {
+    type Result = Result<BalanceSnapshot, UtxoManagerError>;
+
+    fn handle(&mut self, msg: GetAddressBalance, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(BalanceSnapshot {
+            epoch: self.snapshot_epoch(),
+            balance: self.address_balance(&msg.address),
+        })
+    }
+}
+
+/// Handler for GetUtxos message
+impl Handler<GetUtxos> for UtxoManager {
+    type Result = Result<UtxosSnapshot, UtxoManagerError>;
+
+    fn handle(&mut self, msg: GetUtxos, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(UtxosSnapshot {
+            epoch: self.snapshot_epoch(),
+            utxos: self.address_utxos(&msg.address, msg.include_unconfirmed),
+        })
+    }
+}
@@ -0,0 +1,87 @@
+use actix::Message;
+
+use witnet_data_structures::chain::{Epoch, Hash, PublicKeyHash, ValueTransferOutput};
+
+use super::{BalanceSnapshot, UtxoManagerError, UtxosSnapshot};
+
+/// Track the UTXOs paying to a watch-only address
+pub struct WatchAddress {
+    /// Address to watch
+    pub address: PublicKeyHash,
+}
+
+impl Message for WatchAddress {
+    type Result = ();
+}
+
+/// Notify the outputs created by a new consolidated block
+pub struct AddBlockOutputs {
+    /// Hash of the block
+    pub block_hash: Hash,
+    /// Checkpoint of the block
+    pub checkpoint: Epoch,
+    /// Outputs created by the block
+    pub outputs: Vec<ValueTransferOutput>,
+}
+
+impl Message for AddBlockOutputs {
+    type Result = ();
+}
+
+/// Discard the outputs created by the blocks consolidated since a checkpoint, before they are
+/// replayed by a rescan
+pub struct DiscardOutputsSince {
+    /// First checkpoint whose outputs are discarded
+    pub checkpoint: Epoch,
+}
+
+impl Message for DiscardOutputsSince {
+    type Result = ();
+}
+
+/// Keep answering the queries from the current state of the UTXO set while a series of updates
+/// is applied (e.g. the blocks replayed by a rescan), until `ReleaseSnapshot` is received
+pub struct TakeSnapshot;
+
+impl Message for TakeSnapshot {
+    type Result = ();
+}
+
+/// Notify that a series of updates started with `TakeSnapshot` has been applied
+pub struct ReleaseSnapshot;
+
+impl Message for ReleaseSnapshot {
+    type Result = ();
+}
+
+/// Ask for the balance of a set of watch-only addresses
+pub struct GetBalance {
+    /// Addresses
+    pub addresses: Vec<PublicKeyHash>,
+}
+
+impl Message for GetBalance {
+    type Result = Result<BalanceSnapshot, UtxoManagerError>;
+}
+
+/// Ask for the balance of any address
+pub struct GetAddressBalance {
+    /// Address
+    pub address: PublicKeyHash,
+}
+
+impl Message for GetAddressBalance {
+    type Result = Result<BalanceSnapshot, UtxoManagerError>;
+}
+
+/// Ask for the UTXOs of any address
+pub struct GetUtxos {
+    /// Address
+    pub address: PublicKeyHash,
+    /// Whether to include the outputs which have not been confirmed yet
+    pub include_unconfirmed: bool,
+}
+
+impl Message for GetUtxos {
+    type Result = Result<UtxosSnapshot, UtxoManagerError>;
+}
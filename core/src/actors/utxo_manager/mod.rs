@@ -6,11 +6,428 @@
 //! * Updating the UTXO set with valid transactions that have already been anchored into a valid block. This includes:
 //!     - Removing the UTXOs that the transaction spends as inputs.
 //!     - Adding a new UTXO for every output in the transaction.
+//! * Keeping track of the UTXOs paying to watch-only addresses, whose private keys are not held by the node.
+//! * Reporting the balance of the watch-only addresses, split into the value which can be spent and the value which is still time locked.
+//! * Reporting the balance and the UTXOs of any address, so external wallets can build transactions without importing their keys into the node.
+//! * Answering those queries from a consistent snapshot of the UTXO set while it goes through a series of updates (e.g. a rescan).
+use std::collections::{HashMap, HashSet};
+
+use serde_derive::{Deserialize, Serialize};
+
+use witnet_data_structures::chain::{Epoch, Hash, PublicKeyHash, ValueTransferOutput};
+
 mod actor;
+mod handlers;
+
+/// Messages for UtxoManager
+pub mod messages;
 
 ////////////////////////////////////////////////////////////////////////////////////////
 // ACTOR BASIC STRUCTURE
 ////////////////////////////////////////////////////////////////////////////////////////
 /// UtxoManager actor
 #[derive(Default)]
-pub struct UtxoManager;
+pub struct UtxoManager {
+    /// Watch-only addresses whose balance can be requested by the wallet
+    watched_addresses: HashSet<PublicKeyHash>,
+    /// Up-to-date UTXO set
+    utxo_set: UtxoSet,
+    /// Copy of the UTXO set taken before a series of updates, from which the queries are
+    /// answered until all the updates have been applied (see `TakeSnapshot`)
+    snapshot: Option<UtxoSet>,
+    /// Number of series of updates in progress, which hold the snapshot
+    snapshot_holds: usize,
+}
+
+/// UTXO set as of a consolidated block
+#[derive(Clone, Debug, Default)]
+struct UtxoSet {
+    /// Unspent outputs, by the address they pay to
+    utxos: HashMap<PublicKeyHash, Vec<Utxo>>,
+    /// Checkpoint of the last consolidated block
+    tip: Epoch,
+}
+
+/// Unspent transaction output, along with the block which created it
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Utxo {
+    /// Hash of the block which created the output
+    block_hash: Hash,
+    /// Index of the output among the outputs created by the block
+    index: u32,
+    /// Checkpoint of the block which created the output
+    checkpoint: Epoch,
+    /// Output
+    output: ValueTransferOutput,
+}
+
+/// Possible errors when interacting with UtxoManager
+#[derive(Debug, Eq, PartialEq)]
+pub enum UtxoManagerError {
+    /// The address is not being watched, so its UTXOs are not tracked
+    AddressNotWatched,
+}
+
+/// Unspent transaction output of an address, as reported to other components
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UtxoInfo {
+    /// Hash of the block which created the output
+    pub block_hash: Hash,
+    /// Index of the output among the outputs created by the block
+    pub index: u32,
+    /// Value of the output
+    pub value: u64,
+    /// Epoch from which the output can be spent, 0 if it is not locked
+    pub time_lock: Epoch,
+    /// Number of confirmations, that is, the number of checkpoints since the block which created
+    /// the output, including it (0 if the output is not confirmed)
+    pub confirmations: u32,
+}
+
+/// Balance of a set of addresses
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Balance {
+    /// Value of the outputs which can be spent
+    pub available: u64,
+    /// Value of the outputs whose time lock has not expired yet
+    pub locked: u64,
+}
+
+/// Balance of a set of addresses, along with the checkpoint of the UTXO set it was read from
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    /// Checkpoint of the last consolidated block applied to the UTXO set
+    pub epoch: Epoch,
+    /// Balance
+    #[serde(flatten)]
+    pub balance: Balance,
+}
+
+/// UTXOs of an address, along with the checkpoint of the UTXO set they were read from
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UtxosSnapshot {
+    /// Checkpoint of the last consolidated block applied to the UTXO set
+    pub epoch: Epoch,
+    /// Unspent outputs
+    pub utxos: Vec<UtxoInfo>,
+}
+
+impl UtxoManager {
+    /// Add the outputs of a consolidated block to the UTXO set
+    /// Returns the number of outputs paying to watch-only addresses
+    fn add_block_outputs(
+        &mut self,
+        block_hash: Hash,
+        checkpoint: Epoch,
+        outputs: Vec<ValueTransferOutput>,
+    ) -> usize {
+        let utxo_set = &mut self.utxo_set;
+        utxo_set.tip = std::cmp::max(utxo_set.tip, checkpoint);
+
+        let mut watched = 0;
+        for (index, output) in outputs.into_iter().enumerate() {
+            if self.watched_addresses.contains(&output.pkh) {
+                watched += 1;
+            }
+            utxo_set
+                .utxos
+                .entry(output.pkh)
+                .or_insert_with(Vec::new)
+                .push(Utxo {
+                    block_hash,
+                    index: index as u32,
+                    checkpoint,
+                    output,
+                });
+        }
+
+        watched
+    }
+
+    /// Discard the outputs created by the blocks consolidated since a checkpoint, moving the tip
+    /// back to the previous checkpoint
+    /// Returns the number of discarded outputs
+    fn discard_outputs_since(&mut self, checkpoint: Epoch) -> usize {
+        let utxo_set = &mut self.utxo_set;
+        utxo_set.tip = std::cmp::min(utxo_set.tip, checkpoint.saturating_sub(1));
+
+        let mut discarded = 0;
+        for utxos in utxo_set.utxos.values_mut() {
+            let len = utxos.len();
+            utxos.retain(|utxo| utxo.checkpoint < checkpoint);
+            discarded += len - utxos.len();
+        }
+        utxo_set.utxos.retain(|_, utxos| !utxos.is_empty());
+
+        discarded
+    }
+
+    /// Take a snapshot of the UTXO set before a series of updates, unless another series is
+    /// already in progress, so the queries keep being answered from a consistent state
+    fn take_snapshot(&mut self) {
+        if self.snapshot_holds == 0 {
+            self.snapshot = Some(self.utxo_set.clone());
+        }
+        self.snapshot_holds += 1;
+    }
+
+    /// Release the snapshot held by a series of updates which has been applied
+    /// Returns true if no other series of updates holds it, so the queries are answered from the
+    /// up-to-date UTXO set again
+    fn release_snapshot(&mut self) -> bool {
+        self.snapshot_holds = self.snapshot_holds.saturating_sub(1);
+        if self.snapshot_holds == 0 {
+            self.snapshot = None;
+        }
+
+        self.snapshot.is_none()
+    }
+
+    /// Get the UTXO set the queries are answered from: the snapshot if a series of updates is
+    /// in progress, or the up-to-date UTXO set otherwise
+    fn view(&self) -> &UtxoSet {
+        self.snapshot.as_ref().unwrap_or(&self.utxo_set)
+    }
+
+    /// Get the checkpoint of the UTXO set the queries are answered from
+    fn snapshot_epoch(&self) -> Epoch {
+        self.view().tip
+    }
+
+    /// Get the balance of a set of watch-only addresses as of the last consolidated block
+    fn balance(&self, addresses: &[PublicKeyHash]) -> Result<Balance, UtxoManagerError> {
+        if addresses
+            .iter()
+            .any(|address| !self.watched_addresses.contains(address))
+        {
+            return Err(UtxoManagerError::AddressNotWatched);
+        }
+
+        Ok(addresses
+            .iter()
+            .map(|address| self.address_balance(address))
+            .fold(Balance::default(), |mut balance, address_balance| {
+                balance.available = balance.available.saturating_add(address_balance.available);
+                balance.locked = balance.locked.saturating_add(address_balance.locked);
+                balance
+            }))
+    }
+
+    /// Get the balance of any address as of the last consolidated block
+    fn address_balance(&self, address: &PublicKeyHash) -> Balance {
+        self.view().address_balance(address)
+    }
+
+    /// Get the UTXOs of any address, along with their number of confirmations
+    fn address_utxos(&self, address: &PublicKeyHash, include_unconfirmed: bool) -> Vec<UtxoInfo> {
+        self.view().address_utxos(address, include_unconfirmed)
+    }
+}
+
+impl UtxoSet {
+    /// Get the balance of any address
+    // FIXME(#99): exclude the spent outputs once transactions define their inputs
+    fn address_balance(&self, address: &PublicKeyHash) -> Balance {
+        self.utxos.get(address).into_iter().flatten().fold(
+            Balance::default(),
+            |mut balance, utxo| {
+                if utxo.output.is_spendable(self.tip) {
+                    balance.available = balance.available.saturating_add(utxo.output.value);
+                } else {
+                    balance.locked = balance.locked.saturating_add(utxo.output.value);
+                }
+                balance
+            },
+        )
+    }
+
+    /// Get the UTXOs of any address, along with their number of confirmations
+    // FIXME(#99): transactions do not define outputs yet, so there are no unconfirmed outputs
+    // (the ones created by the transactions in the mempool) to be included
+    fn address_utxos(&self, address: &PublicKeyHash, _include_unconfirmed: bool) -> Vec<UtxoInfo> {
+        self.utxos
+            .get(address)
+            .into_iter()
+            .flatten()
+            .map(|utxo| UtxoInfo {
+                block_hash: utxo.block_hash,
+                index: utxo.index,
+                value: utxo.output.value,
+                time_lock: utxo.output.time_lock,
+                confirmations: self.tip.saturating_sub(utxo.checkpoint).saturating_add(1),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(pkh: PublicKeyHash, value: u64, time_lock: Epoch) -> ValueTransferOutput {
+        ValueTransferOutput {
+            pkh,
+            value,
+            time_lock,
+        }
+    }
+
+    #[test]
+    fn balance_of_watched_addresses() {
+        let mut utxo_manager = UtxoManager::default();
+        utxo_manager.watched_addresses.insert([1; 20]);
+        utxo_manager.watched_addresses.insert([2; 20]);
+
+        let outputs = vec![
+            output([1; 20], 10, 0),
+            output([1; 20], 20, 5),
+            output([2; 20], 40, 3),
+            output([3; 20], 80, 0),
+        ];
+        assert_eq!(
+            utxo_manager.add_block_outputs(Hash::SHA256([0; 32]), 2, outputs),
+            3
+        );
+
+        assert_eq!(
+            utxo_manager.balance(&[[1; 20]]),
+            Ok(Balance {
+                available: 10,
+                locked: 20
+            })
+        );
+        assert_eq!(
+            utxo_manager.balance(&[[1; 20], [2; 20]]),
+            Ok(Balance {
+                available: 10,
+                locked: 60
+            })
+        );
+        assert_eq!(
+            utxo_manager.balance(&[[3; 20]]),
+            Err(UtxoManagerError::AddressNotWatched)
+        );
+
+        // The time locks expire as new blocks are consolidated
+        utxo_manager.add_block_outputs(Hash::SHA256([1; 32]), 3, vec![]);
+        assert_eq!(
+            utxo_manager.balance(&[[1; 20], [2; 20]]),
+            Ok(Balance {
+                available: 50,
+                locked: 20
+            })
+        );
+        utxo_manager.add_block_outputs(Hash::SHA256([2; 32]), 5, vec![]);
+        assert_eq!(
+            utxo_manager.balance(&[[1; 20], [2; 20]]),
+            Ok(Balance {
+                available: 70,
+                locked: 0
+            })
+        );
+    }
+
+    #[test]
+    fn balance_and_utxos_of_any_address() {
+        let mut utxo_manager = UtxoManager::default();
+        let block_a = Hash::SHA256([0; 32]);
+        let block_b = Hash::SHA256([1; 32]);
+        utxo_manager.add_block_outputs(
+            block_a,
+            2,
+            vec![output([3; 20], 80, 0), output([4; 20], 1, 0)],
+        );
+        utxo_manager.add_block_outputs(block_b, 4, vec![output([3; 20], 20, 6)]);
+
+        // The addresses do not need to be watched
+        assert_eq!(
+            utxo_manager.address_balance(&[3; 20]),
+            Balance {
+                available: 80,
+                locked: 20
+            }
+        );
+        assert_eq!(utxo_manager.address_balance(&[5; 20]), Balance::default());
+
+        assert_eq!(
+            utxo_manager.address_utxos(&[3; 20], false),
+            vec![
+                UtxoInfo {
+                    block_hash: block_a,
+                    index: 0,
+                    value: 80,
+                    time_lock: 0,
+                    confirmations: 3,
+                },
+                UtxoInfo {
+                    block_hash: block_b,
+                    index: 0,
+                    value: 20,
+                    time_lock: 6,
+                    confirmations: 1,
+                },
+            ]
+        );
+        assert_eq!(utxo_manager.address_utxos(&[4; 20], true)[0].index, 1);
+        assert!(utxo_manager.address_utxos(&[5; 20], true).is_empty());
+    }
+
+    #[test]
+    fn discard_outputs_since() {
+        let mut utxo_manager = UtxoManager::default();
+        utxo_manager.add_block_outputs(Hash::SHA256([0; 32]), 2, vec![output([3; 20], 80, 0)]);
+        utxo_manager.add_block_outputs(
+            Hash::SHA256([1; 32]),
+            4,
+            vec![output([3; 20], 20, 0), output([4; 20], 1, 0)],
+        );
+
+        assert_eq!(utxo_manager.discard_outputs_since(3), 2);
+        assert_eq!(utxo_manager.utxo_set.tip, 2);
+        assert_eq!(utxo_manager.address_balance(&[3; 20]).available, 80);
+        assert!(utxo_manager.address_utxos(&[4; 20], false).is_empty());
+
+        // Replaying the discarded block restores its outputs
+        utxo_manager.add_block_outputs(
+            Hash::SHA256([1; 32]),
+            4,
+            vec![output([3; 20], 20, 0), output([4; 20], 1, 0)],
+        );
+        assert_eq!(utxo_manager.address_balance(&[3; 20]).available, 100);
+        assert_eq!(utxo_manager.utxo_set.tip, 4);
+
+        assert_eq!(utxo_manager.discard_outputs_since(0), 3);
+        assert!(utxo_manager.utxo_set.utxos.is_empty());
+    }
+
+    #[test]
+    fn queries_answered_from_snapshot() {
+        let mut utxo_manager = UtxoManager::default();
+        utxo_manager.add_block_outputs(Hash::SHA256([0; 32]), 2, vec![output([3; 20], 80, 0)]);
+
+        // A rescan discards the outputs and replays them, but the queries keep being answered
+        // from the UTXO set as it was before the rescan started
+        utxo_manager.take_snapshot();
+        utxo_manager.discard_outputs_since(0);
+        assert_eq!(utxo_manager.snapshot_epoch(), 2);
+        assert_eq!(utxo_manager.address_balance(&[3; 20]).available, 80);
+        assert_eq!(utxo_manager.address_utxos(&[3; 20], false).len(), 1);
+
+        // Snapshots are held until every series of updates has been applied
+        utxo_manager.take_snapshot();
+        utxo_manager.add_block_outputs(Hash::SHA256([0; 32]), 2, vec![output([3; 20], 80, 0)]);
+        utxo_manager.add_block_outputs(Hash::SHA256([1; 32]), 3, vec![output([3; 20], 20, 0)]);
+        assert!(!utxo_manager.release_snapshot());
+        assert_eq!(utxo_manager.snapshot_epoch(), 2);
+        assert_eq!(utxo_manager.address_balance(&[3; 20]).available, 80);
+
+        assert!(utxo_manager.release_snapshot());
+        assert_eq!(utxo_manager.snapshot_epoch(), 3);
+        assert_eq!(utxo_manager.address_balance(&[3; 20]).available, 100);
+
+        // Unbalanced releases do not hold the up-to-date UTXO set back
+        assert!(utxo_manager.release_snapshot());
+        utxo_manager.take_snapshot();
+        assert!(utxo_manager.release_snapshot());
+    }
+}
@@ -0,0 +1,234 @@
+use actix::{Actor, ActorFuture, Context, ContextFutureSpawner, System, WrapFuture};
+use futures::future;
+use log::{debug, error, info, warn};
+
+use super::{
+    history_entry_key, signer::SocketSigner, Accounts, HistoryEntry, HistoryHead, KeysSecrets,
+    TransactionHistory, WalletKeys, WalletManager, WatchedAddresses,
+};
+use crate::actors::{
+    config_manager::send_get_config_request,
+    storage_keys::{
+        WALLET_ACCOUNTS_KEY, WALLET_HISTORY_HEAD_KEY, WALLET_HISTORY_KEY, WALLET_KEYS_KEY,
+        WALLET_SEED_KEY, WALLET_WATCHED_ADDRESSES_KEY,
+    },
+    storage_manager::{messages::Get, StorageManager},
+};
+use witnet_storage::storage::Storable;
+
+/// Implement Actor trait for `WalletManager`
+impl Actor for WalletManager {
+    /// Every actor has to provide execution `Context` in which it can run
+    type Context = Context<Self>;
+
+    /// Method to be executed when the actor is started
+    fn started(&mut self, ctx: &mut Self::Context) {
+        debug!("WalletManager actor has been started!");
+
+        // Restore the accounts, the watch-only addresses and the transaction history from storage
+        get_from_storage(
+            self,
+            ctx,
+            WALLET_ACCOUNTS_KEY,
+            |act, _ctx, accounts: Accounts| {
+                act.watch_addresses(&accounts.addresses());
+                act.accounts = accounts;
+            },
+        );
+        get_from_storage(
+            self,
+            ctx,
+            WALLET_WATCHED_ADDRESSES_KEY,
+            |act, _ctx, watched_addresses: WatchedAddresses| {
+                let addresses: Vec<_> = watched_addresses
+                    .get_all()
+                    .iter()
+                    .map(|watched| watched.address)
+                    .collect();
+                act.watch_addresses(&addresses);
+                act.watched_addresses = watched_addresses;
+            },
+        );
+        restore_history(self, ctx);
+
+        // Sign with the external signer if there is one configured, or else with the keys
+        // derived from the seed of the wallet
+        send_get_config_request(self, ctx, |act, ctx, config| {
+            match config.wallet.external_signer {
+                Some(address) => {
+                    info!("Signing with the external signer at {}", address);
+                    act.signer = Some(Box::new(SocketSigner::new(address)));
+                    act.external_signer = true;
+                }
+                None => restore_keys(act, ctx),
+            }
+        });
+    }
+}
+
+/// Restore the keys of the wallet, or create them the first time the wallet is started
+fn restore_keys(act: &mut WalletManager, ctx: &mut Context<WalletManager>) {
+    let storage_manager_addr = System::current().registry().get::<StorageManager>();
+    storage_manager_addr
+        .send(Get::<WalletKeys>::new(WALLET_KEYS_KEY))
+        .into_actor(act)
+        .then(|res, act, ctx| {
+            match res {
+                Ok(Ok(Some(keys))) => {
+                    act.load_keys(keys);
+                    info!("Wallet keys successfully obtained from storage");
+                }
+                Ok(Ok(None)) => restore_legacy_seed(act, ctx),
+                // Never create new keys if the storage could not be read, as they would replace
+                // the existing ones
+                Ok(Err(e)) => error!("Error while getting wallet keys from storage: {}", e),
+                Err(e) => error!("Unsuccessful communication with storage manager: {}", e),
+            }
+
+            actix::fut::ok(())
+        })
+        .wait(ctx);
+}
+
+/// Restore the seed persisted by older versions of the node, migrating it to the current format,
+/// or create new keys if there is none
+fn restore_legacy_seed(act: &mut WalletManager, ctx: &mut Context<WalletManager>) {
+    let storage_manager_addr = System::current().registry().get::<StorageManager>();
+    storage_manager_addr
+        .send(Get::<Vec<u8>>::new(WALLET_SEED_KEY))
+        .into_actor(act)
+        .then(|res, act, ctx| {
+            match res {
+                Ok(Ok(Some(seed))) => {
+                    act.load_keys(WalletKeys::Plain(KeysSecrets::from_legacy_seed(seed)));
+                    act.persist_keys(ctx);
+                    info!("Wallet seed successfully obtained from storage");
+                }
+                Ok(Ok(None)) => {
+                    info!("No wallet keys in storage, creating new ones");
+                    act.create_keys(ctx);
+                }
+                // Never create a new seed if the storage could not be read, as it would
+                // replace the existing one
+                Ok(Err(e)) => error!("Error while getting wallet seed from storage: {}", e),
+                Err(e) => error!("Unsuccessful communication with storage manager: {}", e),
+            }
+
+            actix::fut::ok(())
+        })
+        .wait(ctx);
+}
+
+/// Restore the transaction history from its head and its entries, or from the single value
+/// persisted by older versions of the node if there is no head
+fn restore_history(act: &mut WalletManager, ctx: &mut Context<WalletManager>) {
+    let storage_manager_addr = System::current().registry().get::<StorageManager>();
+    storage_manager_addr
+        .send(Get::<HistoryHead>::new(WALLET_HISTORY_HEAD_KEY))
+        .into_actor(act)
+        .then(|res, act, ctx| {
+            match res {
+                Ok(Ok(Some(head))) => restore_history_entries(act, ctx, head),
+                Ok(Ok(None)) => get_from_storage(
+                    act,
+                    ctx,
+                    WALLET_HISTORY_KEY,
+                    |act, ctx, history: TransactionHistory| {
+                        act.history = history;
+                        act.migrate_legacy_history(ctx);
+                    },
+                ),
+                Ok(Err(e)) => error!("Error while getting wallet history from storage: {}", e),
+                Err(e) => error!("Unsuccessful communication with storage manager: {}", e),
+            }
+
+            actix::fut::ok(())
+        })
+        .wait(ctx);
+}
+
+/// Read the entries of the transaction history given its head
+/// If some entry cannot be read, the rest of them are persisted again, so their positions match
+/// their storage keys
+fn restore_history_entries(
+    act: &mut WalletManager,
+    ctx: &mut Context<WalletManager>,
+    head: HistoryHead,
+) {
+    let storage_manager_addr = System::current().registry().get::<StorageManager>();
+    let gets = (0..head.len).map(move |index| {
+        storage_manager_addr.send(Get::<HistoryEntry>::new(history_entry_key(index)))
+    });
+
+    future::join_all(gets)
+        .into_actor(act)
+        .then(move |res, act, ctx| {
+            match res {
+                Ok(results) => {
+                    let mut entries = Vec::with_capacity(results.len());
+                    for (index, res) in results.into_iter().enumerate() {
+                        match res {
+                            Ok(Some(entry)) => entries.push(entry),
+                            Ok(None) => warn!("Missing wallet history entry {}", index),
+                            Err(e) => error!("Error while getting wallet history entry: {}", e),
+                        }
+                    }
+                    let missing = entries.len() < head.len as usize;
+                    act.history = TransactionHistory::from_parts(&head, entries);
+                    if missing {
+                        act.history.mark_all_changed();
+                        act.persist_history(ctx);
+                    }
+                    info!("Wallet history successfully obtained from storage");
+                }
+                Err(e) => error!("Unsuccessful communication with storage manager: {}", e),
+            }
+
+            actix::fut::ok(())
+        })
+        .wait(ctx);
+}
+
+/// Read a value from the storage and process it, if the storage contains that key
+fn get_from_storage<T, F>(
+    act: &mut WalletManager,
+    ctx: &mut Context<WalletManager>,
+    key: &'static [u8],
+    f: F,
+) where
+    T: Storable + Send + 'static,
+    F: FnOnce(&mut WalletManager, &mut Context<WalletManager>, T) + 'static,
+{
+    let key_name = String::from_utf8_lossy(key);
+
+    // Get storage manager actor address
+    let storage_manager_addr = System::current().registry().get::<StorageManager>();
+    storage_manager_addr
+        // Send a message to read the value from the storage
+        .send(Get::<T>::new(key))
+        .into_actor(act)
+        // Process the response
+        .then(move |res, act, ctx| {
+            match res {
+                Err(e) => {
+                    // Error when sending message
+                    error!("Unsuccessful communication with storage manager: {}", e);
+                }
+                Ok(Err(e)) => {
+                    // Storage error
+                    error!("Error while getting {} from storage: {}", key_name, e);
+                }
+                Ok(Ok(Some(value))) => {
+                    f(act, ctx, value);
+                    info!("{} successfully obtained from storage", key_name);
+                }
+                Ok(Ok(None)) => {
+                    // The storage does not contain that key
+                    debug!("No {} in storage", key_name);
+                }
+            }
+
+            actix::fut::ok(())
+        })
+        .wait(ctx);
+}
@@ -0,0 +1,403 @@
+use actix::{ActorFuture, Context, Handler, ResponseActFuture, WrapFuture};
+use futures::Future;
+use log::{debug, info};
+use secp256k1::Secp256k1;
+
+use crate::actors::data_requests_manager::data_request_hash;
+use witnet_crypto::mnemonic::Mnemonic;
+use witnet_data_structures::chain::{Hash, PublicKeyHash};
+
+use super::{
+    add_signature,
+    messages::{
+        AddPendingTransaction, BlockConsolidated, BlockReverted, CreateAccount, CreateAddress,
+        CreateValueTransfer, CreateWallet, ExportMasterKey, FundDataRequest, GetAccounts,
+        GetAddresses, GetTransactionHistory, ImportAddress, ImportMnemonic,
+        RevertConfirmationsSince, SetWalletPassword, SignTransaction, TransactionResubmitted,
+        TransactionsExpired, UnlockWallet,
+    },
+    signing_request, value_transfer_hash, Account, HistoryItem, KeysBackup, KeysSecrets,
+    SignedTransaction, WalletManager, WalletManagerError, WatchedAddress, MAX_HISTORY_PAGE_SIZE,
+};
+
+////////////////////////////////////////////////////////////////////////////////////////
+// ACTOR MESSAGE HANDLERS
+////////////////////////////////////////////////////////////////////////////////////////
+/// Handler for CreateAccount message
+impl Handler<CreateAccount> for WalletManager {
+    type Result = Result<Account, WalletManagerError>;
+
+    fn handle(&mut self, msg: CreateAccount, ctx: &mut Context<Self>) -> Self::Result {
+        let account = self.accounts.create(msg.name)?;
+        debug!(
+            "Created account {} with key path {}",
+            account.name,
+            account.key_path()
+        );
+        self.persist_accounts(ctx);
+
+        Ok(account)
+    }
+}
+
+/// Handler for GetAccounts message
+impl Handler<GetAccounts> for WalletManager {
+    type Result = Result<Vec<Account>, WalletManagerError>;
+
+    fn handle(&mut self, _msg: GetAccounts, _ctx: &mut Context<Self>) -> Self::Result {
+        Ok(self.accounts.get_all().to_vec())
+    }
+}
+
+/// Handler for ImportAddress message
+impl Handler<ImportAddress> for WalletManager {
+    type Result = Result<(), WalletManagerError>;
+
+    fn handle(&mut self, msg: ImportAddress, ctx: &mut Context<Self>) -> Self::Result {
+        self.accounts.get(msg.account)?;
+
+        let watched = WatchedAddress {
+            address: msg.address,
+            account: msg.account,
+            label: msg.label,
+        };
+        self.watched_addresses.import(watched.clone())?;
+        debug!(
+            "Imported watch-only address {:?} into account {}",
+            watched.address, watched.account
+        );
+        self.watch_addresses(&[watched.address]);
+        self.persist_watched_addresses(ctx);
+
+        Ok(())
+    }
+}
+
+/// Handler for AddPendingTransaction message
+impl Handler<AddPendingTransaction> for WalletManager {
+    type Result = Result<(), WalletManagerError>;
+
+    fn handle(&mut self, msg: AddPendingTransaction, ctx: &mut Context<Self>) -> Self::Result {
+        self.accounts.get(msg.account)?;
+
+        if self.history.add_pending(msg.account, msg.hash) {
+            self.persist_history(ctx);
+        } else {
+            debug!(
+                "Transaction {:?} was already in the history of account {}",
+                msg.hash, msg.account
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Handler for CreateAddress message
+impl Handler<CreateAddress> for WalletManager {
+    type Result = Result<PublicKeyHash, WalletManagerError>;
+
+    fn handle(&mut self, msg: CreateAddress, ctx: &mut Context<Self>) -> Self::Result {
+        if self.external_signer {
+            return Err(WalletManagerError::ExternalSigner);
+        }
+        let account = self.accounts.get(msg.account)?;
+        let key_index = account.next_key_index()?;
+        let key_path = account.signing_key_path(key_index);
+
+        let address = self
+            .signer
+            .as_ref()
+            .ok_or(WalletManagerError::NoSigner)?
+            .address(&key_path)?;
+        self.accounts.add_address(msg.account, address)?;
+        debug!(
+            "Derived address {:?} with key path {} for account {}",
+            address, key_path, msg.account
+        );
+        self.watch_addresses(&[address]);
+        self.persist_accounts(ctx);
+
+        Ok(address)
+    }
+}
+
+/// Handler for CreateValueTransfer message
+impl Handler<CreateValueTransfer> for WalletManager {
+    type Result = Result<Hash, WalletManagerError>;
+
+    fn handle(&mut self, msg: CreateValueTransfer, ctx: &mut Context<Self>) -> Self::Result {
+        self.accounts.get(msg.account)?;
+
+        // FIXME(#99): spend the outputs of the account to pay for the value and the fee of the
+        // value transfer, and sign the inputs with the keys of the account
+        let hash = value_transfer_hash(msg.account, &msg.outputs, msg.fee)?;
+        if self.history.add_pending(msg.account, hash) {
+            self.persist_history(ctx);
+        }
+        debug!(
+            "Value transfer {:?} to {} outputs created by account {}",
+            hash,
+            msg.outputs.len(),
+            msg.account
+        );
+
+        Ok(hash)
+    }
+}
+
+/// Handler for FundDataRequest message
+impl Handler<FundDataRequest> for WalletManager {
+    type Result = Result<Hash, WalletManagerError>;
+
+    fn handle(&mut self, msg: FundDataRequest, ctx: &mut Context<Self>) -> Self::Result {
+        self.accounts.get(msg.account)?;
+
+        // FIXME(#99): spend the outputs of the account to pay for the value and the fees of the
+        // data request, and sign the inputs with the keys of the account
+        let hash = data_request_hash(&msg.data_request);
+        if self.history.add_pending(msg.account, hash) {
+            self.persist_history(ctx);
+        }
+        debug!("Data request {:?} funded by account {}", hash, msg.account);
+
+        Ok(hash)
+    }
+}
+
+/// Handler for SignTransaction message
+impl Handler<SignTransaction> for WalletManager {
+    type Result = ResponseActFuture<Self, SignedTransaction, WalletManagerError>;
+
+    fn handle(&mut self, msg: SignTransaction, _ctx: &mut Context<Self>) -> Self::Result {
+        let request = match self
+            .accounts
+            .get(msg.account)
+            .and_then(|account| signing_request(account, msg.key_index, &msg.transaction))
+        {
+            Ok(request) => request,
+            Err(e) => return Box::new(actix::fut::err(e)),
+        };
+        let signer = match &self.signer {
+            Some(signer) => signer,
+            None => return Box::new(actix::fut::err(WalletManagerError::NoSigner)),
+        };
+
+        // The signer may need the approval of the user, so the actor keeps processing other
+        // messages in the meantime
+        let account = msg.account;
+        let transaction = msg.transaction;
+        let fut = signer
+            .sign(&request)
+            .map_err(WalletManagerError::from)
+            .into_actor(self)
+            .and_then(move |keyed, _act, _ctx| {
+                let res = add_signature(&Secp256k1::verification_only(), transaction, keyed);
+                if let Ok(signed) = &res {
+                    debug!(
+                        "Transaction {:?} signed by account {}: {} of {} signatures",
+                        signed.transaction.hash,
+                        account,
+                        signed.transaction.signatures.len(),
+                        signed.transaction.condition.required
+                    );
+                }
+
+                actix::fut::result(res)
+            });
+
+        Box::new(fut)
+    }
+}
+
+/// Handler for BlockConsolidated message
+impl Handler<BlockConsolidated> for WalletManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: BlockConsolidated, ctx: &mut Context<Self>) {
+        // FIXME(#99): detect the received transactions paying to owned and watch-only addresses and
+        // add them to the history once the Transaction data structure is defined
+        let confirmed =
+            self.history
+                .confirm_block(msg.block_hash, msg.checkpoint, &msg.transactions);
+        debug!(
+            "Block {:?} consolidated, {} wallet transactions confirmed",
+            msg.block_hash, confirmed
+        );
+
+        // The number of confirmations depends on the checkpoint of the last consolidated block,
+        // so the history is persisted even if no transactions were confirmed
+        self.persist_history(ctx);
+    }
+}
+
+/// Handler for BlockReverted message
+impl Handler<BlockReverted> for WalletManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: BlockReverted, ctx: &mut Context<Self>) {
+        let reorged_out = self.history.revert_block(msg.block_hash);
+        if reorged_out > 0 {
+            debug!(
+                "Block {:?} reverted, {} wallet transactions reorged out",
+                msg.block_hash, reorged_out
+            );
+            self.persist_history(ctx);
+        }
+    }
+}
+
+/// Handler for TransactionsExpired message
+impl Handler<TransactionsExpired> for WalletManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: TransactionsExpired, ctx: &mut Context<Self>) {
+        let expired = self.history.expire(&msg.transactions);
+        if expired > 0 {
+            info!(
+                "{} wallet transactions expired without being confirmed",
+                expired
+            );
+            self.persist_history(ctx);
+        }
+    }
+}
+
+/// Handler for TransactionResubmitted message
+impl Handler<TransactionResubmitted> for WalletManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: TransactionResubmitted, ctx: &mut Context<Self>) {
+        if self.history.resubmit(msg.previous_hash, msg.hash) > 0 {
+            debug!(
+                "Wallet transaction {:?} submitted again as {:?}",
+                msg.previous_hash, msg.hash
+            );
+            self.persist_history(ctx);
+        }
+    }
+}
+
+/// Handler for RevertConfirmationsSince message
+impl Handler<RevertConfirmationsSince> for WalletManager {
+    type Result = ();
+
+    fn handle(&mut self, msg: RevertConfirmationsSince, ctx: &mut Context<Self>) {
+        let reverted = self.history.revert_since(msg.checkpoint);
+        debug!(
+            "{} wallet transactions confirmed since checkpoint {} are pending again",
+            reverted, msg.checkpoint
+        );
+        self.persist_history(ctx);
+    }
+}
+
+/// Handler for GetAddresses message
+impl Handler<GetAddresses> for WalletManager {
+    type Result = Result<Vec<PublicKeyHash>, WalletManagerError>;
+
+    fn handle(&mut self, msg: GetAddresses, _ctx: &mut Context<Self>) -> Self::Result {
+        let mut addresses = self.accounts.get(msg.account)?.addresses.clone();
+        addresses.extend(self.watched_addresses.of_account(msg.account));
+
+        Ok(addresses)
+    }
+}
+
+/// Handler for GetTransactionHistory message
+impl Handler<GetTransactionHistory> for WalletManager {
+    type Result = Result<Vec<HistoryItem>, WalletManagerError>;
+
+    fn handle(&mut self, msg: GetTransactionHistory, _ctx: &mut Context<Self>) -> Self::Result {
+        self.accounts.get(msg.account)?;
+
+        if msg.limit == 0 || msg.limit > MAX_HISTORY_PAGE_SIZE {
+            return Err(WalletManagerError::InvalidPageSize);
+        }
+
+        Ok(self.history.get_page(msg.account, msg.offset, msg.limit))
+    }
+}
+
+/// Handler for CreateWallet message
+impl Handler<CreateWallet> for WalletManager {
+    type Result = Result<String, WalletManagerError>;
+
+    fn handle(&mut self, msg: CreateWallet, ctx: &mut Context<Self>) -> Self::Result {
+        let mnemonic = Mnemonic::generate();
+        self.replace_keys(
+            ctx,
+            &KeysSecrets::from_mnemonic(&mnemonic),
+            &msg.password,
+            msg.current_password.as_ref().map(String::as_str),
+            msg.overwrite,
+        )?;
+        info!("Created new wallet keys");
+
+        Ok(mnemonic.phrase().to_string())
+    }
+}
+
+/// Handler for ImportMnemonic message
+impl Handler<ImportMnemonic> for WalletManager {
+    type Result = Result<(), WalletManagerError>;
+
+    fn handle(&mut self, msg: ImportMnemonic, ctx: &mut Context<Self>) -> Self::Result {
+        let mnemonic = Mnemonic::from_phrase(&msg.mnemonic)?;
+        self.replace_keys(
+            ctx,
+            &KeysSecrets::from_mnemonic(&mnemonic),
+            &msg.password,
+            msg.current_password.as_ref().map(String::as_str),
+            msg.overwrite,
+        )?;
+        info!("Restored wallet keys from a mnemonic, rescanning the consolidated blocks");
+        self.rescan(ctx);
+
+        Ok(())
+    }
+}
+
+/// Handler for ExportMasterKey message
+impl Handler<ExportMasterKey> for WalletManager {
+    type Result = Result<KeysBackup, WalletManagerError>;
+
+    fn handle(&mut self, msg: ExportMasterKey, _ctx: &mut Context<Self>) -> Self::Result {
+        if self.external_signer {
+            return Err(WalletManagerError::ExternalSigner);
+        }
+
+        self.keys
+            .as_ref()
+            .ok_or(WalletManagerError::NoSigner)?
+            .backup(&msg.password)
+    }
+}
+
+/// Handler for SetWalletPassword message
+impl Handler<SetWalletPassword> for WalletManager {
+    type Result = Result<(), WalletManagerError>;
+
+    fn handle(&mut self, msg: SetWalletPassword, ctx: &mut Context<Self>) -> Self::Result {
+        self.set_password(
+            ctx,
+            &msg.password,
+            msg.current_password.as_ref().map(String::as_str),
+        )?;
+        info!("Set the password of the wallet keys");
+
+        Ok(())
+    }
+}
+
+/// Handler for UnlockWallet message
+impl Handler<UnlockWallet> for WalletManager {
+    type Result = Result<(), WalletManagerError>;
+
+    fn handle(&mut self, msg: UnlockWallet, _ctx: &mut Context<Self>) -> Self::Result {
+        self.unlock_keys(&msg.password)?;
+        info!("Unlocked the wallet keys");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+use actix::Message;
+
+use crate::actors::wallet_manager::{
+    Account, HistoryItem, KeysBackup, PartiallySignedTransaction, SignedTransaction,
+    WalletManagerError,
+};
+use witnet_data_structures::chain::{
+    DataRequestOutput, Epoch, Hash, PublicKeyHash, ValueTransferOutput,
+};
+
+/// Create a new account in the wallet
+pub struct CreateAccount {
+    /// Name of the account
+    pub name: String,
+}
+
+impl Message for CreateAccount {
+    type Result = Result<Account, WalletManagerError>;
+}
+
+/// Ask for the list of accounts of the wallet
+pub struct GetAccounts;
+
+impl Message for GetAccounts {
+    type Result = Result<Vec<Account>, WalletManagerError>;
+}
+
+/// Import a watch-only address, whose private key is not held by the wallet
+pub struct ImportAddress {
+    /// Address
+    pub address: PublicKeyHash,
+    /// Account to which the transactions of the address are assigned
+    pub account: u32,
+    /// Label of the address
+    pub label: String,
+}
+
+impl Message for ImportAddress {
+    type Result = Result<(), WalletManagerError>;
+}
+
+/// Add a new pending transaction to the history of an account
+pub struct AddPendingTransaction {
+    /// Account which owns the transaction
+    pub account: u32,
+    /// Hash of the transaction
+    pub hash: Hash,
+}
+
+impl Message for AddPendingTransaction {
+    type Result = Result<(), WalletManagerError>;
+}
+
+/// Derive a new address from the next signing key of an account
+pub struct CreateAddress {
+    /// Account
+    pub account: u32,
+}
+
+impl Message for CreateAddress {
+    type Result = Result<PublicKeyHash, WalletManagerError>;
+}
+
+/// Pay some value to several outputs with the balance of an account and add the value transfer
+/// to its history as pending
+pub struct CreateValueTransfer {
+    /// Account which pays for the value transfer
+    pub account: u32,
+    /// Outputs of the value transfer
+    pub outputs: Vec<ValueTransferOutput>,
+    /// Fee of the value transfer
+    pub fee: u64,
+}
+
+impl Message for CreateValueTransfer {
+    type Result = Result<Hash, WalletManagerError>;
+}
+
+/// Fund a data request with the balance of an account and add it to its history as pending
+pub struct FundDataRequest {
+    /// Account which pays for the data request
+    pub account: u32,
+    /// Data request
+    pub data_request: DataRequestOutput,
+}
+
+impl Message for FundDataRequest {
+    type Result = Result<Hash, WalletManagerError>;
+}
+
+/// Sign a transaction spending a multi-signature output with a key of an account, adding the
+/// signature to the ones collected so far
+pub struct SignTransaction {
+    /// Account
+    pub account: u32,
+    /// Index of the signing key in the account
+    pub key_index: u32,
+    /// Transaction to sign
+    pub transaction: PartiallySignedTransaction,
+}
+
+impl Message for SignTransaction {
+    type Result = Result<SignedTransaction, WalletManagerError>;
+}
+
+/// Notify a new consolidated block
+pub struct BlockConsolidated {
+    /// Hash of the block
+    pub block_hash: Hash,
+    /// Checkpoint of the block
+    pub checkpoint: Epoch,
+    /// Hashes of the transactions included in the block
+    pub transactions: Vec<Hash>,
+}
+
+impl Message for BlockConsolidated {
+    type Result = ();
+}
+
+/// Notify that a consolidated block has been reverted
+pub struct BlockReverted {
+    /// Hash of the block
+    pub block_hash: Hash,
+}
+
+impl Message for BlockReverted {
+    type Result = ();
+}
+
+/// Notify that some transactions originated by this node have been dropped from the mempool
+/// without being confirmed
+pub struct TransactionsExpired {
+    /// Hashes of the expired transactions
+    pub transactions: Vec<Hash>,
+}
+
+impl Message for TransactionsExpired {
+    type Result = ();
+}
+
+/// Notify that a transaction originated by this node has been submitted again
+pub struct TransactionResubmitted {
+    /// Hash of the transaction as it was submitted before
+    pub previous_hash: Hash,
+    /// Hash of the transaction submitted again
+    pub hash: Hash,
+}
+
+impl Message for TransactionResubmitted {
+    type Result = ();
+}
+
+/// Mark as pending the transactions confirmed since a checkpoint, before the blocks are replayed
+/// by a rescan
+pub struct RevertConfirmationsSince {
+    /// First checkpoint whose confirmations are reverted
+    pub checkpoint: Epoch,
+}
+
+impl Message for RevertConfirmationsSince {
+    type Result = ();
+}
+
+/// Ask for the addresses of an account: the addresses derived from its keys and the watch-only
+/// addresses assigned to it
+pub struct GetAddresses {
+    /// Account
+    pub account: u32,
+}
+
+impl Message for GetAddresses {
+    type Result = Result<Vec<PublicKeyHash>, WalletManagerError>;
+}
+
+/// Ask for a page of the transaction history of an account
+pub struct GetTransactionHistory {
+    /// Account
+    pub account: u32,
+    /// Number of entries to skip, starting from the most recent one
+    pub offset: usize,
+    /// Maximum number of entries to return
+    pub limit: usize,
+}
+
+impl Message for GetTransactionHistory {
+    type Result = Result<Vec<HistoryItem>, WalletManagerError>;
+}
+
+/// Replace the keys of the wallet with new keys derived from a new random mnemonic
+pub struct CreateWallet {
+    /// Password protecting the new keys
+    pub password: String,
+    /// Password of the current keys, required if they are protected by one
+    pub current_password: Option<String>,
+    /// Confirmation to overwrite the current keys, required if they are not protected by a
+    /// password
+    pub overwrite: bool,
+}
+
+impl Message for CreateWallet {
+    /// The mnemonic of the new keys
+    type Result = Result<String, WalletManagerError>;
+}
+
+/// Replace the keys of the wallet with the keys derived from a mnemonic, rescanning the
+/// consolidated blocks to rebuild their transaction history
+pub struct ImportMnemonic {
+    /// Mnemonic
+    pub mnemonic: String,
+    /// Password protecting the restored keys
+    pub password: String,
+    /// Password of the current keys, required if they are protected by one
+    pub current_password: Option<String>,
+    /// Confirmation to overwrite the current keys, required if they are not protected by a
+    /// password
+    pub overwrite: bool,
+}
+
+impl Message for ImportMnemonic {
+    type Result = Result<(), WalletManagerError>;
+}
+
+/// Export the mnemonic and the master key of the wallet
+pub struct ExportMasterKey {
+    /// Password of the keys
+    pub password: String,
+}
+
+impl Message for ExportMasterKey {
+    type Result = Result<KeysBackup, WalletManagerError>;
+}
+
+/// Protect the current keys of the wallet with a password, or change their password, keeping
+/// their transaction history
+pub struct SetWalletPassword {
+    /// New password of the keys
+    pub password: String,
+    /// Password of the current keys, required if they are protected by one
+    pub current_password: Option<String>,
+}
+
+impl Message for SetWalletPassword {
+    type Result = Result<(), WalletManagerError>;
+}
+
+/// Decrypt the keys of the wallet with their password, so they can sign transactions
+pub struct UnlockWallet {
+    /// Password of the keys
+    pub password: String,
+}
+
+impl Message for UnlockWallet {
+    type Result = Result<(), WalletManagerError>;
+}
@@ -0,0 +1,1771 @@
+//! # WalletManager actor
+//!
+//! This module contains the WalletManager actor which is in charge
+//! of keeping track of the transactions that involve the addresses
+//! owned by the node. Among its responsabilities are the following:
+//!
+//! * Managing several named accounts, each one with its own BIP32 derivation branch (`m/44'/4919'/account'`), so a single node can serve several logical users or purposes.
+//! * Deriving new addresses for an account from the external chain of its derivation branch (`m/44'/4919'/account'/0/key_index`), and tracking watch-only addresses, whose private keys are held elsewhere. Both are registered in the [UtxoManager](actors::utxo_manager::UtxoManager) for balance and history tracking. The balance of an account is the balance of its derived and watch-only addresses, split into the available and the time locked value.
+//! * Creating value transfers paid by an account, which are added to its history as pending.
+//! * Signing transactions with the keys of the accounts, derived from a seed which is generated from a new BIP39 mnemonic the first time the wallet is started. Transactions spending multi-signature outputs are signed partially: every owner adds its signature to the same partially signed transaction until there are as many signatures as required.
+//! * Backing up and restoring the keys of the wallet: a new wallet can be created, encrypted with a password, or restored from its mnemonic, and its mnemonic and master key can be exported after re-entering its password. The keys generated the first time the wallet is started have no password until one is set, which keeps their history. Encrypted keys are locked when the node starts, until they are unlocked with their password. Restoring a wallet discards the transaction history of the previous keys and rescans the consolidated blocks to rebuild it.
+//! * Delegating the signatures to an external [Signer](actors::wallet_manager::signer::Signer), such as a hardware wallet or a remote signing service, when one is configured, so the keys never touch the node.
+//! * Keeping a persistent history of the transactions of every account, along with their status:
+//!     - _Pending_: the transaction has been created or received but it has not been included in a consolidated block yet.
+//!     - _Confirmed_: the transaction has been included in a consolidated block. The number of confirmations is the number of checkpoints since that block, including it.
+//!     - _Reorged out_: the block that included the transaction has been reverted.
+//!     - _Expired_: the transaction has been dropped from the mempool without being confirmed. It can be submitted again with a higher fee.
+//! * Updating the status of the transactions from the chain events notified by the [BlocksManager](actors::blocks_manager::BlocksManager).
+//! * Serving paginated queries of the transaction history of an account.
+
+use actix::{
+    ActorFuture, Context, ContextFutureSpawner, Supervised, System, SystemService, WrapFuture,
+};
+use crypto::{
+    aead::{AeadDecryptor, AeadEncryptor},
+    aes::KeySize,
+    aes_gcm::AesGcm,
+    hmac::Hmac,
+    pbkdf2::pbkdf2,
+    sha2::Sha256,
+};
+use log::{debug, error, info};
+use rand::{thread_rng, Rng};
+use secp256k1::{Secp256k1, Verification};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::actors::{
+    blocks_manager::{messages::Rescan, BlocksManager},
+    node,
+    storage_keys::{
+        WALLET_ACCOUNTS_KEY, WALLET_HISTORY_ENTRY_PREFIX, WALLET_HISTORY_HEAD_KEY,
+        WALLET_HISTORY_KEY, WALLET_KEYS_KEY, WALLET_WATCHED_ADDRESSES_KEY,
+    },
+    storage_manager::{
+        messages::{Delete, Put},
+        put_with_retry, StorageManager,
+    },
+    utxo_manager::{messages::WatchAddress, UtxoManager},
+};
+use crate::errors::{NodeError, STORAGE_RETRY_POLICY};
+use witnet_crypto::{
+    hash::{calculate_public_key_hash, calculate_sha256},
+    key::{ExtendedSK, KeyError, KeyPath, HARDENED_INDEX},
+    mnemonic::{Mnemonic, MnemonicError},
+    signature::{verify, verify_multisig, SignatureJob},
+};
+use witnet_data_structures::{
+    chain::{Epoch, Hash, KeyedSignature, MultiSigCondition, PublicKeyHash, ValueTransferOutput},
+    signing::SigningRequest,
+};
+use witnet_storage::{error::StorageError, storage::Storable};
+use witnet_util::error::WitnetError;
+
+mod actor;
+mod handlers;
+
+/// Messages for WalletManager
+pub mod messages;
+
+/// Signers holding the keys of the wallet
+pub mod signer;
+
+use self::signer::{Signer, SignerError, SoftwareSigner};
+
+/// Maximum number of entries that can be returned in a single page of the transaction history
+pub const MAX_HISTORY_PAGE_SIZE: usize = 1000;
+
+/// Purpose of the BIP44 derivation scheme
+pub const BIP44_PURPOSE: u32 = 44;
+
+/// Coin type of Witnet, as registered in SLIP-0044
+pub const WITNET_COIN_TYPE: u32 = 4919;
+
+/// Name of the account which is created by default
+pub const DEFAULT_ACCOUNT_NAME: &str = "default";
+
+/// Length of the seed of the wallet, as derived from its mnemonic, in bytes
+pub const SEED_LENGTH: usize = 64;
+
+/// Number of PBKDF2 iterations used to derive the encryption key of the wallet from its password,
+/// which makes every guess of an attacker holding the encrypted keys as costly as possible while
+/// keeping the unlocking of the wallet below a second. The number of iterations is stored along
+/// with the encrypted keys, so raising it does not affect the keys which are already encrypted.
+pub const PASSWORD_HASH_ITERATIONS: u32 = 100_000;
+
+/// Number of PBKDF2 iterations of the keys encrypted before the number of iterations was stored
+/// along with them
+const LEGACY_PASSWORD_HASH_ITERATIONS: u32 = 10_000;
+
+/// Length of the random salt of the encryption key, in bytes
+const PASSWORD_SALT_LENGTH: usize = 16;
+
+/// Length of the random nonce of the encrypted keys, in bytes
+const KEYS_NONCE_LENGTH: usize = 12;
+
+/// Length of the authentication tag of the encrypted keys, in bytes
+const KEYS_TAG_LENGTH: usize = 16;
+
+/// Possible errors when interacting with WalletManager
+#[derive(Debug)]
+pub enum WalletManagerError {
+    /// The requested page size is zero or bigger than `MAX_HISTORY_PAGE_SIZE`
+    InvalidPageSize,
+    /// There is no account with the given index
+    AccountNotFound,
+    /// There is already an account with the given name
+    DuplicatedAccountName,
+    /// The maximum number of accounts has been reached
+    TooManyAccounts,
+    /// The address has already been imported
+    AddressAlreadyImported,
+    /// The maximum number of addresses of an account has been reached
+    TooManyAddresses,
+    /// The value transfer has no outputs, or some of them have no value
+    InvalidValueTransfer,
+    /// There is no signer yet: the seed of the wallet has not been loaded, or it is encrypted and
+    /// it has not been unlocked
+    NoSigner,
+    /// The multi-signature condition of the transaction is not valid
+    InvalidMultiSigCondition,
+    /// The key is not one of the keys of the multi-signature condition of the transaction
+    KeyNotInCondition,
+    /// The signature returned by the signer is not valid
+    InvalidSignature,
+    /// The keys of the wallet are not protected by a password, so they cannot be exported nor
+    /// unlocked
+    NoPassword,
+    /// The password does not match the password of the wallet
+    WrongPassword,
+    /// The keys of the wallet are not protected by a password, so replacing them must be
+    /// confirmed explicitly
+    ReplaceNotConfirmed,
+    /// The keys of the wallet are held by an external signer, so they cannot be replaced nor
+    /// exported
+    ExternalSigner,
+    /// The mnemonic is not valid
+    InvalidMnemonic(MnemonicError),
+    /// The master key could not be derived from the seed
+    KeyError(KeyError),
+    /// SignerError
+    SignerError(SignerError),
+    /// StorageError
+    StorageError(WitnetError<StorageError>),
+}
+
+impl From<SignerError> for WalletManagerError {
+    fn from(x: SignerError) -> Self {
+        WalletManagerError::SignerError(x)
+    }
+}
+
+impl From<MnemonicError> for WalletManagerError {
+    fn from(x: MnemonicError) -> Self {
+        WalletManagerError::InvalidMnemonic(x)
+    }
+}
+
+impl From<KeyError> for WalletManagerError {
+    fn from(x: KeyError) -> Self {
+        WalletManagerError::KeyError(x)
+    }
+}
+
+impl From<WitnetError<StorageError>> for WalletManagerError {
+    fn from(x: WitnetError<StorageError>) -> Self {
+        WalletManagerError::StorageError(x)
+    }
+}
+
+/// Account of the wallet
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    /// Index of the account, used in its derivation path
+    pub index: u32,
+    /// Name of the account
+    pub name: String,
+    /// Addresses derived from the signing keys of the account, sorted by key index
+    #[serde(default)]
+    pub addresses: Vec<PublicKeyHash>,
+}
+
+impl Account {
+    /// Derivation path of the keys of the account: `m/44'/4919'/index'`
+    pub fn key_path(&self) -> KeyPath {
+        KeyPath::master()
+            .hardened(BIP44_PURPOSE)
+            .hardened(WITNET_COIN_TYPE)
+            .hardened(self.index)
+    }
+
+    /// Derivation path of a signing key of the account, in its external chain:
+    /// `m/44'/4919'/index'/0/key_index`
+    pub fn signing_key_path(&self, key_index: u32) -> KeyPath {
+        self.key_path().child(0).child(key_index)
+    }
+
+    /// Index of the next signing key of the account from which an address can be derived
+    pub fn next_key_index(&self) -> Result<u32, WalletManagerError> {
+        let key_index = self.addresses.len() as u32;
+        if key_index >= HARDENED_INDEX {
+            return Err(WalletManagerError::TooManyAddresses);
+        }
+
+        Ok(key_index)
+    }
+}
+
+/// Persistent list of the accounts of the wallet
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Accounts {
+    /// Accounts, sorted by index
+    accounts: Vec<Account>,
+}
+
+impl Default for Accounts {
+    fn default() -> Self {
+        Accounts {
+            accounts: vec![Account {
+                index: 0,
+                name: DEFAULT_ACCOUNT_NAME.to_string(),
+                addresses: vec![],
+            }],
+        }
+    }
+}
+
+impl Accounts {
+    /// Create a new account with the next unused index
+    pub fn create(&mut self, name: String) -> Result<Account, WalletManagerError> {
+        if self.accounts.iter().any(|account| account.name == name) {
+            return Err(WalletManagerError::DuplicatedAccountName);
+        }
+
+        let index = self.accounts.len() as u32;
+        if index >= HARDENED_INDEX {
+            return Err(WalletManagerError::TooManyAccounts);
+        }
+
+        let account = Account {
+            index,
+            name,
+            addresses: vec![],
+        };
+        self.accounts.push(account.clone());
+
+        Ok(account)
+    }
+
+    /// Get the account with the given index
+    pub fn get(&self, index: u32) -> Result<&Account, WalletManagerError> {
+        self.accounts
+            .get(index as usize)
+            .ok_or(WalletManagerError::AccountNotFound)
+    }
+
+    /// Get all the accounts
+    pub fn get_all(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    /// Add the address derived from the next signing key of an account
+    pub fn add_address(
+        &mut self,
+        index: u32,
+        address: PublicKeyHash,
+    ) -> Result<(), WalletManagerError> {
+        let account = self
+            .accounts
+            .get_mut(index as usize)
+            .ok_or(WalletManagerError::AccountNotFound)?;
+        account.next_key_index()?;
+        account.addresses.push(address);
+
+        Ok(())
+    }
+
+    /// Get the addresses derived by all the accounts
+    pub fn addresses(&self) -> Vec<PublicKeyHash> {
+        self.accounts
+            .iter()
+            .flat_map(|account| account.addresses.iter().cloned())
+            .collect()
+    }
+
+    /// Forget the addresses derived by all the accounts, as they belong to keys which have been
+    /// replaced
+    pub fn clear_addresses(&mut self) {
+        for account in &mut self.accounts {
+            account.addresses.clear();
+        }
+    }
+}
+
+/// Watch-only address: an external address whose private key is not held by the wallet
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WatchedAddress {
+    /// Address
+    pub address: PublicKeyHash,
+    /// Account to which the transactions of the address are assigned
+    pub account: u32,
+    /// Label of the address
+    pub label: String,
+}
+
+/// Persistent list of the watch-only addresses of the wallet
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WatchedAddresses {
+    /// Watch-only addresses, sorted by insertion order
+    addresses: Vec<WatchedAddress>,
+}
+
+impl WatchedAddresses {
+    /// Import a new watch-only address
+    pub fn import(&mut self, watched: WatchedAddress) -> Result<(), WalletManagerError> {
+        if self.account_of(&watched.address).is_some() {
+            return Err(WalletManagerError::AddressAlreadyImported);
+        }
+
+        self.addresses.push(watched);
+
+        Ok(())
+    }
+
+    /// Get the account to which a watch-only address is assigned, if it has been imported
+    pub fn account_of(&self, address: &PublicKeyHash) -> Option<u32> {
+        self.addresses
+            .iter()
+            .find(|watched| watched.address == *address)
+            .map(|watched| watched.account)
+    }
+
+    /// Get the watch-only addresses assigned to an account
+    pub fn of_account(&self, account: u32) -> Vec<PublicKeyHash> {
+        self.addresses
+            .iter()
+            .filter(|watched| watched.account == account)
+            .map(|watched| watched.address)
+            .collect()
+    }
+
+    /// Get all the watch-only addresses
+    pub fn get_all(&self) -> &[WatchedAddress] {
+        &self.addresses
+    }
+}
+
+/// Transaction spending a multi-signature output, along with the signatures of its owners collected
+/// so far
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PartiallySignedTransaction {
+    /// Hash of the transaction, which is the message signed by the owners
+    pub hash: Hash,
+    /// Condition of the spent output
+    pub condition: MultiSigCondition,
+    /// Signatures collected so far
+    #[serde(default)]
+    pub signatures: Vec<KeyedSignature>,
+}
+
+/// Result of signing a partially signed transaction
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    /// Transaction with the new signature
+    pub transaction: PartiallySignedTransaction,
+    /// Whether the transaction has all the valid signatures required by its condition
+    pub complete: bool,
+}
+
+/// Check that a value transfer pays some value to every output and calculate the hash which
+/// identifies it in the history of the account paying for it: the SHA256 hash of the index of the
+/// account, the outputs and the fee, encoded in big endian byte order
+// FIXME(#99): use the hash of the transaction once Transaction is defined
+pub fn value_transfer_hash(
+    account: u32,
+    outputs: &[ValueTransferOutput],
+    fee: u64,
+) -> Result<Hash, WalletManagerError> {
+    if outputs.is_empty() || outputs.iter().any(|output| output.value == 0) {
+        return Err(WalletManagerError::InvalidValueTransfer);
+    }
+
+    let mut bytes = account.to_be_bytes().to_vec();
+    for output in outputs {
+        bytes.extend_from_slice(&output.pkh);
+        bytes.extend_from_slice(&output.value.to_be_bytes());
+        bytes.extend_from_slice(&output.time_lock.to_be_bytes());
+    }
+    bytes.extend_from_slice(&fee.to_be_bytes());
+
+    Ok(calculate_sha256(&bytes))
+}
+
+/// Build the request to sign a partially signed transaction with a key of an account
+pub fn signing_request(
+    account: &Account,
+    key_index: u32,
+    transaction: &PartiallySignedTransaction,
+) -> Result<SigningRequest, WalletManagerError> {
+    if !transaction.condition.is_valid() {
+        return Err(WalletManagerError::InvalidMultiSigCondition);
+    }
+
+    Ok(SigningRequest {
+        key_path: account.signing_key_path(key_index).indexes().to_vec(),
+        hash: transaction.hash,
+        condition: transaction.condition.clone(),
+    })
+}
+
+/// Add the signature returned by a signer to a partially signed transaction, unless the
+/// transaction had already been signed with that key
+pub fn add_signature<C: Verification>(
+    secp: &Secp256k1<C>,
+    mut transaction: PartiallySignedTransaction,
+    keyed: KeyedSignature,
+) -> Result<SignedTransaction, WalletManagerError> {
+    if !transaction
+        .condition
+        .pkhs
+        .contains(&calculate_public_key_hash(&keyed.public_key))
+    {
+        return Err(WalletManagerError::KeyNotInCondition);
+    }
+
+    // External signers are not trusted to sign the right message with the right key
+    let Hash::SHA256(message) = transaction.hash;
+    let job = SignatureJob {
+        public_key: keyed.public_key.clone(),
+        message,
+        signature: keyed.signature.clone(),
+    };
+    verify(secp, &job).map_err(|_| WalletManagerError::InvalidSignature)?;
+
+    if !transaction
+        .signatures
+        .iter()
+        .any(|signed| signed.public_key == keyed.public_key)
+    {
+        transaction.signatures.push(keyed);
+    }
+
+    let complete = verify_multisig(
+        secp,
+        &transaction.condition,
+        &message,
+        &transaction.signatures,
+    )
+    .is_ok();
+
+    Ok(SignedTransaction {
+        transaction,
+        complete,
+    })
+}
+
+/// Derive the encryption key of the wallet keys from a password and a salt, with PBKDF2-HMAC-SHA256
+fn encryption_key(password: &str, salt: &[u8], iterations: u32) -> Vec<u8> {
+    let mut mac = Hmac::new(Sha256::new(), password.as_bytes());
+    let mut key = vec![0; 32];
+    pbkdf2(&mut mac, salt, iterations, &mut key);
+
+    key
+}
+
+/// Number of PBKDF2 iterations of the keys encrypted by older versions of the node
+fn legacy_password_hash_iterations() -> u32 {
+    LEGACY_PASSWORD_HASH_ITERATIONS
+}
+
+/// Secrets of the keys of the wallet: the seed and what is needed to back it up
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KeysSecrets {
+    /// Seed from which the master key is derived
+    seed: Vec<u8>,
+    /// Mnemonic of the seed, unless the seed was generated before mnemonics were supported
+    mnemonic: Option<String>,
+}
+
+impl KeysSecrets {
+    /// Derive the secrets from a mnemonic
+    pub fn from_mnemonic(mnemonic: &Mnemonic) -> Self {
+        KeysSecrets {
+            seed: mnemonic.seed(""),
+            mnemonic: Some(mnemonic.phrase().to_string()),
+        }
+    }
+
+    /// Secrets of a seed without mnemonic, as persisted by older versions of the node
+    pub fn from_legacy_seed(seed: Vec<u8>) -> Self {
+        KeysSecrets {
+            seed,
+            mnemonic: None,
+        }
+    }
+
+    /// Derive the master key from the seed
+    pub fn master_key(&self) -> Result<ExtendedSK, WalletManagerError> {
+        Ok(ExtendedSK::from_seed(&self.seed)?)
+    }
+
+    /// Encrypt the secrets with a password, using a new random salt and nonce
+    pub fn encrypt(&self, password: &str) -> Result<EncryptedSecrets, WalletManagerError> {
+        let mut rng = thread_rng();
+        let mut salt = vec![0; PASSWORD_SALT_LENGTH];
+        rng.fill(&mut salt[..]);
+        let mut nonce = vec![0; KEYS_NONCE_LENGTH];
+        rng.fill(&mut nonce[..]);
+
+        let plaintext = self.to_bytes()?;
+        let mut ciphertext = vec![0; plaintext.len()];
+        let mut tag = vec![0; KEYS_TAG_LENGTH];
+        AesGcm::new(
+            KeySize::KeySize256,
+            &encryption_key(password, &salt, PASSWORD_HASH_ITERATIONS),
+            &nonce,
+            &[],
+        )
+        .encrypt(&plaintext, &mut ciphertext, &mut tag);
+
+        Ok(EncryptedSecrets {
+            salt,
+            nonce,
+            ciphertext,
+            tag,
+            iterations: PASSWORD_HASH_ITERATIONS,
+        })
+    }
+
+    /// Export the mnemonic and the master key
+    pub fn backup(&self) -> Result<KeysBackup, WalletManagerError> {
+        let master_key = self.master_key()?;
+        let master_key = master_key
+            .chain_code()
+            .iter()
+            .chain(master_key.secret_key()[..].iter())
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        Ok(KeysBackup {
+            mnemonic: self.mnemonic.clone(),
+            master_key,
+        })
+    }
+}
+
+/// Secrets of the keys of the wallet, encrypted with AES-256-GCM under a key derived from a
+/// password
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EncryptedSecrets {
+    /// Random salt of the encryption key
+    salt: Vec<u8>,
+    /// Random nonce of the encryption
+    nonce: Vec<u8>,
+    /// Encrypted secrets
+    ciphertext: Vec<u8>,
+    /// Authentication tag of the encrypted secrets
+    tag: Vec<u8>,
+    /// Number of PBKDF2 iterations of the encryption key
+    #[serde(default = "legacy_password_hash_iterations")]
+    iterations: u32,
+}
+
+impl EncryptedSecrets {
+    /// Decrypt the secrets with a password, failing with `WrongPassword` if they were encrypted
+    /// with another one
+    pub fn decrypt(&self, password: &str) -> Result<KeysSecrets, WalletManagerError> {
+        if self.nonce.len() != KEYS_NONCE_LENGTH || self.tag.len() != KEYS_TAG_LENGTH {
+            return Err(WalletManagerError::WrongPassword);
+        }
+
+        let mut plaintext = vec![0; self.ciphertext.len()];
+        let authentic = AesGcm::new(
+            KeySize::KeySize256,
+            &encryption_key(password, &self.salt, self.iterations),
+            &self.nonce,
+            &[],
+        )
+        .decrypt(&self.ciphertext, &mut plaintext, &self.tag);
+        if !authentic {
+            return Err(WalletManagerError::WrongPassword);
+        }
+
+        Ok(KeysSecrets::from_bytes(&plaintext)?)
+    }
+}
+
+/// Persistent keys of the wallet: the keys created the first time the wallet is started are not
+/// protected by a password, while the keys created or restored by the user are encrypted with
+/// their password
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum WalletKeys {
+    /// Keys without password
+    Plain(KeysSecrets),
+    /// Keys encrypted with a password
+    Encrypted(EncryptedSecrets),
+}
+
+/// Backup of the keys of the wallet
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KeysBackup {
+    /// Mnemonic of the seed, if the wallet has one
+    pub mnemonic: Option<String>,
+    /// Master key: its chain code followed by its secret key, hex encoded
+    pub master_key: String,
+}
+
+impl WalletKeys {
+    /// Check that the keys can be replaced: keys protected by a password can only be replaced by
+    /// someone who knows it, and the replacement of keys without password must be confirmed
+    /// explicitly, as they cannot have been backed up
+    pub fn check_replace(
+        &self,
+        password: Option<&str>,
+        overwrite: bool,
+    ) -> Result<(), WalletManagerError> {
+        match (self, password) {
+            (WalletKeys::Plain(_), _) if overwrite => Ok(()),
+            (WalletKeys::Plain(_), _) => Err(WalletManagerError::ReplaceNotConfirmed),
+            (WalletKeys::Encrypted(encrypted), Some(password)) => {
+                encrypted.decrypt(password).map(|_| ())
+            }
+            (WalletKeys::Encrypted(_), None) => Err(WalletManagerError::WrongPassword),
+        }
+    }
+
+    /// Decrypt the secrets of the keys with their password
+    pub fn unlock(&self, password: &str) -> Result<KeysSecrets, WalletManagerError> {
+        match self {
+            WalletKeys::Plain(_) => Err(WalletManagerError::NoPassword),
+            WalletKeys::Encrypted(encrypted) => encrypted.decrypt(password),
+        }
+    }
+
+    /// Export the mnemonic and the master key, after checking the password of the keys
+    pub fn backup(&self, password: &str) -> Result<KeysBackup, WalletManagerError> {
+        self.unlock(password)?.backup()
+    }
+
+    /// Encrypt the same secrets with a new password: keys without password only need the new
+    /// one, while the current password of encrypted keys is checked before changing it
+    /// Returns the decrypted secrets along with the newly encrypted keys
+    pub fn set_password(
+        &self,
+        password: &str,
+        current_password: Option<&str>,
+    ) -> Result<(KeysSecrets, WalletKeys), WalletManagerError> {
+        let secrets = match (self, current_password) {
+            (WalletKeys::Plain(secrets), _) => secrets.clone(),
+            (WalletKeys::Encrypted(encrypted), Some(current_password)) => {
+                encrypted.decrypt(current_password)?
+            }
+            (WalletKeys::Encrypted(_), None) => return Err(WalletManagerError::WrongPassword),
+        };
+        let keys = WalletKeys::Encrypted(secrets.encrypt(password)?);
+
+        Ok((secrets, keys))
+    }
+}
+
+/// Status of a transaction of the wallet
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TransactionStatus {
+    /// The transaction has not been included in a consolidated block yet
+    #[serde(rename = "pending")]
+    Pending,
+    /// The transaction has been included in a consolidated block
+    #[serde(rename = "confirmed")]
+    Confirmed {
+        /// Hash of the block which includes the transaction
+        block_hash: Hash,
+        /// Checkpoint of the block which includes the transaction
+        checkpoint: Epoch,
+    },
+    /// The block which included the transaction has been reverted
+    #[serde(rename = "reorged_out")]
+    ReorgedOut,
+    /// The transaction has been dropped from the mempool without being confirmed
+    #[serde(rename = "expired")]
+    Expired,
+}
+
+/// Entry of the transaction history
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Account which owns the transaction
+    pub account: u32,
+    /// Hash of the transaction
+    pub hash: Hash,
+    /// Status of the transaction
+    pub status: TransactionStatus,
+}
+
+/// Entry of the transaction history along with its number of confirmations
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HistoryItem {
+    /// Hash of the transaction
+    pub hash: Hash,
+    /// Status of the transaction
+    pub status: TransactionStatus,
+    /// Number of confirmations (0 if the transaction is not confirmed)
+    pub confirmations: u32,
+}
+
+/// Summary of the persisted transaction history. Every entry of the history is persisted under
+/// its own key (see `history_entry_key`), so only the entries which change are written again
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HistoryHead {
+    /// Number of entries of the history
+    pub len: u32,
+    /// Checkpoint of the last consolidated block
+    pub tip_checkpoint: Option<Epoch>,
+}
+
+/// Storage key of the entry of the transaction history at the given position
+pub fn history_entry_key(index: u32) -> Vec<u8> {
+    let mut key = WALLET_HISTORY_ENTRY_PREFIX.to_vec();
+    key.extend_from_slice(&index.to_be_bytes());
+
+    key
+}
+
+/// Persistent history of the transactions of the wallet
+/// Older versions of the node persisted the whole history under a single key
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionHistory {
+    /// Entries of the history, sorted by insertion order
+    entries: Vec<HistoryEntry>,
+    /// Checkpoint of the last consolidated block
+    tip_checkpoint: Option<Epoch>,
+    /// Positions of the entries changed since the history was last persisted
+    #[serde(skip)]
+    changed: BTreeSet<usize>,
+}
+
+impl TransactionHistory {
+    /// Rebuild the history from its persisted head and entries
+    pub fn from_parts(head: &HistoryHead, entries: Vec<HistoryEntry>) -> Self {
+        TransactionHistory {
+            entries,
+            tip_checkpoint: head.tip_checkpoint,
+            changed: BTreeSet::new(),
+        }
+    }
+
+    /// Summary of the history to be persisted along with the changed entries
+    pub fn head(&self) -> HistoryHead {
+        HistoryHead {
+            len: self.entries.len() as u32,
+            tip_checkpoint: self.tip_checkpoint,
+        }
+    }
+
+    /// Mark every entry as changed, so the whole history is persisted again
+    pub fn mark_all_changed(&mut self) {
+        self.changed = (0..self.entries.len()).collect();
+    }
+
+    /// Take the entries changed since the history was last persisted, along with their positions
+    pub fn take_changed(&mut self) -> Vec<(u32, HistoryEntry)> {
+        let changed = std::mem::replace(&mut self.changed, BTreeSet::new());
+
+        changed
+            .into_iter()
+            .filter_map(|index| {
+                self.entries
+                    .get(index)
+                    .map(|entry| (index as u32, entry.clone()))
+            })
+            .collect()
+    }
+
+    /// Add a new pending transaction to the history of an account.
+    /// Returns false if the transaction was already in the history.
+    pub fn add_pending(&mut self, account: u32, hash: Hash) -> bool {
+        if self
+            .entries
+            .iter()
+            .any(|entry| entry.account == account && entry.hash == hash)
+        {
+            return false;
+        }
+
+        self.changed.insert(self.entries.len());
+        self.entries.push(HistoryEntry {
+            account,
+            hash,
+            status: TransactionStatus::Pending,
+        });
+
+        true
+    }
+
+    /// Mark as confirmed the transactions included in a new consolidated block.
+    /// Returns the number of updated entries.
+    pub fn confirm_block(
+        &mut self,
+        block_hash: Hash,
+        checkpoint: Epoch,
+        transactions: &[Hash],
+    ) -> usize {
+        self.tip_checkpoint = Some(
+            self.tip_checkpoint
+                .map_or(checkpoint, |tip| tip.max(checkpoint)),
+        );
+
+        let mut updated = 0;
+        for (index, entry) in self
+            .entries
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, entry)| transactions.contains(&entry.hash))
+        {
+            entry.status = TransactionStatus::Confirmed {
+                block_hash,
+                checkpoint,
+            };
+            self.changed.insert(index);
+            updated += 1;
+        }
+
+        updated
+    }
+
+    /// Mark as reorged out the transactions included in a reverted block.
+    /// Returns the number of updated entries.
+    pub fn revert_block(&mut self, block_hash: Hash) -> usize {
+        let mut updated = 0;
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            match entry.status {
+                TransactionStatus::Confirmed { block_hash: h, .. } if h == block_hash => {
+                    entry.status = TransactionStatus::ReorgedOut;
+                    self.changed.insert(index);
+                    updated += 1;
+                }
+                _ => {}
+            }
+        }
+
+        updated
+    }
+
+    /// Mark as expired the pending transactions dropped from the mempool.
+    /// Returns the number of updated entries.
+    pub fn expire(&mut self, transactions: &[Hash]) -> usize {
+        let mut updated = 0;
+        for (index, entry) in self
+            .entries
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, entry)| transactions.contains(&entry.hash))
+        {
+            if entry.status == TransactionStatus::Pending {
+                entry.status = TransactionStatus::Expired;
+                self.changed.insert(index);
+                updated += 1;
+            }
+        }
+
+        updated
+    }
+
+    /// Replace a transaction which has been submitted again (e.g. with a higher fee) with the new
+    /// one, which is pending.
+    /// Returns the number of updated entries.
+    pub fn resubmit(&mut self, previous_hash: Hash, hash: Hash) -> usize {
+        let mut updated = 0;
+        for (index, entry) in self.entries.iter_mut().enumerate().filter(|(_, entry)| {
+            entry.hash == previous_hash
+                && match entry.status {
+                    TransactionStatus::Pending | TransactionStatus::Expired => true,
+                    _ => false,
+                }
+        }) {
+            entry.hash = hash;
+            entry.status = TransactionStatus::Pending;
+            self.changed.insert(index);
+            updated += 1;
+        }
+
+        updated
+    }
+
+    /// Mark as pending the transactions confirmed since a checkpoint, moving the tip back to the
+    /// previous checkpoint, so the blocks can be replayed.
+    /// Returns the number of updated entries.
+    pub fn revert_since(&mut self, since: Epoch) -> usize {
+        self.tip_checkpoint = match self.tip_checkpoint {
+            Some(tip) if tip >= since => since.checked_sub(1),
+            tip => tip,
+        };
+
+        let mut updated = 0;
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            match entry.status {
+                TransactionStatus::Confirmed { checkpoint, .. } if checkpoint >= since => {
+                    entry.status = TransactionStatus::Pending;
+                    self.changed.insert(index);
+                    updated += 1;
+                }
+                _ => {}
+            }
+        }
+
+        updated
+    }
+
+    /// Number of confirmations of a transaction with the given status
+    pub fn confirmations(&self, status: &TransactionStatus) -> u32 {
+        match (status, self.tip_checkpoint) {
+            (TransactionStatus::Confirmed { checkpoint, .. }, Some(tip)) if tip >= *checkpoint => {
+                tip - checkpoint + 1
+            }
+            _ => 0,
+        }
+    }
+
+    /// Get a page of the transaction history of an account, most recent transactions first
+    pub fn get_page(&self, account: u32, offset: usize, limit: usize) -> Vec<HistoryItem> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.account == account)
+            .skip(offset)
+            .take(limit)
+            .map(|entry| HistoryItem {
+                hash: entry.hash,
+                status: entry.status.clone(),
+                confirmations: self.confirmations(&entry.status),
+            })
+            .collect()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////
+// ACTOR BASIC STRUCTURE
+////////////////////////////////////////////////////////////////////////////////////////
+/// WalletManager actor
+#[derive(Default)]
+pub struct WalletManager {
+    /// Accounts of the wallet
+    accounts: Accounts,
+    /// Watch-only addresses of the wallet
+    watched_addresses: WatchedAddresses,
+    /// Transaction history of the accounts of the wallet
+    history: TransactionHistory,
+    /// Signer holding the keys of the wallet
+    signer: Option<Box<dyn Signer>>,
+    /// Keys of the wallet, once loaded (unless an external signer holds them)
+    keys: Option<WalletKeys>,
+    /// Whether the keys of the wallet are held by an external signer
+    external_signer: bool,
+}
+
+/// Make the WalletManager a Supervisor, which provides the ability to be restarted
+impl Supervised for WalletManager {}
+
+/// Required trait for being able to retrieve WalletManager address from registry
+impl SystemService for WalletManager {}
+
+/// Auxiliary methods for WalletManager actor
+impl WalletManager {
+    /// Method to persist the accounts into storage
+    fn persist_accounts(&self, ctx: &mut Context<Self>) {
+        self.persist(ctx, WALLET_ACCOUNTS_KEY, &self.accounts);
+    }
+
+    /// Method to persist the watch-only addresses into storage
+    fn persist_watched_addresses(&self, ctx: &mut Context<Self>) {
+        self.persist(ctx, WALLET_WATCHED_ADDRESSES_KEY, &self.watched_addresses);
+    }
+
+    /// Method to register the derived and watch-only addresses in the UtxoManager
+    fn watch_addresses(&self, addresses: &[PublicKeyHash]) {
+        // Get UtxoManager address
+        let utxo_manager_addr = System::current().registry().get::<UtxoManager>();
+
+        for address in addresses {
+            utxo_manager_addr.do_send(WatchAddress { address: *address });
+        }
+    }
+
+    /// Method to persist the changes of the transaction history into storage: the changed
+    /// entries first and then the head. The writes are not waited for, so the chain events keep
+    /// being processed meanwhile
+    fn persist_history(&mut self, ctx: &mut Context<Self>) {
+        let mut msgs = vec![];
+        for (index, entry) in self.history.take_changed() {
+            match Put::from_value(history_entry_key(index), &entry) {
+                Ok(msg) => msgs.push(msg),
+                Err(e) => {
+                    error!(
+                        "Error when serializing wallet history entry {}: {}",
+                        index, e
+                    );
+                    node::escalate(&NodeError::from(e));
+                    return;
+                }
+            }
+        }
+        match Put::from_value(WALLET_HISTORY_HEAD_KEY, &self.history.head()) {
+            Ok(msg) => msgs.push(msg),
+            Err(e) => {
+                error!("Error when serializing wallet history head: {}", e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        }
+
+        for msg in msgs {
+            let key_name = String::from_utf8_lossy(&msg.key).into_owned();
+            put_with_retry(msg, STORAGE_RETRY_POLICY)
+                .into_actor(self)
+                .then(move |res, _act, _ctx| {
+                    if let Err(e) = res {
+                        error!(
+                            "WalletManager failed to persist {} into storage: {}",
+                            key_name, e
+                        );
+                        node::escalate(&e);
+                    }
+                    actix::fut::ok(())
+                })
+                .spawn(ctx);
+        }
+    }
+
+    /// Method to persist the transaction history read from the single key used by older versions
+    /// of the node in the current format, deleting the old value once it has been persisted
+    fn migrate_legacy_history(&mut self, ctx: &mut Context<Self>) {
+        self.history.mark_all_changed();
+        self.persist_history(ctx);
+
+        // The old value is only deleted once the head has been persisted, so the history cannot
+        // be lost if the node stops in the meantime
+        let msg = match Put::from_value(WALLET_HISTORY_HEAD_KEY, &self.history.head()) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error when serializing wallet history head: {}", e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+        put_with_retry(msg, STORAGE_RETRY_POLICY)
+            .into_actor(self)
+            .then(|res, _act, _ctx| {
+                match res {
+                    Ok(()) => {
+                        info!("Wallet transaction history migrated to the current format");
+                        System::current()
+                            .registry()
+                            .get::<StorageManager>()
+                            .do_send(Delete::new(WALLET_HISTORY_KEY));
+                    }
+                    Err(e) => {
+                        error!("WalletManager failed to migrate the wallet history: {}", e);
+                        node::escalate(&e);
+                    }
+                }
+                actix::fut::ok(())
+            })
+            .spawn(ctx);
+    }
+
+    /// Method to load the keys of the wallet. The master key is derived from keys without
+    /// password and signs right away, while encrypted keys are kept locked until they are
+    /// unlocked with their password.
+    fn load_keys(&mut self, keys: WalletKeys) {
+        match &keys {
+            WalletKeys::Plain(secrets) => match secrets.master_key() {
+                Ok(master_key) => {
+                    self.signer = Some(Box::new(SoftwareSigner::new(master_key)));
+                    self.keys = Some(keys);
+                }
+                Err(e) => error!("Invalid wallet seed: {:?}", e),
+            },
+            WalletKeys::Encrypted(_) => {
+                info!("The wallet keys are encrypted, unlock them with their password to sign");
+                self.keys = Some(keys);
+            }
+        }
+    }
+
+    /// Method to decrypt the keys of the wallet with their password and sign with them
+    fn unlock_keys(&mut self, password: &str) -> Result<(), WalletManagerError> {
+        if self.external_signer {
+            return Err(WalletManagerError::ExternalSigner);
+        }
+        let secrets = self
+            .keys
+            .as_ref()
+            .ok_or(WalletManagerError::NoSigner)?
+            .unlock(password)?;
+
+        let master_key = secrets.master_key()?;
+        self.signer = Some(Box::new(SoftwareSigner::new(master_key)));
+
+        Ok(())
+    }
+
+    /// Method to generate new keys for the wallet from a new mnemonic, without password, and
+    /// persist them into storage
+    fn create_keys(&mut self, ctx: &mut Context<Self>) {
+        let secrets = KeysSecrets::from_mnemonic(&Mnemonic::generate());
+        self.load_keys(WalletKeys::Plain(secrets));
+        self.persist_keys(ctx);
+    }
+
+    /// Method to persist the keys into storage
+    fn persist_keys(&self, ctx: &mut Context<Self>) {
+        if let Some(keys) = &self.keys {
+            self.persist(ctx, WALLET_KEYS_KEY, keys);
+        }
+    }
+
+    /// Method to replace the keys of the wallet with new secrets encrypted with a password,
+    /// checking the password of the current keys or, if they have none, the confirmation to
+    /// overwrite them. The transaction history and the derived addresses of the current keys are
+    /// discarded.
+    fn replace_keys(
+        &mut self,
+        ctx: &mut Context<Self>,
+        secrets: &KeysSecrets,
+        password: &str,
+        current_password: Option<&str>,
+        overwrite: bool,
+    ) -> Result<(), WalletManagerError> {
+        if self.external_signer {
+            return Err(WalletManagerError::ExternalSigner);
+        }
+        // Never replace keys which have not been loaded yet, as they would be lost
+        self.keys
+            .as_ref()
+            .ok_or(WalletManagerError::NoSigner)?
+            .check_replace(current_password, overwrite)?;
+
+        let master_key = secrets.master_key()?;
+        let keys = WalletKeys::Encrypted(secrets.encrypt(password)?);
+        self.signer = Some(Box::new(SoftwareSigner::new(master_key)));
+        self.keys = Some(keys);
+        self.persist_keys(ctx);
+
+        self.history = TransactionHistory::default();
+        self.persist_history(ctx);
+        self.accounts.clear_addresses();
+        self.persist_accounts(ctx);
+
+        Ok(())
+    }
+
+    /// Method to protect the current keys of the wallet with a password, or to change it, keeping
+    /// their transaction history and derived addresses. The keys are unlocked afterwards.
+    fn set_password(
+        &mut self,
+        ctx: &mut Context<Self>,
+        password: &str,
+        current_password: Option<&str>,
+    ) -> Result<(), WalletManagerError> {
+        if self.external_signer {
+            return Err(WalletManagerError::ExternalSigner);
+        }
+        let (secrets, keys) = self
+            .keys
+            .as_ref()
+            .ok_or(WalletManagerError::NoSigner)?
+            .set_password(password, current_password)?;
+
+        let master_key = secrets.master_key()?;
+        self.signer = Some(Box::new(SoftwareSigner::new(master_key)));
+        self.keys = Some(keys);
+        self.persist_keys(ctx);
+
+        Ok(())
+    }
+
+    /// Method to ask the BlocksManager to rescan all the consolidated blocks, so the transaction
+    /// history of restored keys is rebuilt
+    fn rescan(&self, ctx: &mut Context<Self>) {
+        System::current()
+            .registry()
+            .get::<BlocksManager>()
+            .send(Rescan { from_epoch: 0 })
+            .into_actor(self)
+            .then(|res, _act, _ctx| {
+                match res {
+                    Ok(Ok(status)) => debug!("Rescanning {} blocks", status.total),
+                    Ok(Err(e)) => error!(
+                        "The consolidated blocks could not be rescanned ({:?}), run a rescan \
+                         once the current one finishes",
+                        e
+                    ),
+                    Err(e) => error!("Unsuccessful communication with blocks manager: {}", e),
+                }
+                actix::fut::ok(())
+            })
+            .spawn(ctx);
+    }
+
+    /// Method to persist a value into storage
+    fn persist<T: Storable>(&self, ctx: &mut Context<Self>, key: &'static [u8], value: &T) {
+        let key_name = String::from_utf8_lossy(key);
+        let msg = match Put::from_value(key, value) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error when serializing {}: {}", key_name, e);
+                node::escalate(&NodeError::from(e));
+                return;
+            }
+        };
+
+        put_with_retry(msg, STORAGE_RETRY_POLICY)
+            .into_actor(self)
+            .then(move |res, _act, _ctx| {
+                match res {
+                    Ok(()) => info!(
+                        "WalletManager successfully persisted {} into storage",
+                        key_name
+                    ),
+                    Err(e) => {
+                        error!(
+                            "WalletManager failed to persist {} into storage: {}",
+                            key_name, e
+                        );
+                        node::escalate(&e);
+                    }
+                }
+                actix::fut::ok(())
+            })
+            .wait(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secp256k1::PublicKey;
+
+    #[test]
+    fn default_account() {
+        let accounts = Accounts::default();
+        assert_eq!(
+            accounts.get_all(),
+            &[Account {
+                index: 0,
+                name: DEFAULT_ACCOUNT_NAME.to_string(),
+                addresses: vec![],
+            }]
+        );
+        assert_eq!(
+            accounts.get(0).unwrap().key_path().to_string(),
+            "m/44'/4919'/0'"
+        );
+    }
+
+    #[test]
+    fn create_accounts() {
+        let mut accounts = Accounts::default();
+
+        let account = accounts.create("savings".to_string()).unwrap();
+        assert_eq!(account.index, 1);
+        assert_eq!(account.key_path().to_string(), "m/44'/4919'/1'");
+        assert_eq!(accounts.get(1).unwrap(), &account);
+
+        // Account names must be unique
+        match accounts.create("savings".to_string()) {
+            Err(WalletManagerError::DuplicatedAccountName) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match accounts.get(2) {
+            Err(WalletManagerError::AccountNotFound) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn account_addresses() {
+        let mut accounts = Accounts::default();
+        accounts.create("savings".to_string()).unwrap();
+
+        assert_eq!(accounts.get(1).unwrap().next_key_index().unwrap(), 0);
+        accounts.add_address(1, [1; 20]).unwrap();
+        accounts.add_address(1, [2; 20]).unwrap();
+        accounts.add_address(0, [3; 20]).unwrap();
+        assert_eq!(accounts.get(1).unwrap().addresses, vec![[1; 20], [2; 20]]);
+        assert_eq!(accounts.get(1).unwrap().next_key_index().unwrap(), 2);
+        assert_eq!(accounts.addresses(), vec![[3; 20], [1; 20], [2; 20]]);
+        match accounts.add_address(2, [4; 20]) {
+            Err(WalletManagerError::AccountNotFound) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // The addresses of replaced keys are forgotten
+        accounts.clear_addresses();
+        assert!(accounts.addresses().is_empty());
+        assert_eq!(accounts.get(1).unwrap().next_key_index().unwrap(), 0);
+    }
+
+    #[test]
+    fn value_transfers() {
+        let output = ValueTransferOutput {
+            pkh: [1; 20],
+            value: 100,
+            time_lock: 0,
+        };
+
+        let hash = value_transfer_hash(0, &[output.clone()], 1).unwrap();
+        assert_eq!(value_transfer_hash(0, &[output.clone()], 1).unwrap(), hash);
+        assert_ne!(value_transfer_hash(1, &[output.clone()], 1).unwrap(), hash);
+        assert_ne!(value_transfer_hash(0, &[output.clone()], 2).unwrap(), hash);
+
+        // Value transfers must pay some value to every output
+        match value_transfer_hash(0, &[], 1) {
+            Err(WalletManagerError::InvalidValueTransfer) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match value_transfer_hash(
+            0,
+            &[output.clone(), ValueTransferOutput { value: 0, ..output }],
+            1,
+        ) {
+            Err(WalletManagerError::InvalidValueTransfer) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn import_watched_addresses() {
+        let mut watched_addresses = WatchedAddresses::default();
+        let watched = WatchedAddress {
+            address: [1; 20],
+            account: 2,
+            label: "exchange".to_string(),
+        };
+
+        assert_eq!(watched_addresses.account_of(&[1; 20]), None);
+        watched_addresses.import(watched.clone()).unwrap();
+        assert_eq!(watched_addresses.account_of(&[1; 20]), Some(2));
+        assert_eq!(watched_addresses.get_all(), &[watched.clone()]);
+        assert_eq!(watched_addresses.of_account(2), vec![[1; 20]]);
+        assert!(watched_addresses.of_account(0).is_empty());
+
+        // The same address cannot be imported twice, even to a different account
+        match watched_addresses.import(WatchedAddress {
+            account: 0,
+            ..watched
+        }) {
+            Err(WalletManagerError::AddressAlreadyImported) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn add_pending_transaction() {
+        let mut history = TransactionHistory::default();
+        let hash = Hash::SHA256([1; 32]);
+
+        assert!(history.add_pending(0, hash));
+        // The same transaction cannot be added twice to the same account
+        assert!(!history.add_pending(0, hash));
+        // But it can belong to different accounts
+        assert!(history.add_pending(1, hash));
+
+        assert_eq!(
+            history.get_page(0, 0, 10),
+            vec![HistoryItem {
+                hash,
+                status: TransactionStatus::Pending,
+                confirmations: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn confirmations_increase_with_new_blocks() {
+        let mut history = TransactionHistory::default();
+        let tx_hash = Hash::SHA256([1; 32]);
+        let block_hash = Hash::SHA256([2; 32]);
+        history.add_pending(0, tx_hash);
+
+        assert_eq!(history.confirm_block(block_hash, 5, &[tx_hash]), 1);
+        assert_eq!(history.get_page(0, 0, 10)[0].confirmations, 1);
+
+        // A new block without wallet transactions
+        assert_eq!(history.confirm_block(Hash::SHA256([3; 32]), 7, &[]), 0);
+        let page = history.get_page(0, 0, 10);
+        assert_eq!(
+            page[0].status,
+            TransactionStatus::Confirmed {
+                block_hash,
+                checkpoint: 5
+            }
+        );
+        assert_eq!(page[0].confirmations, 3);
+    }
+
+    #[test]
+    fn reverted_block_reorgs_out_transactions() {
+        let mut history = TransactionHistory::default();
+        let tx_hash = Hash::SHA256([1; 32]);
+        let block_hash = Hash::SHA256([2; 32]);
+        history.add_pending(0, tx_hash);
+        history.confirm_block(block_hash, 5, &[tx_hash]);
+
+        // Reverting an unrelated block does not change anything
+        assert_eq!(history.revert_block(Hash::SHA256([3; 32])), 0);
+        assert_eq!(history.revert_block(block_hash), 1);
+
+        let page = history.get_page(0, 0, 10);
+        assert_eq!(page[0].status, TransactionStatus::ReorgedOut);
+        assert_eq!(page[0].confirmations, 0);
+    }
+
+    #[test]
+    fn revert_confirmations_since() {
+        let mut history = TransactionHistory::default();
+        let old_tx = Hash::SHA256([1; 32]);
+        let new_tx = Hash::SHA256([2; 32]);
+        history.add_pending(0, old_tx);
+        history.add_pending(0, new_tx);
+        history.confirm_block(Hash::SHA256([3; 32]), 2, &[old_tx]);
+        history.confirm_block(Hash::SHA256([4; 32]), 5, &[new_tx]);
+
+        assert_eq!(history.revert_since(4), 1);
+        assert_eq!(history.tip_checkpoint, Some(3));
+        let page = history.get_page(0, 0, 10);
+        assert_eq!(page[0].status, TransactionStatus::Pending);
+        assert_eq!(page[1].confirmations, 2);
+
+        // Replaying the block confirms the transaction again
+        history.confirm_block(Hash::SHA256([4; 32]), 5, &[new_tx]);
+        assert_eq!(history.get_page(0, 0, 10)[0].confirmations, 1);
+
+        assert_eq!(history.revert_since(0), 2);
+        assert_eq!(history.tip_checkpoint, None);
+    }
+
+    #[test]
+    fn expired_transactions_can_be_resubmitted() {
+        let mut history = TransactionHistory::default();
+        let pending_tx = Hash::SHA256([1; 32]);
+        let confirmed_tx = Hash::SHA256([2; 32]);
+        history.add_pending(0, pending_tx);
+        history.add_pending(0, confirmed_tx);
+        history.confirm_block(Hash::SHA256([3; 32]), 5, &[confirmed_tx]);
+
+        // Only pending transactions expire
+        assert_eq!(history.expire(&[pending_tx, confirmed_tx]), 1);
+        let page = history.get_page(0, 0, 10);
+        assert_eq!(page[1].status, TransactionStatus::Expired);
+        assert_eq!(page[0].confirmations, 1);
+
+        let resubmitted_tx = Hash::SHA256([4; 32]);
+        assert_eq!(history.resubmit(confirmed_tx, resubmitted_tx), 0);
+        assert_eq!(history.resubmit(pending_tx, resubmitted_tx), 1);
+        assert_eq!(
+            history.get_page(0, 1, 10),
+            vec![HistoryItem {
+                hash: resubmitted_tx,
+                status: TransactionStatus::Pending,
+                confirmations: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn history_pagination() {
+        let mut history = TransactionHistory::default();
+        for i in 0..5 {
+            history.add_pending(0, Hash::SHA256([i; 32]));
+            history.add_pending(1, Hash::SHA256([i + 10; 32]));
+        }
+
+        // Most recent transactions first
+        let hashes: Vec<_> = history
+            .get_page(0, 1, 2)
+            .into_iter()
+            .map(|item| item.hash)
+            .collect();
+        assert_eq!(hashes, vec![Hash::SHA256([3; 32]), Hash::SHA256([2; 32])]);
+
+        assert_eq!(history.get_page(0, 4, 10).len(), 1);
+        assert!(history.get_page(0, 5, 10).is_empty());
+        assert!(history.get_page(2, 0, 10).is_empty());
+    }
+
+    #[test]
+    fn history_changed_entries() {
+        let mut history = TransactionHistory::default();
+        let first_tx = Hash::SHA256([1; 32]);
+        let second_tx = Hash::SHA256([2; 32]);
+        history.add_pending(0, first_tx);
+        history.add_pending(0, second_tx);
+        let positions = |changed: Vec<(u32, HistoryEntry)>| -> Vec<u32> {
+            changed.into_iter().map(|(index, _)| index).collect()
+        };
+        assert_eq!(positions(history.take_changed()), vec![0, 1]);
+        assert!(history.take_changed().is_empty());
+
+        // Only the confirmed entry is persisted again, along with the new tip in the head
+        history.confirm_block(Hash::SHA256([3; 32]), 5, &[second_tx]);
+        let changed = history.take_changed();
+        assert_eq!(changed[0].1.hash, second_tx);
+        assert_eq!(positions(changed), vec![1]);
+        assert_eq!(
+            history.head(),
+            HistoryHead {
+                len: 2,
+                tip_checkpoint: Some(5)
+            }
+        );
+
+        // The history is rebuilt from its head and its entries
+        let entries = history.entries.clone();
+        let restored = TransactionHistory::from_parts(&history.head(), entries);
+        assert_eq!(restored.get_page(0, 0, 10), history.get_page(0, 0, 10));
+
+        history.mark_all_changed();
+        assert_eq!(positions(history.take_changed()), vec![0, 1]);
+    }
+
+    #[test]
+    fn history_legacy_value_decodes() {
+        /// Transaction history as persisted by older versions of the node
+        #[derive(Serialize, Deserialize)]
+        struct LegacyHistory {
+            entries: Vec<HistoryEntry>,
+            tip_checkpoint: Option<Epoch>,
+        }
+        let entry = HistoryEntry {
+            account: 0,
+            hash: Hash::SHA256([1; 32]),
+            status: TransactionStatus::Pending,
+        };
+        let legacy = LegacyHistory {
+            entries: vec![entry.clone()],
+            tip_checkpoint: Some(7),
+        };
+        let bytes = legacy.to_bytes().unwrap();
+
+        let history = TransactionHistory::from_bytes(&bytes).unwrap();
+        assert_eq!(history.entries, vec![entry]);
+        assert_eq!(history.tip_checkpoint, Some(7));
+    }
+
+    #[test]
+    fn encrypted_secrets() {
+        let secrets = KeysSecrets::from_mnemonic(&Mnemonic::generate());
+        let encrypted = secrets.encrypt("secret").unwrap();
+        assert_eq!(encrypted.decrypt("secret").unwrap(), secrets);
+        for password in &["Secret", ""] {
+            match encrypted.decrypt(password) {
+                Err(WalletManagerError::WrongPassword) => {}
+                res => panic!("Unexpected result: {:?}", res),
+            }
+        }
+
+        // The salt and the nonce are random, so the same secrets are encrypted differently
+        let other = secrets.encrypt("secret").unwrap();
+        assert_ne!(other.salt, encrypted.salt);
+        assert_ne!(other.nonce, encrypted.nonce);
+        assert_ne!(other.ciphertext, encrypted.ciphertext);
+
+        // The secrets cannot be tampered with
+        let mut tampered = encrypted.clone();
+        tampered.ciphertext[0] ^= 1;
+        match tampered.decrypt("secret") {
+            Err(WalletManagerError::WrongPassword) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn wallet_keys_backup() {
+        let mnemonic = Mnemonic::generate();
+        let secrets = KeysSecrets::from_mnemonic(&mnemonic);
+        assert_eq!(secrets.seed.len(), SEED_LENGTH);
+        let keys = WalletKeys::Encrypted(secrets.encrypt("secret").unwrap());
+
+        let backup = keys.backup("secret").unwrap();
+        assert_eq!(backup.mnemonic, Some(mnemonic.phrase().to_string()));
+        assert_eq!(backup.master_key.len(), 128);
+
+        // The same mnemonic always derives the same master key
+        let restored =
+            KeysSecrets::from_mnemonic(&Mnemonic::from_phrase(mnemonic.phrase()).unwrap());
+        let restored = WalletKeys::Encrypted(restored.encrypt("other").unwrap());
+        assert_eq!(restored.backup("other").unwrap(), backup);
+
+        match keys.backup("other") {
+            Err(WalletManagerError::WrongPassword) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match WalletKeys::Plain(secrets).backup("") {
+            Err(WalletManagerError::NoPassword) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // Legacy seeds have neither mnemonic nor password
+        let legacy = KeysSecrets::from_legacy_seed(vec![1; 32]);
+        assert_eq!(legacy.mnemonic, None);
+        assert!(legacy.master_key().is_ok());
+    }
+
+    #[test]
+    fn wallet_keys_replace() {
+        let secrets = KeysSecrets::from_mnemonic(&Mnemonic::generate());
+
+        // Keys without password can only be replaced after confirming it
+        let keys = WalletKeys::Plain(secrets.clone());
+        assert!(keys.check_replace(None, true).is_ok());
+        assert!(keys.check_replace(Some("anything"), true).is_ok());
+        for password in &[None, Some("anything")] {
+            match keys.check_replace(*password, false) {
+                Err(WalletManagerError::ReplaceNotConfirmed) => {}
+                res => panic!("Unexpected result: {:?}", res),
+            }
+        }
+
+        // Keys with password can only be replaced with their password, even if it is confirmed
+        let keys = WalletKeys::Encrypted(secrets.encrypt("secret").unwrap());
+        assert!(keys.check_replace(Some("secret"), false).is_ok());
+        for password in &[None, Some("other")] {
+            match keys.check_replace(*password, true) {
+                Err(WalletManagerError::WrongPassword) => {}
+                res => panic!("Unexpected result: {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn wallet_keys_set_password() {
+        let mnemonic = Mnemonic::generate();
+        let secrets = KeysSecrets::from_mnemonic(&mnemonic);
+
+        // Keys without password can be encrypted, and exported afterwards
+        let (decrypted, keys) = WalletKeys::Plain(secrets.clone())
+            .set_password("secret", None)
+            .unwrap();
+        assert_eq!(decrypted, secrets);
+        assert_eq!(
+            keys.backup("secret").unwrap().mnemonic,
+            Some(mnemonic.phrase().to_string())
+        );
+
+        // The password of encrypted keys can only be changed with the current one
+        for password in &[None, Some("other")] {
+            match keys.set_password("new", *password) {
+                Err(WalletManagerError::WrongPassword) => {}
+                res => panic!("Unexpected result: {:?}", res),
+            }
+        }
+        let (decrypted, keys) = keys.set_password("new", Some("secret")).unwrap();
+        assert_eq!(decrypted, secrets);
+        assert!(keys.unlock("new").is_ok());
+        assert!(keys.unlock("secret").is_err());
+    }
+
+    #[test]
+    fn legacy_encrypted_secrets() {
+        /// Encrypted secrets as persisted by older versions of the node
+        #[derive(Serialize, Deserialize)]
+        struct LegacyEncryptedSecrets {
+            salt: Vec<u8>,
+            nonce: Vec<u8>,
+            ciphertext: Vec<u8>,
+            tag: Vec<u8>,
+        }
+
+        let secrets = KeysSecrets::from_mnemonic(&Mnemonic::generate());
+        let mut encrypted = secrets.encrypt("secret").unwrap();
+        assert_eq!(encrypted.iterations, PASSWORD_HASH_ITERATIONS);
+        encrypted.iterations = LEGACY_PASSWORD_HASH_ITERATIONS;
+        // Encrypt again with the legacy number of iterations
+        let plaintext = secrets.to_bytes().unwrap();
+        AesGcm::new(
+            KeySize::KeySize256,
+            &encryption_key("secret", &encrypted.salt, LEGACY_PASSWORD_HASH_ITERATIONS),
+            &encrypted.nonce,
+            &[],
+        )
+        .encrypt(&plaintext, &mut encrypted.ciphertext, &mut encrypted.tag);
+        let legacy = LegacyEncryptedSecrets {
+            salt: encrypted.salt.clone(),
+            nonce: encrypted.nonce.clone(),
+            ciphertext: encrypted.ciphertext.clone(),
+            tag: encrypted.tag.clone(),
+        };
+
+        // Keys encrypted before the number of iterations was stored used the legacy one
+        let restored = EncryptedSecrets::from_bytes(&legacy.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored, encrypted);
+        assert_eq!(restored.decrypt("secret").unwrap(), secrets);
+    }
+
+    #[test]
+    fn partial_signatures_are_aggregated() {
+        let secp = Secp256k1::new();
+        let master_key = ExtendedSK::from_seed(&[1; SEED_LENGTH]).unwrap();
+        let accounts = Accounts::default();
+        let account = accounts.get(0).unwrap();
+        let pkhs = (0..3)
+            .map(|i| {
+                let key = master_key
+                    .derive(&secp, &account.signing_key_path(i))
+                    .unwrap();
+                calculate_public_key_hash(
+                    &PublicKey::from_secret_key(&secp, key.secret_key()).serialize(),
+                )
+            })
+            .collect();
+        let transaction = PartiallySignedTransaction {
+            hash: Hash::SHA256([1; 32]),
+            condition: MultiSigCondition { required: 2, pkhs },
+            signatures: vec![],
+        };
+
+        let signer = SoftwareSigner::new(master_key);
+        // The addresses derived by the signer are the ones of its signing keys
+        assert_eq!(
+            signer.address(&account.signing_key_path(1)).unwrap(),
+            transaction.condition.pkhs[1]
+        );
+        let sign_with = |key_index: u32,
+                         transaction: PartiallySignedTransaction|
+         -> Result<SignedTransaction, WalletManagerError> {
+            let request = signing_request(account, key_index, &transaction)?;
+            let keyed = signer.sign_request(&request)?;
+
+            add_signature(&secp, transaction, keyed)
+        };
+
+        // The first owner signs
+        let signed = sign_with(0, transaction).unwrap();
+        assert_eq!(signed.transaction.signatures.len(), 1);
+        assert!(!signed.complete);
+
+        // Signing twice with the same key does not add another signature
+        let signed = sign_with(0, signed.transaction).unwrap();
+        assert_eq!(signed.transaction.signatures.len(), 1);
+
+        // The second owner completes the transaction
+        let signed = sign_with(2, signed.transaction).unwrap();
+        assert_eq!(signed.transaction.signatures.len(), 2);
+        assert!(signed.complete);
+
+        // Keys of other owners cannot sign
+        match sign_with(3, signed.transaction.clone()) {
+            Err(WalletManagerError::KeyNotInCondition) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // Signatures of other messages are rejected
+        let mut request = signing_request(account, 1, &signed.transaction).unwrap();
+        request.hash = Hash::SHA256([2; 32]);
+        let keyed = signer.sign_request(&request).unwrap();
+        match add_signature(&secp, signed.transaction, keyed) {
+            Err(WalletManagerError::InvalidSignature) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! # Signers of the wallet
+//!
+//! The WalletManager never signs by itself: it builds a
+//! [SigningRequest](witnet_data_structures::signing::SigningRequest) and hands it to a [Signer],
+//! so the private keys of the wallet can live outside the node. There are two signers:
+//!
+//! * [SoftwareSigner]: derives the keys from the seed stored by the node. This is the default.
+//! * [SocketSigner]: forwards the requests to an external signer listening on a local socket, such
+//!   as a remote signing service or a bridge to a hardware wallet. A hardware wallet connected over
+//!   HID (e.g. a Ledger device) can be supported by implementing [Signer] on top of its transport.
+//!
+//! The socket protocol is as simple as possible: for every request the node opens a connection,
+//! writes the length of the encoded request (4 bytes, big endian) followed by the encoded request
+//! and reads the encoded response. The signer rejects a request by closing the connection without
+//! answering.
+use std::{io, net::SocketAddr};
+
+use futures::{future, Future};
+use secp256k1::{All, PublicKey, Secp256k1};
+use tokio::{io as tokio_io, net::TcpStream};
+
+use witnet_crypto::{
+    hash::calculate_public_key_hash,
+    key::{ExtendedSK, KeyPath},
+    signature::sign,
+};
+use witnet_data_structures::{
+    chain::{Hash, KeyedSignature, PublicKeyHash},
+    error::DecodeError,
+    signing::{decode_signing_response, SigningRequest, SIGNING_RESPONSE_LENGTH},
+};
+use witnet_util::error::WitnetError;
+
+/// Future resolved with the signature of a signing request
+pub type SignerFuture = Box<dyn Future<Item = KeyedSignature, Error = SignerError>>;
+
+/// Possible errors when signing with a Signer
+#[derive(Debug)]
+pub enum SignerError {
+    /// The key could not be derived from the seed
+    KeyDerivationFailed,
+    /// The signer does not expose the public keys it holds, so it cannot derive addresses
+    Unsupported,
+    /// The external signer rejected the request
+    Rejected,
+    /// The external signer could not be reached
+    Io(io::Error),
+    /// The response of the external signer is not valid
+    InvalidResponse(WitnetError<DecodeError>),
+}
+
+/// Holder of the keys of the wallet, able to sign transactions with them
+pub trait Signer {
+    /// Sign the hash of a transaction with the key at the derivation path of the request,
+    /// returning the signature along with the public key of that key
+    fn sign(&self, request: &SigningRequest) -> SignerFuture;
+
+    /// Derive the address (the hash of the public key) of the key at a derivation path
+    fn address(&self, _key_path: &KeyPath) -> Result<PublicKeyHash, SignerError> {
+        Err(SignerError::Unsupported)
+    }
+}
+
+/// Signer deriving the keys from the seed stored by the node
+pub struct SoftwareSigner {
+    /// Secp256k1 context used for the derivation and the signatures
+    secp: Secp256k1<All>,
+    /// Master key of the wallet, derived from its seed
+    master_key: ExtendedSK,
+}
+
+impl SoftwareSigner {
+    /// Create a signer from the master key of the wallet
+    pub fn new(master_key: ExtendedSK) -> Self {
+        SoftwareSigner {
+            secp: Secp256k1::new(),
+            master_key,
+        }
+    }
+
+    /// Sign a request synchronously
+    pub fn sign_request(&self, request: &SigningRequest) -> Result<KeyedSignature, SignerError> {
+        let path = request
+            .key_path
+            .iter()
+            .fold(KeyPath::master(), |path, index| path.child(*index));
+        let key = self
+            .master_key
+            .derive(&self.secp, &path)
+            .map_err(|_| SignerError::KeyDerivationFailed)?;
+
+        let Hash::SHA256(message) = request.hash;
+
+        Ok(KeyedSignature {
+            public_key: PublicKey::from_secret_key(&self.secp, key.secret_key())
+                .serialize()
+                .to_vec(),
+            signature: sign(&self.secp, key.secret_key(), &message),
+        })
+    }
+}
+
+impl Signer for SoftwareSigner {
+    fn sign(&self, request: &SigningRequest) -> SignerFuture {
+        Box::new(future::result(self.sign_request(request)))
+    }
+
+    fn address(&self, key_path: &KeyPath) -> Result<PublicKeyHash, SignerError> {
+        let key = self
+            .master_key
+            .derive(&self.secp, key_path)
+            .map_err(|_| SignerError::KeyDerivationFailed)?;
+
+        Ok(calculate_public_key_hash(
+            &PublicKey::from_secret_key(&self.secp, key.secret_key()).serialize(),
+        ))
+    }
+}
+
+/// Signer forwarding the requests to an external signer listening on a socket
+pub struct SocketSigner {
+    /// Socket address of the external signer
+    address: SocketAddr,
+}
+
+impl SocketSigner {
+    /// Create a signer for the external signer listening on a socket address
+    pub fn new(address: SocketAddr) -> Self {
+        SocketSigner { address }
+    }
+}
+
+impl Signer for SocketSigner {
+    fn sign(&self, request: &SigningRequest) -> SignerFuture {
+        let encoded = request.encode();
+        let mut frame = Vec::with_capacity(4 + encoded.len());
+        frame.extend_from_slice(&(encoded.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&encoded);
+
+        let fut = TcpStream::connect(&self.address)
+            .and_then(move |stream| tokio_io::write_all(stream, frame))
+            .and_then(|(stream, _frame)| {
+                tokio_io::read_exact(stream, vec![0; SIGNING_RESPONSE_LENGTH])
+            })
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::UnexpectedEof => SignerError::Rejected,
+                _ => SignerError::Io(e),
+            })
+            .and_then(|(_stream, response)| {
+                decode_signing_response(&response).map_err(SignerError::InvalidResponse)
+            });
+
+        Box::new(fut)
+    }
+}
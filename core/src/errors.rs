@@ -0,0 +1,222 @@
+//! Errors that can stop the node
+//!
+//! Every error is either retriable or fatal:
+//!
+//! * Retriable errors are transient (for example, a write to the storage
+//!   backend which failed or a message which timed out), so the operation
+//!   is retried with an exponential backoff.
+//! * Fatal errors cannot be fixed by retrying (for example, corrupted
+//!   values or an actor which has been stopped), as well as retriable
+//!   errors which kept failing after every retry. The node is stopped in a
+//!   controlled way when they happen, instead of going on with a state
+//!   which was not persisted.
+use std::fmt;
+use std::time::Duration;
+
+use actix::MailboxError;
+
+use witnet_storage::error::{StorageError, StorageErrorKind};
+use witnet_util::error::WitnetError;
+
+/// Whether an operation which failed can be retried
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    /// The error is transient and the operation can be retried
+    Retriable,
+    /// The error cannot be fixed by retrying and the node has to be stopped
+    Fatal,
+}
+
+/// Error of the node
+#[derive(Debug)]
+pub enum NodeError {
+    /// Error returned by the storage backend
+    Storage {
+        /// Operation kind
+        kind: StorageErrorKind,
+        /// Error message
+        msg: String,
+    },
+    /// A message could not be delivered to an actor
+    Mailbox(MailboxError),
+    /// An actor could not be initialized
+    Initialization(String),
+    /// A retriable error which kept failing after every retry
+    RetriesExhausted {
+        /// Number of attempts
+        attempts: u32,
+        /// Error of the last attempt
+        last: Box<NodeError>,
+    },
+    /// A supervised actor failed too many times in a row
+    RestartsExhausted {
+        /// Name of the actor
+        actor: &'static str,
+        /// Number of restarts within the restart window
+        restarts: usize,
+    },
+}
+
+impl NodeError {
+    /// Get the severity of the error
+    pub fn severity(&self) -> Severity {
+        match self {
+            NodeError::Storage { kind, .. } => match kind {
+                StorageErrorKind::Connection
+                | StorageErrorKind::Put
+                | StorageErrorKind::Get
+                | StorageErrorKind::Delete => Severity::Retriable,
+                StorageErrorKind::Encode
+                | StorageErrorKind::Decode
+                | StorageErrorKind::Version
+                | StorageErrorKind::Corrupted => Severity::Fatal,
+            },
+            NodeError::Mailbox(MailboxError::Timeout) => Severity::Retriable,
+            NodeError::Mailbox(MailboxError::Closed) => Severity::Fatal,
+            NodeError::Initialization(_)
+            | NodeError::RetriesExhausted { .. }
+            | NodeError::RestartsExhausted { .. } => Severity::Fatal,
+        }
+    }
+
+    /// Whether the node has to be stopped because of this error
+    pub fn is_fatal(&self) -> bool {
+        self.severity() == Severity::Fatal
+    }
+}
+
+impl fmt::Display for NodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeError::Storage { msg, .. } => write!(f, "{}", msg),
+            NodeError::Mailbox(e) => write!(f, "{}", e),
+            NodeError::Initialization(msg) => write!(f, "Initialization failed: {}", msg),
+            NodeError::RetriesExhausted { attempts, last } => {
+                write!(f, "{} (failed {} times)", last, attempts)
+            }
+            NodeError::RestartsExhausted { actor, restarts } => {
+                write!(f, "{} was restarted {} times", actor, restarts)
+            }
+        }
+    }
+}
+
+impl From<StorageError> for NodeError {
+    fn from(e: StorageError) -> Self {
+        NodeError::Storage {
+            kind: *e.kind(),
+            msg: e.to_string(),
+        }
+    }
+}
+
+impl From<WitnetError<StorageError>> for NodeError {
+    fn from(e: WitnetError<StorageError>) -> Self {
+        NodeError::Storage {
+            kind: *e.inner().kind(),
+            msg: e.to_string(),
+        }
+    }
+}
+
+impl From<MailboxError> for NodeError {
+    fn from(e: MailboxError) -> Self {
+        NodeError::Mailbox(e)
+    }
+}
+
+/// Retries of an operation which failed with a retriable error
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Time to wait before the first retry
+    pub initial_backoff: Duration,
+    /// Maximum time to wait between retries
+    pub max_backoff: Duration,
+}
+
+/// Retry policy of the writes to the storage: up to 5 attempts over 1.5 seconds
+pub const STORAGE_RETRY_POLICY: RetryPolicy = RetryPolicy {
+    max_attempts: 5,
+    initial_backoff: Duration::from_millis(100),
+    max_backoff: Duration::from_secs(1),
+};
+
+impl RetryPolicy {
+    /// Get the time to wait after a failed attempt (starting at 1) before the next one, which
+    /// doubles after every attempt up to `max_backoff`
+    /// Returns `None` if there are no attempts left
+    pub fn backoff(&self, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::max_value());
+        Some(
+            self.initial_backoff
+                .checked_mul(factor)
+                .map_or(self.max_backoff, |backoff| {
+                    std::cmp::min(backoff, self.max_backoff)
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_severity() {
+        let put = NodeError::from(StorageError::new(
+            StorageErrorKind::Put,
+            "chain".to_string(),
+            "IO error".to_string(),
+        ));
+        assert_eq!(put.severity(), Severity::Retriable);
+
+        let corrupted = NodeError::from(StorageError::new(
+            StorageErrorKind::Corrupted,
+            "chain".to_string(),
+            "checksum mismatch".to_string(),
+        ));
+        assert!(corrupted.is_fatal());
+
+        assert!(!NodeError::Mailbox(MailboxError::Timeout).is_fatal());
+        assert!(NodeError::Mailbox(MailboxError::Closed).is_fatal());
+
+        // Retriable errors become fatal once there are no retries left
+        let exhausted = NodeError::RetriesExhausted {
+            attempts: 5,
+            last: Box::new(put),
+        };
+        assert!(exhausted.is_fatal());
+    }
+
+    #[test]
+    fn retry_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 6,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+
+        assert_eq!(policy.backoff(1), Some(Duration::from_millis(100)));
+        assert_eq!(policy.backoff(2), Some(Duration::from_millis(200)));
+        assert_eq!(policy.backoff(3), Some(Duration::from_millis(400)));
+        assert_eq!(policy.backoff(4), Some(Duration::from_millis(500)));
+        assert_eq!(policy.backoff(5), Some(Duration::from_millis(500)));
+        assert_eq!(policy.backoff(6), None);
+    }
+
+    #[test]
+    fn storage_retry_policy() {
+        let total: Duration = (1..STORAGE_RETRY_POLICY.max_attempts)
+            .filter_map(|attempt| STORAGE_RETRY_POLICY.backoff(attempt))
+            .sum();
+        assert_eq!(total, Duration::from_millis(1500));
+    }
+}
@@ -0,0 +1,177 @@
+//! Handle to embed a Witnet node in another program
+//!
+//! A [`NodeHandle`](NodeHandle) starts the actors of the node in a thread
+//! of their own and talks to them through typed messages, so other Rust
+//! programs can run a node in-process instead of running the `witnet`
+//! binary and scraping its JSON-RPC interface. Every request returns a
+//! future, which can be run in the executor of the embedding program or
+//! just waited on:
+//!
+//! ```no_run
+//! use futures::{Future, Stream};
+//! use witnet_core::handle::NodeHandle;
+//!
+//! let node = NodeHandle::start(None).unwrap();
+//! println!("Chain tip: {:?}", node.chain_tip().wait());
+//!
+//! // Print the beacons of the next blocks added to the local chain
+//! for block in node.subscribe_blocks().take(3).wait() {
+//!     println!("{:?}", block.unwrap().header.block_header.beacon);
+//! }
+//!
+//! node.shutdown().unwrap();
+//! ```
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+
+use actix::{Addr, MailboxError, System};
+use futures::{sync::mpsc::UnboundedReceiver, Future};
+
+use crate::actors::{
+    blocks_manager::{
+        messages::{GetHighestCheckpointBeacon, SubscribeBlocks},
+        BlocksManager,
+    },
+    mempool_manager::{messages::SubmitTransaction, MempoolManager, MempoolManagerError},
+    node,
+};
+use witnet_data_structures::chain::{Block, CheckpointBeacon, Hash, Transaction};
+
+/// Error of a request made through a `NodeHandle`
+#[derive(Debug)]
+pub enum HandleError {
+    /// The request could not be delivered to the actor (e.g. because the node has been stopped)
+    Mailbox(MailboxError),
+    /// The local chain is not available yet
+    Chain(String),
+    /// The mempool rejected the transaction
+    Mempool(MempoolManagerError),
+}
+
+impl fmt::Display for HandleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandleError::Mailbox(e) => write!(f, "The node did not answer the request: {}", e),
+            HandleError::Chain(e) => write!(f, "The local chain is not available: {}", e),
+            HandleError::Mempool(e) => write!(f, "The transaction was rejected: {:?}", e),
+        }
+    }
+}
+
+impl From<MailboxError> for HandleError {
+    fn from(e: MailboxError) -> Self {
+        HandleError::Mailbox(e)
+    }
+}
+
+/// Handle to a node running in-process
+///
+/// The node keeps running until `shutdown` is called or it is stopped after a fatal error, even
+/// if the handle is dropped.
+pub struct NodeHandle {
+    /// System running the actors of the node
+    system: System,
+    /// Address of the blocks manager
+    blocks_manager: Addr<BlocksManager>,
+    /// Address of the mempool manager
+    mempool_manager: Addr<MempoolManager>,
+    /// Thread running the system, which returns its exit code
+    thread: JoinHandle<i32>,
+}
+
+impl NodeHandle {
+    /// Start a node with the configuration read from a file (or the default configuration if
+    /// there is none), returning once its actors have been started
+    pub fn start(config: Option<PathBuf>) -> Result<NodeHandle, io::Error> {
+        let config_manager = node::load_config(config)?;
+
+        let (tx, rx) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("witnet-node".to_string())
+            .spawn(move || {
+                let system = System::new("node");
+                node::start_actors(config_manager);
+
+                let registry = System::current().registry();
+                // The receiver only goes away if the thread starting the node panicked
+                let _ = tx.send((
+                    System::current(),
+                    registry.get::<BlocksManager>(),
+                    registry.get::<MempoolManager>(),
+                ));
+
+                system.run()
+            })?;
+        let (system, blocks_manager, mempool_manager) = rx
+            .recv()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "The node could not be started"))?;
+
+        Ok(NodeHandle {
+            system,
+            blocks_manager,
+            mempool_manager,
+            thread,
+        })
+    }
+
+    /// Get the beacon of the tip of the local chain
+    pub fn chain_tip(&self) -> impl Future<Item = CheckpointBeacon, Error = HandleError> {
+        self.blocks_manager
+            .send(GetHighestCheckpointBeacon)
+            .map_err(HandleError::from)
+            .and_then(|res| res.map_err(|e| HandleError::Chain(e.to_string())))
+    }
+
+    /// Add a transaction to the mempool and announce it to the peers, returning its hash
+    pub fn submit_transaction(
+        &self,
+        transaction: Transaction,
+        fee: u64,
+    ) -> impl Future<Item = Hash, Error = HandleError> {
+        self.mempool_manager
+            .send(SubmitTransaction { transaction, fee })
+            .map_err(HandleError::from)
+            .and_then(|res| res.map_err(HandleError::Mempool))
+    }
+
+    /// Get a stream of the blocks added to the local chain from now on
+    /// The subscription is cancelled when the stream is dropped
+    pub fn subscribe_blocks(&self) -> UnboundedReceiver<Block> {
+        let (sender, receiver) = futures::sync::mpsc::unbounded();
+        self.blocks_manager.do_send(SubscribeBlocks { sender });
+
+        receiver
+    }
+
+    /// Stop the node, waiting until its actors have been stopped
+    /// Fails if the node had already been stopped after a fatal error
+    pub fn shutdown(self) -> Result<(), io::Error> {
+        self.system.stop();
+        let code = self
+            .thread
+            .join()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "The thread of the node panicked"))?;
+
+        node::exit_status(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handle_error_display() {
+        assert_eq!(
+            HandleError::from(MailboxError::Closed).to_string(),
+            "The node did not answer the request: Mailbox has closed"
+        );
+        assert_eq!(
+            HandleError::Mempool(MempoolManagerError::FeeBelowMinRelayFee).to_string(),
+            "The transaction was rejected: FeeBelowMinRelayFee"
+        );
+    }
+}
@@ -0,0 +1,432 @@
+//! # Inspector of binary data
+//!
+//! Decodes the raw bytes exchanged with the peers or kept in the storage, so
+//! their structure can be examined when debugging interoperability issues
+//! between versions of the node:
+//!
+//! * Frames captured from the wire, as written by the
+//!   [P2PCodec](crate::actors::codec::P2PCodec): a big endian `u16` with the
+//!   length of the message followed by the message itself, optionally
+//!   prepended with a compression flag. A capture may contain several frames.
+//!   The frames of encrypted sessions cannot be decoded.
+//! * Protocol messages, as described in the file `schemas/protocol.fbs`.
+//! * Blocks, as persisted under their hash.
+//! * The transactions of the mempool, as persisted under the `mempool` key.
+use std::fmt;
+use std::str::FromStr;
+
+use tokio::codec::Decoder;
+
+use witnet_data_structures::{chain::Block, serializers::TryFrom, types::Message};
+use witnet_storage::storage::Storable;
+
+use crate::actors::{
+    codec::{decompress_message, BytesMut, P2PCodec, FRAME_SNAPPY, FRAME_UNCOMPRESSED},
+    mempool_manager::MempoolTransaction,
+};
+
+/// Kind of the bytes to decode
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    /// Try every other kind, in the order they are listed below
+    Auto,
+    /// Frames captured from the wire
+    Frames,
+    /// Protocol message
+    Message,
+    /// Block from the storage
+    Block,
+    /// Transactions of the mempool from the storage
+    Transactions,
+}
+
+/// Kinds tried, in order, when the kind of the bytes is not known
+const AUTO_KINDS: [Kind; 4] = [Kind::Frames, Kind::Message, Kind::Block, Kind::Transactions];
+
+impl FromStr for Kind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Kind::Auto),
+            "frames" => Ok(Kind::Frames),
+            "message" => Ok(Kind::Message),
+            "block" => Ok(Kind::Block),
+            "transactions" => Ok(Kind::Transactions),
+            _ => Err(format!(
+                "Unknown kind {}, expected one of: auto, frames, message, block, transactions",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Kind::Auto => "auto",
+            Kind::Frames => "frames",
+            Kind::Message => "message",
+            Kind::Block => "block",
+            Kind::Transactions => "transactions",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Compression of the message of a frame
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FrameCompression {
+    /// The message has no compression flag (compression had not been negotiated)
+    None,
+    /// The message has a compression flag, but it is not compressed
+    Uncompressed,
+    /// The message is compressed with Snappy
+    Snappy,
+}
+
+/// Frame captured from the wire
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Frame {
+    /// Length of the frame, as written in its header
+    pub length: usize,
+    /// Compression of the message
+    pub compression: FrameCompression,
+    /// Message of the frame
+    pub message: Message,
+}
+
+/// Decoded bytes
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decoded {
+    /// Frames captured from the wire
+    Frames(Vec<Frame>),
+    /// Protocol message
+    Message(Message),
+    /// Block from the storage
+    Block(Block),
+    /// Transactions of the mempool from the storage
+    Transactions(Vec<MempoolTransaction>),
+}
+
+impl Decoded {
+    /// Kind of the decoded bytes
+    pub fn kind(&self) -> Kind {
+        match self {
+            Decoded::Frames(_) => Kind::Frames,
+            Decoded::Message(_) => Kind::Message,
+            Decoded::Block(_) => Kind::Block,
+            Decoded::Transactions(_) => Kind::Transactions,
+        }
+    }
+}
+
+impl fmt::Display for Decoded {
+    /// Summary of the decoded bytes followed by their structure
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decoded::Frames(frames) => {
+                for (i, frame) in frames.iter().enumerate() {
+                    writeln!(
+                        f,
+                        "Frame {} ({} bytes, compression: {:?}): {} message (magic {:#x})",
+                        i + 1,
+                        frame.length,
+                        frame.compression,
+                        frame.message.kind,
+                        frame.message.magic
+                    )?;
+                    writeln!(f, "{:#?}", frame.message.kind)?;
+                }
+
+                Ok(())
+            }
+            Decoded::Message(message) => {
+                writeln!(f, "{} message (magic {:#x})", message.kind, message.magic)?;
+                writeln!(f, "{:#?}", message.kind)
+            }
+            Decoded::Block(block) => {
+                writeln!(
+                    f,
+                    "Block for checkpoint {} ({} transactions)",
+                    block.header.block_header.beacon.checkpoint, block.txn_count
+                )?;
+                writeln!(f, "{:#?}", block)
+            }
+            Decoded::Transactions(transactions) => {
+                writeln!(f, "{} mempool transactions", transactions.len())?;
+                writeln!(f, "{:#?}", transactions)
+            }
+        }
+    }
+}
+
+/// Error when inspecting binary data
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InspectError {
+    /// The input is not a valid hexadecimal string
+    InvalidHex(String),
+    /// The bytes cannot be decoded as the requested kind
+    Undecodable {
+        /// Requested kind
+        kind: Kind,
+        /// Reason why the bytes cannot be decoded
+        reason: String,
+    },
+    /// The bytes cannot be decoded as any known kind
+    UnknownFormat,
+}
+
+impl fmt::Display for InspectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InspectError::InvalidHex(reason) => write!(f, "Invalid hexadecimal input: {}", reason),
+            InspectError::Undecodable { kind, reason } => {
+                write!(f, "The input cannot be decoded as {}: {}", kind, reason)
+            }
+            InspectError::UnknownFormat => write!(
+                f,
+                "The input cannot be decoded as any of: frames, message, block, transactions"
+            ),
+        }
+    }
+}
+
+/// Parse a hexadecimal string, which may start with `0x` and contain whitespace
+pub fn parse_hex(hex: &str) -> Result<Vec<u8>, InspectError> {
+    let hex = hex.trim();
+    let hex = if hex.starts_with("0x") || hex.starts_with("0X") {
+        &hex[2..]
+    } else {
+        hex
+    };
+    let digits = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            c.to_digit(16)
+                .map(|digit| digit as u8)
+                .ok_or_else(|| InspectError::InvalidHex(format!("unexpected character {:?}", c)))
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    if digits.is_empty() {
+        return Err(InspectError::InvalidHex("empty input".to_string()));
+    }
+    if digits.len() % 2 != 0 {
+        return Err(InspectError::InvalidHex("odd number of digits".to_string()));
+    }
+
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
+
+/// Get the bytes of the contents of a file, which may be binary or a hexadecimal string
+pub fn file_bytes(contents: Vec<u8>) -> Vec<u8> {
+    match std::str::from_utf8(&contents).map(parse_hex) {
+        Ok(Ok(bytes)) => bytes,
+        _ => contents,
+    }
+}
+
+/// Decode bytes of the given kind
+pub fn decode(bytes: &[u8], kind: Kind) -> Result<Decoded, InspectError> {
+    let undecodable = |reason: String| InspectError::Undecodable { kind, reason };
+
+    match kind {
+        Kind::Auto => AUTO_KINDS
+            .iter()
+            .filter_map(|&kind| decode(bytes, kind).ok())
+            .next()
+            .ok_or(InspectError::UnknownFormat),
+        Kind::Frames => decode_frames(bytes)
+            .map(Decoded::Frames)
+            .map_err(undecodable),
+        Kind::Message => decode_message(bytes)
+            .map(Decoded::Message)
+            .map_err(undecodable),
+        Kind::Block => Block::from_bytes(bytes)
+            .map(Decoded::Block)
+            .map_err(|e| undecodable(e.to_string())),
+        Kind::Transactions => Vec::<MempoolTransaction>::from_bytes(bytes)
+            .map(Decoded::Transactions)
+            .map_err(|e| undecodable(e.to_string())),
+    }
+}
+
+/// Decode a protocol message
+fn decode_message(bytes: &[u8]) -> Result<Message, String> {
+    Message::try_from(bytes.to_vec()).map_err(|e| e.to_string())
+}
+
+/// Decode the frames of a capture, which must contain only complete frames
+fn decode_frames(bytes: &[u8]) -> Result<Vec<Frame>, String> {
+    let mut src = BytesMut::from(bytes);
+    let mut frames = vec![];
+    while !src.is_empty() {
+        let frame = P2PCodec
+            .decode(&mut src)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("incomplete frame after {} frames", frames.len()))?;
+        frames.push(decode_frame(&frame).map_err(|e| {
+            format!(
+                "frame {} cannot be decoded ({}), the session may be encrypted",
+                frames.len() + 1,
+                e
+            )
+        })?);
+    }
+    if frames.is_empty() {
+        return Err("empty input".to_string());
+    }
+
+    Ok(frames)
+}
+
+/// Decode the message of a frame, with or without a compression flag
+fn decode_frame(frame: &[u8]) -> Result<Frame, String> {
+    let length = frame.len();
+    let error = match decode_message(frame) {
+        Ok(message) => {
+            return Ok(Frame {
+                length,
+                compression: FrameCompression::None,
+                message,
+            })
+        }
+        Err(e) => e,
+    };
+
+    let compression = match frame.first() {
+        Some(&FRAME_UNCOMPRESSED) => FrameCompression::Uncompressed,
+        Some(&FRAME_SNAPPY) => FrameCompression::Snappy,
+        _ => return Err(error),
+    };
+    let message = decompress_message(frame)
+        .map_err(|e| e.to_string())
+        .and_then(|message| decode_message(&message))?;
+
+    Ok(Frame {
+        length,
+        compression,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::codec::compress_message;
+    use tokio::codec::Encoder;
+    use witnet_data_structures::chain::*;
+
+    fn frame(message: Vec<u8>) -> Vec<u8> {
+        let mut dst = BytesMut::new();
+        P2PCodec.encode(BytesMut::from(message), &mut dst).unwrap();
+
+        dst.to_vec()
+    }
+
+    fn block() -> Block {
+        Block {
+            header: BlockHeaderWithProof {
+                block_header: BlockHeader {
+                    version: 1,
+                    beacon: CheckpointBeacon {
+                        checkpoint: 7,
+                        hash_prev_block: Hash::SHA256([4; 32]),
+                    },
+                    hash_merkle_root: Hash::SHA256([3; 32]),
+                    utxo_merkle_root: Hash::SHA256([0; 32]),
+                },
+                proof: LeadershipProof {
+                    block_sig: None,
+                    influence: 0,
+                },
+            },
+            txn_count: 1,
+            txns: vec![Transaction],
+            mint: MintTransaction {
+                epoch: 7,
+                output: ValueTransferOutput {
+                    pkh: [0; 20],
+                    value: 0,
+                    time_lock: 7,
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn parse_hex_input() {
+        assert_eq!(parse_hex("0x00ff"), Ok(vec![0x00, 0xff]));
+        assert_eq!(parse_hex(" 01 Ab\n"), Ok(vec![0x01, 0xab]));
+        assert!(parse_hex("abc").is_err());
+        assert!(parse_hex("zz").is_err());
+        assert!(parse_hex("").is_err());
+
+        // Binary files are kept as they are
+        assert_eq!(file_bytes(b"0a0b\n".to_vec()), vec![0x0a, 0x0b]);
+        assert_eq!(file_bytes(vec![0xff, 0x00]), vec![0xff, 0x00]);
+    }
+
+    #[test]
+    fn decode_captured_frames() {
+        let ping: Vec<u8> = Message::build_ping().into();
+        let verack: Vec<u8> = Message::build_verack().into();
+        let mut capture = frame(ping.clone());
+        capture.extend(frame(compress_message(verack, 1024).unwrap()));
+
+        let frames = match decode(&capture, Kind::Frames).unwrap() {
+            Decoded::Frames(frames) => frames,
+            decoded => panic!("Unexpected {:?}", decoded),
+        };
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].length, ping.len());
+        assert_eq!(frames[0].compression, FrameCompression::None);
+        assert_eq!(frames[0].message.kind.to_string(), "PING");
+        assert_eq!(frames[1].compression, FrameCompression::Uncompressed);
+        assert_eq!(frames[1].message.kind.to_string(), "VERACK");
+
+        // Truncated captures are rejected
+        assert!(decode(&capture[..capture.len() - 1], Kind::Frames).is_err());
+    }
+
+    #[test]
+    fn decode_auto_detects_kind() {
+        let ping: Vec<u8> = Message::build_ping().into();
+        assert_eq!(
+            decode(&frame(ping.clone()), Kind::Auto).unwrap().kind(),
+            Kind::Frames
+        );
+        assert_eq!(decode(&ping, Kind::Auto).unwrap().kind(), Kind::Message);
+
+        let stored_block = block().to_bytes().unwrap();
+        assert_eq!(
+            decode(&stored_block, Kind::Auto).unwrap(),
+            Decoded::Block(block())
+        );
+
+        let stored_mempool = Vec::<MempoolTransaction>::new().to_bytes().unwrap();
+        assert_eq!(
+            decode(&stored_mempool, Kind::Transactions).unwrap(),
+            Decoded::Transactions(vec![])
+        );
+
+        assert_eq!(
+            decode(&[0xde, 0xad, 0xbe, 0xef], Kind::Auto),
+            Err(InspectError::UnknownFormat)
+        );
+    }
+
+    #[test]
+    fn decode_wrong_kind() {
+        let ping: Vec<u8> = Message::build_ping().into();
+        match decode(&ping, Kind::Block) {
+            Err(InspectError::Undecodable { kind, .. }) => assert_eq!(kind, Kind::Block),
+            result => panic!("Unexpected {:?}", result),
+        }
+    }
+}
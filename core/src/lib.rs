@@ -9,3 +9,21 @@
 
 /// Actors module
 pub mod actors;
+
+/// Errors module
+pub mod errors;
+
+/// Handle to embed the node in other programs
+pub mod handle;
+
+/// Inspector of binary data module
+pub mod inspector;
+
+/// Metrics module
+pub mod metrics;
+
+/// Tracing module
+pub mod tracing;
+
+/// Watchdog of the message handlers module
+pub mod watchdog;
@@ -0,0 +1,357 @@
+//! Counters of the events that the node shed under load, of the hits
+//! and misses of the storage read cache, of the time it takes to relay
+//! the block candidates, of the isolation of the node from the network
+//! and of the handlers which exceeded their time budget
+//!
+//! The counters are global to the process so that any actor can update
+//! them without having to route a message through another (possibly
+//! overloaded) actor. They can be read through the `getMetrics`
+//! JSON-RPC method.
+
+use lazy_static::lazy_static;
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Messages not delivered to a session because its mailbox was full
+static SESSION_MAILBOX_FULL: AtomicUsize = AtomicUsize::new(0);
+
+/// Inventory vectors discarded because they were already being processed
+static DUPLICATE_INV_VECTORS: AtomicUsize = AtomicUsize::new(0);
+
+/// Payloads dropped because the peer had already sent them within the deduplication window
+static DUPLICATE_PAYLOADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Reads served by the storage read cache
+static STORAGE_CACHE_HITS: AtomicUsize = AtomicUsize::new(0);
+
+/// Reads that missed the storage read cache and went to the storage backend
+static STORAGE_CACHE_MISSES: AtomicUsize = AtomicUsize::new(0);
+
+/// Block candidates announced to the peers
+static BLOCKS_RELAYED: AtomicUsize = AtomicUsize::new(0);
+
+/// Sum of the times (in microseconds) elapsed between the arrival and the relay of every block
+static BLOCK_RELAY_MICROS_TOTAL: AtomicUsize = AtomicUsize::new(0);
+
+/// Longest time (in microseconds) elapsed between the arrival and the relay of a block
+static BLOCK_RELAY_MICROS_MAX: AtomicUsize = AtomicUsize::new(0);
+
+/// Block candidates relayed before being fully validated which turned out to be invalid
+static INVALID_RELAYED_BLOCKS: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether the node is currently isolated from the network
+static ISOLATED: AtomicBool = AtomicBool::new(false);
+
+/// Times the node has become isolated from the network
+static ISOLATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Messages whose handler exceeded its time budget
+static SLOW_HANDLERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Longest time (in microseconds) taken by a handler which exceeded its time budget
+static SLOW_HANDLER_MICROS_MAX: AtomicUsize = AtomicUsize::new(0);
+
+/// Sessions closed because the messages of their peer kept exceeding their time budget
+static SLOW_SESSIONS_CLOSED: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    /// Messages whose handler exceeded its time budget, by actor and message type
+    static ref SLOW_HANDLERS_BY_TYPE: Mutex<BTreeMap<(&'static str, &'static str), usize>> =
+        Mutex::new(BTreeMap::new());
+}
+
+/// Convert a duration into the microseconds kept by the counters
+fn as_micros(duration: Duration) -> usize {
+    duration.as_secs() as usize * 1_000_000 + duration.subsec_micros() as usize
+}
+
+/// Raise a counter keeping a maximum to a new value, unless it already holds a higher one
+fn fetch_max(counter: &AtomicUsize, value: usize) {
+    let mut max = counter.load(Ordering::Relaxed);
+    while value > max {
+        match counter.compare_exchange_weak(max, value, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => break,
+            Err(current) => max = current,
+        }
+    }
+}
+
+/// Snapshot of the dropped messages counters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct DroppedMessages {
+    /// Messages not delivered to a session because its mailbox was full
+    pub session_mailbox_full: usize,
+    /// Inventory vectors discarded because they were already being processed
+    pub duplicate_inv_vectors: usize,
+    /// Payloads dropped because the peer had already sent them within the deduplication window
+    pub duplicate_payloads: usize,
+}
+
+/// Count a message that was not delivered to a session because its mailbox was full
+pub fn record_session_mailbox_full() {
+    SESSION_MAILBOX_FULL.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count inventory vectors that were discarded because they were already being processed
+pub fn record_duplicate_inv_vectors(count: usize) {
+    DUPLICATE_INV_VECTORS.fetch_add(count, Ordering::Relaxed);
+}
+
+/// Count a payload that was dropped because the peer had already sent it within the
+/// deduplication window
+pub fn record_duplicate_payload() {
+    DUPLICATE_PAYLOADS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Snapshot of the storage read cache counters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct StorageCacheStats {
+    /// Reads served by the cache
+    pub hits: usize,
+    /// Reads that went to the storage backend
+    pub misses: usize,
+}
+
+/// Count a read served by the storage read cache
+pub fn record_storage_cache_hit() {
+    STORAGE_CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a read that missed the storage read cache
+pub fn record_storage_cache_miss() {
+    STORAGE_CACHE_MISSES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Get the current value of the storage read cache counters
+pub fn storage_cache() -> StorageCacheStats {
+    StorageCacheStats {
+        hits: STORAGE_CACHE_HITS.load(Ordering::Relaxed),
+        misses: STORAGE_CACHE_MISSES.load(Ordering::Relaxed),
+    }
+}
+
+/// Snapshot of the block relay counters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct BlockRelayStats {
+    /// Block candidates announced to the peers
+    pub relayed: usize,
+    /// Average time elapsed between the arrival and the relay of a block
+    pub avg_latency: Duration,
+    /// Longest time elapsed between the arrival and the relay of a block
+    pub max_latency: Duration,
+    /// Block candidates relayed before being fully validated which turned out to be invalid
+    pub invalid_relayed: usize,
+}
+
+/// Count a block candidate announced to the peers, along with the time elapsed since it arrived
+pub fn record_block_relay(latency: Duration) {
+    let micros = as_micros(latency);
+    BLOCKS_RELAYED.fetch_add(1, Ordering::Relaxed);
+    BLOCK_RELAY_MICROS_TOTAL.fetch_add(micros, Ordering::Relaxed);
+    fetch_max(&BLOCK_RELAY_MICROS_MAX, micros);
+}
+
+/// Count a block candidate relayed before being fully validated which turned out to be invalid
+pub fn record_invalid_relayed_block() {
+    INVALID_RELAYED_BLOCKS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Get the current value of the block relay counters
+pub fn block_relay() -> BlockRelayStats {
+    let relayed = BLOCKS_RELAYED.load(Ordering::Relaxed);
+    let total = BLOCK_RELAY_MICROS_TOTAL.load(Ordering::Relaxed);
+    let avg_latency = if relayed == 0 {
+        Duration::from_secs(0)
+    } else {
+        Duration::from_micros((total / relayed) as u64)
+    };
+
+    BlockRelayStats {
+        relayed,
+        avg_latency,
+        max_latency: Duration::from_micros(BLOCK_RELAY_MICROS_MAX.load(Ordering::Relaxed) as u64),
+        invalid_relayed: INVALID_RELAYED_BLOCKS.load(Ordering::Relaxed),
+    }
+}
+
+/// Snapshot of the isolation of the node
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct IsolationStats {
+    /// Whether the node is currently isolated from the network
+    pub isolated: bool,
+    /// Times the node has become isolated from the network
+    pub isolations: usize,
+}
+
+/// Record that the node has become isolated from the network, or that it has been reconnected
+pub fn record_isolation(isolated: bool) {
+    if !ISOLATED.swap(isolated, Ordering::Relaxed) && isolated {
+        ISOLATIONS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Get the current isolation of the node
+pub fn isolation() -> IsolationStats {
+    IsolationStats {
+        isolated: ISOLATED.load(Ordering::Relaxed),
+        isolations: ISOLATIONS.load(Ordering::Relaxed),
+    }
+}
+
+/// Snapshot of the handlers which exceeded their time budget
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SlowHandlerStats {
+    /// Messages whose handler exceeded its time budget
+    pub slow_handlers: usize,
+    /// Messages whose handler exceeded its time budget, by handler (`Actor::MESSAGE`)
+    pub by_handler: BTreeMap<String, usize>,
+    /// Longest time taken by a handler which exceeded its time budget
+    pub max_elapsed: Duration,
+    /// Sessions closed because the messages of their peer kept exceeding their time budget
+    pub sessions_closed: usize,
+}
+
+/// Count a message whose handler exceeded its time budget, along with the actor and the message
+/// type of the handler and the time it took
+pub fn record_slow_handler(actor: &'static str, message: &'static str, elapsed: Duration) {
+    SLOW_HANDLERS.fetch_add(1, Ordering::Relaxed);
+    fetch_max(&SLOW_HANDLER_MICROS_MAX, as_micros(elapsed));
+
+    // A poisoned lock only means that another thread panicked while counting
+    let mut by_type = SLOW_HANDLERS_BY_TYPE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *by_type.entry((actor, message)).or_insert(0) += 1;
+}
+
+/// Count a session closed because the messages of its peer kept exceeding their time budget
+pub fn record_slow_session_closed() {
+    SLOW_SESSIONS_CLOSED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Get the current value of the slow handlers counters
+pub fn slow_handlers() -> SlowHandlerStats {
+    let by_handler = SLOW_HANDLERS_BY_TYPE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+        .map(|((actor, message), count)| (format!("{}::{}", actor, message), *count))
+        .collect();
+
+    SlowHandlerStats {
+        slow_handlers: SLOW_HANDLERS.load(Ordering::Relaxed),
+        by_handler,
+        max_elapsed: Duration::from_micros(SLOW_HANDLER_MICROS_MAX.load(Ordering::Relaxed) as u64),
+        sessions_closed: SLOW_SESSIONS_CLOSED.load(Ordering::Relaxed),
+    }
+}
+
+/// Get the current value of the dropped messages counters
+pub fn dropped_messages() -> DroppedMessages {
+    DroppedMessages {
+        session_mailbox_full: SESSION_MAILBOX_FULL.load(Ordering::Relaxed),
+        duplicate_inv_vectors: DUPLICATE_INV_VECTORS.load(Ordering::Relaxed),
+        duplicate_payloads: DUPLICATE_PAYLOADS.load(Ordering::Relaxed),
+    }
+}
+
+/// Snapshot of every counter
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Metrics {
+    /// Dropped messages counters
+    pub dropped_messages: DroppedMessages,
+    /// Storage read cache counters
+    pub storage_cache: StorageCacheStats,
+    /// Block relay counters
+    pub block_relay: BlockRelayStats,
+    /// Isolation of the node
+    pub isolation: IsolationStats,
+    /// Slow handlers counters
+    pub slow_handlers: SlowHandlerStats,
+}
+
+/// Get the current value of every counter
+pub fn snapshot() -> Metrics {
+    Metrics {
+        dropped_messages: dropped_messages(),
+        storage_cache: storage_cache(),
+        block_relay: block_relay(),
+        isolation: isolation(),
+        slow_handlers: slow_handlers(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_are_monotonic() {
+        let before = dropped_messages();
+
+        record_session_mailbox_full();
+        record_duplicate_inv_vectors(3);
+        record_duplicate_payload();
+
+        let after = dropped_messages();
+        assert!(after.session_mailbox_full >= before.session_mailbox_full + 1);
+        assert!(after.duplicate_inv_vectors >= before.duplicate_inv_vectors + 3);
+        assert!(after.duplicate_payloads >= before.duplicate_payloads + 1);
+
+        let before = storage_cache();
+
+        record_storage_cache_hit();
+        record_storage_cache_miss();
+
+        let after = storage_cache();
+        assert!(after.hits >= before.hits + 1);
+        assert!(after.misses >= before.misses + 1);
+
+        let before = block_relay();
+
+        record_block_relay(Duration::from_secs(3600));
+        record_invalid_relayed_block();
+
+        let after = block_relay();
+        assert!(after.relayed >= before.relayed + 1);
+        assert!(after.max_latency >= Duration::from_secs(3600));
+        assert!(after.avg_latency > Duration::from_secs(0));
+        assert!(after.invalid_relayed >= before.invalid_relayed + 1);
+
+        let before = isolation();
+
+        record_isolation(true);
+        record_isolation(true);
+
+        let after = isolation();
+        assert!(after.isolations >= before.isolations + 1);
+
+        record_isolation(false);
+
+        let before = slow_handlers();
+
+        record_slow_handler("Session", "BLOCK", Duration::from_secs(7200));
+        record_slow_session_closed();
+
+        let after = slow_handlers();
+        assert!(after.slow_handlers >= before.slow_handlers + 1);
+        let count =
+            |stats: &SlowHandlerStats| stats.by_handler.get("Session::BLOCK").cloned().unwrap_or(0);
+        assert!(count(&after) >= count(&before) + 1);
+        assert!(after.max_elapsed >= Duration::from_secs(7200));
+        assert!(after.sessions_closed >= before.sessions_closed + 1);
+
+        let before = snapshot();
+
+        record_session_mailbox_full();
+
+        let after = snapshot();
+        assert!(
+            after.dropped_messages.session_mailbox_full
+                >= before.dropped_messages.session_mailbox_full + 1
+        );
+    }
+}
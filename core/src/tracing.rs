@@ -0,0 +1,125 @@
+//! Correlation IDs of the protocol messages received from the peers
+//!
+//! When tracing is enabled (`node.tracing` in the configuration), every
+//! protocol message received by a session is tagged with a new
+//! correlation ID. The ID travels along with the actor messages sent
+//! while processing it (e.g. from the `Session` to the `BlocksManager`,
+//! and from there to the `MempoolManager` and the `StorageManager`), and
+//! it prefixes the log lines written by every actor, so all the steps of
+//! the processing of a message can be found by searching for its ID.
+//!
+//! When tracing is disabled, the IDs are empty and they are written as
+//! an empty string, so the log lines do not change.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
+
+use log::debug;
+
+/// Whether new correlation IDs are assigned
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Last correlation ID assigned
+static LAST_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Enable or disable the assignment of correlation IDs
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Check whether correlation IDs are being assigned
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Correlation ID of a protocol message, or empty if the message is not traced
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Trace(Option<usize>);
+
+impl Trace {
+    /// Start tracing a new message: a new correlation ID is assigned if tracing is enabled,
+    /// otherwise the trace is empty
+    pub fn start() -> Self {
+        if is_enabled() {
+            Trace(Some(LAST_ID.fetch_add(1, Ordering::Relaxed) + 1))
+        } else {
+            Trace(None)
+        }
+    }
+
+    /// Get the correlation ID, if the message is traced
+    pub fn id(self) -> Option<usize> {
+        self.0
+    }
+
+    /// Start a span of the processing of the message, which is logged along with its duration
+    /// when it is dropped
+    pub fn span(self, name: &'static str) -> Span {
+        Span {
+            trace: self,
+            name,
+            start: Instant::now(),
+        }
+    }
+}
+
+/// Correlation ID written as a prefix of the log lines (`[trace 1f] `), or nothing if the
+/// message is not traced
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(id) => write!(f, "[trace {:x}] ", id),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Step of the processing of a traced message
+#[derive(Debug)]
+pub struct Span {
+    /// Correlation ID of the message
+    trace: Trace,
+    /// Name of the step (usually the actor and the message handled)
+    name: &'static str,
+    /// Time at which the step started
+    start: Instant,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if self.trace.id().is_some() {
+            debug!(
+                "{}{} took {:?}",
+                self.trace,
+                self.name,
+                self.start.elapsed()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_ids() {
+        // Tracing is a global switch, so it is only toggled in this test
+        set_enabled(false);
+        let untraced = Trace::start();
+        assert_eq!(untraced.id(), None);
+        assert_eq!(untraced, Trace::default());
+        assert_eq!(untraced.to_string(), "");
+
+        set_enabled(true);
+        let first = Trace::start();
+        let second = Trace::start();
+        set_enabled(false);
+
+        let first_id = first.id().unwrap();
+        assert_eq!(second.id(), Some(first_id + 1));
+        assert_eq!(first.to_string(), format!("[trace {:x}] ", first_id));
+        assert_eq!(Trace::start(), Trace::default());
+    }
+}
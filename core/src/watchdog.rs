@@ -0,0 +1,211 @@
+//! # Watchdog of the message handlers
+//!
+//! The actors share the threads of the event loop, so a handler which takes
+//! too long processing a message delays every other actor. The handlers of
+//! the messages coming from the peers are timed against a budget, which
+//! depends on the actor and the message type (see the `watchdog` section of
+//! the configuration). The handlers exceeding their budget are logged and
+//! counted in the `metrics` module.
+//!
+//! A handler cannot be interrupted once it has started, but the sessions
+//! keep track of the messages of their peer whose handler exceeded its
+//! budget. The messages of a peer are mostly processed by other actors (e.g.
+//! the blocks are validated by the BlocksManager), so the messages forwarded
+//! on behalf of a peer carry its session, and the handlers of those actors
+//! report back to it when they exceed their budget. A peer whose messages
+//! keep causing slow handlers (e.g. crafted to hit the worst case of some
+//! algorithm) is disconnected, and the messages it sent which were still
+//! queued are discarded without being processed.
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix::Recipient;
+use log::warn;
+
+use witnet_config::config::{Config, Watchdog as WatchdogConfig};
+
+use crate::actors::session::messages::ReportSlowMessage;
+use crate::metrics;
+
+/// Time budgets of the handlers, shared by the actors
+#[derive(Clone, Debug)]
+pub struct Watchdog {
+    /// Watchdog configuration
+    config: Arc<WatchdogConfig>,
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self::new(&Config::default().watchdog)
+    }
+}
+
+impl Watchdog {
+    /// Create a watchdog with the given configuration
+    pub fn new(config: &WatchdogConfig) -> Self {
+        Watchdog {
+            config: Arc::new(config.clone()),
+        }
+    }
+
+    /// Start timing the handler of a message type of an actor
+    pub fn start(&self, actor: &'static str, message: &'static str) -> HandlerTimer {
+        self.start_for(actor, message, None)
+    }
+
+    /// Start timing the handler of a message type of an actor, processed on behalf of the peer
+    /// of a session, which is notified if the handler exceeds its budget
+    pub fn start_for(
+        &self,
+        actor: &'static str,
+        message: &'static str,
+        origin: Option<Recipient<ReportSlowMessage>>,
+    ) -> HandlerTimer {
+        HandlerTimer {
+            actor,
+            message,
+            budget: self.config.budget_of(actor, message),
+            start: Instant::now(),
+            stopped: false,
+            origin,
+        }
+    }
+
+    /// Create an empty record of the slow messages of a peer
+    pub fn slow_messages(&self) -> SlowMessages {
+        SlowMessages::new(
+            self.config.slow_messages_limit,
+            self.config.slow_messages_window,
+        )
+    }
+}
+
+/// Timer of the execution of a handler. The handler is checked against its budget when the timer
+/// is stopped, or when it is dropped if it was not stopped
+pub struct HandlerTimer {
+    /// Actor running the handler
+    actor: &'static str,
+    /// Message type handled
+    message: &'static str,
+    /// Budget of the handler
+    budget: Duration,
+    /// Time at which the handler started
+    start: Instant,
+    /// Whether the handler has already been checked
+    stopped: bool,
+    /// Session of the peer on whose behalf the message is processed, if any
+    origin: Option<Recipient<ReportSlowMessage>>,
+}
+
+impl HandlerTimer {
+    /// Stop the timer, logging and counting the handler if it exceeded its budget
+    /// Returns the time taken by the handler if it exceeded its budget
+    pub fn stop(mut self) -> Option<Duration> {
+        self.stopped = true;
+
+        self.check(self.start.elapsed())
+    }
+
+    /// Check the time taken by the handler against its budget
+    fn check(&self, elapsed: Duration) -> Option<Duration> {
+        if elapsed <= self.budget {
+            return None;
+        }
+
+        warn!(
+            "{}::{} took {:?}, exceeding its budget of {:?}",
+            self.actor, self.message, elapsed, self.budget
+        );
+        metrics::record_slow_handler(self.actor, self.message, elapsed);
+        if let Some(origin) = &self.origin {
+            // The session may have been closed in the meantime
+            let _ = origin.do_send(ReportSlowMessage {
+                actor: self.actor,
+                message: self.message,
+                elapsed,
+            });
+        }
+
+        Some(elapsed)
+    }
+}
+
+impl Drop for HandlerTimer {
+    fn drop(&mut self) {
+        if !self.stopped {
+            self.check(self.start.elapsed());
+        }
+    }
+}
+
+/// Messages of a peer whose handler exceeded its budget during the last window
+#[derive(Debug)]
+pub struct SlowMessages {
+    /// Number of slow messages within the window after which the peer is disconnected
+    limit: usize,
+    /// Time during which a slow message is remembered
+    window: Duration,
+    /// Time at which the slow messages were handled, oldest first
+    handled: VecDeque<Instant>,
+}
+
+impl SlowMessages {
+    /// Create an empty record of slow messages, which are remembered during `window`
+    pub fn new(limit: usize, window: Duration) -> Self {
+        SlowMessages {
+            limit,
+            window,
+            handled: VecDeque::new(),
+        }
+    }
+
+    /// Record a message whose handler exceeded its budget
+    /// Returns whether the peer has reached the limit of slow messages within the window
+    pub fn record(&mut self, now: Instant) -> bool {
+        while let Some(&handled_at) = self.handled.front() {
+            if now.duration_since(handled_at) < self.window {
+                break;
+            }
+            self.handled.pop_front();
+        }
+        self.handled.push_back(now);
+
+        self.handled.len() >= self.limit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handlers_are_checked_against_their_budget() {
+        let watchdog = Watchdog::default();
+        let config = Config::default().watchdog;
+
+        let timer = watchdog.start("Session", "PING");
+        assert_eq!(timer.budget, config.budget);
+        assert_eq!(timer.check(config.budget), None);
+        let slow = config.budget + Duration::from_millis(1);
+        assert_eq!(timer.check(slow), Some(slow));
+        assert_eq!(timer.stop(), None);
+
+        // Message types with their own budget
+        let timer = watchdog.start("Session", "BLOCK");
+        assert_eq!(timer.budget, config.budget_of("Session", "BLOCK"));
+        assert_eq!(timer.check(slow), None);
+    }
+
+    #[test]
+    fn slow_messages_are_counted_within_the_window() {
+        let mut slow_messages = SlowMessages::new(3, Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert!(!slow_messages.record(start));
+        assert!(!slow_messages.record(start + Duration::from_secs(5)));
+        // The first slow message is forgotten once the window has elapsed
+        assert!(!slow_messages.record(start + Duration::from_secs(10)));
+        assert!(slow_messages.record(start + Duration::from_secs(11)));
+    }
+}
@@ -1,4 +1,4 @@
-use witnet_core::actors::epoch_manager::{EpochManager, EpochManagerError};
+use witnet_core::actors::epoch_manager::{missed_epochs, EpochManager, EpochManagerError};
 
 #[test]
 fn epoch_zero_range() {
@@ -44,3 +44,57 @@ fn epoch_unknown() {
     // By default, the epoch manager doesn't know when the epoch zero started
     assert_eq!(em.epoch_at(1234), Err(EpochManagerError::UnknownEpochZero));
 }
+
+#[test]
+fn next_checkpoint() {
+    let zero = 1000;
+    let period = 5;
+    let mut em = EpochManager::default();
+    em.set_checkpoint_zero(zero);
+    em.set_period(period);
+
+    // Before checkpoint zero, the next checkpoint is checkpoint zero
+    assert_eq!(em.next_checkpoint_at(0), Ok(zero));
+    assert_eq!(em.next_checkpoint_at(999), Ok(zero));
+    // Sub-minute periods
+    assert_eq!(em.next_checkpoint_at(1000), Ok(1005));
+    assert_eq!(em.next_checkpoint_at(1004), Ok(1005));
+    assert_eq!(em.next_checkpoint_at(1005), Ok(1010));
+}
+
+#[test]
+fn next_checkpoint_unknown_period() {
+    let mut em = EpochManager::default();
+    em.set_checkpoint_zero(1000);
+
+    assert_eq!(
+        em.next_checkpoint_at(1234),
+        Err(EpochManagerError::UnknownCheckpointPeriod)
+    );
+}
+
+#[test]
+fn missed_epochs_range() {
+    // Nothing is missed before the first checkpoint is checked
+    assert_eq!(missed_epochs(None, 7), None);
+    // Nothing is missed between consecutive epochs
+    assert_eq!(missed_epochs(Some(6), 7), None);
+    assert_eq!(missed_epochs(Some(7), 7), None);
+    // Epochs 5 and 6 were skipped
+    assert_eq!(missed_epochs(Some(4), 7), Some((5, 6)));
+}
+
+#[test]
+fn epoch_info() {
+    let mut em = EpochManager::default();
+    em.set_checkpoint_zero(1000);
+    em.set_period(90);
+
+    let info = em.epoch_info_at(999).unwrap();
+    assert_eq!(info.epoch, None);
+    assert_eq!(info.next_checkpoint, 1000);
+
+    let info = em.epoch_info_at(1100).unwrap();
+    assert_eq!(info.epoch, Some(1));
+    assert_eq!(info.next_checkpoint, 1180);
+}
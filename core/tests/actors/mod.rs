@@ -1,2 +1,3 @@
 pub mod codec;
 pub mod epoch_manager;
+pub mod session;
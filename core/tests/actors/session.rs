@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+use witnet_core::actors::session::announcements::MAX_INV_VECTORS_PER_MESSAGE;
+use witnet_data_structures::{
+    builders::MAGIC,
+    chain::{Hash, InvVector},
+    types::Message as WitnetMessage,
+};
+
+use crate::support::scripted_peer::{
+    frame, handshake, peer_address, peer_version, run_script, Step,
+};
+
+/// Build an Inv message announcing `len` different blocks
+fn inv(len: usize) -> WitnetMessage {
+    let inv_vectors = (0..len)
+        .map(|i| {
+            let mut hash = [0; 32];
+            hash[0] = (i >> 8) as u8;
+            hash[1] = i as u8;
+            InvVector::Block(Hash::SHA256(hash))
+        })
+        .collect();
+
+    WitnetMessage::build_inv(inv_vectors).unwrap()
+}
+
+/// Check that a session is consolidated once the handshake is completed, so it is not closed
+/// when the handshake timeout (1 second) expires
+#[test]
+fn session_conformance_handshake() {
+    let mut script = handshake();
+    script.push(Step::ExpectOpen(Duration::from_millis(1500)));
+
+    assert_eq!(run_script(script), Ok(()));
+}
+
+/// Check that a session which is never consolidated is closed when the handshake timeout expires
+#[test]
+fn session_conformance_handshake_timeout() {
+    let script = vec![
+        Step::ExpectNothing(Duration::from_millis(500)),
+        Step::ExpectClosed,
+    ];
+
+    assert_eq!(run_script(script), Ok(()));
+}
+
+/// Check that the session is closed when a message of another network is received
+#[test]
+fn session_conformance_bad_magic() {
+    let version = peer_version();
+    let script = vec![
+        Step::Send(WitnetMessage {
+            magic: !MAGIC,
+            ..version
+        }),
+        Step::ExpectClosed,
+    ];
+
+    assert_eq!(run_script(script), Ok(()));
+}
+
+/// Check that the session is never consolidated with a peer whose consensus constants do not
+/// match the ones of the node (or which does not send them), so it is closed right away
+#[test]
+fn session_conformance_consensus_constants_mismatch() {
+    let other_network = Some(Hash::SHA256([0; 32]));
+    for &consensus_constants_hash in &[other_network, None] {
+        let version = WitnetMessage::build_version(
+            peer_address(),
+            peer_address(),
+            0,
+            0,
+            consensus_constants_hash,
+        );
+        let script = vec![Step::Send(version), Step::ExpectClosed];
+
+        assert_eq!(run_script(script), Ok(()));
+    }
+}
+
+/// Check that a truncated frame is not processed until the rest of its bytes arrive
+#[test]
+fn session_conformance_truncated_frame() {
+    let version: Vec<u8> = peer_version().into();
+    let version = frame(&version);
+    let (head, tail) = version.split_at(version.len() / 2);
+
+    let script = vec![
+        Step::SendRaw(head.to_vec()),
+        Step::ExpectNothing(Duration::from_millis(300)),
+        Step::SendRaw(tail.to_vec()),
+        Step::Expect("VERACK"),
+        Step::Expect("VERSION"),
+    ];
+
+    assert_eq!(run_script(script), Ok(()));
+}
+
+/// Check that a frame whose header announces more bytes than the ones sent is never processed,
+/// so the session is closed when the handshake timeout expires
+#[test]
+fn session_conformance_incomplete_frame() {
+    let version: Vec<u8> = peer_version().into();
+    let mut truncated = frame(&version);
+    truncated.truncate(truncated.len() - 1);
+
+    let script = vec![
+        Step::SendRaw(truncated),
+        Step::ExpectNothing(Duration::from_millis(500)),
+        Step::ExpectClosed,
+    ];
+
+    assert_eq!(run_script(script), Ok(()));
+}
+
+/// Check that a Verack message received before the Version message of the session has been
+/// sent does not consolidate the session
+#[test]
+fn session_conformance_verack_before_version() {
+    let script = vec![
+        Step::Send(WitnetMessage::build_verack()),
+        Step::ExpectNothing(Duration::from_millis(500)),
+        Step::ExpectClosed,
+    ];
+
+    assert_eq!(run_script(script), Ok(()));
+}
+
+/// Check that a Verack message received once the session is consolidated closes the session
+#[test]
+fn session_conformance_verack_after_handshake() {
+    let mut script = handshake();
+    script.push(Step::Send(WitnetMessage::build_verack()));
+    script.push(Step::ExpectClosed);
+
+    assert_eq!(run_script(script), Ok(()));
+}
+
+/// Check that an Inv message announcing as many items as allowed is accepted
+#[test]
+fn session_conformance_max_inv() {
+    let mut script = handshake();
+    script.push(Step::Send(inv(MAX_INV_VECTORS_PER_MESSAGE)));
+    script.push(Step::ExpectOpen(Duration::from_millis(500)));
+
+    assert_eq!(run_script(script), Ok(()));
+}
+
+/// Check that an Inv message announcing more items than allowed closes the session
+#[test]
+fn session_conformance_oversized_inv() {
+    let mut script = handshake();
+    script.push(Step::Send(inv(MAX_INV_VECTORS_PER_MESSAGE + 1)));
+    script.push(Step::ExpectClosed);
+
+    assert_eq!(run_script(script), Ok(()));
+}
@@ -25,3 +25,38 @@ fn test_config_manager_load_config() {
 
     sys.run();
 }
+
+#[test]
+fn test_config_manager_invalid_config() {
+    use std::path::PathBuf;
+    let mut config_manager =
+        ConfigManager::new(Some(PathBuf::from("tests/fixtures/invalid_config.toml")));
+
+    match config_manager.load() {
+        Err(ConfigError::Invalid(problems)) => {
+            let fields: Vec<_> = problems.iter().map(|problem| problem.field).collect();
+            assert_eq!(
+                fields,
+                vec![
+                    "connections.outbound_limit",
+                    "witnessing.max_concurrent_jobs"
+                ]
+            );
+        }
+        res => panic!("Unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn test_config_manager_missing_file() {
+    use std::path::PathBuf;
+    let mut config_manager =
+        ConfigManager::new(Some(PathBuf::from("tests/fixtures/missing_config.toml")));
+
+    match config_manager.load() {
+        Err(ConfigError::Load(file, _)) => {
+            assert_eq!(file, PathBuf::from("tests/fixtures/missing_config.toml"))
+        }
+        res => panic!("Unexpected result: {:?}", res),
+    }
+}
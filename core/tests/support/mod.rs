@@ -0,0 +1 @@
+pub mod scripted_peer;
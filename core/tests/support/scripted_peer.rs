@@ -0,0 +1,280 @@
+//! Scripted peer to test the conformance of the sessions to the wire protocol
+//!
+//! A [`ScriptedPeer`](ScriptedPeer) connects to an inbound session of a
+//! node started for the test and replays a script of byte-level
+//! exchanges: it sends messages (or arbitrary bytes, to exercise malformed
+//! frames) and checks the messages sent back by the session and whether
+//! it closes the connection.
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use actix::{Actor, Arbiter, System};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use futures::{Future, Stream};
+use tokio::net::TcpListener;
+
+use witnet_config::{config::Config, loaders::toml};
+use witnet_core::actors::{
+    config_manager::{consensus_constants_hash, ConfigManager},
+    sessions_manager::{messages::Create, SessionsManager},
+};
+use witnet_data_structures::{chain::Hash, serializers::TryFrom, types::Message as WitnetMessage};
+use witnet_p2p::sessions::SessionType;
+
+/// Configuration of the node the scripted peer connects to (1 second of handshake timeout, no
+/// encryption nor compression)
+const CONFIG_FILE: &str = "tests/fixtures/conformance.toml";
+
+/// Maximum time the scripted peer waits for the session to send a message or close the
+/// connection
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Step of the script replayed by a scripted peer
+#[derive(Debug)]
+pub enum Step {
+    /// Send a message in a frame of its own
+    Send(WitnetMessage),
+    /// Send some bytes as they are, without framing them
+    SendRaw(Vec<u8>),
+    /// Wait for the next message of the session, which has to be of the given type (e.g.
+    /// `"VERACK"`)
+    Expect(&'static str),
+    /// Check that the session neither sends anything nor closes the connection for a while
+    ExpectNothing(Duration),
+    /// Check that the session does not close the connection for a while, ignoring the messages
+    /// it sends meanwhile
+    ExpectOpen(Duration),
+    /// Wait for the session to close the connection, ignoring the messages it sends meanwhile
+    ExpectClosed,
+}
+
+/// Peer connected to a session which replays a script
+pub struct ScriptedPeer {
+    /// Connection with the session
+    stream: TcpStream,
+}
+
+impl ScriptedPeer {
+    /// Connect to a node
+    pub fn connect(address: SocketAddr) -> io::Result<ScriptedPeer> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+
+        Ok(ScriptedPeer { stream })
+    }
+
+    /// Replay a script, stopping at the first step which fails
+    pub fn run(&mut self, script: Vec<Step>) -> Result<(), String> {
+        for (i, step) in script.into_iter().enumerate() {
+            let description = format!("{:?}", step);
+            self.step(step)
+                .map_err(|e| format!("Step {} ({}) failed: {}", i, description, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Replay a single step of a script
+    fn step(&mut self, step: Step) -> Result<(), String> {
+        match step {
+            Step::Send(msg) => {
+                let bytes: Vec<u8> = msg.into();
+                self.write(&frame(&bytes))
+            }
+            Step::SendRaw(bytes) => self.write(&bytes),
+            Step::Expect(command) => match self.read_frame(TIMEOUT)? {
+                Some(bytes) => {
+                    let msg = WitnetMessage::try_from(bytes)
+                        .map_err(|e| format!("invalid message: {:?}", e))?;
+                    if msg.kind.to_string() == command {
+                        Ok(())
+                    } else {
+                        Err(format!("received a {} message", msg.kind))
+                    }
+                }
+                None => Err("the session closed the connection".to_string()),
+            },
+            Step::ExpectNothing(duration) => match self.read_frame(duration) {
+                Ok(Some(_)) => Err("the session sent a message".to_string()),
+                Ok(None) => Err("the session closed the connection".to_string()),
+                Err(ref e) if is_timeout(e) => Ok(()),
+                Err(e) => Err(e),
+            },
+            Step::ExpectOpen(duration) => {
+                let deadline = Instant::now() + duration;
+                loop {
+                    match self.read_frame(remaining(deadline)?) {
+                        Ok(Some(_)) => {}
+                        Ok(None) => return Err("the session closed the connection".to_string()),
+                        Err(ref e) if is_timeout(e) => return Ok(()),
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+            Step::ExpectClosed => {
+                let deadline = Instant::now() + TIMEOUT;
+                while self.read_frame(remaining(deadline)?)?.is_some() {}
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Write some bytes to the connection
+    fn write(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.stream
+            .write_all(bytes)
+            .map_err(|e| format!("error writing to the session: {}", e))
+    }
+
+    /// Read the next frame sent by the session, waiting at most `timeout`
+    /// Returns `None` if the session closed the connection
+    fn read_frame(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, String> {
+        self.stream
+            .set_read_timeout(Some(timeout))
+            .map_err(|e| e.to_string())?;
+
+        let len = match self.stream.read_u16::<BigEndian>() {
+            Ok(len) => len,
+            Err(ref e) if is_closed(e) => return Ok(None),
+            Err(ref e) if is_read_timeout(e) => return Err(TIMED_OUT.to_string()),
+            Err(e) => return Err(format!("error reading from the session: {}", e)),
+        };
+
+        let mut bytes = vec![0; usize::from(len)];
+        match self.stream.read_exact(&mut bytes) {
+            Ok(()) => Ok(Some(bytes)),
+            Err(ref e) if is_closed(e) => Ok(None),
+            Err(e) => Err(format!("error reading from the session: {}", e)),
+        }
+    }
+}
+
+/// Error of the steps which time out
+const TIMED_OUT: &str = "timed out";
+
+/// Get the time remaining until a deadline, failing if it has already passed
+fn remaining(deadline: Instant) -> Result<Duration, String> {
+    let now = Instant::now();
+    if now < deadline {
+        Ok(deadline - now)
+    } else {
+        Err(TIMED_OUT.to_string())
+    }
+}
+
+/// Check whether a step failed because it timed out
+fn is_timeout(error: &str) -> bool {
+    error == TIMED_OUT
+}
+
+/// Check whether a read failed because the connection was closed
+fn is_closed(error: &io::Error) -> bool {
+    match error.kind() {
+        io::ErrorKind::UnexpectedEof
+        | io::ErrorKind::ConnectionReset
+        | io::ErrorKind::ConnectionAborted => true,
+        _ => false,
+    }
+}
+
+/// Check whether a read failed because its timeout expired
+fn is_read_timeout(error: &io::Error) -> bool {
+    match error.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => true,
+        _ => false,
+    }
+}
+
+/// Prepend some bytes with their length, as the `P2PCodec` does
+pub fn frame(bytes: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(2 + bytes.len());
+    frame.write_u16::<BigEndian>(bytes.len() as u16).unwrap();
+    frame.extend_from_slice(bytes);
+
+    frame
+}
+
+/// Address advertised by the scripted peer in its `Version` message
+pub fn peer_address() -> SocketAddr {
+    "127.0.0.1:21337".parse().unwrap()
+}
+
+/// Hash of the consensus constants of the node the scripted peer connects to
+pub fn node_consensus_constants_hash() -> Hash {
+    let partial = toml::from_file(&PathBuf::from(CONFIG_FILE)).unwrap();
+
+    consensus_constants_hash(&Config::from_partial(&partial).consensus_constants)
+}
+
+/// `Version` message of the scripted peer, with the same consensus constants as the node
+pub fn peer_version() -> WitnetMessage {
+    WitnetMessage::build_version(
+        peer_address(),
+        peer_address(),
+        0,
+        0,
+        Some(node_consensus_constants_hash()),
+    )
+}
+
+/// Steps of a successful handshake with an inbound session: the session answers the `Version`
+/// message of the peer with its `Verack` and `Version` messages, and it is consolidated once the
+/// `Verack` message of the peer is received
+pub fn handshake() -> Vec<Step> {
+    vec![
+        Step::Send(peer_version()),
+        Step::Expect("VERACK"),
+        Step::Expect("VERSION"),
+        Step::Send(WitnetMessage::build_verack()),
+    ]
+}
+
+/// Start a node with the actors needed by the sessions, let a scripted peer connect to it and
+/// replay a script, returning the result of the script once the node has been stopped
+pub fn run_script(script: Vec<Step>) -> Result<(), String> {
+    let system = System::new("conformance");
+
+    let config_manager = ConfigManager::new(Some(PathBuf::from(CONFIG_FILE)));
+    System::current().registry().set(config_manager.start());
+
+    // Accept a single connection, which is handled by an inbound session
+    let listener = TcpListener::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let address = listener.local_addr().unwrap();
+    Arbiter::spawn(
+        listener
+            .incoming()
+            .take(1)
+            .for_each(|stream| {
+                System::current()
+                    .registry()
+                    .get::<SessionsManager>()
+                    .do_send(Create {
+                        stream,
+                        session_type: SessionType::Inbound,
+                    });
+
+                Ok(())
+            })
+            .map_err(|e| panic!("Error accepting the scripted peer: {}", e)),
+    );
+
+    // The scripted peer uses blocking sockets, so it runs in a thread of its own
+    let (tx, rx) = mpsc::channel();
+    let node = System::current();
+    thread::spawn(move || {
+        let result = ScriptedPeer::connect(address)
+            .map_err(|e| format!("Error connecting to the node: {}", e))
+            .and_then(|mut peer| peer.run(script));
+        tx.send(result).unwrap();
+        node.stop();
+    });
+
+    system.run();
+
+    rx.recv().unwrap()
+}
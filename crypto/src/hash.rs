@@ -1,6 +1,6 @@
 //! Various hash functions
 
-use witnet_data_structures::chain::Hash;
+use witnet_data_structures::chain::{Hash, PublicKeyHash};
 
 use crypto::digest::Digest;
 use crypto::sha2::Sha256;
@@ -13,3 +13,13 @@ pub fn calculate_sha256(bytes: &[u8]) -> Hash {
     hasher.result(&mut hash);
     Hash::SHA256(hash)
 }
+
+/// Calculate the hash of a public key, which identifies its owner: the first 20 bytes of its
+/// SHA256 hash
+pub fn calculate_public_key_hash(public_key: &[u8]) -> PublicKeyHash {
+    let Hash::SHA256(hash) = calculate_sha256(public_key);
+    let mut pkh = [0; 20];
+    pkh.copy_from_slice(&hash[..20]);
+
+    pkh
+}
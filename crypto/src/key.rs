@@ -0,0 +1,270 @@
+//! Hierarchical deterministic keys, as defined in BIP32
+
+use std::fmt;
+use std::str::FromStr;
+
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::Sha512;
+use failure::Fail;
+use secp256k1::{All, PublicKey, Secp256k1, SecretKey};
+
+/// Child indexes equal or greater than this one are hardened
+pub const HARDENED_INDEX: u32 = 1 << 31;
+
+/// HMAC key used to derive the master key from a seed, as defined in BIP32
+const MASTER_KEY_HMAC_KEY: &[u8] = b"Bitcoin seed";
+
+/// Minimum length of a seed, in bytes
+const MIN_SEED_LENGTH: usize = 16;
+
+/// Maximum length of a seed, in bytes
+const MAX_SEED_LENGTH: usize = 64;
+
+/// Possible errors when deriving keys
+#[derive(Debug, Fail, PartialEq)]
+pub enum KeyError {
+    /// The seed is too short or too long
+    #[fail(display = "Invalid seed length: {} bytes", _0)]
+    InvalidSeedLength(usize),
+    /// The derived key is not a valid secp256k1 secret key
+    #[fail(display = "Derived key is not valid")]
+    InvalidKey,
+    /// The key path could not be parsed
+    #[fail(display = "Invalid key path: {}", _0)]
+    InvalidKeyPath(String),
+}
+
+/// Derivation path of a key, as the list of child indexes starting from the master key
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct KeyPath(Vec<u32>);
+
+impl KeyPath {
+    /// Path of the master key
+    pub fn master() -> Self {
+        KeyPath(vec![])
+    }
+
+    /// Append a child index to the path
+    pub fn child(mut self, index: u32) -> Self {
+        self.0.push(index);
+        self
+    }
+
+    /// Append a hardened child index to the path
+    pub fn hardened(self, index: u32) -> Self {
+        self.child(index | HARDENED_INDEX)
+    }
+
+    /// Child indexes of the path
+    pub fn indexes(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            if *index >= HARDENED_INDEX {
+                write!(f, "/{}'", index - HARDENED_INDEX)?;
+            } else {
+                write!(f, "/{}", index)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for KeyPath {
+    type Err = KeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || KeyError::InvalidKeyPath(s.to_string());
+        let mut parts = s.split('/');
+        if parts.next() != Some("m") {
+            return Err(invalid());
+        }
+
+        parts.try_fold(KeyPath::master(), |path, part| {
+            let (index, hardened) = if part.ends_with('\'') {
+                (&part[..part.len() - 1], true)
+            } else {
+                (part, false)
+            };
+            let index: u32 = index.parse().map_err(|_| invalid())?;
+            if index >= HARDENED_INDEX {
+                Err(invalid())
+            } else if hardened {
+                Ok(path.hardened(index))
+            } else {
+                Ok(path.child(index))
+            }
+        })
+    }
+}
+
+/// Extended secret key: a secret key along with its chain code
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExtendedSK {
+    /// Secret key
+    secret_key: SecretKey,
+    /// Chain code
+    chain_code: [u8; 32],
+}
+
+impl ExtendedSK {
+    /// Derive the master key from a seed
+    pub fn from_seed(seed: &[u8]) -> Result<Self, KeyError> {
+        if seed.len() < MIN_SEED_LENGTH || seed.len() > MAX_SEED_LENGTH {
+            return Err(KeyError::InvalidSeedLength(seed.len()));
+        }
+
+        Self::from_hmac_result(hmac_sha512(MASTER_KEY_HMAC_KEY, seed))
+    }
+
+    /// Secret key
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.secret_key
+    }
+
+    /// Chain code
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    /// Derive the child key with the given index
+    pub fn child(&self, secp: &Secp256k1<All>, index: u32) -> Result<Self, KeyError> {
+        let mut data = Vec::with_capacity(37);
+        if index >= HARDENED_INDEX {
+            data.push(0);
+            data.extend_from_slice(&self.secret_key[..]);
+        } else {
+            data.extend_from_slice(&PublicKey::from_secret_key(secp, &self.secret_key).serialize());
+        }
+        data.extend_from_slice(&[
+            (index >> 24) as u8,
+            (index >> 16) as u8,
+            (index >> 8) as u8,
+            index as u8,
+        ]);
+
+        let child = Self::from_hmac_result(hmac_sha512(&self.chain_code, &data))?;
+        let mut secret_key = self.secret_key.clone();
+        secret_key
+            .add_assign(&child.secret_key[..])
+            .map_err(|_| KeyError::InvalidKey)?;
+
+        Ok(ExtendedSK {
+            secret_key,
+            chain_code: child.chain_code,
+        })
+    }
+
+    /// Derive the key with the given path, relative to this key
+    pub fn derive(&self, secp: &Secp256k1<All>, path: &KeyPath) -> Result<Self, KeyError> {
+        path.indexes()
+            .iter()
+            .try_fold(self.clone(), |key, index| key.child(secp, *index))
+    }
+
+    /// Build an extended key from the left and right halves of an HMAC-SHA512 result
+    fn from_hmac_result(result: [u8; 64]) -> Result<Self, KeyError> {
+        let secret_key = SecretKey::from_slice(&result[..32]).map_err(|_| KeyError::InvalidKey)?;
+        let mut chain_code = [0; 32];
+        chain_code.copy_from_slice(&result[32..]);
+
+        Ok(ExtendedSK {
+            secret_key,
+            chain_code,
+        })
+    }
+}
+
+/// Calculate the HMAC-SHA512 of some data
+fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let mut hmac = Hmac::new(Sha512::new(), key);
+    hmac.input(data);
+    let mut result = [0; 64];
+    hmac.raw_result(&mut result);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn key_path_display_and_parse() {
+        let path = KeyPath::master().hardened(44).hardened(4919).child(3);
+        assert_eq!(path.to_string(), "m/44'/4919'/3");
+        assert_eq!("m/44'/4919'/3".parse(), Ok(path));
+        assert_eq!("m".parse(), Ok(KeyPath::master()));
+
+        for invalid in &["", "44'", "m/", "m/a", "m/2147483648", "m/1''"] {
+            assert_eq!(
+                invalid.parse::<KeyPath>(),
+                Err(KeyError::InvalidKeyPath(invalid.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_seed_length() {
+        assert_eq!(
+            ExtendedSK::from_seed(&[0; 15]),
+            Err(KeyError::InvalidSeedLength(15))
+        );
+        assert_eq!(
+            ExtendedSK::from_seed(&[0; 65]),
+            Err(KeyError::InvalidSeedLength(65))
+        );
+    }
+
+    #[test]
+    fn bip32_test_vector_1() {
+        let secp = Secp256k1::new();
+        let master = ExtendedSK::from_seed(&from_hex("000102030405060708090a0b0c0d0e0f")).unwrap();
+        assert_eq!(
+            master.secret_key()[..],
+            from_hex("e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35")[..]
+        );
+        assert_eq!(
+            master.chain_code()[..],
+            from_hex("873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508")[..]
+        );
+
+        // m/0'
+        let key = master
+            .derive(&secp, &KeyPath::master().hardened(0))
+            .unwrap();
+        assert_eq!(
+            key.secret_key()[..],
+            from_hex("edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea")[..]
+        );
+        assert_eq!(
+            key.chain_code()[..],
+            from_hex("47fdacbd0f1097043b78c63c20c34ef4ed9a111d980047ad16282c7ae6236141")[..]
+        );
+
+        // m/0'/1
+        let key = master.derive(&secp, &"m/0'/1".parse().unwrap()).unwrap();
+        assert_eq!(
+            key.secret_key()[..],
+            from_hex("3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368")[..]
+        );
+        assert_eq!(
+            key.chain_code()[..],
+            from_hex("2a7857631386ba23dacac34180dd1983734e444fdbf774041578e9b6adb37c19")[..]
+        );
+    }
+}
@@ -9,3 +9,15 @@
 
 /// Hash functions
 pub mod hash;
+
+/// Merkle trees
+pub mod merkle;
+
+/// Hierarchical deterministic keys
+pub mod key;
+
+/// Mnemonic codes
+pub mod mnemonic;
+
+/// Digital signatures
+pub mod signature;
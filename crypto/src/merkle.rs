@@ -0,0 +1,59 @@
+//! Merkle trees of hashes
+
+use witnet_data_structures::chain::Hash;
+
+use crate::hash::calculate_sha256;
+
+/// Prefix of the hashed bytes of the leaves of a merkle tree
+const LEAF_TAG: u8 = 0x00;
+
+/// Prefix of the hashed bytes of the inner nodes of a merkle tree
+const INNER_NODE_TAG: u8 = 0x01;
+
+/// Calculate the merkle root of a list of hashes
+///
+/// Every leaf is the SHA256 hash of `0x00` followed by the hash it commits to, and every inner
+/// node is the SHA256 hash of `0x01` followed by its two children. The different prefixes
+/// separate both kinds of nodes, so an inner node can never be passed off as a leaf (and the
+/// other way round). When a level has an odd number of nodes, the last one is promoted to the
+/// next level as is (instead of being paired with itself, so that two different lists cannot have
+/// the same root). The root of an empty list is the zero hash.
+pub fn merkle_root(leaves: &[Hash]) -> Hash {
+    if leaves.is_empty() {
+        return Hash::SHA256([0; 32]);
+    }
+
+    let mut level: Vec<Hash> = leaves.iter().map(merkle_leaf).collect();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => merkle_inner_node(left, right),
+                _ => pair[0],
+            })
+            .collect();
+    }
+
+    level[0]
+}
+
+/// Calculate the leaf of a merkle tree committing to a hash
+pub fn merkle_leaf(hash: &Hash) -> Hash {
+    let Hash::SHA256(hash) = hash;
+    let mut bytes = [0; 33];
+    bytes[0] = LEAF_TAG;
+    bytes[1..].copy_from_slice(hash);
+
+    calculate_sha256(&bytes)
+}
+
+/// Calculate the inner node of a merkle tree from its two children
+pub fn merkle_inner_node(left: &Hash, right: &Hash) -> Hash {
+    let (Hash::SHA256(left), Hash::SHA256(right)) = (left, right);
+    let mut bytes = [0; 65];
+    bytes[0] = INNER_NODE_TAG;
+    bytes[1..33].copy_from_slice(left);
+    bytes[33..].copy_from_slice(right);
+
+    calculate_sha256(&bytes)
+}
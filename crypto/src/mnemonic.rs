@@ -0,0 +1,91 @@
+//! Mnemonic codes to back up the seed of a wallet, as defined in BIP39
+
+use bip39::{Language, Mnemonic as Bip39Mnemonic, MnemonicType, Seed};
+use failure::Fail;
+
+/// Number of words of the generated mnemonics (256 bits of entropy)
+pub const MNEMONIC_WORDS: usize = 24;
+
+/// Possible errors when parsing mnemonics
+#[derive(Debug, Fail, PartialEq)]
+pub enum MnemonicError {
+    /// The mnemonic has a word not included in the English wordlist, a wrong number of words
+    /// or a wrong checksum
+    #[fail(display = "Invalid mnemonic: {}", _0)]
+    InvalidMnemonic(String),
+}
+
+/// Mnemonic code: a list of words encoding the entropy from which the seed of a wallet is derived
+pub struct Mnemonic(Bip39Mnemonic);
+
+impl Mnemonic {
+    /// Generate a new random mnemonic of `MNEMONIC_WORDS` words
+    pub fn generate() -> Self {
+        Mnemonic(Bip39Mnemonic::new(MnemonicType::Words24, Language::English))
+    }
+
+    /// Parse a mnemonic from its words, separated by whitespace
+    pub fn from_phrase(phrase: &str) -> Result<Self, MnemonicError> {
+        let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        Bip39Mnemonic::from_phrase(phrase.as_str(), Language::English)
+            .map(Mnemonic)
+            .map_err(|e| MnemonicError::InvalidMnemonic(e.to_string()))
+    }
+
+    /// Words of the mnemonic, separated by single spaces
+    pub fn phrase(&self) -> &str {
+        self.0.phrase()
+    }
+
+    /// Derive the 64 bytes seed of the mnemonic, protected by an optional passphrase (which can
+    /// be empty)
+    pub fn seed(&self, passphrase: &str) -> Vec<u8> {
+        Seed::new(&self.0, passphrase).as_bytes().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bip39_test_vector() {
+        let phrase = "abandon abandon abandon abandon abandon abandon \
+                      abandon abandon abandon abandon abandon about";
+        let mnemonic = Mnemonic::from_phrase(phrase).unwrap();
+        let seed: String = mnemonic
+            .seed("TREZOR")
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        assert_eq!(
+            seed,
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f\
+             09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
+
+    #[test]
+    fn generate_and_parse() {
+        let mnemonic = Mnemonic::generate();
+        assert_eq!(mnemonic.phrase().split(' ').count(), MNEMONIC_WORDS);
+
+        // Extra whitespace between the words is ignored
+        let spaced = mnemonic.phrase().replace(" ", "  \n");
+        let parsed = Mnemonic::from_phrase(&spaced).unwrap();
+        assert_eq!(parsed.phrase(), mnemonic.phrase());
+        assert_eq!(parsed.seed(""), mnemonic.seed(""));
+        assert_ne!(parsed.seed(""), mnemonic.seed("passphrase"));
+    }
+
+    #[test]
+    fn invalid_checksum() {
+        let phrase = "abandon abandon abandon abandon abandon abandon \
+                      abandon abandon abandon abandon abandon abandon";
+
+        assert!(Mnemonic::from_phrase(phrase).is_err());
+        assert!(Mnemonic::from_phrase("").is_err());
+    }
+}
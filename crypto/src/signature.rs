@@ -0,0 +1,308 @@
+//! ECDSA signatures over secp256k1
+
+use failure::Fail;
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey, Signature, Signing, Verification};
+
+use witnet_data_structures::chain::{KeyedSignature, MultiSigCondition, Secp256k1Signature};
+
+use crate::hash::calculate_public_key_hash;
+
+/// Possible errors when verifying signatures
+#[derive(Debug, Fail, PartialEq)]
+pub enum SignatureError {
+    /// The public key is not a valid secp256k1 public key
+    #[fail(display = "Invalid public key")]
+    InvalidPublicKey,
+    /// The signature is not a valid secp256k1 signature
+    #[fail(display = "Invalid signature")]
+    InvalidSignature,
+    /// The signature does not match the message and the public key
+    #[fail(display = "Signature verification failed")]
+    VerificationFailed,
+}
+
+/// Possible errors when verifying the signatures of a multi-signature input
+#[derive(Debug, Fail, PartialEq)]
+pub enum MultiSigError {
+    /// The multi-signature condition is not valid
+    #[fail(display = "Invalid multi-signature condition")]
+    InvalidCondition,
+    /// There are less signatures than required
+    #[fail(
+        display = "Not enough signatures: {} required, {} found",
+        required, found
+    )]
+    NotEnoughSignatures {
+        /// Number of signatures required
+        required: usize,
+        /// Number of signatures found
+        found: usize,
+    },
+    /// The signer of a signature is not one of the owners
+    #[fail(display = "Signature {} is not from an owner", _0)]
+    UnknownSigner(usize),
+    /// The signer of a signature already signed
+    #[fail(display = "Signature {} is from a repeated signer", _0)]
+    RepeatedSigner(usize),
+    /// A signature is not valid
+    #[fail(display = "Signature {} is not valid: {}", index, error)]
+    InvalidSignature {
+        /// Index of the signature
+        index: usize,
+        /// Reason why the signature is not valid
+        error: SignatureError,
+    },
+}
+
+/// Signature to be verified, along with the signed message and the public key of the signer
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureJob {
+    /// Serialized public key of the signer
+    pub public_key: Vec<u8>,
+    /// Hash of the signed data
+    pub message: [u8; 32],
+    /// Signature
+    pub signature: Secp256k1Signature,
+}
+
+/// Sign the hash of some data
+pub fn sign<C: Signing>(
+    secp: &Secp256k1<C>,
+    secret_key: &SecretKey,
+    message: &[u8; 32],
+) -> Secp256k1Signature {
+    // A 32 bytes message is always valid
+    let message = Message::from_slice(message).unwrap();
+    let compact = secp.sign(&message, secret_key).serialize_compact();
+    let mut r = [0; 32];
+    let mut s = [0; 32];
+    r.copy_from_slice(&compact[..32]);
+    s.copy_from_slice(&compact[32..]);
+
+    Secp256k1Signature { r, s, v: 0 }
+}
+
+/// Verify a signature
+pub fn verify<C: Verification>(
+    secp: &Secp256k1<C>,
+    job: &SignatureJob,
+) -> Result<(), SignatureError> {
+    let public_key =
+        PublicKey::from_slice(&job.public_key).map_err(|_| SignatureError::InvalidPublicKey)?;
+    let mut compact = [0; 64];
+    compact[..32].copy_from_slice(&job.signature.r);
+    compact[32..].copy_from_slice(&job.signature.s);
+    let signature =
+        Signature::from_compact(&compact).map_err(|_| SignatureError::InvalidSignature)?;
+    let message = Message::from_slice(&job.message).unwrap();
+
+    secp.verify(&message, &signature, &public_key)
+        .map_err(|_| SignatureError::VerificationFailed)
+}
+
+/// Verify a batch of signatures, returning the index of the first invalid one along with the
+/// error
+pub fn verify_all<C: Verification>(
+    secp: &Secp256k1<C>,
+    jobs: &[SignatureJob],
+) -> Result<(), (usize, SignatureError)> {
+    jobs.iter()
+        .enumerate()
+        .try_for_each(|(index, job)| verify(secp, job).map_err(|e| (index, e)))
+}
+
+/// Check that the signatures of a multi-signature input fulfill its condition, that is, there are
+/// at least as many signatures as required and every one of them is from a different owner, and
+/// get the signatures to be verified
+pub fn multisig_jobs(
+    condition: &MultiSigCondition,
+    message: &[u8; 32],
+    signatures: &[KeyedSignature],
+) -> Result<Vec<SignatureJob>, MultiSigError> {
+    if !condition.is_valid() {
+        return Err(MultiSigError::InvalidCondition);
+    }
+
+    let required = usize::from(condition.required);
+    if signatures.len() < required {
+        return Err(MultiSigError::NotEnoughSignatures {
+            required,
+            found: signatures.len(),
+        });
+    }
+
+    let mut signers = Vec::with_capacity(signatures.len());
+    signatures
+        .iter()
+        .enumerate()
+        .map(|(index, keyed)| {
+            let pkh = calculate_public_key_hash(&keyed.public_key);
+            if !condition.pkhs.contains(&pkh) {
+                return Err(MultiSigError::UnknownSigner(index));
+            }
+            if signers.contains(&pkh) {
+                return Err(MultiSigError::RepeatedSigner(index));
+            }
+            signers.push(pkh);
+
+            Ok(SignatureJob {
+                public_key: keyed.public_key.clone(),
+                message: *message,
+                signature: keyed.signature.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Verify the signatures of a multi-signature input
+pub fn verify_multisig<C: Verification>(
+    secp: &Secp256k1<C>,
+    condition: &MultiSigCondition,
+    message: &[u8; 32],
+    signatures: &[KeyedSignature],
+) -> Result<(), MultiSigError> {
+    let jobs = multisig_jobs(condition, message, signatures)?;
+
+    verify_all(secp, &jobs)
+        .map_err(|(index, error)| MultiSigError::InvalidSignature { index, error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(secret: u8, message: [u8; 32]) -> SignatureJob {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[secret; 32]).unwrap();
+
+        SignatureJob {
+            public_key: PublicKey::from_secret_key(&secp, &secret_key)
+                .serialize()
+                .to_vec(),
+            message,
+            signature: sign(&secp, &secret_key, &message),
+        }
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let secp = Secp256k1::verification_only();
+        let job = job(1, [2; 32]);
+        assert_eq!(verify(&secp, &job), Ok(()));
+
+        let mut other_message = job.clone();
+        other_message.message = [3; 32];
+        assert_eq!(
+            verify(&secp, &other_message),
+            Err(SignatureError::VerificationFailed)
+        );
+
+        let mut invalid_key = job.clone();
+        invalid_key.public_key = vec![0; 33];
+        assert_eq!(
+            verify(&secp, &invalid_key),
+            Err(SignatureError::InvalidPublicKey)
+        );
+    }
+
+    #[test]
+    fn verify_batch() {
+        let secp = Secp256k1::verification_only();
+        let mut jobs: Vec<_> = (1..5).map(|i| job(i, [i; 32])).collect();
+        assert_eq!(verify_all(&secp, &jobs), Ok(()));
+
+        jobs[2].message = [0; 32];
+        assert_eq!(
+            verify_all(&secp, &jobs),
+            Err((2, SignatureError::VerificationFailed))
+        );
+    }
+
+    fn keyed(secret: u8, message: [u8; 32]) -> KeyedSignature {
+        let job = job(secret, message);
+
+        KeyedSignature {
+            public_key: job.public_key,
+            signature: job.signature,
+        }
+    }
+
+    #[test]
+    fn verify_2_of_3() {
+        let secp = Secp256k1::verification_only();
+        let message = [7; 32];
+        let signatures: Vec<_> = (1..4).map(|i| keyed(i, message)).collect();
+        let condition = MultiSigCondition {
+            required: 2,
+            pkhs: signatures
+                .iter()
+                .map(|keyed| calculate_public_key_hash(&keyed.public_key))
+                .collect(),
+        };
+
+        assert_eq!(
+            verify_multisig(&secp, &condition, &message, &signatures[..2]),
+            Ok(())
+        );
+        assert_eq!(
+            verify_multisig(&secp, &condition, &message, &signatures[1..]),
+            Ok(())
+        );
+        assert_eq!(
+            verify_multisig(&secp, &condition, &message, &signatures[..1]),
+            Err(MultiSigError::NotEnoughSignatures {
+                required: 2,
+                found: 1
+            })
+        );
+
+        // Every signature must be from a different owner
+        let repeated = vec![signatures[0].clone(), signatures[0].clone()];
+        assert_eq!(
+            verify_multisig(&secp, &condition, &message, &repeated),
+            Err(MultiSigError::RepeatedSigner(1))
+        );
+
+        // Signatures from other keys are not accepted
+        let unknown = vec![signatures[0].clone(), keyed(4, message)];
+        assert_eq!(
+            verify_multisig(&secp, &condition, &message, &unknown),
+            Err(MultiSigError::UnknownSigner(1))
+        );
+
+        // Signatures of other messages are not valid
+        let other_message = vec![signatures[0].clone(), keyed(2, [8; 32])];
+        assert_eq!(
+            verify_multisig(&secp, &condition, &message, &other_message),
+            Err(MultiSigError::InvalidSignature {
+                index: 1,
+                error: SignatureError::VerificationFailed
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_multisig_conditions() {
+        let secp = Secp256k1::verification_only();
+        let invalid = vec![
+            MultiSigCondition {
+                required: 0,
+                pkhs: vec![[1; 20]],
+            },
+            MultiSigCondition {
+                required: 2,
+                pkhs: vec![[1; 20]],
+            },
+            MultiSigCondition {
+                required: 1,
+                pkhs: vec![[1; 20], [1; 20]],
+            },
+        ];
+        for condition in invalid {
+            assert_eq!(
+                verify_multisig(&secp, &condition, &[0; 32], &[]),
+                Err(MultiSigError::InvalidCondition)
+            );
+        }
+    }
+}
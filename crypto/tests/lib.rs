@@ -1,6 +1,9 @@
 use witnet_data_structures::chain::Hash;
 
-use witnet_crypto::hash::calculate_sha256;
+use witnet_crypto::{
+    hash::calculate_sha256,
+    merkle::{merkle_inner_node, merkle_leaf, merkle_root},
+};
 
 #[test]
 fn sha256() {
@@ -12,3 +15,43 @@ fn sha256() {
     let witnet_hash = calculate_sha256(b"WITNET");
     assert_eq!(witnet_hash, Hash::SHA256(expected_witnet_hash));
 }
+
+#[test]
+fn merkle_root_of_hashes() {
+    let hash = |byte| Hash::SHA256([byte; 32]);
+    let leaf = |byte| calculate_sha256(&[&[0x00][..], &[byte; 32][..]].concat());
+    let parent = |Hash::SHA256(left): Hash, Hash::SHA256(right): Hash| {
+        calculate_sha256(&[&[0x01][..], &left[..], &right[..]].concat())
+    };
+
+    assert_eq!(merkle_root(&[]), Hash::SHA256([0; 32]));
+    assert_eq!(merkle_root(&[hash(1)]), leaf(1));
+    assert_eq!(merkle_root(&[hash(1), hash(2)]), parent(leaf(1), leaf(2)));
+
+    // The last node of an odd level is promoted as is
+    assert_eq!(
+        merkle_root(&[hash(1), hash(2), hash(3)]),
+        parent(parent(leaf(1), leaf(2)), leaf(3))
+    );
+    assert_ne!(
+        merkle_root(&[hash(1), hash(2), hash(3)]),
+        merkle_root(&[hash(1), hash(2), hash(3), hash(3)])
+    );
+}
+
+#[test]
+fn merkle_root_domain_separation() {
+    let hashes = [
+        Hash::SHA256([1; 32]),
+        Hash::SHA256([2; 32]),
+        Hash::SHA256([3; 32]),
+        Hash::SHA256([4; 32]),
+    ];
+    let left = merkle_inner_node(&merkle_leaf(&hashes[0]), &merkle_leaf(&hashes[1]));
+    let right = merkle_inner_node(&merkle_leaf(&hashes[2]), &merkle_leaf(&hashes[3]));
+
+    // The inner nodes of a tree cannot be passed off as the leaves of a shorter list (without the
+    // prefixes, both lists would have the same root)
+    assert_ne!(merkle_root(&hashes), merkle_root(&[left, right]));
+    assert_ne!(merkle_root(&hashes[..2]), merkle_root(&[left]));
+}
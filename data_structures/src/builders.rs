@@ -3,11 +3,14 @@ use std::u32::MAX as U32_MAX;
 
 use rand::{thread_rng, Rng};
 
-use crate::chain::{Block, BlockHeaderWithProof, CheckpointBeacon, InvVector, Transaction};
+use crate::chain::{
+    Block, BlockHeaderWithProof, CheckpointBeacon, Hash, InvVector, KeyedSignature,
+    MintTransaction, Transaction,
+};
 use crate::error::{BuildersError, BuildersErrorKind, BuildersResult};
 use crate::types::{
-    Address, Command, GetBlocks, GetData, GetPeers, Inv, IpAddress, Message, Peers, Ping, Pong,
-    Verack, Version,
+    Address, Command, GetBlocks, GetData, GetPeers, Inv, IpAddress, LastBeacon, Message,
+    PeerAddress, Peers, Ping, Pong, Verack, Version,
 };
 
 use witnet_util::error::WitnetError;
@@ -25,6 +28,12 @@ pub const PROTOCOL_VERSION: u32 = 0x0000_0001;
 /// Capabilities
 pub const CAPABILITIES: u64 = 0x0000_0000_0000_0001;
 
+/// Capability flag of the nodes supporting encrypted connections (Noise protocol)
+pub const CAPABILITY_NOISE: u64 = 0x0000_0000_0000_0002;
+
+/// Capability flag of the nodes supporting compressed messages (Snappy)
+pub const CAPABILITY_COMPRESSION: u64 = 0x0000_0000_0000_0004;
+
 /// User agent
 pub const USER_AGENT: &str = "full-node-desktop-edition";
 
@@ -45,11 +54,16 @@ const BUILD_GET_DATA_ERR_MSG: &str = "No inventory vectors to be added to GetDat
 ////////////////////////////////////////////////////////////////////////////////////////
 impl Message {
     /// Function to build Block message
-    pub fn build_block(header: BlockHeaderWithProof, txns: Vec<Transaction>) -> Message {
+    pub fn build_block(
+        header: BlockHeaderWithProof,
+        txns: Vec<Transaction>,
+        mint: MintTransaction,
+    ) -> Message {
         Message::build_message(Command::Block(Block {
             header,
             txn_count: txns.len() as u32,
             txns,
+            mint,
         }))
     }
     /// Function to build GetBlocks messages
@@ -59,17 +73,29 @@ impl Message {
         }))
     }
 
+    /// Function to build LastBeacon messages
+    pub fn build_last_beacon(highest_block_checkpoint: CheckpointBeacon) -> Message {
+        Message::build_message(Command::LastBeacon(LastBeacon {
+            highest_block_checkpoint,
+        }))
+    }
+
     /// Function to build GetPeers messages
     pub fn build_get_peers() -> Message {
         Message::build_message(Command::GetPeers(GetPeers))
     }
 
-    /// Function to build Peers messages
-    pub fn build_peers(peers: &[SocketAddr]) -> Message {
-        // Cast all peers to witnet's address struct
+    /// Function to build Peers messages from a list of addresses, their last seen timestamps and
+    /// the signatures of the nodes that originated them (if any)
+    pub fn build_peers(peers: &[(SocketAddr, i64, Option<KeyedSignature>)]) -> Message {
+        // Cast all peers to witnet's peer address struct
         let mut casted_peers = Vec::new();
-        peers.iter().for_each(|peer| {
-            casted_peers.push(to_address(*peer));
+        peers.iter().for_each(|(peer, last_seen, signature)| {
+            casted_peers.push(PeerAddress {
+                address: to_address(*peer),
+                last_seen: *last_seen,
+                signature: signature.clone(),
+            });
         });
 
         Message::build_message(Command::Peers(Peers {
@@ -89,22 +115,25 @@ impl Message {
         Message::build_message(Command::Pong(Pong { nonce }))
     }
 
-    /// Function to build Version messages
+    /// Function to build Version messages, advertising the given capabilities
     pub fn build_version(
         sender_addr: SocketAddr,
         receiver_addr: SocketAddr,
         last_epoch: u32,
+        capabilities: u64,
+        consensus_constants_hash: Option<Hash>,
     ) -> Message {
         Message::build_message(Command::Version(Version {
             version: PROTOCOL_VERSION,
             timestamp: get_timestamp(),
-            capabilities: CAPABILITIES,
+            capabilities,
             sender_address: to_address(sender_addr),
             receiver_address: to_address(receiver_addr),
             user_agent: USER_AGENT.to_string(),
             last_epoch,
             genesis: GENESIS,
             nonce: random_nonce(),
+            consensus_constants_hash,
         }))
     }
 
@@ -163,7 +192,7 @@ fn random_nonce() -> u64 {
 }
 
 /// Function to build address witnet type from socket addr
-fn to_address(socket_addr: SocketAddr) -> Address {
+pub fn to_address(socket_addr: SocketAddr) -> Address {
     match socket_addr {
         SocketAddr::V4(addr) => Address {
             ip: {
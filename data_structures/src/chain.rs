@@ -1,3 +1,5 @@
+use crate::emission;
+
 /// Data structure holding critical information about the chain state and protocol constants
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct ChainInfo {
@@ -49,6 +51,134 @@ pub struct ConsensusConstants {
     /// Punishment value for claims out of the consensus bounds
     // TODO Use fixed point arithmetic (see Issue #172)
     pub reputation_punishment: f64,
+
+    /// Blocks known to belong to the valid chain, sorted by checkpoint
+    // Defaults to an empty list to keep on reading the chain info persisted by previous versions
+    #[serde(default)]
+    pub trusted_checkpoints: Vec<TrustedCheckpoint>,
+
+    /// Reward of the first blocks, which is halved every `halving_period` epochs
+    // Defaults to the emission schedule of the chains initialized by previous versions
+    #[serde(default = "default_initial_block_reward")]
+    pub initial_block_reward: u64,
+
+    /// Number of epochs between two halvings of the block reward
+    #[serde(default = "default_halving_period")]
+    pub halving_period: u32,
+
+    /// Number of epochs after which the reward of a block can be spent
+    #[serde(default = "default_reward_maturity")]
+    pub reward_maturity: u32,
+
+    /// Outputs created by the genesis block, to pre-fund some addresses in development networks
+    /// They are not included in any block, so they are only valid at genesis
+    #[serde(default)]
+    pub genesis_allocation: Vec<ValueTransferOutput>,
+}
+
+impl ConsensusConstants {
+    /// Bytes hashed to identify the network of a node: every consensus constant in order of
+    /// declaration, big endian, with the lists prefixed by their length
+    /// Unlike the storage encoding, it does not depend on the version of the node
+    pub fn hashed_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&self.checkpoint_zero_timestamp.to_be_bytes());
+        bytes.extend_from_slice(&self.checkpoints_period.to_be_bytes());
+        put_hash(&mut bytes, &self.genesis_hash);
+        bytes.extend_from_slice(&self.reputation_demurrage.to_bits().to_be_bytes());
+        bytes.extend_from_slice(&self.reputation_punishment.to_bits().to_be_bytes());
+        put_len(&mut bytes, self.trusted_checkpoints.len());
+        for trusted in &self.trusted_checkpoints {
+            bytes.extend_from_slice(&trusted.checkpoint.to_be_bytes());
+            put_hash(&mut bytes, &trusted.hash);
+        }
+        bytes.extend_from_slice(&self.initial_block_reward.to_be_bytes());
+        bytes.extend_from_slice(&self.halving_period.to_be_bytes());
+        bytes.extend_from_slice(&self.reward_maturity.to_be_bytes());
+        put_len(&mut bytes, self.genesis_allocation.len());
+        for output in &self.genesis_allocation {
+            bytes.extend_from_slice(&output.pkh);
+            bytes.extend_from_slice(&output.value.to_be_bytes());
+            bytes.extend_from_slice(&output.time_lock.to_be_bytes());
+        }
+
+        bytes
+    }
+}
+
+/// Append a length (of a list or a byte string) to the hashed bytes of a value, as a big endian
+/// u32
+fn put_len(bytes: &mut Vec<u8>, len: usize) {
+    bytes.extend_from_slice(&(len as u32).to_be_bytes());
+}
+
+/// Append a byte string prefixed by its length to the hashed bytes of a value
+fn put_bytes(bytes: &mut Vec<u8>, value: &[u8]) {
+    put_len(bytes, value.len());
+    bytes.extend_from_slice(value);
+}
+
+/// Append a hash to the hashed bytes of a value
+fn put_hash(bytes: &mut Vec<u8>, hash: &Hash) {
+    let Hash::SHA256(hash) = hash;
+    bytes.extend_from_slice(hash);
+}
+
+fn default_initial_block_reward() -> u64 {
+    emission::DEFAULT_INITIAL_BLOCK_REWARD
+}
+
+fn default_halving_period() -> u32 {
+    emission::DEFAULT_HALVING_PERIOD
+}
+
+fn default_reward_maturity() -> u32 {
+    emission::DEFAULT_REWARD_MATURITY
+}
+
+/// Block known in advance to belong to the valid chain, used to speed up the initial
+/// synchronization of the node
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct TrustedCheckpoint {
+    /// Checkpoint of the block
+    pub checkpoint: Epoch,
+    /// Hash of the block
+    pub hash: Hash,
+}
+
+/// Summary of the consolidated blocks of a range of epochs (a superblock)
+/// Superblocks are chained by their hashes and commit to the hashes of the consolidated blocks
+/// and to the UTXO set, so they can be used to verify a chain without all of its blocks
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SuperBlock {
+    /// Index of the superblock: superblock `i` covers the epochs `[i * period, (i + 1) * period)`
+    pub index: u32,
+    /// Hash of the previous superblock (the zero hash for the first superblock)
+    pub hash_prev_superblock: Hash,
+    /// Number of consolidated blocks in the range of epochs
+    pub num_blocks: u32,
+    /// Merkle root of the hashes of the consolidated blocks in the range of epochs, sorted by
+    /// checkpoint
+    pub blocks_merkle_root: Hash,
+    /// Merkle root of the UTXO set after applying the last block of the range of epochs
+    pub utxo_merkle_root: Hash,
+}
+
+impl SuperBlock {
+    /// Bytes hashed to chain the superblocks: the index, the hash of the previous superblock, the
+    /// number of blocks, the merkle root of the blocks and the merkle root of the UTXO set, all of
+    /// them big endian
+    /// Unlike the storage encoding, it does not depend on the version of the node
+    pub fn hashed_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(104);
+        bytes.extend_from_slice(&self.index.to_be_bytes());
+        put_hash(&mut bytes, &self.hash_prev_superblock);
+        bytes.extend_from_slice(&self.num_blocks.to_be_bytes());
+        put_hash(&mut bytes, &self.blocks_merkle_root);
+        put_hash(&mut bytes, &self.utxo_merkle_root);
+
+        bytes
+    }
 }
 
 /// Checkpoint beacon structure
@@ -81,6 +211,46 @@ pub struct Block {
     pub txn_count: u32,
     /// A non-empty list of transactions
     pub txns: Vec<Transaction>,
+    /// The transaction paying the block reward plus the fees of the transactions to the miner
+    pub mint: MintTransaction,
+}
+
+impl Block {
+    /// Bytes hashed to identify a block: every field in order of declaration, big endian, with
+    /// the lists prefixed by their length, the optional signature prefixed by 0 (none) or 1, and
+    /// every transaction prefixed by the length of its hashed bytes
+    /// Unlike the storage encoding, it does not depend on the version of the node
+    pub fn hashed_bytes(&self) -> Vec<u8> {
+        let header = &self.header.block_header;
+        let proof = &self.header.proof;
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&header.version.to_be_bytes());
+        bytes.extend_from_slice(&header.beacon.checkpoint.to_be_bytes());
+        put_hash(&mut bytes, &header.beacon.hash_prev_block);
+        put_hash(&mut bytes, &header.hash_merkle_root);
+        put_hash(&mut bytes, &header.utxo_merkle_root);
+        match &proof.block_sig {
+            None => bytes.push(0),
+            Some(Signature::Secp256k1(signature)) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&signature.r);
+                bytes.extend_from_slice(&signature.s);
+                bytes.push(signature.v);
+            }
+        }
+        bytes.extend_from_slice(&proof.influence.to_be_bytes());
+        bytes.extend_from_slice(&self.txn_count.to_be_bytes());
+        put_len(&mut bytes, self.txns.len());
+        for transaction in &self.txns {
+            put_bytes(&mut bytes, &transaction.hashed_bytes());
+        }
+        bytes.extend_from_slice(&self.mint.epoch.to_be_bytes());
+        bytes.extend_from_slice(&self.mint.output.pkh);
+        bytes.extend_from_slice(&self.mint.output.value.to_be_bytes());
+        bytes.extend_from_slice(&self.mint.output.time_lock.to_be_bytes());
+
+        bytes
+    }
 }
 
 /// Block header structure
@@ -92,6 +262,8 @@ pub struct BlockHeader {
     pub beacon: CheckpointBeacon,
     /// A 256-bit hash based on all of the transactions committed to this block
     pub hash_merkle_root: Hash,
+    /// A 256-bit hash committing to the UTXO set resulting from applying this block
+    pub utxo_merkle_root: Hash,
 }
 
 /// Block header structure with proof of leadership
@@ -140,11 +312,115 @@ pub enum Hash {
 /// SHA-256 Hash
 pub type SHA256 = [u8; 32];
 
+/// Public key hash, which identifies the owner of a transaction output
+// FIXME(#99): use PublicKeyHash in the outputs of Transaction once it is defined
+pub type PublicKeyHash = [u8; 20];
+
+/// Output of a transaction: a value paid to the owner of a public key hash
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValueTransferOutput {
+    /// Public key hash of the owner
+    pub pkh: PublicKeyHash,
+    /// Value paid
+    pub value: u64,
+    /// Epoch from which the output can be spent, 0 if it is not locked
+    #[serde(default)]
+    pub time_lock: Epoch,
+}
+
+impl ValueTransferOutput {
+    /// Check whether the output can be spent in an epoch, that is, its time lock has expired
+    pub fn is_spendable(&self, epoch: Epoch) -> bool {
+        epoch >= self.time_lock
+    }
+}
+
+/// Maximum number of public key hashes of a multi-signature condition
+pub const MAX_MULTISIG_KEYS: usize = 16;
+
+/// M-of-N multi-signature condition: an output locked by it can only be spent with the
+/// signatures of `required` of the owners of the public key hashes
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct MultiSigCondition {
+    /// Number of signatures required (M)
+    pub required: u8,
+    /// Public key hashes of the owners (N)
+    pub pkhs: Vec<PublicKeyHash>,
+}
+
+impl MultiSigCondition {
+    /// Check that at least one signature is required, that the number of required signatures does
+    /// not exceed the number of owners, that there are at most `MAX_MULTISIG_KEYS` owners and that
+    /// none of them is repeated
+    pub fn is_valid(&self) -> bool {
+        let required = usize::from(self.required);
+
+        required >= 1
+            && required <= self.pkhs.len()
+            && self.pkhs.len() <= MAX_MULTISIG_KEYS
+            && self
+                .pkhs
+                .iter()
+                .enumerate()
+                .all(|(i, pkh)| !self.pkhs[..i].contains(pkh))
+    }
+}
+
+/// Output of a transaction locked by a multi-signature condition
+// FIXME(#99): include multi-signature outputs in Transaction once it is defined
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct MultiSigOutput {
+    /// Condition to spend the output
+    pub condition: MultiSigCondition,
+    /// Value paid
+    pub value: u64,
+    /// Epoch from which the output can be spent, 0 if it is not locked
+    #[serde(default)]
+    pub time_lock: Epoch,
+}
+
+/// Signature along with the public key of the signer
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct KeyedSignature {
+    /// Serialized public key of the signer
+    pub public_key: Vec<u8>,
+    /// Signature
+    pub signature: Secp256k1Signature,
+}
+
+/// Input of a transaction spending a multi-signature output: the signatures of the hash of the
+/// transaction by the owners of the output
+// FIXME(#99): include multi-signature inputs in Transaction once it is defined
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct MultiSigInput {
+    /// Signatures of the owners
+    pub signatures: Vec<KeyedSignature>,
+}
+
+/// Mint transaction: every block includes exactly one, paying the block reward set by the
+/// emission schedule plus the fees of the transactions of the block to its miner
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct MintTransaction {
+    /// Epoch of the block
+    pub epoch: Epoch,
+    /// Output paying the miner
+    pub output: ValueTransferOutput,
+}
+
 // FIXME(#99): define Transaction as defined in issue
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Transaction;
 
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+impl Transaction {
+    /// Bytes hashed to identify a transaction
+    /// Unlike the storage encoding, it does not depend on the version of the node
+    // FIXME(#99): encode the fields of the transaction once Transaction is defined
+    pub fn hashed_bytes(&self) -> Vec<u8> {
+        vec![]
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, Hash)]
 pub enum InvVector {
     Error(Hash),
     Tx(Hash),
@@ -152,3 +428,141 @@ pub enum InvVector {
     DataRequest(Hash),
     DataResult(Hash),
 }
+
+/// Kind of the source of a retrieval or of the destination of a delivery
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum RADType {
+    /// HTTP GET request
+    #[serde(rename = "HTTP-GET")]
+    HttpGet,
+    /// HTTP GET request over TLS
+    #[serde(rename = "HTTPS-GET")]
+    HttpsGet,
+    /// Deterministic pseudo-random number, seeded by the URL (for testing purposes)
+    #[serde(rename = "RNG")]
+    Rng,
+}
+
+/// Retrieval phase of a data request: a source and the RADON script to apply on the retrieved
+/// data
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RADRetrieve {
+    /// Kind of the source
+    pub kind: RADType,
+    /// URL of the source
+    pub url: String,
+    /// Headers of the request (name and value), if the source is requested through HTTP(S)
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    /// RADON script, encoded using MessagePack
+    pub script: Vec<u8>,
+}
+
+/// Aggregation phase of a data request: the RADON script to apply on the results of the
+/// retrievals of a witness
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RADAggregate {
+    /// RADON script, encoded using MessagePack
+    pub script: Vec<u8>,
+}
+
+/// Consensus phase of a data request: the RADON script to apply on the results revealed by the
+/// witnesses
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RADConsensus {
+    /// RADON script, encoded using MessagePack
+    pub script: Vec<u8>,
+}
+
+/// Delivery phase of a data request: a destination of the result
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RADDeliver {
+    /// Kind of the destination
+    pub kind: RADType,
+    /// URL of the destination
+    pub url: String,
+}
+
+/// RAD request: the instructions for every phase of a data request
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RADRequest {
+    /// Timestamp before which the data must not be retrieved
+    pub not_before: u64,
+    /// Sources of the data
+    pub retrieve: Vec<RADRetrieve>,
+    /// Aggregation of the retrieved data
+    pub aggregate: RADAggregate,
+    /// Consensus on the results of the witnesses
+    pub consensus: RADConsensus,
+    /// Destinations of the result
+    pub deliver: Vec<RADDeliver>,
+}
+
+/// Data request posted by a client, along with the rewards of the nodes resolving it
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct DataRequestOutput {
+    /// RAD request
+    pub data_request: RADRequest,
+    /// Value paid to the witnesses
+    pub value: u64,
+    /// Number of witnesses
+    pub witnesses: u16,
+    /// Fee paid to the miners for every commit transaction
+    pub commit_fee: u64,
+    /// Fee paid to the miners for every reveal transaction
+    pub reveal_fee: u64,
+    /// Fee paid to the miner of the tally transaction
+    pub tally_fee: u64,
+    /// Collateral locked by every witness when committing, which is forfeited if the witness
+    /// does not reveal its result or its result is out of the consensus
+    #[serde(default)]
+    pub collateral: u64,
+}
+
+impl RADType {
+    /// Tag identifying the kind of a source or a destination in the hashed bytes of a data request
+    fn tag(&self) -> u8 {
+        match self {
+            RADType::HttpGet => 0,
+            RADType::HttpsGet => 1,
+            RADType::Rng => 2,
+        }
+    }
+}
+
+impl DataRequestOutput {
+    /// Bytes hashed to identify a data request: every field in order of declaration, big endian,
+    /// with the lists and the byte strings prefixed by their length
+    /// Unlike the storage encoding, it does not depend on the version of the node
+    pub fn hashed_bytes(&self) -> Vec<u8> {
+        let request = &self.data_request;
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&request.not_before.to_be_bytes());
+        put_len(&mut bytes, request.retrieve.len());
+        for retrieve in &request.retrieve {
+            bytes.push(retrieve.kind.tag());
+            put_bytes(&mut bytes, retrieve.url.as_bytes());
+            put_len(&mut bytes, retrieve.headers.len());
+            for (name, value) in &retrieve.headers {
+                put_bytes(&mut bytes, name.as_bytes());
+                put_bytes(&mut bytes, value.as_bytes());
+            }
+            put_bytes(&mut bytes, &retrieve.script);
+        }
+        put_bytes(&mut bytes, &request.aggregate.script);
+        put_bytes(&mut bytes, &request.consensus.script);
+        put_len(&mut bytes, request.deliver.len());
+        for deliver in &request.deliver {
+            bytes.push(deliver.kind.tag());
+            put_bytes(&mut bytes, deliver.url.as_bytes());
+        }
+        bytes.extend_from_slice(&self.value.to_be_bytes());
+        bytes.extend_from_slice(&self.witnesses.to_be_bytes());
+        bytes.extend_from_slice(&self.commit_fee.to_be_bytes());
+        bytes.extend_from_slice(&self.reveal_fee.to_be_bytes());
+        bytes.extend_from_slice(&self.tally_fee.to_be_bytes());
+        bytes.extend_from_slice(&self.collateral.to_be_bytes());
+
+        bytes
+    }
+}
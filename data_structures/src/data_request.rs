@@ -0,0 +1,140 @@
+//! Economics of the data requests
+//!
+//! The value of a data request pays for the whole resolution of the request: the fees of the
+//! commit and reveal transactions of every witness, the fee of the tally transaction and the
+//! rewards of the witnesses. The rewards must make it worth for a witness to lock its
+//! collateral, so the collateral can be at most `MAX_COLLATERAL_RATIO` times the reward of a
+//! witness. Both the clients posting data requests and the nodes validating their tallies split
+//! the value with the same function, so they always agree on the rewards.
+use crate::chain::DataRequestOutput;
+use crate::error::{DataRequestError, DataRequestErrorKind, DataRequestResult};
+use witnet_util::error::WitnetError;
+
+/// Maximum ratio between the collateral locked by a witness and its reward
+pub const MAX_COLLATERAL_RATIO: u64 = 125;
+
+/// Split of the value of a data request
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct ValueSplit {
+    /// Fees of the commit transactions of all the witnesses
+    pub commit_fees: u64,
+    /// Fees of the reveal transactions of all the witnesses
+    pub reveal_fees: u64,
+    /// Fee of the tally transaction
+    pub tally_fee: u64,
+    /// Value left for the rewards of all the witnesses
+    pub rewards: u64,
+    /// Reward of every witness (the rewards divided by the number of witnesses)
+    pub witness_reward: u64,
+}
+
+impl ValueSplit {
+    /// Get the sum of all the fees
+    pub fn fees(&self) -> u64 {
+        self.commit_fees + self.reveal_fees + self.tally_fee
+    }
+}
+
+/// Split the value of a data request into the fees and the rewards of the witnesses, checking
+/// that the value covers all the fees, that every witness gets a reward and that the
+/// collateral is not too high for that reward
+pub fn value_split(data_request: &DataRequestOutput) -> DataRequestResult<ValueSplit> {
+    let witnesses = u64::from(data_request.witnesses);
+    if witnesses == 0 {
+        return Err(error(
+            DataRequestErrorKind::NoWitnesses,
+            "the data request does not ask for any witness".to_string(),
+        ));
+    }
+
+    let overflow = || {
+        error(
+            DataRequestErrorKind::ValueOverflow,
+            "the fees of the data request overflow".to_string(),
+        )
+    };
+    let commit_fees = data_request
+        .commit_fee
+        .checked_mul(witnesses)
+        .ok_or_else(overflow)?;
+    let reveal_fees = data_request
+        .reveal_fee
+        .checked_mul(witnesses)
+        .ok_or_else(overflow)?;
+    let fees = commit_fees
+        .checked_add(reveal_fees)
+        .and_then(|fees| fees.checked_add(data_request.tally_fee))
+        .ok_or_else(overflow)?;
+    data_request
+        .collateral
+        .checked_mul(witnesses)
+        .ok_or_else(|| {
+            error(
+                DataRequestErrorKind::ValueOverflow,
+                "the collateral of the witnesses overflows".to_string(),
+            )
+        })?;
+
+    // Every witness must get a reward of at least 1
+    let required = fees.checked_add(witnesses).ok_or_else(overflow)?;
+    if data_request.value < required {
+        return Err(error(
+            DataRequestErrorKind::InsufficientValue,
+            format!(
+                "the value {} does not cover the fees ({}) and a reward for each of the {} \
+                 witnesses (at least {})",
+                data_request.value, fees, witnesses, required
+            ),
+        ));
+    }
+
+    let rewards = data_request.value - fees;
+    let witness_reward = rewards / witnesses;
+    let max_collateral = witness_reward.saturating_mul(MAX_COLLATERAL_RATIO);
+    if data_request.collateral > max_collateral {
+        return Err(error(
+            DataRequestErrorKind::CollateralTooHigh,
+            format!(
+                "the collateral {} is more than {} times the reward of a witness ({}, at most {})",
+                data_request.collateral, MAX_COLLATERAL_RATIO, witness_reward, max_collateral
+            ),
+        ));
+    }
+
+    Ok(ValueSplit {
+        commit_fees,
+        reveal_fees,
+        tally_fee: data_request.tally_fee,
+        rewards,
+        witness_reward,
+    })
+}
+
+/// Get the minimum value of a data request asking for a number of witnesses with the given fees
+/// and collateral, that is, the value covering all the fees and the lowest reward for which the
+/// witnesses may lock that collateral
+/// Returns None if the value overflows
+pub fn min_value(
+    witnesses: u16,
+    commit_fee: u64,
+    reveal_fee: u64,
+    tally_fee: u64,
+    collateral: u64,
+) -> Option<u64> {
+    let witnesses = u64::from(witnesses);
+    let witness_reward = std::cmp::max(
+        1,
+        collateral / MAX_COLLATERAL_RATIO + u64::from(collateral % MAX_COLLATERAL_RATIO != 0),
+    );
+
+    commit_fee
+        .checked_add(reveal_fee)?
+        .checked_add(witness_reward)?
+        .checked_mul(witnesses)?
+        .checked_add(tally_fee)
+}
+
+/// Build an error of the economics of a data request
+fn error(kind: DataRequestErrorKind, msg: String) -> WitnetError<DataRequestError> {
+    DataRequestError::new(kind, msg).into()
+}
@@ -0,0 +1,93 @@
+//! Emission schedule of the coins
+//!
+//! Every block (but the genesis one) issues a reward for its miner. The reward starts at
+//! `initial_block_reward` and is halved every `halving_period` epochs, until it reaches zero.
+//! The amounts are expressed in the smallest unit of the coin.
+use crate::chain::{ConsensusConstants, Epoch};
+
+/// Default reward of the first blocks: 500 coins (in the smallest unit, 10^9 per coin)
+pub const DEFAULT_INITIAL_BLOCK_REWARD: u64 = 500_000_000_000;
+
+/// Default number of epochs between two halvings of the block reward (about 5 years with 90
+/// seconds epochs)
+pub const DEFAULT_HALVING_PERIOD: u32 = 1_750_000;
+
+/// Default number of epochs after which the reward of a block can be spent
+pub const DEFAULT_REWARD_MATURITY: u32 = 100;
+
+/// Range of epochs with the same block reward
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct EmissionPeriod {
+    /// First epoch of the range
+    pub start: Epoch,
+    /// Last epoch of the range
+    pub end: Epoch,
+    /// Reward of every block in the range
+    pub reward: u64,
+}
+
+/// Get the number of halvings of the block reward up to an epoch
+fn halvings(epoch: Epoch, constants: &ConsensusConstants) -> u32 {
+    epoch / std::cmp::max(constants.halving_period, 1)
+}
+
+/// Get the reward issued by the block of an epoch
+pub fn block_reward(epoch: Epoch, constants: &ConsensusConstants) -> u64 {
+    if epoch == 0 {
+        return 0;
+    }
+
+    constants
+        .initial_block_reward
+        .checked_shr(halvings(epoch, constants))
+        .unwrap_or(0)
+}
+
+/// Get the epoch from which the reward of the block of an epoch can be spent, that is, the time
+/// lock of the output of its mint transaction
+pub fn reward_time_lock(epoch: Epoch, constants: &ConsensusConstants) -> Epoch {
+    epoch.saturating_add(constants.reward_maturity)
+}
+
+/// Get the total reward issued by the blocks of the epochs in `[1, epoch]`, assuming there is a
+/// block for every epoch
+pub fn emission_until(epoch: Epoch, constants: &ConsensusConstants) -> u64 {
+    emission_curve(1, constants)
+        .into_iter()
+        .take_while(|period| period.start <= epoch)
+        .map(|period| {
+            let blocks = u64::from(std::cmp::min(period.end, epoch) - period.start + 1);
+            period.reward.saturating_mul(blocks)
+        })
+        .fold(0, u64::saturating_add)
+}
+
+/// Get the total reward that will ever be issued, assuming there is a block for every epoch
+pub fn total_emission(constants: &ConsensusConstants) -> u64 {
+    emission_until(Epoch::max_value(), constants)
+}
+
+/// Get the ranges of epochs with the same block reward from an epoch on, up to the last epoch
+/// with a reward
+pub fn emission_curve(from: Epoch, constants: &ConsensusConstants) -> Vec<EmissionPeriod> {
+    let halving_period = std::cmp::max(constants.halving_period, 1);
+    let mut curve = vec![];
+    let mut start = std::cmp::max(from, 1);
+    loop {
+        let reward = block_reward(start, constants);
+        if reward == 0 {
+            break;
+        }
+        let end = (halvings(start, constants) + 1)
+            .checked_mul(halving_period)
+            .map_or(Epoch::max_value(), |next| next - 1);
+        curve.push(EmissionPeriod { start, end, reward });
+
+        match end.checked_add(1) {
+            Some(next) => start = next,
+            None => break,
+        }
+    }
+
+    curve
+}
@@ -69,3 +69,95 @@ impl fmt::Display for BuildersErrorKind {
 
 /// Result type used as return value for the builder functions in the builders module
 pub type BuildersResult<T> = WitnetResult<T, BuildersError>;
+
+/// Error when decoding a protocol message received from the network
+#[derive(Debug, Fail)]
+#[fail(display = "{} :  msg {}", kind, msg)]
+pub struct DecodeError {
+    /// Error kind
+    kind: DecodeErrorKind,
+    /// Error message
+    msg: String,
+}
+
+impl DecodeError {
+    /// Create a DecodeError based on kind and related info
+    pub fn new(kind: DecodeErrorKind, msg: String) -> Self {
+        Self { kind, msg }
+    }
+
+    /// Kind of the error
+    pub fn kind(&self) -> &DecodeErrorKind {
+        &self.kind
+    }
+}
+
+/// Kind of errors while decoding a protocol message
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecodeErrorKind {
+    /// The buffer is not a well-formed flatbuffer: offsets out of bounds, misaligned data, etc.
+    Malformed,
+    /// A required field is missing
+    MissingField,
+    /// A field contains an unknown enum or union value
+    InvalidEnumValue,
+    /// A hash or a signature does not have the expected length
+    InvalidLength,
+    /// The message does not contain any command
+    UnknownCommand,
+    /// The version of the encoding is not supported
+    UnsupportedVersion,
+}
+
+impl fmt::Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DecodeError::{:?}", self)
+    }
+}
+
+/// Result type used as return value when decoding protocol messages
+pub type DecodeResult<T> = WitnetResult<T, DecodeError>;
+
+/// Error in the economics of a data request
+#[derive(Debug, Fail)]
+#[fail(display = "{} :  msg {}", kind, msg)]
+pub struct DataRequestError {
+    /// Error kind
+    kind: DataRequestErrorKind,
+    /// Error message
+    msg: String,
+}
+
+impl DataRequestError {
+    /// Create a DataRequestError based on kind and related info
+    pub fn new(kind: DataRequestErrorKind, msg: String) -> Self {
+        Self { kind, msg }
+    }
+
+    /// Kind of the error
+    pub fn kind(&self) -> &DataRequestErrorKind {
+        &self.kind
+    }
+}
+
+/// Kind of errors in the economics of a data request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DataRequestErrorKind {
+    /// The data request does not ask for any witness
+    NoWitnesses,
+    /// The fees or the collateral of the witnesses overflow
+    ValueOverflow,
+    /// The value does not cover the fees and a reward for every witness
+    InsufficientValue,
+    /// The collateral is too high for the reward of a witness
+    CollateralTooHigh,
+}
+
+impl fmt::Display for DataRequestErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DataRequestError::{:?}", self)
+    }
+}
+
+/// Result type used as return value when checking the economics of a data request
+pub type DataRequestResult<T> = WitnetResult<T, DataRequestError>;
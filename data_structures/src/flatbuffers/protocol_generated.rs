@@ -28,11 +28,12 @@ pub enum Command {
   Inv = 8,
   GetData = 9,
   GetBlocks = 10,
+  LastBeacon = 11,
 
 }
 
 const ENUM_MIN_COMMAND: u8 = 0;
-const ENUM_MAX_COMMAND: u8 = 10;
+const ENUM_MAX_COMMAND: u8 = 11;
 
 impl<'a> flatbuffers::Follow<'a> for Command {
   type Inner = Self;
@@ -66,7 +67,7 @@ impl flatbuffers::Push for Command {
 }
 
 #[allow(non_camel_case_types)]
-const ENUM_VALUES_COMMAND:[Command; 11] = [
+const ENUM_VALUES_COMMAND:[Command; 12] = [
   Command::NONE,
   Command::Version,
   Command::Verack,
@@ -77,11 +78,12 @@ const ENUM_VALUES_COMMAND:[Command; 11] = [
   Command::Block,
   Command::Inv,
   Command::GetData,
-  Command::GetBlocks
+  Command::GetBlocks,
+  Command::LastBeacon
 ];
 
 #[allow(non_camel_case_types)]
-const ENUM_NAMES_COMMAND:[&'static str; 11] = [
+const ENUM_NAMES_COMMAND:[&'static str; 12] = [
     "NONE",
     "Version",
     "Verack",
@@ -92,7 +94,8 @@ const ENUM_NAMES_COMMAND:[&'static str; 11] = [
     "Block",
     "Inv",
     "GetData",
-    "GetBlocks"
+    "GetBlocks",
+    "LastBeacon"
 ];
 
 pub fn enum_name_command(e: Command) -> &'static str {
@@ -505,6 +508,16 @@ impl<'a> Message<'a> {
     }
   }
 
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_last_beacon(&'a self) -> Option<LastBeacon> {
+    if self.command_type() == Command::LastBeacon {
+      Some(LastBeacon::init_from_table(self.command()))
+    } else {
+      None
+    }
+  }
+
 }
 
 pub struct MessageArgs {
@@ -770,10 +783,14 @@ impl<'a> Address<'a> {
     #[allow(unused_mut)]
     pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
         _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
-        args: &'args AddressArgs) -> flatbuffers::WIPOffset<Address<'bldr>> {
+        args: &'args AddressArgs<'args>) -> flatbuffers::WIPOffset<Address<'bldr>> {
       let mut builder = AddressBuilder::new(_fbb);
+      builder.add_last_seen(args.last_seen);
+      if let Some(x) = args.public_key { builder.add_public_key(x); }
+      if let Some(x) = args.signature { builder.add_signature(x); }
       if let Some(x) = args.ip { builder.add_ip(x); }
       builder.add_port(args.port);
+      builder.add_signature_type(args.signature_type);
       builder.add_ip_type(args.ip_type);
       builder.finish()
     }
@@ -781,6 +798,10 @@ impl<'a> Address<'a> {
     pub const VT_IP_TYPE: flatbuffers::VOffsetT = 4;
     pub const VT_IP: flatbuffers::VOffsetT = 6;
     pub const VT_PORT: flatbuffers::VOffsetT = 8;
+    pub const VT_LAST_SEEN: flatbuffers::VOffsetT = 10;
+    pub const VT_SIGNATURE_TYPE: flatbuffers::VOffsetT = 12;
+    pub const VT_SIGNATURE: flatbuffers::VOffsetT = 14;
+    pub const VT_PUBLIC_KEY: flatbuffers::VOffsetT = 16;
 
   #[inline]
   pub fn ip_type(&self) -> IpAddress {
@@ -795,6 +816,22 @@ impl<'a> Address<'a> {
     self._tab.get::<u16>(Address::VT_PORT, Some(0)).unwrap()
   }
   #[inline]
+  pub fn last_seen(&self) -> i64 {
+    self._tab.get::<i64>(Address::VT_LAST_SEEN, Some(0)).unwrap()
+  }
+  #[inline]
+  pub fn signature_type(&self) -> Signature {
+    self._tab.get::<Signature>(Address::VT_SIGNATURE_TYPE, Some(Signature::NONE)).unwrap()
+  }
+  #[inline]
+  pub fn signature(&self) -> Option<flatbuffers::Table<'a>> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Table<'a>>>(Address::VT_SIGNATURE, None)
+  }
+  #[inline]
+  pub fn public_key(&self) -> Option<&'a [u8]> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, u8>>>(Address::VT_PUBLIC_KEY, None).map(|v| v.safe_slice())
+  }
+  #[inline]
   #[allow(non_snake_case)]
   pub fn ip_as_ipv_4(&'a self) -> Option<Ipv4> {
     if self.ip_type() == IpAddress::Ipv4 {
@@ -814,20 +851,38 @@ impl<'a> Address<'a> {
     }
   }
 
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn signature_as_secp_256k_1signature(&'a self) -> Option<Secp256k1Signature> {
+    if self.signature_type() == Signature::Secp256k1Signature {
+      self.signature().map(|u| Secp256k1Signature::init_from_table(u))
+    } else {
+      None
+    }
+  }
+
 }
 
-pub struct AddressArgs {
+pub struct AddressArgs<'a> {
     pub ip_type: IpAddress,
     pub ip: Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>>,
     pub port: u16,
+    pub last_seen: i64,
+    pub signature_type: Signature,
+    pub signature: Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>>,
+    pub public_key: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a ,  u8>>>,
 }
-impl<'a> Default for AddressArgs {
+impl<'a> Default for AddressArgs<'a> {
     #[inline]
     fn default() -> Self {
         AddressArgs {
             ip_type: IpAddress::NONE,
             ip: None,
             port: 0,
+            last_seen: 0,
+            signature_type: Signature::NONE,
+            signature: None,
+            public_key: None,
         }
     }
 }
@@ -849,6 +904,22 @@ impl<'a: 'b, 'b> AddressBuilder<'a, 'b> {
     self.fbb_.push_slot::<u16>(Address::VT_PORT, port, 0);
   }
   #[inline]
+  pub fn add_last_seen(&mut self, last_seen: i64) {
+    self.fbb_.push_slot::<i64>(Address::VT_LAST_SEEN, last_seen, 0);
+  }
+  #[inline]
+  pub fn add_signature_type(&mut self, signature_type: Signature) {
+    self.fbb_.push_slot::<Signature>(Address::VT_SIGNATURE_TYPE, signature_type, Signature::NONE);
+  }
+  #[inline]
+  pub fn add_signature(&mut self, signature: flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Address::VT_SIGNATURE, signature);
+  }
+  #[inline]
+  pub fn add_public_key(&mut self, public_key: flatbuffers::WIPOffset<flatbuffers::Vector<'b , u8>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Address::VT_PUBLIC_KEY, public_key);
+  }
+  #[inline]
   pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> AddressBuilder<'a, 'b> {
     let start = _fbb.start_table();
     AddressBuilder {
@@ -1407,6 +1478,83 @@ impl<'a: 'b, 'b> GetBlocksBuilder<'a, 'b> {
   }
 }
 
+pub enum LastBeaconOffset {}
+#[derive(Copy, Clone, Debug, PartialEq)]
+
+pub struct LastBeacon<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for LastBeacon<'a> {
+    type Inner = LastBeacon<'a>;
+    #[inline]
+    fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+        Self {
+            _tab: flatbuffers::Table { buf: buf, loc: loc },
+        }
+    }
+}
+
+impl<'a> LastBeacon<'a> {
+    #[inline]
+    pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+        LastBeacon {
+            _tab: table,
+        }
+    }
+    #[allow(unused_mut)]
+    pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+        _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+        args: &'args LastBeaconArgs<'args>) -> flatbuffers::WIPOffset<LastBeacon<'bldr>> {
+      let mut builder = LastBeaconBuilder::new(_fbb);
+      if let Some(x) = args.highest_block_checkpoint { builder.add_highest_block_checkpoint(x); }
+      builder.finish()
+    }
+
+    pub const VT_HIGHEST_BLOCK_CHECKPOINT: flatbuffers::VOffsetT = 4;
+
+  #[inline]
+  pub fn highest_block_checkpoint(&self) -> CheckpointBeacon<'a> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<CheckpointBeacon<'a>>>(LastBeacon::VT_HIGHEST_BLOCK_CHECKPOINT, None).unwrap()
+  }
+}
+
+pub struct LastBeaconArgs<'a> {
+    pub highest_block_checkpoint: Option<flatbuffers::WIPOffset<CheckpointBeacon<'a >>>,
+}
+impl<'a> Default for LastBeaconArgs<'a> {
+    #[inline]
+    fn default() -> Self {
+        LastBeaconArgs {
+            highest_block_checkpoint: None, // required field
+        }
+    }
+}
+pub struct LastBeaconBuilder<'a: 'b, 'b> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b> LastBeaconBuilder<'a, 'b> {
+  #[inline]
+  pub fn add_highest_block_checkpoint(&mut self, highest_block_checkpoint: flatbuffers::WIPOffset<CheckpointBeacon<'b >>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<CheckpointBeacon>>(LastBeacon::VT_HIGHEST_BLOCK_CHECKPOINT, highest_block_checkpoint);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> LastBeaconBuilder<'a, 'b> {
+    let start = _fbb.start_table();
+    LastBeaconBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<LastBeacon<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    self.fbb_.required(o, LastBeacon::VT_HIGHEST_BLOCK_CHECKPOINT,"highest_block_checkpoint");
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
 pub enum BlockHeaderOffset {}
 #[derive(Copy, Clone, Debug, PartialEq)]
 
@@ -1436,6 +1584,7 @@ impl<'a> BlockHeader<'a> {
         _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
         args: &'args BlockHeaderArgs<'args>) -> flatbuffers::WIPOffset<BlockHeader<'bldr>> {
       let mut builder = BlockHeaderBuilder::new(_fbb);
+      if let Some(x) = args.utxo_merkle_root { builder.add_utxo_merkle_root(x); }
       if let Some(x) = args.proof { builder.add_proof(x); }
       if let Some(x) = args.hash_merkle_root { builder.add_hash_merkle_root(x); }
       if let Some(x) = args.beacon { builder.add_beacon(x); }
@@ -1447,6 +1596,7 @@ impl<'a> BlockHeader<'a> {
     pub const VT_BEACON: flatbuffers::VOffsetT = 6;
     pub const VT_HASH_MERKLE_ROOT: flatbuffers::VOffsetT = 8;
     pub const VT_PROOF: flatbuffers::VOffsetT = 10;
+    pub const VT_UTXO_MERKLE_ROOT: flatbuffers::VOffsetT = 12;
 
   #[inline]
   pub fn version(&self) -> u32 {
@@ -1464,6 +1614,10 @@ impl<'a> BlockHeader<'a> {
   pub fn proof(&self) -> LeadershipProof<'a> {
     self._tab.get::<flatbuffers::ForwardsUOffset<LeadershipProof<'a>>>(BlockHeader::VT_PROOF, None).unwrap()
   }
+  #[inline]
+  pub fn utxo_merkle_root(&self) -> Hash<'a> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<Hash<'a>>>(BlockHeader::VT_UTXO_MERKLE_ROOT, None).unwrap()
+  }
 }
 
 pub struct BlockHeaderArgs<'a> {
@@ -1471,6 +1625,7 @@ pub struct BlockHeaderArgs<'a> {
     pub beacon: Option<flatbuffers::WIPOffset<CheckpointBeacon<'a >>>,
     pub hash_merkle_root: Option<flatbuffers::WIPOffset<Hash<'a >>>,
     pub proof: Option<flatbuffers::WIPOffset<LeadershipProof<'a >>>,
+    pub utxo_merkle_root: Option<flatbuffers::WIPOffset<Hash<'a >>>,
 }
 impl<'a> Default for BlockHeaderArgs<'a> {
     #[inline]
@@ -1480,6 +1635,7 @@ impl<'a> Default for BlockHeaderArgs<'a> {
             beacon: None, // required field
             hash_merkle_root: None, // required field
             proof: None, // required field
+            utxo_merkle_root: None, // required field
         }
     }
 }
@@ -1505,6 +1661,10 @@ impl<'a: 'b, 'b> BlockHeaderBuilder<'a, 'b> {
     self.fbb_.push_slot_always::<flatbuffers::WIPOffset<LeadershipProof>>(BlockHeader::VT_PROOF, proof);
   }
   #[inline]
+  pub fn add_utxo_merkle_root(&mut self, utxo_merkle_root: flatbuffers::WIPOffset<Hash<'b >>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<Hash>>(BlockHeader::VT_UTXO_MERKLE_ROOT, utxo_merkle_root);
+  }
+  #[inline]
   pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> BlockHeaderBuilder<'a, 'b> {
     let start = _fbb.start_table();
     BlockHeaderBuilder {
@@ -1518,6 +1678,7 @@ impl<'a: 'b, 'b> BlockHeaderBuilder<'a, 'b> {
     self.fbb_.required(o, BlockHeader::VT_BEACON,"beacon");
     self.fbb_.required(o, BlockHeader::VT_HASH_MERKLE_ROOT,"hash_merkle_root");
     self.fbb_.required(o, BlockHeader::VT_PROOF,"proof");
+    self.fbb_.required(o, BlockHeader::VT_UTXO_MERKLE_ROOT,"utxo_merkle_root");
     flatbuffers::WIPOffset::new(o.value())
   }
 }
@@ -1557,6 +1718,7 @@ impl<'a> Version<'a> {
       builder.add_genesis(args.genesis);
       builder.add_capabilities(args.capabilities);
       builder.add_timestamp(args.timestamp);
+      if let Some(x) = args.consensus_constants_hash { builder.add_consensus_constants_hash(x); }
       builder.add_last_epoch(args.last_epoch);
       if let Some(x) = args.user_agent { builder.add_user_agent(x); }
       if let Some(x) = args.receiver_address { builder.add_receiver_address(x); }
@@ -1574,6 +1736,7 @@ impl<'a> Version<'a> {
     pub const VT_LAST_EPOCH: flatbuffers::VOffsetT = 16;
     pub const VT_GENESIS: flatbuffers::VOffsetT = 18;
     pub const VT_NONCE: flatbuffers::VOffsetT = 20;
+    pub const VT_CONSENSUS_CONSTANTS_HASH: flatbuffers::VOffsetT = 22;
 
   #[inline]
   pub fn version(&self) -> u32 {
@@ -1611,6 +1774,10 @@ impl<'a> Version<'a> {
   pub fn nonce(&self) -> u64 {
     self._tab.get::<u64>(Version::VT_NONCE, Some(0)).unwrap()
   }
+  #[inline]
+  pub fn consensus_constants_hash(&self) -> Option<Hash<'a>> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<Hash<'a>>>(Version::VT_CONSENSUS_CONSTANTS_HASH, None)
+  }
 }
 
 pub struct VersionArgs<'a> {
@@ -1623,6 +1790,7 @@ pub struct VersionArgs<'a> {
     pub last_epoch: u32,
     pub genesis: u64,
     pub nonce: u64,
+    pub consensus_constants_hash: Option<flatbuffers::WIPOffset<Hash<'a >>>,
 }
 impl<'a> Default for VersionArgs<'a> {
     #[inline]
@@ -1637,6 +1805,7 @@ impl<'a> Default for VersionArgs<'a> {
             last_epoch: 0,
             genesis: 0,
             nonce: 0,
+            consensus_constants_hash: None,
         }
     }
 }
@@ -1682,6 +1851,10 @@ impl<'a: 'b, 'b> VersionBuilder<'a, 'b> {
     self.fbb_.push_slot::<u64>(Version::VT_NONCE, nonce, 0);
   }
   #[inline]
+  pub fn add_consensus_constants_hash(&mut self, consensus_constants_hash: flatbuffers::WIPOffset<Hash<'b >>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<Hash>>(Version::VT_CONSENSUS_CONSTANTS_HASH, consensus_constants_hash);
+  }
+  #[inline]
   pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> VersionBuilder<'a, 'b> {
     let start = _fbb.start_table();
     VersionBuilder {
@@ -2114,6 +2287,196 @@ impl<'a: 'b, 'b> TransactionBuilder<'a, 'b> {
   }
 }
 
+pub enum ValueTransferOutputOffset {}
+#[derive(Copy, Clone, Debug, PartialEq)]
+
+pub struct ValueTransferOutput<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for ValueTransferOutput<'a> {
+    type Inner = ValueTransferOutput<'a>;
+    #[inline]
+    fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+        Self {
+            _tab: flatbuffers::Table { buf: buf, loc: loc },
+        }
+    }
+}
+
+impl<'a> ValueTransferOutput<'a> {
+    #[inline]
+    pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+        ValueTransferOutput {
+            _tab: table,
+        }
+    }
+    #[allow(unused_mut)]
+    pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+        _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+        args: &'args ValueTransferOutputArgs<'args>) -> flatbuffers::WIPOffset<ValueTransferOutput<'bldr>> {
+      let mut builder = ValueTransferOutputBuilder::new(_fbb);
+      builder.add_value(args.value);
+      builder.add_time_lock(args.time_lock);
+      if let Some(x) = args.pkh { builder.add_pkh(x); }
+      builder.finish()
+    }
+
+    pub const VT_PKH: flatbuffers::VOffsetT = 4;
+    pub const VT_VALUE: flatbuffers::VOffsetT = 6;
+    pub const VT_TIME_LOCK: flatbuffers::VOffsetT = 8;
+
+  #[inline]
+  pub fn pkh(&self) -> &'a [u8] {
+    self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, u8>>>(ValueTransferOutput::VT_PKH, None).map(|v| v.safe_slice()).unwrap()
+  }
+  #[inline]
+  pub fn value(&self) -> u64 {
+    self._tab.get::<u64>(ValueTransferOutput::VT_VALUE, Some(0)).unwrap()
+  }
+  #[inline]
+  pub fn time_lock(&self) -> u32 {
+    self._tab.get::<u32>(ValueTransferOutput::VT_TIME_LOCK, Some(0)).unwrap()
+  }
+}
+
+pub struct ValueTransferOutputArgs<'a> {
+    pub pkh: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a ,  u8>>>,
+    pub value: u64,
+    pub time_lock: u32,
+}
+impl<'a> Default for ValueTransferOutputArgs<'a> {
+    #[inline]
+    fn default() -> Self {
+        ValueTransferOutputArgs {
+            pkh: None, // required field
+            value: 0,
+            time_lock: 0,
+        }
+    }
+}
+pub struct ValueTransferOutputBuilder<'a: 'b, 'b> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b> ValueTransferOutputBuilder<'a, 'b> {
+  #[inline]
+  pub fn add_pkh(&mut self, pkh: flatbuffers::WIPOffset<flatbuffers::Vector<'b , u8>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(ValueTransferOutput::VT_PKH, pkh);
+  }
+  #[inline]
+  pub fn add_value(&mut self, value: u64) {
+    self.fbb_.push_slot::<u64>(ValueTransferOutput::VT_VALUE, value, 0);
+  }
+  #[inline]
+  pub fn add_time_lock(&mut self, time_lock: u32) {
+    self.fbb_.push_slot::<u32>(ValueTransferOutput::VT_TIME_LOCK, time_lock, 0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> ValueTransferOutputBuilder<'a, 'b> {
+    let start = _fbb.start_table();
+    ValueTransferOutputBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<ValueTransferOutput<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    self.fbb_.required(o, ValueTransferOutput::VT_PKH,"pkh");
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+pub enum MintTransactionOffset {}
+#[derive(Copy, Clone, Debug, PartialEq)]
+
+pub struct MintTransaction<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for MintTransaction<'a> {
+    type Inner = MintTransaction<'a>;
+    #[inline]
+    fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+        Self {
+            _tab: flatbuffers::Table { buf: buf, loc: loc },
+        }
+    }
+}
+
+impl<'a> MintTransaction<'a> {
+    #[inline]
+    pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+        MintTransaction {
+            _tab: table,
+        }
+    }
+    #[allow(unused_mut)]
+    pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+        _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+        args: &'args MintTransactionArgs<'args>) -> flatbuffers::WIPOffset<MintTransaction<'bldr>> {
+      let mut builder = MintTransactionBuilder::new(_fbb);
+      if let Some(x) = args.output { builder.add_output(x); }
+      builder.add_epoch(args.epoch);
+      builder.finish()
+    }
+
+    pub const VT_EPOCH: flatbuffers::VOffsetT = 4;
+    pub const VT_OUTPUT: flatbuffers::VOffsetT = 6;
+
+  #[inline]
+  pub fn epoch(&self) -> u32 {
+    self._tab.get::<u32>(MintTransaction::VT_EPOCH, Some(0)).unwrap()
+  }
+  #[inline]
+  pub fn output(&self) -> ValueTransferOutput<'a> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<ValueTransferOutput<'a>>>(MintTransaction::VT_OUTPUT, None).unwrap()
+  }
+}
+
+pub struct MintTransactionArgs<'a> {
+    pub epoch: u32,
+    pub output: Option<flatbuffers::WIPOffset<ValueTransferOutput<'a >>>,
+}
+impl<'a> Default for MintTransactionArgs<'a> {
+    #[inline]
+    fn default() -> Self {
+        MintTransactionArgs {
+            epoch: 0,
+            output: None, // required field
+        }
+    }
+}
+pub struct MintTransactionBuilder<'a: 'b, 'b> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b> MintTransactionBuilder<'a, 'b> {
+  #[inline]
+  pub fn add_epoch(&mut self, epoch: u32) {
+    self.fbb_.push_slot::<u32>(MintTransaction::VT_EPOCH, epoch, 0);
+  }
+  #[inline]
+  pub fn add_output(&mut self, output: flatbuffers::WIPOffset<ValueTransferOutput<'b >>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<ValueTransferOutput>>(MintTransaction::VT_OUTPUT, output);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> MintTransactionBuilder<'a, 'b> {
+    let start = _fbb.start_table();
+    MintTransactionBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<MintTransaction<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    self.fbb_.required(o, MintTransaction::VT_OUTPUT,"output");
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
 pub enum BlockOffset {}
 #[derive(Copy, Clone, Debug, PartialEq)]
 
@@ -2143,6 +2506,7 @@ impl<'a> Block<'a> {
         _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
         args: &'args BlockArgs<'args>) -> flatbuffers::WIPOffset<Block<'bldr>> {
       let mut builder = BlockBuilder::new(_fbb);
+      if let Some(x) = args.mint { builder.add_mint(x); }
       if let Some(x) = args.txns { builder.add_txns(x); }
       builder.add_txn_count(args.txn_count);
       if let Some(x) = args.header { builder.add_header(x); }
@@ -2152,6 +2516,7 @@ impl<'a> Block<'a> {
     pub const VT_HEADER: flatbuffers::VOffsetT = 4;
     pub const VT_TXN_COUNT: flatbuffers::VOffsetT = 6;
     pub const VT_TXNS: flatbuffers::VOffsetT = 8;
+    pub const VT_MINT: flatbuffers::VOffsetT = 10;
 
   #[inline]
   pub fn header(&self) -> BlockHeader<'a> {
@@ -2165,12 +2530,17 @@ impl<'a> Block<'a> {
   pub fn txns(&self) -> flatbuffers::Vector<flatbuffers::ForwardsUOffset<Transaction<'a>>> {
     self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<flatbuffers::ForwardsUOffset<Transaction<'a>>>>>(Block::VT_TXNS, None).unwrap()
   }
+  #[inline]
+  pub fn mint(&self) -> MintTransaction<'a> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<MintTransaction<'a>>>(Block::VT_MINT, None).unwrap()
+  }
 }
 
 pub struct BlockArgs<'a> {
     pub header: Option<flatbuffers::WIPOffset<BlockHeader<'a >>>,
     pub txn_count: u32,
     pub txns: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a , flatbuffers::ForwardsUOffset<Transaction<'a >>>>>,
+    pub mint: Option<flatbuffers::WIPOffset<MintTransaction<'a >>>,
 }
 impl<'a> Default for BlockArgs<'a> {
     #[inline]
@@ -2179,6 +2549,7 @@ impl<'a> Default for BlockArgs<'a> {
             header: None, // required field
             txn_count: 0,
             txns: None, // required field
+            mint: None, // required field
         }
     }
 }
@@ -2200,6 +2571,10 @@ impl<'a: 'b, 'b> BlockBuilder<'a, 'b> {
     self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Block::VT_TXNS, txns);
   }
   #[inline]
+  pub fn add_mint(&mut self, mint: flatbuffers::WIPOffset<MintTransaction<'b >>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<MintTransaction>>(Block::VT_MINT, mint);
+  }
+  #[inline]
   pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> BlockBuilder<'a, 'b> {
     let start = _fbb.start_table();
     BlockBuilder {
@@ -2212,6 +2587,7 @@ impl<'a: 'b, 'b> BlockBuilder<'a, 'b> {
     let o = self.fbb_.end_table(self.start_);
     self.fbb_.required(o, Block::VT_HEADER,"header");
     self.fbb_.required(o, Block::VT_TXNS,"txns");
+    self.fbb_.required(o, Block::VT_MINT,"mint");
     flatbuffers::WIPOffset::new(o.value())
   }
 }
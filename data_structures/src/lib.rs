@@ -12,12 +12,24 @@ pub mod builders;
 /// Module containing Witnet's chain data types
 pub mod chain;
 
+/// Module containing the economics of the data requests
+pub mod data_request;
+
+/// Module containing the emission schedule of the coins
+pub mod emission;
+
 /// Module generated by flatbuffers compiler, containing flatbuffers protocol messages types
 pub mod flatbuffers;
 
 /// Module containing functions to cast witnet's protocol messages to flatbuffers and vice versa
 pub mod serializers;
 
+/// Module containing the serialization format of the signing requests sent to external signers
+pub mod signing;
+
+/// Module containing the verification of the flatbuffers received from the network
+pub mod verifier;
+
 /// Module containing witnet's protocol messages types
 pub mod types;
 
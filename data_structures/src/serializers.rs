@@ -3,23 +3,27 @@ extern crate flatbuffers;
 use std::convert::Into;
 
 use crate::chain::{
-    Block, BlockHeader, BlockHeaderWithProof, CheckpointBeacon, Hash, InvVector, LeadershipProof,
-    Secp256k1Signature, Signature, Transaction, SHA256,
+    Block, BlockHeader, BlockHeaderWithProof, CheckpointBeacon, Hash, InvVector, KeyedSignature,
+    LeadershipProof, MintTransaction, Secp256k1Signature, Signature, Transaction,
+    ValueTransferOutput, SHA256,
 };
+use crate::error::{DecodeError, DecodeErrorKind, DecodeResult};
 use crate::flatbuffers::protocol_generated::protocol;
+use crate::verifier::verify_message;
 
 use crate::types::{
     Address, Command, GetBlocks, GetData, GetPeers, Inv,
     IpAddress::{Ipv4, Ipv6},
-    Message, Peers, Ping, Pong, Verack, Version,
+    LastBeacon, Message, PeerAddress, Peers, Ping, Pong, Verack, Version,
 };
 
 use flatbuffers::FlatBufferBuilder;
+use witnet_util::error::WitnetError;
 
 const FTB_SIZE: usize = 1024;
 
 #[derive(Debug, Clone, Copy)]
-struct GetBlocksCommandArgs {
+struct BeaconCommandArgs {
     highest_block_checkpoint: CheckpointBeacon,
     magic: u16,
 }
@@ -32,7 +36,7 @@ struct EmptyCommandArgs {
 #[derive(Debug, Clone, Copy)]
 struct PeersFlatbufferArgs<'a> {
     magic: u16,
-    peers: &'a [Address],
+    peers: &'a [PeerAddress],
 }
 // Refactor
 #[derive(Debug, Clone, Copy)]
@@ -54,6 +58,7 @@ struct VersionCommandArgs<'a> {
     genesis: u64,
     last_epoch: u32,
     nonce: u64,
+    consensus_constants_hash: Option<Hash>,
     receiver_address: &'a Address,
     sender_address: &'a Address,
     timestamp: i64,
@@ -67,6 +72,7 @@ struct BlockCommandArgs<'a> {
     header: BlockHeaderWithProof,
     txn_count: u32,
     txns: &'a [Transaction],
+    mint: &'a MintTransaction,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -94,9 +100,13 @@ pub trait TryFrom<T>: Sized {
 }
 
 impl TryFrom<Vec<u8>> for Message {
-    type Error = &'static str;
-    // type Error = Err<&'static str>;
-    fn try_from(bytes: Vec<u8>) -> Result<Self, &'static str> {
+    type Error = WitnetError<DecodeError>;
+
+    fn try_from(bytes: Vec<u8>) -> DecodeResult<Self> {
+        // The bytes come from the network, check that they are a well-formed flatbuffer before
+        // accessing them
+        verify_message(&bytes)?;
+
         // Get Flatbuffers Message
         let message = protocol::get_root_as_message(&bytes);
 
@@ -113,7 +123,7 @@ impl TryFrom<Vec<u8>> for Message {
                         magic,
                     })
                 })
-                .ok_or(""),
+                .ok_or_else(|| missing_field("Ping")),
             protocol::Command::Pong => message
                 .command_as_pong()
                 .map(|pong| {
@@ -122,40 +132,42 @@ impl TryFrom<Vec<u8>> for Message {
                         magic,
                     })
                 })
-                .ok_or(""),
+                .ok_or_else(|| missing_field("Pong")),
             protocol::Command::GetBlocks => message
                 .command_as_get_blocks()
                 .map(|get_blocks| {
-                    let hash_prev_block = match get_blocks
-                        .highest_block_checkpoint()
-                        .hash_prev_block()
-                        .type_()
-                    {
-                        protocol::HashType::SHA256 => {
-                            let mut sha256: SHA256 = [0; 32];
-                            let sha256_bytes = get_blocks
-                                .highest_block_checkpoint()
-                                .hash_prev_block()
-                                .bytes();
-                            sha256.copy_from_slice(sha256_bytes);
-                            Hash::SHA256(sha256)
-                        }
-                    };
+                    let hash_prev_block =
+                        create_hash(get_blocks.highest_block_checkpoint().hash_prev_block());
                     let highest_block_checkpoint = CheckpointBeacon {
                         checkpoint: get_blocks.highest_block_checkpoint().checkpoint(),
                         hash_prev_block,
                     };
-                    create_get_blocks_message(GetBlocksCommandArgs {
+                    create_get_blocks_message(BeaconCommandArgs {
+                        highest_block_checkpoint,
+                        magic,
+                    })
+                })
+                .ok_or_else(|| missing_field("GetBlocks")),
+            protocol::Command::LastBeacon => message
+                .command_as_last_beacon()
+                .map(|last_beacon| {
+                    let hash_prev_block =
+                        create_hash(last_beacon.highest_block_checkpoint().hash_prev_block());
+                    let highest_block_checkpoint = CheckpointBeacon {
+                        checkpoint: last_beacon.highest_block_checkpoint().checkpoint(),
+                        hash_prev_block,
+                    };
+                    create_last_beacon_message(BeaconCommandArgs {
                         highest_block_checkpoint,
                         magic,
                     })
                 })
-                .ok_or(""),
+                .ok_or_else(|| missing_field("LastBeacon")),
             protocol::Command::GetPeers => Ok(create_get_peers_message(EmptyCommandArgs { magic })),
             protocol::Command::Peers => message
                 .command_as_peers()
                 .and_then(|peers| create_peers_message(PeersWitnetArgs { magic, peers }))
-                .ok_or(""),
+                .ok_or_else(|| missing_field("Peers.peers")),
             protocol::Command::Verack => Ok(create_verack_message(EmptyCommandArgs { magic })),
             protocol::Command::Version => message
                 .command_as_version()
@@ -176,13 +188,16 @@ impl TryFrom<Vec<u8>> for Message {
                             last_epoch: command.last_epoch(),
                             genesis: command.genesis(),
                             nonce: command.nonce(),
+                            consensus_constants_hash: command
+                                .consensus_constants_hash()
+                                .map(create_hash),
                             magic,
                         }))
                     } else {
                         None
                     }
                 })
-                .ok_or(""),
+                .ok_or_else(|| missing_field("Version.sender_address or Version.receiver_address")),
             protocol::Command::Block => message
                 .command_as_block()
                 .map(|block| {
@@ -190,48 +205,19 @@ impl TryFrom<Vec<u8>> for Message {
                     let header_ftb = block.header();
                     let version = header_ftb.version();
                     // Get CheckpointBeacon
-                    let hash: Hash = match header_ftb.beacon().hash_prev_block().type_() {
-                        protocol::HashType::SHA256 => {
-                            let mut sha256: SHA256 = [0; 32];
-                            let sha256_bytes = header_ftb.beacon().hash_prev_block().bytes();
-                            sha256.copy_from_slice(sha256_bytes);
-
-                            Hash::SHA256(sha256)
-                        }
-                    };
                     let beacon = CheckpointBeacon {
                         checkpoint: header_ftb.beacon().checkpoint(),
-                        hash_prev_block: hash,
+                        hash_prev_block: create_hash(header_ftb.beacon().hash_prev_block()),
                     };
                     // Get hash merkle root
-                    let hash_merkle_root: Hash = match header_ftb.hash_merkle_root().type_() {
-                        protocol::HashType::SHA256 => {
-                            let mut sha256: SHA256 = [0; 32];
-                            let sha256_bytes = header_ftb.hash_merkle_root().bytes();
-                            sha256.copy_from_slice(sha256_bytes);
-
-                            Hash::SHA256(sha256)
-                        }
-                    };
+                    let hash_merkle_root = create_hash(header_ftb.hash_merkle_root());
+                    // Get UTXO set merkle root
+                    let utxo_merkle_root = create_hash(header_ftb.utxo_merkle_root());
                     // Get proof of leadership
-                    let block_sig = match header_ftb.proof().block_sig_type() {
-                        protocol::Signature::Secp256k1Signature => header_ftb
-                            .proof()
-                            .block_sig_as_secp_256k_1signature()
-                            .and_then(|signature_ftb| {
-                                let mut signature = Secp256k1Signature {
-                                    r: [0; 32],
-                                    s: [0; 32],
-                                    v: 0,
-                                };
-                                signature.r.copy_from_slice(&signature_ftb.r()[0..32]);
-                                signature.s.copy_from_slice(&signature_ftb.s()[0..32]);
-                                signature.v = signature_ftb.s()[32];
-
-                                Some(Signature::Secp256k1(signature))
-                            }),
-                        _ => None,
-                    };
+                    let block_sig = header_ftb
+                        .proof()
+                        .block_sig_as_secp_256k_1signature()
+                        .and_then(create_secp256k1_signature);
                     let influence = header_ftb.proof().influence();
                     let proof = LeadershipProof {
                         block_sig,
@@ -243,6 +229,7 @@ impl TryFrom<Vec<u8>> for Message {
                             version,
                             beacon,
                             hash_merkle_root,
+                            utxo_merkle_root,
                         },
                         proof,
                     };
@@ -259,17 +246,30 @@ impl TryFrom<Vec<u8>> for Message {
                         txns.push(Transaction);
                         counter += 1;
                     }
+                    // Get mint transaction
+                    let mint_ftb = block.mint();
+                    let mut pkh = [0; 20];
+                    pkh.copy_from_slice(mint_ftb.output().pkh());
+                    let mint = MintTransaction {
+                        epoch: mint_ftb.epoch(),
+                        output: ValueTransferOutput {
+                            pkh,
+                            value: mint_ftb.output().value(),
+                            time_lock: mint_ftb.output().time_lock(),
+                        },
+                    };
                     // Create Message with command
                     Message {
                         kind: Command::Block(Block {
                             header,
                             txn_count,
                             txns,
+                            mint,
                         }),
                         magic,
                     }
                 })
-                .ok_or(""),
+                .ok_or_else(|| missing_field("Block")),
             protocol::Command::Inv => message
                 .command_as_inv()
                 .and_then(|inv| {
@@ -278,7 +278,7 @@ impl TryFrom<Vec<u8>> for Message {
                         inventory: inv,
                     }))
                 })
-                .ok_or(""),
+                .ok_or_else(|| missing_field("Inv")),
             protocol::Command::GetData => message
                 .command_as_get_data()
                 .and_then(|get_data| {
@@ -287,12 +287,25 @@ impl TryFrom<Vec<u8>> for Message {
                         inventory: get_data,
                     }))
                 })
-                .ok_or(""),
-            protocol::Command::NONE => Err(""),
+                .ok_or_else(|| missing_field("GetData")),
+            protocol::Command::NONE => Err(DecodeError::new(
+                DecodeErrorKind::UnknownCommand,
+                "Message without command".to_string(),
+            )
+            .into()),
         }
     }
 }
 
+// Create a missing field error
+fn missing_field(name: &str) -> WitnetError<DecodeError> {
+    DecodeError::new(
+        DecodeErrorKind::MissingField,
+        format!("Missing field {}", name),
+    )
+    .into()
+}
+
 impl Into<Vec<u8>> for Message {
     fn into(self) -> Vec<u8> {
         let mut builder = flatbuffers::FlatBufferBuilder::new_with_capacity(FTB_SIZE);
@@ -303,7 +316,16 @@ impl Into<Vec<u8>> for Message {
                 highest_block_checkpoint,
             }) => create_get_blocks_flatbuffer(
                 &mut builder,
-                GetBlocksCommandArgs {
+                BeaconCommandArgs {
+                    magic: self.magic,
+                    highest_block_checkpoint,
+                },
+            ),
+            Command::LastBeacon(LastBeacon {
+                highest_block_checkpoint,
+            }) => create_last_beacon_flatbuffer(
+                &mut builder,
+                BeaconCommandArgs {
                     magic: self.magic,
                     highest_block_checkpoint,
                 },
@@ -345,6 +367,7 @@ impl Into<Vec<u8>> for Message {
                 last_epoch,
                 genesis,
                 nonce,
+                consensus_constants_hash,
             }) => create_version_flatbuffer(
                 &mut builder,
                 VersionCommandArgs {
@@ -358,12 +381,14 @@ impl Into<Vec<u8>> for Message {
                     last_epoch,
                     genesis,
                     nonce,
+                    consensus_constants_hash,
                 },
             ),
             Command::Block(Block {
                 header,
                 txn_count,
                 txns,
+                mint,
             }) => create_block_flatbuffer(
                 &mut builder,
                 BlockCommandArgs {
@@ -371,6 +396,7 @@ impl Into<Vec<u8>> for Message {
                     header,
                     txn_count,
                     txns: &txns,
+                    mint: &mint,
                 },
             ),
             Command::Inv(Inv { inventory }) => create_inv_flatbuffer(
@@ -423,7 +449,7 @@ fn create_address(address: protocol::Address) -> Option<Address> {
 // Create a ping Flatbuffer to encode a Witnet ping message
 fn create_get_blocks_flatbuffer(
     builder: &mut FlatBufferBuilder,
-    get_blocks_args: GetBlocksCommandArgs,
+    get_blocks_args: BeaconCommandArgs,
 ) -> Vec<u8> {
     let Hash::SHA256(hash) = get_blocks_args.highest_block_checkpoint.hash_prev_block;
     let ftb_hash = builder.create_vector(&hash);
@@ -461,7 +487,7 @@ fn create_get_blocks_flatbuffer(
 }
 
 // Create a Witnet ping message to decode Flatbuffers' ping message
-fn create_get_blocks_message(get_blocks_args: GetBlocksCommandArgs) -> Message {
+fn create_get_blocks_message(get_blocks_args: BeaconCommandArgs) -> Message {
     Message {
         kind: Command::GetBlocks(GetBlocks {
             highest_block_checkpoint: CheckpointBeacon {
@@ -473,6 +499,56 @@ fn create_get_blocks_message(get_blocks_args: GetBlocksCommandArgs) -> Message {
     }
 }
 
+// Create a last beacon Flatbuffer to encode a Witnet last beacon message
+fn create_last_beacon_flatbuffer(
+    builder: &mut FlatBufferBuilder,
+    last_beacon_args: BeaconCommandArgs,
+) -> Vec<u8> {
+    let Hash::SHA256(hash) = last_beacon_args.highest_block_checkpoint.hash_prev_block;
+    let ftb_hash = builder.create_vector(&hash);
+    let hash_command = protocol::Hash::create(
+        builder,
+        &protocol::HashArgs {
+            type_: protocol::HashType::SHA256,
+            bytes: Some(ftb_hash),
+        },
+    );
+
+    let beacon = protocol::CheckpointBeacon::create(
+        builder,
+        &protocol::CheckpointBeaconArgs {
+            checkpoint: last_beacon_args.highest_block_checkpoint.checkpoint,
+            hash_prev_block: Some(hash_command),
+        },
+    );
+
+    let last_beacon_command = protocol::LastBeacon::create(
+        builder,
+        &protocol::LastBeaconArgs {
+            highest_block_checkpoint: Some(beacon),
+        },
+    );
+    let message = protocol::Message::create(
+        builder,
+        &protocol::MessageArgs {
+            magic: last_beacon_args.magic,
+            command_type: protocol::Command::LastBeacon,
+            command: Some(last_beacon_command.as_union_value()),
+        },
+    );
+    build_flatbuffer(builder, message)
+}
+
+// Create a Witnet last beacon message to decode Flatbuffers' last beacon message
+fn create_last_beacon_message(last_beacon_args: BeaconCommandArgs) -> Message {
+    Message {
+        kind: Command::LastBeacon(LastBeacon {
+            highest_block_checkpoint: last_beacon_args.highest_block_checkpoint,
+        }),
+        magic: last_beacon_args.magic,
+    }
+}
+
 // Create a get peers Flatbuffer to encode Witnet's get peers message
 fn create_get_peers_flatbuffer(
     builder: &mut FlatBufferBuilder,
@@ -523,30 +599,46 @@ fn create_peers_flatbuffer(
     let addresses_command: Vec<flatbuffers::WIPOffset<protocol::Address>> = peers_args
         .peers
         .iter()
-        .map(|peer: &Address| match peer.ip {
-            Ipv4 { ip } => {
-                let ip_command = protocol::Ipv4::create(builder, &protocol::Ipv4Args { ip });
-                protocol::Address::create(
-                    builder,
-                    &protocol::AddressArgs {
-                        ip_type: protocol::IpAddress::Ipv4,
-                        ip: Some(ip_command.as_union_value()),
-                        port: peer.port,
-                    },
-                )
-            }
-            Ipv6 { ip0, ip1, ip2, ip3 } => {
-                let ip_command =
-                    protocol::Ipv6::create(builder, &protocol::Ipv6Args { ip0, ip1, ip2, ip3 });
-                protocol::Address::create(
-                    builder,
-                    &protocol::AddressArgs {
-                        ip_type: protocol::IpAddress::Ipv6,
-                        ip: Some(ip_command.as_union_value()),
-                        port: peer.port,
-                    },
-                )
-            }
+        .map(|peer: &PeerAddress| {
+            // Create the signature of the node originating the address, along with its public
+            // key (if any)
+            let (signature_type, signature, public_key) = match &peer.signature {
+                Some(keyed) => {
+                    let (signature_type, signature) = create_signature_flatbuffer(
+                        builder,
+                        &Signature::Secp256k1(keyed.signature.clone()),
+                    );
+                    let public_key = builder.create_vector(&keyed.public_key);
+
+                    (signature_type, Some(signature), Some(public_key))
+                }
+                None => (protocol::Signature::NONE, None, None),
+            };
+
+            let (ip_type, ip) = match peer.address.ip {
+                Ipv4 { ip } => (
+                    protocol::IpAddress::Ipv4,
+                    protocol::Ipv4::create(builder, &protocol::Ipv4Args { ip }).as_union_value(),
+                ),
+                Ipv6 { ip0, ip1, ip2, ip3 } => (
+                    protocol::IpAddress::Ipv6,
+                    protocol::Ipv6::create(builder, &protocol::Ipv6Args { ip0, ip1, ip2, ip3 })
+                        .as_union_value(),
+                ),
+            };
+
+            protocol::Address::create(
+                builder,
+                &protocol::AddressArgs {
+                    ip_type,
+                    ip: Some(ip),
+                    port: peer.address.port,
+                    last_seen: peer.last_seen,
+                    signature_type,
+                    signature,
+                    public_key,
+                },
+            )
         })
         .collect();
 
@@ -576,8 +668,12 @@ fn create_peers_message(peers_args: PeersWitnetArgs) -> Option<Message> {
         while counter < len {
             peer = ftb_addresses.get(counter);
             ftb_address = create_address(peer);
-            if ftb_address.is_some() {
-                vec_addresses.push(ftb_address.unwrap());
+            if let Some(address) = ftb_address {
+                vec_addresses.push(PeerAddress {
+                    address,
+                    last_seen: peer.last_seen(),
+                    signature: create_keyed_signature(peer),
+                });
             }
             counter += 1;
         }
@@ -590,6 +686,69 @@ fn create_peers_message(peers_args: PeersWitnetArgs) -> Option<Message> {
     })
 }
 
+// Create a signature flatbuffer, returning its union type and value
+fn create_signature_flatbuffer(
+    builder: &mut FlatBufferBuilder,
+    signature: &Signature,
+) -> (
+    protocol::Signature,
+    flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>,
+) {
+    match signature {
+        Signature::Secp256k1(secp256k1) => {
+            let mut s = secp256k1.s.to_vec();
+            s.push(secp256k1.v);
+            let r_ftb = Some(builder.create_vector(&secp256k1.r));
+            let s_ftb = Some(builder.create_vector(&s));
+
+            let signature_ftb = protocol::Secp256k1Signature::create(
+                builder,
+                &protocol::Secp256k1SignatureArgs { r: r_ftb, s: s_ftb },
+            );
+
+            (
+                protocol::Signature::Secp256k1Signature,
+                signature_ftb.as_union_value(),
+            )
+        }
+    }
+}
+
+// Create a witnet's keyed signature from the signature and the public key of a flatbuffers'
+// peer address, if it has both of them
+fn create_keyed_signature(peer: protocol::Address) -> Option<KeyedSignature> {
+    let public_key = peer.public_key()?.to_vec();
+    let signature = peer
+        .signature_as_secp_256k_1signature()
+        .and_then(create_secp256k1_signature)?;
+
+    match signature {
+        Signature::Secp256k1(signature) => Some(KeyedSignature {
+            public_key,
+            signature,
+        }),
+    }
+}
+
+// Create a witnet's signature from a flatbuffers' secp256k1 signature
+fn create_secp256k1_signature(signature_ftb: protocol::Secp256k1Signature) -> Option<Signature> {
+    // Check lengths to avoid panicking with malformed signatures
+    if signature_ftb.r().len() != 32 || signature_ftb.s().len() != 33 {
+        return None;
+    }
+
+    let mut signature = Secp256k1Signature {
+        r: [0; 32],
+        s: [0; 32],
+        v: 0,
+    };
+    signature.r.copy_from_slice(&signature_ftb.r()[0..32]);
+    signature.s.copy_from_slice(&signature_ftb.s()[0..32]);
+    signature.v = signature_ftb.s()[32];
+
+    Some(Signature::Secp256k1(signature))
+}
+
 // Create a ping flatbuffer to encode a witnet's ping message
 fn create_ping_flatbuffer(
     builder: &mut FlatBufferBuilder,
@@ -696,6 +855,7 @@ fn create_version_flatbuffer(
                     ip_type: protocol::IpAddress::Ipv4,
                     ip: Some(ip_command.as_union_value()),
                     port: version_args.sender_address.port,
+                    ..Default::default()
                 },
             )
         }
@@ -708,6 +868,7 @@ fn create_version_flatbuffer(
                     ip_type: protocol::IpAddress::Ipv6,
                     ip: Some(ip_command.as_union_value()),
                     port: version_args.sender_address.port,
+                    ..Default::default()
                 },
             )
         }
@@ -722,6 +883,7 @@ fn create_version_flatbuffer(
                     ip_type: protocol::IpAddress::Ipv4,
                     ip: Some(ip_command.as_union_value()),
                     port: version_args.receiver_address.port,
+                    ..Default::default()
                 },
             )
         }
@@ -734,12 +896,23 @@ fn create_version_flatbuffer(
                     ip_type: protocol::IpAddress::Ipv6,
                     ip: Some(ip_command.as_union_value()),
                     port: version_args.receiver_address.port,
+                    ..Default::default()
                 },
             )
         }
     };
 
     let user_agent = Some(builder.create_string(&version_args.user_agent));
+    // Create consensus constants hash flatbuffer, if any
+    let consensus_constants_hash = version_args.consensus_constants_hash.map(|hash| {
+        let hash_args = match hash {
+            Hash::SHA256(hash) => protocol::HashArgs {
+                type_: protocol::HashType::SHA256,
+                bytes: Some(builder.create_vector(&hash)),
+            },
+        };
+        protocol::Hash::create(builder, &hash_args)
+    });
     let version_command = protocol::Version::create(
         builder,
         &protocol::VersionArgs {
@@ -752,6 +925,7 @@ fn create_version_flatbuffer(
             last_epoch: version_args.last_epoch,
             genesis: version_args.genesis,
             nonce: version_args.nonce,
+            consensus_constants_hash,
         },
     );
 
@@ -780,6 +954,7 @@ fn create_version_message(version_args: VersionCommandArgs) -> Message {
             last_epoch: version_args.last_epoch,
             genesis: version_args.genesis,
             nonce: version_args.nonce,
+            consensus_constants_hash: version_args.consensus_constants_hash,
         }),
         magic: version_args.magic,
     }
@@ -813,6 +988,14 @@ fn create_block_flatbuffer(
         },
     };
     let hash_merkle_root = Some(protocol::Hash::create(builder, &hash_merkle_root_args));
+    // Create UTXO set merkle root flatbuffer
+    let utxo_merkle_root_args = match block_args.header.block_header.utxo_merkle_root {
+        Hash::SHA256(hash) => protocol::HashArgs {
+            type_: protocol::HashType::SHA256,
+            bytes: Some(builder.create_vector(&hash)),
+        },
+    };
+    let utxo_merkle_root = Some(protocol::Hash::create(builder, &utxo_merkle_root_args));
     // Create proof of leadership flatbuffer
     let block_sig_type =
         block_args
@@ -857,6 +1040,7 @@ fn create_block_flatbuffer(
             beacon,
             hash_merkle_root,
             proof,
+            utxo_merkle_root,
         },
     ));
     // Create transaction array flatbuffer
@@ -868,6 +1052,23 @@ fn create_block_flatbuffer(
         })
         .collect();
     let txns_ftb = Some(builder.create_vector(&txns));
+    // Create mint transaction flatbuffer
+    let pkh = Some(builder.create_vector(&block_args.mint.output.pkh));
+    let output = Some(protocol::ValueTransferOutput::create(
+        builder,
+        &protocol::ValueTransferOutputArgs {
+            pkh,
+            value: block_args.mint.output.value,
+            time_lock: block_args.mint.output.time_lock,
+        },
+    ));
+    let mint = Some(protocol::MintTransaction::create(
+        builder,
+        &protocol::MintTransactionArgs {
+            epoch: block_args.mint.epoch,
+            output,
+        },
+    ));
     // Create block command flatbuffer
     let block_command = protocol::Block::create(
         builder,
@@ -875,6 +1076,7 @@ fn create_block_flatbuffer(
             header,
             txn_count: block_args.txn_count,
             txns: txns_ftb,
+            mint,
         },
     );
     // Create message flatbuffer
@@ -0,0 +1,180 @@
+//! Serialization format of the signing requests sent to external signers
+//!
+//! External signers (hardware wallets, remote signing services) must be able to show the user
+//! what they are about to sign before approving it, without having to understand the internal
+//! data structures of the node. Every signing request is therefore encoded in a simple, versioned
+//! binary format:
+//!
+//! | Field         | Size                  | Description                                         |
+//! |---------------|-----------------------|-----------------------------------------------------|
+//! | `version`     | 1 byte                | Version of the format, currently `1`                |
+//! | `path_length` | 1 byte                | Number of indexes of the derivation path of the key |
+//! | `path`        | 4 bytes per index     | Indexes of the derivation path, big endian          |
+//! | `hash`        | 32 bytes              | Hash of the transaction, the message to be signed   |
+//! | `required`    | 1 byte                | Number of signatures required by the spent output   |
+//! | `owners`      | 1 byte                | Number of owners of the spent output                |
+//! | `pkhs`        | 20 bytes per owner    | Public key hashes of the owners of the spent output |
+//!
+//! The signer answers with the serialized compressed public key of the key used to sign (33
+//! bytes) followed by the `r` (32 bytes), `s` (32 bytes) and `v` (1 byte) values of the
+//! signature.
+// FIXME(#99): include the inputs and outputs of the transaction in the signing request once
+// Transaction is defined, so the signer can display the value being transferred
+use crate::chain::{Hash, KeyedSignature, MultiSigCondition, PublicKeyHash, Secp256k1Signature};
+use crate::error::{DecodeError, DecodeErrorKind, DecodeResult};
+use witnet_util::error::WitnetError;
+
+/// Current version of the signing request format
+pub const SIGNING_FORMAT_VERSION: u8 = 1;
+
+/// Length of a serialized compressed public key
+pub const PUBLIC_KEY_LENGTH: usize = 33;
+
+/// Length of an encoded signing response
+pub const SIGNING_RESPONSE_LENGTH: usize = PUBLIC_KEY_LENGTH + 32 + 32 + 1;
+
+/// Request to sign a transaction with a key of the wallet
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SigningRequest {
+    /// Derivation path of the signing key
+    pub key_path: Vec<u32>,
+    /// Hash of the transaction
+    pub hash: Hash,
+    /// Condition of the spent output
+    pub condition: MultiSigCondition,
+}
+
+impl SigningRequest {
+    /// Encode the signing request
+    pub fn encode(&self) -> Vec<u8> {
+        let Hash::SHA256(hash) = self.hash;
+        let mut bytes =
+            Vec::with_capacity(36 + 4 * self.key_path.len() + 20 * self.condition.pkhs.len());
+
+        bytes.push(SIGNING_FORMAT_VERSION);
+        bytes.push(self.key_path.len() as u8);
+        for index in &self.key_path {
+            bytes.extend_from_slice(&index.to_be_bytes());
+        }
+        bytes.extend_from_slice(&hash);
+        bytes.push(self.condition.required);
+        bytes.push(self.condition.pkhs.len() as u8);
+        for pkh in &self.condition.pkhs {
+            bytes.extend_from_slice(pkh);
+        }
+
+        bytes
+    }
+
+    /// Decode a signing request
+    pub fn decode(bytes: &[u8]) -> DecodeResult<Self> {
+        let mut reader = Reader(bytes);
+
+        let version = reader.byte()?;
+        if version != SIGNING_FORMAT_VERSION {
+            return Err(decode_error(
+                DecodeErrorKind::UnsupportedVersion,
+                format!("Unsupported signing request version {}", version),
+            ));
+        }
+
+        let path_length = reader.byte()?;
+        let mut key_path = Vec::with_capacity(usize::from(path_length));
+        for _ in 0..path_length {
+            let mut index = [0; 4];
+            index.copy_from_slice(reader.take(4)?);
+            key_path.push(u32::from_be_bytes(index));
+        }
+
+        let mut hash = [0; 32];
+        hash.copy_from_slice(reader.take(32)?);
+
+        let required = reader.byte()?;
+        let owners = reader.byte()?;
+        let mut pkhs = Vec::with_capacity(usize::from(owners));
+        for _ in 0..owners {
+            let mut pkh: PublicKeyHash = [0; 20];
+            pkh.copy_from_slice(reader.take(20)?);
+            pkhs.push(pkh);
+        }
+
+        reader.finish()?;
+
+        Ok(SigningRequest {
+            key_path,
+            hash: Hash::SHA256(hash),
+            condition: MultiSigCondition { required, pkhs },
+        })
+    }
+}
+
+/// Encode the response of a signer: the public key of the signing key and the signature
+pub fn encode_signing_response(keyed: &KeyedSignature) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(SIGNING_RESPONSE_LENGTH);
+    bytes.extend_from_slice(&keyed.public_key);
+    bytes.extend_from_slice(&keyed.signature.r);
+    bytes.extend_from_slice(&keyed.signature.s);
+    bytes.push(keyed.signature.v);
+
+    bytes
+}
+
+/// Decode the response of a signer
+pub fn decode_signing_response(bytes: &[u8]) -> DecodeResult<KeyedSignature> {
+    let mut reader = Reader(bytes);
+
+    let public_key = reader.take(PUBLIC_KEY_LENGTH)?.to_vec();
+    let mut r = [0; 32];
+    r.copy_from_slice(reader.take(32)?);
+    let mut s = [0; 32];
+    s.copy_from_slice(reader.take(32)?);
+    let v = reader.byte()?;
+
+    reader.finish()?;
+
+    Ok(KeyedSignature {
+        public_key,
+        signature: Secp256k1Signature { r, s, v },
+    })
+}
+
+/// Build a decode error
+fn decode_error(kind: DecodeErrorKind, msg: String) -> WitnetError<DecodeError> {
+    DecodeError::new(kind, msg).into()
+}
+
+/// Cursor over the bytes being decoded
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    /// Consume the next `n` bytes
+    fn take(&mut self, n: usize) -> DecodeResult<&'a [u8]> {
+        if self.0.len() < n {
+            return Err(decode_error(
+                DecodeErrorKind::InvalidLength,
+                format!("Expected {} more bytes, found {}", n, self.0.len()),
+            ));
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+
+        Ok(head)
+    }
+
+    /// Consume the next byte
+    fn byte(&mut self) -> DecodeResult<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    /// Fail if there are bytes left
+    fn finish(&self) -> DecodeResult<()> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(decode_error(
+                DecodeErrorKind::InvalidLength,
+                format!("{} unexpected trailing bytes", self.0.len()),
+            ))
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use crate::chain::{Block, CheckpointBeacon, InvVector};
+use crate::chain::{Block, CheckpointBeacon, Hash, InvVector, KeyedSignature};
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum Command {
@@ -14,6 +14,7 @@ pub enum Command {
     Inv(Inv),
     GetData(GetData),
     GetBlocks(GetBlocks),
+    LastBeacon(LastBeacon),
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -21,7 +22,40 @@ pub struct GetPeers;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Peers {
-    pub peers: Vec<Address>,
+    pub peers: Vec<PeerAddress>,
+}
+
+/// Address shared through peer discovery, along with the last time it was seen and an optional
+/// signature of the node that originated it (see `PeerAddress::signed_bytes`)
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PeerAddress {
+    pub address: Address,
+    pub last_seen: i64,
+    pub signature: Option<KeyedSignature>,
+}
+
+impl PeerAddress {
+    /// Bytes signed by the node originating an address: the version of the IP address (4 or 6),
+    /// the IP address, the port and the last time the address was seen, all of them big endian
+    pub fn signed_bytes(address: &Address, last_seen: i64) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(27);
+        match address.ip {
+            IpAddress::Ipv4 { ip } => {
+                bytes.push(4);
+                bytes.extend_from_slice(&ip.to_be_bytes());
+            }
+            IpAddress::Ipv6 { ip0, ip1, ip2, ip3 } => {
+                bytes.push(6);
+                for ip in &[ip0, ip1, ip2, ip3] {
+                    bytes.extend_from_slice(&ip.to_be_bytes());
+                }
+            }
+        }
+        bytes.extend_from_slice(&address.port.to_be_bytes());
+        bytes.extend_from_slice(&last_seen.to_be_bytes());
+
+        bytes
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -34,6 +68,13 @@ pub struct GetBlocks {
     pub highest_block_checkpoint: CheckpointBeacon,
 }
 
+/// Highest consolidated checkpoint beacon of a peer, used to decide whether blocks need to be
+/// downloaded from it
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LastBeacon {
+    pub highest_block_checkpoint: CheckpointBeacon,
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Pong {
     pub nonce: u64,
@@ -53,6 +94,7 @@ pub struct Version {
     pub last_epoch: u32,
     pub genesis: u64,
     pub nonce: u64,
+    pub consensus_constants_hash: Option<Hash>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -65,24 +107,28 @@ pub struct GetData {
     pub inventory: Vec<InvVector>,
 }
 
+impl Command {
+    /// Name of the command, as written in the logs
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::GetBlocks(_) => "GET_BLOCKS",
+            Command::GetPeers(_) => "GET_PEERS",
+            Command::Peers(_) => "PEERS",
+            Command::Ping(_) => "PING",
+            Command::Pong(_) => "PONG",
+            Command::Verack(_) => "VERACK",
+            Command::Version(_) => "VERSION",
+            Command::Block(_) => "BLOCK",
+            Command::Inv(_) => "INV",
+            Command::GetData(_) => "GET_DATA",
+            Command::LastBeacon(_) => "LAST_BEACON",
+        }
+    }
+}
+
 impl fmt::Display for Command {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Command::GetBlocks(_) => "GET_BLOCKS",
-                Command::GetPeers(_) => "GET_PEERS",
-                Command::Peers(_) => "PEERS",
-                Command::Ping(_) => "PING",
-                Command::Pong(_) => "PONG",
-                Command::Verack(_) => "VERACK",
-                Command::Version(_) => "VERSION",
-                Command::Block(_) => "BLOCK",
-                Command::Inv(_) => "INV",
-                Command::GetData(_) => "GET_DATA",
-            }
-        )
+        write!(f, "{}", self.name())
     }
 }
 
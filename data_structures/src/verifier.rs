@@ -0,0 +1,514 @@
+//! Verification of the flatbuffers protocol messages received from the network
+//!
+//! The flatbuffers runtime trusts the buffers it reads: following an offset which points out of
+//! the buffer or reading an unknown enum value is undefined behaviour. Since protocol messages come
+//! from untrusted peers, every buffer must pass `verify_message` before being accessed through
+//! the generated `protocol` module.
+
+use std::str;
+
+use crate::error::{DecodeError, DecodeErrorKind, DecodeResult};
+use crate::flatbuffers::protocol_generated::protocol;
+use witnet_util::error::WitnetError;
+
+/// Size of an offset to a table, vector or string
+const SIZE_UOFFSET: usize = 4;
+
+/// Size of an offset from a table to its vtable
+const SIZE_SOFFSET: usize = 4;
+
+/// Size of an entry of a vtable
+const SIZE_VOFFSET: usize = 2;
+
+/// Length of a SHA-256 hash
+const SHA256_LENGTH: usize = 32;
+
+/// Length of a public key hash
+const PUBLIC_KEY_HASH_LENGTH: usize = 20;
+
+/// Length of a serialized compressed secp256k1 public key
+const PUBLIC_KEY_LENGTH: usize = 33;
+
+/// Length of the `r` component of a secp256k1 signature
+const SECP256K1_R_LENGTH: usize = 32;
+
+/// Length of the `s` component of a secp256k1 signature, followed by the `v` byte
+const SECP256K1_S_LENGTH: usize = 33;
+
+/// Table whose position and vtable have been checked
+#[derive(Clone, Copy, Debug)]
+struct Table {
+    /// Position of the table in the buffer
+    pos: usize,
+    /// Position of the vtable in the buffer
+    vtable: usize,
+    /// Length of the vtable
+    vtable_len: usize,
+    /// Length of the inline data of the table
+    table_len: usize,
+}
+
+/// Bounds and alignment checker for a flatbuffer
+struct Verifier<'a> {
+    /// Buffer being verified
+    buf: &'a [u8],
+}
+
+impl<'a> Verifier<'a> {
+    /// Check that `len` bytes starting at `pos` are inside the buffer
+    fn check_range(&self, pos: usize, len: usize) -> DecodeResult<()> {
+        match pos.checked_add(len) {
+            Some(end) if end <= self.buf.len() => Ok(()),
+            _ => Err(malformed(format!(
+                "{} bytes at position {} are out of bounds",
+                len, pos
+            ))),
+        }
+    }
+
+    /// Check that a scalar of `size` bytes at `pos` is aligned
+    fn check_alignment(&self, pos: usize, size: usize) -> DecodeResult<()> {
+        if pos % size == 0 {
+            Ok(())
+        } else {
+            Err(malformed(format!(
+                "Position {} is not aligned to {} bytes",
+                pos, size
+            )))
+        }
+    }
+
+    /// Read a little endian scalar of `size` bytes
+    fn read_scalar(&self, pos: usize, size: usize) -> DecodeResult<u64> {
+        self.check_range(pos, size)?;
+        self.check_alignment(pos, size)?;
+
+        Ok(self.buf[pos..pos + size]
+            .iter()
+            .rev()
+            .fold(0, |acc, byte| (acc << 8) | u64::from(*byte)))
+    }
+
+    /// Follow the offset stored at `pos`, returning the position it points to
+    fn follow_offset(&self, pos: usize) -> DecodeResult<usize> {
+        let offset = self.read_scalar(pos, SIZE_UOFFSET)? as usize;
+
+        pos.checked_add(offset)
+            .ok_or_else(|| malformed(format!("Offset at position {} overflows", pos)))
+    }
+
+    /// Check the table at `pos` and its vtable
+    fn table(&self, pos: usize) -> DecodeResult<Table> {
+        let soffset = self.read_scalar(pos, SIZE_SOFFSET)? as u32 as i32;
+        let vtable = pos as i64 - i64::from(soffset);
+        if vtable < 0 || vtable >= self.buf.len() as i64 {
+            return Err(malformed(format!(
+                "Vtable of the table at position {} is out of bounds",
+                pos
+            )));
+        }
+        let vtable = vtable as usize;
+
+        let vtable_len = self.read_scalar(vtable, SIZE_VOFFSET)? as usize;
+        let table_len = self.read_scalar(vtable + SIZE_VOFFSET, SIZE_VOFFSET)? as usize;
+        if vtable_len < 2 * SIZE_VOFFSET || vtable_len % SIZE_VOFFSET != 0 {
+            return Err(malformed(format!(
+                "Invalid vtable length {} at position {}",
+                vtable_len, vtable
+            )));
+        }
+        if table_len < SIZE_SOFFSET {
+            return Err(malformed(format!(
+                "Invalid table length {} at position {}",
+                table_len, pos
+            )));
+        }
+        self.check_range(vtable, vtable_len)?;
+        self.check_range(pos, table_len)?;
+
+        Ok(Table {
+            pos,
+            vtable,
+            vtable_len,
+            table_len,
+        })
+    }
+
+    /// Position of a field of `size` bytes, if it is present in the table
+    fn field(&self, table: Table, voffset: u16, size: usize) -> DecodeResult<Option<usize>> {
+        let voffset = voffset as usize;
+        if voffset + SIZE_VOFFSET > table.vtable_len {
+            return Ok(None);
+        }
+
+        let offset = self.read_scalar(table.vtable + voffset, SIZE_VOFFSET)? as usize;
+        if offset == 0 {
+            return Ok(None);
+        }
+        if offset + size > table.table_len {
+            return Err(malformed(format!(
+                "Field at offset {} of the table at position {} is out of the table",
+                offset, table.pos
+            )));
+        }
+
+        let pos = table.pos + offset;
+        self.check_alignment(pos, size)?;
+
+        Ok(Some(pos))
+    }
+
+    /// Check a scalar field of `size` bytes
+    fn scalar_field(&self, table: Table, voffset: u16, size: usize) -> DecodeResult<()> {
+        self.field(table, voffset, size).map(|_| ())
+    }
+
+    /// Check an enum field, returning its value (0 if it is not present)
+    fn enum_field(&self, table: Table, voffset: u16, max: u8) -> DecodeResult<u8> {
+        let value = match self.field(table, voffset, 1)? {
+            Some(pos) => self.buf[pos],
+            None => 0,
+        };
+        if value > max {
+            return Err(DecodeError::new(
+                DecodeErrorKind::InvalidEnumValue,
+                format!("Value {} is greater than {}", value, max),
+            )
+            .into());
+        }
+
+        Ok(value)
+    }
+
+    /// Check a table field, returning the table if it is present
+    fn table_field(&self, table: Table, voffset: u16) -> DecodeResult<Option<Table>> {
+        match self.field(table, voffset, SIZE_UOFFSET)? {
+            Some(pos) => Ok(Some(self.table(self.follow_offset(pos)?)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Check a vector field with elements of `elem_size` bytes, returning the position of its
+    /// first element and its length if it is present
+    fn vector_field(
+        &self,
+        table: Table,
+        voffset: u16,
+        elem_size: usize,
+    ) -> DecodeResult<Option<(usize, usize)>> {
+        let pos = match self.field(table, voffset, SIZE_UOFFSET)? {
+            Some(pos) => self.follow_offset(pos)?,
+            None => return Ok(None),
+        };
+        let len = self.read_scalar(pos, SIZE_UOFFSET)? as usize;
+        let start = pos + SIZE_UOFFSET;
+        let size = len
+            .checked_mul(elem_size)
+            .ok_or_else(|| malformed(format!("Vector length {} overflows", len)))?;
+        self.check_range(start, size)?;
+
+        Ok(Some((start, len)))
+    }
+
+    /// Check a vector of tables field, returning its tables if it is present
+    fn tables_vector_field(&self, table: Table, voffset: u16) -> DecodeResult<Option<Vec<Table>>> {
+        match self.vector_field(table, voffset, SIZE_UOFFSET)? {
+            Some((start, len)) => (0..len)
+                .map(|i| self.table(self.follow_offset(start + i * SIZE_UOFFSET)?))
+                .collect::<DecodeResult<Vec<Table>>>()
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Check a bytes vector field, returning its length if it is present
+    fn bytes_field(&self, table: Table, voffset: u16) -> DecodeResult<Option<usize>> {
+        Ok(self.vector_field(table, voffset, 1)?.map(|(_, len)| len))
+    }
+
+    /// Check a string field, which must be null terminated and valid UTF-8
+    fn string_field(&self, table: Table, voffset: u16) -> DecodeResult<Option<()>> {
+        match self.vector_field(table, voffset, 1)? {
+            Some((start, len)) => {
+                self.check_range(start, len + 1)?;
+                str::from_utf8(&self.buf[start..start + len])
+                    .map_err(|e| malformed(format!("Invalid UTF-8 string: {}", e)))?;
+
+                Ok(Some(()))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Create a malformed buffer error
+fn malformed(msg: String) -> WitnetError<DecodeError> {
+    DecodeError::new(DecodeErrorKind::Malformed, msg).into()
+}
+
+/// Fail if a required field is not present
+fn required<T>(value: Option<T>, name: &str) -> DecodeResult<T> {
+    value.ok_or_else(|| {
+        DecodeError::new(
+            DecodeErrorKind::MissingField,
+            format!("Missing required field {}", name),
+        )
+        .into()
+    })
+}
+
+/// Fail if a hash or signature component does not have the expected length
+fn check_length(len: usize, expected: usize, name: &str) -> DecodeResult<()> {
+    if len == expected {
+        Ok(())
+    } else {
+        Err(DecodeError::new(
+            DecodeErrorKind::InvalidLength,
+            format!("{} has {} bytes instead of {}", name, len, expected),
+        )
+        .into())
+    }
+}
+
+/// Check that a buffer contains a well-formed protocol message, so that it can be safely accessed
+/// through the generated flatbuffers code
+pub fn verify_message(buf: &[u8]) -> DecodeResult<()> {
+    let v = Verifier { buf };
+    let message = v.table(v.follow_offset(0)?)?;
+    v.scalar_field(message, protocol::Message::VT_MAGIC, 2)?;
+    v.enum_field(
+        message,
+        protocol::Message::VT_COMMAND_TYPE,
+        protocol::Command::LastBeacon as u8,
+    )?;
+    let command = required(
+        v.table_field(message, protocol::Message::VT_COMMAND)?,
+        "Message.command",
+    )?;
+
+    // The command type has been checked, so it can be safely read
+    match protocol::get_root_as_message(buf).command_type() {
+        protocol::Command::NONE => Err(DecodeError::new(
+            DecodeErrorKind::UnknownCommand,
+            "Message without command".to_string(),
+        )
+        .into()),
+        protocol::Command::Version => verify_version(&v, command),
+        protocol::Command::Verack | protocol::Command::GetPeers => Ok(()),
+        protocol::Command::Peers => verify_peers(&v, command),
+        protocol::Command::Ping => v.scalar_field(command, protocol::Ping::VT_NONCE, 8),
+        protocol::Command::Pong => v.scalar_field(command, protocol::Pong::VT_NONCE, 8),
+        protocol::Command::Block => verify_block(&v, command),
+        protocol::Command::Inv => verify_inventory(&v, command, protocol::Inv::VT_INVENTORY),
+        protocol::Command::GetData => {
+            verify_inventory(&v, command, protocol::GetData::VT_INVENTORY)
+        }
+        protocol::Command::GetBlocks => verify_checkpoint_beacon(
+            &v,
+            required(
+                v.table_field(command, protocol::GetBlocks::VT_HIGHEST_BLOCK_CHECKPOINT)?,
+                "GetBlocks.highest_block_checkpoint",
+            )?,
+        ),
+        protocol::Command::LastBeacon => verify_checkpoint_beacon(
+            &v,
+            required(
+                v.table_field(command, protocol::LastBeacon::VT_HIGHEST_BLOCK_CHECKPOINT)?,
+                "LastBeacon.highest_block_checkpoint",
+            )?,
+        ),
+    }
+}
+
+fn verify_version(v: &Verifier<'_>, version: Table) -> DecodeResult<()> {
+    v.scalar_field(version, protocol::Version::VT_VERSION, 4)?;
+    v.scalar_field(version, protocol::Version::VT_TIMESTAMP, 8)?;
+    v.scalar_field(version, protocol::Version::VT_CAPABILITIES, 8)?;
+    if let Some(address) = v.table_field(version, protocol::Version::VT_SENDER_ADDRESS)? {
+        verify_address(v, address)?;
+    }
+    if let Some(address) = v.table_field(version, protocol::Version::VT_RECEIVER_ADDRESS)? {
+        verify_address(v, address)?;
+    }
+    required(
+        v.string_field(version, protocol::Version::VT_USER_AGENT)?,
+        "Version.user_agent",
+    )?;
+    v.scalar_field(version, protocol::Version::VT_LAST_EPOCH, 4)?;
+    v.scalar_field(version, protocol::Version::VT_GENESIS, 8)?;
+    v.scalar_field(version, protocol::Version::VT_NONCE, 8)
+}
+
+fn verify_peers(v: &Verifier<'_>, peers: Table) -> DecodeResult<()> {
+    if let Some(addresses) = v.tables_vector_field(peers, protocol::Peers::VT_PEERS)? {
+        for address in addresses {
+            verify_address(v, address)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn verify_address(v: &Verifier<'_>, address: Table) -> DecodeResult<()> {
+    let ip_type = v.enum_field(
+        address,
+        protocol::Address::VT_IP_TYPE,
+        protocol::IpAddress::Ipv6 as u8,
+    )?;
+    if let Some(ip) = v.table_field(address, protocol::Address::VT_IP)? {
+        if ip_type == protocol::IpAddress::Ipv4 as u8 {
+            v.scalar_field(ip, protocol::Ipv4::VT_IP, 4)?;
+        } else if ip_type == protocol::IpAddress::Ipv6 as u8 {
+            v.scalar_field(ip, protocol::Ipv6::VT_IP0, 4)?;
+            v.scalar_field(ip, protocol::Ipv6::VT_IP1, 4)?;
+            v.scalar_field(ip, protocol::Ipv6::VT_IP2, 4)?;
+            v.scalar_field(ip, protocol::Ipv6::VT_IP3, 4)?;
+        }
+    }
+    v.scalar_field(address, protocol::Address::VT_PORT, 2)?;
+    v.scalar_field(address, protocol::Address::VT_LAST_SEEN, 8)?;
+    if let Some(len) = v.bytes_field(address, protocol::Address::VT_PUBLIC_KEY)? {
+        check_length(len, PUBLIC_KEY_LENGTH, "Address.public_key")?;
+    }
+    verify_signature(
+        v,
+        address,
+        protocol::Address::VT_SIGNATURE_TYPE,
+        protocol::Address::VT_SIGNATURE,
+    )
+}
+
+fn verify_signature(
+    v: &Verifier<'_>,
+    table: Table,
+    type_voffset: u16,
+    voffset: u16,
+) -> DecodeResult<()> {
+    let signature_type = v.enum_field(
+        table,
+        type_voffset,
+        protocol::Signature::Secp256k1Signature as u8,
+    )?;
+    if let Some(signature) = v.table_field(table, voffset)? {
+        if signature_type == protocol::Signature::Secp256k1Signature as u8 {
+            let r = required(
+                v.bytes_field(signature, protocol::Secp256k1Signature::VT_R)?,
+                "Secp256k1Signature.r",
+            )?;
+            check_length(r, SECP256K1_R_LENGTH, "Secp256k1Signature.r")?;
+            let s = required(
+                v.bytes_field(signature, protocol::Secp256k1Signature::VT_S)?,
+                "Secp256k1Signature.s",
+            )?;
+            check_length(s, SECP256K1_S_LENGTH, "Secp256k1Signature.s")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn verify_hash(v: &Verifier<'_>, hash: Table) -> DecodeResult<()> {
+    v.enum_field(
+        hash,
+        protocol::Hash::VT_TYPE_,
+        protocol::HashType::SHA256 as u8,
+    )?;
+    let len = required(v.bytes_field(hash, protocol::Hash::VT_BYTES)?, "Hash.bytes")?;
+    check_length(len, SHA256_LENGTH, "Hash.bytes")
+}
+
+fn verify_checkpoint_beacon(v: &Verifier<'_>, beacon: Table) -> DecodeResult<()> {
+    v.scalar_field(beacon, protocol::CheckpointBeacon::VT_CHECKPOINT, 4)?;
+    verify_hash(
+        v,
+        required(
+            v.table_field(beacon, protocol::CheckpointBeacon::VT_HASH_PREV_BLOCK)?,
+            "CheckpointBeacon.hash_prev_block",
+        )?,
+    )
+}
+
+fn verify_block(v: &Verifier<'_>, block: Table) -> DecodeResult<()> {
+    let header = required(
+        v.table_field(block, protocol::Block::VT_HEADER)?,
+        "Block.header",
+    )?;
+    v.scalar_field(header, protocol::BlockHeader::VT_VERSION, 4)?;
+    verify_checkpoint_beacon(
+        v,
+        required(
+            v.table_field(header, protocol::BlockHeader::VT_BEACON)?,
+            "BlockHeader.beacon",
+        )?,
+    )?;
+    verify_hash(
+        v,
+        required(
+            v.table_field(header, protocol::BlockHeader::VT_HASH_MERKLE_ROOT)?,
+            "BlockHeader.hash_merkle_root",
+        )?,
+    )?;
+    let proof = required(
+        v.table_field(header, protocol::BlockHeader::VT_PROOF)?,
+        "BlockHeader.proof",
+    )?;
+    verify_signature(
+        v,
+        proof,
+        protocol::LeadershipProof::VT_BLOCK_SIG_TYPE,
+        protocol::LeadershipProof::VT_BLOCK_SIG,
+    )?;
+    v.scalar_field(proof, protocol::LeadershipProof::VT_INFLUENCE, 8)?;
+    verify_hash(
+        v,
+        required(
+            v.table_field(header, protocol::BlockHeader::VT_UTXO_MERKLE_ROOT)?,
+            "BlockHeader.utxo_merkle_root",
+        )?,
+    )?;
+
+    v.scalar_field(block, protocol::Block::VT_TXN_COUNT, 4)?;
+    // FIXME(#99): verify the fields of the transactions once Transaction is defined
+    required(
+        v.tables_vector_field(block, protocol::Block::VT_TXNS)?,
+        "Block.txns",
+    )?;
+
+    let mint = required(
+        v.table_field(block, protocol::Block::VT_MINT)?,
+        "Block.mint",
+    )?;
+    v.scalar_field(mint, protocol::MintTransaction::VT_EPOCH, 4)?;
+    let output = required(
+        v.table_field(mint, protocol::MintTransaction::VT_OUTPUT)?,
+        "MintTransaction.output",
+    )?;
+    let len = required(
+        v.bytes_field(output, protocol::ValueTransferOutput::VT_PKH)?,
+        "ValueTransferOutput.pkh",
+    )?;
+    check_length(len, PUBLIC_KEY_HASH_LENGTH, "ValueTransferOutput.pkh")?;
+    v.scalar_field(output, protocol::ValueTransferOutput::VT_VALUE, 8)?;
+
+    Ok(())
+}
+
+fn verify_inventory(v: &Verifier<'_>, inv: Table, voffset: u16) -> DecodeResult<()> {
+    let inv_vectors = required(v.tables_vector_field(inv, voffset)?, "inventory")?;
+    for inv_vector in inv_vectors {
+        v.enum_field(
+            inv_vector,
+            protocol::InvVector::VT_TYPE_,
+            protocol::InvVectorType::DataResult as u8,
+        )?;
+        verify_hash(
+            v,
+            required(
+                v.table_field(inv_vector, protocol::InvVector::VT_HASH)?,
+                "InvVector.hash",
+            )?,
+        )?;
+    }
+
+    Ok(())
+}
@@ -28,6 +28,7 @@ fn builders_build_block() {
             hash_prev_block: Hash::SHA256([0; 32]),
         },
         hash_merkle_root: Hash::SHA256([0; 32]),
+        utxo_merkle_root: Hash::SHA256([0; 32]),
     };
     let signature = Signature::Secp256k1(Secp256k1Signature {
         r: [0; 32],
@@ -39,6 +40,7 @@ fn builders_build_block() {
             version: header.version,
             beacon: header.beacon,
             hash_merkle_root: header.hash_merkle_root,
+            utxo_merkle_root: header.utxo_merkle_root,
         },
         proof: LeadershipProof {
             block_sig: Some(signature),
@@ -46,6 +48,14 @@ fn builders_build_block() {
         },
     };
     let txns: Vec<Transaction> = vec![Transaction];
+    let mint = MintTransaction {
+        epoch: header_with_proof.block_header.beacon.checkpoint,
+        output: ValueTransferOutput {
+            pkh: [1; 20],
+            value: 500,
+            time_lock: 600,
+        },
+    };
 
     // Expected message
     let msg = Message {
@@ -53,12 +63,13 @@ fn builders_build_block() {
             header: header_with_proof.clone(),
             txn_count: txns.len() as u32,
             txns: txns.clone(),
+            mint: mint.clone(),
         }),
         magic: MAGIC,
     };
 
     // Check that the build_block function builds the expected message
-    assert_eq!(msg, Message::build_block(header_with_proof, txns));
+    assert_eq!(msg, Message::build_block(header_with_proof, txns, mint));
 }
 
 #[test]
@@ -81,14 +92,19 @@ fn builders_build_peers() {
         ip: IpAddress::Ipv4 { ip: 3232235777 },
         port: 8000,
     };
-    addresses.push(address);
+    addresses.push(PeerAddress {
+        address,
+        last_seen: 1_540_000_000,
+        signature: None,
+    });
     let msg = Message {
         kind: Command::Peers(Peers { peers: addresses }),
         magic: MAGIC,
     };
 
-    // Build vector of socket addresses
-    let sock_addresses: Vec<SocketAddr> = vec!["192.168.1.1:8000".parse().unwrap()];
+    // Build vector of socket addresses with their last seen timestamps and (missing) signatures
+    let sock_addresses: Vec<(SocketAddr, i64, Option<KeyedSignature>)> =
+        vec![("192.168.1.1:8000".parse().unwrap(), 1_540_000_000, None)];
 
     // Check that the build_peers function builds the expected message
     assert_eq!(msg, Message::build_peers(&sock_addresses));
@@ -141,13 +157,14 @@ fn builders_build_version() {
     let version_cmd = Command::Version(Version {
         version: PROTOCOL_VERSION,
         timestamp: 1234,
-        capabilities: CAPABILITIES,
+        capabilities: CAPABILITIES | CAPABILITY_NOISE,
         sender_address: sender_addr,
         receiver_address: receiver_addr,
         user_agent: USER_AGENT.to_string(),
         last_epoch: hardcoded_last_epoch,
         genesis: GENESIS,
         nonce: 1234,
+        consensus_constants_hash: Some(Hash::SHA256([1; 32])),
     });
     let msg = Message {
         kind: version_cmd,
@@ -157,8 +174,13 @@ fn builders_build_version() {
     // Build message
     let sender_sock_addr = "192.168.1.1:8000".parse().unwrap();
     let receiver_sock_addr = "192.168.1.2:8001".parse().unwrap();
-    let built_msg =
-        Message::build_version(sender_sock_addr, receiver_sock_addr, hardcoded_last_epoch);
+    let built_msg = Message::build_version(
+        sender_sock_addr,
+        receiver_sock_addr,
+        hardcoded_last_epoch,
+        CAPABILITIES | CAPABILITY_NOISE,
+        Some(Hash::SHA256([1; 32])),
+    );
 
     // Check that the build_version function builds the expected message
     assert_eq!(built_msg.magic, msg.magic);
@@ -173,13 +195,15 @@ fn builders_build_version() {
             last_epoch,
             genesis,
             nonce: _,
+            consensus_constants_hash,
         }) if *version == PROTOCOL_VERSION
-            && *capabilities == CAPABILITIES
+            && *capabilities == CAPABILITIES | CAPABILITY_NOISE
             && *sender_address == sender_addr
             && *receiver_address == receiver_addr
             && user_agent == USER_AGENT
             && *last_epoch == hardcoded_last_epoch
-            && *genesis == GENESIS =>
+            && *genesis == GENESIS
+            && *consensus_constants_hash == Some(Hash::SHA256([1; 32])) =>
         {
             assert!(true)
         }
@@ -0,0 +1,61 @@
+use witnet_data_structures::chain::*;
+
+fn block(block_sig: Option<Signature>) -> Block {
+    Block {
+        header: BlockHeaderWithProof {
+            block_header: BlockHeader {
+                version: 1,
+                beacon: CheckpointBeacon {
+                    checkpoint: 2,
+                    hash_prev_block: Hash::SHA256([3; 32]),
+                },
+                hash_merkle_root: Hash::SHA256([4; 32]),
+                utxo_merkle_root: Hash::SHA256([5; 32]),
+            },
+            proof: LeadershipProof {
+                block_sig,
+                influence: 6,
+            },
+        },
+        txn_count: 1,
+        txns: vec![Transaction],
+        mint: MintTransaction {
+            epoch: 2,
+            output: ValueTransferOutput {
+                pkh: [7; 20],
+                value: 500,
+                time_lock: 0,
+            },
+        },
+    }
+}
+
+#[test]
+fn block_hashed_bytes() {
+    // Header, missing signature, influence, transactions and mint
+    let bytes = block(None).hashed_bytes();
+    assert_eq!(
+        bytes.len(),
+        4 + 4 + 3 * 32 + 1 + 8 + 4 + 4 + 4 + 4 + 20 + 8 + 4
+    );
+    assert_eq!(&bytes[..8], &[0, 0, 0, 1, 0, 0, 0, 2]);
+    assert_eq!(
+        &bytes[bytes.len() - 12..bytes.len() - 4],
+        &500u64.to_be_bytes()
+    );
+
+    // The signature is part of the hashed bytes
+    let signature = Signature::Secp256k1(Secp256k1Signature {
+        r: [1; 32],
+        s: [2; 32],
+        v: 0,
+    });
+    let signed = block(Some(signature)).hashed_bytes();
+    assert_eq!(signed.len(), bytes.len() + 65);
+    assert_ne!(signed, bytes);
+
+    // Every field is part of the hashed bytes
+    let mut other = block(None);
+    other.header.proof.influence = 7;
+    assert_ne!(other.hashed_bytes(), bytes);
+}
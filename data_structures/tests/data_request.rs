@@ -0,0 +1,124 @@
+use witnet_data_structures::chain::*;
+use witnet_data_structures::data_request::*;
+use witnet_data_structures::error::DataRequestErrorKind;
+
+fn data_request(value: u64, witnesses: u16, collateral: u64) -> DataRequestOutput {
+    DataRequestOutput {
+        data_request: RADRequest {
+            not_before: 0,
+            retrieve: vec![],
+            aggregate: RADAggregate { script: vec![] },
+            consensus: RADConsensus { script: vec![] },
+            deliver: vec![],
+        },
+        value,
+        witnesses,
+        commit_fee: 2,
+        reveal_fee: 3,
+        tally_fee: 4,
+        collateral,
+    }
+}
+
+fn error_kind(data_request: &DataRequestOutput) -> DataRequestErrorKind {
+    *value_split(data_request).unwrap_err().inner().kind()
+}
+
+#[test]
+fn data_request_value_split() {
+    // Fees: 3 * 2 + 3 * 3 + 4 = 19, rewards: 1000 - 19 = 981
+    assert_eq!(
+        value_split(&data_request(1000, 3, 100)).unwrap(),
+        ValueSplit {
+            commit_fees: 6,
+            reveal_fees: 9,
+            tally_fee: 4,
+            rewards: 981,
+            witness_reward: 327,
+        }
+    );
+    assert_eq!(value_split(&data_request(1000, 3, 100)).unwrap().fees(), 19);
+}
+
+#[test]
+fn data_request_value_must_cover_fees_and_rewards() {
+    // The fees plus a reward of 1 for every witness
+    assert!(value_split(&data_request(22, 3, 0)).is_ok());
+    assert_eq!(
+        error_kind(&data_request(21, 3, 0)),
+        DataRequestErrorKind::InsufficientValue
+    );
+    assert_eq!(
+        error_kind(&data_request(1000, 0, 0)),
+        DataRequestErrorKind::NoWitnesses
+    );
+}
+
+#[test]
+fn data_request_collateral_ratio() {
+    // Reward of every witness: (19 + 3 * 10 - 19) / 3 = 10
+    assert!(value_split(&data_request(49, 3, 10 * MAX_COLLATERAL_RATIO)).is_ok());
+    assert_eq!(
+        error_kind(&data_request(49, 3, 10 * MAX_COLLATERAL_RATIO + 1)),
+        DataRequestErrorKind::CollateralTooHigh
+    );
+}
+
+#[test]
+fn data_request_overflow() {
+    assert_eq!(
+        error_kind(&data_request(u64::max_value(), 2, u64::max_value())),
+        DataRequestErrorKind::ValueOverflow
+    );
+
+    let mut overflowing_fees = data_request(u64::max_value(), 2, 0);
+    overflowing_fees.commit_fee = u64::max_value();
+    assert_eq!(
+        error_kind(&overflowing_fees),
+        DataRequestErrorKind::ValueOverflow
+    );
+}
+
+#[test]
+fn data_request_min_value() {
+    for collateral in &[0, 1, 125, 126, 1000] {
+        let value = min_value(3, 2, 3, 4, *collateral).unwrap();
+        assert!(value_split(&data_request(value, 3, *collateral)).is_ok());
+        assert!(value_split(&data_request(value - 1, 3, *collateral)).is_err());
+    }
+
+    assert_eq!(min_value(3, u64::max_value(), 3, 4, 0), None);
+}
+
+#[test]
+fn data_request_hashed_bytes() {
+    let retrieve = |url: &str, script: Vec<u8>| RADRetrieve {
+        kind: RADType::HttpGet,
+        url: url.to_string(),
+        headers: vec![],
+        script,
+    };
+    let with_retrieve = |retrieve| {
+        let mut data_request = data_request(1000, 3, 100);
+        data_request.data_request.retrieve = vec![retrieve];
+        data_request
+    };
+
+    // Empty request: not_before, 3 empty lists and 2 empty scripts, then the 6 numbers
+    let bytes = data_request(1000, 3, 100).hashed_bytes();
+    assert_eq!(bytes.len(), 8 + 5 * 4 + 8 + 2 + 4 * 8);
+    assert_eq!(&bytes[bytes.len() - 8..], &100u64.to_be_bytes());
+
+    // Byte strings are prefixed by their length, so moving a byte from the URL to the script
+    // changes the hashed bytes
+    assert_ne!(
+        with_retrieve(retrieve("http://a", vec![1])).hashed_bytes(),
+        with_retrieve(retrieve("http://", vec![b'a', 1])).hashed_bytes()
+    );
+    let mut https = retrieve("http://a", vec![1]);
+    https.kind = RADType::HttpsGet;
+    assert_ne!(
+        with_retrieve(retrieve("http://a", vec![1])).hashed_bytes(),
+        with_retrieve(https).hashed_bytes()
+    );
+}
@@ -0,0 +1,108 @@
+use witnet_data_structures::chain::*;
+use witnet_data_structures::emission::*;
+
+fn consensus_constants(initial_block_reward: u64, halving_period: u32) -> ConsensusConstants {
+    ConsensusConstants {
+        checkpoint_zero_timestamp: 0,
+        checkpoints_period: 90,
+        genesis_hash: Hash::SHA256([0; 32]),
+        reputation_demurrage: 0.0,
+        reputation_punishment: 0.0,
+        trusted_checkpoints: vec![],
+        initial_block_reward,
+        halving_period,
+        reward_maturity: 0,
+        genesis_allocation: vec![],
+    }
+}
+
+#[test]
+fn emission_block_reward_halvings() {
+    let constants = consensus_constants(100, 10);
+
+    // The genesis block issues no reward
+    assert_eq!(block_reward(0, &constants), 0);
+    assert_eq!(block_reward(1, &constants), 100);
+    assert_eq!(block_reward(9, &constants), 100);
+    assert_eq!(block_reward(10, &constants), 50);
+    assert_eq!(block_reward(25, &constants), 25);
+    assert_eq!(block_reward(60, &constants), 1);
+    assert_eq!(block_reward(70, &constants), 0);
+    assert_eq!(block_reward(Epoch::max_value(), &constants), 0);
+}
+
+#[test]
+fn emission_curve_until_zero_reward() {
+    let constants = consensus_constants(100, 10);
+
+    assert_eq!(
+        emission_curve(0, &constants)[..2],
+        [
+            EmissionPeriod {
+                start: 1,
+                end: 9,
+                reward: 100
+            },
+            EmissionPeriod {
+                start: 10,
+                end: 19,
+                reward: 50
+            }
+        ]
+    );
+    // The curve starts at the given epoch and ends with the last reward
+    let curve = emission_curve(15, &constants);
+    assert_eq!(
+        curve[0],
+        EmissionPeriod {
+            start: 15,
+            end: 19,
+            reward: 50
+        }
+    );
+    assert_eq!(
+        curve.last(),
+        Some(&EmissionPeriod {
+            start: 60,
+            end: 69,
+            reward: 1
+        })
+    );
+    assert!(emission_curve(70, &constants).is_empty());
+}
+
+#[test]
+fn emission_total_supply() {
+    let constants = consensus_constants(100, 10);
+
+    assert_eq!(emission_until(0, &constants), 0);
+    assert_eq!(emission_until(9, &constants), 900);
+    assert_eq!(emission_until(12, &constants), 1050);
+    // 900 + 500 + 250 + 120 + 60 + 30 + 10
+    assert_eq!(total_emission(&constants), 1870);
+
+    // The default schedule does not overflow
+    let constants = consensus_constants(DEFAULT_INITIAL_BLOCK_REWARD, DEFAULT_HALVING_PERIOD);
+    assert_eq!(total_emission(&constants), 1_749_999_499_977_250_000);
+}
+
+#[test]
+fn emission_rewards_locked_until_maturity() {
+    let mut constants = consensus_constants(100, 10);
+    constants.reward_maturity = 5;
+
+    let output = ValueTransferOutput {
+        pkh: [0; 20],
+        value: block_reward(12, &constants),
+        time_lock: reward_time_lock(12, &constants),
+    };
+    assert_eq!(output.time_lock, 17);
+    assert!(!output.is_spendable(16));
+    assert!(output.is_spendable(17));
+
+    // The time lock saturates instead of overflowing
+    assert_eq!(
+        reward_time_lock(Epoch::max_value(), &constants),
+        Epoch::max_value()
+    );
+}
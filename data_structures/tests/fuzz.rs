@@ -0,0 +1,225 @@
+use proptest::prelude::*;
+use witnet_data_structures::{
+    serializers::TryFrom,
+    {chain::*, types::*},
+};
+
+fn arb_hash() -> impl Strategy<Value = Hash> {
+    prop::array::uniform32(any::<u8>()).prop_map(Hash::SHA256)
+}
+
+fn arb_signature() -> impl Strategy<Value = Signature> {
+    (
+        prop::array::uniform32(any::<u8>()),
+        prop::array::uniform32(any::<u8>()),
+        any::<u8>(),
+    )
+        .prop_map(|(r, s, v)| Signature::Secp256k1(Secp256k1Signature { r, s, v }))
+}
+
+fn arb_address() -> impl Strategy<Value = Address> {
+    let ipv4 = any::<u32>().prop_map(|ip| IpAddress::Ipv4 { ip });
+    let ipv6 = any::<[u32; 4]>().prop_map(|ip| IpAddress::Ipv6 {
+        ip0: ip[0],
+        ip1: ip[1],
+        ip2: ip[2],
+        ip3: ip[3],
+    });
+
+    (prop_oneof![ipv4, ipv6], any::<u16>()).prop_map(|(ip, port)| Address { ip, port })
+}
+
+fn arb_peer_address() -> impl Strategy<Value = PeerAddress> {
+    (
+        arb_address(),
+        any::<i64>(),
+        prop::option::of(arb_signature()),
+    )
+        .prop_map(|(address, last_seen, signature)| PeerAddress {
+            address,
+            last_seen,
+            signature,
+        })
+}
+
+fn arb_inv_vector() -> impl Strategy<Value = InvVector> {
+    prop_oneof![
+        arb_hash().prop_map(InvVector::Error),
+        arb_hash().prop_map(InvVector::Tx),
+        arb_hash().prop_map(InvVector::Block),
+        arb_hash().prop_map(InvVector::DataRequest),
+        arb_hash().prop_map(InvVector::DataResult),
+    ]
+}
+
+fn arb_beacon() -> impl Strategy<Value = CheckpointBeacon> {
+    (any::<u32>(), arb_hash()).prop_map(|(checkpoint, hash_prev_block)| CheckpointBeacon {
+        checkpoint,
+        hash_prev_block,
+    })
+}
+
+fn arb_mint() -> impl Strategy<Value = MintTransaction> {
+    (any::<u32>(), any::<[u8; 20]>(), any::<u64>(), any::<u32>()).prop_map(
+        |(epoch, pkh, value, time_lock)| MintTransaction {
+            epoch,
+            output: ValueTransferOutput {
+                pkh,
+                value,
+                time_lock,
+            },
+        },
+    )
+}
+
+fn arb_block() -> impl Strategy<Value = Block> {
+    (
+        any::<u32>(),
+        arb_beacon(),
+        arb_hash(),
+        arb_hash(),
+        prop::option::of(arb_signature()),
+        any::<u64>(),
+        0..4usize,
+        arb_mint(),
+    )
+        .prop_map(
+            |(
+                version,
+                beacon,
+                hash_merkle_root,
+                utxo_merkle_root,
+                block_sig,
+                influence,
+                txn_count,
+                mint,
+            )| {
+                Block {
+                    header: BlockHeaderWithProof {
+                        block_header: BlockHeader {
+                            version,
+                            beacon,
+                            hash_merkle_root,
+                            utxo_merkle_root,
+                        },
+                        proof: LeadershipProof {
+                            block_sig,
+                            influence,
+                        },
+                    },
+                    txn_count: txn_count as u32,
+                    txns: vec![Transaction; txn_count],
+                    mint,
+                }
+            },
+        )
+}
+
+fn arb_version() -> impl Strategy<Value = Version> {
+    (
+        (any::<u32>(), any::<i64>(), any::<u64>()),
+        (arb_address(), arb_address(), ".*"),
+        (any::<u32>(), any::<u64>(), any::<u64>()),
+        prop::option::of(arb_hash()),
+    )
+        .prop_map(
+            |(
+                (version, timestamp, capabilities),
+                (sender_address, receiver_address, user_agent),
+                (last_epoch, genesis, nonce),
+                consensus_constants_hash,
+            )| Version {
+                version,
+                timestamp,
+                capabilities,
+                sender_address,
+                receiver_address,
+                user_agent,
+                last_epoch,
+                genesis,
+                nonce,
+                consensus_constants_hash,
+            },
+        )
+}
+
+fn arb_command() -> impl Strategy<Value = Command> {
+    prop_oneof![
+        Just(Command::GetPeers(GetPeers)),
+        prop::collection::vec(arb_peer_address(), 0..8)
+            .prop_map(|peers| Command::Peers(Peers { peers })),
+        any::<u64>().prop_map(|nonce| Command::Ping(Ping { nonce })),
+        any::<u64>().prop_map(|nonce| Command::Pong(Pong { nonce })),
+        Just(Command::Verack(Verack)),
+        arb_version().prop_map(Command::Version),
+        arb_block().prop_map(Command::Block),
+        prop::collection::vec(arb_inv_vector(), 0..8)
+            .prop_map(|inventory| Command::Inv(Inv { inventory })),
+        prop::collection::vec(arb_inv_vector(), 0..8)
+            .prop_map(|inventory| Command::GetData(GetData { inventory })),
+        arb_beacon().prop_map(|highest_block_checkpoint| Command::GetBlocks(GetBlocks {
+            highest_block_checkpoint
+        })),
+        arb_beacon().prop_map(|highest_block_checkpoint| Command::LastBeacon(LastBeacon {
+            highest_block_checkpoint
+        })),
+    ]
+}
+
+fn arb_message() -> impl Strategy<Value = Message> {
+    (arb_command(), any::<u16>()).prop_map(|(kind, magic)| Message { kind, magic })
+}
+
+/// Encoded message along with a list of (position, value) mutations to apply to it
+fn arb_mutated_message() -> impl Strategy<Value = (Vec<u8>, Vec<(usize, u8)>)> {
+    (
+        arb_message(),
+        prop::collection::vec((any::<usize>(), any::<u8>()), 1..8),
+    )
+        .prop_map(|(msg, mutations)| (msg.into(), mutations))
+}
+
+proptest! {
+    #[test]
+    fn fuzz_decode_arbitrary_bytes(bytes in prop::collection::vec(any::<u8>(), 0..1024)) {
+        // Decoding must never panic, whatever the input
+        let _ = Message::try_from(bytes);
+    }
+
+    #[test]
+    fn fuzz_decode_mutated_message((mut bytes, mutations) in arb_mutated_message()) {
+        let len = bytes.len();
+        for (pos, value) in mutations {
+            bytes[pos % len] = value;
+        }
+
+        // If the mutated message is still valid, it must survive a round trip
+        if let Ok(msg) = Message::try_from(bytes) {
+            let encoded: Vec<u8> = msg.clone().into();
+            prop_assert_eq!(Message::try_from(encoded).unwrap(), msg);
+        }
+    }
+
+    #[test]
+    fn fuzz_decode_truncated_message(msg in arb_message(), cut in any::<usize>()) {
+        let bytes: Vec<u8> = msg.into();
+        let len = cut % bytes.len();
+
+        let _ = Message::try_from(bytes[..len].to_vec());
+    }
+
+    #[test]
+    fn fuzz_encode_decode_roundtrip(msg in arb_message()) {
+        let bytes: Vec<u8> = msg.clone().into();
+
+        prop_assert_eq!(Message::try_from(bytes).unwrap(), msg);
+    }
+
+    #[test]
+    fn fuzz_decode_encode_roundtrip(msg in arb_message()) {
+        let bytes: Vec<u8> = msg.into();
+        let encoded: Vec<u8> = Message::try_from(bytes.clone()).unwrap().into();
+
+        prop_assert_eq!(encoded, bytes);
+    }
+}
@@ -1,8 +1,23 @@
 /// Builders library tests
 pub mod builders;
 
+/// Chain data structures library tests
+pub mod chain;
+
+/// Data request economics library tests
+pub mod data_request;
+
 /// Flatbuffers library tests
 pub mod flatbuffers;
 
 /// Serializers library tests
 pub mod serializers;
+
+/// Signing requests library tests
+pub mod signing;
+
+/// Verifier library tests
+pub mod verifier;
+
+/// Codec fuzzing tests
+pub mod fuzz;
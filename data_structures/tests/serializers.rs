@@ -49,6 +49,52 @@ fn message_get_blocks_to_bytes() {
     assert_eq!(result, expected_buf);
 }
 
+#[test]
+fn message_last_beacon_from_bytes() {
+    let buff: Vec<u8> = [
+        16, 0, 0, 0, 0, 0, 10, 0, 14, 0, 0, 0, 7, 0, 8, 0, 10, 0, 0, 0, 0, 0, 0, 11, 12, 0, 0, 0,
+        0, 0, 6, 0, 8, 0, 4, 0, 6, 0, 0, 0, 4, 0, 0, 0, 248, 255, 255, 255, 12, 0, 0, 0, 8, 0, 8,
+        0, 0, 0, 4, 0, 8, 0, 0, 0, 4, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ]
+    .to_vec();
+
+    let expected_msg = Message {
+        kind: Command::LastBeacon(LastBeacon {
+            highest_block_checkpoint: CheckpointBeacon {
+                hash_prev_block: Hash::SHA256([0; 32]),
+                checkpoint: 0,
+            },
+        }),
+        magic: 0,
+    };
+
+    assert_eq!(Message::try_from(buff).unwrap(), expected_msg);
+}
+
+#[test]
+fn message_last_beacon_to_bytes() {
+    let msg = Message {
+        kind: Command::LastBeacon(LastBeacon {
+            highest_block_checkpoint: CheckpointBeacon {
+                hash_prev_block: Hash::SHA256([0; 32]),
+                checkpoint: 0,
+            },
+        }),
+        magic: 0,
+    };
+    let expected_buf: Vec<u8> = [
+        16, 0, 0, 0, 0, 0, 10, 0, 14, 0, 0, 0, 7, 0, 8, 0, 10, 0, 0, 0, 0, 0, 0, 11, 12, 0, 0, 0,
+        0, 0, 6, 0, 8, 0, 4, 0, 6, 0, 0, 0, 4, 0, 0, 0, 248, 255, 255, 255, 12, 0, 0, 0, 8, 0, 8,
+        0, 0, 0, 4, 0, 8, 0, 0, 0, 4, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ]
+    .to_vec();
+    let result: Vec<u8> = msg.into();
+
+    assert_eq!(result, expected_buf);
+}
+
 #[test]
 fn message_ping_to_bytes() {
     let msg = Message {
@@ -184,7 +230,11 @@ fn message_get_peer_to_bytes() {
         ip: IpAddress::Ipv4 { ip: 3232235777 },
         port: 8000,
     };
-    addresses.push(address);
+    addresses.push(PeerAddress {
+        address,
+        last_seen: 0,
+        signature: None,
+    });
     let msg = Message {
         kind: Command::Peers(Peers { peers: addresses }),
         magic: 0,
@@ -216,7 +266,11 @@ fn message_peer_from_bytes() {
     };
     let mut addresses = Vec::new();
 
-    addresses.push(address);
+    addresses.push(PeerAddress {
+        address,
+        last_seen: 0,
+        signature: None,
+    });
 
     let expected_msg = Message {
         kind: Command::Peers(Peers { peers: addresses }),
@@ -243,8 +297,23 @@ fn message_get_peer_encode_decode() {
         port: 8000,
     };
 
-    addresses.push(address_ipv4);
-    addresses.push(address_ipv6);
+    addresses.push(PeerAddress {
+        address: address_ipv4,
+        last_seen: 0,
+        signature: None,
+    });
+    addresses.push(PeerAddress {
+        address: address_ipv6,
+        last_seen: 1_540_000_000,
+        signature: Some(KeyedSignature {
+            public_key: vec![2; 33],
+            signature: Secp256k1Signature {
+                r: [1; 32],
+                s: [2; 32],
+                v: 3,
+            },
+        }),
+    });
 
     let msg = Message {
         kind: Command::Peers(Peers { peers: addresses }),
@@ -320,6 +389,7 @@ fn message_version_to_bytes() {
             last_epoch: 8,
             genesis: 2,
             nonce: 1,
+            consensus_constants_hash: None,
         }),
         magic: 1,
     };
@@ -359,6 +429,7 @@ fn message_version_from_bytes() {
             last_epoch: 8,
             genesis: 2,
             nonce: 1,
+            consensus_constants_hash: None,
         }),
         magic: 1,
     };
@@ -397,6 +468,7 @@ fn message_version_encode_decode() {
             last_epoch: 8,
             genesis: 2,
             nonce: 1,
+            consensus_constants_hash: Some(Hash::SHA256([3; 32])),
         }),
         magic: 1,
     };
@@ -415,6 +487,7 @@ fn message_block_to_bytes() {
             hash_prev_block: Hash::SHA256([0; 32]),
         },
         hash_merkle_root: Hash::SHA256([0; 32]),
+        utxo_merkle_root: Hash::SHA256([0; 32]),
     };
     let signature = Signature::Secp256k1(Secp256k1Signature {
         r: [0; 32],
@@ -426,6 +499,7 @@ fn message_block_to_bytes() {
             version: header.version,
             beacon: header.beacon,
             hash_merkle_root: header.hash_merkle_root,
+            utxo_merkle_root: header.utxo_merkle_root,
         },
         proof: LeadershipProof {
             block_sig: Some(signature),
@@ -433,28 +507,41 @@ fn message_block_to_bytes() {
         },
     };
     let txns: Vec<Transaction> = vec![Transaction];
+    let mint = MintTransaction {
+        epoch: 0,
+        output: ValueTransferOutput {
+            pkh: [1; 20],
+            value: 500,
+            time_lock: 0,
+        },
+    };
     let msg = Message {
         kind: Command::Block(Block {
             header: header_with_proof.clone(),
             txn_count: txns.len() as u32,
             txns: txns.clone(),
+            mint: mint.clone(),
         }),
         magic: 1,
     };
 
     let expected_buf: Vec<u8> = [
-        16, 0, 0, 0, 0, 0, 10, 0, 14, 0, 6, 0, 5, 0, 8, 0, 10, 0, 0, 0, 0, 7, 1, 0, 16, 0, 0, 0, 0,
-        0, 10, 0, 16, 0, 4, 0, 8, 0, 12, 0, 10, 0, 0, 0, 40, 0, 0, 0, 1, 0, 0, 0, 4, 0, 0, 0, 1, 0,
-        0, 0, 8, 0, 0, 0, 4, 0, 4, 0, 4, 0, 0, 0, 12, 0, 16, 0, 0, 0, 4, 0, 8, 0, 12, 0, 12, 0, 0,
-        0, 172, 0, 0, 0, 124, 0, 0, 0, 12, 0, 0, 0, 8, 0, 12, 0, 7, 0, 8, 0, 8, 0, 0, 0, 0, 0, 0,
-        1, 12, 0, 0, 0, 8, 0, 12, 0, 4, 0, 8, 0, 8, 0, 0, 0, 48, 0, 0, 0, 4, 0, 0, 0, 33, 0, 0, 0,
+        20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10, 0, 12, 0, 6, 0, 5, 0, 8, 0, 10, 0, 0, 0, 0, 7, 1, 0, 16,
+        0, 0, 0, 12, 0, 20, 0, 4, 0, 8, 0, 12, 0, 16, 0, 12, 0, 0, 0, 108, 0, 0, 0, 1, 0, 0, 0, 68,
+        0, 0, 0, 4, 0, 0, 0, 188, 254, 255, 255, 12, 0, 0, 0, 8, 0, 20, 0, 4, 0, 8, 0, 8, 0, 0, 0,
+        16, 0, 0, 0, 244, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 8, 0, 0, 0, 4, 0, 6, 0, 4, 0, 0, 0, 0, 0, 14,
+        0, 20, 0, 0, 0, 4, 0, 8, 0, 12, 0, 16, 0, 14, 0, 0, 0, 220, 0, 0, 0, 172, 0, 0, 0, 16, 0,
+        0, 0, 120, 0, 0, 0, 8, 0, 12, 0, 7, 0, 8, 0, 8, 0, 0, 0, 0, 0, 0, 1, 12, 0, 0, 0, 8, 0, 12,
+        0, 4, 0, 8, 0, 8, 0, 0, 0, 48, 0, 0, 0, 4, 0, 0, 0, 33, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0,
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 204, 255, 255, 255, 4, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 248,
-        255, 255, 255, 12, 0, 0, 0, 8, 0, 8, 0, 0, 0, 4, 0, 8, 0, 0, 0, 4, 0, 0, 0, 32, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0,
+        0, 0, 0, 160, 255, 255, 255, 4, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 204, 255, 255, 255, 4, 0, 0, 0,
+        32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 248, 255, 255, 255, 12, 0, 0, 0, 8, 0, 8, 0, 0, 0, 4, 0, 8, 0, 0, 0, 4,
+        0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0,
     ]
     .to_vec();
     let result: Vec<u8> = msg.into();
@@ -464,18 +551,22 @@ fn message_block_to_bytes() {
 #[test]
 fn message_block_from_bytes() {
     let buf: Vec<u8> = [
-        16, 0, 0, 0, 0, 0, 10, 0, 14, 0, 6, 0, 5, 0, 8, 0, 10, 0, 0, 0, 0, 7, 1, 0, 16, 0, 0, 0, 0,
-        0, 10, 0, 16, 0, 4, 0, 8, 0, 12, 0, 10, 0, 0, 0, 40, 0, 0, 0, 1, 0, 0, 0, 4, 0, 0, 0, 1, 0,
-        0, 0, 8, 0, 0, 0, 4, 0, 4, 0, 4, 0, 0, 0, 12, 0, 16, 0, 0, 0, 4, 0, 8, 0, 12, 0, 12, 0, 0,
-        0, 172, 0, 0, 0, 124, 0, 0, 0, 12, 0, 0, 0, 8, 0, 12, 0, 7, 0, 8, 0, 8, 0, 0, 0, 0, 0, 0,
-        1, 12, 0, 0, 0, 8, 0, 12, 0, 4, 0, 8, 0, 8, 0, 0, 0, 48, 0, 0, 0, 4, 0, 0, 0, 33, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 204, 255, 255, 255, 4, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 248,
-        255, 255, 255, 12, 0, 0, 0, 8, 0, 8, 0, 0, 0, 4, 0, 8, 0, 0, 0, 4, 0, 0, 0, 32, 0, 0, 0, 0,
+        20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10, 0, 12, 0, 6, 0, 5, 0, 8, 0, 10, 0, 0, 0, 0, 7, 1, 0, 16,
+        0, 0, 0, 12, 0, 20, 0, 4, 0, 8, 0, 12, 0, 16, 0, 12, 0, 0, 0, 108, 0, 0, 0, 1, 0, 0, 0, 68,
+        0, 0, 0, 4, 0, 0, 0, 188, 254, 255, 255, 12, 0, 0, 0, 8, 0, 20, 0, 4, 0, 8, 0, 8, 0, 0, 0,
+        16, 0, 0, 0, 244, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 8, 0, 0, 0, 4, 0, 6, 0, 4, 0, 0, 0, 0, 0, 14,
+        0, 20, 0, 0, 0, 4, 0, 8, 0, 12, 0, 16, 0, 14, 0, 0, 0, 220, 0, 0, 0, 172, 0, 0, 0, 16, 0,
+        0, 0, 120, 0, 0, 0, 8, 0, 12, 0, 7, 0, 8, 0, 8, 0, 0, 0, 0, 0, 0, 1, 12, 0, 0, 0, 8, 0, 12,
+        0, 4, 0, 8, 0, 8, 0, 0, 0, 48, 0, 0, 0, 4, 0, 0, 0, 33, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0,
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0,
+        0, 0, 0, 160, 255, 255, 255, 4, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 204, 255, 255, 255, 4, 0, 0, 0,
+        32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 248, 255, 255, 255, 12, 0, 0, 0, 8, 0, 8, 0, 0, 0, 4, 0, 8, 0, 0, 0, 4,
+        0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0,
     ]
     .to_vec();
 
@@ -486,6 +577,7 @@ fn message_block_from_bytes() {
             hash_prev_block: Hash::SHA256([0; 32]),
         },
         hash_merkle_root: Hash::SHA256([0; 32]),
+        utxo_merkle_root: Hash::SHA256([0; 32]),
     };
     let signature = Signature::Secp256k1(Secp256k1Signature {
         r: [0; 32],
@@ -497,6 +589,7 @@ fn message_block_from_bytes() {
             version: header.version,
             beacon: header.beacon,
             hash_merkle_root: header.hash_merkle_root,
+            utxo_merkle_root: header.utxo_merkle_root,
         },
         proof: LeadershipProof {
             block_sig: Some(signature),
@@ -504,11 +597,20 @@ fn message_block_from_bytes() {
         },
     };
     let txns: Vec<Transaction> = vec![Transaction];
+    let mint = MintTransaction {
+        epoch: 0,
+        output: ValueTransferOutput {
+            pkh: [1; 20],
+            value: 500,
+            time_lock: 0,
+        },
+    };
     let expected_msg = Message {
         kind: Command::Block(Block {
             header: header_with_proof.clone(),
             txn_count: txns.len() as u32,
             txns: txns.clone(),
+            mint: mint.clone(),
         }),
         magic: 1,
     };
@@ -525,6 +627,7 @@ fn message_block_encode_decode() {
             hash_prev_block: Hash::SHA256([0; 32]),
         },
         hash_merkle_root: Hash::SHA256([0; 32]),
+        utxo_merkle_root: Hash::SHA256([0; 32]),
     };
     let signature = Signature::Secp256k1(Secp256k1Signature {
         r: [0; 32],
@@ -536,6 +639,7 @@ fn message_block_encode_decode() {
             version: header.version,
             beacon: header.beacon,
             hash_merkle_root: header.hash_merkle_root,
+            utxo_merkle_root: header.utxo_merkle_root,
         },
         proof: LeadershipProof {
             block_sig: Some(signature),
@@ -543,11 +647,20 @@ fn message_block_encode_decode() {
         },
     };
     let txns: Vec<Transaction> = vec![Transaction];
+    let mint = MintTransaction {
+        epoch: 0,
+        output: ValueTransferOutput {
+            pkh: [1; 20],
+            value: 500,
+            time_lock: 100,
+        },
+    };
     let msg = Message {
         kind: Command::Block(Block {
             header: header_with_proof.clone(),
             txn_count: txns.len() as u32,
             txns: txns.clone(),
+            mint: mint.clone(),
         }),
         magic: 1,
     };
@@ -0,0 +1,84 @@
+use witnet_data_structures::{
+    chain::{Hash, KeyedSignature, MultiSigCondition, Secp256k1Signature},
+    error::DecodeErrorKind,
+    signing::*,
+};
+
+fn request() -> SigningRequest {
+    SigningRequest {
+        key_path: vec![0x8000_002c, 0x8000_1337, 0x8000_0000, 0, 5],
+        hash: Hash::SHA256([7; 32]),
+        condition: MultiSigCondition {
+            required: 2,
+            pkhs: vec![[1; 20], [2; 20], [3; 20]],
+        },
+    }
+}
+
+fn decode_error_kind(bytes: &[u8]) -> DecodeErrorKind {
+    match SigningRequest::decode(bytes) {
+        Ok(request) => panic!("Unexpected signing request {:?}", request),
+        Err(e) => *e.inner().kind(),
+    }
+}
+
+#[test]
+fn signing_request_encoding() {
+    let bytes = request().encode();
+
+    let mut expected = vec![SIGNING_FORMAT_VERSION, 5];
+    expected.extend_from_slice(&[0x80, 0x00, 0x00, 0x2c]);
+    expected.extend_from_slice(&[0x80, 0x00, 0x13, 0x37]);
+    expected.extend_from_slice(&[0x80, 0x00, 0x00, 0x00]);
+    expected.extend_from_slice(&[0, 0, 0, 0]);
+    expected.extend_from_slice(&[0, 0, 0, 5]);
+    expected.extend_from_slice(&[7; 32]);
+    expected.extend_from_slice(&[2, 3]);
+    expected.extend_from_slice(&[1; 20]);
+    expected.extend_from_slice(&[2; 20]);
+    expected.extend_from_slice(&[3; 20]);
+
+    assert_eq!(bytes, expected);
+    assert_eq!(SigningRequest::decode(&bytes).unwrap(), request());
+}
+
+#[test]
+fn signing_request_invalid() {
+    let mut bytes = request().encode();
+
+    // Unknown version
+    bytes[0] = SIGNING_FORMAT_VERSION + 1;
+    assert_eq!(
+        decode_error_kind(&bytes),
+        DecodeErrorKind::UnsupportedVersion
+    );
+    bytes[0] = SIGNING_FORMAT_VERSION;
+
+    // Truncated request
+    assert_eq!(
+        decode_error_kind(&bytes[..bytes.len() - 1]),
+        DecodeErrorKind::InvalidLength
+    );
+    assert_eq!(decode_error_kind(&[]), DecodeErrorKind::InvalidLength);
+
+    // Trailing bytes
+    bytes.push(0);
+    assert_eq!(decode_error_kind(&bytes), DecodeErrorKind::InvalidLength);
+}
+
+#[test]
+fn signing_response_encoding() {
+    let keyed = KeyedSignature {
+        public_key: vec![2; PUBLIC_KEY_LENGTH],
+        signature: Secp256k1Signature {
+            r: [3; 32],
+            s: [4; 32],
+            v: 0,
+        },
+    };
+    let bytes = encode_signing_response(&keyed);
+
+    assert_eq!(bytes.len(), SIGNING_RESPONSE_LENGTH);
+    assert_eq!(decode_signing_response(&bytes).unwrap(), keyed);
+    assert!(decode_signing_response(&bytes[1..]).is_err());
+}
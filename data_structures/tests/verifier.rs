@@ -0,0 +1,170 @@
+use witnet_data_structures::{
+    error::DecodeErrorKind,
+    flatbuffers::protocol_generated::protocol,
+    serializers::TryFrom,
+    types::{Command, Message, Ping},
+};
+
+/// Ping message with nonce 7 and magic 0
+const PING: [u8; 48] = [
+    16, 0, 0, 0, 0, 0, 10, 0, 14, 0, 0, 0, 7, 0, 8, 0, 10, 0, 0, 0, 0, 0, 0, 5, 12, 0, 0, 0, 0, 0,
+    6, 0, 12, 0, 4, 0, 6, 0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0,
+];
+
+/// Position of the command type in the ping message
+const PING_COMMAND_TYPE_POS: usize = 23;
+
+fn decode_error_kind(buf: Vec<u8>) -> DecodeErrorKind {
+    match Message::try_from(buf) {
+        Ok(msg) => panic!("Unexpected message {:?}", msg),
+        Err(e) => *e.inner().kind(),
+    }
+}
+
+#[test]
+fn verifier_valid_ping() {
+    let expected_msg = Message {
+        kind: Command::Ping(Ping { nonce: 7 }),
+        magic: 0,
+    };
+
+    assert_eq!(Message::try_from(PING.to_vec()).unwrap(), expected_msg);
+}
+
+#[test]
+fn verifier_empty_buffer() {
+    assert_eq!(decode_error_kind(vec![]), DecodeErrorKind::Malformed);
+}
+
+#[test]
+fn verifier_truncated_message() {
+    for len in 0..PING.len() {
+        assert_eq!(
+            decode_error_kind(PING[..len].to_vec()),
+            DecodeErrorKind::Malformed
+        );
+    }
+}
+
+#[test]
+fn verifier_out_of_bounds_root() {
+    let mut buf = PING.to_vec();
+    buf[0] = 0xff;
+
+    assert_eq!(decode_error_kind(buf), DecodeErrorKind::Malformed);
+}
+
+#[test]
+fn verifier_unknown_command_type() {
+    let mut buf = PING.to_vec();
+    buf[PING_COMMAND_TYPE_POS] = 0xff;
+
+    assert_eq!(decode_error_kind(buf), DecodeErrorKind::InvalidEnumValue);
+}
+
+#[test]
+fn verifier_none_command_type() {
+    let mut buf = PING.to_vec();
+    buf[PING_COMMAND_TYPE_POS] = 0;
+
+    assert_eq!(decode_error_kind(buf), DecodeErrorKind::UnknownCommand);
+}
+
+#[test]
+fn verifier_invalid_hash_length() {
+    // GetBlocks message whose hash has 31 bytes instead of 32
+    let mut builder = flatbuffers::FlatBufferBuilder::new_with_capacity(1024);
+    let bytes = builder.create_vector(&[0u8; 31]);
+    let hash = protocol::Hash::create(
+        &mut builder,
+        &protocol::HashArgs {
+            type_: protocol::HashType::SHA256,
+            bytes: Some(bytes),
+        },
+    );
+    let beacon = protocol::CheckpointBeacon::create(
+        &mut builder,
+        &protocol::CheckpointBeaconArgs {
+            checkpoint: 0,
+            hash_prev_block: Some(hash),
+        },
+    );
+    let get_blocks = protocol::GetBlocks::create(
+        &mut builder,
+        &protocol::GetBlocksArgs {
+            highest_block_checkpoint: Some(beacon),
+        },
+    );
+    let message = protocol::Message::create(
+        &mut builder,
+        &protocol::MessageArgs {
+            magic: 0,
+            command_type: protocol::Command::GetBlocks,
+            command: Some(get_blocks.as_union_value()),
+        },
+    );
+    builder.finish(message, None);
+    let buf = builder.finished_data().to_vec();
+
+    assert_eq!(decode_error_kind(buf), DecodeErrorKind::InvalidLength);
+}
+
+#[test]
+fn verifier_missing_required_field() {
+    // GetBlocks message without checkpoint beacon
+    let mut builder = flatbuffers::FlatBufferBuilder::new_with_capacity(1024);
+    let get_blocks = protocol::GetBlocks::create(
+        &mut builder,
+        &protocol::GetBlocksArgs {
+            highest_block_checkpoint: None,
+        },
+    );
+    let message = protocol::Message::create(
+        &mut builder,
+        &protocol::MessageArgs {
+            magic: 0,
+            command_type: protocol::Command::GetBlocks,
+            command: Some(get_blocks.as_union_value()),
+        },
+    );
+    builder.finish(message, None);
+    let buf = builder.finished_data().to_vec();
+
+    assert_eq!(decode_error_kind(buf), DecodeErrorKind::MissingField);
+}
+
+#[test]
+fn verifier_invalid_public_key_length() {
+    // Peers message whose address has a public key of 32 bytes instead of 33
+    let mut builder = flatbuffers::FlatBufferBuilder::new_with_capacity(1024);
+    let ip = protocol::Ipv4::create(&mut builder, &protocol::Ipv4Args { ip: 0 });
+    let public_key = builder.create_vector(&[2u8; 32]);
+    let address = protocol::Address::create(
+        &mut builder,
+        &protocol::AddressArgs {
+            ip_type: protocol::IpAddress::Ipv4,
+            ip: Some(ip.as_union_value()),
+            public_key: Some(public_key),
+            ..Default::default()
+        },
+    );
+    let addresses = builder.create_vector(&[address]);
+    let peers = protocol::Peers::create(
+        &mut builder,
+        &protocol::PeersArgs {
+            peers: Some(addresses),
+        },
+    );
+    let message = protocol::Message::create(
+        &mut builder,
+        &protocol::MessageArgs {
+            magic: 0,
+            command_type: protocol::Command::Peers,
+            command: Some(peers.as_union_value()),
+        },
+    );
+    builder.finish(message, None);
+    let buf = builder.finished_data().to_vec();
+
+    assert_eq!(decode_error_kind(buf), DecodeErrorKind::InvalidLength);
+}
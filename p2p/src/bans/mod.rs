@@ -0,0 +1,134 @@
+//! Library for managing the list of banned peers
+//!
+//! Peers are banned by IP address, so that a misbehaving peer cannot come back just by
+//! connecting from another port. A ban either lasts until a given time or forever. The list can
+//! be exported and imported, so the operators of several nodes can share the peers they banned:
+//! importing a list never shortens an existing ban.
+
+use serde_derive::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use crate::peers::normalize_ip;
+
+/// Ban of a peer, as exported and imported
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Ban {
+    /// IP address of the peer
+    pub address: IpAddr,
+    /// Timestamp (in seconds) until which the peer is banned, or None if the ban is permanent
+    #[serde(default)]
+    pub until: Option<i64>,
+    /// Reason of the ban
+    #[serde(default)]
+    pub reason: String,
+}
+
+impl Ban {
+    /// Check if the ban is still in force at a given time
+    pub fn is_active(&self, now: i64) -> bool {
+        self.until.map_or(true, |until| now < until)
+    }
+
+    /// Check if this ban lasts longer than another one
+    fn outlasts(&self, other: &Ban) -> bool {
+        match (self.until, other.until) {
+            (None, Some(_)) => true,
+            (Some(until), Some(other_until)) => until > other_until,
+            _ => false,
+        }
+    }
+}
+
+/// Banned peers, indexed by their IP address
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Bans {
+    /// Bans of the peers
+    bans: HashMap<IpAddr, Ban>,
+}
+
+impl Bans {
+    /// Ban a peer until a given time (or forever if `until` is None)
+    /// An existing ban of the same address is replaced
+    pub fn ban(&mut self, address: IpAddr, until: Option<i64>, reason: String) {
+        let address = normalize_ip(address);
+        self.bans.insert(
+            address,
+            Ban {
+                address,
+                until,
+                reason,
+            },
+        );
+    }
+
+    /// Lift the ban of a peer
+    /// Returns false if the peer was not banned
+    pub fn unban(&mut self, address: IpAddr) -> bool {
+        self.bans.remove(&normalize_ip(address)).is_some()
+    }
+
+    /// Check if a peer is banned at a given time
+    pub fn is_banned(&self, address: IpAddr, now: i64) -> bool {
+        self.bans
+            .get(&normalize_ip(address))
+            .map_or(false, |ban| ban.is_active(now))
+    }
+
+    /// Remove the bans which are no longer in force at a given time
+    /// Returns the number of bans removed
+    pub fn remove_expired(&mut self, now: i64) -> usize {
+        let before = self.bans.len();
+        self.bans.retain(|_, ban| ban.is_active(now));
+
+        before - self.bans.len()
+    }
+
+    /// Import a list of bans (e.g. exported by another node)
+    /// Expired bans are ignored, and an existing ban is only replaced if the imported one lasts
+    /// longer
+    /// Returns the addresses banned or whose ban has been extended
+    pub fn import(&mut self, bans: Vec<Ban>, now: i64) -> Vec<IpAddr> {
+        bans.into_iter()
+            .filter(|ban| ban.is_active(now))
+            .filter_map(|mut ban| {
+                ban.address = normalize_ip(ban.address);
+                let replace = match self.bans.get(&ban.address) {
+                    Some(existing) => !existing.is_active(now) || ban.outlasts(existing),
+                    None => true,
+                };
+                if replace {
+                    let address = ban.address;
+                    self.bans.insert(address, ban);
+                    Some(address)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Export the bans in force at a given time, sorted by address
+    pub fn export(&self, now: i64) -> Vec<Ban> {
+        let mut bans: Vec<Ban> = self
+            .bans
+            .values()
+            .filter(|ban| ban.is_active(now))
+            .cloned()
+            .collect();
+        bans.sort_by_key(|ban| ban.address);
+
+        bans
+    }
+
+    /// Get the number of bans, including the expired ones not removed yet
+    pub fn len(&self) -> usize {
+        self.bans.len()
+    }
+
+    /// Check if there are no bans
+    pub fn is_empty(&self) -> bool {
+        self.bans.is_empty()
+    }
+}
@@ -0,0 +1,320 @@
+//! Library for scheduling the download of blocks from multiple peers in parallel
+//!
+//! The blocks to be downloaded are split into ranges of consecutive blocks. Every peer can have
+//! a bounded number of ranges in flight (its window), so that different ranges are requested to
+//! different peers at the same time. Ranges that are not completed before a timeout are given
+//! back to be requested to another peer. Downloaded blocks are buffered until all the blocks
+//! before them have been downloaded, so they are always applied in order.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+/// Default number of blocks in every range
+pub const DEFAULT_RANGE_SIZE: usize = 16;
+
+/// Default number of ranges that can be in flight for every peer
+pub const DEFAULT_WINDOW: usize = 4;
+
+/// Default time (in seconds) after which a range in flight is considered stalled
+pub const DEFAULT_TIMEOUT_SECONDS: u64 = 30;
+
+/// Range of consecutive blocks not downloaded yet
+#[derive(Debug)]
+struct Range<H> {
+    /// Hashes of the blocks of the range that have not been received yet
+    hashes: Vec<H>,
+    /// Peers that did not complete this range in time
+    stalled_peers: Vec<SocketAddr>,
+}
+
+/// Range requested to a peer
+#[derive(Debug)]
+struct InFlight<H> {
+    /// Range requested
+    range: Range<H>,
+    /// Time when the range was requested
+    requested_at: Instant,
+}
+
+/// Outcome of handing a downloaded block to the scheduler
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Delivery {
+    /// The block was not being downloaded (or it had already been received)
+    Unexpected,
+    /// The block was accepted
+    Accepted,
+    /// The block was accepted and it was the last one of its range, so more ranges can be
+    /// requested to the peer
+    RangeCompleted,
+}
+
+/// BlockDownload struct contains:
+/// - the hashes of the blocks to be downloaded, in the order in which they must be applied
+/// - the ranges waiting to be requested
+/// - the ranges in flight for every peer
+/// - the blocks received and waiting for the previous ones to be applied
+pub struct BlockDownload<H, B> {
+    /// Number of blocks in every range
+    range_size: usize,
+    /// Maximum number of ranges in flight for every peer
+    window: usize,
+    /// Time after which a range in flight is considered stalled
+    timeout: Duration,
+    /// Hashes of the blocks to be applied, in order
+    order: VecDeque<H>,
+    /// Hashes of the blocks being downloaded (used to discard duplicates)
+    wanted: HashSet<H>,
+    /// Ranges waiting to be requested, in order
+    pending: VecDeque<Range<H>>,
+    /// Ranges requested to every peer
+    in_flight: HashMap<SocketAddr, Vec<InFlight<H>>>,
+    /// Blocks received but not applied yet
+    received: HashMap<H, B>,
+}
+
+/// Default trait implementation
+impl<H, B> Default for BlockDownload<H, B>
+where
+    H: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_RANGE_SIZE,
+            DEFAULT_WINDOW,
+            Duration::from_secs(DEFAULT_TIMEOUT_SECONDS),
+        )
+    }
+}
+
+impl<H, B> BlockDownload<H, B>
+where
+    H: Clone + Eq + Hash,
+{
+    /// Method to create a new block download scheduler
+    pub fn new(range_size: usize, window: usize, timeout: Duration) -> Self {
+        Self {
+            range_size: std::cmp::max(range_size, 1),
+            window,
+            timeout,
+            order: VecDeque::new(),
+            wanted: HashSet::new(),
+            pending: VecDeque::new(),
+            in_flight: HashMap::new(),
+            received: HashMap::new(),
+        }
+    }
+
+    /// Method to check if there are no blocks being downloaded
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Method to get the number of blocks being downloaded or waiting to be applied
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Method to check if a block is being downloaded
+    pub fn is_wanted(&self, hash: &H) -> bool {
+        self.wanted.contains(hash)
+    }
+
+    /// Method to check if there are ranges waiting to be requested
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Method to get the number of ranges in flight for a peer
+    pub fn num_in_flight(&self, peer: SocketAddr) -> usize {
+        self.in_flight.get(&peer).map_or(0, Vec::len)
+    }
+
+    /// Method to add blocks to be downloaded, in the order in which they must be applied
+    /// Blocks already being downloaded are ignored
+    /// Returns the number of blocks added
+    pub fn enqueue<I>(&mut self, hashes: I) -> usize
+    where
+        I: IntoIterator<Item = H>,
+    {
+        let mut added = 0;
+        for hash in hashes {
+            if !self.wanted.insert(hash.clone()) {
+                continue;
+            }
+            self.order.push_back(hash.clone());
+            added += 1;
+
+            // Fill the last pending range before creating a new one
+            let range_size = self.range_size;
+            match self.pending.back_mut() {
+                Some(range)
+                    if range.hashes.len() < range_size && range.stalled_peers.is_empty() =>
+                {
+                    range.hashes.push(hash)
+                }
+                _ => self.pending.push_back(Range {
+                    hashes: vec![hash],
+                    stalled_peers: vec![],
+                }),
+            }
+        }
+
+        added
+    }
+
+    /// Method to assign ranges to a peer until its window is full
+    /// Ranges that stalled for this peer are only assigned to it if no other range is pending
+    /// Returns the hashes of the blocks of every assigned range
+    pub fn assign(&mut self, peer: SocketAddr, now: Instant) -> Vec<Vec<H>> {
+        let mut assigned = vec![];
+        while self.num_in_flight(peer) < self.window {
+            let position = self
+                .pending
+                .iter()
+                .position(|range| !range.stalled_peers.contains(&peer))
+                .unwrap_or(0);
+            let range = match self.pending.remove(position) {
+                Some(range) => range,
+                None => break,
+            };
+
+            assigned.push(range.hashes.clone());
+            self.in_flight
+                .entry(peer)
+                .or_insert_with(Vec::new)
+                .push(InFlight {
+                    range,
+                    requested_at: now,
+                });
+        }
+
+        assigned
+    }
+
+    /// Method to hand a downloaded block to the scheduler
+    /// Blocks are accepted from any peer, as a stalled range could be delivered late by the
+    /// peer to which it was first requested
+    pub fn receive(&mut self, hash: H, block: B) -> Delivery {
+        if !self.wanted.contains(&hash) || self.received.contains_key(&hash) {
+            return Delivery::Unexpected;
+        }
+        self.received.insert(hash.clone(), block);
+
+        // Remove the block from the range in flight containing it
+        let mut delivery = Delivery::Accepted;
+        for ranges in self.in_flight.values_mut() {
+            if let Some(position) = ranges
+                .iter()
+                .position(|in_flight| in_flight.range.hashes.contains(&hash))
+            {
+                ranges[position].range.hashes.retain(|h| *h != hash);
+                if ranges[position].range.hashes.is_empty() {
+                    ranges.remove(position);
+                    delivery = Delivery::RangeCompleted;
+                }
+                break;
+            }
+        }
+        self.in_flight.retain(|_, ranges| !ranges.is_empty());
+
+        // The block could also belong to a range given back after stalling
+        for range in self.pending.iter_mut() {
+            range.hashes.retain(|h| *h != hash);
+        }
+        self.pending.retain(|range| !range.hashes.is_empty());
+
+        delivery
+    }
+
+    /// Method to take the received blocks that can be applied, that is, the ones whose previous
+    /// blocks have all been received
+    /// Returns the hashes and the blocks in the order in which they must be applied
+    pub fn take_ready(&mut self) -> Vec<(H, B)> {
+        let mut ready = vec![];
+        while let Some(hash) = self.order.front().cloned() {
+            match self.received.remove(&hash) {
+                Some(block) => {
+                    self.order.pop_front();
+                    self.wanted.remove(&hash);
+                    ready.push((hash, block));
+                }
+                None => break,
+            }
+        }
+
+        ready
+    }
+
+    /// Method to give back the ranges in flight that have stalled, so they can be requested to
+    /// another peer
+    /// Stalled ranges are requested before the rest of pending ranges, as they are blocking the
+    /// application of the blocks after them
+    /// Returns the number of stalled ranges
+    pub fn reassign_stalled(&mut self, now: Instant) -> usize {
+        let timeout = self.timeout;
+        let mut stalled = vec![];
+        for (peer, ranges) in self.in_flight.iter_mut() {
+            let (expired, active): (Vec<_>, Vec<_>) = ranges
+                .drain(..)
+                .partition(|in_flight| now.duration_since(in_flight.requested_at) >= timeout);
+            *ranges = active;
+            stalled.extend(expired.into_iter().map(|in_flight| {
+                let mut range = in_flight.range;
+                range.stalled_peers.push(*peer);
+                range
+            }));
+        }
+        self.in_flight.retain(|_, ranges| !ranges.is_empty());
+
+        let num_stalled = stalled.len();
+        self.give_back(stalled);
+
+        num_stalled
+    }
+
+    /// Method to give back all the ranges in flight for a peer (e.g. because the session with
+    /// that peer has been closed)
+    /// Returns the number of ranges given back
+    pub fn remove_peer(&mut self, peer: SocketAddr) -> usize {
+        let ranges: Vec<Range<H>> = self
+            .in_flight
+            .remove(&peer)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|in_flight| in_flight.range)
+            .collect();
+
+        let num_ranges = ranges.len();
+        self.give_back(ranges);
+
+        num_ranges
+    }
+
+    /// Method to discard all the blocks being downloaded
+    pub fn clear(&mut self) {
+        self.order.clear();
+        self.wanted.clear();
+        self.pending.clear();
+        self.in_flight.clear();
+        self.received.clear();
+    }
+
+    /// Method to put ranges back at the front of the pending ranges, keeping them in the order
+    /// in which their blocks must be applied
+    fn give_back(&mut self, mut ranges: Vec<Range<H>>) {
+        let order = &self.order;
+        let index = |range: &Range<H>| {
+            range
+                .hashes
+                .first()
+                .and_then(|first| order.iter().position(|h| h == first))
+                .unwrap_or(0)
+        };
+        ranges.sort_by_key(index);
+        for range in ranges.into_iter().rev() {
+            self.pending.push_front(range);
+        }
+    }
+}
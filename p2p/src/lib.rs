@@ -7,6 +7,12 @@
 #![deny(unused_mut)]
 #![deny(missing_docs)]
 
+pub mod bans;
+
+pub mod download;
+
 pub mod peers;
 
+pub mod requests;
+
 pub mod sessions;
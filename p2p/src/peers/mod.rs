@@ -2,22 +2,290 @@
 
 use serde_derive::{Deserialize, Serialize};
 
-use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
 use rand::{thread_rng, Rng};
 
+use witnet_data_structures::chain::KeyedSignature;
 use witnet_util::timestamp::get_timestamp;
 
 use crate::peers::error::PeersResult;
 
 pub mod error;
 
+/// Maximum number of addresses accepted from a single peers message
+pub const MAX_ADDRESSES_PER_MESSAGE: usize = 1000;
+
+/// Maximum number of addresses in the list: once it is reached, the unconfirmed addresses with
+/// the lowest weight are evicted to make room for the new ones
+pub const MAX_PEERS: usize = 10_000;
+
+/// Maximum number of independent sources taken into account when weighting an address
+pub const MAX_SOURCES: usize = 8;
+
+/// Period (in seconds) during which an address is considered to be recently seen
+pub const RECENTLY_SEEN_PERIOD: i64 = 3 * 60 * 60;
+
+/// Time (in seconds) an address is not tried again after a failed connection attempt, which is
+/// doubled after every consecutive failure
+pub const RETRY_BASE_DELAY: i64 = 60;
+
+/// Maximum time (in seconds) an address is not tried again after failed connection attempts
+pub const RETRY_MAX_DELAY: i64 = 24 * 60 * 60;
+
+/// Outcome of an attempt to connect to an address
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AttemptOutcome {
+    /// The connection was established and the handshake completed
+    Success,
+    /// The connection was refused or it could not be established
+    Refused,
+    /// The connection could not be established in time
+    Timeout,
+    /// The connection was established, but the handshake did not complete
+    HandshakeFailed,
+}
+
+/// Statistics of the attempts to connect to an address
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AttemptStats {
+    /// Number of consecutive failed attempts
+    pub failures: u32,
+    /// Timestamp of the first of the consecutive failed attempts, if the last attempt failed
+    pub failing_since: Option<i64>,
+    /// Timestamp and outcome of the last attempt
+    pub last_attempt: Option<(i64, AttemptOutcome)>,
+    /// Timestamp of the last successful attempt
+    pub last_success: Option<i64>,
+}
+
+impl AttemptStats {
+    /// Record the outcome of an attempt
+    pub fn record(&mut self, outcome: AttemptOutcome, now: i64) {
+        self.last_attempt = Some((now, outcome));
+        if let AttemptOutcome::Success = outcome {
+            self.failures = 0;
+            self.failing_since = None;
+            self.last_success = Some(now);
+        } else {
+            self.failures = self.failures.saturating_add(1);
+            self.failing_since = self.failing_since.or(Some(now));
+        }
+    }
+
+    /// Get the timestamp from which the address can be tried again (exponential backoff)
+    pub fn retry_at(&self) -> i64 {
+        match self.last_attempt {
+            Some((timestamp, _)) => timestamp.saturating_add(retry_delay(self.failures)),
+            None => 0,
+        }
+    }
+
+    /// Check if the address has not been reachable for at least `period` seconds
+    pub fn is_unreachable(&self, now: i64, period: i64) -> bool {
+        self.failing_since
+            .map_or(false, |since| now.saturating_sub(since) >= period)
+    }
+}
+
+/// Time (in seconds) an address is not tried again after some consecutive failed attempts
+pub fn retry_delay(failures: u32) -> i64 {
+    if failures == 0 {
+        return 0;
+    }
+
+    // The shift is capped so it never overflows, the delay is capped anyway
+    let exponent = std::cmp::min(failures - 1, 32);
+    std::cmp::min(RETRY_BASE_DELAY << exponent, RETRY_MAX_DELAY)
+}
+
+/// Normalize the representation of an IP address: IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`)
+/// are converted to IPv4 addresses
+pub fn normalize_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, hi, lo] => IpAddr::V4(Ipv4Addr::new(
+                (hi >> 8) as u8,
+                hi as u8,
+                (lo >> 8) as u8,
+                lo as u8,
+            )),
+            _ => ip,
+        },
+        IpAddr::V4(_) => ip,
+    }
+}
+
+/// Normalize the representation of a socket address, so that the same peer is never stored
+/// twice (see `normalize_ip`)
+pub fn normalize_address(address: SocketAddr) -> SocketAddr {
+    SocketAddr::new(normalize_ip(address.ip()), address.port())
+}
+
+/// Check if an address makes sense to be advertised to other peers, that is, it is neither
+/// unspecified (e.g. `0.0.0.0`) nor a loopback address
+pub fn is_advertisable(address: &SocketAddr) -> bool {
+    let ip = normalize_ip(address.ip());
+
+    address.port() != 0 && !ip.is_unspecified() && !ip.is_loopback()
+}
+
+/// Network group of an address: peers in the same network group are likely to be operated by the
+/// same entity, so outbound connections are spread across different groups to make it harder to
+/// eclipse the node
+// TODO: group the addresses by their autonomous system number (ASN) once a mapping is available
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NetworkGroup {
+    /// `/16` prefix of an IPv4 address
+    Ipv4([u8; 2]),
+    /// `/32` prefix of an IPv6 address
+    Ipv6([u16; 2]),
+    /// Address which is not publicly routable (e.g. loopback or private addresses): every one of
+    /// them is a group of its own, so local networks are not limited to a single connection
+    Local(SocketAddr),
+}
+
+/// Get the network group of an address (see `NetworkGroup`)
+pub fn network_group(address: &SocketAddr) -> NetworkGroup {
+    let address = normalize_address(*address);
+    match address.ip() {
+        IpAddr::V4(ip) if is_routable_v4(ip) => {
+            let octets = ip.octets();
+            NetworkGroup::Ipv4([octets[0], octets[1]])
+        }
+        IpAddr::V6(ip) if is_routable_v6(ip) => {
+            let segments = ip.segments();
+            NetworkGroup::Ipv6([segments[0], segments[1]])
+        }
+        _ => NetworkGroup::Local(address),
+    }
+}
+
+/// Check if an IPv4 address is publicly routable
+fn is_routable_v4(ip: Ipv4Addr) -> bool {
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast())
+}
+
+/// Check if an IPv6 address is publicly routable
+fn is_routable_v6(ip: Ipv6Addr) -> bool {
+    let first_segment = ip.segments()[0];
+
+    // Unique local (fc00::/7) and link-local (fe80::/10) addresses are not routable
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || first_segment & 0xfe00 == 0xfc00
+        || first_segment & 0xffc0 == 0xfe80)
+}
+
+/// Address families that this node is able to connect to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AddressFamilies {
+    /// IPv4 addresses are reachable
+    pub ipv4: bool,
+    /// IPv6 addresses are reachable
+    pub ipv6: bool,
+}
+
+impl Default for AddressFamilies {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl AddressFamilies {
+    /// Both address families
+    pub fn all() -> Self {
+        AddressFamilies {
+            ipv4: true,
+            ipv6: true,
+        }
+    }
+
+    /// Address families of the addresses the node is listening on
+    /// If there are no addresses, all the families are assumed to be reachable
+    pub fn from_listen_addresses(addrs: &[SocketAddr]) -> Self {
+        if addrs.is_empty() {
+            return Self::all();
+        }
+
+        AddressFamilies {
+            ipv4: addrs.iter().any(SocketAddr::is_ipv4),
+            ipv6: addrs.iter().any(SocketAddr::is_ipv6),
+        }
+    }
+
+    /// Check if the family of an address is one of these families
+    pub fn contains(&self, address: &SocketAddr) -> bool {
+        match normalize_address(*address) {
+            SocketAddr::V4(_) => self.ipv4,
+            SocketAddr::V6(_) => self.ipv6,
+        }
+    }
+}
+
+/// Address reported by another peer, along with the last time it was seen and the signature of
+/// that address and time by the node that originated it (if any), which must have been verified
+/// before reporting it
+pub type ReportedAddress = (SocketAddr, i64, Option<KeyedSignature>);
+
+/// Signature of an address by the node that originated it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Origin {
+    /// Last time the address was seen, as signed by its origin
+    pub last_seen: i64,
+    /// Signature of the address and the last time it was seen, along with the public key of the
+    /// origin
+    pub signature: KeyedSignature,
+}
+
 /// Peer information being used while listing available Witnet peers
 #[derive(Serialize, Deserialize)]
 struct PeerInfo {
     address: SocketAddr,
-    _timestamp: i64,
+    last_seen: i64,
+    /// IP addresses of the peers that reported this address
+    #[serde(default)]
+    sources: HashSet<IpAddr>,
+    /// Whether this address has been confirmed by this node (i.e. a connection succeeded)
+    #[serde(default)]
+    confirmed: bool,
+    /// Statistics of the attempts to connect to this address
+    #[serde(default)]
+    attempts: AttemptStats,
+    /// Latest signature of this address by the node that originated it: once an address is
+    /// signed, only its origin (the same public key) can refresh the last time it was seen
+    #[serde(default)]
+    origin: Option<Origin>,
+}
+
+impl PeerInfo {
+    /// Weight of this address when selecting a random peer: addresses reported by more
+    /// independent sources and seen recently are preferred, so that a single peer flooding
+    /// fake addresses has a limited impact on the selection
+    fn weight(&self, now: i64) -> u64 {
+        let confirmations = if self.confirmed {
+            MAX_SOURCES
+        } else {
+            std::cmp::min(self.sources.len(), MAX_SOURCES)
+        };
+        let weight = 1 << confirmations;
+
+        if now - self.last_seen <= RECENTLY_SEEN_PERIOD {
+            weight * 2
+        } else {
+            weight
+        }
+    }
+
+    /// Check if this address can be tried, that is, it is not backing off after failed attempts
+    fn can_retry(&self, now: i64) -> bool {
+        self.attempts.retry_at() <= now
+    }
 }
 
 /// Peers TBD
@@ -28,60 +296,341 @@ pub struct Peers {
 }
 
 impl Peers {
-    /// Add multiple peer addresses and save timestamp
+    /// Add multiple confirmed peer addresses and save timestamp
     /// If an address did already exist, it gets overwritten
     /// Returns all the overwritten addresses
+    /// The statistics of the connection attempts and the origin signature of an overwritten
+    /// address are kept
     pub fn add(&mut self, addrs: Vec<SocketAddr>) -> PeersResult<Vec<SocketAddr>> {
         // Insert address
         // Note: if the peer address exists, the peer info will be overwritten
-        Ok(addrs
+        let overwritten = addrs
             .into_iter()
+            .map(normalize_address)
             .filter_map(|address| {
+                let (attempts, origin) = self
+                    .peers
+                    .get(&address)
+                    .map(|info| (info.attempts.clone(), info.origin.clone()))
+                    .unwrap_or_default();
                 self.peers
                     .insert(
                         address,
                         PeerInfo {
                             address,
-                            _timestamp: get_timestamp(), //msg.timestamp,
+                            last_seen: get_timestamp(),
+                            sources: HashSet::new(),
+                            confirmed: true,
+                            attempts,
+                            origin,
                         },
                     )
                     .map(|v| v.address)
             })
+            .collect();
+        self.evict_excess(get_timestamp());
+
+        Ok(overwritten)
+    }
+
+    /// Restore the peer addresses persisted into storage, along with their sources and the
+    /// statistics of their connection attempts
+    /// If an address did already exist, it gets overwritten
+    /// Returns the restored addresses
+    pub fn restore(&mut self, stored: Peers) -> PeersResult<Vec<SocketAddr>> {
+        let restored = stored
+            .peers
+            .into_iter()
+            .map(|(_, mut info)| {
+                info.address = normalize_address(info.address);
+                let address = info.address;
+                self.peers.insert(address, info);
+
+                address
+            })
+            .collect();
+        self.evict_excess(get_timestamp());
+
+        Ok(restored)
+    }
+
+    /// Add multiple peer addresses reported by another peer (the source), along with the last
+    /// time they were seen and the signatures of their origins, which must have been verified
+    /// Only the first `MAX_ADDRESSES_PER_MESSAGE` addresses are taken into account and
+    /// timestamps in the future are capped to the current time (dropping their signature)
+    /// Once an address has been signed by its origin, the reports signed by other keys are
+    /// ignored and the unsigned reports do not refresh the last time it was seen
+    /// Returns the addresses that were not known before
+    pub fn add_from_source(
+        &mut self,
+        source: IpAddr,
+        addrs: Vec<ReportedAddress>,
+    ) -> PeersResult<Vec<SocketAddr>> {
+        let now = get_timestamp();
+        let source = normalize_ip(source);
+
+        let new_addresses = addrs
+            .into_iter()
+            .take(MAX_ADDRESSES_PER_MESSAGE)
+            .filter_map(|(address, last_seen, signature)| {
+                let address = normalize_address(address);
+                // The signature does not match a capped timestamp
+                let origin = if last_seen <= now {
+                    signature.map(|signature| Origin {
+                        last_seen,
+                        signature,
+                    })
+                } else {
+                    None
+                };
+                let last_seen = std::cmp::min(last_seen, now);
+
+                let mut new_address = false;
+                let info = self.peers.entry(address).or_insert_with(|| {
+                    new_address = true;
+                    PeerInfo {
+                        address,
+                        last_seen,
+                        sources: HashSet::new(),
+                        confirmed: false,
+                        attempts: AttemptStats::default(),
+                        origin: None,
+                    }
+                });
+                // Only the origin of a signed address can vouch for it again
+                let same_origin = match (&info.origin, &origin) {
+                    (Some(known), Some(origin)) => {
+                        known.signature.public_key == origin.signature.public_key
+                    }
+                    _ => true,
+                };
+                if !same_origin {
+                    return None;
+                }
+                match origin {
+                    Some(origin) => {
+                        let newer = info
+                            .origin
+                            .as_ref()
+                            .map_or(true, |known| origin.last_seen > known.last_seen);
+                        if newer {
+                            info.last_seen = std::cmp::max(info.last_seen, origin.last_seen);
+                            info.origin = Some(origin);
+                        }
+                    }
+                    // The timestamps claimed by other peers are not trusted for signed addresses
+                    None if info.origin.is_some() => {}
+                    None => info.last_seen = std::cmp::max(info.last_seen, last_seen),
+                }
+                // A peer cannot vouch for its own address
+                if source != address.ip() {
+                    info.sources.insert(source);
+                }
+
+                if new_address {
+                    Some(address)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        self.evict_excess(now);
+
+        Ok(new_addresses
+            .into_iter()
+            .filter(|address| self.peers.contains_key(address))
             .collect())
     }
 
+    /// Evict the unconfirmed addresses with the lowest weight (the least recently seen first)
+    /// until there are no more than `MAX_PEERS` addresses
+    /// The confirmed addresses are never evicted
+    fn evict_excess(&mut self, now: i64) {
+        if self.peers.len() <= MAX_PEERS {
+            return;
+        }
+
+        let mut unconfirmed: Vec<(u64, i64, SocketAddr)> = self
+            .peers
+            .values()
+            .filter(|info| !info.confirmed)
+            .map(|info| (info.weight(now), info.last_seen, info.address))
+            .collect();
+        unconfirmed.sort();
+
+        let excess = self.peers.len() - MAX_PEERS;
+        for (_, _, address) in unconfirmed.into_iter().take(excess) {
+            self.peers.remove(&address);
+        }
+    }
+
     /// Remove a peer given an address
     /// Returns the removed addresses
     pub fn remove(&mut self, addrs: &[SocketAddr]) -> PeersResult<Vec<SocketAddr>> {
         Ok(addrs
             .iter()
-            .filter_map(|address| self.peers.remove(&address).map(|info| info.address))
+            .filter_map(|address| {
+                self.peers
+                    .remove(&normalize_address(*address))
+                    .map(|info| info.address)
+            })
             .collect())
     }
 
+    /// Record the outcome of an attempt to connect to an address
+    /// Returns false if the address is not in the list
+    pub fn record_attempt(
+        &mut self,
+        address: SocketAddr,
+        outcome: AttemptOutcome,
+        now: i64,
+    ) -> PeersResult<bool> {
+        Ok(match self.peers.get_mut(&normalize_address(address)) {
+            Some(info) => {
+                info.attempts.record(outcome, now);
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Get the statistics of the attempts to connect to an address, if it is in the list
+    pub fn get_attempt_stats(&self, address: &SocketAddr) -> Option<&AttemptStats> {
+        self.peers
+            .get(&normalize_address(*address))
+            .map(|info| &info.attempts)
+    }
+
+    /// Remove the addresses which have not been reachable for at least `period` seconds, that
+    /// is, every attempt to connect to them during that period failed
+    /// Returns the removed addresses
+    pub fn remove_unreachable(&mut self, now: i64, period: i64) -> PeersResult<Vec<SocketAddr>> {
+        let unreachable: Vec<SocketAddr> = self
+            .peers
+            .values()
+            .filter(|info| info.attempts.is_unreachable(now, period))
+            .map(|info| info.address)
+            .collect();
+
+        self.remove(&unreachable)
+    }
+
     /// Get a random socket address from the peers list
+    /// The selection is weighted so that recently seen and independently confirmed addresses
+    /// are more likely to be chosen, and the addresses backing off after failed connection
+    /// attempts are skipped
     pub fn get_random(&mut self) -> PeersResult<Option<SocketAddr>> {
-        // Random index with range [0, len) of the peers vector
-        let index = thread_rng().gen_range(0, std::cmp::max(self.peers.len(), 1));
+        self.get_random_with_families(AddressFamilies::all())
+    }
 
-        // Get element at index
-        let random_addr = self
+    /// Get a random socket address from the peers list, only among the addresses whose family
+    /// is one of the given address families (e.g. so that IPv6-only nodes do not try to connect
+    /// to IPv4 addresses)
+    pub fn get_random_with_families(
+        &mut self,
+        families: AddressFamilies,
+    ) -> PeersResult<Option<SocketAddr>> {
+        let now = get_timestamp();
+        let candidates: Vec<&PeerInfo> = self
             .peers
-            // get peer infos
             .values()
-            // enumerate them -> (indices, peer info)
-            .enumerate()
-            // filter by index and get address -> Iterator<Option<SocketAddr>>
-            .filter_map(|(i, v)| if i == index { Some(v.address) } else { None })
-            // Get first one, because
-            .next()
-            .map(|v| v.to_owned());
+            .filter(|v| families.contains(&v.address) && v.can_retry(now))
+            .collect();
+        let total_weight: u64 = candidates.iter().map(|v| v.weight(now)).sum();
+        if total_weight == 0 {
+            return Ok(None);
+        }
+
+        // Random point with range [0, total_weight)
+        let mut point = thread_rng().gen_range(0, total_weight);
+
+        // Get the address whose weight interval contains the random point
+        let random_addr = candidates
+            .into_iter()
+            .find(|v| {
+                let weight = v.weight(now);
+                if point < weight {
+                    true
+                } else {
+                    point -= weight;
+                    false
+                }
+            })
+            .map(|v| v.address);
 
         Ok(random_addr)
     }
 
+    /// Get up to `count` random socket addresses from the peers list, each one of them from a
+    /// different network group and none of them from the `avoided` groups (e.g. the groups of the
+    /// peers the node is already connected to), only among the addresses of the given families
+    /// The selection of every address is weighted like in `get_random`, and the addresses backing
+    /// off after failed connection attempts are skipped as well
+    pub fn get_diverse(
+        &mut self,
+        count: usize,
+        families: AddressFamilies,
+        avoided: &HashSet<NetworkGroup>,
+    ) -> PeersResult<Vec<SocketAddr>> {
+        let now = get_timestamp();
+        let mut candidates: Vec<(&PeerInfo, NetworkGroup)> = self
+            .peers
+            .values()
+            .filter(|v| families.contains(&v.address) && v.can_retry(now))
+            .map(|v| (v, network_group(&v.address)))
+            .filter(|(_, group)| !avoided.contains(group))
+            .collect();
+
+        let mut selected = vec![];
+        while selected.len() < count {
+            let total_weight: u64 = candidates.iter().map(|(v, _)| v.weight(now)).sum();
+            if total_weight == 0 {
+                break;
+            }
+
+            // Random point with range [0, total_weight)
+            let mut point = thread_rng().gen_range(0, total_weight);
+
+            // Get the address whose weight interval contains the random point
+            let index = candidates.iter().position(|(v, _)| {
+                let weight = v.weight(now);
+                if point < weight {
+                    true
+                } else {
+                    point -= weight;
+                    false
+                }
+            });
+            let (info, group) = match index {
+                Some(index) => candidates.swap_remove(index),
+                None => break,
+            };
+            selected.push(info.address);
+
+            // Only one address is selected from every network group
+            candidates.retain(|(_, candidate_group)| *candidate_group != group);
+        }
+
+        Ok(selected)
+    }
+
     /// Get all the peers from the list
     pub fn get_all(&self) -> PeersResult<Vec<SocketAddr>> {
         Ok(self.peers.values().map(|v| v.address).collect())
     }
+
+    /// Get all the peers from the list along with the last time they were seen and the
+    /// signature of their origin (if any), to be shared with other peers
+    /// The signed addresses are shared with the last time they were seen as signed by their
+    /// origin, so the signature can be verified by the other peers
+    pub fn get_all_with_last_seen(&self) -> PeersResult<Vec<ReportedAddress>> {
+        Ok(self
+            .peers
+            .values()
+            .map(|v| match &v.origin {
+                Some(origin) => (v.address, origin.last_seen, Some(origin.signature.clone())),
+                None => (v.address, v.last_seen, None),
+            })
+            .collect())
+    }
 }
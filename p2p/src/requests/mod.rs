@@ -0,0 +1,214 @@
+//! Library for tracking the inventory items requested to peers
+//!
+//! Every item requested to a peer is tracked until it is delivered. Items that are not delivered
+//! before a timeout are given back to be requested to another peer, up to a maximum number of
+//! attempts. Peers that fail to deliver the items requested to them too many times in a row are
+//! reported, so they can be penalized.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+/// Default time (in seconds) after which a requested item is considered lost
+pub const DEFAULT_TIMEOUT_SECONDS: u64 = 30;
+
+/// Default number of peers an item is requested to before giving up
+pub const DEFAULT_MAX_ATTEMPTS: usize = 3;
+
+/// Default number of consecutive failed requests after which a peer is reported
+pub const DEFAULT_MAX_FAILURES: u32 = 3;
+
+/// Item requested to a peer
+#[derive(Debug)]
+struct Request {
+    /// Peer the item was requested to
+    peer: SocketAddr,
+    /// Time when the item was requested
+    requested_at: Instant,
+    /// Peers that did not deliver the item in time
+    failed_peers: Vec<SocketAddr>,
+}
+
+/// Item which was not delivered in time and has to be requested to another peer
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expired<H> {
+    /// Item
+    pub item: H,
+    /// Peers that did not deliver the item in time (the item should not be requested to them
+    /// again)
+    pub failed_peers: Vec<SocketAddr>,
+}
+
+/// RequestTracker struct contains:
+/// - the items requested to peers and not delivered yet
+/// - the number of consecutive failed requests of every peer
+pub struct RequestTracker<H> {
+    /// Time after which a requested item is considered lost
+    timeout: Duration,
+    /// Maximum number of peers an item is requested to
+    max_attempts: usize,
+    /// Maximum number of consecutive failed requests of a peer before it is reported
+    max_failures: u32,
+    /// Items not delivered yet
+    requests: HashMap<H, Request>,
+    /// Consecutive failed requests of every peer
+    failures: HashMap<SocketAddr, u32>,
+}
+
+/// Default trait implementation
+impl<H> Default for RequestTracker<H>
+where
+    H: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new(
+            Duration::from_secs(DEFAULT_TIMEOUT_SECONDS),
+            DEFAULT_MAX_ATTEMPTS,
+            DEFAULT_MAX_FAILURES,
+        )
+    }
+}
+
+impl<H> RequestTracker<H>
+where
+    H: Clone + Eq + Hash,
+{
+    /// Method to create a new request tracker
+    pub fn new(timeout: Duration, max_attempts: usize, max_failures: u32) -> Self {
+        Self {
+            timeout,
+            max_attempts: std::cmp::max(max_attempts, 1),
+            max_failures: std::cmp::max(max_failures, 1),
+            requests: HashMap::new(),
+            failures: HashMap::new(),
+        }
+    }
+
+    /// Method to check if there are no items being tracked
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Method to get the number of items being tracked
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Method to check if an item is being tracked
+    pub fn is_requested(&self, item: &H) -> bool {
+        self.requests.contains_key(item)
+    }
+
+    /// Method to track the items requested to a peer
+    /// Items already requested to another peer keep being tracked for that peer
+    /// Returns the number of items added
+    pub fn request<I>(&mut self, peer: SocketAddr, items: I, now: Instant) -> usize
+    where
+        I: IntoIterator<Item = H>,
+    {
+        let mut added = 0;
+        for item in items {
+            if self.requests.contains_key(&item) {
+                continue;
+            }
+            self.requests.insert(
+                item,
+                Request {
+                    peer,
+                    requested_at: now,
+                    failed_peers: vec![],
+                },
+            );
+            added += 1;
+        }
+
+        added
+    }
+
+    /// Method to track an expired item requested again to another peer
+    pub fn retry(&mut self, peer: SocketAddr, expired: Expired<H>, now: Instant) {
+        self.requests.insert(
+            expired.item,
+            Request {
+                peer,
+                requested_at: now,
+                failed_peers: expired.failed_peers,
+            },
+        );
+    }
+
+    /// Method to stop tracking an item delivered by a peer
+    /// Items are accepted from any peer, but the failures of a peer are only reset when it
+    /// delivers an item requested to it
+    /// Returns false if the item was not being tracked
+    pub fn deliver(&mut self, peer: SocketAddr, item: &H) -> bool {
+        match self.requests.remove(item) {
+            Some(request) => {
+                if request.peer == peer {
+                    self.failures.remove(&peer);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Method to take the items that have not been delivered in time
+    /// The failures of the peers they were requested to are counted, and the items that have
+    /// already been requested to the maximum number of peers are given up
+    /// Returns the items to be requested to another peer
+    pub fn expire(&mut self, now: Instant) -> Vec<Expired<H>> {
+        let timeout = self.timeout;
+        let expired_items: Vec<H> = self
+            .requests
+            .iter()
+            .filter(|(_, request)| now.duration_since(request.requested_at) >= timeout)
+            .map(|(item, _)| item.clone())
+            .collect();
+
+        let mut expired = vec![];
+        for item in expired_items {
+            let mut request = match self.requests.remove(&item) {
+                Some(request) => request,
+                None => continue,
+            };
+            *self.failures.entry(request.peer).or_insert(0) += 1;
+            request.failed_peers.push(request.peer);
+
+            if request.failed_peers.len() < self.max_attempts {
+                expired.push(Expired {
+                    item,
+                    failed_peers: request.failed_peers,
+                });
+            }
+        }
+
+        expired
+    }
+
+    /// Method to take the peers that have failed to deliver the items requested to them too
+    /// many times in a row, so they can be penalized
+    /// Every peer is only reported once (its failures are reset)
+    pub fn take_failing_peers(&mut self) -> Vec<SocketAddr> {
+        let max_failures = self.max_failures;
+        let failing: Vec<SocketAddr> = self
+            .failures
+            .iter()
+            .filter(|(_, failures)| **failures >= max_failures)
+            .map(|(peer, _)| *peer)
+            .collect();
+        for peer in &failing {
+            self.failures.remove(peer);
+        }
+
+        failing
+    }
+
+    /// Method to forget the failures of a peer (e.g. because the session with that peer has
+    /// been closed)
+    /// The items requested to the peer keep being tracked until they expire
+    pub fn remove_peer(&mut self, peer: SocketAddr) {
+        self.failures.remove(&peer);
+    }
+}
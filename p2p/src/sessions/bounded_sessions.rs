@@ -3,12 +3,15 @@ use std::collections::HashMap;
 use std::net::SocketAddr;
 
 use crate::sessions::error::{SessionsError, SessionsErrorKind, SessionsResult};
+use crate::sessions::peer_stats::PeerStats;
 use witnet_util::error::WitnetError;
 
 /// Session info
 pub struct SessionInfo<T> {
     /// Session reference (e.g. actor address)
     pub reference: T,
+    /// Latency and throughput statistics of the peer
+    pub stats: PeerStats,
 }
 
 /// Sessions struct contains:
@@ -59,7 +62,13 @@ impl<T> BoundedSessions<T> {
             )));
         }
         // Insert session into the right collection
-        self.collection.insert(address, SessionInfo { reference });
+        self.collection.insert(
+            address,
+            SessionInfo {
+                reference,
+                stats: PeerStats::default(),
+            },
+        );
 
         // Return success
         Ok(())
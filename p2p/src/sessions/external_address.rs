@@ -0,0 +1,63 @@
+//! Library for discovering the external address of the node from the addresses reported by its
+//! peers in their `Version` messages
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+
+use crate::peers::{is_advertisable, normalize_ip};
+
+/// Maximum number of peers whose reports are taken into account
+pub const MAX_EXTERNAL_ADDRESS_REPORTERS: usize = 64;
+
+/// Minimum number of peers that must agree on an external address before it is used
+pub const MIN_EXTERNAL_ADDRESS_REPORTS: usize = 2;
+
+/// ExternalAddressReports struct contains the external IP address of the node as reported by
+/// every peer
+/// Only the IP address is taken into account: the port seen by a peer connected through an
+/// outbound session is an ephemeral port, not the server port
+#[derive(Debug, Default)]
+pub struct ExternalAddressReports {
+    /// External IP address reported by every peer (identified by its IP address, so that a
+    /// single host cannot report more than once)
+    reports: HashMap<IpAddr, IpAddr>,
+}
+
+impl ExternalAddressReports {
+    /// Method to record the external IP address reported by a peer
+    /// Reports of unspecified or loopback addresses are ignored, as well as new reporters once
+    /// `MAX_EXTERNAL_ADDRESS_REPORTERS` peers have reported
+    /// Returns true if the report has been recorded
+    pub fn record(&mut self, reporter: IpAddr, reported: IpAddr) -> bool {
+        let reporter = normalize_ip(reporter);
+        let reported = normalize_ip(reported);
+        if !is_advertisable(&SocketAddr::new(reported, 1)) {
+            return false;
+        }
+        if self.reports.len() >= MAX_EXTERNAL_ADDRESS_REPORTERS
+            && !self.reports.contains_key(&reporter)
+        {
+            return false;
+        }
+        self.reports.insert(reporter, reported);
+
+        true
+    }
+
+    /// Method to get the external IP address reported by most peers, as long as it has been
+    /// reported by at least `MIN_EXTERNAL_ADDRESS_REPORTS` peers
+    /// Ties are broken by choosing the lowest address, so that the result is deterministic
+    pub fn most_reported(&self) -> Option<IpAddr> {
+        let mut counts: HashMap<IpAddr, usize> = HashMap::new();
+        for reported in self.reports.values() {
+            *counts.entry(*reported).or_insert(0) += 1;
+        }
+
+        counts
+            .into_iter()
+            .filter(|(_, count)| *count >= MIN_EXTERNAL_ADDRESS_REPORTS)
+            .max_by(|(ip_a, count_a), (ip_b, count_b)| {
+                count_a.cmp(count_b).then_with(|| ip_b.cmp(ip_a))
+            })
+            .map(|(ip, _)| ip)
+    }
+}
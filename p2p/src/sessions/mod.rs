@@ -6,13 +6,24 @@ pub mod error;
 /// Bounded sessions module
 pub mod bounded_sessions;
 
-use std::net::SocketAddr;
+/// Peer statistics module
+pub mod peer_stats;
+
+/// External address discovery module
+pub mod external_address;
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
 use std::time::Duration;
 
 use rand::{thread_rng, Rng};
 
-use crate::sessions::bounded_sessions::BoundedSessions;
+use crate::peers::{network_group, NetworkGroup};
+use crate::sessions::bounded_sessions::{BoundedSessions, SessionInfo};
 use crate::sessions::error::SessionsResult;
+use crate::sessions::external_address::ExternalAddressReports;
+use crate::sessions::peer_stats::{PeerStats, REFERENCE_DOWNLOAD_SIZE};
 
 /// Session type
 #[derive(Copy, Clone, Debug)]
@@ -34,6 +45,8 @@ pub enum SessionStatus {
 
 /// Sessions struct contains:
 /// - server address used to listen to incoming connections
+/// - external address under which the server is reachable (e.g. when behind a NAT)
+/// - external addresses reported by the peers
 /// - list of inbound sessions parametrized with their reference (T)
 /// - list of consolidated outbound sessions parametrized with their reference(T)
 /// - list of unconsolidated outbound sessions parametrized with their reference(T)
@@ -43,6 +56,10 @@ where
 {
     /// Server address listening to incoming connections
     pub server_address: Option<SocketAddr>,
+    /// External address under which the server is reachable by other peers, if known
+    pub external_address: Option<SocketAddr>,
+    /// External IP addresses reported by the peers in their handshakes
+    pub external_reports: ExternalAddressReports,
     /// Inbound sessions: __untrusted__ peers that connect to the server
     pub inbound: BoundedSessions<T>,
     /// Outbound consolidated sessions: __known__ peer sessions that the node is connected to (in
@@ -63,6 +80,8 @@ where
     fn default() -> Self {
         Self {
             server_address: None,
+            external_address: None,
+            external_reports: ExternalAddressReports::default(),
             inbound: BoundedSessions::default(),
             outbound_consolidated: BoundedSessions::default(),
             outbound_unconsolidated: BoundedSessions::default(),
@@ -93,6 +112,31 @@ where
     pub fn set_server_address(&mut self, server_address: SocketAddr) {
         self.server_address = Some(server_address);
     }
+    /// Method to set the external address
+    pub fn set_external_address(&mut self, external_address: SocketAddr) {
+        self.external_address = Some(external_address);
+    }
+    /// Method to record the external IP address of the node reported by a peer
+    /// Returns true if the address to be advertised has changed
+    pub fn record_external_address(&mut self, reporter: IpAddr, reported: IpAddr) -> bool {
+        let previous = self.advertised_address();
+        self.external_reports.record(reporter, reported);
+
+        self.advertised_address() != previous
+    }
+    /// Method to get the address to be advertised to other peers: the external address if
+    /// known (e.g. through port mapping), otherwise the external IP address reported by most
+    /// peers along with the server port, otherwise the server address
+    pub fn advertised_address(&self) -> Option<SocketAddr> {
+        self.external_address
+            .or_else(|| {
+                let server_address = self.server_address?;
+                self.external_reports
+                    .most_reported()
+                    .map(|ip| SocketAddr::new(ip, server_address.port()))
+            })
+            .or(self.server_address)
+    }
     /// Method to set the sessions limits
     pub fn set_limits(&mut self, inbound_limit: u16, outbound_consolidated_limit: u16) {
         self.inbound.set_limit(inbound_limit);
@@ -115,19 +159,40 @@ where
             .collection
             .contains_key(&candidate_addr);
 
-        // Check if address is the server address
+        // Check if address is the server address (or the external address of the server)
         let is_server = self
             .server_address
-            .map(|address| address == candidate_addr)
-            .unwrap_or(false);
+            .into_iter()
+            .chain(self.advertised_address())
+            .any(|address| address == candidate_addr);
+
+        // Check if there is already an outbound session with a peer of the same network group
+        let is_same_group = self
+            .get_outbound_network_groups()
+            .contains(&network_group(&candidate_addr));
 
-        // Return true if the address has not been used as outbound session or server address
-        !is_outbound_consolidated && !is_outbound_unconsolidated && !is_server
+        // Return true if the address has not been used as outbound session or server address,
+        // and its network group is not used by any outbound session
+        !is_outbound_consolidated && !is_outbound_unconsolidated && !is_server && !is_same_group
+    }
+    /// Method to get the network groups of the outbound sessions (consolidated or
+    /// unconsolidated)
+    pub fn get_outbound_network_groups(&self) -> HashSet<NetworkGroup> {
+        self.outbound_consolidated
+            .collection
+            .keys()
+            .chain(self.outbound_unconsolidated.collection.keys())
+            .map(network_group)
+            .collect()
     }
     /// Method to get total number of outbound peers
     pub fn get_num_outbound_sessions(&self) -> usize {
         self.outbound_consolidated.collection.len() + self.outbound_unconsolidated.collection.len()
     }
+    /// Method to get number of consolidated outbound peers
+    pub fn get_num_outbound_consolidated_sessions(&self) -> usize {
+        self.outbound_consolidated.collection.len()
+    }
     /// Method to get number of inbound peers
     pub fn get_num_inbound_sessions(&self) -> usize {
         self.inbound.collection.len()
@@ -141,6 +206,16 @@ where
             .map(|limit| num_outbound_sessions < limit as usize)
             .unwrap_or(true)
     }
+    /// Method to get the number of outbound sessions missing to reach the limit (1 if there is
+    /// no limit)
+    pub fn get_num_missing_outbound_sessions(&self) -> usize {
+        let num_outbound_sessions = self.get_num_outbound_sessions();
+
+        self.outbound_consolidated
+            .limit
+            .map(|limit| (limit as usize).saturating_sub(num_outbound_sessions))
+            .unwrap_or(1)
+    }
     /// Method to get a random consolidated outbound session
     pub fn get_random_anycast_session(&self) -> Option<T> {
         // Get iterator over the values of the hashmap
@@ -161,6 +236,93 @@ where
             .nth(index)
             .map(|info| info.reference.clone())
     }
+    /// Method to get a random consolidated outbound session whose peer is not in the given list,
+    /// along with the address of the peer
+    pub fn get_random_anycast_session_excluding(
+        &self,
+        excluded: &[SocketAddr],
+    ) -> Option<(SocketAddr, T)> {
+        let candidates: Vec<(&SocketAddr, &SessionInfo<T>)> = self
+            .outbound_consolidated
+            .collection
+            .iter()
+            .filter(|(address, _)| !excluded.contains(address))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let index = thread_rng().gen_range(0, candidates.len());
+
+        candidates
+            .get(index)
+            .map(|(address, info)| (**address, info.reference.clone()))
+    }
+    /// Method to get the inbound or consolidated outbound session with a peer
+    pub fn get_consolidated_session(&self, address: SocketAddr) -> Option<T> {
+        self.outbound_consolidated
+            .collection
+            .get(&address)
+            .or_else(|| self.inbound.collection.get(&address))
+            .map(|info| info.reference.clone())
+    }
+    /// Method to get all the consolidated outbound sessions, ranked by their expected delay to
+    /// download a range of blocks (lowest first)
+    /// Sessions without statistics yet are ranked last, in random order
+    pub fn get_ranked_consolidated_outbound_sessions(&self) -> Vec<T> {
+        let mut ranked: Vec<(Option<f64>, T)> = self
+            .outbound_consolidated
+            .collection
+            .values()
+            .map(|info| {
+                (
+                    info.stats.expected_delay(REFERENCE_DOWNLOAD_SIZE),
+                    info.reference.clone(),
+                )
+            })
+            .collect();
+
+        // Shuffle before the (stable) sort so that ties are broken randomly
+        thread_rng().shuffle(&mut ranked);
+        ranked.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+
+        ranked.into_iter().map(|(_, reference)| reference).collect()
+    }
+    /// Method to get the statistics of an inbound or a consolidated outbound session
+    fn get_stats_mut(&mut self, address: SocketAddr) -> Option<&mut PeerStats> {
+        match self.outbound_consolidated.collection.get_mut(&address) {
+            Some(info) => Some(&mut info.stats),
+            None => self
+                .inbound
+                .collection
+                .get_mut(&address)
+                .map(|info| &mut info.stats),
+        }
+    }
+    /// Method to record the round trip time of a ping/pong exchange with a peer
+    /// Returns false if there is no inbound or consolidated outbound session with the peer
+    pub fn record_latency(&mut self, address: SocketAddr, latency: Duration) -> bool {
+        self.get_stats_mut(address)
+            .map(|stats| stats.record_latency(latency))
+            .is_some()
+    }
+    /// Method to record the number of bytes of the blocks received from a peer in a period of
+    /// time
+    /// Returns false if there is no inbound or consolidated outbound session with the peer
+    pub fn record_throughput(
+        &mut self,
+        address: SocketAddr,
+        bytes: usize,
+        elapsed: Duration,
+    ) -> bool {
+        self.get_stats_mut(address)
+            .map(|stats| stats.record_throughput(bytes, elapsed))
+            .is_some()
+    }
     /// Method to get all the consolidated outbound sessions
     pub fn get_all_consolidated_outbound_sessions<'a>(&'a self) -> impl Iterator<Item = &T> + 'a {
         self.outbound_consolidated
@@ -168,6 +330,25 @@ where
             .values()
             .map(|info| &info.reference)
     }
+    /// Method to keep only the sessions whose reference satisfies a predicate (e.g. the sessions
+    /// which are still alive), returning the number of removed sessions
+    pub fn retain<F>(&mut self, f: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut removed = 0;
+        for sessions in &mut [
+            &mut self.inbound,
+            &mut self.outbound_consolidated,
+            &mut self.outbound_unconsolidated,
+        ] {
+            let before = sessions.collection.len();
+            sessions.collection.retain(|_, info| f(&info.reference));
+            removed += before - sessions.collection.len();
+        }
+
+        removed
+    }
     /// Method to insert a new session
     pub fn register_session(
         &mut self,
@@ -0,0 +1,80 @@
+//! Library for keeping the latency and throughput statistics of the peers, used to rank them
+use std::time::Duration;
+
+/// Weight of every new sample in the smoothed statistics
+pub const STATS_SMOOTHING_FACTOR: f64 = 0.25;
+
+/// Number of bytes used to compare the peers (roughly the size of a range of blocks)
+pub const REFERENCE_DOWNLOAD_SIZE: usize = 64 * 1024;
+
+/// PeerStats struct contains:
+/// - the smoothed round trip time of the ping/pong exchanges with the peer
+/// - the smoothed throughput of the blocks received from the peer
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PeerStats {
+    /// Smoothed latency (round trip time), if any ping/pong exchange has completed
+    pub latency: Option<Duration>,
+    /// Smoothed throughput (in bytes per second), if any block has been received
+    pub throughput: Option<f64>,
+}
+
+impl PeerStats {
+    /// Method to record the round trip time of a ping/pong exchange
+    pub fn record_latency(&mut self, latency: Duration) {
+        let sample = duration_as_secs(latency);
+        let smoothed = self
+            .latency
+            .map(|previous| smooth(duration_as_secs(previous), sample))
+            .unwrap_or(sample);
+
+        self.latency = Some(secs_as_duration(smoothed));
+    }
+
+    /// Method to record the number of bytes received from the peer in a period of time
+    /// Periods of zero length are ignored
+    pub fn record_throughput(&mut self, bytes: usize, elapsed: Duration) {
+        let elapsed = duration_as_secs(elapsed);
+        if elapsed <= 0.0 {
+            return;
+        }
+        let sample = bytes as f64 / elapsed;
+
+        self.throughput = Some(
+            self.throughput
+                .map(|previous| smooth(previous, sample))
+                .unwrap_or(sample),
+        );
+    }
+
+    /// Method to estimate the time (in seconds) needed to download a number of bytes from the
+    /// peer: its latency plus the transfer time at its throughput (if known)
+    /// Returns None if the latency of the peer is not known yet
+    pub fn expected_delay(&self, bytes: usize) -> Option<f64> {
+        self.latency.map(|latency| {
+            let transfer = self
+                .throughput
+                .filter(|throughput| *throughput > 0.0)
+                .map(|throughput| bytes as f64 / throughput)
+                .unwrap_or(0.0);
+
+            duration_as_secs(latency) + transfer
+        })
+    }
+}
+
+/// Exponentially weighted moving average of a statistic
+fn smooth(previous: f64, sample: f64) -> f64 {
+    previous * (1.0 - STATS_SMOOTHING_FACTOR) + sample * STATS_SMOOTHING_FACTOR
+}
+
+/// Convert a duration into seconds
+fn duration_as_secs(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) * 1e-9
+}
+
+/// Convert seconds into a duration
+fn secs_as_duration(secs: f64) -> Duration {
+    let secs = secs.max(0.0);
+
+    Duration::new(secs.trunc() as u64, (secs.fract() * 1e9) as u32)
+}
@@ -0,0 +1,93 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use witnet_p2p::bans::*;
+
+fn ip(last: u8) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::new(10, 0, 0, last))
+}
+
+fn ban(address: IpAddr, until: Option<i64>) -> Ban {
+    Ban {
+        address,
+        until,
+        reason: "test".to_string(),
+    }
+}
+
+/// Check that bans last until their expiration time
+#[test]
+fn p2p_bans_ban_and_expire() {
+    let mut bans = Bans::default();
+    bans.ban(ip(1), Some(100), "flooding".to_string());
+    bans.ban(ip(2), None, "invalid blocks".to_string());
+
+    assert!(bans.is_banned(ip(1), 99));
+    assert!(!bans.is_banned(ip(1), 100));
+    assert!(bans.is_banned(ip(2), i64::max_value()));
+    assert!(!bans.is_banned(ip(3), 0));
+
+    assert_eq!(bans.remove_expired(100), 1);
+    assert_eq!(bans.len(), 1);
+}
+
+/// Check that IPv4-mapped IPv6 addresses are banned as IPv4 addresses
+#[test]
+fn p2p_bans_normalize_address() {
+    let mut bans = Bans::default();
+    let mapped = IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0xffff, 0x0a00, 0x0001));
+    bans.ban(mapped, None, String::new());
+
+    assert!(bans.is_banned(ip(1), 0));
+    assert!(bans.unban(ip(1)));
+    assert!(!bans.unban(mapped));
+    assert!(bans.is_empty());
+}
+
+/// Check that only the bans in force are exported
+#[test]
+fn p2p_bans_export() {
+    let mut bans = Bans::default();
+    bans.ban(ip(2), None, "test".to_string());
+    bans.ban(ip(1), Some(100), "test".to_string());
+    bans.ban(ip(3), Some(10), "test".to_string());
+
+    assert_eq!(
+        bans.export(50),
+        vec![ban(ip(1), Some(100)), ban(ip(2), None)]
+    );
+}
+
+/// Check that importing bans never shortens an existing ban
+#[test]
+fn p2p_bans_import() {
+    let mut bans = Bans::default();
+    bans.ban(ip(1), Some(100), "test".to_string());
+    bans.ban(ip(2), None, "test".to_string());
+    bans.ban(ip(3), Some(10), "test".to_string());
+
+    let imported = bans.import(
+        vec![
+            // Extends the ban
+            ban(ip(1), Some(200)),
+            // Does not shorten a permanent ban
+            ban(ip(2), Some(200)),
+            // Replaces an expired ban
+            ban(ip(3), Some(60)),
+            // Already expired
+            ban(ip(4), Some(40)),
+            ban(ip(5), None),
+        ],
+        50,
+    );
+
+    assert_eq!(imported, vec![ip(1), ip(3), ip(5)]);
+    assert_eq!(
+        bans.export(50),
+        vec![
+            ban(ip(1), Some(200)),
+            ban(ip(2), None),
+            ban(ip(3), Some(60)),
+            ban(ip(5), None),
+        ]
+    );
+}
@@ -0,0 +1,155 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use witnet_p2p::download::*;
+
+fn peer(port: u16) -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port)
+}
+
+/// Block download with ranges of 2 blocks, a window of 2 ranges and a 10 seconds timeout
+fn block_download() -> BlockDownload<u32, String> {
+    BlockDownload::new(2, 2, Duration::from_secs(10))
+}
+
+/// Check that duplicate blocks are only enqueued once
+#[test]
+fn p2p_download_enqueue_duplicates() {
+    let mut download = block_download();
+
+    assert!(download.is_empty());
+    assert_eq!(download.enqueue(vec![1, 2, 3]), 3);
+    assert_eq!(download.enqueue(vec![3, 4]), 1);
+
+    assert_eq!(download.len(), 4);
+    assert!(download.is_wanted(&4));
+    assert!(!download.is_wanted(&5));
+}
+
+/// Check that different peers get non-overlapping ranges, bounded by their window
+#[test]
+fn p2p_download_assign_non_overlapping_ranges() {
+    let mut download = block_download();
+    let now = Instant::now();
+    download.enqueue(1..=10);
+
+    // Peer A fills its window with the first two ranges
+    assert_eq!(download.assign(peer(1), now), vec![vec![1, 2], vec![3, 4]]);
+    assert_eq!(download.num_in_flight(peer(1)), 2);
+    assert!(download.assign(peer(1), now).is_empty());
+
+    // Peer B gets the next ranges
+    assert_eq!(download.assign(peer(2), now), vec![vec![5, 6], vec![7, 8]]);
+
+    // Peer C gets the last range
+    assert_eq!(download.assign(peer(3), now), vec![vec![9, 10]]);
+    assert!(!download.has_pending());
+}
+
+/// Check that blocks are applied in order, no matter the order in which they are received
+#[test]
+fn p2p_download_apply_in_order() {
+    let mut download = block_download();
+    let now = Instant::now();
+    download.enqueue(1..=4);
+    download.assign(peer(1), now);
+
+    // Blocks after a missing block cannot be applied yet
+    assert_eq!(download.receive(3, "3".to_string()), Delivery::Accepted);
+    assert_eq!(
+        download.receive(4, "4".to_string()),
+        Delivery::RangeCompleted
+    );
+    assert!(download.take_ready().is_empty());
+
+    assert_eq!(download.receive(2, "2".to_string()), Delivery::Accepted);
+    assert!(download.take_ready().is_empty());
+
+    assert_eq!(
+        download.receive(1, "1".to_string()),
+        Delivery::RangeCompleted
+    );
+    let ready: Vec<u32> = download.take_ready().into_iter().map(|(h, _)| h).collect();
+    assert_eq!(ready, vec![1, 2, 3, 4]);
+
+    assert!(download.is_empty());
+    assert_eq!(download.num_in_flight(peer(1)), 0);
+}
+
+/// Check that unrequested and repeated blocks are rejected
+#[test]
+fn p2p_download_receive_unexpected() {
+    let mut download = block_download();
+    download.enqueue(vec![1]);
+    download.assign(peer(1), Instant::now());
+
+    assert_eq!(download.receive(7, "7".to_string()), Delivery::Unexpected);
+    assert_eq!(
+        download.receive(1, "1".to_string()),
+        Delivery::RangeCompleted
+    );
+    assert_eq!(download.receive(1, "1".to_string()), Delivery::Unexpected);
+}
+
+/// Check that stalled ranges are requested to another peer before the rest of pending ranges
+#[test]
+fn p2p_download_reassign_stalled() {
+    let mut download = block_download();
+    let start = Instant::now();
+    download.enqueue(1..=6);
+
+    assert_eq!(
+        download.assign(peer(1), start),
+        vec![vec![1, 2], vec![3, 4]]
+    );
+    download.receive(3, "3".to_string());
+
+    // Nothing stalls before the timeout
+    assert_eq!(download.reassign_stalled(start + Duration::from_secs(5)), 0);
+
+    // Both ranges of peer A stall
+    assert_eq!(
+        download.reassign_stalled(start + Duration::from_secs(10)),
+        2
+    );
+    assert_eq!(download.num_in_flight(peer(1)), 0);
+
+    // Peer B gets the stalled ranges (without the blocks already received) first
+    let later = start + Duration::from_secs(11);
+    assert_eq!(download.assign(peer(2), later), vec![vec![1, 2], vec![4]]);
+
+    // Peer A does not get its stalled ranges back while there are other ranges
+    assert_eq!(download.assign(peer(1), later), vec![vec![5, 6]]);
+}
+
+/// Check that blocks delivered late by a stalled peer are still accepted
+#[test]
+fn p2p_download_late_delivery() {
+    let mut download = block_download();
+    let start = Instant::now();
+    download.enqueue(1..=2);
+
+    download.assign(peer(1), start);
+    download.reassign_stalled(start + Duration::from_secs(10));
+
+    assert_eq!(download.receive(1, "1".to_string()), Delivery::Accepted);
+    assert_eq!(download.receive(2, "2".to_string()), Delivery::Accepted);
+    assert!(!download.has_pending());
+    assert_eq!(download.take_ready().len(), 2);
+}
+
+/// Check that the ranges of a removed peer are given back
+#[test]
+fn p2p_download_remove_peer() {
+    let mut download = block_download();
+    let now = Instant::now();
+    download.enqueue(1..=6);
+
+    download.assign(peer(1), now);
+    assert_eq!(download.remove_peer(peer(1)), 2);
+    assert_eq!(download.assign(peer(2), now), vec![vec![1, 2], vec![3, 4]]);
+
+    download.clear();
+    assert!(download.is_empty());
+    assert!(!download.has_pending());
+}
@@ -0,0 +1,68 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use witnet_p2p::sessions::{external_address::*, Sessions};
+
+fn ip(last: u8) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::new(203, 0, 113, last))
+}
+
+/// Check that an address is only used once enough peers agree on it
+#[test]
+fn p2p_external_address_most_reported() {
+    let mut reports = ExternalAddressReports::default();
+    assert_eq!(reports.most_reported(), None);
+
+    assert!(reports.record(ip(1), ip(100)));
+    assert_eq!(reports.most_reported(), None);
+
+    assert!(reports.record(ip(2), ip(100)));
+    assert!(reports.record(ip(3), ip(200)));
+    assert_eq!(reports.most_reported(), Some(ip(100)));
+}
+
+/// Check that every peer has a single vote and meaningless addresses are ignored
+#[test]
+fn p2p_external_address_single_vote_per_peer() {
+    let mut reports = ExternalAddressReports::default();
+
+    assert!(reports.record(ip(1), ip(100)));
+    assert!(reports.record(ip(1), ip(100)));
+    assert_eq!(reports.most_reported(), None);
+
+    assert!(!reports.record(ip(2), IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))));
+    assert!(!reports.record(ip(3), IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0))));
+    assert_eq!(reports.most_reported(), None);
+}
+
+/// Check that the number of reporters is bounded
+#[test]
+fn p2p_external_address_bounded_reporters() {
+    let mut reports = ExternalAddressReports::default();
+    for i in 0..MAX_EXTERNAL_ADDRESS_REPORTERS {
+        let reporter = IpAddr::V4(Ipv4Addr::new(10, 0, 0, i as u8));
+        assert!(reports.record(reporter, ip(100)));
+    }
+
+    assert!(!reports.record(ip(1), ip(200)));
+    assert_eq!(reports.most_reported(), Some(ip(100)));
+}
+
+/// Check that the reported address is advertised along with the server port
+#[test]
+fn p2p_external_address_advertised() {
+    let mut sessions = Sessions::<String>::default();
+    let server_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 21337);
+    sessions.set_server_address(server_address);
+
+    assert!(!sessions.record_external_address(ip(1), ip(100)));
+    assert!(sessions.record_external_address(ip(2), ip(100)));
+    assert_eq!(
+        sessions.advertised_address(),
+        Some(SocketAddr::new(ip(100), 21337))
+    );
+
+    // An address learnt through port mapping takes precedence
+    let mapped = SocketAddr::new(ip(50), 31337);
+    sessions.set_external_address(mapped);
+    assert_eq!(sessions.advertised_address(), Some(mapped));
+}
@@ -1,6 +1,21 @@
+/// Ban list library tests
+pub mod bans;
+
+/// Block download library tests
+pub mod download;
+
 /// Peers library tests
 pub mod peers;
 
+/// Request tracker library tests
+pub mod requests;
+
+/// Peer statistics library tests
+pub mod peer_stats;
+
+/// External address discovery library tests
+pub mod external_address;
+
 /// Bounded Sessions library tests
 pub mod bounded_sessions;
 
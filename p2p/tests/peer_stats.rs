@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use witnet_p2p::sessions::peer_stats::*;
+
+/// Check that the first sample is taken as is and the next ones are smoothed
+#[test]
+fn p2p_peer_stats_record_latency() {
+    let mut stats = PeerStats::default();
+    assert_eq!(stats.latency, None);
+
+    stats.record_latency(Duration::from_millis(100));
+    assert_eq!(stats.latency, Some(Duration::from_millis(100)));
+
+    stats.record_latency(Duration::from_millis(500));
+    let latency = stats.latency.unwrap();
+    assert!(latency > Duration::from_millis(199) && latency < Duration::from_millis(201));
+}
+
+/// Check that the throughput is calculated in bytes per second and empty periods are ignored
+#[test]
+fn p2p_peer_stats_record_throughput() {
+    let mut stats = PeerStats::default();
+
+    stats.record_throughput(1000, Duration::from_secs(0));
+    assert_eq!(stats.throughput, None);
+
+    stats.record_throughput(1000, Duration::from_secs(2));
+    assert_eq!(stats.throughput, Some(500.0));
+}
+
+/// Check that the expected delay takes into account both the latency and the throughput
+#[test]
+fn p2p_peer_stats_expected_delay() {
+    let mut stats = PeerStats::default();
+    assert_eq!(stats.expected_delay(1000), None);
+
+    stats.record_latency(Duration::from_secs(1));
+    assert_eq!(stats.expected_delay(1000), Some(1.0));
+
+    stats.record_throughput(1000, Duration::from_secs(1));
+    assert_eq!(stats.expected_delay(1000), Some(2.0));
+}
@@ -1,5 +1,7 @@
+use std::collections::HashSet;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
+use witnet_data_structures::chain::{KeyedSignature, Secp256k1Signature};
 use witnet_p2p::peers::*;
 
 #[test]
@@ -110,3 +112,439 @@ fn p2p_peers_get_all() {
     added_peers.sort_by(sort_by_ip_then_port);
     assert_eq!(many_peers, added_peers);
 }
+
+#[test]
+fn p2p_peers_add_from_source() {
+    // Create peers struct
+    let mut peers = Peers::default();
+
+    // Add addresses reported by a source
+    let source = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    let address1 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+    let address2 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)), 8080);
+
+    assert_eq!(
+        peers
+            .add_from_source(source, vec![(address1, 0, None), (address2, 0, None)])
+            .unwrap(),
+        vec![address1, address2]
+    );
+    // If the same addresses are reported again, they are not new
+    assert_eq!(
+        peers
+            .add_from_source(source, vec![(address1, 10, None)])
+            .unwrap(),
+        vec![]
+    );
+
+    // The most recent timestamp is kept
+    let mut all = peers.get_all_with_last_seen().unwrap();
+    all.sort_by_key(|(address, last_seen, _)| (*address, *last_seen));
+    assert_eq!(all, vec![(address1, 10, None), (address2, 0, None)]);
+
+    // Timestamps in the future are capped
+    peers
+        .add_from_source(source, vec![(address2, std::i64::MAX, None)])
+        .unwrap();
+    assert!(peers
+        .get_all_with_last_seen()
+        .unwrap()
+        .iter()
+        .all(|(_, last_seen, _)| *last_seen < std::i64::MAX));
+
+    // Only a limited number of addresses is accepted per message
+    let many_peers = (0..MAX_ADDRESSES_PER_MESSAGE + 10)
+        .map(|i| {
+            (
+                SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 1, 0, 0)), i as u16),
+                0,
+                None,
+            )
+        })
+        .collect();
+    assert_eq!(
+        peers.add_from_source(source, many_peers).unwrap().len(),
+        MAX_ADDRESSES_PER_MESSAGE
+    );
+}
+
+#[test]
+fn p2p_peers_get_random_prefers_confirmed() {
+    // Create peers struct
+    let mut peers = Peers::default();
+
+    // Add a confirmed address and a stale address reported by a single source
+    let source = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    let confirmed = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+    let reported = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)), 8080);
+    peers.add(vec![confirmed]).unwrap();
+    peers
+        .add_from_source(source, vec![(reported, 0, None)])
+        .unwrap();
+
+    let mut confirmed_count = 0;
+    for _ in 0..10000 {
+        if peers.get_random().unwrap() == Some(confirmed) {
+            confirmed_count += 1;
+        }
+    }
+
+    // The confirmed address has a weight 256 times higher than the reported one
+    assert!(
+        confirmed_count > 9900,
+        "Get random should prefer confirmed addresses"
+    );
+}
+
+/// Signature by the origin with the given public key (the tests do not check its validity)
+fn origin_signature(key: u8) -> Option<KeyedSignature> {
+    Some(KeyedSignature {
+        public_key: vec![key; 33],
+        signature: Secp256k1Signature {
+            r: [key; 32],
+            s: [key; 32],
+            v: 0,
+        },
+    })
+}
+
+#[test]
+fn p2p_peers_add_from_source_signed() {
+    let mut peers = Peers::default();
+    let source = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    let other_source = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+    let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+
+    peers
+        .add_from_source(source, vec![(address, 10, origin_signature(1))])
+        .unwrap();
+    assert_eq!(
+        peers.get_all_with_last_seen().unwrap(),
+        vec![(address, 10, origin_signature(1))]
+    );
+
+    // Unsigned reports and reports signed by other keys do not refresh a signed address
+    peers
+        .add_from_source(other_source, vec![(address, 20, None)])
+        .unwrap();
+    peers
+        .add_from_source(other_source, vec![(address, 30, origin_signature(2))])
+        .unwrap();
+    assert_eq!(
+        peers.get_all_with_last_seen().unwrap(),
+        vec![(address, 10, origin_signature(1))]
+    );
+
+    // The origin can refresh it
+    peers
+        .add_from_source(other_source, vec![(address, 40, origin_signature(1))])
+        .unwrap();
+    assert_eq!(
+        peers.get_all_with_last_seen().unwrap(),
+        vec![(address, 40, origin_signature(1))]
+    );
+
+    // The signature of a timestamp in the future is dropped
+    let future = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)), 8080);
+    peers
+        .add_from_source(source, vec![(future, std::i64::MAX, origin_signature(3))])
+        .unwrap();
+    assert!(peers
+        .get_all_with_last_seen()
+        .unwrap()
+        .iter()
+        .all(|(address, _, signature)| *address != future || signature.is_none()));
+}
+
+#[test]
+fn p2p_peers_max_peers() {
+    let mut peers = Peers::default();
+    let source = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+    // A confirmed address is never evicted
+    let confirmed = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)), 8080);
+    peers.add(vec![confirmed]).unwrap();
+
+    // Fill the list with old addresses, and then report some recently seen ones
+    let address = |i: usize| {
+        SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(127, 1, (i >> 8) as u8, i as u8)),
+            8080,
+        )
+    };
+    let addresses: Vec<_> = (0..MAX_PEERS).map(|i| (address(i), 0, None)).collect();
+    for chunk in addresses.chunks(MAX_ADDRESSES_PER_MESSAGE) {
+        peers.add_from_source(source, chunk.to_vec()).unwrap();
+    }
+    assert_eq!(peers.get_all().unwrap().len(), MAX_PEERS);
+
+    let recent: Vec<_> = (MAX_PEERS..MAX_PEERS + 10)
+        .map(|i| (address(i), 1_000, None))
+        .collect();
+    assert_eq!(peers.add_from_source(source, recent).unwrap().len(), 10);
+
+    let all = peers.get_all().unwrap();
+    assert_eq!(all.len(), MAX_PEERS);
+    assert!(all.contains(&confirmed));
+    assert!((MAX_PEERS..MAX_PEERS + 10).all(|i| all.contains(&address(i))));
+}
+
+#[test]
+fn p2p_peers_normalize_address() {
+    let v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 12)), 8080);
+    let mapped: SocketAddr = "[::ffff:192.168.1.12]:8080".parse().unwrap();
+    let v6: SocketAddr = "[2001:db8::1]:8080".parse().unwrap();
+
+    assert_eq!(normalize_address(mapped), v4);
+    assert_eq!(normalize_address(v4), v4);
+    assert_eq!(normalize_address(v6), v6);
+
+    // The same peer is only stored once, no matter its representation
+    let mut peers = Peers::default();
+    peers.add(vec![v4]).unwrap();
+    assert_eq!(peers.add(vec![mapped]).unwrap(), vec![v4]);
+    assert_eq!(peers.get_all().unwrap(), vec![v4]);
+    assert_eq!(peers.remove(&[mapped]).unwrap(), vec![v4]);
+}
+
+#[test]
+fn p2p_peers_get_random_with_families() {
+    let mut peers = Peers::default();
+    let v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+    let v6: SocketAddr = "[::1]:8080".parse().unwrap();
+    peers.add(vec![v4, v6]).unwrap();
+
+    let ipv6_only = AddressFamilies::from_listen_addresses(&[v6]);
+    assert_eq!(
+        ipv6_only,
+        AddressFamilies {
+            ipv4: false,
+            ipv6: true
+        }
+    );
+    assert_eq!(
+        AddressFamilies::from_listen_addresses(&[]),
+        AddressFamilies::all()
+    );
+
+    for _ in 0..100 {
+        assert_eq!(peers.get_random_with_families(ipv6_only).unwrap(), Some(v6));
+    }
+
+    // No address of the given families
+    peers.remove(&[v6]).unwrap();
+    assert_eq!(peers.get_random_with_families(ipv6_only).unwrap(), None);
+}
+
+#[test]
+fn p2p_peers_is_advertisable() {
+    assert!(is_advertisable(&"203.0.113.7:21337".parse().unwrap()));
+    assert!(is_advertisable(&"[2001:db8::1]:21337".parse().unwrap()));
+    assert!(!is_advertisable(&"0.0.0.0:21337".parse().unwrap()));
+    assert!(!is_advertisable(&"127.0.0.1:21337".parse().unwrap()));
+    assert!(!is_advertisable(
+        &"[::ffff:127.0.0.1]:21337".parse().unwrap()
+    ));
+    assert!(!is_advertisable(&"[::]:21337".parse().unwrap()));
+    assert!(!is_advertisable(&"203.0.113.7:0".parse().unwrap()));
+}
+
+#[test]
+fn p2p_peers_network_group() {
+    let group = |address: &str| network_group(&address.parse().unwrap());
+
+    // Public IPv4 addresses are grouped by their /16 prefix
+    assert_eq!(group("203.0.113.7:21337"), NetworkGroup::Ipv4([203, 0]));
+    assert_eq!(group("203.0.200.1:8080"), group("203.0.113.7:21337"));
+    assert_ne!(group("203.1.113.7:21337"), group("203.0.113.7:21337"));
+    assert_eq!(
+        group("[::ffff:203.0.113.7]:21337"),
+        group("203.0.113.7:21337")
+    );
+
+    // Public IPv6 addresses are grouped by their /32 prefix
+    assert_eq!(
+        group("[2001:db8::1]:21337"),
+        NetworkGroup::Ipv6([0x2001, 0x0db8])
+    );
+    assert_eq!(group("[2001:db8:1::1]:21337"), group("[2001:db8::1]:21337"));
+
+    // Every address which is not publicly routable is a group of its own
+    let local: SocketAddr = "127.0.0.1:21337".parse().unwrap();
+    assert_eq!(network_group(&local), NetworkGroup::Local(local));
+    assert_ne!(group("127.0.0.1:21338"), group("127.0.0.1:21337"));
+    assert_ne!(group("192.168.1.1:21337"), group("192.168.1.2:21337"));
+    assert_ne!(group("[fd00::1]:21337"), group("[fd00::2]:21337"));
+}
+
+#[test]
+fn p2p_peers_get_diverse() {
+    let mut peers = Peers::default();
+    let same_group: Vec<SocketAddr> = (1..=10)
+        .map(|i| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(203, 0, 113, i)), 21337))
+        .collect();
+    let other_group = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1)), 21337);
+    peers.add(same_group.clone()).unwrap();
+    peers.add(vec![other_group]).unwrap();
+
+    for _ in 0..100 {
+        // Only one address is selected from every network group
+        let selected = peers
+            .get_diverse(5, AddressFamilies::all(), &HashSet::new())
+            .unwrap();
+        assert_eq!(selected.len(), 2);
+        assert!(selected.contains(&other_group));
+        assert!(selected.iter().any(|address| same_group.contains(address)));
+
+        // The addresses of the avoided groups are never selected
+        let avoided = vec![network_group(&other_group)].into_iter().collect();
+        let selected = peers
+            .get_diverse(5, AddressFamilies::all(), &avoided)
+            .unwrap();
+        assert_eq!(selected.len(), 1);
+        assert!(same_group.contains(&selected[0]));
+    }
+
+    assert!(peers
+        .get_diverse(0, AddressFamilies::all(), &HashSet::new())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn p2p_peers_retry_delay() {
+    assert_eq!(retry_delay(0), 0);
+    assert_eq!(retry_delay(1), RETRY_BASE_DELAY);
+    assert_eq!(retry_delay(2), 2 * RETRY_BASE_DELAY);
+    assert_eq!(retry_delay(3), 4 * RETRY_BASE_DELAY);
+    assert_eq!(retry_delay(1000), RETRY_MAX_DELAY);
+    assert_eq!(retry_delay(u32::max_value()), RETRY_MAX_DELAY);
+}
+
+#[test]
+fn p2p_peers_record_attempt() {
+    let mut peers = Peers::default();
+    let address: SocketAddr = "203.0.113.7:21337".parse().unwrap();
+    let unknown: SocketAddr = "203.0.113.8:21337".parse().unwrap();
+    peers.add(vec![address]).unwrap();
+
+    assert!(!peers
+        .record_attempt(unknown, AttemptOutcome::Refused, 100)
+        .unwrap());
+
+    assert!(peers
+        .record_attempt(address, AttemptOutcome::Refused, 100)
+        .unwrap());
+    assert!(peers
+        .record_attempt(address, AttemptOutcome::Timeout, 200)
+        .unwrap());
+    let stats = peers.get_attempt_stats(&address).unwrap().clone();
+    assert_eq!(stats.failures, 2);
+    assert_eq!(stats.failing_since, Some(100));
+    assert_eq!(stats.last_attempt, Some((200, AttemptOutcome::Timeout)));
+    assert_eq!(stats.retry_at(), 200 + 2 * RETRY_BASE_DELAY);
+
+    // Overwriting the address keeps the statistics of its attempts
+    peers.add(vec![address]).unwrap();
+    assert_eq!(peers.get_attempt_stats(&address), Some(&stats));
+
+    // A successful attempt resets the failures
+    peers
+        .record_attempt(address, AttemptOutcome::Success, 300)
+        .unwrap();
+    let stats = peers.get_attempt_stats(&address).unwrap();
+    assert_eq!(stats.failures, 0);
+    assert_eq!(stats.failing_since, None);
+    assert_eq!(stats.last_success, Some(300));
+    assert_eq!(stats.retry_at(), 300);
+}
+
+#[test]
+fn p2p_peers_get_random_skips_backing_off() {
+    let mut peers = Peers::default();
+    let failing: SocketAddr = "203.0.113.7:21337".parse().unwrap();
+    let reachable: SocketAddr = "198.51.100.1:21337".parse().unwrap();
+    peers.add(vec![failing, reachable]).unwrap();
+
+    // The failing address backs off from the current time
+    let now = witnet_util::timestamp::get_timestamp();
+    peers
+        .record_attempt(failing, AttemptOutcome::HandshakeFailed, now)
+        .unwrap();
+
+    for _ in 0..100 {
+        assert_eq!(peers.get_random().unwrap(), Some(reachable));
+        assert_eq!(
+            peers
+                .get_diverse(5, AddressFamilies::all(), &HashSet::new())
+                .unwrap(),
+            vec![reachable]
+        );
+    }
+
+    // Once its backoff expires, the address can be selected again
+    peers
+        .record_attempt(failing, AttemptOutcome::Refused, now - RETRY_MAX_DELAY)
+        .unwrap();
+    assert_eq!(
+        peers
+            .get_diverse(5, AddressFamilies::all(), &HashSet::new())
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn p2p_peers_remove_unreachable() {
+    let day = 24 * 60 * 60;
+    let mut peers = Peers::default();
+    let dead: SocketAddr = "203.0.113.7:21337".parse().unwrap();
+    let flaky: SocketAddr = "203.0.113.8:21337".parse().unwrap();
+    let untried: SocketAddr = "203.0.113.9:21337".parse().unwrap();
+    peers.add(vec![dead, flaky, untried]).unwrap();
+
+    peers
+        .record_attempt(dead, AttemptOutcome::Refused, 0)
+        .unwrap();
+    peers
+        .record_attempt(dead, AttemptOutcome::Timeout, 6 * day)
+        .unwrap();
+    peers
+        .record_attempt(flaky, AttemptOutcome::Refused, 0)
+        .unwrap();
+    peers
+        .record_attempt(flaky, AttemptOutcome::Success, day)
+        .unwrap();
+    peers
+        .record_attempt(flaky, AttemptOutcome::Refused, 2 * day)
+        .unwrap();
+
+    assert!(peers
+        .remove_unreachable(7 * day - 1, 7 * day)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        peers.remove_unreachable(7 * day, 7 * day).unwrap(),
+        vec![dead]
+    );
+
+    let mut remaining = peers.get_all().unwrap();
+    remaining.sort();
+    assert_eq!(remaining, vec![flaky, untried]);
+}
+
+#[test]
+fn p2p_peers_restore() {
+    let address: SocketAddr = "203.0.113.7:21337".parse().unwrap();
+    let mut stored = Peers::default();
+    stored.add(vec![address]).unwrap();
+    stored
+        .record_attempt(address, AttemptOutcome::Refused, 100)
+        .unwrap();
+
+    let mut peers = Peers::default();
+    peers.add(vec![address]).unwrap();
+    assert_eq!(peers.restore(stored).unwrap(), vec![address]);
+    assert_eq!(peers.get_attempt_stats(&address).unwrap().failures, 1);
+}
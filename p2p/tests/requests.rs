@@ -0,0 +1,109 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use witnet_p2p::requests::*;
+
+fn peer(port: u16) -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port)
+}
+
+/// Request tracker with a 10 seconds timeout, 2 attempts per item and 2 failures per peer
+fn request_tracker() -> RequestTracker<u32> {
+    RequestTracker::new(Duration::from_secs(10), 2, 2)
+}
+
+/// Check that items are only tracked once
+#[test]
+fn p2p_requests_request_duplicates() {
+    let mut requests = request_tracker();
+    let now = Instant::now();
+
+    assert!(requests.is_empty());
+    assert_eq!(requests.request(peer(1), vec![1, 2], now), 2);
+    assert_eq!(requests.request(peer(2), vec![2, 3], now), 1);
+
+    assert_eq!(requests.len(), 3);
+    assert!(requests.is_requested(&3));
+    assert!(!requests.is_requested(&4));
+}
+
+/// Check that delivered items are no longer tracked nor expired
+#[test]
+fn p2p_requests_deliver() {
+    let mut requests = request_tracker();
+    let start = Instant::now();
+    requests.request(peer(1), vec![1, 2], start);
+
+    assert!(requests.deliver(peer(1), &1));
+    assert!(!requests.deliver(peer(1), &1));
+    assert!(!requests.deliver(peer(1), &5));
+
+    let expired = requests.expire(start + Duration::from_secs(10));
+    assert_eq!(
+        expired,
+        vec![Expired {
+            item: 2,
+            failed_peers: vec![peer(1)],
+        }]
+    );
+    assert!(requests.is_empty());
+}
+
+/// Check that items are given up after the maximum number of attempts
+#[test]
+fn p2p_requests_expire_and_retry() {
+    let mut requests = request_tracker();
+    let start = Instant::now();
+    requests.request(peer(1), vec![1], start);
+
+    // Nothing expires before the timeout
+    assert!(requests.expire(start + Duration::from_secs(5)).is_empty());
+
+    let later = start + Duration::from_secs(10);
+    let mut expired = requests.expire(later);
+    assert_eq!(expired.len(), 1);
+    requests.retry(peer(2), expired.remove(0), later);
+    assert!(requests.is_requested(&1));
+
+    // Second attempt also fails: the item is given up
+    assert!(requests.expire(later + Duration::from_secs(10)).is_empty());
+    assert!(requests.is_empty());
+}
+
+/// Check that peers are reported after repeatedly failing to deliver items
+#[test]
+fn p2p_requests_failing_peers() {
+    let mut requests = request_tracker();
+    let start = Instant::now();
+
+    requests.request(peer(1), vec![1], start);
+    requests.expire(start + Duration::from_secs(10));
+    assert!(requests.take_failing_peers().is_empty());
+
+    // Delivering a requested item resets the failures of the peer
+    requests.request(peer(1), vec![2], start);
+    assert!(requests.deliver(peer(1), &2));
+    requests.request(peer(1), vec![3], start);
+    requests.expire(start + Duration::from_secs(10));
+    assert!(requests.take_failing_peers().is_empty());
+
+    requests.request(peer(1), vec![4], start);
+    requests.expire(start + Duration::from_secs(10));
+    assert_eq!(requests.take_failing_peers(), vec![peer(1)]);
+
+    // Peers are only reported once
+    assert!(requests.take_failing_peers().is_empty());
+}
+
+/// Check that the failures of a removed peer are forgotten
+#[test]
+fn p2p_requests_remove_peer() {
+    let mut requests = request_tracker();
+    let start = Instant::now();
+
+    requests.request(peer(1), vec![1, 2], start);
+    requests.expire(start + Duration::from_secs(10));
+    requests.remove_peer(peer(1));
+
+    assert!(requests.take_failing_peers().is_empty());
+}
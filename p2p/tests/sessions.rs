@@ -1,6 +1,7 @@
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::time::Duration;
 
+use witnet_p2p::peers::NetworkGroup;
 use witnet_p2p::sessions::*;
 
 /// Check if the sessions default initializes with empty state
@@ -36,6 +37,27 @@ fn p2p_sessions_set_server() {
     assert_eq!(sessions.server_address.unwrap(), address);
 }
 
+/// Check setting the external address
+#[test]
+fn p2p_sessions_set_external_address() {
+    // Create sessions struct
+    let mut sessions = Sessions::<String>::default();
+    assert_eq!(sessions.advertised_address(), None);
+
+    // Without an external address, the server address is advertised
+    let server_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2)), 21337);
+    sessions.set_server_address(server_address);
+    assert_eq!(sessions.advertised_address(), Some(server_address));
+
+    // The external address is advertised once it is known
+    let external_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)), 21337);
+    sessions.set_external_address(external_address);
+    assert_eq!(sessions.advertised_address(), Some(external_address));
+
+    // The external address is not eligible as outbound peer
+    assert!(!sessions.is_outbound_address_eligible(external_address));
+}
+
 /// Check setting the sessions limits
 #[test]
 fn p2p_sessions_set_limits() {
@@ -124,6 +146,39 @@ fn p2p_sessions_is_outbound_address_eligible() {
     assert!(sessions.is_outbound_address_eligible(valid_address_3));
 }
 
+/// Check that only one outbound session is opened to every network group
+#[test]
+fn p2p_sessions_outbound_network_groups() {
+    let mut sessions = Sessions::<String>::default();
+
+    let outbound_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1)), 21337);
+    sessions
+        .register_session(
+            SessionType::Outbound,
+            outbound_address,
+            "reference1".to_string(),
+        )
+        .unwrap();
+    assert_eq!(
+        sessions.get_outbound_network_groups(),
+        vec![NetworkGroup::Ipv4([203, 0])].into_iter().collect()
+    );
+
+    // Addresses in the same /16 are not eligible, even if they are different peers
+    let same_group = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(203, 0, 200, 7)), 21337);
+    assert!(!sessions.is_outbound_address_eligible(same_group));
+
+    // Addresses in other network groups are eligible
+    let other_group = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1)), 21337);
+    assert!(sessions.is_outbound_address_eligible(other_group));
+
+    // Inbound sessions do not use any network group
+    sessions
+        .register_session(SessionType::Inbound, other_group, "reference2".to_string())
+        .unwrap();
+    assert!(sessions.is_outbound_address_eligible(other_group));
+}
+
 /// Check if the sum of all outbound sessions (consolidated and unconsolidated) is returned
 #[test]
 fn p2p_sessions_get_num_outbound_sessions() {
@@ -158,6 +213,39 @@ fn p2p_sessions_get_num_outbound_sessions() {
     assert_eq!(sessions.get_num_outbound_sessions(), 2);
 }
 
+/// Check if only the consolidated outbound sessions are counted
+#[test]
+fn p2p_sessions_get_num_outbound_consolidated_sessions() {
+    // Create sessions struct
+    let mut sessions = Sessions::<String>::default();
+
+    // Register two outbound sessions and consolidate only one of them
+    let outbound_uncons_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8001);
+    let outbound_cons_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8002);
+    assert!(sessions
+        .register_session(
+            SessionType::Outbound,
+            outbound_uncons_address,
+            "reference1".to_string()
+        )
+        .is_ok());
+    assert!(sessions
+        .register_session(
+            SessionType::Outbound,
+            outbound_cons_address,
+            "reference2".to_string()
+        )
+        .is_ok());
+    assert_eq!(sessions.get_num_outbound_consolidated_sessions(), 0);
+
+    assert!(sessions
+        .consolidate_session(SessionType::Outbound, outbound_cons_address)
+        .is_ok());
+
+    // Check that only the consolidated session is counted
+    assert_eq!(sessions.get_num_outbound_consolidated_sessions(), 1);
+}
+
 /// Check the conditions upon which the outbound bootstrap is needed
 #[test]
 fn p2p_sessions_is_outbound_bootstrap_needed() {
@@ -196,6 +284,7 @@ fn p2p_sessions_is_outbound_bootstrap_needed() {
 
     // Bootstrap is always needed when there is no limit
     assert!(sessions.is_outbound_bootstrap_needed());
+    assert_eq!(sessions.get_num_missing_outbound_sessions(), 1);
 
     // Set limits
     let limit_inbound = 1;
@@ -204,6 +293,7 @@ fn p2p_sessions_is_outbound_bootstrap_needed() {
 
     // Bootstrap is needed when the limit is higher than the number of outbound sessions
     assert!(sessions.is_outbound_bootstrap_needed());
+    assert_eq!(sessions.get_num_missing_outbound_sessions(), 1);
 
     // Set limits
     let limit_inbound = 1;
@@ -220,6 +310,7 @@ fn p2p_sessions_is_outbound_bootstrap_needed() {
 
     // Bootstrap is not needed when the limit is smaller than the number of outbound sessions
     assert!(!sessions.is_outbound_bootstrap_needed());
+    assert_eq!(sessions.get_num_missing_outbound_sessions(), 0);
 }
 
 /// Check the function to get a random outbound consolidated session
@@ -291,6 +382,59 @@ fn p2p_sessions_get_random_anycast_session() {
     );
 }
 
+/// Check the function to get a random outbound consolidated session excluding some peers
+#[test]
+fn p2p_sessions_get_random_anycast_session_excluding() {
+    // Create sessions struct
+    let mut sessions = Sessions::<String>::default();
+
+    // Register and consolidate two outbound sessions and an inbound session
+    let address_1 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8001);
+    let address_2 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8002);
+    let address_3 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8003);
+    for (session_type, address, reference) in &[
+        (SessionType::Outbound, address_1, "reference1"),
+        (SessionType::Outbound, address_2, "reference2"),
+        (SessionType::Inbound, address_3, "reference3"),
+    ] {
+        assert!(sessions
+            .register_session(*session_type, *address, reference.to_string())
+            .is_ok());
+        assert!(sessions
+            .consolidate_session(*session_type, *address)
+            .is_ok());
+    }
+
+    // Excluded peers are never returned
+    for _ in 0..100 {
+        assert_eq!(
+            sessions.get_random_anycast_session_excluding(&[address_1]),
+            Some((address_2, "reference2".to_string()))
+        );
+    }
+    assert_eq!(
+        sessions.get_random_anycast_session_excluding(&[address_1, address_2]),
+        None
+    );
+
+    // Both inbound and consolidated outbound sessions can be looked up by address
+    assert_eq!(
+        sessions.get_consolidated_session(address_1),
+        Some("reference1".to_string())
+    );
+    assert_eq!(
+        sessions.get_consolidated_session(address_3),
+        Some("reference3".to_string())
+    );
+    assert_eq!(
+        sessions.get_consolidated_session(SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+            8004
+        )),
+        None
+    );
+}
+
 /// Check the registration of sessions
 #[test]
 fn p2p_sessions_register() {
@@ -425,3 +569,81 @@ fn p2p_sessions_consolidate() {
     assert_eq!(sessions.outbound_unconsolidated.collection.len(), 0);
     assert!(sessions.inbound.collection.get(&inbound_address).is_some());
 }
+
+/// Check that consolidated outbound sessions are ranked by their latency and throughput
+#[test]
+fn p2p_sessions_get_ranked_consolidated_outbound_sessions() {
+    // Create sessions struct
+    let mut sessions = Sessions::<String>::default();
+
+    // Register and consolidate three outbound sessions
+    let addresses: Vec<SocketAddr> = (8001..8004)
+        .map(|port| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port))
+        .collect();
+    for (i, address) in addresses.iter().enumerate() {
+        assert!(sessions
+            .register_session(SessionType::Outbound, *address, format!("reference{}", i))
+            .is_ok());
+        assert!(sessions
+            .consolidate_session(SessionType::Outbound, *address)
+            .is_ok());
+    }
+
+    // The first session has a low latency but a very low throughput
+    assert!(sessions.record_latency(addresses[0], Duration::from_millis(10)));
+    assert!(sessions.record_throughput(addresses[0], 1024, Duration::from_secs(1)));
+    // The second session has a higher latency but a high throughput
+    assert!(sessions.record_latency(addresses[1], Duration::from_millis(100)));
+    assert!(sessions.record_throughput(addresses[1], 1024 * 1024, Duration::from_secs(1)));
+    // The third session has no statistics yet
+
+    // Statistics of unknown sessions are not recorded
+    let unknown_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+    assert!(!sessions.record_latency(unknown_address, Duration::from_millis(10)));
+
+    assert_eq!(
+        sessions.get_ranked_consolidated_outbound_sessions(),
+        vec![
+            "reference1".to_string(),
+            "reference0".to_string(),
+            "reference2".to_string()
+        ]
+    );
+}
+
+/// Check that the sessions not satisfying the predicate are removed from every collection
+#[test]
+fn p2p_sessions_retain() {
+    // Create sessions struct
+    let mut sessions = Sessions::<String>::default();
+
+    // Register an inbound session, a consolidated outbound session and an unconsolidated one
+    let address = |port| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port);
+    assert!(sessions
+        .register_session(SessionType::Inbound, address(8001), "alive1".to_string())
+        .is_ok());
+    assert!(sessions
+        .register_session(SessionType::Outbound, address(8002), "dead1".to_string())
+        .is_ok());
+    assert!(sessions
+        .consolidate_session(SessionType::Outbound, address(8002))
+        .is_ok());
+    assert!(sessions
+        .register_session(SessionType::Outbound, address(8003), "alive2".to_string())
+        .is_ok());
+
+    // Remove the dead sessions
+    assert_eq!(
+        sessions.retain(|reference| reference.starts_with("alive")),
+        1
+    );
+    assert_eq!(sessions.inbound.collection.len(), 1);
+    assert_eq!(sessions.outbound_consolidated.collection.len(), 0);
+    assert_eq!(sessions.outbound_unconsolidated.collection.len(), 1);
+
+    // Nothing else to remove
+    assert_eq!(
+        sessions.retain(|reference| reference.starts_with("alive")),
+        0
+    );
+}
@@ -0,0 +1,59 @@
+//! Error type definitions for the RAD engine.
+
+use failure::Fail;
+use std::fmt;
+use witnet_util::error::WitnetResult;
+
+/// RAD Error
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "{} : at \"{}\", msg {}", kind, info, msg)]
+pub struct RadError {
+    /// Error kind
+    kind: RadErrorKind,
+    /// Parameter which caused the error
+    info: String,
+    /// Error message
+    msg: String,
+}
+
+impl RadError {
+    /// Create a RAD error based on its kind and related info.
+    pub fn new(kind: RadErrorKind, info: String, msg: String) -> Self {
+        Self { kind, info, msg }
+    }
+
+    /// Get the kind of the error
+    pub fn kind(&self) -> &RadErrorKind {
+        &self.kind
+    }
+}
+
+/// RAD Errors while resolving a data request
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RadErrorKind {
+    /// Errors when the URL of a source is not valid for its kind
+    Url,
+    /// Errors when a header of a request is not valid
+    Header,
+    /// Errors when requesting data from a HTTP(S) source
+    Http,
+    /// Errors when a script is not a valid MessagePack encoding of a RADON script
+    Decode,
+    /// Errors when a call of a script is not valid for the type of its input
+    Type,
+    /// Errors when a call of a script fails at runtime
+    Execution,
+    /// Errors when a job does not finish within its wall-clock limit
+    Timeout,
+    /// Errors when a job exceeds its resource limits (e.g. the size of the retrieved data)
+    ResourceLimit,
+}
+
+impl fmt::Display for RadErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RadError::{:?}", self)
+    }
+}
+
+/// Result type for the RAD engine.
+pub type RadResult<T> = WitnetResult<T, RadError>;
@@ -0,0 +1,170 @@
+//! RAD engine: retrieval, aggregation, consensus and delivery of the data requested by the
+//! clients, as specified in their data requests
+
+#![deny(rust_2018_idioms)]
+#![deny(non_upper_case_globals)]
+#![deny(non_camel_case_types)]
+#![deny(non_snake_case)]
+#![deny(unused_mut)]
+#![deny(missing_docs)]
+
+/// Errors of the RAD engine
+pub mod error;
+
+/// Retrieval phase
+pub mod retrieval;
+
+/// Sandbox with wall-clock and memory limits for the jobs of the witnesses
+pub mod sandbox;
+
+/// RADON operators
+pub mod operators;
+
+/// RADON scripts
+pub mod script;
+
+/// RADON types and values
+pub mod types;
+
+use std::sync::Arc;
+
+use crate::error::{RadError, RadErrorKind, RadResult};
+use crate::retrieval::cache::RetrievalCache;
+use crate::sandbox::SandboxLimits;
+use crate::script::{decode_script, execute, typecheck};
+use crate::types::{RadonType, RadonValue};
+use witnet_data_structures::chain::{Epoch, RADAggregate, RADConsensus, RADRequest, RADRetrieve};
+
+/// Type check every script of a RAD request, so that invalid requests are rejected before any
+/// witness tries to resolve them
+///
+/// The retrieve scripts take the retrieved string, and all of them must have the same output
+/// type. The aggregate script takes the array of the results of the retrievals, and the
+/// consensus script takes the array of the results of the aggregations revealed by the witnesses.
+pub fn validate_request(request: &RADRequest) -> RadResult<()> {
+    let mut retrieved = None;
+    for (position, retrieve) in request.retrieve.iter().enumerate() {
+        let output = typecheck(&decode_script(&retrieve.script)?, RadonType::String)?;
+        match retrieved {
+            Some(ref expected) if *expected != output => {
+                return Err(RadError::new(
+                    RadErrorKind::Type,
+                    format!("retrieve {}", position),
+                    format!("expected output of type {}, got {}", expected, output),
+                )
+                .into());
+            }
+            _ => retrieved = Some(output),
+        }
+    }
+
+    let retrieved = retrieved.unwrap_or(RadonType::String);
+    let aggregated = typecheck(
+        &decode_script(&request.aggregate.script)?,
+        RadonType::Array(Box::new(retrieved)),
+    )?;
+    typecheck(
+        &decode_script(&request.consensus.script)?,
+        RadonType::Array(Box::new(aggregated)),
+    )?;
+
+    Ok(())
+}
+
+/// Retrieve the data from the source of a retrieval and run its script on it
+pub fn run_retrieval(retrieve: &RADRetrieve, limits: &SandboxLimits) -> RadResult<RadonValue> {
+    let calls = decode_script(&retrieve.script)?;
+    typecheck(&calls, RadonType::String)?;
+    let retrieved = retrieval::fetch(retrieve, limits)?;
+
+    execute(&calls, RadonValue::String(retrieved))
+}
+
+/// Retrieve the data from the source of a retrieval through the cache of an epoch and run its
+/// script on it
+pub fn run_cached_retrieval(
+    retrieve: &RADRetrieve,
+    epoch: Epoch,
+    limits: &SandboxLimits,
+    cache: &RetrievalCache,
+) -> RadResult<RadonValue> {
+    let calls = decode_script(&retrieve.script)?;
+    typecheck(&calls, RadonType::String)?;
+    let retrieved = cache.fetch(retrieve, epoch, limits)?;
+
+    execute(&calls, RadonValue::String(retrieved))
+}
+
+/// Resolve a RAD request as a witness: run every retrieval and aggregate their results, all of
+/// it in the sandbox, so a source which hangs results in a `Timeout` error instead of blocking
+/// the witness
+pub fn witness(request: &RADRequest, limits: &SandboxLimits) -> RadResult<RadonValue> {
+    let request = request.clone();
+
+    sandbox::run(limits, move |limits| {
+        let retrieved = request
+            .retrieve
+            .iter()
+            .map(|retrieve| run_retrieval(retrieve, limits))
+            .collect::<RadResult<Vec<_>>>()?;
+
+        run_aggregation(retrieved, &request.aggregate)
+    })
+}
+
+/// Resolve a RAD request as a witness like `witness`, sharing the data retrieved from the sources
+/// with the other witnessing jobs of the same epoch through a cache
+pub fn witness_cached(
+    request: &RADRequest,
+    epoch: Epoch,
+    limits: &SandboxLimits,
+    cache: &Arc<RetrievalCache>,
+) -> RadResult<RadonValue> {
+    let request = request.clone();
+    let cache = Arc::clone(cache);
+
+    sandbox::run(limits, move |limits| {
+        let retrieved = request
+            .retrieve
+            .iter()
+            .map(|retrieve| run_cached_retrieval(retrieve, epoch, limits, &cache))
+            .collect::<RadResult<Vec<_>>>()?;
+
+        run_aggregation(retrieved, &request.aggregate)
+    })
+}
+
+/// Encode the result of a witness using MessagePack, so that it can be revealed
+/// A value is encoded as `[true, value]`, and an error as `[false, kind]`: only the kind of the
+/// error is encoded, as the details (e.g. the message of a HTTP error) may differ between
+/// witnesses
+pub fn encode_result(result: &RadResult<RadonValue>) -> Vec<u8> {
+    let encoded = match result {
+        Ok(value) => rmpv::Value::Array(vec![true.into(), value.to_msgpack()]),
+        Err(e) => rmpv::Value::Array(vec![false.into(), format!("{:?}", e.inner().kind()).into()]),
+    };
+    let mut bytes = vec![];
+    // Writing into a vector cannot fail
+    let _ = rmpv::encode::write_value(&mut bytes, &encoded);
+
+    bytes
+}
+
+/// Run the aggregate script on the results of the retrievals
+pub fn run_aggregation(
+    retrieved: Vec<RadonValue>,
+    aggregate: &RADAggregate,
+) -> RadResult<RadonValue> {
+    execute(
+        &decode_script(&aggregate.script)?,
+        RadonValue::Array(retrieved),
+    )
+}
+
+/// Run the consensus script on the results revealed by the witnesses
+pub fn run_consensus(revealed: Vec<RadonValue>, consensus: &RADConsensus) -> RadResult<RadonValue> {
+    execute(
+        &decode_script(&consensus.script)?,
+        RadonValue::Array(revealed),
+    )
+}
@@ -0,0 +1,218 @@
+use rmpv::Value;
+
+use super::{expect_args, float_arg, int_arg, unknown_operator};
+use crate::script::{decode_calls, execute, typecheck, RadonCall};
+use crate::types::{RadonType, RadonValue};
+
+/// `ARRAY_COUNT`
+pub const ARRAY_COUNT: u8 = 0x00;
+/// `ARRAY_EVERY`
+pub const ARRAY_EVERY: u8 = 0x01;
+/// `ARRAY_FILTER`
+pub const ARRAY_FILTER: u8 = 0x02;
+/// `ARRAY_FLATTEN`
+pub const ARRAY_FLATTEN: u8 = 0x03;
+/// `ARRAY_GET`
+pub const ARRAY_GET: u8 = 0x04;
+/// `ARRAY_MAP`
+pub const ARRAY_MAP: u8 = 0x05;
+/// `ARRAY_REDUCE`
+pub const ARRAY_REDUCE: u8 = 0x06;
+
+/// `FILTER_GT`
+pub const FILTER_GT: u8 = 0x00;
+/// `FILTER_LT`
+pub const FILTER_LT: u8 = 0x01;
+/// `FILTER_EQ`
+pub const FILTER_EQ: u8 = 0x02;
+/// `FILTER_DEV_ABS`
+pub const FILTER_DEV_ABS: u8 = 0x03;
+/// Added to a filtering function to negate it
+pub const FILTER_NOT: u8 = 0x80;
+
+/// `REDUCER_MIN`
+pub const REDUCER_MIN: u8 = 0x00;
+/// `REDUCER_MAX`
+pub const REDUCER_MAX: u8 = 0x01;
+/// `REDUCER_AVG_MEAN`
+pub const REDUCER_AVG_MEAN: u8 = 0x03;
+/// `REDUCER_AVG_MEDIAN`
+pub const REDUCER_AVG_MEDIAN: u8 = 0x05;
+
+/// Get the type of the output of a call on an `Array<T>`
+pub fn check(item: &RadonType, call: &RadonCall) -> Result<RadonType, String> {
+    let array = || RadonType::Array(Box::new(item.clone()));
+    match call.operator {
+        ARRAY_COUNT => expect_args(call, 0).map(|_| RadonType::Int),
+        ARRAY_GET => {
+            expect_args(call, 1)?;
+            int_arg(call, 0).map(|_| item.clone())
+        }
+        ARRAY_MAP => {
+            expect_args(call, 1)?;
+            let output = typecheck(&subscript(call)?, item.clone())
+                .map_err(|e| format!("invalid subscript: {}", e.inner()))?;
+
+            Ok(RadonType::Array(Box::new(output)))
+        }
+        ARRAY_FILTER => {
+            expect_args(call, 2)?;
+            numeric(item)?;
+            filter_function(call)?;
+            float_arg(call, 1).map(|_| array())
+        }
+        ARRAY_REDUCE => {
+            expect_args(call, 1)?;
+            numeric(item)?;
+            match reducer(call)? {
+                REDUCER_MIN | REDUCER_MAX => Ok(item.clone()),
+                _ => Ok(RadonType::Float),
+            }
+        }
+        _ => Err(unknown_operator(&array(), call)),
+    }
+}
+
+/// Execute a call on an `Array<T>`
+pub fn apply(items: Vec<RadonValue>, call: &RadonCall) -> Result<RadonValue, String> {
+    match call.operator {
+        ARRAY_COUNT => Ok(RadonValue::Int(items.len() as i64)),
+        ARRAY_GET => {
+            let index = int_arg(call, 0)?;
+            if index < 0 || index as u64 >= items.len() as u64 {
+                return Err(format!("index {} out of bounds", index));
+            }
+
+            Ok(items
+                .into_iter()
+                .nth(index as usize)
+                .unwrap_or(RadonValue::Null))
+        }
+        // The whole call fails if the subscript fails for any of the items
+        ARRAY_MAP => {
+            let calls = subscript(call)?;
+            items
+                .into_iter()
+                .map(|item| {
+                    execute(&calls, item).map_err(|e| format!("subscript failed: {}", e.inner()))
+                })
+                .collect::<Result<_, _>>()
+                .map(RadonValue::Array)
+        }
+        ARRAY_FILTER => {
+            let function = filter_function(call)?;
+            let operand = float_arg(call, 1)?;
+            let numbers = as_numbers(&items)?;
+            let mean = numbers.iter().sum::<f64>() / numbers.len().max(1) as f64;
+            let negate = function & FILTER_NOT != 0;
+
+            Ok(RadonValue::Array(
+                items
+                    .into_iter()
+                    .zip(numbers)
+                    .filter(|(_, x)| {
+                        let keep = match function & !FILTER_NOT {
+                            FILTER_GT => *x > operand,
+                            FILTER_LT => *x < operand,
+                            FILTER_EQ => (*x - operand).abs() < std::f64::EPSILON,
+                            _ => (*x - mean).abs() <= operand,
+                        };
+                        keep != negate
+                    })
+                    .map(|(item, _)| item)
+                    .collect(),
+            ))
+        }
+        ARRAY_REDUCE => {
+            let reducer = reducer(call)?;
+            let numbers = as_numbers(&items)?;
+            if numbers.is_empty() {
+                return Err("cannot reduce an empty array".to_string());
+            }
+            match reducer {
+                REDUCER_MIN | REDUCER_MAX => {
+                    let position = (0..numbers.len()).fold(0, |best, i| {
+                        let better = if reducer == REDUCER_MIN {
+                            numbers[i] < numbers[best]
+                        } else {
+                            numbers[i] > numbers[best]
+                        };
+                        if better {
+                            i
+                        } else {
+                            best
+                        }
+                    });
+
+                    Ok(items.into_iter().nth(position).unwrap_or(RadonValue::Null))
+                }
+                REDUCER_AVG_MEAN => Ok(RadonValue::Float(
+                    numbers.iter().sum::<f64>() / numbers.len() as f64,
+                )),
+                _ => {
+                    let mut sorted = numbers;
+                    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+                    let middle = sorted.len() / 2;
+                    let median = if sorted.len() % 2 == 0 {
+                        (sorted[middle - 1] + sorted[middle]) / 2.0
+                    } else {
+                        sorted[middle]
+                    };
+
+                    Ok(RadonValue::Float(median))
+                }
+            }
+        }
+        _ => Err(format!("unknown operator {} for type Array", call.operator)),
+    }
+}
+
+/// Decode the subscript passed as argument of a call
+fn subscript(call: &RadonCall) -> Result<Vec<RadonCall>, String> {
+    match call.args.get(0) {
+        Some(Value::Array(calls)) => {
+            decode_calls(calls).map_err(|e| format!("invalid subscript: {}", e.inner()))
+        }
+        _ => Err(format!(
+            "argument 0 of operator {} must be a subscript",
+            call.operator
+        )),
+    }
+}
+
+/// Get the filtering function of a call
+fn filter_function(call: &RadonCall) -> Result<u8, String> {
+    let function = int_arg(call, 0)?;
+    match function {
+        f if f >= 0 && f <= 0xff && (f as u8 & !FILTER_NOT) <= FILTER_DEV_ABS => Ok(f as u8),
+        f => Err(format!("unknown filtering function {}", f)),
+    }
+}
+
+/// Get the reducing function of a call
+fn reducer(call: &RadonCall) -> Result<u8, String> {
+    match int_arg(call, 0)? {
+        r @ 0..=1 | r @ 3 | r @ 5 => Ok(r as u8),
+        r => Err(format!("unknown reducing function {}", r)),
+    }
+}
+
+/// Check that the items of an array are numbers, as needed by filters and reducers
+fn numeric(item: &RadonType) -> Result<(), String> {
+    match item {
+        RadonType::Int | RadonType::Float => Ok(()),
+        _ => Err(format!("expected an array of numbers, got Array<{}>", item)),
+    }
+}
+
+/// Get the items of an array of numbers as floats
+fn as_numbers(items: &[RadonValue]) -> Result<Vec<f64>, String> {
+    items
+        .iter()
+        .map(|item| match item {
+            RadonValue::Int(x) => Ok(*x as f64),
+            RadonValue::Float(x) => Ok(*x),
+            _ => Err("expected an array of numbers".to_string()),
+        })
+        .collect()
+}
@@ -0,0 +1,28 @@
+use super::{expect_args, unknown_operator};
+use crate::script::RadonCall;
+use crate::types::{RadonType, RadonValue};
+
+/// `BOOLEAN_MATCH`
+pub const BOOLEAN_MATCH: u8 = 0x00;
+/// `BOOLEAN_NEG`
+pub const BOOLEAN_NEG: u8 = 0x01;
+/// `BOOLEAN_TOSTRING`
+pub const BOOLEAN_TOSTRING: u8 = 0x02;
+
+/// Get the type of the output of a call on a `Boolean`
+pub fn check(call: &RadonCall) -> Result<RadonType, String> {
+    match call.operator {
+        BOOLEAN_NEG => expect_args(call, 0).map(|_| RadonType::Boolean),
+        BOOLEAN_TOSTRING => expect_args(call, 0).map(|_| RadonType::String),
+        _ => Err(unknown_operator(&RadonType::Boolean, call)),
+    }
+}
+
+/// Execute a call on a `Boolean`
+pub fn apply(input: bool, call: &RadonCall) -> Result<RadonValue, String> {
+    match call.operator {
+        BOOLEAN_NEG => Ok(RadonValue::Boolean(!input)),
+        BOOLEAN_TOSTRING => Ok(RadonValue::String(input.to_string())),
+        _ => Err(unknown_operator(&RadonType::Boolean, call)),
+    }
+}
@@ -0,0 +1,100 @@
+use super::{expect_args, finite, float_arg, float_to_int, int_arg, unknown_operator};
+use crate::script::RadonCall;
+use crate::types::{RadonType, RadonValue};
+
+/// `FLOAT_ABS`
+pub const FLOAT_ABS: u8 = 0x00;
+/// `FLOAT_CEIL`
+pub const FLOAT_CEIL: u8 = 0x01;
+/// `FLOAT_FLOOR`
+pub const FLOAT_FLOOR: u8 = 0x02;
+/// `FLOAT_MODULO`
+pub const FLOAT_MODULO: u8 = 0x03;
+/// `FLOAT_MULT`
+pub const FLOAT_MULT: u8 = 0x04;
+/// `FLOAT_NEG`
+pub const FLOAT_NEG: u8 = 0x05;
+/// `FLOAT_POW`
+pub const FLOAT_POW: u8 = 0x06;
+/// `FLOAT_RECIP`
+pub const FLOAT_RECIP: u8 = 0x07;
+/// `FLOAT_ROUND`
+pub const FLOAT_ROUND: u8 = 0x08;
+/// `FLOAT_SUM`
+pub const FLOAT_SUM: u8 = 0x09;
+/// `FLOAT_TOSTRING`
+pub const FLOAT_TOSTRING: u8 = 0x0a;
+/// `FLOAT_TRUNC`
+pub const FLOAT_TRUNC: u8 = 0x0b;
+
+/// Get the type of the output of a call on a `Float`
+pub fn check(call: &RadonCall) -> Result<RadonType, String> {
+    match call.operator {
+        FLOAT_ABS | FLOAT_NEG | FLOAT_RECIP => expect_args(call, 0).map(|_| RadonType::Float),
+        FLOAT_CEIL | FLOAT_FLOOR | FLOAT_ROUND | FLOAT_TRUNC => {
+            expect_args(call, 0).map(|_| RadonType::Int)
+        }
+        FLOAT_MODULO => {
+            expect_args(call, 1)?;
+            int_arg(call, 0).map(|_| RadonType::Float)
+        }
+        FLOAT_MULT | FLOAT_POW | FLOAT_SUM => {
+            expect_args(call, 1)?;
+            float_arg(call, 0).map(|_| RadonType::Float)
+        }
+        FLOAT_TOSTRING => match call.args.len() {
+            0 => Ok(RadonType::String),
+            1 => int_arg(call, 0)
+                .and_then(decimals)
+                .map(|_| RadonType::String),
+            _ => expect_args(call, 1).map(|_| RadonType::String),
+        },
+        _ => Err(unknown_operator(&RadonType::Float, call)),
+    }
+}
+
+/// Execute a call on a `Float`
+pub fn apply(input: f64, call: &RadonCall) -> Result<RadonValue, String> {
+    match call.operator {
+        FLOAT_ABS => Ok(RadonValue::Float(input.abs())),
+        FLOAT_NEG => Ok(RadonValue::Float(-input)),
+        FLOAT_RECIP => finite(1.0 / input).map(RadonValue::Float),
+        FLOAT_CEIL => float_to_int(input.ceil()).map(RadonValue::Int),
+        FLOAT_FLOOR => float_to_int(input.floor()).map(RadonValue::Int),
+        FLOAT_ROUND => float_to_int(input.round()).map(RadonValue::Int),
+        FLOAT_TRUNC => float_to_int(input.trunc()).map(RadonValue::Int),
+        FLOAT_MODULO => {
+            let modulus = int_arg(call, 0)?;
+            if modulus == 0 {
+                return Err("modulo by zero".to_string());
+            }
+            finite(input % modulus as f64).map(RadonValue::Float)
+        }
+        FLOAT_MULT => finite(input * float_arg(call, 0)?).map(RadonValue::Float),
+        FLOAT_POW => finite(input.powf(float_arg(call, 0)?)).map(RadonValue::Float),
+        FLOAT_SUM => finite(input + float_arg(call, 0)?).map(RadonValue::Float),
+        FLOAT_TOSTRING => match call.args.len() {
+            0 => Ok(RadonValue::String(input.to_string())),
+            _ => {
+                let decimals = decimals(int_arg(call, 0)?)?;
+                Ok(RadonValue::String(format!("{:.*}", decimals, input)))
+            }
+        },
+        _ => Err(unknown_operator(&RadonType::Float, call)),
+    }
+}
+
+/// Maximum number of decimals of the string representation of a `Float`
+const MAX_DECIMALS: i64 = 20;
+
+/// Check the number of decimals of the string representation of a `Float`
+fn decimals(decimals: i64) -> Result<usize, String> {
+    if decimals >= 0 && decimals <= MAX_DECIMALS {
+        Ok(decimals as usize)
+    } else {
+        Err(format!(
+            "the number of decimals must be between 0 and {}",
+            MAX_DECIMALS
+        ))
+    }
+}
@@ -0,0 +1,79 @@
+use super::{expect_args, finite, float_arg, int_arg, overflow, unknown_operator};
+use crate::script::RadonCall;
+use crate::types::{RadonType, RadonValue};
+
+/// `INT_ABS`
+pub const INT_ABS: u8 = 0x00;
+/// `INT_MATCH`
+pub const INT_MATCH: u8 = 0x01;
+/// `INT_MODULO`
+pub const INT_MODULO: u8 = 0x02;
+/// `INT_MULT`
+pub const INT_MULT: u8 = 0x03;
+/// `INT_NEG`
+pub const INT_NEG: u8 = 0x04;
+/// `INT_POW`
+pub const INT_POW: u8 = 0x05;
+/// `INT_RECIP`
+pub const INT_RECIP: u8 = 0x06;
+/// `INT_SUM`
+pub const INT_SUM: u8 = 0x07;
+/// `INT_TOFLOAT`
+pub const INT_TOFLOAT: u8 = 0x08;
+/// `INT_TOSTRING`
+pub const INT_TOSTRING: u8 = 0x09;
+
+/// Get the type of the output of a call on an `Int`
+pub fn check(call: &RadonCall) -> Result<RadonType, String> {
+    match call.operator {
+        INT_ABS | INT_NEG => expect_args(call, 0).map(|_| RadonType::Int),
+        INT_MODULO | INT_MULT | INT_SUM => {
+            expect_args(call, 1)?;
+            int_arg(call, 0).map(|_| RadonType::Int)
+        }
+        INT_POW => {
+            expect_args(call, 1)?;
+            float_arg(call, 0).map(|_| RadonType::Float)
+        }
+        INT_RECIP | INT_TOFLOAT => expect_args(call, 0).map(|_| RadonType::Float),
+        INT_TOSTRING => expect_args(call, 0).map(|_| RadonType::String),
+        _ => Err(unknown_operator(&RadonType::Int, call)),
+    }
+}
+
+/// Execute a call on an `Int`
+pub fn apply(input: i64, call: &RadonCall) -> Result<RadonValue, String> {
+    match call.operator {
+        INT_ABS => input
+            .checked_abs()
+            .map(RadonValue::Int)
+            .ok_or_else(overflow),
+        INT_NEG => input
+            .checked_neg()
+            .map(RadonValue::Int)
+            .ok_or_else(overflow),
+        INT_MODULO => {
+            let modulus = int_arg(call, 0)?;
+            if modulus == 0 {
+                return Err("modulo by zero".to_string());
+            }
+            input
+                .checked_rem(modulus)
+                .map(RadonValue::Int)
+                .ok_or_else(overflow)
+        }
+        INT_MULT => input
+            .checked_mul(int_arg(call, 0)?)
+            .map(RadonValue::Int)
+            .ok_or_else(overflow),
+        INT_SUM => input
+            .checked_add(int_arg(call, 0)?)
+            .map(RadonValue::Int)
+            .ok_or_else(overflow),
+        INT_POW => finite((input as f64).powf(float_arg(call, 0)?)).map(RadonValue::Float),
+        INT_RECIP => finite(1.0 / input as f64).map(RadonValue::Float),
+        INT_TOFLOAT => Ok(RadonValue::Float(input as f64)),
+        INT_TOSTRING => Ok(RadonValue::String(input.to_string())),
+        _ => Err(unknown_operator(&RadonType::Int, call)),
+    }
+}
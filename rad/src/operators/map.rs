@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+use super::{expect_args, string_arg, unknown_operator};
+use crate::script::RadonCall;
+use crate::types::{RadonType, RadonValue};
+
+/// `MAP_GET`
+pub const MAP_GET: u8 = 0x00;
+/// `MAP_ENTRIES`
+pub const MAP_ENTRIES: u8 = 0x01;
+/// `MAP_KEYS`
+pub const MAP_KEYS: u8 = 0x02;
+/// `MAP_VALUES`
+pub const MAP_VALUES: u8 = 0x03;
+
+/// Get the type of the output of a call on a `Map<String, T>`
+pub fn check(value: &RadonType, call: &RadonCall) -> Result<RadonType, String> {
+    match call.operator {
+        MAP_GET => {
+            expect_args(call, 1)?;
+            string_arg(call, 0).map(|_| value.clone())
+        }
+        MAP_KEYS => expect_args(call, 0).map(|_| RadonType::Array(Box::new(RadonType::String))),
+        MAP_VALUES => expect_args(call, 0).map(|_| RadonType::Array(Box::new(value.clone()))),
+        _ => Err(unknown_operator(
+            &RadonType::Map(Box::new(value.clone())),
+            call,
+        )),
+    }
+}
+
+/// Execute a call on a `Map<String, T>`
+pub fn apply(
+    mut entries: BTreeMap<String, RadonValue>,
+    call: &RadonCall,
+) -> Result<RadonValue, String> {
+    match call.operator {
+        MAP_GET => {
+            let key = string_arg(call, 0)?;
+            entries
+                .remove(key)
+                .ok_or_else(|| format!("key \"{}\" not found", key))
+        }
+        MAP_KEYS => Ok(RadonValue::Array(
+            entries
+                .into_iter()
+                .map(|(key, _)| RadonValue::String(key))
+                .collect(),
+        )),
+        MAP_VALUES => Ok(RadonValue::Array(
+            entries.into_iter().map(|(_, value)| value).collect(),
+        )),
+        _ => Err(format!("unknown operator {} for type Map", call.operator)),
+    }
+}
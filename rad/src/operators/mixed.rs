@@ -0,0 +1,61 @@
+use serde_json::Value as JsonValue;
+
+use super::{expect_args, unknown_operator};
+use crate::script::RadonCall;
+use crate::types::{RadonType, RadonValue};
+
+/// `MIXED_TOARRAY`
+pub const MIXED_TOARRAY: u8 = 0x00;
+/// `MIXED_TOBOOLEAN`
+pub const MIXED_TOBOOLEAN: u8 = 0x01;
+/// `MIXED_TOFLOAT`
+pub const MIXED_TOFLOAT: u8 = 0x02;
+/// `MIXED_TOINT`
+pub const MIXED_TOINT: u8 = 0x03;
+/// `MIXED_TOMAP`
+pub const MIXED_TOMAP: u8 = 0x04;
+
+/// Get the type of the output of a call on a `Mixed`
+pub fn check(call: &RadonCall) -> Result<RadonType, String> {
+    let output = match call.operator {
+        MIXED_TOARRAY => RadonType::Array(Box::new(RadonType::Mixed)),
+        MIXED_TOBOOLEAN => RadonType::Boolean,
+        MIXED_TOFLOAT => RadonType::Float,
+        MIXED_TOINT => RadonType::Int,
+        MIXED_TOMAP => RadonType::Map(Box::new(RadonType::Mixed)),
+        _ => return Err(unknown_operator(&RadonType::Mixed, call)),
+    };
+
+    expect_args(call, 0).map(|_| output)
+}
+
+/// Execute a call on a `Mixed`
+pub fn apply(input: JsonValue, call: &RadonCall) -> Result<RadonValue, String> {
+    match (call.operator, input) {
+        (MIXED_TOARRAY, JsonValue::Array(items)) => Ok(RadonValue::Array(
+            items.into_iter().map(RadonValue::Mixed).collect(),
+        )),
+        (MIXED_TOBOOLEAN, JsonValue::Bool(value)) => Ok(RadonValue::Boolean(value)),
+        (MIXED_TOFLOAT, JsonValue::Number(ref number)) if number.as_f64().is_some() => {
+            Ok(RadonValue::Float(number.as_f64().unwrap_or_default()))
+        }
+        (MIXED_TOINT, JsonValue::Number(ref number)) if number.as_i64().is_some() => {
+            Ok(RadonValue::Int(number.as_i64().unwrap_or_default()))
+        }
+        (MIXED_TOMAP, JsonValue::Object(entries)) => Ok(RadonValue::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key, RadonValue::Mixed(value)))
+                .collect(),
+        )),
+        (MIXED_TOARRAY, _)
+        | (MIXED_TOBOOLEAN, _)
+        | (MIXED_TOFLOAT, _)
+        | (MIXED_TOINT, _)
+        | (MIXED_TOMAP, _) => Err(format!(
+            "the value cannot be converted by operator {}",
+            call.operator
+        )),
+        _ => Err(unknown_operator(&RadonType::Mixed, call)),
+    }
+}
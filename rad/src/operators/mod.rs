@@ -0,0 +1,128 @@
+//! RADON operators, grouped by the type of their input
+//!
+//! For every type, `check` returns the type of the output of a call given the type of its input
+//! (or the reason why the call is not valid), and `apply` executes a call on a value. Both of
+//! them return deterministic error messages, so every witness fails in the same way.
+use rmpv::Value;
+
+use crate::script::RadonCall;
+use crate::types::{RadonType, RadonValue};
+
+/// Operators on `Array<T>`
+pub mod array;
+/// Operators on `Boolean`
+pub mod boolean;
+/// Operators on `Float`
+pub mod float;
+/// Operators on `Int`
+pub mod int;
+/// Operators on `Map<String, T>`
+pub mod map;
+/// Operators on `Mixed`
+pub mod mixed;
+/// Operators on `String`
+pub mod string;
+
+/// Get the type of the output of a call given the type of its input
+pub fn check(input: &RadonType, call: &RadonCall) -> Result<RadonType, String> {
+    match input {
+        RadonType::Boolean => boolean::check(call),
+        RadonType::Int => int::check(call),
+        RadonType::Float => float::check(call),
+        RadonType::String => string::check(call),
+        RadonType::Array(item) => array::check(item, call),
+        RadonType::Map(value) => map::check(value, call),
+        RadonType::Mixed => mixed::check(call),
+        RadonType::Null => Err(unknown_operator(input, call)),
+    }
+}
+
+/// Execute a call on a value
+pub fn apply(input: RadonValue, call: &RadonCall) -> Result<RadonValue, String> {
+    match input {
+        RadonValue::Boolean(value) => boolean::apply(value, call),
+        RadonValue::Int(value) => int::apply(value, call),
+        RadonValue::Float(value) => float::apply(value, call),
+        RadonValue::String(value) => string::apply(value, call),
+        RadonValue::Array(items) => array::apply(items, call),
+        RadonValue::Map(entries) => map::apply(entries, call),
+        RadonValue::Mixed(value) => mixed::apply(value, call),
+        RadonValue::Null => Err(unknown_operator(&RadonType::Null, call)),
+    }
+}
+
+/// Error message for an operator which does not exist for a type
+fn unknown_operator(input: &RadonType, call: &RadonCall) -> String {
+    format!("unknown operator {} for type {}", call.operator, input)
+}
+
+/// Check the number of arguments of a call
+fn expect_args(call: &RadonCall, count: usize) -> Result<(), String> {
+    if call.args.len() == count {
+        Ok(())
+    } else {
+        Err(format!(
+            "operator {} expects {} arguments, got {}",
+            call.operator,
+            count,
+            call.args.len()
+        ))
+    }
+}
+
+/// Get an argument of type `Int`
+fn int_arg(call: &RadonCall, index: usize) -> Result<i64, String> {
+    call.args.get(index).and_then(Value::as_i64).ok_or_else(|| {
+        format!(
+            "argument {} of operator {} must be an Int",
+            index, call.operator
+        )
+    })
+}
+
+/// Get an argument of type `Float` (an `Int` is also accepted)
+fn float_arg(call: &RadonCall, index: usize) -> Result<f64, String> {
+    call.args
+        .get(index)
+        .and_then(|arg| arg.as_f64().or_else(|| arg.as_i64().map(|x| x as f64)))
+        .ok_or_else(|| {
+            format!(
+                "argument {} of operator {} must be a Float",
+                index, call.operator
+            )
+        })
+}
+
+/// Get an argument of type `String`
+fn string_arg(call: &RadonCall, index: usize) -> Result<&str, String> {
+    call.args.get(index).and_then(Value::as_str).ok_or_else(|| {
+        format!(
+            "argument {} of operator {} must be a String",
+            index, call.operator
+        )
+    })
+}
+
+/// Fail if the result of a floating point operation is not a finite number, as infinities and
+/// NaNs cannot be agreed on
+fn finite(x: f64) -> Result<f64, String> {
+    if x.is_finite() {
+        Ok(x)
+    } else {
+        Err("the result is not a finite number".to_string())
+    }
+}
+
+/// Convert an integral float into an `Int`, failing if it does not fit
+fn float_to_int(x: f64) -> Result<i64, String> {
+    if x.is_finite() && x >= i64::min_value() as f64 && x < i64::max_value() as f64 {
+        Ok(x as i64)
+    } else {
+        Err(format!("{} does not fit in an Int", x))
+    }
+}
+
+/// Error message for an arithmetic overflow
+fn overflow() -> String {
+    "arithmetic overflow".to_string()
+}
@@ -0,0 +1,67 @@
+use super::{expect_args, finite, unknown_operator};
+use crate::script::RadonCall;
+use crate::types::{RadonType, RadonValue};
+
+/// `STRING_HASH`
+pub const STRING_HASH: u8 = 0x00;
+/// `STRING_LENGTH`
+pub const STRING_LENGTH: u8 = 0x01;
+/// `STRING_MATCH`
+pub const STRING_MATCH: u8 = 0x02;
+/// `STRING_PARSEJSON`
+pub const STRING_PARSEJSON: u8 = 0x03;
+/// `STRING_PARSEXML`
+pub const STRING_PARSEXML: u8 = 0x04;
+/// `STRING_TOBOOLEAN`
+pub const STRING_TOBOOLEAN: u8 = 0x05;
+/// `STRING_TOFLOAT`
+pub const STRING_TOFLOAT: u8 = 0x06;
+/// `STRING_TOINT`
+pub const STRING_TOINT: u8 = 0x07;
+/// `STRING_TOLOWERCASE`
+pub const STRING_TOLOWERCASE: u8 = 0x08;
+/// `STRING_TOUPPERCASE`
+pub const STRING_TOUPPERCASE: u8 = 0x09;
+
+/// Get the type of the output of a call on a `String`
+pub fn check(call: &RadonCall) -> Result<RadonType, String> {
+    let output = match call.operator {
+        STRING_LENGTH | STRING_TOINT => RadonType::Int,
+        STRING_PARSEJSON => RadonType::Mixed,
+        STRING_TOBOOLEAN => RadonType::Boolean,
+        STRING_TOFLOAT => RadonType::Float,
+        STRING_TOLOWERCASE | STRING_TOUPPERCASE => RadonType::String,
+        _ => return Err(unknown_operator(&RadonType::String, call)),
+    };
+
+    expect_args(call, 0).map(|_| output)
+}
+
+/// Execute a call on a `String`
+pub fn apply(input: String, call: &RadonCall) -> Result<RadonValue, String> {
+    match call.operator {
+        STRING_LENGTH => Ok(RadonValue::Int(input.chars().count() as i64)),
+        STRING_PARSEJSON => serde_json::from_str(&input)
+            .map(RadonValue::Mixed)
+            .map_err(|e| format!("invalid JSON: {}", e)),
+        STRING_TOBOOLEAN => match input.as_str() {
+            "true" => Ok(RadonValue::Boolean(true)),
+            "false" => Ok(RadonValue::Boolean(false)),
+            _ => Err(format!("\"{}\" is not a Boolean", input)),
+        },
+        STRING_TOFLOAT => input
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| format!("\"{}\" is not a Float", input))
+            .and_then(finite)
+            .map(RadonValue::Float),
+        STRING_TOINT => input
+            .trim()
+            .parse::<i64>()
+            .map(RadonValue::Int)
+            .map_err(|_| format!("\"{}\" is not an Int", input)),
+        STRING_TOLOWERCASE => Ok(RadonValue::String(input.to_lowercase())),
+        STRING_TOUPPERCASE => Ok(RadonValue::String(input.to_uppercase())),
+        _ => Err(unknown_operator(&RadonType::String, call)),
+    }
+}
@@ -0,0 +1,142 @@
+//! Cache of the data retrieved from the sources during an epoch
+//!
+//! Several data requests being witnessed in the same epoch often retrieve the data from the same
+//! source. The data retrieved from a source is kept until the epoch is over, so every source is
+//! requested at most once per epoch however many witnessing jobs retrieve it, even when those
+//! jobs run at the same time. Failed retrievals are cached too, so an endpoint which is down is
+//! not requested again by every job.
+//!
+//! Sources are identified by their kind, their URL and their headers, once normalized: the scheme
+//! and the host of the URL are lowercased, the default port is removed, the parameters of the
+//! query are sorted and the fragment is dropped, and the names of the headers are lowercased and
+//! sorted.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use reqwest::Url;
+
+use crate::error::{RadError, RadResult};
+use crate::sandbox::SandboxLimits;
+use witnet_data_structures::chain::{Epoch, RADRetrieve, RADType};
+
+/// Normalized source of a retrieval, along with the epoch it was retrieved in
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SourceKey {
+    /// Epoch of the retrieval
+    pub epoch: Epoch,
+    /// Kind of the source
+    pub kind: RADType,
+    /// Normalized URL of the source
+    pub url: String,
+    /// Headers of the request, with lowercase names and sorted
+    pub headers: Vec<(String, String)>,
+}
+
+impl SourceKey {
+    /// Identify the source of a retrieval in an epoch
+    pub fn new(retrieve: &RADRetrieve, epoch: Epoch) -> Self {
+        let mut headers: Vec<_> = retrieve
+            .headers
+            .iter()
+            .map(|(name, value)| (name.to_lowercase(), value.clone()))
+            .collect();
+        headers.sort();
+
+        SourceKey {
+            epoch,
+            kind: retrieve.kind,
+            url: normalize_url(&retrieve.url),
+            headers,
+        }
+    }
+}
+
+/// Normalize a URL, keeping it as is if it is not valid (the retrieval will fail anyway)
+fn normalize_url(url: &str) -> String {
+    let mut url = match Url::parse(url) {
+        Ok(url) => url,
+        Err(_) => return url.to_string(),
+    };
+    let mut query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    if query.is_empty() {
+        url.set_query(None);
+    } else {
+        query.sort();
+        url.query_pairs_mut().clear().extend_pairs(query);
+    }
+    url.set_fragment(None);
+
+    url.into_string()
+}
+
+/// Result of a retrieval kept in the cache, which is empty while the source is being retrieved
+type CacheEntry = Arc<Mutex<Option<Result<String, RadError>>>>;
+
+/// Data retrieved from the sources during the current epoch, shared by the witnessing jobs
+#[derive(Debug, Default)]
+pub struct RetrievalCache {
+    /// Results of the retrievals, by source
+    entries: Mutex<HashMap<SourceKey, CacheEntry>>,
+}
+
+impl RetrievalCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retrieve the data of a retrieval in an epoch, requesting its source only if no other
+    /// retrieval has requested it in that epoch
+    pub fn fetch(
+        &self,
+        retrieve: &RADRetrieve,
+        epoch: Epoch,
+        limits: &SandboxLimits,
+    ) -> RadResult<String> {
+        self.fetch_with(retrieve, epoch, || super::fetch(retrieve, limits))
+    }
+
+    /// Get the data of a retrieval in an epoch from the cache, retrieving it with `fetch` if it
+    /// is not cached yet. Retrievals of the same source started while it is being retrieved wait
+    /// for that result instead of requesting the source again.
+    /// The entries of the epochs before the given one are forgotten.
+    pub fn fetch_with<F>(&self, retrieve: &RADRetrieve, epoch: Epoch, fetch: F) -> RadResult<String>
+    where
+        F: FnOnce() -> RadResult<String>,
+    {
+        let entry = {
+            let mut entries = lock(&self.entries);
+            entries.retain(|key, _| key.epoch >= epoch);
+            entries
+                .entry(SourceKey::new(retrieve, epoch))
+                .or_insert_with(CacheEntry::default)
+                .clone()
+        };
+
+        // The lock of the entry is held while retrieving the source, so the other retrievals of
+        // the same source wait for its result
+        let mut result = lock(&entry);
+        match result.get_or_insert_with(|| fetch().map_err(|e| e.inner().clone())) {
+            Ok(retrieved) => Ok(retrieved.clone()),
+            Err(e) => Err(e.clone().into()),
+        }
+    }
+
+    /// Number of sources cached
+    pub fn len(&self) -> usize {
+        lock(&self.entries).len()
+    }
+
+    /// Check whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Lock a mutex of the cache, even if a thread panicked while holding it: the cached values are
+/// replaced as a whole, so they are never left in an inconsistent state
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,94 @@
+//! HTTP GET requests, with the headers given by the retrieval
+use std::io::Read;
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client, Url,
+};
+
+use super::Retrieve;
+use crate::error::{RadError, RadErrorKind, RadResult};
+use crate::sandbox::SandboxLimits;
+use witnet_data_structures::chain::RADRetrieve;
+
+/// Source retrieved through an HTTP GET request
+#[derive(Debug)]
+pub struct HttpGet;
+
+impl Retrieve for HttpGet {
+    fn retrieve(&self, retrieve: &RADRetrieve, limits: &SandboxLimits) -> RadResult<String> {
+        http_get(retrieve, limits, "http")
+    }
+}
+
+/// Source retrieved through an HTTP GET request over TLS
+#[derive(Debug)]
+pub struct HttpsGet;
+
+impl Retrieve for HttpsGet {
+    fn retrieve(&self, retrieve: &RADRetrieve, limits: &SandboxLimits) -> RadResult<String> {
+        http_get(retrieve, limits, "https")
+    }
+}
+
+/// Send a GET request to the URL of a retrieval, which must use the given scheme, and return the
+/// body of the response
+/// The request fails if it does not finish within the timeout of the sandbox, or if the body is
+/// larger than the maximum retrieved size
+fn http_get(retrieve: &RADRetrieve, limits: &SandboxLimits, scheme: &str) -> RadResult<String> {
+    let url = Url::parse(&retrieve.url)
+        .map_err(|e| RadError::new(RadErrorKind::Url, retrieve.url.clone(), e.to_string()))?;
+    if url.scheme() != scheme {
+        return Err(RadError::new(
+            RadErrorKind::Url,
+            retrieve.url.clone(),
+            format!("the scheme of the URL must be {}", scheme),
+        )
+        .into());
+    }
+    let headers = build_headers(&retrieve.headers)?;
+
+    let http_error =
+        |e: reqwest::Error| RadError::new(RadErrorKind::Http, retrieve.url.clone(), e.to_string());
+    let response = Client::builder()
+        .timeout(limits.timeout)
+        .build()
+        .and_then(|client| client.get(url).headers(headers).send())
+        .and_then(|response| response.error_for_status())
+        .map_err(http_error)?;
+
+    // Read one byte more than the limit to know whether the body exceeds it
+    let mut body = vec![];
+    response
+        .take(limits.max_retrieved_size as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| RadError::new(RadErrorKind::Http, retrieve.url.clone(), e.to_string()))?;
+    if body.len() > limits.max_retrieved_size {
+        return Err(RadError::new(
+            RadErrorKind::ResourceLimit,
+            retrieve.url.clone(),
+            format!(
+                "the response is larger than {} bytes",
+                limits.max_retrieved_size
+            ),
+        )
+        .into());
+    }
+
+    String::from_utf8(body)
+        .map_err(|e| RadError::new(RadErrorKind::Http, retrieve.url.clone(), e.to_string()).into())
+}
+
+/// Build the headers of a request, failing if any name or value is not valid
+fn build_headers(headers: &[(String, String)]) -> RadResult<HeaderMap> {
+    let mut header_map = HeaderMap::new();
+    for (name, value) in headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| RadError::new(RadErrorKind::Header, name.clone(), e.to_string()))?;
+        let header_value = HeaderValue::from_str(value)
+            .map_err(|e| RadError::new(RadErrorKind::Header, name.clone(), e.to_string()))?;
+        header_map.append(header_name, header_value);
+    }
+
+    Ok(header_map)
+}
@@ -0,0 +1,38 @@
+//! Retrieval of the data from the sources of a data request
+//!
+//! Every kind of source implements the `Retrieve` trait, and the source used by a retrieval is
+//! selected by its `kind` field, so new kinds of sources do not need changes in the execution of
+//! the retrievals.
+
+use crate::error::RadResult;
+use crate::sandbox::SandboxLimits;
+use witnet_data_structures::chain::{RADRetrieve, RADType};
+
+/// Cache of the data retrieved during an epoch
+pub mod cache;
+
+/// HTTP and HTTPS sources
+pub mod http;
+
+/// Deterministic pseudo-random source
+pub mod random;
+
+/// Source of the data of a retrieval
+pub trait Retrieve {
+    /// Retrieve the data from the source, as a string, within the limits of the sandbox
+    fn retrieve(&self, retrieve: &RADRetrieve, limits: &SandboxLimits) -> RadResult<String>;
+}
+
+/// Get the source matching the kind of a retrieval
+pub fn source(kind: RADType) -> &'static dyn Retrieve {
+    match kind {
+        RADType::HttpGet => &http::HttpGet,
+        RADType::HttpsGet => &http::HttpsGet,
+        RADType::Rng => &random::RandomSource,
+    }
+}
+
+/// Retrieve the data of a retrieval from the source matching its kind
+pub fn fetch(retrieve: &RADRetrieve, limits: &SandboxLimits) -> RadResult<String> {
+    source(retrieve.kind).retrieve(retrieve, limits)
+}
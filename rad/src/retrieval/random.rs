@@ -0,0 +1,23 @@
+//! Deterministic pseudo-random source, intended for testing data requests without depending on
+//! external sources
+use rand::{prng::ChaChaRng, Rng, SeedableRng};
+
+use super::Retrieve;
+use crate::error::RadResult;
+use crate::sandbox::SandboxLimits;
+use witnet_crypto::hash::calculate_sha256;
+use witnet_data_structures::chain::{Hash, RADRetrieve};
+
+/// Source returning a pseudo-random number seeded by the URL of the retrieval, so every witness
+/// retrieves the same number for the same URL
+#[derive(Debug)]
+pub struct RandomSource;
+
+impl Retrieve for RandomSource {
+    fn retrieve(&self, retrieve: &RADRetrieve, _limits: &SandboxLimits) -> RadResult<String> {
+        let Hash::SHA256(seed) = calculate_sha256(retrieve.url.as_bytes());
+        let mut rng = ChaChaRng::from_seed(seed);
+
+        Ok(rng.gen::<u32>().to_string())
+    }
+}
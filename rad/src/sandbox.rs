@@ -0,0 +1,85 @@
+//! Sandbox for running the retrievals and scripts of a data request
+//!
+//! A witness must never be blocked by a data source which hangs or returns a huge response, so
+//! every job runs in its own thread and is given up once its wall-clock limit expires. The job
+//! is not killed (threads cannot be), but it is abandoned and its result discarded: the HTTP
+//! requests share the same timeout, so the thread finishes shortly after anyway.
+//!
+//! The memory used by a job is not limited as such: only the size of the data retrieved from
+//! every source is capped, which bounds the input of the scripts but not the values they build.
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{RadError, RadErrorKind, RadResult};
+
+/// Default wall-clock limit for a job, in seconds
+pub const DEFAULT_SANDBOX_TIMEOUT_SECONDS: u64 = 10;
+
+/// Default maximum size of the data retrieved from a source, in bytes
+pub const DEFAULT_MAX_RETRIEVED_SIZE: usize = 1024 * 1024;
+
+/// Stack size of the thread running a job, in bytes
+const SANDBOX_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Limits applied to a job run in the sandbox
+/// The node takes them from the `witnessing` section of its configuration, and the defaults are
+/// used otherwise
+#[derive(Clone, Debug, PartialEq)]
+pub struct SandboxLimits {
+    /// Wall-clock limit for the whole job
+    pub timeout: Duration,
+    /// Maximum size of the data retrieved from a source, in bytes
+    pub max_retrieved_size: usize,
+}
+
+impl Default for SandboxLimits {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(DEFAULT_SANDBOX_TIMEOUT_SECONDS),
+            max_retrieved_size: DEFAULT_MAX_RETRIEVED_SIZE,
+        }
+    }
+}
+
+/// Run a job in its own thread, failing with a `Timeout` error if it does not finish within the
+/// wall-clock limit. A job which panics fails with an `Execution` error instead of bringing the
+/// node down.
+pub fn run<T, F>(limits: &SandboxLimits, job: F) -> RadResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&SandboxLimits) -> RadResult<T> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    let job_limits = limits.clone();
+    thread::Builder::new()
+        .name("rad-sandbox".to_string())
+        .stack_size(SANDBOX_STACK_SIZE)
+        .spawn(move || {
+            // The receiver is gone if the job has timed out, so the result is just dropped
+            let _ = sender.send(job(&job_limits));
+        })
+        .map_err(|e| {
+            RadError::new(
+                RadErrorKind::ResourceLimit,
+                "sandbox".to_string(),
+                e.to_string(),
+            )
+        })?;
+
+    match receiver.recv_timeout(limits.timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(RadError::new(
+            RadErrorKind::Timeout,
+            "sandbox".to_string(),
+            format!("the job did not finish in {:?}", limits.timeout),
+        )
+        .into()),
+        Err(RecvTimeoutError::Disconnected) => Err(RadError::new(
+            RadErrorKind::Execution,
+            "sandbox".to_string(),
+            "the job panicked".to_string(),
+        )
+        .into()),
+    }
+}
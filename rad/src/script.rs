@@ -0,0 +1,110 @@
+//! RADON scripts: decoding of their MessagePack encoding, type checking and execution
+//!
+//! A script is an array of calls, where every call is either an operator or an array with an
+//! operator followed by its arguments. The operators are identified by a byte whose meaning
+//! depends on the type of the input of the call, so the type of the value flowing through the
+//! script is needed to know which operator is called.
+use rmpv::Value;
+
+use crate::error::{RadError, RadErrorKind, RadResult};
+use crate::operators;
+use crate::types::{RadonType, RadonValue};
+use witnet_util::error::WitnetError;
+
+/// Call to an operator: the operator and its arguments
+#[derive(Clone, Debug, PartialEq)]
+pub struct RadonCall {
+    /// Operator, whose meaning depends on the type of the input
+    pub operator: u8,
+    /// Arguments of the call
+    pub args: Vec<Value>,
+}
+
+/// Decode a script encoded using MessagePack
+/// An empty script is valid and returns its input unchanged
+pub fn decode_script(bytes: &[u8]) -> RadResult<Vec<RadonCall>> {
+    if bytes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut reader = bytes;
+    let value = rmpv::decode::read_value(&mut reader)
+        .map_err(|e| decode_error("script".to_string(), e.to_string()))?;
+    if !reader.is_empty() {
+        return Err(decode_error(
+            "script".to_string(),
+            "unexpected bytes after the end of the script".to_string(),
+        ));
+    }
+
+    match value {
+        Value::Array(calls) => decode_calls(&calls),
+        _ => Err(decode_error(
+            "script".to_string(),
+            "a script must be an array of calls".to_string(),
+        )),
+    }
+}
+
+/// Decode the calls of a script (or of a subscript)
+pub fn decode_calls(calls: &[Value]) -> RadResult<Vec<RadonCall>> {
+    calls
+        .iter()
+        .enumerate()
+        .map(|(position, call)| {
+            decode_call(call).map_err(|msg| decode_error(call_info(position), msg))
+        })
+        .collect()
+}
+
+/// Decode a call: either an operator or an array with an operator followed by its arguments
+fn decode_call(call: &Value) -> Result<RadonCall, String> {
+    let (operator, args) = match call {
+        Value::Array(items) if !items.is_empty() => (&items[0], items[1..].to_vec()),
+        Value::Array(_) => return Err("a call cannot be empty".to_string()),
+        operator => (operator, vec![]),
+    };
+    let operator = operator
+        .as_u64()
+        .filter(|operator| *operator <= u64::from(u8::max_value()))
+        .ok_or_else(|| "an operator must be a byte".to_string())?;
+
+    Ok(RadonCall {
+        operator: operator as u8,
+        args,
+    })
+}
+
+/// Check that every call of a script is valid for the type of its input, without executing it
+/// Returns the type of the output of the script
+pub fn typecheck(calls: &[RadonCall], input: RadonType) -> RadResult<RadonType> {
+    calls
+        .iter()
+        .enumerate()
+        .try_fold(input, |input, (position, call)| {
+            operators::check(&input, call)
+                .map_err(|msg| RadError::new(RadErrorKind::Type, call_info(position), msg).into())
+        })
+}
+
+/// Execute the calls of a script, which must have been type checked for the type of the input
+pub fn execute(calls: &[RadonCall], input: RadonValue) -> RadResult<RadonValue> {
+    calls
+        .iter()
+        .enumerate()
+        .try_fold(input, |input, (position, call)| {
+            operators::apply(input, call).map_err(|msg| {
+                RadError::new(RadErrorKind::Execution, call_info(position), msg).into()
+            })
+        })
+}
+
+/// Description of the position of a call, used in the errors
+fn call_info(position: usize) -> String {
+    format!("call {}", position)
+}
+
+/// Build a decoding error
+fn decode_error(info: String, msg: String) -> WitnetError<RadError> {
+    RadError::new(RadErrorKind::Decode, info, msg).into()
+}
@@ -0,0 +1,108 @@
+//! Data types of RADON: the types known when checking a script and the values handled when
+//! executing it
+use std::collections::BTreeMap;
+use std::fmt;
+
+use rmpv::Value;
+use serde_json::Value as JsonValue;
+
+/// Type of a RADON value
+#[derive(Clone, Debug, PartialEq)]
+pub enum RadonType {
+    /// Boolean
+    Boolean,
+    /// Signed integer
+    Int,
+    /// Floating point number
+    Float,
+    /// UTF-8 string
+    String,
+    /// Ordered sequence of values of the same type
+    Array(Box<RadonType>),
+    /// String keys mapped to values of the same type
+    Map(Box<RadonType>),
+    /// Value whose type is only known at runtime (e.g. a parsed JSON document)
+    Mixed,
+    /// Absence of value
+    Null,
+}
+
+impl fmt::Display for RadonType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RadonType::Boolean => write!(f, "Boolean"),
+            RadonType::Int => write!(f, "Int"),
+            RadonType::Float => write!(f, "Float"),
+            RadonType::String => write!(f, "String"),
+            RadonType::Array(item) => write!(f, "Array<{}>", item),
+            RadonType::Map(value) => write!(f, "Map<String, {}>", value),
+            RadonType::Mixed => write!(f, "Mixed"),
+            RadonType::Null => write!(f, "Null"),
+        }
+    }
+}
+
+/// RADON value
+#[derive(Clone, Debug, PartialEq)]
+pub enum RadonValue {
+    /// Boolean
+    Boolean(bool),
+    /// Signed integer
+    Int(i64),
+    /// Floating point number
+    Float(f64),
+    /// UTF-8 string
+    String(String),
+    /// Ordered sequence of values of the same type
+    Array(Vec<RadonValue>),
+    /// String keys mapped to values of the same type, sorted by key
+    Map(BTreeMap<String, RadonValue>),
+    /// Value whose type is only known at runtime
+    Mixed(serde_json::Value),
+    /// Absence of value
+    Null,
+}
+
+impl RadonValue {
+    /// Convert the value into its MessagePack representation
+    pub fn to_msgpack(&self) -> Value {
+        match self {
+            RadonValue::Boolean(x) => Value::from(*x),
+            RadonValue::Int(x) => Value::from(*x),
+            RadonValue::Float(x) => Value::from(*x),
+            RadonValue::String(x) => Value::from(x.as_str()),
+            RadonValue::Array(items) => {
+                Value::Array(items.iter().map(RadonValue::to_msgpack).collect())
+            }
+            RadonValue::Map(entries) => Value::Map(
+                entries
+                    .iter()
+                    .map(|(key, value)| (Value::from(key.as_str()), value.to_msgpack()))
+                    .collect(),
+            ),
+            RadonValue::Mixed(x) => json_to_msgpack(x),
+            RadonValue::Null => Value::Nil,
+        }
+    }
+}
+
+/// Convert a JSON value into its MessagePack representation
+fn json_to_msgpack(value: &JsonValue) -> Value {
+    match value {
+        JsonValue::Null => Value::Nil,
+        JsonValue::Bool(x) => Value::from(*x),
+        JsonValue::Number(x) => x
+            .as_i64()
+            .map(Value::from)
+            .or_else(|| x.as_u64().map(Value::from))
+            .unwrap_or_else(|| Value::from(x.as_f64().unwrap_or_default())),
+        JsonValue::String(x) => Value::from(x.as_str()),
+        JsonValue::Array(items) => Value::Array(items.iter().map(json_to_msgpack).collect()),
+        JsonValue::Object(entries) => Value::Map(
+            entries
+                .iter()
+                .map(|(key, value)| (Value::from(key.as_str()), json_to_msgpack(value)))
+                .collect(),
+        ),
+    }
+}
@@ -0,0 +1,162 @@
+use std::cell::Cell;
+
+use witnet_data_structures::chain::{RADRetrieve, RADType};
+use witnet_rad::{
+    error::{RadError, RadErrorKind},
+    retrieval::{
+        cache::{RetrievalCache, SourceKey},
+        fetch,
+    },
+    sandbox::SandboxLimits,
+};
+
+fn retrieve(kind: RADType, url: &str, headers: Vec<(String, String)>) -> RADRetrieve {
+    RADRetrieve {
+        kind,
+        url: url.to_string(),
+        headers,
+        script: vec![],
+    }
+}
+
+/// Check that the random source is deterministic given the URL
+#[test]
+fn rad_retrieval_random_is_deterministic() {
+    let a = fetch(
+        &retrieve(RADType::Rng, "rng://a", vec![]),
+        &SandboxLimits::default(),
+    )
+    .unwrap();
+    let a_again = fetch(
+        &retrieve(RADType::Rng, "rng://a", vec![]),
+        &SandboxLimits::default(),
+    )
+    .unwrap();
+    let b = fetch(
+        &retrieve(RADType::Rng, "rng://b", vec![]),
+        &SandboxLimits::default(),
+    )
+    .unwrap();
+
+    assert_eq!(a, a_again);
+    assert_ne!(a, b);
+    assert!(a.parse::<u32>().is_ok());
+}
+
+/// Check that the HTTP and HTTPS sources reject URLs with a different scheme
+#[test]
+fn rad_retrieval_http_scheme_mismatch() {
+    let err = fetch(
+        &retrieve(RADType::HttpGet, "https://example.com", vec![]),
+        &SandboxLimits::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Url);
+
+    let err = fetch(
+        &retrieve(RADType::HttpsGet, "http://example.com", vec![]),
+        &SandboxLimits::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Url);
+
+    let err = fetch(
+        &retrieve(RADType::HttpGet, "not a url", vec![]),
+        &SandboxLimits::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Url);
+}
+
+/// Check that invalid headers are rejected before sending any request
+#[test]
+fn rad_retrieval_http_invalid_header() {
+    let headers = vec![("invalid header".to_string(), "value".to_string())];
+    let err = fetch(
+        &retrieve(RADType::HttpGet, "http://example.com", headers),
+        &SandboxLimits::default(),
+    )
+    .unwrap_err();
+
+    assert_eq!(err.inner().kind(), &RadErrorKind::Header);
+}
+
+/// Check that a source is retrieved only once per epoch through the cache
+#[test]
+fn rad_retrieval_cache_once_per_epoch() {
+    let cache = RetrievalCache::new();
+    let requests = Cell::new(0);
+    let fetch_counting = || {
+        requests.set(requests.get() + 1);
+        Ok("42".to_string())
+    };
+    let source = retrieve(RADType::HttpGet, "http://example.com/price", vec![]);
+
+    assert_eq!(cache.fetch_with(&source, 1, fetch_counting).unwrap(), "42");
+    assert_eq!(cache.fetch_with(&source, 1, fetch_counting).unwrap(), "42");
+    assert_eq!(requests.get(), 1);
+
+    // The source is requested again in the next epoch, and the previous epoch is forgotten
+    assert_eq!(cache.fetch_with(&source, 2, fetch_counting).unwrap(), "42");
+    assert_eq!(requests.get(), 2);
+    assert_eq!(cache.len(), 1);
+}
+
+/// Check that failed retrievals are cached too
+#[test]
+fn rad_retrieval_cache_errors() {
+    let cache = RetrievalCache::new();
+    let source = retrieve(RADType::HttpGet, "http://example.com", vec![]);
+    let fail = || {
+        Err(RadError::new(
+            RadErrorKind::Http,
+            "http://example.com".to_string(),
+            "connection refused".to_string(),
+        )
+        .into())
+    };
+
+    let err = cache.fetch_with(&source, 1, fail).unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Http);
+    let err = cache
+        .fetch_with(&source, 1, || Ok("unexpected".to_string()))
+        .unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Http);
+}
+
+/// Check that equivalent sources are identified by the same key
+#[test]
+fn rad_retrieval_cache_normalized_sources() {
+    let key = |url: &str, headers: Vec<(String, String)>| {
+        SourceKey::new(&retrieve(RADType::HttpGet, url, headers), 1)
+    };
+    let header = |name: &str, value: &str| (name.to_string(), value.to_string());
+
+    assert_eq!(
+        key("HTTP://Example.com:80/price?b=2&a=1#top", vec![]),
+        key("http://example.com/price?a=1&b=2", vec![])
+    );
+    assert_eq!(
+        key(
+            "http://example.com",
+            vec![header("X-Api-Key", "k"), header("Accept", "json")]
+        ),
+        key(
+            "http://example.com",
+            vec![header("accept", "json"), header("x-api-key", "k")]
+        )
+    );
+
+    assert_ne!(
+        key("http://example.com/price", vec![]),
+        key("http://example.com/Price", vec![])
+    );
+    assert_ne!(
+        key("http://example.com", vec![header("x-api-key", "k")]),
+        key("http://example.com", vec![header("x-api-key", "K")])
+    );
+    assert_ne!(
+        key("http://example.com", vec![]),
+        SourceKey::new(&retrieve(RADType::HttpGet, "http://example.com", vec![]), 2)
+    );
+}
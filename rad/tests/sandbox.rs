@@ -0,0 +1,73 @@
+use std::thread;
+use std::time::Duration;
+
+use witnet_data_structures::chain::{RADAggregate, RADConsensus, RADRequest, RADRetrieve, RADType};
+use witnet_rad::{
+    encode_result,
+    error::RadErrorKind,
+    sandbox::{run, SandboxLimits},
+    types::RadonValue,
+    witness,
+};
+
+fn limits(timeout_millis: u64) -> SandboxLimits {
+    SandboxLimits {
+        timeout: Duration::from_millis(timeout_millis),
+        ..SandboxLimits::default()
+    }
+}
+
+/// Check that a job which does not finish in time results in a timeout error
+#[test]
+fn rad_sandbox_timeout() {
+    let result = run(&limits(50), |_| {
+        thread::sleep(Duration::from_secs(2));
+        Ok(RadonValue::Null)
+    });
+    let err = result.unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Timeout);
+
+    // The timeout is recorded as the result of the witness
+    assert_eq!(
+        encode_result(&Err(err)),
+        vec![146, 194, 167, 84, 105, 109, 101, 111, 117, 116]
+    );
+}
+
+/// Check that a job which panics results in an error instead of bringing the node down
+#[test]
+fn rad_sandbox_panic() {
+    let result: Result<(), _> = run(&limits(1000), |_| panic!("job failed"));
+
+    assert_eq!(result.unwrap_err().inner().kind(), &RadErrorKind::Execution);
+}
+
+/// Check that a witness resolves a request within the sandbox
+#[test]
+fn rad_sandbox_witness() {
+    let request = RADRequest {
+        not_before: 0,
+        retrieve: vec![RADRetrieve {
+            kind: RADType::Rng,
+            url: "rng://a".to_string(),
+            headers: vec![],
+            // [STRING_TOINT]
+            script: vec![145, 7],
+        }],
+        aggregate: RADAggregate { script: vec![] },
+        consensus: RADConsensus { script: vec![] },
+        deliver: vec![],
+    };
+
+    let result = witness(&request, &limits(1000)).unwrap();
+    match result {
+        RadonValue::Array(ref items) => match items.as_slice() {
+            [RadonValue::Int(_)] => {}
+            x => panic!("{:?}", x),
+        },
+        ref x => panic!("{:?}", x),
+    }
+
+    // [true, [n]]
+    assert_eq!(&encode_result(&Ok(result))[..3], &[146, 195, 145]);
+}
@@ -0,0 +1,106 @@
+use witnet_data_structures::chain::{RADAggregate, RADConsensus, RADRequest, RADRetrieve, RADType};
+use witnet_rad::{
+    error::RadErrorKind,
+    script::{decode_script, execute, typecheck, RadonCall},
+    types::{RadonType, RadonValue},
+    validate_request,
+};
+
+/// `[STRING_PARSEJSON, MIXED_TOMAP, [MAP_GET, "weather"], MIXED_TOMAP, [MAP_GET, "temp"],
+/// MIXED_TOFLOAT]`
+const WEATHER_SCRIPT: [u8; 22] = [
+    150, 3, 4, 146, 0, 167, 119, 101, 97, 116, 104, 101, 114, 4, 146, 0, 164, 116, 101, 109, 112, 2,
+];
+
+/// `[[ARRAY_FILTER, FILTER_DEV_ABS, 1], [ARRAY_REDUCE, REDUCER_AVG_MEAN]]`
+const AGGREGATE_SCRIPT: [u8; 8] = [146, 147, 2, 3, 1, 146, 6, 3];
+
+fn request(retrieve_script: Vec<u8>, aggregate_script: Vec<u8>) -> RADRequest {
+    RADRequest {
+        not_before: 0,
+        retrieve: vec![RADRetrieve {
+            kind: RADType::HttpGet,
+            url: "http://example.com".to_string(),
+            headers: vec![],
+            script: retrieve_script,
+        }],
+        aggregate: RADAggregate {
+            script: aggregate_script,
+        },
+        consensus: RADConsensus { script: vec![] },
+        deliver: vec![],
+    }
+}
+
+#[test]
+fn rad_script_decode() {
+    let calls = decode_script(&WEATHER_SCRIPT).unwrap();
+
+    assert_eq!(calls.len(), 6);
+    assert_eq!(
+        calls[2],
+        RadonCall {
+            operator: 0,
+            args: vec!["weather".into()],
+        }
+    );
+    assert_eq!(decode_script(&[]).unwrap(), vec![]);
+
+    // A script must be an array, without trailing bytes
+    let err = decode_script(&[3]).unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Decode);
+    let err = decode_script(&[145, 3, 3]).unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Decode);
+}
+
+#[test]
+fn rad_script_execute() {
+    let calls = decode_script(&WEATHER_SCRIPT).unwrap();
+    assert_eq!(
+        typecheck(&calls, RadonType::String).unwrap(),
+        RadonType::Float
+    );
+
+    let input = RadonValue::String(r#"{"weather":{"temp":17.5,"wind":3}}"#.to_string());
+    assert_eq!(execute(&calls, input).unwrap(), RadonValue::Float(17.5));
+
+    // Missing keys are deterministic runtime errors
+    let input = RadonValue::String(r#"{"weather":{"wind":3}}"#.to_string());
+    let err = execute(&calls, input).unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Execution);
+}
+
+#[test]
+fn rad_script_type_error() {
+    // MAP_GET cannot be applied on the String returned by the source
+    let calls = decode_script(&[145, 146, 0, 161, 97]).unwrap();
+    let err = typecheck(&calls, RadonType::String).unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Type);
+
+    let err = validate_request(&request(vec![145, 146, 0, 161, 97], vec![])).unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Type);
+
+    // Reducers need an array of numbers, while the retrievals return strings
+    let err = validate_request(&request(vec![], AGGREGATE_SCRIPT.to_vec())).unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Type);
+
+    validate_request(&request(WEATHER_SCRIPT.to_vec(), AGGREGATE_SCRIPT.to_vec())).unwrap();
+}
+
+#[test]
+fn rad_script_filter_reduce() {
+    let calls = decode_script(&AGGREGATE_SCRIPT).unwrap();
+    let input = RadonValue::Array(vec![
+        RadonValue::Float(10.0),
+        RadonValue::Float(10.5),
+        RadonValue::Float(11.0),
+        RadonValue::Float(14.5),
+    ]);
+
+    // The mean is 11.5, so only 10.5 and 11.0 are within the absolute deviation
+    assert_eq!(execute(&calls, input).unwrap(), RadonValue::Float(10.75));
+
+    // Reducing an empty array fails
+    let err = execute(&calls, RadonValue::Array(vec![])).unwrap_err();
+    assert_eq!(err.inner().kind(), &RadErrorKind::Execution);
+}
@@ -5,14 +5,30 @@
 #![deny(unused_mut)]
 #![deny(missing_docs)]
 
-use crate::core::actors;
+use crate::core::{
+    actors::{self, config_manager::ConfigManager},
+    inspector,
+};
 use ctrlc;
 use failure;
+use serde_json::{self, json, Value};
+use witnet_config::{
+    defaults::{Defaults, Testnet1},
+    dirs, template,
+};
 
-use std::path::PathBuf;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
 use std::result::Result;
+use std::thread;
+use std::time::Duration;
 use structopt::StructOpt;
 
+/// Time between the requests for the progress of a rescan
+const RESCAN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Witnet network
 #[derive(Debug, StructOpt)]
 pub(crate) struct Cli {
@@ -46,6 +62,133 @@ pub(crate) enum Command {
         // #[structopt(name = "background", short = "b")]
         // background: bool,
     },
+    #[structopt(
+        name = "init",
+        about = "Write a configuration file with the defaults and create the data directory"
+    )]
+    Init {
+        // Config file path
+        #[structopt(
+            name = "config",
+            long = "config",
+            short = "c",
+            help = "Path of the configuration file to write [default: platform configuration directory]"
+        )]
+        #[structopt(parse(from_os_str))]
+        config: Option<PathBuf>,
+        // Overwrite an existing config file
+        #[structopt(
+            name = "force",
+            long = "force",
+            short = "f",
+            help = "Overwrite the configuration file if it already exists"
+        )]
+        force: bool,
+    },
+    #[structopt(
+        name = "rescan",
+        about = "Rebuild the UTXO set and the wallet history of a running node from its stored blocks"
+    )]
+    Rescan {
+        // Config file path
+        #[structopt(
+            name = "config",
+            long = "config",
+            short = "c",
+            help = "Path to the configuration file of the node"
+        )]
+        #[structopt(parse(from_os_str))]
+        config: Option<PathBuf>,
+        // First epoch to rescan
+        #[structopt(
+            name = "from-epoch",
+            long = "from-epoch",
+            help = "First epoch to rescan",
+            default_value = "0"
+        )]
+        from_epoch: u32,
+    },
+    #[structopt(
+        name = "bans",
+        about = "Export or import the list of peers banned by a running node"
+    )]
+    Bans {
+        #[structopt(subcommand)]
+        cmd: BansCommand,
+    },
+    #[structopt(
+        name = "decode",
+        about = "Decode and print the structure of frames, messages, blocks or transactions"
+    )]
+    Decode {
+        // Bytes to decode
+        #[structopt(
+            name = "input",
+            help = "Hexadecimal string, or path of a file with the bytes (binary or hexadecimal)"
+        )]
+        input: String,
+        // Kind of the bytes
+        #[structopt(
+            name = "kind",
+            long = "kind",
+            short = "k",
+            help = "Kind of the bytes: auto, frames, message, block or transactions",
+            default_value = "auto"
+        )]
+        kind: inspector::Kind,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+pub(crate) enum BansCommand {
+    #[structopt(
+        name = "export",
+        about = "Write the bans in force as JSON, to be imported by other nodes"
+    )]
+    Export {
+        // Config file path
+        #[structopt(
+            name = "config",
+            long = "config",
+            short = "c",
+            help = "Path to the configuration file of the node"
+        )]
+        #[structopt(parse(from_os_str))]
+        config: Option<PathBuf>,
+        // File to write
+        #[structopt(
+            name = "file",
+            long = "file",
+            short = "f",
+            help = "Path of the file to write [default: standard output]"
+        )]
+        #[structopt(parse(from_os_str))]
+        file: Option<PathBuf>,
+    },
+    #[structopt(
+        name = "import",
+        about = "Ban the peers of a JSON file exported by another node"
+    )]
+    Import {
+        // Config file path
+        #[structopt(
+            name = "config",
+            long = "config",
+            short = "c",
+            help = "Path to the configuration file of the node"
+        )]
+        #[structopt(parse(from_os_str))]
+        config: Option<PathBuf>,
+        // File to read
+        #[structopt(
+            name = "file",
+            long = "file",
+            short = "f",
+            help = "Path of the file to read"
+        )]
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
 }
 
 pub(crate) fn exec(command: Command) -> Result<(), failure::Error> {
@@ -59,6 +202,157 @@ pub(crate) fn exec(command: Command) -> Result<(), failure::Error> {
                 .expect("Error setting handler for both SIGINT (Ctrl+C) and SIGTERM (kill)");
             })?;
         }
+        Command::Init { config, force } => init(config, force)?,
+        Command::Rescan { config, from_epoch } => rescan(config, from_epoch)?,
+        Command::Bans { cmd } => bans(cmd)?,
+        Command::Decode { input, kind } => decode(&input, kind)?,
+    }
+    Ok(())
+}
+
+/// Write a commented configuration file with the defaults of testnet-1 and
+/// create the directory of its database
+fn init(config: Option<PathBuf>, force: bool) -> Result<(), failure::Error> {
+    let config_file = match config.or_else(dirs::config_file) {
+        Some(config_file) => config_file,
+        None => failure::bail!(
+            "The configuration directory of the platform cannot be determined, use --config"
+        ),
+    };
+    if config_file.exists() && !force {
+        failure::bail!(
+            "The configuration file {} already exists, use --force to overwrite it",
+            config_file.display()
+        );
+    }
+
+    if let Some(config_dir) = config_file.parent() {
+        fs::create_dir_all(config_dir)?;
+    }
+    fs::write(
+        &config_file,
+        template::config_file_contents("testnet-1", &Testnet1),
+    )?;
+    println!("Configuration file written to {}", config_file.display());
+
+    let data_dir = Testnet1.storage_db_path();
+    fs::create_dir_all(&data_dir)?;
+    println!("Data directory created at {}", data_dir.display());
+
+    Ok(())
+}
+
+/// Rescan the stored blocks of a running node through its JSON-RPC interface, reporting the
+/// progress until the rescan finishes
+fn rescan(config: Option<PathBuf>, from_epoch: u32) -> Result<(), failure::Error> {
+    let address = jsonrpc_address(config)?;
+    let mut status = jsonrpc_call(address, "rescan", json!([from_epoch]))?;
+    println!(
+        "Rescanning {} blocks since epoch {}",
+        status["total"], from_epoch
+    );
+
+    while !status["finished"].as_bool().unwrap_or(true) {
+        thread::sleep(RESCAN_POLL_INTERVAL);
+        status = jsonrpc_call(address, "getRescanStatus", Value::Null)?;
+        println!(
+            "{} of {} blocks replayed (last epoch: {})",
+            status["replayed"], status["total"], status["last_epoch"]
+        );
+    }
+    println!(
+        "Rescan finished: {} blocks replayed, {} skipped",
+        status["replayed"], status["skipped"]
+    );
+
+    Ok(())
+}
+
+/// Export or import the bans of a running node through its JSON-RPC interface
+fn bans(command: BansCommand) -> Result<(), failure::Error> {
+    match command {
+        BansCommand::Export { config, file } => {
+            let address = jsonrpc_address(config)?;
+            let bans = jsonrpc_call(address, "getBans", Value::Null)?;
+            let contents = serde_json::to_string_pretty(&bans)?;
+            match file {
+                Some(file) => {
+                    fs::write(&file, contents + "\n")?;
+                    println!(
+                        "{} bans written to {}",
+                        bans.as_array().map_or(0, Vec::len),
+                        file.display()
+                    );
+                }
+                None => println!("{}", contents),
+            }
+        }
+        BansCommand::Import { config, file } => {
+            let bans: Value = serde_json::from_str(&fs::read_to_string(&file)?)?;
+            if !bans.is_array() {
+                failure::bail!("{} does not contain a list of bans", file.display());
+            }
+            let address = jsonrpc_address(config)?;
+            let banned = jsonrpc_call(address, "importBans", json!([bans]))?;
+            println!(
+                "{} peers banned or with their ban extended",
+                banned.as_array().map_or(0, Vec::len)
+            );
+        }
     }
+
+    Ok(())
+}
+
+/// Decode the bytes given as a hexadecimal string or in a file and print their structure
+fn decode(input: &str, kind: inspector::Kind) -> Result<(), failure::Error> {
+    let bytes = if Path::new(input).is_file() {
+        inspector::file_bytes(fs::read(input)?)
+    } else {
+        inspector::parse_hex(input).map_err(|e| failure::err_msg(e.to_string()))?
+    };
+    let decoded = inspector::decode(&bytes, kind).map_err(|e| failure::err_msg(e.to_string()))?;
+    print!("{}", decoded);
+
     Ok(())
 }
+
+/// Get the address of the JSON-RPC server of the node from its configuration
+fn jsonrpc_address(config: Option<PathBuf>) -> Result<SocketAddr, failure::Error> {
+    let mut config_manager = ConfigManager::new(config);
+    config_manager
+        .load()
+        .map_err(|e| failure::err_msg(e.to_string()))?;
+    let config = config_manager.config();
+    if !config.jsonrpc.enabled {
+        failure::bail!("The JSON-RPC interface of the node is disabled in its configuration");
+    }
+
+    Ok(config.jsonrpc.server_address)
+}
+
+/// Call a method of the JSON-RPC interface of a node
+/// The requests and the responses are JSON objects written in a single line
+fn jsonrpc_call(address: SocketAddr, method: &str, params: Value) -> Result<Value, failure::Error> {
+    let mut request = json!({"jsonrpc": "2.0", "method": method, "id": 1});
+    if !params.is_null() {
+        request["params"] = params;
+    }
+
+    let mut stream = TcpStream::connect(address).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Cannot connect to the node at {}: {}", address, e),
+        )
+    })?;
+    writeln!(stream, "{}", request)?;
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+
+    let mut response: Value = serde_json::from_str(&response)?;
+    if let Some(error) = response.get("error") {
+        failure::bail!("The node returned an error: {}", error);
+    }
+
+    Ok(response["result"].take())
+}
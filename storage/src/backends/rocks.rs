@@ -73,3 +73,11 @@ impl<'a> Storage<String, &'a [u8], Vec<u8>> for RocksStorage {
         }
     }
 }
+
+impl RocksStorage {
+    /// Compact the whole database, so that the space taken by deleted and overwritten entries is
+    /// reclaimed
+    pub fn compact(&self) {
+        self.db.compact_range(None::<&[u8]>, None::<&[u8]>);
+    }
+}
@@ -21,10 +21,15 @@ impl StorageError {
     pub fn new(kind: StorageErrorKind, info: String, msg: String) -> Self {
         Self { kind, info, msg }
     }
+
+    /// Get the kind of the error
+    pub fn kind(&self) -> &StorageErrorKind {
+        &self.kind
+    }
 }
 
 /// Storage Errors while operating on database
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum StorageErrorKind {
     /// Errors when create a connection to backend database
     Connection,
@@ -38,6 +43,10 @@ pub enum StorageErrorKind {
     Encode,
     /// Errors when creating a value from bytes
     Decode,
+    /// Errors when the value was encoded with an unsupported encoding version
+    Version,
+    /// Errors when the value does not match its checksum
+    Corrupted,
 }
 
 impl fmt::Display for StorageErrorKind {
@@ -9,4 +9,5 @@
 
 pub mod backends;
 pub mod error;
+pub mod migrations;
 pub mod storage;
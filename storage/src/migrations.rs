@@ -0,0 +1,115 @@
+//! Module containing a simple migration framework used to upgrade the values persisted in a
+//! `Storage` from older encodings to the current one.
+//!
+//! The storage keeps track of its schema version under a dedicated key. Each `Migration` upgrades
+//! the values of a list of keys to a given schema version, so running all the migrations whose
+//! version is greater than the stored one brings the storage up to date.
+//!
+//! The schema version is stored as a raw little endian `u32`, so it can be read whatever the
+//! encoding of the values is (see `ENCODING_VERSION`).
+
+use crate::error::{StorageError, StorageErrorKind, StorageResult};
+use crate::storage::{Storable, Storage};
+use std::fmt::Debug;
+use witnet_util::error::WitnetError;
+
+/// Schema version of a storage
+pub type SchemaVersion = u32;
+
+/// Size in bytes of a stored schema version
+const SCHEMA_VERSION_SIZE: usize = 4;
+
+/// Function converting the raw bytes of a value into the raw bytes of the upgraded value
+pub type MigrationFn = fn(&[u8]) -> StorageResult<Vec<u8>>;
+
+/// Migration from the previous schema version to `version`
+pub struct Migration {
+    /// Schema version of the storage after applying this migration
+    pub version: SchemaVersion,
+    /// Human readable description of the migration
+    pub description: &'static str,
+    /// Keys whose values need to be upgraded
+    pub keys: Vec<&'static [u8]>,
+    /// Function to upgrade a single value
+    pub migrate: MigrationFn,
+}
+
+/// Bring the storage up to date by applying all the `migrations` with a version greater than the
+/// schema version stored under `version_key`, in ascending order. A storage without schema version
+/// is considered to be at version 0.
+///
+/// Returns the schema version of the storage after applying the migrations, or an error if the
+/// storage was written by a newer version of the software.
+pub fn migrate<'a, C, S>(
+    storage: &mut S,
+    version_key: &'a [u8],
+    migrations: &[Migration],
+) -> StorageResult<SchemaVersion>
+where
+    C: Debug,
+    S: Storage<C, &'a [u8], Vec<u8>>,
+{
+    let latest_version = migrations.iter().map(|m| m.version).max().unwrap_or(0);
+    let mut version = match storage.get(version_key)? {
+        Some(bytes) => decode_schema_version(&bytes)?,
+        None => 0,
+    };
+
+    if version > latest_version {
+        return Err(WitnetError::from(StorageError::new(
+            StorageErrorKind::Version,
+            format!("Schema version: {}", version),
+            format!(
+                "Storage was written by a newer version (latest supported is {})",
+                latest_version
+            ),
+        )));
+    }
+
+    let mut pending: Vec<&Migration> = migrations.iter().filter(|m| m.version > version).collect();
+    pending.sort_by_key(|m| m.version);
+
+    for migration in pending {
+        for &key in &migration.keys {
+            if let Some(value) = storage.get(key)? {
+                let value = (migration.migrate)(&value)?;
+                storage.put(key, value)?;
+            }
+        }
+
+        version = migration.version;
+        storage.put(version_key, encode_schema_version(version))?;
+    }
+
+    Ok(version)
+}
+
+/// Encode a schema version to be stored
+pub fn encode_schema_version(version: SchemaVersion) -> Vec<u8> {
+    version.to_le_bytes().to_vec()
+}
+
+/// Decode a stored schema version. Older versions of the software stored it like any other
+/// value, so the versioned MessagePack encodings are also accepted: the encoding version 1 (not
+/// followed by a checksum) and the current one.
+pub fn decode_schema_version(bytes: &[u8]) -> StorageResult<SchemaVersion> {
+    if bytes.len() == SCHEMA_VERSION_SIZE {
+        // The encoded schema versions never have this size: they are small integers, whose
+        // encoding takes a single byte, and the current encoding appends a checksum
+        let mut version = [0; SCHEMA_VERSION_SIZE];
+        version.copy_from_slice(bytes);
+
+        return Ok(SchemaVersion::from_le_bytes(version));
+    }
+
+    match bytes.split_first() {
+        Some((&1, encoded)) => rmp_serde::from_slice(encoded).map_err(|e| {
+            WitnetError::from(StorageError::new(
+                StorageErrorKind::Decode,
+                "Error when decoding schema version".to_string(),
+                format!("{}", e),
+            ))
+        }),
+        _ => SchemaVersion::from_bytes(bytes),
+    }
+}
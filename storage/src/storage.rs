@@ -29,8 +29,8 @@ pub trait Storage<ConnData: Debug, Key, Value> {
 /// The simplest way to implement this trait
 /// is to add `#[derive(Serialize, Deserialize)]` to the type definition.
 /// The storage works on raw bytes, so we need to serialize and deserialize the data.
-/// The default implementation uses MessagePack, but the implementor is free to choose
-/// a different encoding for their custom types.
+/// The default implementation uses a versioned MessagePack encoding (see `ENCODING_VERSION`),
+/// but the implementor is free to choose a different encoding for their custom types.
 pub trait Storable: Sized {
     /// Convert `Self` into `Vec<u8>`
     fn to_bytes(&self) -> StorageResult<Vec<u8>>;
@@ -38,6 +38,27 @@ pub trait Storable: Sized {
     fn from_bytes(x: &[u8]) -> StorageResult<Self>;
 }
 
+/// Version of the binary encoding used by the default `Storable` implementation.
+/// Every encoded value starts with this byte, followed by the MessagePack representation of the
+/// value and a checksum of both. It must be increased whenever the encoding changes in a non
+/// backwards compatible way.
+pub const ENCODING_VERSION: u8 = 2;
+
+/// Size in bytes of the checksum appended to the encoded values
+pub const CHECKSUM_SIZE: usize = 4;
+
+/// Calculate the checksum (CRC-32) appended to the encoded values, in little endian byte order
+pub fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_SIZE] {
+    let crc = crc::crc32::checksum_ieee(bytes);
+
+    [
+        crc as u8,
+        (crc >> 8) as u8,
+        (crc >> 16) as u8,
+        (crc >> 24) as u8,
+    ]
+}
+
 // By default, mark all the types which can be serialized and deserialized
 // using serde as `Storable`
 impl<T> Storable for T
@@ -46,17 +67,49 @@ where
 {
     /// Convert `Self` into `Vec<u8>`
     fn to_bytes(&self) -> StorageResult<Vec<u8>> {
-        rmp_serde::to_vec(&self).map_err(|e| {
+        let mut bytes = vec![ENCODING_VERSION];
+        rmp_serde::encode::write(&mut bytes, &self).map_err(|e| {
             WitnetError::from(StorageError::new(
                 StorageErrorKind::Encode,
                 "Error when encoding value".to_string(),
                 format!("{}", e),
             ))
-        })
+        })?;
+        let checksum = checksum(&bytes);
+        bytes.extend_from_slice(&checksum);
+
+        Ok(bytes)
     }
     /// Convert `Vec<u8>` into `Self
     fn from_bytes(x: &[u8]) -> StorageResult<Self> {
-        rmp_serde::from_slice(x).map_err(|e| {
+        match x.first() {
+            Some(&ENCODING_VERSION) if x.len() > CHECKSUM_SIZE => {}
+            Some(&ENCODING_VERSION) | None => {
+                return Err(WitnetError::from(StorageError::new(
+                    StorageErrorKind::Corrupted,
+                    "Error when decoding value".to_string(),
+                    format!("Value too short ({} bytes)", x.len()),
+                )));
+            }
+            Some(version) => {
+                return Err(WitnetError::from(StorageError::new(
+                    StorageErrorKind::Version,
+                    "Error when decoding value".to_string(),
+                    format!("Unsupported encoding version {}", version),
+                )));
+            }
+        }
+
+        let (bytes, expected_checksum) = x.split_at(x.len() - CHECKSUM_SIZE);
+        if checksum(bytes) != expected_checksum {
+            return Err(WitnetError::from(StorageError::new(
+                StorageErrorKind::Corrupted,
+                "Error when decoding value".to_string(),
+                "Checksum mismatch".to_string(),
+            )));
+        }
+
+        rmp_serde::from_slice(&bytes[1..]).map_err(|e| {
             WitnetError::from(StorageError::new(
                 StorageErrorKind::Decode,
                 "Error when decoding value".to_string(),
@@ -115,8 +168,9 @@ pub trait StorageHelper<'a, ConnData: Debug>: Storage<ConnData, &'a [u8], Vec<u8
         let value = value.unwrap();
         match T::from_bytes(&value) {
             Ok(v) => Ok(Some(v)),
+            // Keep the kind of the error so callers can tell corrupted values apart
             Err(e) => Err(WitnetError::from(StorageError::new(
-                StorageErrorKind::Get,
+                *e.inner().kind(),
                 format!("Key: {:?}", key),
                 format!("Failed to create value from bytes: {:?}", e),
             ))),
@@ -0,0 +1,108 @@
+use witnet_storage::backends::in_memory::InMemoryStorage;
+use witnet_storage::error::StorageResult;
+use witnet_storage::migrations::{encode_schema_version, migrate, Migration};
+use witnet_storage::storage::{Storage, StorageHelper};
+
+static VERSION_KEY: &[u8] = b"version";
+
+fn append_one(value: &[u8]) -> StorageResult<Vec<u8>> {
+    let mut value = value.to_vec();
+    value.push(1);
+    Ok(value)
+}
+
+fn append_two(value: &[u8]) -> StorageResult<Vec<u8>> {
+    let mut value = value.to_vec();
+    value.push(2);
+    Ok(value)
+}
+
+fn migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            version: 1,
+            description: "Append 1",
+            keys: vec![&b"a"[..], &b"b"[..]],
+            migrate: append_one,
+        },
+        Migration {
+            version: 2,
+            description: "Append 2",
+            keys: vec![&b"a"[..]],
+            migrate: append_two,
+        },
+    ]
+}
+
+#[test]
+fn migrations_from_scratch() -> StorageResult<()> {
+    let mut s = InMemoryStorage::new(())?;
+    s.put(b"a", vec![0])?;
+    s.put(b"b", vec![0])?;
+
+    assert_eq!(migrate(&mut *s, VERSION_KEY, &migrations())?, 2);
+    assert_eq!(s.get(b"a")?, Some(vec![0, 1, 2]));
+    assert_eq!(s.get(b"b")?, Some(vec![0, 1]));
+    assert_eq!(s.get(VERSION_KEY)?, Some(vec![2, 0, 0, 0]));
+
+    // Migrations are applied only once
+    assert_eq!(migrate(&mut *s, VERSION_KEY, &migrations())?, 2);
+    assert_eq!(s.get(b"a")?, Some(vec![0, 1, 2]));
+
+    Ok(())
+}
+
+#[test]
+fn migrations_from_intermediate_version() -> StorageResult<()> {
+    let mut s = InMemoryStorage::new(())?;
+    s.put(b"a", vec![0])?;
+    s.put(VERSION_KEY, encode_schema_version(1))?;
+
+    assert_eq!(migrate(&mut *s, VERSION_KEY, &migrations())?, 2);
+    assert_eq!(s.get(b"a")?, Some(vec![0, 2]));
+
+    Ok(())
+}
+
+#[test]
+fn migrations_schema_version_with_old_encoding() -> StorageResult<()> {
+    // Schema version 1 stored with the encoding version 1: MessagePack without checksum
+    let mut s = InMemoryStorage::new(())?;
+    s.put(b"a", vec![0])?;
+    s.put(VERSION_KEY, vec![1, 1])?;
+
+    assert_eq!(migrate(&mut *s, VERSION_KEY, &migrations())?, 2);
+    assert_eq!(s.get(b"a")?, Some(vec![0, 2]));
+    assert_eq!(s.get(VERSION_KEY)?, Some(encode_schema_version(2)));
+
+    // Schema version 1 stored with the current encoding
+    let mut s = InMemoryStorage::new(())?;
+    s.put(b"a", vec![0])?;
+    s.put_t(VERSION_KEY, 1u32)?;
+
+    assert_eq!(migrate(&mut *s, VERSION_KEY, &migrations())?, 2);
+    assert_eq!(s.get(b"a")?, Some(vec![0, 2]));
+    assert_eq!(s.get(VERSION_KEY)?, Some(encode_schema_version(2)));
+
+    Ok(())
+}
+
+#[test]
+fn migrations_missing_keys() -> StorageResult<()> {
+    let mut s = InMemoryStorage::new(())?;
+
+    assert_eq!(migrate(&mut *s, VERSION_KEY, &migrations())?, 2);
+    assert_eq!(s.get(b"a")?, None);
+
+    Ok(())
+}
+
+#[test]
+fn migrations_newer_version() -> StorageResult<()> {
+    let mut s = InMemoryStorage::new(())?;
+    s.put(VERSION_KEY, encode_schema_version(3))?;
+
+    assert!(migrate(&mut *s, VERSION_KEY, &migrations()).is_err());
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use witnet_storage::backends::in_memory::InMemoryStorage;
-use witnet_storage::error::StorageResult;
-use witnet_storage::storage::{Storable, Storage, StorageHelper};
+use witnet_storage::error::{StorageErrorKind, StorageResult};
+use witnet_storage::storage::{Storable, Storage, StorageHelper, CHECKSUM_SIZE, ENCODING_VERSION};
 
 #[test]
 fn storable_types() -> StorageResult<()> {
@@ -50,3 +50,51 @@ fn storable_types() -> StorageResult<()> {
 
     Ok(())
 }
+
+#[test]
+fn storable_versioned_encoding() -> StorageResult<()> {
+    let x: u16 = 0x1234;
+    let bytes = x.to_bytes()?;
+
+    // Values encoded with the default implementation start with the encoding version
+    assert_eq!(bytes[0], ENCODING_VERSION);
+    assert_eq!(u16::from_bytes(&bytes)?, x);
+
+    // Values encoded with a different version are rejected
+    let mut unsupported = bytes.clone();
+    unsupported[0] = ENCODING_VERSION + 1;
+    assert!(u16::from_bytes(&unsupported).is_err());
+
+    // Empty values are rejected
+    assert!(u16::from_bytes(&[]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn storable_corrupted_value() -> StorageResult<()> {
+    let x = "Hello, world".to_string();
+    let bytes = x.to_bytes()?;
+
+    // Flipping any bit of the value is detected by the checksum
+    for i in 1..bytes.len() {
+        let mut corrupted = bytes.clone();
+        corrupted[i] ^= 0x01;
+        let err = String::from_bytes(&corrupted).unwrap_err();
+        assert_eq!(err.inner().kind(), &StorageErrorKind::Corrupted);
+    }
+
+    // Truncated values are detected as well
+    let err = String::from_bytes(&bytes[..CHECKSUM_SIZE]).unwrap_err();
+    assert_eq!(err.inner().kind(), &StorageErrorKind::Corrupted);
+
+    // The kind of the error is kept when using the helper trait
+    let mut s = InMemoryStorage::new(())?;
+    let mut corrupted = bytes.clone();
+    corrupted[1] ^= 0x01;
+    s.put(b"corrupted", corrupted)?;
+    let err = s.get_t::<String>(b"corrupted").unwrap_err();
+    assert_eq!(err.inner().kind(), &StorageErrorKind::Corrupted);
+
+    Ok(())
+}
@@ -19,6 +19,13 @@ impl<K: Fail> From<K> for WitnetError<K> {
     }
 }
 
+impl<K: Fail> WitnetError<K> {
+    /// Get the inner error
+    pub fn inner(&self) -> &K {
+        self.inner.get_context()
+    }
+}
+
 impl<K: Fail> Display for WitnetError<K> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         Display::fmt(&self.inner, f)
@@ -8,3 +8,13 @@ pub fn get_timestamp() -> i64 {
     // Return number of non-leap seconds since Unix epoch
     utc.timestamp()
 }
+
+/// Function to get timestamp from system as UTC Unix timestamp, seconds since Unix epoch along
+/// with the nanoseconds elapsed since the start of that second
+pub fn get_timestamp_nanos() -> (i64, u32) {
+    // Get UTC current datetime
+    let utc: DateTime<Utc> = Utc::now();
+
+    // Return number of non-leap seconds since Unix epoch and the subsecond nanoseconds
+    (utc.timestamp(), utc.timestamp_subsec_nanos())
+}